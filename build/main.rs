@@ -30,6 +30,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_APPLE_vertex_array_object",
             "GL_ARB_bindless_texture",
             "GL_ARB_buffer_storage",
+            "GL_ARB_clear_buffer_object",
             "GL_ARB_compute_shader",
             "GL_ARB_copy_buffer",
             "GL_ARB_debug_output",
@@ -44,6 +45,8 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_geometry_shader4",
             "GL_ARB_gpu_shader_fp64",
             "GL_ARB_gpu_shader_int64",
+            "GL_ARB_indirect_parameters",
+            "GL_ARB_instanced_arrays",
             "GL_ARB_invalidate_subdata",
             "GL_ARB_multi_draw_indirect",
             "GL_ARB_occlusion_query",
@@ -51,25 +54,36 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_robustness",
             "GL_ARB_shader_image_load_store",
             "GL_ARB_shader_objects",
+            "GL_ARB_sparse_buffer",
+            "GL_ARB_sparse_texture",
             "GL_ARB_texture_buffer_object",
             "GL_ARB_texture_float",
             "GL_ARB_texture_multisample",
             "GL_ARB_texture_rg",
             "GL_ARB_texture_rgb10_a2ui",
+            "GL_ARB_transform_feedback2",
             "GL_ARB_transform_feedback3",
+            "GL_ARB_transform_feedback_instanced",
             "GL_ARB_vertex_buffer_object",
             "GL_ARB_vertex_shader",
             "GL_ATI_draw_buffers",
             "GL_ATI_meminfo",
             "GL_EXT_debug_marker",
             "GL_EXT_direct_state_access",
+            "GL_EXT_draw_buffers2",
             "GL_EXT_framebuffer_blit",
             "GL_EXT_framebuffer_multisample",
             "GL_EXT_framebuffer_object",
             "GL_EXT_framebuffer_sRGB",
             "GL_EXT_gpu_shader4",
+            "GL_EXT_memory_object",
+            "GL_EXT_memory_object_fd",
+            "GL_EXT_memory_object_win32",
             "GL_EXT_packed_depth_stencil",
             "GL_EXT_provoking_vertex",
+            "GL_EXT_semaphore",
+            "GL_EXT_semaphore_fd",
+            "GL_EXT_semaphore_win32",
             "GL_EXT_texture_array",
             "GL_EXT_texture_buffer_object",
             "GL_EXT_texture_compression_s3tc",
@@ -81,6 +95,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_KHR_robustness",
             "GL_NVX_gpu_memory_info",
             "GL_NV_conditional_render",
+            "GL_NV_mesh_shader",
             "GL_NV_vertex_attrib_integer_64bit",
         ],
     );
@@ -92,23 +107,38 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
         Fallbacks::None,
         vec![
             "GL_ANGLE_framebuffer_multisample",
+            "GL_ANGLE_instanced_arrays",
             "GL_APPLE_framebuffer_multisample",
             "GL_APPLE_sync",
             "GL_ARM_rgba8",
+            "GL_EXT_base_instance",
             "GL_EXT_buffer_storage",
             "GL_EXT_disjoint_timer_query",
+            "GL_EXT_draw_buffers",
+            "GL_EXT_draw_buffers_indexed",
+            "GL_EXT_instanced_arrays",
+            "GL_EXT_memory_object",
+            "GL_EXT_memory_object_fd",
+            "GL_EXT_memory_object_win32",
             "GL_EXT_multi_draw_indirect",
             "GL_EXT_multisampled_render_to_texture",
             "GL_EXT_occlusion_query_boolean",
             "GL_EXT_primitive_bounding_box",
             "GL_EXT_robustness",
+            "GL_EXT_semaphore",
+            "GL_EXT_semaphore_fd",
+            "GL_EXT_semaphore_win32",
             "GL_KHR_debug",
             "GL_NV_copy_buffer",
+            "GL_NV_framebuffer_blit",
             "GL_NV_framebuffer_multisample",
             "GL_NV_internalformat_sample_query",
             "GL_NV_pixel_buffer_object",
             "GL_OES_depth_texture",
+            "GL_OES_draw_buffers_indexed",
             "GL_OES_draw_elements_base_vertex",
+            "GL_OES_EGL_image",
+            "GL_OES_EGL_image_external",
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
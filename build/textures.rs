@@ -877,6 +877,10 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
                 /// It is possible that the current OpenGL context does not support the given
                 /// format, in which case the returned data will be invalid.
                 ///
+                /// Choosing the format of the texture avoids any conversion. For example
+                /// `(Half, Half, Half, Half)` reads `F16F16F16F16` textures and `Rgb10A2`
+                /// reads `U10U10U10U2` textures.
+                ///
                 /// You should avoid doing this at all cost during performance-critical
                 /// operations (for example, while you're drawing).
                 /// Use `read_to_pixel_buffer` instead.
@@ -909,6 +913,55 @@ fn build_texture<W: Write>(dest: &mut W, ty: TextureType, dimensions: TextureDim
             "#)).unwrap();
     }
 
+    // writing the `read` functions of depth and stencil textures
+    if dimensions == TextureDimensions::Texture2d &&
+       (ty == TextureType::Depth || ty == TextureType::DepthStencil || ty == TextureType::Stencil)
+    {
+        let (pixel, description) = match ty {
+            TextureType::Stencil => ("u8", "stencil values of the texture"),
+            TextureType::DepthStencil => ("f32", "depth values of the texture, without the \
+                                                  stencil values"),
+            _ => ("f32", "depth values of the texture, whatever its depth format is"),
+        };
+
+        (write!(dest, r#"
+                /// Reads the {description} to RAM.
+                ///
+                /// You should avoid doing this at all cost during performance-critical
+                /// operations (for example, while you're drawing).
+                /// Use `read_to_pixel_buffer` instead.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the backend doesn't support reading this kind of attachment, which
+                /// is the case of OpenGL ES without `GL_NV_read_depth` or `GL_NV_read_stencil`.
+                #[inline]
+                pub fn read<T>(&self) -> T where T: Texture2dDataSink<{pixel}> {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    self.0.main_level().first_layer().into_image(None).unwrap().raw_read(&rect)
+                }}
+            "#, description = description, pixel = pixel)).unwrap();
+
+        (write!(dest, r#"
+                /// Reads the {description} into a buffer in video memory.
+                ///
+                /// This operation copies the texture's data into a buffer in video memory
+                /// (a pixel buffer). Contrary to the `read` function, this operation is
+                /// done asynchronously and doesn't need a synchronization.
+                #[inline]
+                pub fn read_to_pixel_buffer(&self) -> PixelBuffer<{pixel}> {{
+                    let rect = Rect {{ left: 0, bottom: 0, width: self.get_width(),
+                                       height: self.get_height().unwrap_or(1) }};
+                    let pb = PixelBuffer::new_empty(self.0.get_context(),
+                                                    rect.width as usize * rect.height as usize);
+                    self.0.main_level().first_layer().into_image(None).unwrap()
+                          .raw_read_to_pixel_buffer(&rect, &pb);
+                    pb
+                }}
+            "#, description = description, pixel = pixel)).unwrap();
+    }
+
     // writing the `read_compressed_data` function
     if is_compressed && !dimensions.is_array() {
         (write!(dest, r#"
@@ -1,6 +1,6 @@
 //! Backend implementation for a glutin headless renderer.
 
-use {Frame, IncompatibleOpenGl, SwapBuffersError};
+use {ContextCreationError, Frame, SwapBuffersError};
 use debug;
 use context;
 use backend::{self, Backend};
@@ -73,7 +73,7 @@ impl Headless {
     ///
     /// Performs a compatibility check to make sure that all core elements of glium are supported
     /// by the implementation.
-    pub fn new(context: glutin::HeadlessContext) -> Result<Self, IncompatibleOpenGl> {
+    pub fn new(context: glutin::HeadlessContext) -> Result<Self, ContextCreationError> {
         Self::with_debug(context, Default::default())
     }
 
@@ -81,13 +81,13 @@ impl Headless {
     ///
     /// This function does the same as `build_glium`, except that the resulting context
     /// will assume that the current OpenGL context will never change.
-    pub unsafe fn unchecked(context: glutin::HeadlessContext) -> Result<Self, IncompatibleOpenGl> {
+    pub unsafe fn unchecked(context: glutin::HeadlessContext) -> Result<Self, ContextCreationError> {
         Self::unchecked_with_debug(context, Default::default())
     }
 
     /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
     pub fn with_debug(context: glutin::HeadlessContext, debug: debug::DebugCallbackBehavior)
-        -> Result<Self, IncompatibleOpenGl>
+        -> Result<Self, ContextCreationError>
     {
         Self::new_inner(context, debug, true)
     }
@@ -96,7 +96,7 @@ impl Headless {
     pub unsafe fn unchecked_with_debug(
         context: glutin::HeadlessContext,
         debug: debug::DebugCallbackBehavior,
-    ) -> Result<Self, IncompatibleOpenGl>
+    ) -> Result<Self, ContextCreationError>
     {
         Self::new_inner(context, debug, false)
     }
@@ -105,7 +105,7 @@ impl Headless {
         context: glutin::HeadlessContext,
         debug: debug::DebugCallbackBehavior,
         checked: bool,
-    ) -> Result<Self, IncompatibleOpenGl>
+    ) -> Result<Self, ContextCreationError>
     {
         let glutin_context = Rc::new(context);
         let glutin_backend = GlutinBackend(glutin_context.clone());
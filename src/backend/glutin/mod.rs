@@ -12,12 +12,16 @@ pub extern crate glutin;
 
 pub mod headless;
 
-use {Frame, IncompatibleOpenGl, SwapBuffersError};
+mod swap_control;
+
+use {ContextCreationError, Frame, Rect, SwapBuffersError};
 use debug;
 use context;
 use backend;
 use backend::Context;
 use backend::Backend;
+use backend::ContextAttributes;
+use pacing::{SwapInterval, SwapIntervalError};
 use glutin::GlContext;
 use std;
 use std::cell::{Cell, RefCell, Ref};
@@ -52,8 +56,22 @@ pub struct GlutinBackend(Rc<RefCell<glutin::GlWindow>>);
 pub enum DisplayCreationError {
     /// An error has happened while creating the backend.
     GlutinCreationError(glutin::CreationError),
-    /// The OpenGL implementation is too old.
-    IncompatibleOpenGl(IncompatibleOpenGl),
+    /// The OpenGL implementation can't be used by glium.
+    ContextCreationError(ContextCreationError),
+}
+
+/// Asks for a default framebuffer with 10 bits per color channel and 2 bits of alpha
+/// (`RGB10_A2`), for applications that target deep color or HDR10 displays.
+///
+/// The windowing system may not be able to provide such a framebuffer, in which case creating
+/// the display fails. Check `Capabilities::color_bits` to know the format that was actually
+/// chosen.
+///
+/// Floating-point default framebuffers can't be requested through glutin yet, but they are
+/// reported with `Capabilities::float_color_buffer` when the windowing system provides one.
+#[inline]
+pub fn with_deep_color(context_builder: glutin::ContextBuilder) -> glutin::ContextBuilder {
+    context_builder.with_pixel_format(30, 2)
 }
 
 impl Display {
@@ -71,11 +89,43 @@ impl Display {
         Self::from_gl_window(gl_window).map_err(From::from)
     }
 
+    /// Create a new glium `Display` whose context shares its objects with the one of `shared`.
+    ///
+    /// Buffers, textures and programs created with one of the two displays can be used with
+    /// the other one. See `Context::new_shared` for more details.
+    pub fn new_shared(
+        window_builder: glutin::WindowBuilder,
+        context_builder: glutin::ContextBuilder,
+        events_loop: &glutin::EventsLoop,
+        shared: &Display,
+    ) -> Result<Self, DisplayCreationError>
+    {
+        let gl_window = {
+            let shared_window = shared.gl_window.borrow();
+            let context_builder = context_builder.with_shared_lists(shared_window.context());
+            try!(glutin::GlWindow::new(window_builder, context_builder, events_loop))
+        };
+
+        let gl_window = Rc::new(RefCell::new(gl_window));
+        let glutin_backend = GlutinBackend(gl_window.clone());
+        let framebuffer_dimensions = glutin_backend.get_framebuffer_dimensions();
+        let context = try!(unsafe {
+            context::Context::new_shared(glutin_backend, true, Default::default(),
+                                         &shared.context)
+        });
+
+        Ok(Display {
+            gl_window: gl_window,
+            context: context,
+            last_framebuffer_dimensions: Cell::new(framebuffer_dimensions),
+        })
+    }
+
     /// Create a new glium `Display`.
     ///
     /// Performs a compatibility check to make sure that all core elements of glium are supported
     /// by the implementation.
-    pub fn from_gl_window(gl_window: glutin::GlWindow) -> Result<Self, IncompatibleOpenGl> {
+    pub fn from_gl_window(gl_window: glutin::GlWindow) -> Result<Self, ContextCreationError> {
         Self::with_debug(gl_window, Default::default())
     }
 
@@ -83,13 +133,13 @@ impl Display {
     ///
     /// This function does the same as `build_glium`, except that the resulting context
     /// will assume that the current OpenGL context will never change.
-    pub unsafe fn unchecked(gl_window: glutin::GlWindow) -> Result<Self, IncompatibleOpenGl> {
+    pub unsafe fn unchecked(gl_window: glutin::GlWindow) -> Result<Self, ContextCreationError> {
         Self::unchecked_with_debug(gl_window, Default::default())
     }
 
     /// The same as the `new` constructor, but allows for specifying debug callback behaviour.
     pub fn with_debug(gl_window: glutin::GlWindow, debug: debug::DebugCallbackBehavior)
-        -> Result<Self, IncompatibleOpenGl>
+        -> Result<Self, ContextCreationError>
     {
         Self::new_inner(gl_window, debug, true)
     }
@@ -98,7 +148,7 @@ impl Display {
     pub unsafe fn unchecked_with_debug(
         gl_window: glutin::GlWindow,
         debug: debug::DebugCallbackBehavior,
-    ) -> Result<Self, IncompatibleOpenGl>
+    ) -> Result<Self, ContextCreationError>
     {
         Self::new_inner(gl_window, debug, false)
     }
@@ -107,7 +157,7 @@ impl Display {
         gl_window: glutin::GlWindow,
         debug: debug::DebugCallbackBehavior,
         checked: bool,
-    ) -> Result<Self, IncompatibleOpenGl>
+    ) -> Result<Self, ContextCreationError>
     {
         let gl_window = Rc::new(RefCell::new(gl_window));
         let glutin_backend = GlutinBackend(gl_window.clone());
@@ -146,7 +196,7 @@ impl Display {
 
         // Rebuild the Context.
         let backend = GlutinBackend(self.gl_window.clone());
-        try!(unsafe { self.context.rebuild(backend) });
+        try!(unsafe { self.context.rebuild(backend) }.map_err(ContextCreationError::from));
 
         Ok(())
     }
@@ -191,15 +241,15 @@ impl Error for DisplayCreationError {
     fn description(&self) -> &str {
         match *self {
             DisplayCreationError::GlutinCreationError(ref err) => err.description(),
-            DisplayCreationError::IncompatibleOpenGl(ref err) => err.description(),
+            DisplayCreationError::ContextCreationError(ref err) => err.description(),
         }
     }
 
     #[inline]
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         match *self {
             DisplayCreationError::GlutinCreationError(ref err) => Some(err),
-            DisplayCreationError::IncompatibleOpenGl(ref err) => Some(err),
+            DisplayCreationError::ContextCreationError(ref err) => Some(err),
         }
     }
 }
@@ -211,10 +261,10 @@ impl From<glutin::CreationError> for DisplayCreationError {
     }
 }
 
-impl From<IncompatibleOpenGl> for DisplayCreationError {
+impl From<ContextCreationError> for DisplayCreationError {
     #[inline]
-    fn from(err: IncompatibleOpenGl) -> DisplayCreationError {
-        DisplayCreationError::IncompatibleOpenGl(err)
+    fn from(err: ContextCreationError) -> DisplayCreationError {
+        DisplayCreationError::ContextCreationError(err)
     }
 }
 
@@ -241,6 +291,46 @@ impl Deref for GlutinBackend {
     }
 }
 
+impl GlutinBackend {
+    /// Returns the windowing system API that the context was created with.
+    #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+              target_os = "openbsd"))]
+    fn windowing_api(&self) -> swap_control::WindowingApi {
+        use self::glutin::os::GlContextExt;
+        use self::glutin::os::unix::RawHandle;
+
+        match unsafe { self.borrow().context().raw_handle() } {
+            RawHandle::Glx(_) => swap_control::WindowingApi::Glx,
+            RawHandle::Egl(_) => swap_control::WindowingApi::Egl,
+        }
+    }
+
+    /// Returns the windowing system API that the context was created with.
+    #[cfg(target_os = "windows")]
+    fn windowing_api(&self) -> swap_control::WindowingApi {
+        use self::glutin::os::GlContextExt;
+        use self::glutin::os::windows::RawHandle;
+
+        match unsafe { self.borrow().context().raw_handle() } {
+            RawHandle::Wgl(_) => swap_control::WindowingApi::Wgl,
+            RawHandle::Egl(_) => swap_control::WindowingApi::Egl,
+        }
+    }
+
+    /// Returns the windowing system API that the context was created with.
+    #[cfg(target_os = "android")]
+    fn windowing_api(&self) -> swap_control::WindowingApi {
+        swap_control::WindowingApi::Egl
+    }
+
+    /// Returns the windowing system API that the context was created with.
+    #[cfg(not(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                  target_os = "openbsd", target_os = "windows", target_os = "android")))]
+    fn windowing_api(&self) -> swap_control::WindowingApi {
+        swap_control::WindowingApi::Other
+    }
+}
+
 unsafe impl Backend for GlutinBackend {
     #[inline]
     fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
@@ -251,6 +341,18 @@ unsafe impl Backend for GlutinBackend {
         }
     }
 
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        let result = unsafe {
+            swap_control::swap_buffers_with_damage(|symbol| self.get_proc_address(symbol),
+                                                   self.windowing_api(), damage)
+        };
+
+        match result {
+            Some(result) => result,
+            None => self.swap_buffers(),
+        }
+    }
+
     #[inline]
     unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
         self.borrow().get_proc_address(symbol) as *const _
@@ -276,4 +378,34 @@ unsafe impl Backend for GlutinBackend {
     unsafe fn make_current(&self) {
         self.borrow().make_current().unwrap();
     }
+
+    fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        unsafe {
+            swap_control::set_swap_interval(|symbol| self.get_proc_address(symbol),
+                                            self.windowing_api(), interval)
+        }
+    }
+
+    fn get_context_attributes(&self) -> ContextAttributes {
+        let format = self.borrow().get_pixel_format();
+
+        // glutin only reports the total number of bits of the color channels
+        let color_bits = if format.color_bits % 3 == 0 {
+            let bits = (format.color_bits / 3) as u16;
+            Some([bits, bits, bits, format.alpha_bits as u16])
+        } else {
+            None
+        };
+
+        ContextAttributes {
+            color_bits: color_bits,
+            depth_bits: Some(format.depth_bits as u16),
+            stencil_bits: Some(format.stencil_bits as u16),
+            samples: Some(format.multisampling.unwrap_or(0)),
+            double_buffer: Some(format.double_buffer),
+            srgb: Some(format.srgb),
+            stereo: Some(format.stereoscopy),
+            .. Default::default()
+        }
+    }
 }
@@ -0,0 +1,296 @@
+/*!
+Controlling how buffers are swapped by calling the windowing system directly.
+
+Glutin only allows choosing vsync when the context is built and doesn't expose partial
+presentation, so the swap interval and the damage of a frame are handled by calling the
+functions of the windowing system directly.
+
+*/
+use std::os::raw::{c_int, c_void};
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "dragonfly",
+          target_os = "freebsd", target_os = "netbsd", target_os = "openbsd",
+          target_os = "android"))]
+use std::mem;
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+use std::ffi::CStr;
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+use std::os::raw::c_char;
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd"))]
+use std::os::raw::c_ulong;
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+use std::os::raw::c_uint;
+
+use Rect;
+use SwapBuffersError;
+use pacing::{SwapInterval, SwapIntervalError};
+
+/// The windowing system API that a context was created with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WindowingApi {
+    /// WGL, on Windows.
+    Wgl,
+    /// GLX, on X11.
+    Glx,
+    /// EGL.
+    Egl,
+    /// Any other API.
+    Other,
+}
+
+/// Changes the swap interval of the current context.
+pub unsafe fn set_swap_interval<F>(get_proc_address: F, api: WindowingApi, interval: SwapInterval)
+                                   -> Result<(), SwapIntervalError>
+    where F: Fn(&str) -> *const c_void
+{
+    // negative values mean adaptive vsync for all the extensions
+    let value = match interval {
+        SwapInterval::DontWait => 0,
+        SwapInterval::Wait(n) => n as c_int,
+        SwapInterval::AdaptiveWait(n) => -(n as c_int),
+    };
+
+    match api {
+        #[cfg(target_os = "windows")]
+        WindowingApi::Wgl => wgl_set_swap_interval(&get_proc_address, value),
+        #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                  target_os = "netbsd", target_os = "openbsd"))]
+        WindowingApi::Glx => glx_set_swap_interval(&get_proc_address, value),
+        #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                  target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+        WindowingApi::Egl => egl_set_swap_interval(&get_proc_address, value),
+        _ => Err(SwapIntervalError::NotSupported),
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn wgl_set_swap_interval(get_proc_address: &Fn(&str) -> *const c_void, value: c_int)
+                                -> Result<(), SwapIntervalError>
+{
+    let swap_interval = get_proc_address("wglSwapIntervalEXT");
+    if swap_interval.is_null() {
+        return Err(SwapIntervalError::NotSupported);
+    }
+
+    let swap_interval: extern "system" fn(c_int) -> c_int = mem::transmute(swap_interval);
+
+    if swap_interval(value) != 0 {
+        Ok(())
+    } else if value < 0 {
+        // `wglSwapIntervalEXT` fails with negative values if `WGL_EXT_swap_control_tear`
+        // isn't supported
+        Err(SwapIntervalError::AdaptiveNotSupported)
+    } else {
+        Err(SwapIntervalError::NotSupported)
+    }
+}
+
+/// Swaps the buffers of the current context and reports the damaged regions.
+///
+/// Returns `None` if the windowing system doesn't support reporting damage or if swapping with
+/// damage failed, in which case the buffers haven't been swapped.
+pub unsafe fn swap_buffers_with_damage<F>(get_proc_address: F, api: WindowingApi,
+                                          damage: &[Rect])
+                                          -> Option<Result<(), SwapBuffersError>>
+    where F: Fn(&str) -> *const c_void
+{
+    // GLX and WGL don't have an equivalent of `EGL_KHR_swap_buffers_with_damage`
+    match api {
+        #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                  target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+        WindowingApi::Egl => egl_swap_buffers_with_damage(&get_proc_address, damage),
+        _ => {
+            let _ = (get_proc_address, damage);
+            None
+        },
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd"))]
+unsafe fn glx_set_swap_interval(get_proc_address: &Fn(&str) -> *const c_void, value: c_int)
+                                -> Result<(), SwapIntervalError>
+{
+    const GLX_EXTENSIONS: c_int = 3;
+
+    let display = {
+        let get_current_display = get_proc_address("glXGetCurrentDisplay");
+        if get_current_display.is_null() {
+            return Err(SwapIntervalError::NotSupported);
+        }
+
+        let get_current_display: extern "C" fn() -> *mut c_void =
+                                                        mem::transmute(get_current_display);
+        get_current_display()
+    };
+
+    if display.is_null() {
+        return Err(SwapIntervalError::NotSupported);
+    }
+
+    // `glXGetProcAddress` returns a non-null pointer even for functions that don't exist, so we
+    // have to check the list of extensions first
+    let extensions = {
+        let get_client_string = get_proc_address("glXGetClientString");
+        if get_client_string.is_null() {
+            return Err(SwapIntervalError::NotSupported);
+        }
+
+        let get_client_string: extern "C" fn(*mut c_void, c_int) -> *const c_char =
+                                                        mem::transmute(get_client_string);
+        let extensions = get_client_string(display, GLX_EXTENSIONS);
+        if extensions.is_null() {
+            return Err(SwapIntervalError::NotSupported);
+        }
+
+        CStr::from_ptr(extensions).to_string_lossy().into_owned()
+    };
+
+    let has_extension = |name: &str| extensions.split(' ').any(|e| e == name);
+
+    if value < 0 && !has_extension("GLX_EXT_swap_control_tear") {
+        return Err(SwapIntervalError::AdaptiveNotSupported);
+    }
+
+    if has_extension("GLX_EXT_swap_control") {
+        let get_current_drawable: extern "C" fn() -> c_ulong =
+                                mem::transmute(get_proc_address("glXGetCurrentDrawable"));
+        let swap_interval: extern "C" fn(*mut c_void, c_ulong, c_int) =
+                                mem::transmute(get_proc_address("glXSwapIntervalEXT"));
+        swap_interval(display, get_current_drawable(), value);
+        Ok(())
+
+    } else if value >= 0 && has_extension("GLX_MESA_swap_control") {
+        let swap_interval: extern "C" fn(c_uint) -> c_int =
+                                mem::transmute(get_proc_address("glXSwapIntervalMESA"));
+        if swap_interval(value as c_uint) == 0 {
+            Ok(())
+        } else {
+            Err(SwapIntervalError::NotSupported)
+        }
+
+    } else if value >= 1 && has_extension("GLX_SGI_swap_control") {
+        // `GLX_SGI_swap_control` doesn't allow disabling vsync
+        let swap_interval: extern "C" fn(c_int) -> c_int =
+                                mem::transmute(get_proc_address("glXSwapIntervalSGI"));
+        if swap_interval(value) == 0 {
+            Ok(())
+        } else {
+            Err(SwapIntervalError::NotSupported)
+        }
+
+    } else {
+        Err(SwapIntervalError::NotSupported)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+unsafe fn egl_set_swap_interval(get_proc_address: &Fn(&str) -> *const c_void, value: c_int)
+                                -> Result<(), SwapIntervalError>
+{
+    if value < 0 {
+        return Err(SwapIntervalError::AdaptiveNotSupported);
+    }
+
+    let get_current_display = get_proc_address("eglGetCurrentDisplay");
+    let swap_interval = get_proc_address("eglSwapInterval");
+    if get_current_display.is_null() || swap_interval.is_null() {
+        return Err(SwapIntervalError::NotSupported);
+    }
+
+    let get_current_display: extern "system" fn() -> *mut c_void =
+                                                        mem::transmute(get_current_display);
+    let swap_interval: extern "system" fn(*mut c_void, c_int) -> c_uint =
+                                                        mem::transmute(swap_interval);
+
+    let display = get_current_display();
+    if display.is_null() {
+        return Err(SwapIntervalError::NotSupported);
+    }
+
+    if swap_interval(display, value) != 0 {
+        Ok(())
+    } else {
+        Err(SwapIntervalError::NotSupported)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+          target_os = "netbsd", target_os = "openbsd", target_os = "android"))]
+unsafe fn egl_swap_buffers_with_damage(get_proc_address: &Fn(&str) -> *const c_void,
+                                       damage: &[Rect])
+                                       -> Option<Result<(), SwapBuffersError>>
+{
+    const EGL_EXTENSIONS: c_int = 0x3055;
+    const EGL_DRAW: c_int = 0x3059;
+    const EGL_CONTEXT_LOST: c_int = 0x300E;
+
+    let get_current_display = get_proc_address("eglGetCurrentDisplay");
+    let get_current_surface = get_proc_address("eglGetCurrentSurface");
+    let query_string = get_proc_address("eglQueryString");
+    let get_error = get_proc_address("eglGetError");
+    if get_current_display.is_null() || get_current_surface.is_null() ||
+       query_string.is_null() || get_error.is_null()
+    {
+        return None;
+    }
+
+    let get_current_display: extern "system" fn() -> *mut c_void =
+                                                        mem::transmute(get_current_display);
+    let get_current_surface: extern "system" fn(c_int) -> *mut c_void =
+                                                        mem::transmute(get_current_surface);
+    let query_string: extern "system" fn(*mut c_void, c_int) -> *const c_char =
+                                                        mem::transmute(query_string);
+    let get_error: extern "system" fn() -> c_int = mem::transmute(get_error);
+
+    let display = get_current_display();
+    let surface = get_current_surface(EGL_DRAW);
+    if display.is_null() || surface.is_null() {
+        return None;
+    }
+
+    let extensions = query_string(display, EGL_EXTENSIONS);
+    if extensions.is_null() {
+        return None;
+    }
+    let extensions = CStr::from_ptr(extensions).to_string_lossy();
+    let has_extension = |name: &str| extensions.split(' ').any(|e| e == name);
+
+    let swap = if has_extension("EGL_KHR_swap_buffers_with_damage") {
+        get_proc_address("eglSwapBuffersWithDamageKHR")
+    } else if has_extension("EGL_EXT_swap_buffers_with_damage") {
+        get_proc_address("eglSwapBuffersWithDamageEXT")
+    } else {
+        return None;
+    };
+
+    if swap.is_null() {
+        return None;
+    }
+
+    let swap: extern "system" fn(*mut c_void, *mut c_void, *const c_int, c_int) -> c_uint =
+                                                        mem::transmute(swap);
+
+    // EGL's rectangles have their origin at the bottom-left corner of the surface, like ours
+    let mut rects = Vec::with_capacity(damage.len() * 4);
+    for rect in damage {
+        rects.push(rect.left as c_int);
+        rects.push(rect.bottom as c_int);
+        rects.push(rect.width as c_int);
+        rects.push(rect.height as c_int);
+    }
+
+    if swap(display, surface, rects.as_ptr(), damage.len() as c_int) != 0 {
+        return Some(Ok(()));
+    }
+
+    match get_error() {
+        EGL_CONTEXT_LOST => Some(Err(SwapBuffersError::ContextLost)),
+        // the buffers haven't been swapped, so the caller falls back to a regular swap
+        _ => None,
+    }
+}
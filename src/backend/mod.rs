@@ -11,24 +11,76 @@ There are three concepts in play:
  - The `Facade` trait. Calling functions like `VertexBuffer::new` requires passing an object
    that implements this trait. It is implemented on `Rc<Context>`.
 
+Backends that need to query the OpenGL context themselves can use `get_integer` and `get_float`,
+which load the function they call with a `get_proc_address`-like loader. For other functions,
+`get_output` retrieves the values that OpenGL functions write through a pointer without reading
+uninitialized memory.
+
 */
+use std::mem;
 use std::rc::Rc;
 use std::ops::Deref;
 use std::os::raw::c_void;
 
 use CapabilitiesSource;
+use Rect;
 use SwapBuffersError;
 
 use context::Capabilities;
 use context::ExtensionsList;
+use pacing::{SwapInterval, SwapIntervalError};
 use version::Version;
 
 pub use context::Context;
 pub use context::ReleaseBehavior;
+pub use context::SwapBehavior;
+pub use utils::gl_get::{GlOutput, get_output};
 
 #[cfg(feature = "glutin")]
 pub mod glutin;
 
+/// Returns the value of an integer parameter of the current OpenGL context with
+/// `glGetIntegerv`.
+///
+/// `get_proc_address` is used to load `glGetIntegerv`, and usually calls
+/// `Backend::get_proc_address`. Returns `None` if the function can't be loaded.
+///
+/// # Unsafe
+///
+/// The context that `get_proc_address` loads functions from must be current in the thread.
+pub unsafe fn get_integer<F>(get_proc_address: F, pname: u32) -> Option<i32>
+    where F: Fn(&str) -> *const c_void
+{
+    let get_integerv = get_proc_address("glGetIntegerv");
+    if get_integerv.is_null() {
+        return None;
+    }
+
+    let get_integerv: extern "system" fn(u32, *mut i32) = mem::transmute(get_integerv);
+    Some(get_output(|value| get_integerv(pname, value)))
+}
+
+/// Returns the value of a floating-point parameter of the current OpenGL context with
+/// `glGetFloatv`.
+///
+/// `get_proc_address` is used to load `glGetFloatv`, and usually calls
+/// `Backend::get_proc_address`. Returns `None` if the function can't be loaded.
+///
+/// # Unsafe
+///
+/// The context that `get_proc_address` loads functions from must be current in the thread.
+pub unsafe fn get_float<F>(get_proc_address: F, pname: u32) -> Option<f32>
+    where F: Fn(&str) -> *const c_void
+{
+    let get_floatv = get_proc_address("glGetFloatv");
+    if get_floatv.is_null() {
+        return None;
+    }
+
+    let get_floatv: extern "system" fn(u32, *mut f32) = mem::transmute(get_floatv);
+    Some(get_output(|value| get_floatv(pname, value)))
+}
+
 /// Trait for types that can be used as a backend for a glium context.
 ///
 /// This trait is unsafe, as you can get undefined behaviors or crashes if you don't implement
@@ -37,6 +89,16 @@ pub unsafe trait Backend {
     /// Swaps buffers at the end of a frame.
     fn swap_buffers(&self) -> Result<(), SwapBuffersError>;
 
+    /// Swaps buffers at the end of a frame, and tells the windowing system that only the given
+    /// regions have changed since the previous frame.
+    ///
+    /// The default implementation ignores the damage and calls `swap_buffers`.
+    #[inline]
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        let _ = damage;
+        self.swap_buffers()
+    }
+
     /// Returns the address of an OpenGL function.
     ///
     /// Supposes that the context has been made current before this function is called.
@@ -50,6 +112,67 @@ pub unsafe trait Backend {
 
     /// Makes the OpenGL context the current context in the current thread.
     unsafe fn make_current(&self);
+
+    /// Changes the number of vertical blanks to wait for when swapping buffers.
+    ///
+    /// Supposes that the context has been made current before this function is called. The
+    /// default implementation returns `SwapIntervalError::NotSupported`.
+    #[inline]
+    fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        let _ = interval;
+        Err(SwapIntervalError::NotSupported)
+    }
+
+    /// Returns the attributes of the OpenGL context and of its default framebuffer, as known
+    /// by the windowing system.
+    ///
+    /// Called once when the context is created. The default implementation doesn't report
+    /// anything, and glium queries OpenGL instead.
+    #[inline]
+    fn get_context_attributes(&self) -> ContextAttributes {
+        Default::default()
+    }
+}
+
+/// Attributes of an OpenGL context and of its default framebuffer, reported by a `Backend`.
+///
+/// Glium uses these values for the `Capabilities` of the context instead of querying OpenGL,
+/// as some drivers return wrong values. Each field is `None` if the backend doesn't know it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextAttributes {
+    /// Number of bits of the red, green, blue and alpha channels of the color buffer.
+    pub color_bits: Option<[u16; 4]>,
+
+    /// Number of bits of the depth buffer, or 0 if there is no depth buffer.
+    pub depth_bits: Option<u16>,
+
+    /// Number of bits of the stencil buffer, or 0 if there is no stencil buffer.
+    pub stencil_bits: Option<u16>,
+
+    /// Number of samples per pixel, or 0 if the default framebuffer isn't multisampled.
+    pub samples: Option<u16>,
+
+    /// True if the default framebuffer has a back buffer.
+    pub double_buffer: Option<bool>,
+
+    /// True if the default framebuffer is in sRGB.
+    pub srgb: Option<bool>,
+
+    /// True if the default framebuffer has left and right buffers.
+    pub stereo: Option<bool>,
+
+    /// What happens to the content of the back buffer when swapping buffers.
+    pub swap_behavior: Option<SwapBehavior>,
+
+    /// True if the context was created with the debug flag.
+    pub debug: Option<bool>,
+
+    /// True if the context was created with the forward-compatible flag. Must be reported
+    /// along with `debug`, otherwise it is ignored.
+    pub forward_compatible: Option<bool>,
+
+    /// True if the context was created with robust buffer access.
+    pub robustness: Option<bool>,
 }
 
 unsafe impl<T> Backend for Rc<T> where T: Backend {
@@ -57,6 +180,10 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
         self.deref().swap_buffers()
     }
 
+    fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.deref().swap_buffers_with_damage(damage)
+    }
+
     unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
         self.deref().get_proc_address(symbol)
     }
@@ -72,6 +199,14 @@ unsafe impl<T> Backend for Rc<T> where T: Backend {
     unsafe fn make_current(&self) {
         self.deref().make_current();
     }
+
+    fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        self.deref().set_swap_interval(interval)
+    }
+
+    fn get_context_attributes(&self) -> ContextAttributes {
+        self.deref().get_context_attributes()
+    }
 }
 
 /// Trait for types that provide a safe access for glium functions.
@@ -1,7 +1,9 @@
 use backend::Facade;
 use context::CommandContext;
 use context::Context;
+use context::PooledPixelBuffer;
 use version::Version;
+use sync;
 use CapabilitiesSource;
 use ContextExt;
 use gl;
@@ -13,8 +15,10 @@ use std::rc::Rc;
 use std::ops::{Deref, DerefMut, Range};
 use GlObject;
 use TransformFeedbackSessionExt;
+use interop::MemoryObject;
+use debug::ObjectKind;
 
-use buffer::{Content, BufferType, BufferMode, BufferCreationError};
+use buffer::{Content, BufferType, BufferMode, BufferCreationError, MapWriteOptions};
 use vertex::TransformFeedbackSession;
 use vertex_array_object::VertexAttributesSystem;
 
@@ -101,8 +105,26 @@ pub struct Alloc {
 
     /// ID of the draw call where the buffer was last written as an SSBO.
     latest_shader_write: Cell<u64>,
+
+    /// If `Some`, the buffer comes from the pixel buffers pool of the context and will be
+    /// returned to it when destroyed. Contains the actual size in bytes of the buffer, which can
+    /// be larger than `size`.
+    pool_capacity: Option<usize>,
+
+    /// If true, the buffer was created with `GL_SPARSE_STORAGE_BIT_ARB` and its pages must be
+    /// committed before being used.
+    sparse: bool,
 }
 
+/// Maximum number of pixel buffers that the context keeps around for reuse.
+const MAX_POOLED_PIXEL_BUFFERS: usize = 16;
+
+/// Maximum total size in bytes of the pixel buffers that the context keeps around for reuse.
+const MAX_POOLED_PIXEL_BUFFERS_BYTES: usize = 16 * 1024 * 1024;
+
+/// Pixel buffers larger than this size are never pooled.
+const MAX_POOLED_PIXEL_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
 impl Alloc {
     /// Builds a new buffer containing the given data. The size of the buffer is equal to the
     /// size of the data.
@@ -118,6 +140,9 @@ impl Alloc {
             create_buffer(&mut ctxt, size, Some(data), ty, mode)
         });
 
+        ctxt.live_objects.insert(ObjectKind::Buffer, id, 0, Some(size),
+                                 Some(format!("{:?}", ty)), None);
+
         Ok(Alloc {
             context: facade.get_context().clone(),
             id: id,
@@ -129,6 +154,8 @@ impl Alloc {
             creation_mode: mode,
             mapped: Cell::new(false),
             latest_shader_write: Cell::new(0),
+            pool_capacity: None,
+            sparse: false,
         })
     }
 
@@ -142,6 +169,9 @@ impl Alloc {
             create_buffer::<()>(&mut ctxt, size, None, ty, mode)
         });
 
+        ctxt.live_objects.insert(ObjectKind::Buffer, id, 0, Some(size),
+                                 Some(format!("{:?}", ty)), None);
+
         Ok(Alloc {
             context: facade.get_context().clone(),
             id: id,
@@ -153,6 +183,178 @@ impl Alloc {
             creation_mode: mode,
             mapped: Cell::new(false),
             latest_shader_write: Cell::new(0),
+            pool_capacity: None,
+            sparse: false,
+        })
+    }
+
+    /// Builds a new empty buffer of the given size, reusing a buffer from the pixel buffers pool
+    /// of the context if possible.
+    ///
+    /// Buffers are grouped by sizes rounded up to the next power of two. A buffer of the pool is
+    /// only reused once the GPU has finished using it. When the `Alloc` is destroyed, the buffer
+    /// is returned to the pool instead of being deleted. The pool only keeps a limited number of
+    /// bytes and evicts the buffers that were returned the longest time ago first.
+    ///
+    /// Buffers larger than 4 MiB are neither taken from nor returned to the pool.
+    pub fn empty_pooled<F: ?Sized>(facade: &F, ty: BufferType, size: usize)
+                           -> Result<Alloc, BufferCreationError> where F: Facade
+    {
+        if size > MAX_POOLED_PIXEL_BUFFER_SIZE {
+            return Alloc::empty(facade, ty, size, BufferMode::Default);
+        }
+
+        let mut ctxt = facade.get_context().make_current();
+
+        let capacity = size.next_power_of_two();
+
+        let mut pooled = None;
+
+        for index in 0 .. ctxt.pixel_buffers_pool.len() {
+            if ctxt.pixel_buffers_pool[index].size != capacity {
+                continue;
+            }
+
+            // the fence is taken out of the pool while checking it, and put back if the GPU is
+            // still using the buffer
+            if let Some(fence) = ctxt.pixel_buffers_pool[index].fence.take() {
+                if unsafe { sync::is_linear_sync_fence_signaled(&mut ctxt, &fence) } {
+                    unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence) };
+                } else {
+                    ctxt.pixel_buffers_pool[index].fence = Some(fence);
+                    continue;
+                }
+            }
+
+            pooled = Some(ctxt.pixel_buffers_pool.remove(index));
+            break;
+        }
+
+        let (id, immutable, created_with_buffer_storage) = match pooled {
+            Some(buffer) => (buffer.id, buffer.immutable, buffer.created_with_buffer_storage),
+            None => {
+                let (id, immutable, created_with_buffer_storage, _) = try!(unsafe {
+                    create_buffer::<()>(&mut ctxt, capacity, None, ty, BufferMode::Default)
+                });
+
+                (id, immutable, created_with_buffer_storage)
+            },
+        };
+
+        ctxt.live_objects.insert(ObjectKind::Buffer, id, 0, Some(capacity),
+                                 Some(format!("{:?}", ty)), None);
+
+        Ok(Alloc {
+            context: facade.get_context().clone(),
+            id: id,
+            ty: ty,
+            size: size,
+            persistent_mapping: None,
+            immutable: immutable,
+            created_with_buffer_storage: created_with_buffer_storage,
+            creation_mode: BufferMode::Default,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+            pool_capacity: Some(capacity),
+            sparse: false,
+        })
+    }
+
+    /// Builds a new sparse buffer of the given size. None of its pages is committed.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `size` is not a multiple of the page size of sparse buffers.
+    pub fn empty_sparse<F: ?Sized>(facade: &F, ty: BufferType, size: usize)
+                           -> Result<Alloc, BufferCreationError> where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        let page_size = match ctxt.capabilities.sparse_buffer_page_size {
+            Some(page_size) => page_size as usize,
+            None => return Err(BufferCreationError::SparseBuffersNotSupported),
+        };
+
+        assert!(size % page_size == 0, "The size of a sparse buffer must be a multiple of \
+                                        the page size");
+
+        let id = try!(unsafe { create_sparse_buffer(&mut ctxt, size, ty) });
+
+        ctxt.live_objects.insert(ObjectKind::Buffer, id, 0, Some(size),
+                                 Some(format!("{:?}", ty)), None);
+
+        Ok(Alloc {
+            context: facade.get_context().clone(),
+            id: id,
+            ty: ty,
+            size: size,
+            persistent_mapping: None,
+            immutable: true,
+            created_with_buffer_storage: true,
+            creation_mode: BufferMode::Default,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+            pool_capacity: None,
+            sparse: true,
+        })
+    }
+
+    /// Builds a new buffer whose storage is a range of an imported memory object.
+    ///
+    /// # Unsafety
+    ///
+    /// The content of the memory must be valid for the buffer type.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of the bounds of the memory object.
+    pub unsafe fn from_memory_object<F: ?Sized>(facade: &F, ty: BufferType,
+                                                memory: &MemoryObject, offset: u64, size: usize)
+                                                -> Result<Alloc, BufferCreationError>
+                                                where F: Facade
+    {
+        assert!(offset + size as u64 <= memory.get_size(),
+                "The range is out of the bounds of the memory object");
+
+        let mut ctxt = facade.get_context().make_current();
+
+        if !is_buffer_type_supported(&mut ctxt, ty) {
+            return Err(BufferCreationError::BufferTypeNotSupported);
+        }
+
+        if !ctxt.extensions.gl_ext_memory_object {
+            return Err(BufferCreationError::MemoryObjectsNotSupported);
+        }
+
+        let mut id: gl::types::GLuint = 0;
+
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+            ctxt.gl.CreateBuffers(1, &mut id);
+            ctxt.gl.NamedBufferStorageMemEXT(id, size as gl::types::GLsizeiptr, memory.get_id(),
+                                             offset);
+        } else {
+            ctxt.gl.GenBuffers(1, &mut id);
+            let bind = bind_buffer(&mut ctxt, id, ty);
+            ctxt.gl.BufferStorageMemEXT(bind, size as gl::types::GLsizeiptr, memory.get_id(),
+                                        offset);
+        }
+
+        ctxt.live_objects.insert(ObjectKind::Buffer, id, 0, Some(size),
+                                 Some(format!("{:?}", ty)), None);
+
+        Ok(Alloc {
+            context: facade.get_context().clone(),
+            id: id,
+            ty: ty,
+            size: size,
+            persistent_mapping: None,
+            immutable: true,
+            created_with_buffer_storage: true,
+            creation_mode: BufferMode::Immutable,
+            mapped: Cell::new(false),
+            latest_shader_write: Cell::new(0),
+            pool_capacity: None,
+            sparse: false,
         })
     }
 
@@ -162,6 +364,50 @@ impl Alloc {
         &self.context
     }
 
+    /// Returns true if the buffer was created as a sparse buffer.
+    #[inline]
+    pub fn is_sparse(&self) -> bool {
+        self.sparse
+    }
+
+    /// Commits or decommits the physical memory of a range of bytes of a sparse buffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer is not sparse, if the range is out of bounds, or if the start or the
+    /// length of the range are not multiples of the page size.
+    pub fn set_page_commitment(&self, range: Range<usize>, commit: bool) {
+        assert!(self.sparse, "The buffer is not a sparse buffer");
+        assert!(range.start <= range.end && range.end <= self.size);
+
+        let mut ctxt = self.context.make_current();
+
+        let page_size = ctxt.capabilities.sparse_buffer_page_size.unwrap() as usize;
+        assert!(range.start % page_size == 0, "The range must be aligned to the page size");
+        assert!(range.end % page_size == 0 || range.end == self.size,
+                "The range must be aligned to the page size");
+
+        self.assert_unmapped(&mut ctxt);
+        self.assert_not_transform_feedback(&mut ctxt);
+
+        let offset = range.start as gl::types::GLintptr;
+        let size = (range.end - range.start) as gl::types::GLsizeiptr;
+        let commit = if commit { gl::TRUE } else { gl::FALSE };
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.NamedBufferPageCommitmentARB(self.id, offset, size, commit);
+            } else if ctxt.extensions.gl_ext_direct_state_access {
+                ctxt.gl.NamedBufferPageCommitmentEXT(self.id, offset, size, commit);
+            } else {
+                let bind = bind_buffer(&mut ctxt, self.id, self.ty);
+                ctxt.gl.BufferPageCommitmentARB(bind, offset, size, commit);
+            }
+        }
+    }
+
     /// Returns the total size in bytes of this buffer.
     #[inline]
     pub fn get_size(&self) -> usize {
@@ -208,6 +454,7 @@ impl Alloc {
     pub fn prepare_for_vertex_attrib_array(&self, ctxt: &mut CommandContext) {
         self.assert_unmapped(ctxt);
         self.assert_not_transform_feedback(ctxt);
+        ctxt.live_objects.mark_used(ObjectKind::Buffer, self.id);
 
         if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_vertex_attrib_array {
             unsafe { ctxt.gl.MemoryBarrier(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT); }
@@ -219,6 +466,7 @@ impl Alloc {
     pub fn prepare_for_element_array(&self, ctxt: &mut CommandContext) {
         self.assert_unmapped(ctxt);
         self.assert_not_transform_feedback(ctxt);
+        ctxt.live_objects.mark_used(ObjectKind::Buffer, self.id);
 
         if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_element_array {
             unsafe { ctxt.gl.MemoryBarrier(gl::ELEMENT_ARRAY_BARRIER_BIT); }
@@ -318,6 +566,20 @@ impl Alloc {
         unsafe { bind_buffer(ctxt, self.id, BufferType::DrawIndirectBuffer); }
     }
 
+    /// Makes sure that the buffer is bound to the `GL_PARAMETER_BUFFER` and calls
+    /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
+    pub fn prepare_and_bind_for_draw_parameters(&self, ctxt: &mut CommandContext) {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_command {
+            unsafe { ctxt.gl.MemoryBarrier(gl::COMMAND_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_command = ctxt.state.next_draw_call_id;
+        }
+
+        unsafe { bind_buffer(ctxt, self.id, BufferType::ParameterBuffer); }
+    }
+
     /// Makes sure that the buffer is bound to the `GL_DISPATCH_INDIRECT_BUFFER` and calls
     /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
     pub fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext) {
@@ -348,6 +610,33 @@ impl Alloc {
         self.indexed_bind(ctxt, BufferType::UniformBuffer, index, range);
     }
 
+    /// Calls `glMemoryBarrier(GL_TEXTURE_FETCH_BARRIER_BIT)` if necessary.
+    pub fn prepare_for_texture_fetch(&self, ctxt: &mut CommandContext) {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_texture_fetch {
+            unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_texture_fetch = ctxt.state.next_draw_call_id;
+        }
+    }
+
+    /// Calls `glMemoryBarrier(GL_SHADER_IMAGE_ACCESS_BARRIER_BIT)` if necessary. If `write` is
+    /// true, the buffer is considered as modified by the next draw call.
+    pub fn prepare_for_shader_image_access(&self, ctxt: &mut CommandContext, write: bool) {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_shader_image_access {
+            unsafe { ctxt.gl.MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_shader_image_access = ctxt.state.next_draw_call_id;
+        }
+
+        if write {
+            self.latest_shader_write.set(ctxt.state.next_draw_call_id);
+        }
+    }
+
     /// Makes sure that the buffer is bound to the indexed `GL_SHARED_STORAGE_BUFFER` point and calls
     /// `glMemoryBarrier(GL_SHADER_STORAGE_BARRIER_BIT)` if necessary.
     pub fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint,
@@ -385,6 +674,7 @@ impl Alloc {
     #[inline]
     fn bind(&self, ctxt: &mut CommandContext, ty: BufferType) {
         self.assert_unmapped(ctxt);
+        ctxt.live_objects.mark_used(ObjectKind::Buffer, self.id);
         unsafe { bind_buffer(ctxt, self.id, ty); }
     }
 
@@ -403,6 +693,7 @@ impl Alloc {
                     index: gl::types::GLuint, range: Range<usize>)
     {
         self.assert_unmapped(ctxt);
+        ctxt.live_objects.mark_used(ObjectKind::Buffer, self.id);
         unsafe { indexed_bind_buffer(ctxt, self.id, ty, index, range); }
     }
 
@@ -564,7 +855,7 @@ impl Alloc {
     ///
     /// # Panic
     ///
-    /// Panics if the `bytes_range` is not aligned to a mappable slice.
+    /// Panics if the buffer is sparse or if the `bytes_range` is not aligned to a mappable slice.
     ///
     /// # Unsafety
     ///
@@ -577,6 +868,8 @@ impl Alloc {
     unsafe fn map_shared<D: ?Sized>(&self, bytes_range: Range<usize>, read: bool, write: bool)
                                     -> MappingImpl<D> where D: Content
     {
+        assert!(!self.sparse, "Sparse buffers can't be mapped");
+
         if let Some(existing_mapping) = self.persistent_mapping.clone() {
             // TODO: optimize so that it's not always necessary to make the context current
             let mut ctxt = self.context.make_current();
@@ -617,7 +910,8 @@ impl Alloc {
                                 temporary_buffer, 0, size_bytes).unwrap();
                 }
 
-                map_buffer(&mut ctxt, temporary_buffer, self.ty, 0 .. size_bytes, true, true)
+                map_buffer(&mut ctxt, temporary_buffer, self.ty, 0 .. size_bytes, true, true,
+                           Default::default())
                                     .expect("Buffer mapping is not supported by the backend")
             };
 
@@ -652,7 +946,7 @@ impl Alloc {
     ///
     /// # Panic
     ///
-    /// Panics if the `bytes_range` is not aligned to a mappable slice.
+    /// Panics if the buffer is sparse or if the `bytes_range` is not aligned to a mappable slice.
     ///
     /// # Unsafety
     ///
@@ -662,9 +956,14 @@ impl Alloc {
     /// If you pass `false` for `read`, you **must not** read the returned buffer. If you pass
     /// `false` for `write`, you **must not** write the returned buffer.
     ///
-    unsafe fn map_impl<D: ?Sized>(&mut self, bytes_range: Range<usize>, read: bool, write: bool)
-                                  -> MappingImpl<D> where D: Content
+    /// `options` are only used when mapping with `glMapBufferRange`, and only if `read` is
+    /// `false`.
+    ///
+    unsafe fn map_impl<D: ?Sized>(&mut self, bytes_range: Range<usize>, read: bool, write: bool,
+                                  options: MapWriteOptions) -> MappingImpl<D> where D: Content
     {
+        assert!(!self.sparse, "Sparse buffers can't be mapped");
+
         if self.persistent_mapping.is_some() || self.immutable {
             self.map_shared(bytes_range, read, write)
 
@@ -677,7 +976,7 @@ impl Alloc {
                     self.assert_not_transform_feedback(&mut ctxt);
                     self.barrier_for_buffer_update(&mut ctxt);
                     let ptr = map_buffer(&mut ctxt, self.id, self.ty, bytes_range.clone(),
-                                         read, write)
+                                         read, write, options)
                                         .expect("Buffer mapping is not supported by the backend");
                     self.mapped.set(true);
                     ptr
@@ -704,7 +1003,7 @@ impl Alloc {
     ///
     /// # Panic
     ///
-    /// Panics if the `bytes_range` is not aligned to a mappable slice.
+    /// Panics if the buffer is sparse or if the `bytes_range` is not aligned to a mappable slice.
     ///
     /// # Unsafety
     ///
@@ -716,7 +1015,7 @@ impl Alloc {
                                  -> Mapping<D> where D: Content
     {
         Mapping {
-            mapping: self.map_impl(bytes_range, true, true)
+            mapping: self.map_impl(bytes_range, true, true, Default::default())
         }
     }
 
@@ -724,7 +1023,7 @@ impl Alloc {
     ///
     /// # Panic
     ///
-    /// Panics if the `bytes_range` is not aligned to a mappable slice.
+    /// Panics if the buffer is sparse or if the `bytes_range` is not aligned to a mappable slice.
     ///
     /// # Unsafety
     ///
@@ -736,7 +1035,7 @@ impl Alloc {
                                       -> ReadMapping<D> where D: Content
     {
         ReadMapping {
-            mapping: self.map_impl(bytes_range, true, false)
+            mapping: self.map_impl(bytes_range, true, false, Default::default())
         }
     }
 
@@ -744,7 +1043,7 @@ impl Alloc {
     ///
     /// # Panic
     ///
-    /// Panics if the `bytes_range` is not aligned to a mappable slice.
+    /// Panics if the buffer is sparse or if the `bytes_range` is not aligned to a mappable slice.
     ///
     /// # Unsafety
     ///
@@ -756,7 +1055,29 @@ impl Alloc {
                                        -> WriteMapping<D> where D: Content
     {
         WriteMapping {
-            mapping: self.map_impl(bytes_range, false, true)
+            mapping: self.map_impl(bytes_range, false, true, Default::default())
+        }
+    }
+
+    /// Returns a write-only mapping in memory of the content of the buffer, with additional
+    /// options passed to `glMapBufferRange`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer is sparse or if the `bytes_range` is not aligned to a mappable slice.
+    ///
+    /// # Unsafety
+    ///
+    /// If the buffer uses persistent mapping, the caller of this function must handle
+    /// synchronization.
+    ///
+    #[inline]
+    pub unsafe fn map_write_with_options<D: ?Sized>(&mut self, bytes_range: Range<usize>,
+                                                    options: MapWriteOptions)
+                                                    -> WriteMapping<D> where D: Content
+    {
+        WriteMapping {
+            mapping: self.map_impl(bytes_range, false, true, options)
         }
     }
 
@@ -777,6 +1098,10 @@ impl Alloc {
     {
         let size_to_read = range.end - range.start;
 
+        if self.sparse {
+            return Err(ReadError::NotSupported);
+        }
+
         if self.persistent_mapping.is_some() {
             let mapping = ReadMapping { mapping: self.map_shared(range, true, false) };
             <D as Content>::read(size_to_read, |output| {
@@ -824,6 +1149,73 @@ impl Alloc {
         }
     }
 
+    /// Fills a range of the buffer with copies of `value`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of bounds or if its length is not a multiple of the size of
+    /// `T`.
+    ///
+    /// # Unsafety
+    ///
+    /// If the buffer uses persistent mapping, the caller of this function must handle
+    /// synchronization.
+    ///
+    pub unsafe fn clear<T>(&self, bytes_range: Range<usize>, value: &T) where T: Copy {
+        let size = mem::size_of::<T>();
+
+        assert!(bytes_range.start <= bytes_range.end && bytes_range.end <= self.size);
+        assert!(size != 0 && (bytes_range.end - bytes_range.start) % size == 0);
+
+        if bytes_range.start == bytes_range.end {
+            return;
+        }
+
+        let mut ctxt = self.context.make_current();
+
+        let clear_supported = ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                              ctxt.extensions.gl_arb_clear_buffer_object;
+
+        // `glClearBufferSubData` requires the offset to be a multiple of the size of the format
+        let format = if clear_supported && self.persistent_mapping.is_none() &&
+                        bytes_range.start % size == 0
+        {
+            clear_buffer_format(size)
+        } else {
+            None
+        };
+
+        if let Some((internal_format, format, ty)) = format {
+            self.assert_unmapped(&mut ctxt);
+            self.assert_not_transform_feedback(&mut ctxt);
+            self.barrier_for_buffer_update(&mut ctxt);
+
+            let offset = bytes_range.start as gl::types::GLintptr;
+            let len = (bytes_range.end - bytes_range.start) as gl::types::GLsizeiptr;
+            let data = value as *const T as *const _;
+
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.ClearNamedBufferSubData(self.id, internal_format, offset, len, format,
+                                                ty, data);
+            } else {
+                let bind = bind_buffer(&mut ctxt, self.id, self.ty);
+                ctxt.gl.ClearBufferSubData(bind, internal_format, offset, len, format, ty, data);
+            }
+
+        } else {
+            drop(ctxt);
+
+            let mut mapping = Mapping {
+                mapping: self.map_shared::<[T]>(bytes_range, false, true),
+            };
+            for element in mapping.iter_mut() {
+                *element = *value;
+            }
+        }
+    }
+
     /// Copies data from this buffer to another one.
     ///
     /// With persistent-mapped buffers you must create a sync fence *after* this operation.
@@ -859,12 +1251,51 @@ impl fmt::Debug for Alloc {
 
 impl Drop for Alloc {
     fn drop(&mut self) {
+        // buffers that have been deleted and must be purged from the other contexts
+        let mut destroyed = Vec::with_capacity(1);
+
         unsafe {
             let mut ctxt = self.context.make_current();
             self.assert_unmapped(&mut ctxt);
             self.assert_not_transform_feedback(&mut ctxt);
             VertexAttributesSystem::purge_buffer(&mut ctxt, self.id);
-            destroy_buffer(&mut ctxt, self.id);
+            ctxt.live_objects.remove(ObjectKind::Buffer, self.id);
+
+            if let Some(capacity) = self.pool_capacity {
+                let fence = sync::new_linear_sync_fence(&mut ctxt).ok();
+                ctxt.pixel_buffers_pool.push(PooledPixelBuffer {
+                    id: self.id,
+                    size: capacity,
+                    immutable: self.immutable,
+                    created_with_buffer_storage: self.created_with_buffer_storage,
+                    fence: fence,
+                });
+
+                // the buffers that were returned the longest time ago are at the front of the pool
+                loop {
+                    let total_size = ctxt.pixel_buffers_pool.iter().map(|b| b.size).sum::<usize>();
+                    if ctxt.pixel_buffers_pool.len() <= MAX_POOLED_PIXEL_BUFFERS &&
+                       total_size <= MAX_POOLED_PIXEL_BUFFERS_BYTES
+                    {
+                        break;
+                    }
+
+                    let evicted = ctxt.pixel_buffers_pool.remove(0);
+                    if let Some(fence) = evicted.fence {
+                        sync::destroy_linear_sync_fence(&mut ctxt, fence);
+                    }
+                    destroy_buffer(&mut ctxt, evicted.id);
+                    destroyed.push(evicted.id);
+                }
+
+            } else {
+                destroy_buffer(&mut ctxt, self.id);
+                destroyed.push(self.id);
+            }
+        }
+
+        for id in destroyed {
+            self.context.purge_shared_object(ObjectKind::Buffer, id);
         }
     }
 }
@@ -1124,7 +1555,7 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
 
     // creating the id of the buffer
     let id = {
-        let mut id: gl::types::GLuint = mem::uninitialized();
+        let mut id: gl::types::GLuint = 0;
         if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
             ctxt.gl.CreateBuffers(1, &mut id);
         } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
@@ -1179,7 +1610,7 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
     };
 
     // will store the actual size of the buffer so that we can compare it with the expected size
-    let mut obtained_size: gl::types::GLint = mem::uninitialized();
+    let mut obtained_size: gl::types::GLint = 0;
 
     // the value of `immutable` is determined below
     // if true, the buffer won't be modifiable with regular OpenGL function calls
@@ -1297,6 +1728,60 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
     Ok((id, immutable, created_with_buffer_storage, persistent_mapping))
 }
 
+/// Creates a new sparse buffer and returns its identifier. None of its pages is committed.
+///
+/// # Unsafety
+///
+/// The size must be a multiple of the page size of sparse buffers.
+unsafe fn create_sparse_buffer(mut ctxt: &mut CommandContext, size: usize, ty: BufferType)
+                               -> Result<gl::types::GLuint, BufferCreationError>
+{
+    if !is_buffer_type_supported(ctxt, ty) {
+        return Err(BufferCreationError::BufferTypeNotSupported);
+    }
+
+    if !ctxt.extensions.gl_arb_sparse_buffer {
+        return Err(BufferCreationError::SparseBuffersNotSupported);
+    }
+
+    let flags = gl::SPARSE_STORAGE_BIT_ARB | gl::DYNAMIC_STORAGE_BIT;
+
+    let mut id: gl::types::GLuint = 0;
+
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        ctxt.gl.CreateBuffers(1, &mut id);
+        ctxt.gl.NamedBufferStorage(id, size as gl::types::GLsizeiptr, ptr::null(), flags);
+
+    } else if ctxt.extensions.gl_ext_direct_state_access {
+        ctxt.gl.GenBuffers(1, &mut id);
+        ctxt.gl.NamedBufferStorageEXT(id, size as gl::types::GLsizeiptr, ptr::null(), flags);
+
+    } else {
+        // `GL_ARB_sparse_buffer` requires `GL_ARB_buffer_storage`
+        ctxt.gl.GenBuffers(1, &mut id);
+        let bind = bind_buffer(&mut ctxt, id, ty);
+        ctxt.gl.BufferStorage(bind, size as gl::types::GLsizeiptr, ptr::null(), flags);
+    }
+
+    Ok(id)
+}
+
+/// Returns the internal format, format and type to pass to `glClearBufferSubData` in order to
+/// fill a buffer with values of the given size. Returns `None` if no format matches.
+fn clear_buffer_format(size: usize)
+                       -> Option<(gl::types::GLenum, gl::types::GLenum, gl::types::GLenum)>
+{
+    match size {
+        1 => Some((gl::R8UI, gl::RED_INTEGER, gl::UNSIGNED_BYTE)),
+        2 => Some((gl::R16UI, gl::RED_INTEGER, gl::UNSIGNED_SHORT)),
+        4 => Some((gl::R32UI, gl::RED_INTEGER, gl::UNSIGNED_INT)),
+        8 => Some((gl::RG32UI, gl::RG_INTEGER, gl::UNSIGNED_INT)),
+        12 => Some((gl::RGB32UI, gl::RGB_INTEGER, gl::UNSIGNED_INT)),
+        16 => Some((gl::RGBA32UI, gl::RGBA_INTEGER, gl::UNSIGNED_INT)),
+        _ => None,
+    }
+}
+
 /// Returns true if a given buffer type is supported on a platform.
 fn is_buffer_type_supported(ctxt: &mut CommandContext, ty: BufferType) -> bool {
     match ty {
@@ -1331,6 +1816,10 @@ fn is_buffer_type_supported(ctxt: &mut CommandContext, ty: BufferType) -> bool {
             ctxt.extensions.gl_ext_multi_draw_indirect
         },
 
+        BufferType::ParameterBuffer => {
+            ctxt.extensions.gl_arb_indirect_parameters
+        },
+
         BufferType::DispatchIndirectBuffer => {
             ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
             ctxt.extensions.gl_arb_compute_shader
@@ -1410,6 +1899,7 @@ unsafe fn bind_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint, ty: Buff
     check!(ctxt, id, ty, CopyWriteBuffer, copy_write_buffer_binding);
     check!(ctxt, id, ty, DispatchIndirectBuffer, dispatch_indirect_buffer_binding);
     check!(ctxt, id, ty, DrawIndirectBuffer, draw_indirect_buffer_binding);
+    check!(ctxt, id, ty, ParameterBuffer, parameter_buffer_binding);
     check!(ctxt, id, ty, QueryBuffer, query_buffer_binding);
     check!(ctxt, id, ty, TextureBuffer, texture_buffer_binding);
     check!(ctxt, id, ty, AtomicCounterBuffer, atomic_counter_buffer_binding);
@@ -1484,17 +1974,36 @@ unsafe fn indexed_bind_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint,
                 }
 
                 if ctxt.state.$state_var.len() <= $input_index as usize {
-                    for _ in 0 .. 1 + ctxt.state.$state_var.len() - $input_index as usize {
+                    for _ in ctxt.state.$state_var.len() .. $input_index as usize + 1 {
                         ctxt.state.$state_var.push(Default::default());
                     }
                 }
 
-                let unit = &mut ctxt.state.$state_var[$input_index as usize];
+                let state = &mut *ctxt.state;
+                let unit = &mut state.$state_var[$input_index as usize];
                 if unit.buffer != $input_id || unit.offset != offset || unit.size != size {
                     unit.buffer = $input_id;
                     unit.offset = offset;
                     unit.size = size;
 
+                    // the binding is sent later with `glBindBuffersRange`
+                    if state.pending_multi_bind.active {
+                        match $input_ty {
+                            BufferType::UniformBuffer => {
+                                state.pending_multi_bind.uniform_buffers.push($input_index as u16);
+                                return;
+                            },
+                            BufferType::ShaderStorageBuffer => {
+                                state.pending_multi_bind.shader_storage_buffers
+                                                        .push($input_index as u16);
+                                return;
+                            },
+                            _ => ()
+                        }
+                    }
+
+                    state.statistics.indexed_buffer_bind_calls += 1;
+
                     if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                        ctxt.version >= &Version(Api::GlEs, 3, 0)
                     {
@@ -1612,78 +2121,7 @@ unsafe fn destroy_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
     // FIXME: uncomment this and move it from Buffer's destructor
     //self.context.vertex_array_objects.purge_buffer(&mut ctxt, id);
 
-    if ctxt.state.array_buffer_binding == id {
-        ctxt.state.array_buffer_binding = 0;
-    }
-
-    if ctxt.state.pixel_pack_buffer_binding == id {
-        ctxt.state.pixel_pack_buffer_binding = 0;
-    }
-
-    if ctxt.state.pixel_unpack_buffer_binding == id {
-        ctxt.state.pixel_unpack_buffer_binding = 0;
-    }
-
-    if ctxt.state.uniform_buffer_binding == id {
-        ctxt.state.uniform_buffer_binding = 0;
-    }
-
-    if ctxt.state.copy_read_buffer_binding == id {
-        ctxt.state.copy_read_buffer_binding = 0;
-    }
-
-    if ctxt.state.copy_write_buffer_binding == id {
-        ctxt.state.copy_write_buffer_binding = 0;
-    }
-
-    if ctxt.state.dispatch_indirect_buffer_binding == id {
-        ctxt.state.dispatch_indirect_buffer_binding = 0;
-    }
-
-    if ctxt.state.draw_indirect_buffer_binding == id {
-        ctxt.state.draw_indirect_buffer_binding = 0;
-    }
-
-    if ctxt.state.query_buffer_binding == id {
-        ctxt.state.query_buffer_binding = 0;
-    }
-
-    if ctxt.state.texture_buffer_binding == id {
-        ctxt.state.texture_buffer_binding = 0;
-    }
-
-    if ctxt.state.atomic_counter_buffer_binding == id {
-        ctxt.state.atomic_counter_buffer_binding = 0;
-    }
-
-    if ctxt.state.shader_storage_buffer_binding == id {
-        ctxt.state.shader_storage_buffer_binding = 0;
-    }
-
-    for point in ctxt.state.indexed_atomic_counter_buffer_bindings.iter_mut() {
-        if point.buffer == id {
-            point.buffer = 0;
-        }
-    }
-
-    for point in ctxt.state.indexed_shader_storage_buffer_bindings.iter_mut() {
-        if point.buffer == id {
-            point.buffer = 0;
-        }
-    }
-
-    for point in ctxt.state.indexed_uniform_buffer_bindings.iter_mut() {
-        if point.buffer == id {
-            point.buffer = 0;
-        }
-    }
-
-    for point in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
-        // FIXME: end transform feedback if it is active
-        if point.buffer == id {
-            point.buffer = 0;
-        }
-    }
+    ctxt.state.forget_buffer(id);
 
     if ctxt.version >= &Version(Api::Gl, 1, 5) ||
         ctxt.version >= &Version(Api::GlEs, 2, 0)
@@ -1725,15 +2163,26 @@ unsafe fn flush_range(mut ctxt: &mut CommandContext, id: gl::types::GLuint, ty:
 ///
 /// *Warning*: always passes `GL_MAP_FLUSH_EXPLICIT_BIT`.
 unsafe fn map_buffer(mut ctxt: &mut CommandContext, id: gl::types::GLuint, ty: BufferType,
-                     range: Range<usize>, read: bool, write: bool) -> Option<*mut ()>
+                     range: Range<usize>, read: bool, write: bool, options: MapWriteOptions)
+                     -> Option<*mut ()>
 {
-    let flags = match (read, write) {
+    let mut flags = match (read, write) {
         (true, true) => gl::MAP_FLUSH_EXPLICIT_BIT | gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
         (true, false) => gl::MAP_READ_BIT,
         (false, true) => gl::MAP_FLUSH_EXPLICIT_BIT | gl::MAP_WRITE_BIT,
         (false, false) => 0,
     };
 
+    // `GL_MAP_INVALIDATE_RANGE_BIT` can't be combined with `GL_MAP_READ_BIT`
+    if write && !read {
+        if options.invalidate_range {
+            flags |= gl::MAP_INVALIDATE_RANGE_BIT;
+        }
+        if options.unsynchronized {
+            flags |= gl::MAP_UNSYNCHRONIZED_BIT;
+        }
+    }
+
     if ctxt.version >= &Version(Api::Gl, 4, 5) {
         Some(ctxt.gl.MapNamedBufferRange(id, range.start as gl::types::GLintptr,
                                          (range.end - range.start) as gl::types::GLsizeiptr,
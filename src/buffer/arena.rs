@@ -0,0 +1,308 @@
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use backend::Facade;
+use context::Context;
+use CapabilitiesSource;
+
+use buffer::BufferType;
+use buffer::BufferMode;
+use buffer::BufferCreationError;
+use buffer::Content;
+use buffer::view::{self, Buffer, BufferSlice};
+
+/// Source of the identifiers of the arenas, used to check that allocations are given back to
+/// the arena that created them.
+static NEXT_ARENA_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Suballocates many small allocations from a few large buffers.
+///
+/// Creating thousands of small buffer objects is expensive, both when creating them and when
+/// switching between them during draws. An arena instead creates large buffers (named blocks) and
+/// hands out ranges of them. Each allocation is represented by an `ArenaAllocation` which can be
+/// turned into a `BufferSlice` with `slice` and then used like any other slice of a buffer.
+///
+/// The offset of each allocation is aligned to the size of its elements and to the offset
+/// alignment required by the backend for the type of buffer of the arena (for example
+/// `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT` for uniform buffers).
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// use glium::buffer::{BufferArena, BufferType, BufferMode};
+///
+/// let mut arena = BufferArena::new(&display, BufferType::ArrayBuffer, BufferMode::Default,
+///                                  1024 * 1024);
+///
+/// let allocation = arena.allocate_with_data(&[0.0f32, 1.0, 2.0, 3.0]).unwrap();
+/// assert_eq!(arena.slice(&allocation).len(), 4);
+///
+/// arena.free(allocation);
+/// ```
+pub struct BufferArena {
+    context: Rc<Context>,
+
+    /// Identifier of the arena, stored in each of its allocations.
+    id: usize,
+
+    ty: BufferType,
+    mode: BufferMode,
+
+    /// Size in bytes of the blocks created by the arena.
+    block_size: usize,
+
+    /// Minimal alignment in bytes of the offset of each allocation.
+    alignment: usize,
+
+    blocks: Vec<Block>,
+}
+
+/// A buffer of the arena and the ranges of bytes that are not allocated.
+struct Block {
+    buffer: Buffer<[u8]>,
+
+    /// Sorted list of the ranges of bytes that are free. Adjacent ranges are always merged.
+    free: Vec<Range<usize>>,
+}
+
+/// Handle to an allocation of elements of type `T` inside a `BufferArena`.
+///
+/// Must be given back to the arena with `BufferArena::free` in order to be reused. If the handle
+/// is dropped instead, the space stays allocated until the arena is destroyed.
+#[derive(Debug)]
+pub struct ArenaAllocation<T> {
+    arena: usize,
+    block: usize,
+    offset: usize,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> ArenaAllocation<T> {
+    /// Returns the number of elements of the allocation.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the offset in bytes of the allocation in its block.
+    #[inline]
+    pub fn get_offset_bytes(&self) -> usize {
+        self.offset
+    }
+}
+
+impl BufferArena {
+    /// Builds a new empty arena. Blocks of `block_size` bytes are created when needed.
+    ///
+    /// No buffer is created by this function.
+    pub fn new<F: ?Sized>(facade: &F, ty: BufferType, mode: BufferMode, block_size: usize)
+                          -> BufferArena where F: Facade
+    {
+        let context = facade.get_context();
+
+        let alignment = match ty {
+            BufferType::UniformBuffer => {
+                context.get_capabilities().uniform_buffer_offset_alignment
+            },
+            BufferType::ShaderStorageBuffer => {
+                context.get_capabilities().shader_storage_buffer_offset_alignment
+            },
+            _ => 4,
+        };
+
+        BufferArena {
+            context: context.clone(),
+            id: NEXT_ARENA_ID.fetch_add(1, Ordering::Relaxed),
+            ty: ty,
+            mode: mode,
+            block_size: block_size,
+            alignment: if alignment >= 1 { alignment as usize } else { 1 },
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Returns the context corresponding to this arena.
+    #[inline]
+    pub fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    /// Returns the number of buffers that the arena has created.
+    #[inline]
+    pub fn get_blocks_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Allocates space for `len` elements of type `T`. The content of the allocation is
+    /// undefined.
+    ///
+    /// If no block has enough free space, a new block is created. Allocations larger than the
+    /// size of the blocks get a block of their own.
+    pub fn allocate<T>(&mut self, len: usize) -> Result<ArenaAllocation<T>, BufferCreationError>
+                       where [T]: Content, T: Copy
+    {
+        let size = len * mem::size_of::<T>();
+        let alignment = lcm(self.alignment, mem::size_of::<T>());
+
+        for (index, block) in self.blocks.iter_mut().enumerate() {
+            if let Some(offset) = block.allocate(size, alignment) {
+                return Ok(ArenaAllocation {
+                    arena: self.id,
+                    block: index,
+                    offset: offset,
+                    len: len,
+                    marker: PhantomData,
+                });
+            }
+        }
+
+        let block_size = if size > self.block_size { size } else { self.block_size };
+        let buffer = try!(Buffer::<[u8]>::empty_array(&self.context, self.ty, block_size,
+                                                      self.mode));
+
+        let mut block = Block {
+            buffer: buffer,
+            free: vec![0 .. block_size],
+        };
+
+        let offset = block.allocate(size, alignment).unwrap();
+        self.blocks.push(block);
+
+        Ok(ArenaAllocation {
+            arena: self.id,
+            block: self.blocks.len() - 1,
+            offset: offset,
+            len: len,
+            marker: PhantomData,
+        })
+    }
+
+    /// Allocates space for the elements of `data` and uploads them.
+    pub fn allocate_with_data<T>(&mut self, data: &[T])
+                                 -> Result<ArenaAllocation<T>, BufferCreationError>
+                                 where [T]: Content, T: Copy
+    {
+        let allocation = try!(self.allocate(data.len()));
+
+        if !data.is_empty() {
+            self.slice(&allocation).write(data);
+        }
+
+        Ok(allocation)
+    }
+
+    /// Gives back an allocation to the arena. Its space can then be reused by other allocations.
+    ///
+    /// Blocks are never destroyed, even if they become entirely free.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    pub fn free<T>(&mut self, allocation: ArenaAllocation<T>) {
+        assert!(allocation.arena == self.id, "The allocation doesn't belong to this arena");
+
+        let size = allocation.len * mem::size_of::<T>();
+        self.blocks[allocation.block].free(allocation.offset .. allocation.offset + size);
+    }
+
+    /// Returns a slice of the buffer that corresponds to the allocation.
+    ///
+    /// The slice can be used like any other slice, for example to upload data or to build a
+    /// `VerticesSource` or an `IndicesSource`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the allocation doesn't belong to this arena.
+    pub fn slice<T>(&self, allocation: &ArenaAllocation<T>) -> BufferSlice<[T]>
+                    where [T]: Content, T: Copy
+    {
+        assert!(allocation.arena == self.id, "The allocation doesn't belong to this arena");
+
+        let size = allocation.len * mem::size_of::<T>();
+        let bytes = self.blocks[allocation.block].buffer
+                        .slice(allocation.offset .. allocation.offset + size)
+                        .unwrap();
+        view::cast_bytes_slice(bytes)
+    }
+}
+
+impl Block {
+    /// Finds a free range of `size` bytes whose start is a multiple of `alignment`, and marks it
+    /// as used. Returns its offset.
+    fn allocate(&mut self, size: usize, alignment: usize) -> Option<usize> {
+        for index in 0 .. self.free.len() {
+            let range = self.free[index].clone();
+
+            let offset = (range.start + alignment - 1) / alignment * alignment;
+            if offset + size > range.end {
+                continue;
+            }
+
+            self.free.remove(index);
+
+            let mut insert = index;
+            if range.start < offset {
+                self.free.insert(insert, range.start .. offset);
+                insert += 1;
+            }
+            if offset + size < range.end {
+                self.free.insert(insert, offset + size .. range.end);
+            }
+
+            return Some(offset);
+        }
+
+        None
+    }
+
+    /// Marks a range of bytes as free again, merging it with the neighbouring free ranges.
+    fn free(&mut self, range: Range<usize>) {
+        if range.start == range.end {
+            return;
+        }
+
+        let index = self.free.iter().position(|r| r.start > range.start)
+                                    .unwrap_or(self.free.len());
+
+        let mut range = range;
+
+        if index < self.free.len() && self.free[index].start == range.end {
+            range.end = self.free.remove(index).end;
+        }
+
+        if index >= 1 && self.free[index - 1].end == range.start {
+            self.free[index - 1].end = range.end;
+        } else {
+            self.free.insert(index, range);
+        }
+    }
+}
+
+/// Greatest common divisor.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Least common multiple. Zero-sized values are ignored.
+fn lcm(a: usize, b: usize) -> usize {
+    if a == 0 { return b; }
+    if b == 0 { return a; }
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lcm;
+
+    #[test]
+    fn lcm_alignment() {
+        assert_eq!(lcm(4, 4), 4);
+        assert_eq!(lcm(256, 12), 768);
+        assert_eq!(lcm(4, 0), 4);
+    }
+}
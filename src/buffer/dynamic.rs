@@ -0,0 +1,168 @@
+use std::ops::{Deref, DerefMut};
+
+use backend::Facade;
+
+use buffer::BufferType;
+use buffer::BufferMode;
+use buffer::BufferCreationError;
+use buffer::Content;
+use buffer::view::{self, Buffer};
+
+/// A buffer whose content is replaced every frame, and that never stalls when doing so.
+///
+/// When you modify a buffer that the GPU is still reading from, the modification has to wait
+/// until the GPU has finished. A `DynamicBuffer` instead contains multiple copies of the buffer.
+/// Each call to `write` switches to the next copy that the GPU no longer uses, and only writes to
+/// this copy. The buffer that you use in your draw commands is always the latest one that has
+/// been written.
+///
+/// Two copies are enough if you write to the buffer once per frame. Use three copies if the GPU
+/// can be more than one frame late.
+///
+/// The copies are created with `BufferMode::Persistent`.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// use glium::buffer::{DynamicBuffer, BufferType};
+///
+/// let mut buffer = DynamicBuffer::new(&display, &[0.0f32; 16][..],
+///                                     BufferType::UniformBuffer, 3).unwrap();
+///
+/// // each frame:
+/// buffer.write(&[1.0f32; 16]);
+/// // then draw with `buffer.current()`
+/// ```
+pub struct DynamicBuffer<T: ?Sized> where T: Content {
+    buffers: Vec<Buffer<T>>,
+    current: usize,
+}
+
+impl<T: ?Sized> DynamicBuffer<T> where T: Content {
+    /// Builds a new buffer with `copies` copies, each of them containing the given data.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `copies` is `0`.
+    pub fn new<F: ?Sized>(facade: &F, data: &T, ty: BufferType, copies: usize)
+                          -> Result<DynamicBuffer<T>, BufferCreationError> where F: Facade
+    {
+        assert!(copies >= 1);
+
+        let mut buffers = Vec::with_capacity(copies);
+        for _ in 0 .. copies {
+            buffers.push(try!(Buffer::new(facade, data, ty, BufferMode::Persistent)));
+        }
+
+        Ok(DynamicBuffer {
+            buffers: buffers,
+            current: 0,
+        })
+    }
+
+    /// Builds a new buffer with `copies` copies of `size` bytes each.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `copies` is `0`.
+    pub fn empty_unsized<F: ?Sized>(facade: &F, ty: BufferType, size: usize, copies: usize)
+                                    -> Result<DynamicBuffer<T>, BufferCreationError>
+                                    where F: Facade
+    {
+        assert!(copies >= 1);
+
+        let mut buffers = Vec::with_capacity(copies);
+        for _ in 0 .. copies {
+            buffers.push(try!(Buffer::empty_unsized(facade, ty, size, BufferMode::Persistent)));
+        }
+
+        Ok(DynamicBuffer {
+            buffers: buffers,
+            current: 0,
+        })
+    }
+
+    /// Returns the number of copies of the buffer.
+    #[inline]
+    pub fn get_copies_count(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns the copy that has been written last. This is the one to use in draw commands.
+    #[inline]
+    pub fn current(&self) -> &Buffer<T> {
+        &self.buffers[self.current]
+    }
+
+    /// Returns the copy that has been written last.
+    #[inline]
+    pub fn current_mut(&mut self) -> &mut Buffer<T> {
+        &mut self.buffers[self.current]
+    }
+
+    /// Switches to the next copy that is no longer used by the GPU, and returns it.
+    ///
+    /// If all the copies are still in use, switches to the copy that follows the current one.
+    /// Modifying it will then wait for the GPU.
+    pub fn rotate(&mut self) -> &mut Buffer<T> {
+        let count = self.buffers.len();
+
+        let next = (1 .. count + 1).map(|offset| (self.current + offset) % count)
+                                   .find(|&index| view::is_buffer_idle(&self.buffers[index]))
+                                   .unwrap_or((self.current + 1) % count);
+
+        self.current = next;
+        &mut self.buffers[next]
+    }
+
+    /// Switches to a copy that is no longer used by the GPU, then uploads some data in it.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of `data` is different from the length of the buffer.
+    #[inline]
+    pub fn write(&mut self, data: &T) {
+        self.rotate().write(data);
+    }
+}
+
+impl<T> DynamicBuffer<[T]> where [T]: Content, T: Copy {
+    /// Builds a new buffer with `copies` copies of `len` elements each.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `copies` is `0`.
+    pub fn empty_array<F: ?Sized>(facade: &F, ty: BufferType, len: usize, copies: usize)
+                                  -> Result<DynamicBuffer<[T]>, BufferCreationError>
+                                  where F: Facade
+    {
+        assert!(copies >= 1);
+
+        let mut buffers = Vec::with_capacity(copies);
+        for _ in 0 .. copies {
+            buffers.push(try!(Buffer::empty_array(facade, ty, len, BufferMode::Persistent)));
+        }
+
+        Ok(DynamicBuffer {
+            buffers: buffers,
+            current: 0,
+        })
+    }
+}
+
+impl<T: ?Sized> Deref for DynamicBuffer<T> where T: Content {
+    type Target = Buffer<T>;
+
+    #[inline]
+    fn deref(&self) -> &Buffer<T> {
+        self.current()
+    }
+}
+
+impl<T: ?Sized> DerefMut for DynamicBuffer<T> where T: Content {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Buffer<T> {
+        self.current_mut()
+    }
+}
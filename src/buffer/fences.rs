@@ -50,6 +50,25 @@ impl Fences {
         *existing_fences = new_fences;
     }
 
+    /// Returns true if none of the fences of the container is still pending. Destroys the fences
+    /// that have been signaled. Never waits.
+    pub fn is_idle(&self, ctxt: &mut CommandContext) -> bool {
+        let mut existing_fences = self.fences.borrow_mut();
+        let mut new_fences = SmallVec::new();
+
+        for existing in existing_fences.drain() {
+            if unsafe { sync::is_linear_sync_fence_signaled(ctxt, &existing.1) } {
+                unsafe { sync::destroy_linear_sync_fence(ctxt, existing.1) };
+            } else {
+                new_fences.push(existing);
+            }
+        }
+
+        let idle = new_fences.is_empty();
+        *existing_fences = new_fences;
+        idle
+    }
+
     /// Cleans up all fences in the container. Must be called or you'll get a panic.
     pub fn clean(&mut self, ctxt: &mut CommandContext) {
         let mut fences = self.fences.borrow_mut();
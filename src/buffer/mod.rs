@@ -44,6 +44,9 @@
 //! # }
 //! ```
 //!
+pub use self::arena::{BufferArena, ArenaAllocation};
+pub use self::dynamic::DynamicBuffer;
+pub use self::pending::PendingRead;
 pub use self::view::{Buffer, BufferAny, BufferMutSlice};
 pub use self::view::{BufferSlice, BufferAnySlice};
 pub use self::alloc::{Mapping, WriteMapping, ReadMapping, ReadError, CopyError};
@@ -68,7 +71,10 @@ use std::mem;
 use std::slice;
 
 mod alloc;
+mod arena;
+mod dynamic;
 mod fences;
+mod pending;
 mod view;
 
 /// Trait for types of data that can be put inside buffers.
@@ -179,6 +185,12 @@ pub enum BufferCreationError {
 
     /// This type of buffer is not supported.
     BufferTypeNotSupported,
+
+    /// Sparse buffers are not supported by the backend.
+    SparseBuffersNotSupported,
+
+    /// Buffers backed by a memory object are not supported by the backend.
+    MemoryObjectsNotSupported,
 }
 
 impl fmt::Display for BufferCreationError {
@@ -192,6 +204,12 @@ impl Error for BufferCreationError {
         match self {
             &BufferCreationError::OutOfMemory => "Not enough memory to create the buffer",
             &BufferCreationError::BufferTypeNotSupported => "This type of buffer is not supported",
+            &BufferCreationError::SparseBuffersNotSupported => {
+                "Sparse buffers are not supported by the backend"
+            },
+            &BufferCreationError::MemoryObjectsNotSupported => {
+                "Buffers backed by a memory object are not supported by the backend"
+            },
         }
     }
 }
@@ -276,6 +294,26 @@ impl Default for BufferMode {
     }
 }
 
+/// Options for mapping a range of a buffer for writing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct MapWriteOptions {
+    /// If true, the previous content of the range is discarded and must be entirely rewritten
+    /// through the mapping. The content of the rest of the buffer is preserved.
+    ///
+    /// Corresponds to `GL_MAP_INVALIDATE_RANGE_BIT`. This lets the driver hand out fresh memory
+    /// instead of waiting for the GPU or copying the old data. Ignored for persistent-mapped and
+    /// immutable buffers.
+    pub invalidate_range: bool,
+
+    /// If true, glium doesn't wait for the GPU to stop using the buffer before mapping it.
+    ///
+    /// Corresponds to `GL_MAP_UNSYNCHRONIZED_BIT`. You must make sure that the range is not
+    /// being read by a draw command that hasn't been executed yet, for example by writing to a
+    /// range that hasn't been used since a few frames. Otherwise the draw command may see the
+    /// new data.
+    pub unsynchronized: bool,
+}
+
 /// Type of a buffer.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(missing_docs)]
@@ -289,6 +327,7 @@ pub enum BufferType {
     AtomicCounterBuffer,
     DispatchIndirectBuffer,
     DrawIndirectBuffer,
+    ParameterBuffer,
     QueryBuffer,
     ShaderStorageBuffer,
     TextureBuffer,
@@ -308,6 +347,7 @@ impl BufferType {
             BufferType::AtomicCounterBuffer => gl::ATOMIC_COUNTER_BUFFER,
             BufferType::DispatchIndirectBuffer => gl::DISPATCH_INDIRECT_BUFFER,
             BufferType::DrawIndirectBuffer => gl::DRAW_INDIRECT_BUFFER,
+            BufferType::ParameterBuffer => gl::PARAMETER_BUFFER_ARB,
             BufferType::QueryBuffer => gl::QUERY_BUFFER,
             BufferType::ShaderStorageBuffer => gl::SHADER_STORAGE_BUFFER,
             BufferType::TextureBuffer => gl::TEXTURE_BUFFER,
@@ -0,0 +1,102 @@
+use std::thread;
+
+use sync::{self, LinearSyncFence};
+use ContextExt;
+
+use buffer::BufferType;
+use buffer::BufferMode;
+use buffer::Content;
+use buffer::alloc::ReadError;
+use buffer::view::{self, Buffer, BufferSlice};
+
+/// A read of the content of a buffer that has been started with `BufferSlice::read_async`.
+///
+/// The data is first copied by the GPU to a staging buffer. Once the copy is finished, the data
+/// can be read without waiting for the GPU. Use `is_ready` to poll the status of the copy, and
+/// `read` to obtain the data.
+pub struct PendingRead<T: ?Sized> where T: Content {
+    staging: Buffer<T>,
+
+    /// Fence signaled when the copy is over. `None` if fences are not supported or if the fence
+    /// has already been signaled.
+    fence: Option<LinearSyncFence>,
+}
+
+impl<T: ?Sized> PendingRead<T> where T: Content {
+    /// Returns true if the GPU has finished copying the data, in which case `read` will not
+    /// block.
+    ///
+    /// Always returns true if the backend doesn't support fences.
+    pub fn is_ready(&mut self) -> bool {
+        let signaled = match self.fence {
+            Some(ref fence) => {
+                let mut ctxt = self.staging.get_context().make_current();
+                unsafe { sync::is_linear_sync_fence_signaled(&mut ctxt, fence) }
+            },
+            None => return true,
+        };
+
+        if signaled {
+            let mut ctxt = self.staging.get_context().make_current();
+            unsafe { sync::destroy_linear_sync_fence(&mut ctxt, self.fence.take().unwrap()) };
+        }
+
+        signaled
+    }
+
+    /// Returns the data. Blocks until the GPU has finished copying it if necessary.
+    pub fn read(mut self) -> Result<T::Owned, ReadError> {
+        if let Some(fence) = self.fence.take() {
+            let mut ctxt = self.staging.get_context().make_current();
+            unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt) };
+        }
+
+        self.staging.read()
+    }
+}
+
+impl<T: ?Sized> Drop for PendingRead<T> where T: Content {
+    fn drop(&mut self) {
+        if let Some(fence) = self.fence.take() {
+            if thread::panicking() {
+                return;
+            }
+
+            let mut ctxt = self.staging.get_context().make_current();
+            unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence) };
+        }
+    }
+}
+
+/// Starts copying the content of a slice to a staging buffer. See `BufferSlice::read_async`.
+pub fn read_async<'a, T: ?Sized>(slice: &BufferSlice<'a, T>)
+                                 -> Result<PendingRead<T>, ReadError> where T: Content
+{
+    let staging = match Buffer::empty_unsized(slice.get_context(), BufferType::CopyWriteBuffer,
+                                              slice.get_size(), BufferMode::Dynamic)
+    {
+        Ok(buffer) => buffer,
+        Err(_) => return Err(ReadError::NotSupported),
+    };
+
+    if view::copy_slice_to_buffer(slice, &staging).is_err() {
+        return Err(ReadError::NotSupported);
+    }
+
+    let fence = {
+        let mut ctxt = slice.get_context().make_current();
+        let fence = unsafe { sync::new_linear_sync_fence(&mut ctxt).ok() };
+
+        // without a flush, the fence might never be signaled when polling it
+        if fence.is_some() {
+            unsafe { ctxt.gl.Flush(); }
+        }
+
+        fence
+    };
+
+    Ok(PendingRead {
+        staging: staging,
+        fence: fence,
+    })
+}
@@ -3,6 +3,7 @@ use std::mem;
 use std::borrow::Cow;
 use utils::range::RangeArgument;
 use std::marker::PhantomData;
+use std::ops::Range;
 
 use texture::{PixelValue, Texture1dDataSink};
 use gl;
@@ -10,6 +11,7 @@ use gl;
 use backend::Facade;
 use BufferExt;
 use BufferSliceExt;
+use BufferArraySliceExt;
 use GlObject;
 
 use context::Context;
@@ -20,6 +22,7 @@ use ContextExt;
 use buffer::BufferType;
 use buffer::BufferMode;
 use buffer::BufferCreationError;
+use buffer::MapWriteOptions;
 use buffer::Content;
 use buffer::fences::Fences;
 use buffer::fences::Inserter;
@@ -29,6 +32,8 @@ use buffer::alloc::ReadMapping;
 use buffer::alloc::WriteMapping;
 use buffer::alloc::ReadError;
 use buffer::alloc::CopyError;
+use buffer::pending::{self, PendingRead};
+use interop::MemoryObject;
 
 /// Represents a view of a buffer.
 pub struct Buffer<T: ?Sized> where T: Content {
@@ -99,6 +104,92 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         self.alloc.as_ref().unwrap().uses_persistent_mapping()
     }
 
+    /// Builds a new sparse buffer of the given size in bytes.
+    ///
+    /// The buffer initially doesn't have any physical memory. Use `commit` to give memory to the
+    /// parts of the buffer that you are going to use.
+    ///
+    /// Sparse buffers can't be mapped, and reading them returns `ReadError::NotSupported`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `size` is not a multiple of the page size of sparse buffers, which is available
+    /// in the capabilities of the context.
+    pub fn empty_sparse_unsized<F: ?Sized>(facade: &F, ty: BufferType, size: usize)
+                                   -> Result<Buffer<T>, BufferCreationError> where F: Facade
+    {
+        assert!(<T as Content>::is_size_suitable(size));
+
+        Alloc::empty_sparse(facade, ty, size)
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
+    /// Builds a new buffer of `size` bytes whose storage is the range of the memory object that
+    /// starts at `offset`. See the `interop` module.
+    ///
+    /// # Unsafety
+    ///
+    /// The content of the memory must be valid for `T`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of the bounds of the memory object.
+    pub unsafe fn from_memory_object_unsized<F: ?Sized>(facade: &F, ty: BufferType,
+                                                        memory: &MemoryObject, offset: u64,
+                                                        size: usize)
+                                                        -> Result<Buffer<T>, BufferCreationError>
+                                                        where F: Facade
+    {
+        assert!(<T as Content>::is_size_suitable(size));
+
+        Alloc::from_memory_object(facade, ty, memory, offset, size)
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
+    /// Returns true if this buffer is a sparse buffer.
+    #[inline]
+    pub fn is_sparse(&self) -> bool {
+        self.alloc.as_ref().unwrap().is_sparse()
+    }
+
+    /// Gives physical memory to a range of bytes of this sparse buffer. The content of the newly
+    /// committed range is undefined.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer is not sparse, if the range is out of bounds, or if the range is not
+    /// aligned to the page size of sparse buffers.
+    #[inline]
+    pub fn commit(&self, range: Range<usize>) {
+        self.alloc.as_ref().unwrap().set_page_commitment(range, true);
+    }
+
+    /// Frees the physical memory of a range of bytes of this sparse buffer. Waits until the GPU
+    /// no longer uses the range.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the buffer is not sparse, if the range is out of bounds, or if the range is not
+    /// aligned to the page size of sparse buffers.
+    pub fn decommit(&self, range: Range<usize>) {
+        let alloc = self.alloc.as_ref().unwrap();
+        self.fence.as_ref().unwrap().wait(&mut alloc.get_context().make_current(),
+                                          range.clone());
+        alloc.set_page_commitment(range, false);
+    }
+
     /// Uploads some data in this buffer.
     ///
     /// # Implementation
@@ -354,12 +445,111 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
             })
     }
 
+    /// Builds a new sparse buffer of `len` elements. See `empty_sparse_unsized`.
+    pub fn empty_array_sparse<F: ?Sized>(facade: &F, ty: BufferType, len: usize)
+                                 -> Result<Buffer<[T]>, BufferCreationError> where F: Facade
+    {
+        Alloc::empty_sparse(facade, ty, len * mem::size_of::<T>())
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
+    /// Builds a new buffer of `len` elements whose storage comes from a memory object. See
+    /// `from_memory_object_unsized`.
+    pub unsafe fn from_memory_object_array<F: ?Sized>(facade: &F, ty: BufferType,
+                                                      memory: &MemoryObject, offset: u64,
+                                                      len: usize)
+                                                      -> Result<Buffer<[T]>, BufferCreationError>
+                                                      where F: Facade
+    {
+        Alloc::from_memory_object(facade, ty, memory, offset, len * mem::size_of::<T>())
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
+    /// Builds a new buffer of the given size whose storage comes from the pixel buffers pool of
+    /// the context. The storage is returned to the pool when the buffer is destroyed.
+    pub fn empty_array_pooled<F: ?Sized>(facade: &F, ty: BufferType, len: usize)
+                                 -> Result<Buffer<[T]>, BufferCreationError> where F: Facade
+    {
+        Alloc::empty_pooled(facade, ty, len * mem::size_of::<T>())
+            .map(|buffer| {
+                Buffer {
+                    alloc: Some(buffer),
+                    fence: Some(Fences::new()),
+                    marker: PhantomData,
+                }
+            })
+    }
+
     /// Returns the number of elements in this buffer.
     #[inline]
     pub fn len(&self) -> usize {
         self.alloc.as_ref().unwrap().get_size() / mem::size_of::<T>()
     }
 
+    /// Maps a range of the buffer in memory for writing only. `offset` and `len` are in number
+    /// of elements.
+    ///
+    /// Contrary to `map_write`, only the range is mapped and waited upon. Combined with the
+    /// options, this makes it possible to update a small part of a large buffer every frame
+    /// without stalling or making the driver copy the whole buffer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is empty or out of bounds.
+    ///
+    /// # Implementation
+    ///
+    /// Same as `map_write`. If `options.unsynchronized` is true, doesn't wait for the GPU to stop
+    /// accessing the range.
+    ///
+    pub fn map_range_write(&mut self, offset: usize, len: usize, options: MapWriteOptions)
+                           -> WriteMapping<[T]>
+    {
+        assert!(len != 0 && offset + len <= self.len(), "The range is empty or out of bounds");
+
+        let range = offset * mem::size_of::<T>() .. (offset + len) * mem::size_of::<T>();
+
+        if !options.unsynchronized {
+            self.fence.as_ref().unwrap().wait(&mut self.alloc.as_ref().unwrap().get_context()
+                                                                               .make_current(),
+                                              range.clone());
+        }
+
+        unsafe { self.alloc.as_mut().unwrap().map_write_with_options(range, options) }
+    }
+
+    /// Sets all the elements of the buffer to `value`.
+    ///
+    /// # Implementation
+    ///
+    /// Calls `glClearBufferSubData` if it is supported and if the size of `T` is 1, 2, 4, 8, 12
+    /// or 16 bytes. Otherwise, maps the buffer and writes the value in each element.
+    #[inline]
+    pub fn clear(&self, value: T) {
+        self.as_slice().clear(value);
+    }
+
+    /// Sets the elements of a range of the buffer to `value`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn clear_range<R: RangeArgument<usize>>(&self, range: R, value: T) {
+        self.slice(range).expect("The range is out of bounds").clear(value);
+    }
+
     /// Builds a slice of this subbuffer. Returns `None` if out of range.
     ///
     /// This method builds an object that represents a slice of the buffer. No actual operation
@@ -467,6 +657,12 @@ impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
         alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_draw_parameters(&self, ctxt: &mut CommandContext) {
+        let alloc = self.alloc.as_ref().unwrap();
+        alloc.prepare_and_bind_for_draw_parameters(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext) {
         let alloc = self.alloc.as_ref().unwrap();
@@ -485,6 +681,18 @@ impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
         alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. alloc.get_size());
     }
 
+    #[inline]
+    fn prepare_for_texture_fetch(&self, ctxt: &mut CommandContext) {
+        let alloc = self.alloc.as_ref().unwrap();
+        alloc.prepare_for_texture_fetch(ctxt);
+    }
+
+    #[inline]
+    fn prepare_for_shader_image_access(&self, ctxt: &mut CommandContext, write: bool) {
+        let alloc = self.alloc.as_ref().unwrap();
+        alloc.prepare_for_shader_image_access(ctxt, write);
+    }
+
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
         let alloc = self.alloc.as_ref().unwrap();
@@ -560,6 +768,19 @@ impl<'a, T: ?Sized> BufferSlice<'a, T> where T: Content + 'a {
         }
     }
 
+    /// Starts reading the content of the slice without waiting for the GPU.
+    ///
+    /// The GPU copies the content of the slice to a staging buffer. You can then poll the
+    /// returned object, for example at the next frame, and read the data once the copy is over.
+    /// This is useful to read data generated by the GPU, like the output of a compute shader,
+    /// without stalling.
+    ///
+    /// Returns `Err` if the backend doesn't support copying between buffers.
+    #[inline]
+    pub fn read_async(&self) -> Result<PendingRead<T>, ReadError> {
+        pending::read_async(self)
+    }
+
     /// Copies the content of this slice to another slice.
     ///
     /// # Panic
@@ -656,6 +877,15 @@ impl<'a, T> BufferSlice<'a, [T]> where [T]: Content + 'a {
         (self.bytes_end - self.bytes_start) / mem::size_of::<T>()
     }
 
+    /// Sets all the elements of the slice to `value`.
+    ///
+    /// See `Buffer::clear` for more information.
+    pub fn clear(&self, value: T) where T: Copy {
+        self.fence.wait(&mut self.alloc.get_context().make_current(),
+                        self.bytes_start .. self.bytes_end);
+        unsafe { self.alloc.clear(self.bytes_start .. self.bytes_end, &value); }
+    }
+
     /// Builds a subslice of this slice. Returns `None` if out of range.
     ///
     /// This method builds an object that represents a slice of the buffer. No actual operation
@@ -676,6 +906,74 @@ impl<'a, T> BufferSlice<'a, [T]> where [T]: Content + 'a {
     }
 }
 
+/// Copies the content of a slice to the start of a buffer.
+pub fn copy_slice_to_buffer<'a, T: ?Sized>(slice: &BufferSlice<'a, T>, target: &Buffer<T>)
+                                           -> Result<(), CopyError> where T: Content
+{
+    let target_alloc = target.alloc.as_ref().unwrap();
+    try!(slice.alloc.copy_to(slice.bytes_start .. slice.bytes_end, target_alloc, 0));
+
+    if let Some(inserter) = slice.add_fence() {
+        let mut ctxt = slice.alloc.get_context().make_current();
+        inserter.insert(&mut ctxt);
+    }
+
+    if let Some(inserter) = target.as_slice().add_fence() {
+        let mut ctxt = slice.alloc.get_context().make_current();
+        inserter.insert(&mut ctxt);
+    }
+
+    Ok(())
+}
+
+/// Returns true if the GPU has finished all the operations that use the buffer and that glium
+/// keeps track of. Never waits.
+pub fn is_buffer_idle<T: ?Sized>(buffer: &Buffer<T>) -> bool where T: Content {
+    let alloc = buffer.alloc.as_ref().unwrap();
+    buffer.fence.as_ref().unwrap().is_idle(&mut alloc.get_context().make_current())
+}
+
+/// Turns a slice of bytes into a slice of elements of type `T`.
+///
+/// # Panic
+///
+/// Panics if the size of the slice is not a multiple of the size of `T`.
+pub fn cast_bytes_slice<'a, T>(slice: BufferSlice<'a, [u8]>) -> BufferSlice<'a, [T]>
+                               where [T]: Content, T: Copy
+{
+    assert_eq!(slice.get_size() % mem::size_of::<T>(), 0);
+
+    BufferSlice {
+        alloc: slice.alloc,
+        bytes_start: slice.bytes_start,
+        bytes_end: slice.bytes_end,
+        fence: slice.fence,
+        marker: PhantomData,
+    }
+}
+
+impl<'a, T> BufferArraySliceExt<'a> for BufferSlice<'a, [T]> where [T]: Content {
+    fn element_field<F>(&self, index: usize, offset: usize) -> Option<BufferSlice<'a, F>>
+                        where F: Content + Copy
+    {
+        if index >= self.len() {
+            return None;
+        }
+
+        assert!(offset + mem::size_of::<F>() <= mem::size_of::<T>());
+
+        let start = self.bytes_start + index * mem::size_of::<T>() + offset;
+
+        Some(BufferSlice {
+            alloc: self.alloc,
+            bytes_start: start,
+            bytes_end: start + mem::size_of::<F>(),
+            fence: self.fence,
+            marker: PhantomData,
+        })
+    }
+}
+
 impl<'a, T> BufferSlice<'a, [T]> where T: PixelValue + 'a {
     /// Reads the content of the buffer.
     #[inline]
@@ -786,6 +1084,11 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
         self.alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_draw_parameters(&self, ctxt: &mut CommandContext) {
+        self.alloc.prepare_and_bind_for_draw_parameters(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext) {
         self.alloc.prepare_and_bind_for_dispatch_indirect(ctxt);
@@ -801,6 +1104,16 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
         self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
     }
 
+    #[inline]
+    fn prepare_for_texture_fetch(&self, ctxt: &mut CommandContext) {
+        self.alloc.prepare_for_texture_fetch(ctxt);
+    }
+
+    #[inline]
+    fn prepare_for_shader_image_access(&self, ctxt: &mut CommandContext, write: bool) {
+        self.alloc.prepare_for_shader_image_access(ctxt, write);
+    }
+
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
         self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
@@ -1253,6 +1566,11 @@ impl BufferExt for BufferAny {
         self.alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_draw_parameters(&self, ctxt: &mut CommandContext) {
+        self.alloc.prepare_and_bind_for_draw_parameters(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext) {
         self.alloc.prepare_and_bind_for_dispatch_indirect(ctxt);
@@ -1268,6 +1586,16 @@ impl BufferExt for BufferAny {
         self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
     }
 
+    #[inline]
+    fn prepare_for_texture_fetch(&self, ctxt: &mut CommandContext) {
+        self.alloc.prepare_for_texture_fetch(ctxt);
+    }
+
+    #[inline]
+    fn prepare_for_shader_image_access(&self, ctxt: &mut CommandContext, write: bool) {
+        self.alloc.prepare_for_shader_image_access(ctxt, write);
+    }
+
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
         self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
@@ -1404,6 +1732,11 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
         self.alloc.prepare_and_bind_for_draw_indirect(ctxt);
     }
 
+    #[inline]
+    fn prepare_and_bind_for_draw_parameters(&self, ctxt: &mut CommandContext) {
+        self.alloc.prepare_and_bind_for_draw_parameters(ctxt);
+    }
+
     #[inline]
     fn prepare_and_bind_for_dispatch_indirect(&self, ctxt: &mut CommandContext) {
         self.alloc.prepare_and_bind_for_dispatch_indirect(ctxt);
@@ -1419,6 +1752,16 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
         self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
     }
 
+    #[inline]
+    fn prepare_for_texture_fetch(&self, ctxt: &mut CommandContext) {
+        self.alloc.prepare_for_texture_fetch(ctxt);
+    }
+
+    #[inline]
+    fn prepare_for_shader_image_access(&self, ctxt: &mut CommandContext, write: bool) {
+        self.alloc.prepare_for_shader_image_access(ctxt, write);
+    }
+
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
         self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
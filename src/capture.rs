@@ -0,0 +1,255 @@
+/*!
+Saving the content of a surface to an image file.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+use glium::capture::{self, ScreenshotOptions};
+
+let target = display.draw();
+// ... draw ...
+capture::save_screenshot(&display, &target, "screenshot.png", &Default::default()).unwrap();
+target.finish().unwrap();
+```
+
+The content of the surface is first copied to a texture, then read back and encoded. Encoding
+depends on optional features:
+
+ - `screenshot_png` for PNG files, through the `image` crate.
+ - `screenshot_exr` for OpenEXR files, through the `exr` crate. EXR files keep the
+   floating-point values of the surface, which is useful for HDR targets.
+
+If the feature corresponding to the requested format is disabled,
+`ScreenshotError::FormatNotSupported` is returned.
+
+Reading floating-point data requires desktop OpenGL or OpenGL ES 3.0.
+
+*/
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::error::Error;
+
+use backend::Facade;
+use texture::{MipmapsOption, RawImage2d, Texture2d, TextureCreationError};
+use texture::UncompressedFloatFormat;
+use uniforms::MagnifySamplerFilter;
+use BlitTarget;
+use Surface;
+
+/// Format of the file written by `save_screenshot`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScreenshotFormat {
+    /// 8 bits per component PNG. Requires the `screenshot_png` feature.
+    Png,
+
+    /// 32 bits floating-point OpenEXR. Requires the `screenshot_exr` feature.
+    Exr,
+}
+
+/// Options of `save_screenshot`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenshotOptions {
+    /// Format of the file.
+    pub format: ScreenshotFormat,
+
+    /// If true, the content of the surface is considered to be linear RGB and is converted to
+    /// sRGB before being written. Ignored for EXR files, which are always linear.
+    ///
+    /// Set this to true if your shaders write linear colors to a surface that doesn't convert
+    /// them to sRGB, like a `Texture2d`.
+    pub srgb: bool,
+
+    /// If true, the image is flipped so that its first row is the top of the surface, like
+    /// image viewers expect. OpenGL stores the bottom row first.
+    pub flip_vertically: bool,
+}
+
+impl Default for ScreenshotOptions {
+    #[inline]
+    fn default() -> ScreenshotOptions {
+        ScreenshotOptions {
+            format: ScreenshotFormat::Png,
+            srgb: false,
+            flip_vertically: true,
+        }
+    }
+}
+
+/// Error that can happen while saving a screenshot.
+#[derive(Debug)]
+pub enum ScreenshotError {
+    /// The feature required to encode this format is disabled.
+    FormatNotSupported,
+
+    /// Failed to create the texture used to read the surface.
+    TextureCreationError(TextureCreationError),
+
+    /// Failed to write the file.
+    IoError(io::Error),
+
+    /// Failed to encode the image.
+    EncodingError(String),
+}
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScreenshotError::EncodingError(ref msg) => {
+                write!(fmt, "{}: {}", self.description(), msg)
+            },
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for ScreenshotError {
+    fn description(&self) -> &str {
+        use self::ScreenshotError::*;
+        match *self {
+            FormatNotSupported =>
+                "The feature required to encode this format is disabled",
+            TextureCreationError(_) =>
+                "Failed to create the texture used to read the surface",
+            IoError(_) =>
+                "Failed to write the file",
+            EncodingError(_) =>
+                "Failed to encode the image",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::ScreenshotError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            IoError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TextureCreationError> for ScreenshotError {
+    #[inline]
+    fn from(err: TextureCreationError) -> ScreenshotError {
+        ScreenshotError::TextureCreationError(err)
+    }
+}
+
+impl From<io::Error> for ScreenshotError {
+    #[inline]
+    fn from(err: io::Error) -> ScreenshotError {
+        ScreenshotError::IoError(err)
+    }
+}
+
+/// Reads the color content of a surface and writes it to a file.
+pub fn save_screenshot<F: ?Sized, S, P>(facade: &F, surface: &S, path: P,
+                                        options: &ScreenshotOptions)
+                                        -> Result<(), ScreenshotError>
+                                        where F: Facade, S: Surface, P: AsRef<Path>
+{
+    let (width, height) = surface.get_dimensions();
+
+    match options.format {
+        ScreenshotFormat::Png => {
+            if !cfg!(feature = "screenshot_png") {
+                return Err(ScreenshotError::FormatNotSupported);
+            }
+
+            let data = if options.srgb {
+                let image = try!(read_f32(facade, surface, options.flip_vertically));
+                image.data.iter().enumerate().map(|(n, &v)| {
+                    // alpha is never converted
+                    let v = if n % 4 == 3 { v } else { linear_to_srgb(v) };
+                    (v.max(0.0).min(1.0) * 255.0 + 0.5) as u8
+                }).collect()
+            } else {
+                try!(read_u8(facade, surface, options.flip_vertically)).data.into_owned()
+            };
+
+            write_png(path.as_ref(), &data, width, height)
+        },
+
+        ScreenshotFormat::Exr => {
+            if !cfg!(feature = "screenshot_exr") {
+                return Err(ScreenshotError::FormatNotSupported);
+            }
+
+            let image = try!(read_f32(facade, surface, options.flip_vertically));
+            write_exr(path.as_ref(), &image.data, width, height)
+        },
+    }
+}
+
+/// Returns the blit target that copies a whole surface, flipped or not.
+fn blit_target(width: u32, height: u32, flip: bool) -> BlitTarget {
+    BlitTarget {
+        left: 0,
+        bottom: if flip { height } else { 0 },
+        width: width as i32,
+        height: if flip { -(height as i32) } else { height as i32 },
+    }
+}
+
+/// Copies the surface to a `U8U8U8U8` texture and reads it.
+fn read_u8<F: ?Sized, S>(facade: &F, surface: &S, flip: bool)
+                         -> Result<RawImage2d<'static, u8>, TextureCreationError>
+                         where F: Facade, S: Surface
+{
+    let (width, height) = surface.get_dimensions();
+    let texture = try!(Texture2d::empty_with_format(facade, UncompressedFloatFormat::U8U8U8U8,
+                                                    MipmapsOption::NoMipmap, width, height));
+    surface.blit_whole_color_to(&texture.as_surface(), &blit_target(width, height, flip),
+                                MagnifySamplerFilter::Nearest);
+    Ok(texture.read())
+}
+
+/// Copies the surface to a `F32F32F32F32` texture and reads it.
+fn read_f32<F: ?Sized, S>(facade: &F, surface: &S, flip: bool)
+                          -> Result<RawImage2d<'static, f32>, TextureCreationError>
+                          where F: Facade, S: Surface
+{
+    let (width, height) = surface.get_dimensions();
+    let texture = try!(Texture2d::empty_with_format(facade,
+                                                    UncompressedFloatFormat::F32F32F32F32,
+                                                    MipmapsOption::NoMipmap, width, height));
+    surface.blit_whole_color_to(&texture.as_surface(), &blit_target(width, height, flip),
+                                MagnifySamplerFilter::Nearest);
+    Ok(unsafe { texture.unchecked_read::<_, (f32, f32, f32, f32)>() })
+}
+
+/// Converts a linear component to sRGB.
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(feature = "screenshot_png")]
+fn write_png(path: &Path, data: &[u8], width: u32, height: u32) -> Result<(), ScreenshotError> {
+    use image;
+
+    image::save_buffer(path, data, width, height, image::ColorType::RGBA(8))
+        .map_err(|err| ScreenshotError::IoError(err))
+}
+
+#[cfg(not(feature = "screenshot_png"))]
+fn write_png(_: &Path, _: &[u8], _: u32, _: u32) -> Result<(), ScreenshotError> {
+    Err(ScreenshotError::FormatNotSupported)
+}
+
+#[cfg(feature = "screenshot_exr")]
+fn write_exr(path: &Path, data: &[f32], width: u32, height: u32) -> Result<(), ScreenshotError> {
+    use exr::prelude::write_rgba_file;
+
+    write_rgba_file(path, width as usize, height as usize, |x, y| {
+        let offset = (y * width as usize + x) * 4;
+        (data[offset], data[offset + 1], data[offset + 2], data[offset + 3])
+    }).map_err(|err| ScreenshotError::EncodingError(format!("{}", err)))
+}
+
+#[cfg(not(feature = "screenshot_exr"))]
+fn write_exr(_: &Path, _: &[f32], _: u32, _: u32) -> Result<(), ScreenshotError> {
+    Err(ScreenshotError::FormatNotSupported)
+}
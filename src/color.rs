@@ -0,0 +1,154 @@
+/*!
+Conversions between sRGB and linear RGB, and pixel types tagged with their color space.
+
+Most image files contain colors in sRGB, while lighting computations must be done in linear RGB.
+The functions of this module convert pixel data on the CPU, for example before uploading data
+that was generated procedurally or after reading back a texture.
+
+When uploading textures, the GPU can do the conversion for you if the texture has an sRGB
+format (see the `texture` module). Wrapping the pixels in `Srgb` or `Linear` documents the
+color space of the data, and `texture_2d` picks the right kind of texture: an `SrgbTexture2d`
+for color data and a `Texture2d` for data textures like normal maps.
+
+```no_run
+# extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+use glium::color::{self, Linear, Srgb};
+
+// the colors of an image file
+let albedo = vec![vec![Srgb((255u8, 128u8, 0u8, 255u8)); 16]; 16];
+let albedo: glium::texture::SrgbTexture2d = color::texture_2d(&display, albedo).unwrap();
+
+// a normal map, which must not be converted
+let normals = vec![vec![Linear((128u8, 128u8, 255u8)); 16]; 16];
+let normals: glium::texture::Texture2d = color::texture_2d(&display, normals).unwrap();
+# }
+```
+
+*/
+use backend::Facade;
+use texture::{ClientFormat, MipmapsOption, PixelValue, Texture2dDataSource};
+use texture::{SrgbTexture2d, Texture2d, TextureCreationError};
+
+/// Converts a color component from sRGB to linear RGB. The value should be between `0.0` and
+/// `1.0`.
+#[inline]
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a color component from linear RGB to sRGB. The value should be between `0.0` and
+/// `1.0`.
+#[inline]
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts an 8-bits sRGB color component to a linear value between `0.0` and `1.0`.
+#[inline]
+pub fn srgb_u8_to_linear(value: u8) -> f32 {
+    srgb_to_linear(value as f32 / 255.0)
+}
+
+/// Converts a linear color component to the nearest 8-bits sRGB value. The input is clamped
+/// between `0.0` and `1.0`.
+#[inline]
+pub fn linear_to_srgb_u8(value: f32) -> u8 {
+    let value = if value < 0.0 { 0.0 } else if value > 1.0 { 1.0 } else { value };
+    (linear_to_srgb(value) * 255.0 + 0.5) as u8
+}
+
+/// Converts 8-bits sRGBA pixels to linear RGBA. The alpha channel is never converted.
+pub fn srgba8_to_linear(pixels: &[(u8, u8, u8, u8)]) -> Vec<(f32, f32, f32, f32)> {
+    pixels.iter().map(|&(r, g, b, a)| {
+        (srgb_u8_to_linear(r), srgb_u8_to_linear(g), srgb_u8_to_linear(b), a as f32 / 255.0)
+    }).collect()
+}
+
+/// Converts linear RGBA pixels to 8-bits sRGBA. The alpha channel is never converted.
+pub fn linear_to_srgba8(pixels: &[(f32, f32, f32, f32)]) -> Vec<(u8, u8, u8, u8)> {
+    pixels.iter().map(|&(r, g, b, a)| {
+        let a = if a < 0.0 { 0.0 } else if a > 1.0 { 1.0 } else { a };
+        (linear_to_srgb_u8(r), linear_to_srgb_u8(g), linear_to_srgb_u8(b), (a * 255.0 + 0.5) as u8)
+    }).collect()
+}
+
+/// A pixel whose color components are in sRGB.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Srgb<P>(pub P);
+
+/// A pixel whose components are linear, for example a color in linear RGB or a normal.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Linear<P>(pub P);
+
+unsafe impl<P> PixelValue for Srgb<P> where P: PixelValue {
+    #[inline]
+    fn get_format() -> ClientFormat {
+        P::get_format()
+    }
+}
+
+unsafe impl<P> PixelValue for Linear<P> where P: PixelValue {
+    #[inline]
+    fn get_format() -> ClientFormat {
+        P::get_format()
+    }
+}
+
+/// A pixel type that knows the color space of its data.
+pub trait TaggedPixel: PixelValue {
+    /// The kind of texture that must contain pixels of this type.
+    type Texture;
+
+    /// Builds a texture containing the given pixels.
+    fn build_texture_2d<'a, F: ?Sized, T>(facade: &F, data: T, mipmaps: MipmapsOption)
+                                          -> Result<Self::Texture, TextureCreationError>
+        where T: Texture2dDataSource<'a, Data = Self>, F: Facade;
+}
+
+impl<P> TaggedPixel for Srgb<P> where P: PixelValue {
+    type Texture = SrgbTexture2d;
+
+    #[inline]
+    fn build_texture_2d<'a, F: ?Sized, T>(facade: &F, data: T, mipmaps: MipmapsOption)
+                                          -> Result<SrgbTexture2d, TextureCreationError>
+        where T: Texture2dDataSource<'a, Data = Self>, F: Facade
+    {
+        SrgbTexture2d::with_mipmaps(facade, data, mipmaps)
+    }
+}
+
+impl<P> TaggedPixel for Linear<P> where P: PixelValue {
+    type Texture = Texture2d;
+
+    #[inline]
+    fn build_texture_2d<'a, F: ?Sized, T>(facade: &F, data: T, mipmaps: MipmapsOption)
+                                          -> Result<Texture2d, TextureCreationError>
+        where T: Texture2dDataSource<'a, Data = Self>, F: Facade
+    {
+        Texture2d::with_mipmaps(facade, data, mipmaps)
+    }
+}
+
+/// Builds an `SrgbTexture2d` if the pixels are `Srgb`, or a `Texture2d` if they are `Linear`.
+///
+/// Mipmaps are automatically generated.
+#[inline]
+pub fn texture_2d<'a, F: ?Sized, T>(facade: &F, data: T)
+                                    -> Result<<T::Data as TaggedPixel>::Texture,
+                                              TextureCreationError>
+    where T: Texture2dDataSource<'a>, T::Data: TaggedPixel, F: Facade
+{
+    <T::Data as TaggedPixel>::build_texture_2d(facade, data, MipmapsOption::AutoGeneratedMipmaps)
+}
@@ -6,14 +6,16 @@ use std::cmp;
 use std::collections::HashMap;
 use std::ffi::CStr;
 use std::hash::BuildHasherDefault;
-use std::mem;
 
 use fnv::FnvHasher;
 
 use gl;
+use utils::gl_get::{get_boolean, get_float, get_integer, get_integer_indexed, get_output};
 use ToGlEnum;
 
 use CapabilitiesSource;
+use ContextCreationError;
+use backend::ContextAttributes;
 use image_format::TextureFormat;
 
 /// Describes the OpenGL context profile.
@@ -35,6 +37,12 @@ pub struct Capabilities {
     /// An empty list means that the backend doesn't have a compiler.
     pub supported_glsl_versions: Vec<Version>,
 
+    /// List of vendor-specific formats of precompiled shaders that can be loaded with
+    /// `glShaderBinary`. See `ProgramCreationInput::ShaderBinaries`.
+    ///
+    /// On backends that don't have a compiler, this is the only way to create programs.
+    pub shader_binary_formats: Vec<gl::types::GLenum>,
+
     /// Returns a version or release number. Vendor-specific information may follow the version
     /// number.
     pub version: String,
@@ -74,6 +82,23 @@ pub struct Capabilities {
     /// True if the default framebuffer is in sRGB.
     pub srgb: bool,
 
+    /// Number of samples per pixel of the default framebuffer, or 0 if it isn't multisampled.
+    pub samples: u16,
+
+    /// True if the default framebuffer has a back buffer.
+    pub double_buffer: bool,
+
+    /// What happens to the content of the back buffer when swapping buffers. `None` if unknown.
+    pub swap_behavior: Option<SwapBehavior>,
+
+    /// Number of bits of the red, green, blue and alpha channels of the default framebuffer's
+    /// color buffer. For example `[10, 10, 10, 2]` for a deep color framebuffer.
+    pub color_bits: [u16; 4],
+
+    /// True if the default framebuffer's color buffer contains floating-point values, which is
+    /// usually the case of HDR framebuffers.
+    pub float_color_buffer: bool,
+
     /// Number of bits in the default framebuffer's depth buffer
     pub depth_bits: Option<u16>,
 
@@ -91,14 +116,26 @@ pub struct Capabilities {
     /// `glActiveTexture` must be between `GL_TEXTURE0` and `GL_TEXTURE0` + this value - 1.
     pub max_combined_texture_image_units: gl::types::GLint,
 
-    /// Maximum value for `GL_TEXTURE_MAX_ANISOTROPY_EXT​`.
+    /// Maximum value for `GL_TEXTURE_MAX_ANISOTROPY`.
     ///
-    /// `None` if the extension is not supported by the hardware.
+    /// `None` if anisotropic filtering is not supported by the hardware.
     pub max_texture_max_anisotropy: Option<gl::types::GLfloat>,
 
     /// Maximum size of a buffer texture. `None` if this is not supported.
     pub max_texture_buffer_size: Option<gl::types::GLint>,
 
+    /// Required alignment in bytes of the offset of a buffer texture that only uses a range of
+    /// its buffer. `None` if buffer texture ranges are not supported.
+    pub texture_buffer_offset_alignment: Option<gl::types::GLint>,
+
+    /// Minimum and maximum values of the offsets passed to `textureOffset` and similar GLSL
+    /// functions. `None` if texel offsets are not supported.
+    pub program_texel_offset: Option<(gl::types::GLint, gl::types::GLint)>,
+
+    /// Describes what shaders can do with `textureGather`. `None` if `textureGather` is not
+    /// available.
+    pub texture_gather: Option<TextureGather>,
+
     /// Maximum width and height of `glViewport`.
     pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
 
@@ -108,6 +145,26 @@ pub struct Capabilities {
     /// Maximum number of vertices per patch. `None` if tessellation is not supported.
     pub max_patch_vertices: Option<gl::types::GLint>,
 
+    /// Maximum value of `max_vertices` in the output layout qualifier of a geometry shader.
+    /// `None` if geometry shaders are not supported.
+    pub max_geometry_output_vertices: Option<gl::types::GLint>,
+
+    /// Maximum number of components written by a single invocation of a geometry shader, which
+    /// is `max_vertices` multiplied by the number of components of each output vertex. `None` if
+    /// geometry shaders are not supported.
+    pub max_geometry_total_output_components: Option<gl::types::GLint>,
+
+    /// Maximum value of `invocations` in the input layout qualifier of a geometry shader. `None`
+    /// if geometry shaders or geometry shader instancing are not supported.
+    pub max_geometry_shader_invocations: Option<gl::types::GLint>,
+
+    /// Number of image units that shaders can access with image load/store. `None` if image
+    /// load/store is not supported.
+    pub max_image_units: Option<gl::types::GLint>,
+
+    /// True if shaders can perform atomic operations on images, like `imageAtomicAdd`.
+    pub image_atomic: bool,
+
     /// Number of available buffer bind points for `GL_ATOMIC_COUNTER_BUFFER`.
     pub max_indexed_atomic_counter_buffer: gl::types::GLint,
 
@@ -120,6 +177,21 @@ pub struct Capabilities {
     /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
     pub max_indexed_uniform_buffer: gl::types::GLint,
 
+    /// Required alignment in bytes of the offset of a buffer bound to `GL_UNIFORM_BUFFER`.
+    pub uniform_buffer_offset_alignment: gl::types::GLint,
+
+    /// Required alignment in bytes of the offset of a buffer bound to
+    /// `GL_SHADER_STORAGE_BUFFER`.
+    pub shader_storage_buffer_offset_alignment: gl::types::GLint,
+
+    /// Size in bytes of the pages of sparse buffers. Sparse buffers are committed and decommitted
+    /// by multiples of this size. `None` if sparse buffers are not supported.
+    pub sparse_buffer_page_size: Option<gl::types::GLint>,
+
+    /// Maximum number of mesh tasks that can be launched by a single draw command. `None` if
+    /// mesh shaders are not supported.
+    pub max_draw_mesh_tasks_count: Option<gl::types::GLint>,
+
     /// Number of work groups for compute shaders.
     pub max_compute_work_group_count: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
 
@@ -137,6 +209,27 @@ pub struct Capabilities {
 
     /// Maximum samples of an empty framebuffer. `None` if not supported.
     pub max_framebuffer_samples: Option<gl::types::GLint>,
+
+    /// How fragment shaders can read the current content of the framebuffer. `None` if
+    /// framebuffer fetch is not supported.
+    pub framebuffer_fetch: Option<FramebufferFetch>,
+
+    /// How vertex shaders can write `gl_Layer` in order to render to a layered framebuffer
+    /// without a geometry shader. `None` if this is not supported.
+    pub vertex_shader_layer: Option<VertexShaderLayer>,
+
+    /// How fragment shaders can order their accesses to images and buffers with the ones of
+    /// the fragment shaders invocations that cover the same pixel. `None` if this is not
+    /// supported.
+    pub fragment_shader_interlock: Option<FragmentShaderInterlock>,
+
+    /// True if the bounding box of the primitives can be passed to the implementation through
+    /// `DrawParameters::primitive_bounding_box`.
+    pub primitive_bounding_box: bool,
+
+    /// True if indices of type `u8` are handled natively by the implementation. If false, they
+    /// are still supported but are converted by the driver each time they are used.
+    pub native_u8_indices: bool,
 }
 
 /// Information about an internal format.
@@ -144,6 +237,75 @@ pub struct Capabilities {
 pub struct FormatInfos {
     /// Possible values for multisampling. `None` if unknown.
     pub multisamples: Option<Vec<gl::types::GLint>>,
+
+    /// How textures of this format can be accessed with image load/store. `None` if unknown
+    /// or for renderbuffers.
+    pub image: Option<ImageFormatSupport>,
+
+    /// True if the driver reports that this format can be attached to a framebuffer, whether
+    /// as a color, depth, stencil or depth-stencil attachment. `None` if unknown.
+    pub renderable: Option<bool>,
+}
+
+/// Describes how textures of a format can be accessed by shaders with image load/store.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImageFormatSupport {
+    /// True if shaders can read images of this format with `imageLoad`.
+    pub load: bool,
+    /// True if shaders can write images of this format with `imageStore`.
+    pub store: bool,
+    /// True if shaders can perform atomic operations on images of this format.
+    pub atomic: bool,
+}
+
+/// Describes what shaders can do with the `textureGather` family of GLSL functions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TextureGather {
+    /// True if `textureGather` can be called with a shadow sampler and a reference value, which
+    /// returns the result of the depth comparison for the four texels.
+    pub shadow: bool,
+
+    /// True if `textureGather` accepts a component to gather other than the red one.
+    pub component_selection: bool,
+
+    /// Minimum and maximum values of the offsets passed to `textureGatherOffset`.
+    pub offset: (gl::types::GLint, gl::types::GLint),
+}
+
+/// Describes how fragment shaders can read the current content of the framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FramebufferFetch {
+    /// Fragment outputs can be declared `inout`, and reading them returns the current value
+    /// of the corresponding color attachment. Provided by `GL_EXT_shader_framebuffer_fetch`.
+    InoutOutputs,
+
+    /// The current value of the first color attachment can be read through
+    /// `gl_LastFragColorARM`. Provided by `GL_ARM_shader_framebuffer_fetch`.
+    LastFragColor,
+}
+
+/// Describes how shaders other than geometry shaders can choose the layer to render to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VertexShaderLayer {
+    /// Vertex shaders and tessellation evaluation shaders can write `gl_Layer` and
+    /// `gl_ViewportIndex`. Provided by `GL_ARB_shader_viewport_layer_array`.
+    LayerAndViewportIndex,
+
+    /// Vertex shaders can write `gl_Layer`. Provided by `GL_AMD_vertex_shader_layer`.
+    Layer,
+}
+
+/// Describes how fragment shaders can access memory in the order of the primitives, which is
+/// required for example by order-independent transparency or programmable blending.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FragmentShaderInterlock {
+    /// The critical section is delimited by `beginInvocationInterlockARB()` and
+    /// `endInvocationInterlockARB()`. Provided by `GL_ARB_fragment_shader_interlock`.
+    Interlock,
+
+    /// Accesses after a call to `beginFragmentShaderOrderingINTEL()` are ordered. Provided by
+    /// `GL_INTEL_fragment_shader_ordering`.
+    Ordering,
 }
 
 /// Defines what happens when you change the current context.
@@ -156,22 +318,41 @@ pub enum ReleaseBehavior {
     Flush,
 }
 
+/// Defines what happens to the content of the back buffer when swapping buffers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SwapBehavior {
+    /// The back buffer keeps its content.
+    Preserved,
+
+    /// The content of the back buffer is undefined after swapping.
+    Destroyed,
+}
+
 /// Loads the capabilities.
 ///
 /// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
 ///
+/// The values of `attributes` are reported by the backend, and are used instead of querying
+/// OpenGL, as some drivers return wrong values.
+///
+/// Returns an error if the implementation returns values that don't follow the specifications.
+///
 /// ## Panic
 ///
 /// Can panic if the version number or extensions list don't match the backend, leading to
 /// unloaded functions being called.
 ///
-pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList)
-                               -> Capabilities
+pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList,
+                               attributes: &ContextAttributes)
+                               -> Result<Capabilities, ContextCreationError>
 {
     // GL_CONTEXT_FLAGS are only available from GL 3.0 onwards
-    let (debug, forward_compatible) = if version >= &Version(Api::Gl, 3, 0) {
-        let mut val = mem::uninitialized();
-        gl.GetIntegerv(gl::CONTEXT_FLAGS, &mut val);
+    let (debug, forward_compatible) = if let (Some(debug), Some(forward_compatible)) =
+                                             (attributes.debug, attributes.forward_compatible)
+    {
+        (debug, forward_compatible)
+    } else if version >= &Version(Api::Gl, 3, 0) {
+        let val = get_integer(gl, gl::CONTEXT_FLAGS);
         let val = val as gl::types::GLenum;
         ((val & gl::CONTEXT_FLAG_DEBUG_BIT) != 0,
          (val & gl::CONTEXT_FLAG_FORWARD_COMPATIBLE_BIT) != 0)
@@ -180,36 +361,43 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
     };
 
     // getting the value of `GL_RENDERER`
-    let renderer = {
-        let s = gl.GetString(gl::RENDERER);
-        assert!(!s.is_null());
-        String::from_utf8(CStr::from_ptr(s as *const _).to_bytes().to_vec()).ok()
-                                    .expect("glGetString(GL_RENDERER) returned a non-UTF8 string")
-    };
+    let renderer = try!(get_string(gl, gl::RENDERER, "GL_RENDERER"));
+
+    let (color_bits, float_color_buffer) = get_default_framebuffer_color(gl, version, extensions);
+    let color_bits = attributes.color_bits.unwrap_or(color_bits);
 
-    Capabilities {
+    Ok(Capabilities {
         supported_glsl_versions: {
             get_supported_glsl(gl, version, extensions)
         },
 
-        version: {
-            let s = gl.GetString(gl::VERSION);
-            assert!(!s.is_null());
-            String::from_utf8(CStr::from_ptr(s as *const _).to_bytes().to_vec()).ok()
-                                        .expect("glGetString(GL_VERSION) returned a non-UTF8 string")
-        },
+        shader_binary_formats: {
+            if version >= &Version(Api::GlEs, 2, 0) || version >= &Version(Api::Gl, 4, 1) ||
+               extensions.gl_arb_es2_compatibility
+            {
+                let num = get_integer(gl, gl::NUM_SHADER_BINARY_FORMATS);
+
+                if num >= 1 {
+                    let mut formats = Vec::with_capacity(num as usize);
+                    gl.GetIntegerv(gl::SHADER_BINARY_FORMATS, formats.as_mut_ptr());
+                    formats.set_len(num as usize);
+                    formats.into_iter().map(|f| f as gl::types::GLenum).collect()
+                } else {
+                    Vec::new()
+                }
 
-        vendor: {
-            let s = gl.GetString(gl::VENDOR);
-            assert!(!s.is_null());
-            String::from_utf8(CStr::from_ptr(s as *const _).to_bytes().to_vec()).ok()
-                                        .expect("glGetString(GL_VENDOR) returned a non-UTF8 string")
+            } else {
+                Vec::new()
+            }
         },
 
+        version: try!(get_string(gl, gl::VERSION, "GL_VERSION")),
+
+        vendor: try!(get_string(gl, gl::VENDOR, "GL_VENDOR")),
+
         profile: {
             if version >= &Version(Api::Gl, 3, 2) {
-                let mut val = mem::uninitialized();
-                gl.GetIntegerv(gl::CONTEXT_PROFILE_MASK, &mut val);
+                let val = get_integer(gl, gl::CONTEXT_PROFILE_MASK);
                 let val = val as gl::types::GLenum;
                 if (val & gl::CONTEXT_COMPATIBILITY_PROFILE_BIT) != 0 {
                     Some(Profile::Compatibility)
@@ -227,20 +415,20 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 
         forward_compatible: forward_compatible,
 
-        robustness: if version >= &Version(Api::Gl, 4, 5) || version >= &Version(Api::GlEs, 3, 2) ||
-                       (version >= &Version(Api::Gl, 3, 0) && extensions.gl_arb_robustness)
+        robustness: if let Some(robustness) = attributes.robustness {
+            robustness
+
+        } else if version >= &Version(Api::Gl, 4, 5) || version >= &Version(Api::GlEs, 3, 2) ||
+                  (version >= &Version(Api::Gl, 3, 0) && extensions.gl_arb_robustness)
         {
             // TODO: there seems to be no way to query `GL_CONTEXT_FLAGS` before OpenGL 3.0, even
             //       if `GL_ARB_robustness` is there
-            let mut val = mem::uninitialized();
-            gl.GetIntegerv(gl::CONTEXT_FLAGS, &mut val);
+            let val = get_integer(gl, gl::CONTEXT_FLAGS);
             let val = val as gl::types::GLenum;
             (val & gl::CONTEXT_FLAG_ROBUST_ACCESS_BIT) != 0
 
         } else if extensions.gl_khr_robustness || extensions.gl_ext_robustness {
-            let mut val = mem::uninitialized();
-            gl.GetBooleanv(gl::CONTEXT_ROBUST_ACCESS, &mut val);
-            val != 0
+            get_boolean(gl, gl::CONTEXT_ROBUST_ACCESS)
 
         } else {
             false
@@ -249,8 +437,7 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         can_lose_context: if version >= &Version(Api::Gl, 4, 5) || extensions.gl_khr_robustness ||
                              extensions.gl_arb_robustness || extensions.gl_ext_robustness
         {
-            let mut val = mem::uninitialized();
-            gl.GetIntegerv(gl::RESET_NOTIFICATION_STRATEGY, &mut val);
+            let val = get_integer(gl, gl::RESET_NOTIFICATION_STRATEGY);
 
             match val as gl::types::GLenum {
                 gl::LOSE_CONTEXT_ON_RESET => true,
@@ -264,7 +451,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
                 // WORK-AROUND: Adreno 430/506 drivers return NO_ERROR.
                 gl::NO_ERROR => false,
 
-                _ => unreachable!()
+                val => return Err(ContextCreationError::DriverBug(
+                    format!("Invalid value {:#x} for GL_RESET_NOTIFICATION_STRATEGY", val)
+                )),
             }
 
         } else {
@@ -272,13 +461,14 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         },
 
         release_behavior: if extensions.gl_khr_context_flush_control {
-            let mut val = mem::uninitialized();
-            gl.GetIntegerv(gl::CONTEXT_RELEASE_BEHAVIOR, &mut val);
+            let val = get_integer(gl, gl::CONTEXT_RELEASE_BEHAVIOR);
 
             match val as gl::types::GLenum {
                 gl::NONE => ReleaseBehavior::None,
                 gl::CONTEXT_RELEASE_BEHAVIOR_FLUSH => ReleaseBehavior::Flush,
-                _ => unreachable!()
+                val => return Err(ContextCreationError::DriverBug(
+                    format!("Invalid value {:#x} for GL_CONTEXT_RELEASE_BEHAVIOR", val)
+                )),
             }
 
         } else {
@@ -286,10 +476,10 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         },
 
         stereo: {
-            if version >= &Version(Api::Gl, 1, 0) {
-                let mut val: gl::types::GLboolean = mem::uninitialized();
-                gl.GetBooleanv(gl::STEREO, &mut val);
-                val != 0
+            if let Some(stereo) = attributes.stereo {
+                stereo
+            } else if version >= &Version(Api::Gl, 1, 0) {
+                get_boolean(gl, gl::STEREO)
             } else {
                 false
             }
@@ -298,25 +488,52 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         srgb: {
             // `glGetFramebufferAttachmentParameteriv` incorrectly returns GL_INVALID_ENUM on some
             // drivers, so we prefer using `glGetIntegerv` if possible.
-            if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_ext_framebuffer_srgb {
-                let mut value = mem::uninitialized();
-                gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::FRONT_LEFT,
-                                                       gl::FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING,
-                                                       &mut value);
+            if let Some(srgb) = attributes.srgb {
+                srgb
+
+            } else if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_ext_framebuffer_srgb {
+                let value = get_output(|ptr| {
+                    gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::FRONT_LEFT,
+                                                           gl::FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING,
+                                                           ptr)
+                });
                 value as gl::types::GLenum == gl::SRGB
 
             } else if extensions.gl_ext_framebuffer_srgb {
-                let mut value = mem::uninitialized();
-                gl.GetBooleanv(gl::FRAMEBUFFER_SRGB_CAPABLE_EXT, &mut value);
-                value != 0
+                get_boolean(gl, gl::FRAMEBUFFER_SRGB_CAPABLE_EXT)
 
             } else {
                 false
             }
         },
 
-        depth_bits: {
-            let mut value = mem::uninitialized();
+        samples: if let Some(samples) = attributes.samples {
+            samples
+        } else if version >= &Version(Api::Gl, 1, 3) || version >= &Version(Api::GlEs, 2, 0) {
+            get_integer(gl, gl::SAMPLES) as u16
+        } else {
+            0
+        },
+
+        double_buffer: if let Some(double_buffer) = attributes.double_buffer {
+            double_buffer
+        } else if version.0 == Api::Gl {
+            get_boolean(gl, gl::DOUBLEBUFFER)
+        } else {
+            // OpenGL ES doesn't provide a way to know this, but single-buffered surfaces are rare
+            true
+        },
+
+        swap_behavior: attributes.swap_behavior,
+
+        color_bits: color_bits,
+
+        float_color_buffer: float_color_buffer,
+
+        depth_bits: if let Some(bits) = attributes.depth_bits {
+            if bits == 0 { None } else { Some(bits) }
+        } else {
+            let mut value = 0;
 
             // `glGetFramebufferAttachmentParameteriv` incorrectly returns GL_INVALID_ENUM on some
             // drivers, so we prefer using `glGetIntegerv` if possible.
@@ -325,10 +542,11 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             // doesn't even though it provides this extension. I'm not sure whether this is a bug
             // with OS/X or just the extension actually not providing it.
             if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_arb_compatibility {
-                let mut ty = mem::uninitialized();
-                gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::DEPTH,
-                                                       gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
-                                                       &mut ty);
+                let ty = get_output(|ptr| {
+                    gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::DEPTH,
+                                                           gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
+                                                           ptr)
+                });
 
                 if ty as gl::types::GLenum == gl::NONE {
                     value = 0;
@@ -348,8 +566,10 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
-        stencil_bits: {
-            let mut value = mem::uninitialized();
+        stencil_bits: if let Some(bits) = attributes.stencil_bits {
+            if bits == 0 { None } else { Some(bits) }
+        } else {
+            let mut value = 0;
 
             // `glGetFramebufferAttachmentParameteriv` incorrectly returns GL_INVALID_ENUM on some
             // drivers, so we prefer using `glGetIntegerv` if possible.
@@ -358,10 +578,11 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             // doesn't even though it provides this extension. I'm not sure whether this is a bug
             // with OS/X or just the extension actually not providing it.
             if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_arb_compatibility {
-                let mut ty = mem::uninitialized();
-                gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::STENCIL,
-                                                       gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
-                                                       &mut ty);
+                let ty = get_output(|ptr| {
+                    gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, gl::STENCIL,
+                                                           gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
+                                                           ptr)
+                });
 
                 if ty as gl::types::GLenum == gl::NONE {
                     value = 0;
@@ -397,13 +618,15 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             val
         },
 
-        max_texture_max_anisotropy: if !extensions.gl_ext_texture_filter_anisotropic {
+        max_texture_max_anisotropy: if !(version >= &Version(Api::Gl, 4, 6) ||
+                                          extensions.gl_arb_texture_filter_anisotropic ||
+                                          extensions.gl_ext_texture_filter_anisotropic)
+        {
             None
 
         } else {
             Some({
-                let mut val = mem::uninitialized();
-                gl.GetFloatv(gl::MAX_TEXTURE_MAX_ANISOTROPY_EXT, &mut val);
+                let val = get_float(gl, gl::MAX_TEXTURE_MAX_ANISOTROPY_EXT);
                 val
             })
         },
@@ -414,8 +637,21 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
                extensions.gl_ext_texture_buffer
             {
                 Some({
-                    let mut val = mem::uninitialized();
-                    gl.GetIntegerv(gl::MAX_TEXTURE_BUFFER_SIZE, &mut val);
+                    let val = get_integer(gl, gl::MAX_TEXTURE_BUFFER_SIZE);
+                    val
+                })
+
+            } else {
+                None
+            }
+        },
+
+        texture_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 2) ||
+               extensions.gl_arb_texture_buffer_range || extensions.gl_oes_texture_buffer
+            {
+                Some({
+                    let val = get_integer(gl, gl::TEXTURE_BUFFER_OFFSET_ALIGNMENT);
                     val
                 })
 
@@ -424,6 +660,33 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        program_texel_offset: if version >= &Version(Api::Gl, 3, 0) ||
+            version >= &Version(Api::GlEs, 3, 0) || extensions.gl_ext_gpu_shader4
+        {
+            Some((get_integer(gl, gl::MIN_PROGRAM_TEXEL_OFFSET),
+                  get_integer(gl, gl::MAX_PROGRAM_TEXEL_OFFSET)))
+
+        } else {
+            None
+        },
+
+        texture_gather: if version >= &Version(Api::Gl, 4, 0) ||
+            version >= &Version(Api::GlEs, 3, 1) || extensions.gl_arb_texture_gather
+        {
+            let extended = version >= &Version(Api::Gl, 4, 0) ||
+                           version >= &Version(Api::GlEs, 3, 1) || extensions.gl_arb_gpu_shader5;
+
+            Some(TextureGather {
+                shadow: extended,
+                component_selection: extended,
+                offset: (get_integer(gl, gl::MIN_PROGRAM_TEXTURE_GATHER_OFFSET),
+                         get_integer(gl, gl::MAX_PROGRAM_TEXTURE_GATHER_OFFSET)),
+            })
+
+        } else {
+            None
+        },
+
         max_viewport_dims: {
             let mut val: [gl::types::GLint; 2] = [ 0, 0 ];
             gl.GetIntegerv(gl::MAX_VIEWPORT_DIMS, val.as_mut_ptr());
@@ -433,7 +696,8 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         max_draw_buffers: {
             if version >= &Version(Api::Gl, 2, 0) ||
                 version >= &Version(Api::GlEs, 3, 0) ||
-                extensions.gl_ati_draw_buffers || extensions.gl_arb_draw_buffers
+                extensions.gl_ati_draw_buffers || extensions.gl_arb_draw_buffers ||
+                extensions.gl_ext_draw_buffers
             {
                 let mut val = 1;
                 gl.GetIntegerv(gl::MAX_DRAW_BUFFERS, &mut val);
@@ -447,8 +711,7 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             extensions.gl_arb_tessellation_shader
         {
             Some({
-                let mut val = mem::uninitialized();
-                gl.GetIntegerv(gl::MAX_PATCH_VERTICES, &mut val);
+                let val = get_integer(gl, gl::MAX_PATCH_VERTICES);
                 val
             })
 
@@ -456,9 +719,67 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             None
         },
 
+        max_geometry_output_vertices: if version >= &Version(Api::Gl, 3, 2) ||
+            version >= &Version(Api::GlEs, 3, 2) || extensions.gl_arb_geometry_shader4 ||
+            extensions.gl_ext_geometry_shader4 || extensions.gl_ext_geometry_shader ||
+            extensions.gl_oes_geometry_shader
+        {
+            Some({
+                let val = get_integer(gl, gl::MAX_GEOMETRY_OUTPUT_VERTICES);
+                val
+            })
+
+        } else {
+            None
+        },
+
+        max_geometry_total_output_components: if version >= &Version(Api::Gl, 3, 2) ||
+            version >= &Version(Api::GlEs, 3, 2) || extensions.gl_arb_geometry_shader4 ||
+            extensions.gl_ext_geometry_shader4 || extensions.gl_ext_geometry_shader ||
+            extensions.gl_oes_geometry_shader
+        {
+            Some({
+                let val = get_integer(gl, gl::MAX_GEOMETRY_TOTAL_OUTPUT_COMPONENTS);
+                val
+            })
+
+        } else {
+            None
+        },
+
+        max_geometry_shader_invocations: if version >= &Version(Api::Gl, 4, 0) ||
+            version >= &Version(Api::GlEs, 3, 2) || extensions.gl_arb_gpu_shader5 ||
+            extensions.gl_ext_geometry_shader || extensions.gl_oes_geometry_shader
+        {
+            Some({
+                let val = get_integer(gl, gl::MAX_GEOMETRY_SHADER_INVOCATIONS);
+                val
+            })
+
+        } else {
+            None
+        },
+
+        max_image_units: if version >= &Version(Api::Gl, 4, 2) ||
+            version >= &Version(Api::GlEs, 3, 1) || extensions.gl_arb_shader_image_load_store ||
+            extensions.gl_ext_shader_image_load_store
+        {
+            Some({
+                let val = get_integer(gl, gl::MAX_IMAGE_UNITS);
+                val
+            })
+
+        } else {
+            None
+        },
+
+        image_atomic: version >= &Version(Api::Gl, 4, 2) || version >= &Version(Api::GlEs, 3, 2) ||
+                      extensions.gl_arb_shader_image_load_store ||
+                      extensions.gl_ext_shader_image_load_store ||
+                      extensions.gl_oes_shader_image_atomic,
+
         max_indexed_atomic_counter_buffer: if version >= &Version(Api::Gl, 4, 2) {      // TODO: ARB_shader_atomic_counters   // TODO: GLES
-            let mut val = mem::uninitialized();
-            gl.GetIntegerv(gl::MAX_ATOMIC_COUNTER_BUFFER_BINDINGS, &mut val);
+            let val = get_integer(gl, gl::MAX_ATOMIC_COUNTER_BUFFER_BINDINGS);
             val
         } else {
             0
@@ -466,8 +787,7 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 
         max_indexed_shader_storage_buffer: {
             if version >= &Version(Api::Gl, 4, 3) || extensions.gl_arb_shader_storage_buffer_object {      // TODO: GLES
-                let mut val = mem::uninitialized();
-                gl.GetIntegerv(gl::MAX_SHADER_STORAGE_BUFFER_BINDINGS, &mut val);
+                let val = get_integer(gl, gl::MAX_SHADER_STORAGE_BUFFER_BINDINGS);
                 val
             } else {
                 0
@@ -476,12 +796,10 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 
         max_indexed_transform_feedback_buffer: {
             if version >= &Version(Api::Gl, 4, 0) || extensions.gl_arb_transform_feedback3 {      // TODO: GLES
-                let mut val = mem::uninitialized();
-                gl.GetIntegerv(gl::MAX_TRANSFORM_FEEDBACK_BUFFERS, &mut val);
+                let val = get_integer(gl, gl::MAX_TRANSFORM_FEEDBACK_BUFFERS);
                 val
             } else if version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_transform_feedback {
-                let mut val = mem::uninitialized();
-                gl.GetIntegerv(gl::MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS_EXT, &mut val);
+                let val = get_integer(gl, gl::MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS_EXT);
                 val
             } else {
                 0
@@ -490,25 +808,60 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
 
         max_indexed_uniform_buffer: {
             if version >= &Version(Api::Gl, 3, 1) || extensions.gl_arb_uniform_buffer_object {      // TODO: GLES
-                let mut val = mem::uninitialized();
-                gl.GetIntegerv(gl::MAX_UNIFORM_BUFFER_BINDINGS, &mut val);
+                let val = get_integer(gl, gl::MAX_UNIFORM_BUFFER_BINDINGS);
                 val
             } else {
                 0
             }
         },
 
+        uniform_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                let val = get_integer(gl, gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT);
+                val
+            } else {
+                1
+            }
+        },
+
+        shader_storage_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
+                let val = get_integer(gl, gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT);
+                val
+            } else {
+                1
+            }
+        },
+
+        sparse_buffer_page_size: {
+            if extensions.gl_arb_sparse_buffer {
+                let val = get_integer(gl, gl::SPARSE_BUFFER_PAGE_SIZE_ARB);
+                Some(val)
+            } else {
+                None
+            }
+        },
+
+        max_draw_mesh_tasks_count: {
+            if extensions.gl_nv_mesh_shader {
+                let val = get_integer(gl, gl::MAX_DRAW_MESH_TASKS_COUNT_NV);
+                Some(val)
+            } else {
+                None
+            }
+        },
+
         max_compute_work_group_count: if version >= &Version(Api::Gl, 4, 3) ||
                                          version >= &Version(Api::GlEs, 3, 1) ||
                                          extensions.gl_arb_compute_shader
         {
-            let mut val1 = mem::uninitialized();
-            let mut val2 = mem::uninitialized();
-            let mut val3 = mem::uninitialized();
-            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_COUNT, 0, &mut val1);
-            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_COUNT, 1, &mut val2);
-            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_COUNT, 2, &mut val3);
-            (val1, val2, val3)
+            (get_integer_indexed(gl, gl::MAX_COMPUTE_WORK_GROUP_COUNT, 0),
+             get_integer_indexed(gl, gl::MAX_COMPUTE_WORK_GROUP_COUNT, 1),
+             get_integer_indexed(gl, gl::MAX_COMPUTE_WORK_GROUP_COUNT, 2))
 
         } else {
             (0, 0, 0)
@@ -525,8 +878,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             } else if version >= &Version(Api::GlEs, 2, 0) {
                 1
             } else {
-                // glium doesn't allow creating contexts that don't support FBOs
-                unreachable!()
+                // glium doesn't allow creating contexts that don't support FBOs, so this is
+                // normally caught earlier by `check_gl_compatibility`
+                return Err(ContextCreationError::MissingExtension("GL_ARB_framebuffer_object"));
             }
         },
 
@@ -582,8 +936,59 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        framebuffer_fetch: if extensions.gl_ext_shader_framebuffer_fetch {
+            Some(FramebufferFetch::InoutOutputs)
+        } else if extensions.gl_arm_shader_framebuffer_fetch {
+            Some(FramebufferFetch::LastFragColor)
+        } else {
+            None
+        },
+
+        vertex_shader_layer: if extensions.gl_arb_shader_viewport_layer_array {
+            Some(VertexShaderLayer::LayerAndViewportIndex)
+        } else if extensions.gl_amd_vertex_shader_layer {
+            Some(VertexShaderLayer::Layer)
+        } else {
+            None
+        },
+
+        fragment_shader_interlock: if extensions.gl_arb_fragment_shader_interlock {
+            Some(FragmentShaderInterlock::Interlock)
+        } else if extensions.gl_intel_fragment_shader_ordering {
+            Some(FragmentShaderInterlock::Ordering)
+        } else {
+            None
+        },
+
+        primitive_bounding_box: version >= &Version(Api::GlEs, 3, 2) ||
+                                extensions.gl_arb_es3_2_compatibility ||
+                                extensions.gl_oes_primitive_bounding_box ||
+                                extensions.gl_ext_primitive_bounding_box,
+
+        // WORK-AROUND
+        // Direct3D doesn't have 8-bit indices, so ANGLE converts them to 16 bits at each draw.
+        native_u8_indices: !renderer.contains("ANGLE"),
+
         renderer: renderer,
+    })
+}
+
+/// Returns the value of a string with `glGetString`.
+///
+/// Returns an error if the implementation returns a null pointer or a string that isn't UTF-8.
+unsafe fn get_string(gl: &gl::Gl, name: gl::types::GLenum, name_str: &'static str)
+                     -> Result<String, ContextCreationError>
+{
+    let s = gl.GetString(name);
+    if s.is_null() {
+        return Err(ContextCreationError::DriverBug(format!("glGetString({}) returned null",
+                                                           name_str)));
     }
+
+    String::from_utf8(CStr::from_ptr(s as *const _).to_bytes().to_vec()).map_err(|_| {
+        ContextCreationError::DriverBug(format!("glGetString({}) returned a non-UTF8 string",
+                                                name_str))
+    })
 }
 
 /// Gets the list of GLSL versions supported by the backend.
@@ -601,9 +1006,7 @@ pub unsafe fn get_supported_glsl(gl: &gl::Gl, version: &Version, extensions: &Ex
     // checking if the implementation has a shader compiler
     // a compiler is optional in OpenGL ES
     if version.0 == Api::GlEs {
-        let mut val = mem::uninitialized();
-        gl.GetBooleanv(gl::SHADER_COMPILER, &mut val);
-        if val == 0 {
+        if !get_boolean(gl, gl::SHADER_COMPILER) {
             return vec![];
         }
     }
@@ -670,6 +1073,59 @@ pub unsafe fn get_supported_glsl(gl: &gl::Gl, version: &Version, extensions: &Ex
     result
 }
 
+/// Returns the number of bits of each channel of the default framebuffer's color buffer, and
+/// whether it contains floating-point values.
+unsafe fn get_default_framebuffer_color(gl: &gl::Gl, version: &Version,
+                                        extensions: &ExtensionsList) -> ([u16; 4], bool)
+{
+    // same as for the depth buffer, `GL_RED_BITS` and similar don't exist in core profiles
+    if version >= &Version(Api::Gl, 3, 0) && !extensions.gl_arb_compatibility {
+        let mut attachment = gl::BACK_LEFT;
+
+        let ty = get_output(|ptr| {
+            gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment,
+                                                   gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE, ptr)
+        });
+
+        // single-buffered contexts don't have a back buffer
+        if ty as gl::types::GLenum == gl::NONE {
+            attachment = gl::FRONT_LEFT;
+        }
+
+        let mut bits = [0; 4];
+        for (&pname, bits) in [gl::FRAMEBUFFER_ATTACHMENT_RED_SIZE,
+                               gl::FRAMEBUFFER_ATTACHMENT_GREEN_SIZE,
+                               gl::FRAMEBUFFER_ATTACHMENT_BLUE_SIZE,
+                               gl::FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE].iter()
+                                                                      .zip(bits.iter_mut())
+        {
+            let value = get_output(|ptr| {
+                gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment, pname, ptr)
+            });
+            *bits = value as u16;
+        }
+
+        let component_type = get_output(|ptr| {
+            gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment,
+                                                   gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE, ptr)
+        });
+
+        (bits, component_type as gl::types::GLenum == gl::FLOAT)
+
+    } else {
+        let mut bits = [0; 4];
+        for (&pname, bits) in [gl::RED_BITS, gl::GREEN_BITS, gl::BLUE_BITS, gl::ALPHA_BITS]
+                                .iter().zip(bits.iter_mut())
+        {
+            let value = get_integer(gl, pname);
+            *bits = value as u16;
+        }
+
+        // float default framebuffers can't be detected without `glGetFramebufferAttachmentParameteriv`
+        (bits, false)
+    }
+}
+
 /// Returns all informations about all supported internal formats.
 pub fn get_internal_formats(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList,
                             renderbuffer: bool) -> HashMap<TextureFormat, FormatInfos, BuildHasherDefault<FnvHasher>>
@@ -724,8 +1180,9 @@ pub fn get_internal_format(gl: &gl::Gl, version: &Version, extensions: &Extensio
                           version >= &Version(Api::Gl, 4, 2) ||
                           extensions.gl_arb_internalformat_query)
         {
-            let mut num = mem::uninitialized();
-            gl.GetInternalformativ(target, format.to_glenum(), gl::NUM_SAMPLE_COUNTS, 1, &mut num);
+            let num = get_output(|ptr| {
+                gl.GetInternalformativ(target, format.to_glenum(), gl::NUM_SAMPLE_COUNTS, 1, ptr)
+            });
 
             if num >= 1 {
                 let mut formats = Vec::with_capacity(num as usize);
@@ -742,8 +1199,44 @@ pub fn get_internal_format(gl: &gl::Gl, version: &Version, extensions: &Extensio
             None
         };
 
+        let image = if !renderbuffer && (version >= &Version(Api::Gl, 4, 3) ||
+                                         extensions.gl_arb_internalformat_query2)
+        {
+            let query = |pname: gl::types::GLenum| {
+                let support = get_output(|ptr| {
+                    gl.GetInternalformativ(gl::TEXTURE_2D, format.to_glenum(), pname, 1, ptr)
+                });
+                support as gl::types::GLenum != gl::NONE
+            };
+
+            Some(ImageFormatSupport {
+                load: query(gl::SHADER_IMAGE_LOAD),
+                store: query(gl::SHADER_IMAGE_STORE),
+                atomic: query(gl::SHADER_IMAGE_ATOMIC),
+            })
+
+        } else {
+            None
+        };
+
+        let renderable = if version >= &Version(Api::Gl, 4, 3) ||
+                            extensions.gl_arb_internalformat_query2
+        {
+            let target = if renderbuffer { gl::RENDERBUFFER } else { gl::TEXTURE_2D };
+            let support = get_output(|ptr| {
+                gl.GetInternalformativ(target, format.to_glenum(), gl::FRAMEBUFFER_RENDERABLE, 1,
+                                       ptr)
+            });
+            Some(support as gl::types::GLenum != gl::NONE)
+
+        } else {
+            None
+        };
+
         FormatInfos {
             multisamples: samples,
+            image: image,
+            renderable: renderable,
         }
     }
 }
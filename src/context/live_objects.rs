@@ -0,0 +1,278 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+use std::ptr;
+
+use fnv::FnvHasher;
+
+use gl;
+use utils::gl_get::get_output;
+use context::CommandContext;
+use debug::{DebugDump, ObjectKind, ObjectReport};
+use texture::Dimensions;
+use version::Api;
+use version::Version;
+
+/// An object of the list, with the bind point required to query its storage.
+struct Entry {
+    report: ObjectReport,
+    bind_point: gl::types::GLenum,
+}
+
+/// List of the OpenGL objects created by glium that are still alive.
+///
+/// Objects register themselves when they are created and unregister themselves when they are
+/// destroyed. Binding an object for drawing, reading or writing marks it as used during the
+/// current frame.
+pub struct LiveObjects {
+    objects: RefCell<HashMap<(ObjectKind, gl::types::GLuint), Entry,
+                             BuildHasherDefault<FnvHasher>>>,
+
+    // incremented each time the buffers are swapped
+    frame: Cell<u64>,
+}
+
+impl LiveObjects {
+    /// Builds a new empty list.
+    #[inline]
+    pub fn new() -> LiveObjects {
+        LiveObjects {
+            objects: RefCell::new(HashMap::with_hasher(Default::default())),
+            frame: Cell::new(0),
+        }
+    }
+
+    /// Registers a newly-created object.
+    ///
+    /// `bind_point` is the target the object must be bound to in order to query its storage,
+    /// for textures and render buffers. It is ignored for other kinds of objects.
+    pub fn insert(&self, kind: ObjectKind, id: gl::types::GLuint, bind_point: gl::types::GLenum,
+                  size: Option<usize>, format: Option<String>, dimensions: Option<Dimensions>)
+    {
+        let frame = self.frame.get();
+
+        self.objects.borrow_mut().insert((kind, id), Entry {
+            report: ObjectReport {
+                kind: kind,
+                id: id,
+                label: None,
+                size: size,
+                format: format,
+                dimensions: dimensions,
+                created_frame: frame,
+                last_used_frame: frame,
+            },
+            bind_point: bind_point,
+        });
+    }
+
+    /// Unregisters an object that is being destroyed.
+    #[inline]
+    pub fn remove(&self, kind: ObjectKind, id: gl::types::GLuint) {
+        self.objects.borrow_mut().remove(&(kind, id));
+    }
+
+    /// Marks an object as used during the current frame.
+    #[inline]
+    pub fn mark_used(&self, kind: ObjectKind, id: gl::types::GLuint) {
+        if let Some(entry) = self.objects.borrow_mut().get_mut(&(kind, id)) {
+            entry.report.last_used_frame = self.frame.get();
+        }
+    }
+
+    /// Must be called after the buffers have been swapped.
+    #[inline]
+    pub fn next_frame(&self) {
+        self.frame.set(self.frame.get() + 1);
+    }
+
+    /// Builds a report of all the objects of the list. Labels and sizes of textures and render
+    /// buffers are queried from the backend.
+    pub fn dump(ctxt: &mut CommandContext) -> DebugDump {
+        let live_objects = ctxt.live_objects;
+        let objects = live_objects.objects.borrow();
+
+        let mut reports = Vec::with_capacity(objects.len());
+
+        for entry in objects.values() {
+            let mut report = entry.report.clone();
+
+            unsafe {
+                report.label = get_label(ctxt, report.kind, report.id);
+
+                match report.kind {
+                    ObjectKind::Texture => {
+                        report.size = get_texture_size(ctxt, report.id, entry.bind_point);
+                    },
+                    ObjectKind::RenderBuffer => {
+                        report.size = get_render_buffer_size(ctxt, report.id);
+                    },
+                    _ => ()
+                }
+            }
+
+            reports.push(report);
+        }
+
+        reports.sort_by(|a, b| (a.kind, a.id).cmp(&(b.kind, b.id)));
+
+        DebugDump {
+            frame: live_objects.frame.get(),
+            objects: reports,
+        }
+    }
+}
+
+/// Returns the label of an object, or `None` if it doesn't have any or if labels aren't
+/// supported.
+unsafe fn get_label(ctxt: &mut CommandContext, kind: ObjectKind, id: gl::types::GLuint)
+                    -> Option<String>
+{
+    let identifier = match kind {
+        ObjectKind::Buffer => gl::BUFFER,
+        ObjectKind::Texture => gl::TEXTURE,
+        ObjectKind::RenderBuffer => gl::RENDERBUFFER,
+        ObjectKind::Program => gl::PROGRAM,
+    };
+
+    let core = ctxt.version >= &Version(Api::Gl, 4, 3) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+               (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug);
+    let khr = ctxt.version >= &Version(Api::GlEs, 2, 0) && ctxt.extensions.gl_khr_debug;
+
+    if !core && !khr {
+        return None;
+    }
+
+    // querying the length first ; it doesn't include the null terminator
+    let mut length = 0;
+    if core {
+        ctxt.gl.GetObjectLabel(identifier, id, 0, &mut length, ptr::null_mut());
+    } else {
+        ctxt.gl.GetObjectLabelKHR(identifier, id, 0, &mut length, ptr::null_mut());
+    }
+
+    if length <= 0 {
+        return None;
+    }
+
+    let mut buffer: Vec<u8> = Vec::with_capacity(length as usize + 1);
+    if core {
+        ctxt.gl.GetObjectLabel(identifier, id, length + 1, &mut length,
+                               buffer.as_mut_ptr() as *mut gl::types::GLchar);
+    } else {
+        ctxt.gl.GetObjectLabelKHR(identifier, id, length + 1, &mut length,
+                                  buffer.as_mut_ptr() as *mut gl::types::GLchar);
+    }
+    buffer.set_len(length as usize);
+
+    Some(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Queries the size in bytes of all the mipmaps of a texture.
+unsafe fn get_texture_size(ctxt: &mut CommandContext, id: gl::types::GLuint,
+                           bind_point: gl::types::GLenum) -> Option<usize>
+{
+    // `glGetTexLevelParameteriv` isn't available on OpenGL ES before 3.1
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 1)) {
+        return None;
+    }
+
+    let texture_unit = ctxt.state.active_texture as usize;
+    if ctxt.state.texture_units[texture_unit].texture != id {
+        ctxt.gl.BindTexture(bind_point, id);
+        ctxt.state.texture_units[texture_unit].texture = id;
+    }
+
+    // the faces of a cubemap are queried individually
+    let (target, faces) = if bind_point == gl::TEXTURE_CUBE_MAP {
+        (gl::TEXTURE_CUBE_MAP_POSITIVE_X, 6)
+    } else {
+        (bind_point, 1)
+    };
+
+    let get = |ctxt: &mut CommandContext, level, pname| {
+        let value = get_output(|ptr| ctxt.gl.GetTexLevelParameteriv(target, level, pname, ptr));
+        value as usize
+    };
+
+    let mut total = 0;
+
+    for level in 0 .. {
+        let width = get(ctxt, level, gl::TEXTURE_WIDTH);
+        if width == 0 {
+            break;
+        }
+
+        let size = if get(ctxt, level, gl::TEXTURE_COMPRESSED) != 0 {
+            // OpenGL ES can't query the size of compressed images
+            if ctxt.version.0 == Api::GlEs {
+                return None;
+            }
+
+            get(ctxt, level, gl::TEXTURE_COMPRESSED_IMAGE_SIZE)
+        } else {
+            let height = get(ctxt, level, gl::TEXTURE_HEIGHT);
+            let depth = get(ctxt, level, gl::TEXTURE_DEPTH);
+            let samples = if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                             ctxt.version >= &Version(Api::GlEs, 3, 1)
+            {
+                get(ctxt, level, gl::TEXTURE_SAMPLES)
+            } else {
+                0
+            };
+
+            let bits = get(ctxt, level, gl::TEXTURE_RED_SIZE) +
+                       get(ctxt, level, gl::TEXTURE_GREEN_SIZE) +
+                       get(ctxt, level, gl::TEXTURE_BLUE_SIZE) +
+                       get(ctxt, level, gl::TEXTURE_ALPHA_SIZE) +
+                       get(ctxt, level, gl::TEXTURE_DEPTH_SIZE) +
+                       get(ctxt, level, gl::TEXTURE_STENCIL_SIZE);
+
+            width * height.max(1) * depth.max(1) * samples.max(1) * bits / 8
+        };
+
+        total += size * faces;
+    }
+
+    Some(total)
+}
+
+/// Queries the size in bytes of a render buffer.
+unsafe fn get_render_buffer_size(ctxt: &mut CommandContext, id: gl::types::GLuint)
+                                 -> Option<usize>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 2, 0)) {
+        return None;
+    }
+
+    if ctxt.state.renderbuffer != id {
+        ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, id);
+        ctxt.state.renderbuffer = id;
+    }
+
+    let get = |ctxt: &mut CommandContext, pname| {
+        let value = get_output(|ptr| {
+            ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, pname, ptr)
+        });
+        value as usize
+    };
+
+    let samples = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                     ctxt.version >= &Version(Api::GlEs, 3, 0)
+    {
+        get(ctxt, gl::RENDERBUFFER_SAMPLES)
+    } else {
+        0
+    };
+
+    let bits = get(ctxt, gl::RENDERBUFFER_RED_SIZE) +
+               get(ctxt, gl::RENDERBUFFER_GREEN_SIZE) +
+               get(ctxt, gl::RENDERBUFFER_BLUE_SIZE) +
+               get(ctxt, gl::RENDERBUFFER_ALPHA_SIZE) +
+               get(ctxt, gl::RENDERBUFFER_DEPTH_SIZE) +
+               get(ctxt, gl::RENDERBUFFER_STENCIL_SIZE);
+
+    Some(get(ctxt, gl::RENDERBUFFER_WIDTH) * get(ctxt, gl::RENDERBUFFER_HEIGHT) *
+         samples.max(1) * bits / 8)
+}
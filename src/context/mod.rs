@@ -3,7 +3,7 @@
 use gl;
 use backtrace;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::mem;
 use std::ptr;
 use std::str;
@@ -11,14 +11,18 @@ use std::borrow::Cow;
 use std::cell::{Cell, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ffi::CStr;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::os::raw;
 use std::hash::BuildHasherDefault;
 
 use fnv::FnvHasher;
 
+use ContextCreationError;
 use IncompatibleOpenGl;
+use Rect;
 use SwapBuffersError;
+use ToGlEnum;
+use Handle;
 use CapabilitiesSource;
 use ContextExt;
 use backend::Backend;
@@ -28,20 +32,64 @@ use version::Version;
 
 use debug;
 use fbo;
+use framebuffer::{BufferSelectionError, DefaultFramebufferAttachment};
 use ops;
+use program;
+use pacing::{SwapInterval, SwapIntervalError};
 use sampler_object;
+use sync;
 use texture;
 use uniforms;
 use vertex_array_object;
 
-pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile};
+pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile, FramebufferFetch};
+pub use self::capabilities::{VertexShaderLayer, FragmentShaderInterlock};
+pub use self::capabilities::{ImageFormatSupport, SwapBehavior, TextureGather};
 pub use self::extensions::ExtensionsList;
-pub use self::state::GlState;
+pub use self::state::{GlState, SavedTransformFeedbackCapture, StateStatistics};
+pub use self::program_cache::ProgramCache;
+pub use self::live_objects::LiveObjects;
 
 mod capabilities;
 mod extensions;
+mod live_objects;
+mod program_cache;
 mod state;
 
+/// A pixel buffer that is no longer in use and that is kept by the context in order to be reused.
+pub struct PooledPixelBuffer {
+    /// OpenGL identifier of the buffer.
+    pub id: gl::types::GLuint,
+
+    /// Size in bytes of the buffer. Always a power of two.
+    pub size: usize,
+
+    /// True if the buffer was created immutable.
+    pub immutable: bool,
+
+    /// True if the buffer was created with `glBufferStorage`.
+    pub created_with_buffer_storage: bool,
+
+    /// Fence signaled when the GPU has finished using the buffer, if fences are supported.
+    pub fence: Option<sync::LinearSyncFence>,
+}
+
+/// Statistics about one of the internal caches of the context.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CacheStatistics {
+    /// Number of objects currently in the cache.
+    pub entries: usize,
+
+    /// Maximum number of objects in the cache before the least recently used ones get destroyed.
+    pub capacity: usize,
+
+    /// Number of times an object has been found in the cache.
+    pub hits: u64,
+
+    /// Number of times an object had to be created because it wasn't in the cache.
+    pub misses: u64,
+}
+
 /// Stores the state and information required for glium to execute commands. Most public glium
 /// functions require passing a `Rc<Context>`.
 pub struct Context {
@@ -96,6 +144,63 @@ pub struct Context {
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// List of query objects that are no longer in use, with the target they were created for.
+    /// They are reused instead of being destroyed and created again.
+    query_objects_pool: RefCell<Vec<(gl::types::GLenum, gl::types::GLuint)>>,
+
+    /// Programs that are kept alive in order to be reused.
+    program_cache: ProgramCache,
+
+    /// Pixel buffers that are no longer in use and can be reused for other transfers.
+    pixel_buffers_pool: RefCell<Vec<PooledPixelBuffer>>,
+
+    /// List of the objects created by glium that are alive.
+    live_objects: LiveObjects,
+
+    /// Binding points reserved for the uniform blocks shared between programs, by block name.
+    shared_uniform_block_bindings: RefCell<HashMap<String, u32, BuildHasherDefault<FnvHasher>>>,
+
+    /// Binding points reserved for the shader storage blocks shared between programs, by block
+    /// name.
+    shared_shader_storage_block_bindings: RefCell<HashMap<String, u32,
+                                                          BuildHasherDefault<FnvHasher>>>,
+
+    /// The swap interval that was last set with `set_swap_interval`. Applied again to the new
+    /// backend when rebuilding the context.
+    swap_interval: Cell<Option<SwapInterval>>,
+
+    /// The GLSL modules that shaders can include.
+    shader_library: program::ShaderLibrary,
+
+    /// Whether the default framebuffer must be considered as sRGB, overriding the value detected
+    /// in the capabilities.
+    default_framebuffer_srgb: Cell<Option<bool>>,
+
+    /// Maximum number of frames that can be queued by the driver, or `None` to let the driver
+    /// decide.
+    max_frames_in_flight: Cell<Option<u32>>,
+
+    /// One fence for each frame that has been swapped and that may still be in flight.
+    frame_fences: RefCell<VecDeque<sync::LinearSyncFence>>,
+
+    /// When glium calls `glGetError`.
+    error_check_policy: Cell<ErrorCheckPolicy>,
+
+    /// Number of errors detected by `glGetError` and ignored because of the error check policy.
+    swallowed_errors: Cell<u64>,
+
+    /// What to do when a texture is created with an unsupported compressed format, for the
+    /// formats that don't have a policy in `format_fallback_policies`.
+    default_format_fallback_policy: Cell<texture::FormatFallbackPolicy>,
+
+    /// Fallback policies registered for specific formats.
+    format_fallback_policies: RefCell<HashMap<texture::TextureFormat, texture::FormatFallbackPolicy,
+                                              BuildHasherDefault<FnvHasher>>>,
+
+    /// List of the contexts whose OpenGL context shares its objects with this one, including
+    /// this context itself. Shared between all the contexts of the group.
+    share_group: Rc<RefCell<Vec<Weak<Context>>>>,
 }
 
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
@@ -116,10 +221,19 @@ pub struct CommandContext<'a> {
     /// Capabilities of the backend.
     pub capabilities: &'a Capabilities,
 
+    /// Whether the default framebuffer is in sRGB. Takes the user's override into account.
+    pub default_framebuffer_srgb: bool,
+
     /// Whether or not errors triggered by ARB_debug_output (and similar extensions) should be
     /// reported to the user (by panicking).
     pub report_debug_output_errors: &'a Cell<bool>,
 
+    /// When glium calls `glGetError`.
+    pub error_check_policy: ErrorCheckPolicy,
+
+    /// Number of errors detected by `glGetError` and ignored because of the error check policy.
+    pub swallowed_errors: &'a Cell<u64>,
+
     /// The list of vertex array objects.
     pub vertex_array_objects: &'a vertex_array_object::VertexAttributesSystem,
 
@@ -135,6 +249,15 @@ pub struct CommandContext<'a> {
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// List of query objects that can be reused, with their target.
+    pub query_objects_pool: RefMut<'a, Vec<(gl::types::GLenum, gl::types::GLuint)>>,
+
+    /// List of pixel buffers that can be reused.
+    pub pixel_buffers_pool: RefMut<'a, Vec<PooledPixelBuffer>>,
+
+    /// List of the objects created by glium that are alive.
+    pub live_objects: &'a LiveObjects,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -154,11 +277,53 @@ impl Context {
     /// The OpenGL context must be newly-created. If you make modifications to the context before
     /// passing it to this function, glium's state cache may mismatch the actual one.
     ///
+    /// Returns an error if the OpenGL implementation can't be used by glium, for example because
+    /// it is too old or because its driver returns invalid values.
+    ///
     pub unsafe fn new<B>(
         backend: B,
         check_current_context: bool,
         callback_behavior: DebugCallbackBehavior,
-    ) -> Result<Rc<Context>, IncompatibleOpenGl>
+    ) -> Result<Rc<Context>, ContextCreationError>
+        where B: Backend + 'static
+    {
+        Context::new_impl(backend, check_current_context, callback_behavior,
+                          Rc::new(RefCell::new(Vec::new())))
+    }
+
+    /// Builds a new context whose OpenGL context shares its objects with the one of `shared`.
+    ///
+    /// The backend's OpenGL context **must** have been created with its lists shared with the
+    /// OpenGL context of `shared` (for example with glutin's `with_shared_lists`).
+    ///
+    /// Buffers, textures, render buffers and programs are shared between the two contexts.
+    /// They can be created with one context and used with the other. When one of these objects
+    /// is destroyed, glium removes it from the caches of all the contexts of the group.
+    ///
+    /// Vertex array objects, framebuffer objects, queries and transform feedback objects can't
+    /// be shared. Glium creates them separately for each context when they are needed, so you
+    /// don't have to worry about them.
+    ///
+    /// Since contexts are not `Send`, all the contexts of a group must live in the same thread.
+    /// Both contexts should be built with `check_current_context` set to `true`.
+    pub unsafe fn new_shared<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        shared: &Rc<Context>,
+    ) -> Result<Rc<Context>, ContextCreationError>
+        where B: Backend + 'static
+    {
+        Context::new_impl(backend, check_current_context, callback_behavior,
+                          shared.share_group.clone())
+    }
+
+    unsafe fn new_impl<B>(
+        backend: B,
+        check_current_context: bool,
+        callback_behavior: DebugCallbackBehavior,
+        share_group: Rc<RefCell<Vec<Weak<Context>>>>,
+    ) -> Result<Rc<Context>, ContextCreationError>
         where B: Backend + 'static
     {
         backend.make_current();
@@ -166,11 +331,13 @@ impl Context {
         let gl = gl::Gl::load_with(|symbol| backend.get_proc_address(symbol) as *const _);
         let gl_state: RefCell<GlState> = RefCell::new(Default::default());
 
-        let version = version::get_gl_version(&gl);
-        let extensions = extensions::get_extensions(&gl, &version);
+        let version = try!(version::get_gl_version(&gl));
+        let extensions = try!(extensions::get_extensions(&gl, &version));
         try!(check_gl_compatibility(&version, &extensions));
 
-        let capabilities = capabilities::get_capabilities(&gl, &version, &extensions);
+        let attributes = backend.get_context_attributes();
+        let capabilities = try!(capabilities::get_capabilities(&gl, &version, &extensions,
+                                                               &attributes));
         let report_debug_output_errors = Cell::new(true);
 
         let vertex_array_objects = vertex_array_object::VertexAttributesSystem::new();
@@ -182,6 +349,7 @@ impl Context {
         });
         let resident_texture_handles = RefCell::new(Vec::new());
         let resident_image_handles = RefCell::new(Vec::new());
+        let query_objects_pool = RefCell::new(Vec::new());
 
         let (debug_callback, synchronous) = match callback_behavior {
             DebugCallbackBehavior::Ignore => (None, false),
@@ -211,8 +379,30 @@ impl Context {
             samplers: samplers,
             resident_texture_handles: resident_texture_handles,
             resident_image_handles: resident_image_handles,
+            query_objects_pool: query_objects_pool,
+            program_cache: ProgramCache::new(),
+            pixel_buffers_pool: RefCell::new(Vec::new()),
+            live_objects: LiveObjects::new(),
+            shared_uniform_block_bindings: RefCell::new(HashMap::with_hasher(Default::default())),
+            shared_shader_storage_block_bindings: RefCell::new(HashMap::with_hasher(Default::default())),
+            swap_interval: Cell::new(None),
+            shader_library: program::ShaderLibrary::new(),
+            default_framebuffer_srgb: Cell::new(None),
+            max_frames_in_flight: Cell::new(None),
+            frame_fences: RefCell::new(VecDeque::new()),
+            error_check_policy: Cell::new(Default::default()),
+            swallowed_errors: Cell::new(0),
+            default_format_fallback_policy: Cell::new(Default::default()),
+            format_fallback_policies: RefCell::new(HashMap::with_hasher(Default::default())),
+            share_group: share_group,
         });
 
+        {
+            let mut group = context.share_group.borrow_mut();
+            group.retain(|c| c.upgrade().is_some());
+            group.push(Rc::downgrade(&context));
+        }
+
         if context.debug_callback.is_some() {
             init_debug_callback(&context, synchronous);
         }
@@ -220,9 +410,16 @@ impl Context {
         // making sure that an error wasn't triggered during initialization
         {
             let mut ctxt = context.make_current();
-            if ::get_gl_error(&mut ctxt).is_some() {
-                println!("glium has triggered an OpenGL error during initialization. Please report \
-                          this error: https://github.com/tomaka/glium/issues");
+            match ::get_gl_error(&mut ctxt) {
+                None => (),
+                // the context can't be used at all after these errors
+                Some(err @ "GL_OUT_OF_MEMORY") | Some(err @ "GL_CONTEXT_LOST") => {
+                    return Err(ContextCreationError::GlError(err));
+                },
+                Some(_) => {
+                    println!("glium has triggered an OpenGL error during initialization. Please \
+                              report this error: https://github.com/tomaka/glium/issues");
+                },
             }
             /*assert!(::get_gl_error(&mut ctxt).is_none(),
                     "glium has triggered an OpenGL error during initialization. Please report \
@@ -238,18 +435,37 @@ impl Context {
         self.backend.borrow().get_framebuffer_dimensions()
     }
 
+    /// Calls `get_proc_address` on the backend object stored by this context.
+    ///
+    /// Depending on the backend, this can also return platform functions that aren't part of
+    /// OpenGL, like EGL extension functions.
+    #[inline]
+    pub unsafe fn get_proc_address(&self, symbol: &str) -> *const raw::c_void {
+        self.backend.borrow().get_proc_address(symbol)
+    }
+
+    /// Returns true if the objects of this context can be used with `other`.
+    ///
+    /// This is the case if both are the same context, or if one was built with
+    /// `Context::new_shared` from the other or from a context of the same group.
+    #[inline]
+    pub fn shares_objects_with(&self, other: &Context) -> bool {
+        &*self.share_group as *const _ == &*other.share_group as *const _
+    }
+
     /// Changes the OpenGL context associated with this context.
     ///
     /// The new context **must** have lists shared with the old one.
     pub unsafe fn rebuild<B>(&self, new_backend: B) -> Result<(), IncompatibleOpenGl>
         where B: Backend + 'static
     {
-        // framebuffer objects and vertex array objects aren't shared,
+        // framebuffer objects, vertex array objects and query objects aren't shared,
         // so we have to destroy them
         {
             let mut ctxt = self.make_current();
             fbo::FramebuffersContainer::purge_all(&mut ctxt);
             vertex_array_object::VertexAttributesSystem::purge_all(&mut ctxt);
+            destroy_query_objects_pool(&mut ctxt);
         }
 
         new_backend.make_current();
@@ -258,6 +474,10 @@ impl Context {
         // FIXME: verify version, capabilities and extensions
         *self.backend.borrow_mut() = Box::new(new_backend);
 
+        if let Some(interval) = self.swap_interval.get() {
+            let _ = self.backend.borrow().set_swap_interval(interval);
+        }
+
         // making textures resident
         let textures = self.resident_texture_handles.borrow();
         for &texture in textures.iter() {
@@ -274,7 +494,23 @@ impl Context {
     }
 
     /// Swaps the buffers in the backend.
+    #[inline]
     pub fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        self.swap_buffers_impl(None)
+    }
+
+    /// Swaps the buffers in the backend, and tells the windowing system that only the given
+    /// regions of the default framebuffer have changed since the previous frame.
+    ///
+    /// This is only a hint that allows the compositor to save work, and is ignored if the
+    /// backend doesn't support it. The whole framebuffer must still contain the correct image.
+    /// Passing an empty list means that the whole framebuffer has changed.
+    #[inline]
+    pub fn swap_buffers_with_damage(&self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.swap_buffers_impl(Some(damage))
+    }
+
+    fn swap_buffers_impl(&self, damage: Option<&[Rect]>) -> Result<(), SwapBuffersError> {
         if self.state.borrow().lost_context {
             return Err(SwapBuffersError::ContextLost);
         }
@@ -313,13 +549,166 @@ impl Context {
         }
 
         // swapping
-        let err = backend.swap_buffers();
+        let err = match damage {
+            Some(damage) => backend.swap_buffers_with_damage(damage),
+            None => backend.swap_buffers(),
+        };
         if let Err(SwapBuffersError::ContextLost) = err {
             self.state.borrow_mut().lost_context = true;
         }
+
+        if err.is_ok() {
+            let mut ctxt = self.make_current();
+            check_gl_error(&mut ctxt, "Swapping buffers");
+        }
+
+        if let (Ok(()), Some(max)) = (err, self.max_frames_in_flight.get()) {
+            let mut ctxt = self.make_current();
+            let mut fences = self.frame_fences.borrow_mut();
+
+            // the fence is signaled when the GPU has finished the commands of the frame
+            if let Ok(fence) = unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+                fences.push_back(fence);
+            }
+
+            while fences.len() > max as usize {
+                let fence = fences.pop_front().unwrap();
+                unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt); }
+            }
+        }
+
+        self.live_objects.next_frame();
         err
     }
 
+    /// Limits the number of frames that can be in flight, in other words the number of frames
+    /// that have been swapped but that the GPU hasn't finished rendering yet.
+    ///
+    /// After the buffers are swapped, glium inserts a fence in the commands queue and blocks
+    /// until there are no more than `max` frames in flight. A lower value reduces the latency
+    /// between the user's input and the image on the screen, while a higher value allows the CPU
+    /// and the GPU to work in parallel. `Some(0)` waits for each frame to be finished before
+    /// `swap_buffers` returns.
+    ///
+    /// Passing `None`, which is the default, lets the driver decide how many frames it queues.
+    ///
+    /// Returns an error if the backend doesn't support fences.
+    pub fn set_max_frames_in_flight(&self, max: Option<u32>)
+                                    -> Result<(), sync::SyncNotSupportedError>
+    {
+        let mut ctxt = self.make_current();
+
+        if max.is_some() && !sync::is_sync_supported(&ctxt) {
+            return Err(sync::SyncNotSupportedError);
+        }
+
+        self.max_frames_in_flight.set(max);
+
+        if max.is_none() {
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence); }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the library of GLSL modules that the shaders built with this context can
+    /// include.
+    #[inline]
+    pub fn get_shader_library(&self) -> &program::ShaderLibrary {
+        &self.shader_library
+    }
+
+    /// Returns true if the default framebuffer is in sRGB.
+    ///
+    /// When drawing on an sRGB default framebuffer, glium enables `GL_FRAMEBUFFER_SRGB` so that
+    /// the linear output of the fragment shader is converted to sRGB, unless the program was
+    /// created with `outputs_srgb` set to true. When the default framebuffer isn't in sRGB, the
+    /// output is written as it is.
+    ///
+    /// The value is detected when the context is created, unless it has been overridden with
+    /// `set_default_framebuffer_srgb`.
+    #[inline]
+    pub fn is_default_framebuffer_srgb(&self) -> bool {
+        self.default_framebuffer_srgb.get().unwrap_or(self.capabilities.srgb)
+    }
+
+    /// Overrides whether glium considers the default framebuffer to be in sRGB. Passing `None`
+    /// restores the value that was detected when the context was created.
+    ///
+    /// Some drivers create an sRGB default framebuffer even when it wasn't requested, or report
+    /// the wrong encoding. With glutin, the encoding can be requested or rejected at creation
+    /// with `ContextBuilder::with_srgb`.
+    ///
+    /// Passing `Some(false)` makes glium write the output of the fragment shader in the default
+    /// framebuffer without any conversion, even if the framebuffer is actually in sRGB.
+    #[inline]
+    pub fn set_default_framebuffer_srgb(&self, srgb: Option<bool>) {
+        self.default_framebuffer_srgb.set(srgb);
+    }
+
+    /// Returns the maximum number of frames in flight set with `set_max_frames_in_flight`.
+    #[inline]
+    pub fn get_max_frames_in_flight(&self) -> Option<u32> {
+        self.max_frames_in_flight.get()
+    }
+
+    /// Changes when glium calls `glGetError` to detect the errors triggered by its commands.
+    ///
+    /// The default is `ErrorCheckPolicy::Never`. Applications that ship with `Always` or
+    /// `Panic` pay the cost of a `glGetError` after each draw command.
+    #[inline]
+    pub fn set_error_check_policy(&self, policy: ErrorCheckPolicy) {
+        self.error_check_policy.set(policy);
+    }
+
+    /// Returns the policy set with `set_error_check_policy`.
+    #[inline]
+    pub fn get_error_check_policy(&self) -> ErrorCheckPolicy {
+        self.error_check_policy.get()
+    }
+
+    /// Returns the number of errors that were detected with `glGetError` and then ignored
+    /// because of the `ErrorCheckPolicy`.
+    ///
+    /// These errors are not reported again by `assert_no_error`.
+    #[inline]
+    pub fn get_swallowed_errors_count(&self) -> u64 {
+        self.swallowed_errors.get()
+    }
+
+    /// Changes what happens when a texture is created with a compressed format that the backend
+    /// doesn't support, for all the formats that don't have their own policy.
+    ///
+    /// The default is `FormatFallbackPolicy::Error`.
+    #[inline]
+    pub fn set_default_format_fallback_policy(&self, policy: texture::FormatFallbackPolicy) {
+        self.default_format_fallback_policy.set(policy);
+    }
+
+    /// Changes what happens when a texture is created with `format` and the backend doesn't
+    /// support it. This overrides the default policy for this format.
+    ///
+    /// Only compressed formats can fall back to another format. The policy of the other
+    /// formats is ignored.
+    #[inline]
+    pub fn set_format_fallback_policy(&self, format: texture::TextureFormat,
+                                      policy: texture::FormatFallbackPolicy)
+    {
+        self.format_fallback_policies.borrow_mut().insert(format, policy);
+    }
+
+    /// Returns the policy that is used when a texture is created with `format` and the backend
+    /// doesn't support it.
+    #[inline]
+    pub fn get_format_fallback_policy(&self, format: texture::TextureFormat)
+                                      -> texture::FormatFallbackPolicy
+    {
+        self.format_fallback_policies.borrow().get(&format).cloned()
+            .unwrap_or(self.default_format_fallback_policy.get())
+    }
+
     /// DEPRECATED. Use `get_opengl_version` instead.
     #[inline]
     pub fn get_version(&self) -> &Version {
@@ -477,6 +866,135 @@ impl Context {
         }
     }
 
+    /// Destroys all the objects that glium keeps in its internal caches, such as vertex array
+    /// objects, framebuffer objects, pooled pixel buffers and cached programs.
+    ///
+    /// The objects will be created again when they are needed. This can be useful to free
+    /// resources after a loading screen, or before a period where many buffers are going to be
+    /// created and destroyed.
+    pub fn purge_caches(&self) {
+        {
+            let mut ctxt = self.make_current();
+            fbo::FramebuffersContainer::purge_all(&mut ctxt);
+            vertex_array_object::VertexAttributesSystem::purge_all(&mut ctxt);
+            unsafe { destroy_pixel_buffers_pool(&mut ctxt) };
+        }
+
+        // destroying programs requires calling `make_current`
+        self.program_cache.purge();
+    }
+
+    /// Returns the binding point reserved for the uniform blocks named `name`, reserving one if
+    /// this is the first time this name is requested.
+    ///
+    /// Assigning this binding point to the block of each program with
+    /// `Program::set_uniform_block_binding` allows a single uniform buffer to stay bound while
+    /// switching between these programs. Reserved binding points are taken from the end of the
+    /// range supported by the backend, while the binding points that glium chooses at each draw
+    /// call start from `0`.
+    ///
+    /// Returns `None` if all the binding points have been reserved.
+    pub fn reserve_uniform_block_binding(&self, name: &str) -> Option<u32> {
+        reserve_block_binding(&mut self.shared_uniform_block_bindings.borrow_mut(), name,
+                              self.capabilities.max_indexed_uniform_buffer)
+    }
+
+    /// Same as `reserve_uniform_block_binding`, but for shader storage blocks.
+    pub fn reserve_shader_storage_block_binding(&self, name: &str) -> Option<u32> {
+        reserve_block_binding(&mut self.shared_shader_storage_block_bindings.borrow_mut(), name,
+                              self.capabilities.max_indexed_shader_storage_buffer)
+    }
+
+    /// Returns the number of OpenGL calls that glium has made to bind resources since the
+    /// context was created or since the last call to `reset_state_statistics`.
+    ///
+    /// With OpenGL 4.4 or `GL_ARB_multi_bind`, the textures, samplers and uniform or shader
+    /// storage buffers that a draw call uses are bound with a few calls to `glBindTextures`,
+    /// `glBindSamplers` and `glBindBuffersRange` instead of one call per resource.
+    #[inline]
+    pub fn get_state_statistics(&self) -> StateStatistics {
+        self.state.borrow().statistics
+    }
+
+    /// Resets the counters returned by `get_state_statistics`.
+    #[inline]
+    pub fn reset_state_statistics(&self) {
+        self.state.borrow_mut().statistics = Default::default();
+    }
+
+    /// Returns statistics about the cache of programs.
+    #[inline]
+    pub fn get_program_cache_statistics(&self) -> CacheStatistics {
+        self.program_cache.get_statistics()
+    }
+
+    /// Changes the maximum number of programs that are kept in the cache of programs.
+    ///
+    /// When the cache is enabled, building a program from source code that is identical to a
+    /// program built earlier returns the same OpenGL program instead of compiling it again.
+    /// When the limit is reached, the least recently used programs are removed from the cache.
+    ///
+    /// The cache is disabled by default, which corresponds to a capacity of `0`.
+    ///
+    /// Cached programs don't keep the context alive, and are destroyed with it.
+    #[inline]
+    pub fn set_program_cache_capacity(&self, capacity: usize) {
+        self.program_cache.set_capacity(capacity);
+    }
+
+    /// Returns statistics about the cache of vertex array objects.
+    ///
+    /// A VAO is created for each combination of vertex buffers, index buffer and layout of the
+    /// attributes of the program that is used for drawing. Programs whose attributes have the
+    /// same names, types and locations share their VAOs.
+    #[inline]
+    pub fn get_vertex_array_objects_cache_statistics(&self) -> CacheStatistics {
+        let (entries, hits, misses) = self.vertex_array_objects.get_statistics();
+
+        CacheStatistics {
+            entries: entries,
+            capacity: self.vertex_array_objects.get_capacity(),
+            hits: hits,
+            misses: misses,
+        }
+    }
+
+    /// Changes the maximum number of vertex array objects that are kept in the cache.
+    ///
+    /// When the limit is reached, the least recently used VAOs are destroyed. If the new
+    /// capacity is lower than the current number of VAOs, the extra ones are destroyed
+    /// immediately.
+    pub fn set_vertex_array_objects_cache_capacity(&self, capacity: usize) {
+        let mut ctxt = self.make_current();
+        vertex_array_object::VertexAttributesSystem::set_capacity(&mut ctxt, capacity);
+    }
+
+    /// Returns the list of the buffers, textures, render buffers and programs created with
+    /// this context that are still alive, in order to track down leaks and large video memory
+    /// usage.
+    ///
+    /// The frames are counted with the number of calls to `swap_buffers`. Objects that are kept
+    /// in the internal caches and pools of glium aren't reported, unless they are also in use.
+    ///
+    /// This function queries the backend for the size of each texture and render buffer, and
+    /// is therefore slow.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// let dump = display.debug_dump();
+    /// println!("{} bytes in {} objects", dump.total_size(), dump.objects.len());
+    ///
+    /// for object in dump.unused_since(60) {
+    ///     println!("{:?} #{} hasn't been used for a while", object.kind, object.id);
+    /// }
+    /// ```
+    pub fn debug_dump(&self) -> debug::DebugDump {
+        let mut ctxt = self.make_current();
+        LiveObjects::dump(&mut ctxt)
+    }
+
     /// Returns an estimate of the amount of video memory available in bytes.
     ///
     /// Returns `None` if no estimate is available.
@@ -484,7 +1002,7 @@ impl Context {
         unsafe {
             let ctxt = self.make_current();
 
-            let mut value: [gl::types::GLint; 4] = mem::uninitialized();
+            let mut value: [gl::types::GLint; 4] = [0; 4];
 
             if ctxt.extensions.gl_nvx_gpu_memory_info {
                 ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX,
@@ -524,12 +1042,74 @@ impl Context {
         let dimensions = self.get_framebuffer_dimensions();
         let rect = ::Rect { left: 0, bottom: 0, width: dimensions.0, height: dimensions.1 };
 
+        // restoring the read buffer chosen with `set_default_framebuffer_read_buffer`
+        let previous_read_buffer = ctxt.state.default_framebuffer_read;
+
         let mut data = Vec::with_capacity(0);
         ops::read(&mut ctxt, ops::Source::DefaultFramebuffer(gl::FRONT_LEFT), &rect,
                           &mut data, false);
+
+        if let Some(previous_read_buffer) = previous_read_buffer {
+            fbo::FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt,
+                                                                         previous_read_buffer);
+        }
+
         T::from_raw(Cow::Owned(data), dimensions.0, dimensions.1)
     }
 
+    /// Chooses the buffer of the default framebuffer that is drawn to by the `Frame`. `None`
+    /// discards the color output of drawing commands.
+    ///
+    /// By default the backbuffer is drawn to. Contexts that don't have a backbuffer must choose
+    /// `DefaultFramebufferAttachment::Front`.
+    ///
+    /// OpenGL ES only allows choosing `Back` or `None`, and OpenGL ES 2 doesn't allow choosing
+    /// anything.
+    pub fn set_default_framebuffer_draw_buffer(&self, buffer: Option<DefaultFramebufferAttachment>)
+                                               -> Result<(), BufferSelectionError>
+    {
+        let buffer = try!(self.default_framebuffer_buffer(buffer));
+        let mut ctxt = self.make_current();
+        fbo::FramebuffersContainer::set_default_framebuffer_draw_buffer(&mut ctxt, buffer);
+        Ok(())
+    }
+
+    /// Chooses the buffer of the default framebuffer that blitting and reading operations read
+    /// from. `None` means that there is nothing to read.
+    ///
+    /// Reading from the frontbuffer after drawing to the backbuffer makes it possible to access
+    /// the previous frame without copying it to a texture.
+    ///
+    /// OpenGL ES only allows choosing `Back` or `None`, and OpenGL ES 2 doesn't allow choosing
+    /// anything.
+    pub fn set_default_framebuffer_read_buffer(&self, buffer: Option<DefaultFramebufferAttachment>)
+                                               -> Result<(), BufferSelectionError>
+    {
+        let buffer = try!(self.default_framebuffer_buffer(buffer));
+        let mut ctxt = self.make_current();
+        fbo::FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt, buffer);
+        Ok(())
+    }
+
+    /// Checks that a buffer of the default framebuffer can be chosen, and returns its enum.
+    fn default_framebuffer_buffer(&self, buffer: Option<DefaultFramebufferAttachment>)
+                                  -> Result<gl::types::GLenum, BufferSelectionError>
+    {
+        if self.version >= Version(Api::Gl, 1, 0) {
+            return Ok(buffer.map(|b| b.to_glenum()).unwrap_or(gl::NONE));
+        }
+
+        if self.version < Version(Api::GlEs, 3, 0) {
+            return Err(BufferSelectionError::NotSupported);
+        }
+
+        match buffer {
+            None => Ok(gl::NONE),
+            Some(DefaultFramebufferAttachment::Back) => Ok(gl::BACK),
+            Some(_) => Err(BufferSelectionError::BufferNotSupported),
+        }
+    }
+
     /// Execute an arbitrary closure with the OpenGL context active. Useful if another
     /// component needs to directly manipulate OpenGL state.
     ///
@@ -592,6 +1172,66 @@ impl Context {
         unsafe { ctxt.gl.Flush(); }
     }
 
+    /// Changes the number of vertical blanks to wait for when swapping buffers.
+    ///
+    /// This overrides the vsync setting that was chosen when the context was created. The
+    /// interval is kept when the context is rebuilt.
+    ///
+    /// Returns an error if the backend doesn't allow changing the swap interval after creation,
+    /// or if `SwapInterval::AdaptiveWait` is requested but adaptive vsync isn't supported.
+    pub fn set_swap_interval(&self, interval: SwapInterval) -> Result<(), SwapIntervalError> {
+        let _ctxt = self.make_current();
+        try!(self.backend.borrow().set_swap_interval(interval));
+        self.swap_interval.set(Some(interval));
+        Ok(())
+    }
+
+    /// Returns true if RenderDoc is injected in the process.
+    ///
+    /// Only available with the `renderdoc` feature.
+    #[cfg(feature = "renderdoc")]
+    #[inline]
+    pub fn is_renderdoc_available(&self) -> bool {
+        ::renderdoc::RenderDoc::get().is_some()
+    }
+
+    /// Asks RenderDoc to capture the next frame that is presented. Does nothing if RenderDoc
+    /// is not available.
+    ///
+    /// Only available with the `renderdoc` feature.
+    #[cfg(feature = "renderdoc")]
+    pub fn trigger_capture(&self) {
+        if let Some(renderdoc) = ::renderdoc::RenderDoc::get() {
+            renderdoc.trigger_capture();
+        }
+    }
+
+    /// Captures with RenderDoc all the commands that are executed by the closure. The closure
+    /// is simply called if RenderDoc is not available.
+    ///
+    /// Only available with the `renderdoc` feature.
+    #[cfg(feature = "renderdoc")]
+    pub fn capture<F, R>(&self, f: F) -> R where F: FnOnce() -> R {
+        let renderdoc = match ::renderdoc::RenderDoc::get() {
+            Some(r) => r,
+            None => return f(),
+        };
+
+        {
+            let _ctxt = self.make_current();
+            renderdoc.start_frame_capture();
+        }
+
+        let result = f();
+
+        {
+            let _ctxt = self.make_current();
+            renderdoc.end_frame_capture();
+        }
+
+        result
+    }
+
     /// Inserts a debugging string in the commands queue. If you use an OpenGL debugger, you will
     /// be able to see that string.
     ///
@@ -632,6 +1272,11 @@ impl Context {
 }
 
 impl ContextExt for Context {
+    #[inline]
+    fn get_program_cache(&self) -> &ProgramCache {
+        &self.program_cache
+    }
+
     #[inline]
     fn set_report_debug_output_errors(&self, value: bool) {
         self.report_debug_output_errors.set(value);
@@ -652,12 +1297,18 @@ impl ContextExt for Context {
             version: &self.version,
             extensions: &self.extensions,
             capabilities: &self.capabilities,
+            default_framebuffer_srgb: self.is_default_framebuffer_srgb(),
             report_debug_output_errors: &self.report_debug_output_errors,
+            error_check_policy: self.error_check_policy.get(),
+            swallowed_errors: &self.swallowed_errors,
             vertex_array_objects: &self.vertex_array_objects,
             framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            query_objects_pool: self.query_objects_pool.borrow_mut(),
+            pixel_buffers_pool: self.pixel_buffers_pool.borrow_mut(),
+            live_objects: &self.live_objects,
             marker: PhantomData,
         }
     }
@@ -666,6 +1317,64 @@ impl ContextExt for Context {
     fn capabilities(&self) -> &Capabilities {
         &self.capabilities
     }
+
+    fn purge_shared_object(&self, kind: debug::ObjectKind, id: gl::types::GLuint) {
+        let others = self.share_group.borrow().iter()
+                         .filter_map(|c| c.upgrade())
+                         .filter(|c| &**c as *const Context != self as *const Context)
+                         .collect::<Vec<_>>();
+
+        if others.is_empty() {
+            return;
+        }
+
+        for other in others.iter() {
+            // the cached VAOs and FBOs have to be destroyed in the context that owns them
+            unsafe {
+                let backend = other.backend.borrow();
+                if !backend.is_current() {
+                    backend.make_current();
+                }
+            }
+
+            let mut ctxt = other.make_current();
+
+            match kind {
+                debug::ObjectKind::Buffer => {
+                    vertex_array_object::VertexAttributesSystem::purge_buffer(&mut ctxt, id);
+                    ctxt.state.forget_buffer(id);
+                },
+                debug::ObjectKind::Texture => {
+                    fbo::FramebuffersContainer::purge_texture(&mut ctxt, id);
+                    for tex_unit in ctxt.state.texture_units.iter_mut() {
+                        if tex_unit.texture == id {
+                            tex_unit.texture = 0;
+                        }
+                    }
+                },
+                debug::ObjectKind::RenderBuffer => {
+                    fbo::FramebuffersContainer::purge_renderbuffer(&mut ctxt, id);
+                    if ctxt.state.renderbuffer == id {
+                        ctxt.state.renderbuffer = 0;
+                    }
+                },
+                debug::ObjectKind::Program => {
+                    // VAOs don't depend on the program itself but on the locations of its
+                    // attributes, so they stay valid and are evicted when no longer used
+                    if ctxt.state.program == Handle::Id(id) {
+                        ctxt.state.program = Handle::Id(0);
+                    }
+                },
+            }
+        }
+
+        unsafe {
+            let backend = self.backend.borrow();
+            if !backend.is_current() {
+                backend.make_current();
+            }
+        }
+    }
 }
 
 impl CapabilitiesSource for Context {
@@ -703,15 +1412,25 @@ impl Drop for Context {
                 version: &self.version,
                 extensions: &self.extensions,
                 capabilities: &self.capabilities,
+                default_framebuffer_srgb: self.is_default_framebuffer_srgb(),
                 report_debug_output_errors: &self.report_debug_output_errors,
+                error_check_policy: self.error_check_policy.get(),
+                swallowed_errors: &self.swallowed_errors,
                 vertex_array_objects: &self.vertex_array_objects,
                 framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                query_objects_pool: self.query_objects_pool.borrow_mut(),
+                pixel_buffers_pool: self.pixel_buffers_pool.borrow_mut(),
+                live_objects: &self.live_objects,
                 marker: PhantomData,
             };
 
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                sync::destroy_linear_sync_fence(&mut ctxt, fence);
+            }
+
             fbo::FramebuffersContainer::cleanup(&mut ctxt);
             vertex_array_object::VertexAttributesSystem::cleanup(&mut ctxt);
 
@@ -719,13 +1438,18 @@ impl Drop for Context {
                 s.destroy(&mut ctxt);
             }
 
+            destroy_query_objects_pool(&mut ctxt);
+            destroy_pixel_buffers_pool(&mut ctxt);
+
+            // the cached programs don't keep the context alive and can't access it anymore
+            self.program_cache.cleanup(&mut ctxt);
+
             // disabling callback
             if ctxt.state.enabled_debug_output != Some(false) {
                 if ctxt.version >= &Version(Api::Gl, 4,5) || ctxt.extensions.gl_khr_debug {
                     ctxt.gl.Disable(gl::DEBUG_OUTPUT);
                 } else if ctxt.extensions.gl_arb_debug_output {
-                    ctxt.gl.DebugMessageCallbackARB(mem::transmute(0usize),
-                                                    ptr::null());
+                    ctxt.gl.DebugMessageCallbackARB(None, ptr::null());
                 }
 
                 ctxt.state.enabled_debug_output = Some(false);
@@ -752,44 +1476,116 @@ impl<'a> CapabilitiesSource for CommandContext<'a> {
     }
 }
 
-/// Checks whether the backend supports glium. Returns an `Err` if it doesn't.
+/// Returns the binding point reserved for `name`, reserving the highest free one if necessary.
+fn reserve_block_binding(bindings: &mut HashMap<String, u32, BuildHasherDefault<FnvHasher>>,
+                         name: &str, max: gl::types::GLint) -> Option<u32>
+{
+    if let Some(binding) = bindings.get(name) {
+        return Some(*binding);
+    }
+
+    if bindings.len() >= max as usize {
+        return None;
+    }
+
+    let binding = max as u32 - 1 - bindings.len() as u32;
+    bindings.insert(name.to_owned(), binding);
+    Some(binding)
+}
+
+/// Destroys all the query objects that are waiting in the pool.
+unsafe fn destroy_query_objects_pool(ctxt: &mut CommandContext) {
+    for (_, id) in mem::replace(&mut *ctxt.query_objects_pool, Vec::new()) {
+        if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+           ctxt.version >= &Version(Api::GlEs, 3, 0)
+        {
+            ctxt.gl.DeleteQueries(1, [id].as_ptr());
+
+        } else if ctxt.extensions.gl_arb_occlusion_query {
+            ctxt.gl.DeleteQueriesARB(1, [id].as_ptr());
+
+        } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            ctxt.gl.DeleteQueriesEXT(1, [id].as_ptr());
+
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+/// Destroys all the pixel buffers that are waiting in the pool.
+unsafe fn destroy_pixel_buffers_pool(ctxt: &mut CommandContext) {
+    for buffer in mem::replace(&mut *ctxt.pixel_buffers_pool, Vec::new()) {
+        if let Some(fence) = buffer.fence {
+            sync::destroy_linear_sync_fence(ctxt, fence);
+        }
+
+        if ctxt.state.pixel_pack_buffer_binding == buffer.id {
+            ctxt.state.pixel_pack_buffer_binding = 0;
+        }
+
+        if ctxt.state.pixel_unpack_buffer_binding == buffer.id {
+            ctxt.state.pixel_unpack_buffer_binding = 0;
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+           ctxt.version >= &Version(Api::GlEs, 2, 0)
+        {
+            ctxt.gl.DeleteBuffers(1, [buffer.id].as_ptr());
+        } else if ctxt.extensions.gl_arb_vertex_buffer_object {
+            ctxt.gl.DeleteBuffersARB(1, [buffer.id].as_ptr());
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+/// Checks whether the backend supports glium. Returns an `Err` containing the first missing
+/// extension if it doesn't.
 fn check_gl_compatibility(version: &Version, extensions: &ExtensionsList)
-    -> Result<(), IncompatibleOpenGl>
+    -> Result<(), ContextCreationError>
 {
-    let mut result = Vec::with_capacity(0);
+    // buffer objects
+    if !(version >= &Version(Api::Gl, 1, 5)) && !(version >= &Version(Api::GlEs, 2, 0)) {
+        if !extensions.gl_arb_vertex_buffer_object {
+            return Err(ContextCreationError::MissingExtension("GL_ARB_vertex_buffer_object"));
+        }
 
-    if !(version >= &Version(Api::Gl, 1, 5)) &&
-        !(version >= &Version(Api::GlEs, 2, 0)) &&
-        (!extensions.gl_arb_vertex_buffer_object || !extensions.gl_arb_map_buffer_range)
-    {
-        result.push("OpenGL implementation doesn't support buffer objects");
+        if !extensions.gl_arb_map_buffer_range {
+            return Err(ContextCreationError::MissingExtension("GL_ARB_map_buffer_range"));
+        }
     }
 
-    if !(version >= &Version(Api::Gl, 2, 0)) &&
-        !(version >= &Version(Api::GlEs, 2, 0)) &&
-        (!extensions.gl_arb_shader_objects ||
-            !extensions.gl_arb_vertex_shader || !extensions.gl_arb_fragment_shader)
-    {
-        result.push("OpenGL implementation doesn't support vertex/fragment shaders");
+    // vertex and fragment shaders
+    if !(version >= &Version(Api::Gl, 2, 0)) && !(version >= &Version(Api::GlEs, 2, 0)) {
+        if !extensions.gl_arb_shader_objects {
+            return Err(ContextCreationError::MissingExtension("GL_ARB_shader_objects"));
+        }
+
+        if !extensions.gl_arb_vertex_shader {
+            return Err(ContextCreationError::MissingExtension("GL_ARB_vertex_shader"));
+        }
+
+        if !extensions.gl_arb_fragment_shader {
+            return Err(ContextCreationError::MissingExtension("GL_ARB_fragment_shader"));
+        }
     }
 
+    // framebuffer objects
     if !extensions.gl_ext_framebuffer_object && !(version >= &Version(Api::Gl, 3, 0)) &&
         !(version >= &Version(Api::GlEs, 2, 0)) && !extensions.gl_arb_framebuffer_object
     {
-        result.push("OpenGL implementation doesn't support framebuffers");
+        return Err(ContextCreationError::MissingExtension("GL_ARB_framebuffer_object"));
     }
 
+    // blitting framebuffers
     if !extensions.gl_ext_framebuffer_blit && !(version >= &Version(Api::Gl, 3, 0)) &&
         !(version >= &Version(Api::GlEs, 2, 0))
     {
-        result.push("OpenGL implementation doesn't support blitting framebuffers");
+        return Err(ContextCreationError::MissingExtension("GL_EXT_framebuffer_blit"));
     }
 
-    if result.len() == 0 {
-        Ok(())
-    } else {
-        Err(IncompatibleOpenGl(result.join("\n")))
-    }
+    Ok(())
 }
 
 /// Describes the behavior that the debug output should have.
@@ -824,6 +1620,54 @@ impl Default for DebugCallbackBehavior {
     }
 }
 
+/// Describes when glium calls `glGetError` to detect the errors triggered by its commands.
+///
+/// Each call to `glGetError` can force the driver to synchronize with its internal threads,
+/// which costs CPU time. The debug output (see `DebugCallbackBehavior`) reports errors without
+/// this cost when `GL_KHR_debug` or a similar extension is available.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorCheckPolicy {
+    /// Call `glGetError` after each draw command and each buffer swap. The errors are counted
+    /// and then ignored. The count is returned by `Context::get_swallowed_errors_count`.
+    Always,
+
+    /// Same as `Always` if debug assertions are enabled, and same as `Never` otherwise.
+    DebugOnly,
+
+    /// Only call `glGetError` when glium needs to know why something failed. This is the default.
+    Never,
+
+    /// Call `glGetError` after each draw command and each buffer swap, and panic if an error
+    /// is detected.
+    Panic,
+}
+
+impl Default for ErrorCheckPolicy {
+    #[inline]
+    fn default() -> ErrorCheckPolicy {
+        ErrorCheckPolicy::Never
+    }
+}
+
+/// Calls `glGetError` if the error check policy of the context requires it, after `operation`
+/// has been executed.
+pub fn check_gl_error(ctxt: &mut CommandContext, operation: &str) {
+    let panic = match ctxt.error_check_policy {
+        ErrorCheckPolicy::Always => false,
+        ErrorCheckPolicy::DebugOnly if cfg!(debug_assertions) => false,
+        ErrorCheckPolicy::DebugOnly | ErrorCheckPolicy::Never => return,
+        ErrorCheckPolicy::Panic => true,
+    };
+
+    if let Some(error) = ::get_gl_error(ctxt) {
+        if panic {
+            panic!("{} triggered {}", operation, error);
+        }
+
+        ctxt.swallowed_errors.set(ctxt.swallowed_errors.get() + 1);
+    }
+}
+
 /// The callback corresponding to `DebugMessageOnError`.
 fn default_debug_callback(_: debug::Source, ty: debug::MessageType, severity: debug::Severity,
                           _: u32, report_debug_output_errors: bool, message: &str)
@@ -962,7 +1806,7 @@ fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
                ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
             {
-                ctxt.gl.DebugMessageCallback(callback_wrapper, context_raw_ptr.0
+                ctxt.gl.DebugMessageCallback(Some(callback_wrapper), context_raw_ptr.0
                                                                  as *const _);
                 ctxt.gl.DebugMessageControl(gl::DONT_CARE, gl::DONT_CARE, gl::DONT_CARE, 0,
                                             ptr::null(), gl::TRUE);
@@ -975,7 +1819,7 @@ fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
             } else if ctxt.version >= &Version(Api::GlEs, 2, 0) &&
                       ctxt.extensions.gl_khr_debug
             {
-                ctxt.gl.DebugMessageCallbackKHR(callback_wrapper, context_raw_ptr.0
+                ctxt.gl.DebugMessageCallbackKHR(Some(callback_wrapper), context_raw_ptr.0
                                                                  as *const _);
                 ctxt.gl.DebugMessageControlKHR(gl::DONT_CARE, gl::DONT_CARE, gl::DONT_CARE, 0,
                                                ptr::null(), gl::TRUE);
@@ -986,7 +1830,7 @@ fn init_debug_callback(context: &Rc<Context>, synchronous: bool) {
                 }
 
             } else {
-                ctxt.gl.DebugMessageCallbackARB(callback_wrapper, context_raw_ptr.0
+                ctxt.gl.DebugMessageCallbackARB(Some(callback_wrapper), context_raw_ptr.0
                                                                     as *const _);
                 ctxt.gl.DebugMessageControlARB(gl::DONT_CARE, gl::DONT_CARE, gl::DONT_CARE,
                                                0, ptr::null(), gl::TRUE);
@@ -0,0 +1,123 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+
+use fnv::FnvHasher;
+
+use program::{CachedProgram, ProgramCacheKey};
+
+use context::CacheStatistics;
+use context::CommandContext;
+
+/// Keeps programs alive so that building a program with the same source code twice returns
+/// the same OpenGL program.
+///
+/// The key of the cache contains the source code and the other parameters of the program. The
+/// programs don't keep the context alive, so the context must call `cleanup` when it is
+/// destroyed.
+pub struct ProgramCache {
+    // each program is associated to the value of `clock` when it was last used
+    programs: RefCell<HashMap<ProgramCacheKey, (CachedProgram, u64),
+                              BuildHasherDefault<FnvHasher>>>,
+
+    // maximum number of programs in the cache ; `0` disables the cache
+    capacity: Cell<usize>,
+
+    // incremented each time a program is looked up
+    clock: Cell<u64>,
+
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+}
+
+impl ProgramCache {
+    /// Builds a new empty cache. The cache is disabled by default.
+    #[inline]
+    pub fn new() -> ProgramCache {
+        ProgramCache {
+            programs: RefCell::new(HashMap::with_hasher(Default::default())),
+            capacity: Cell::new(0),
+            clock: Cell::new(0),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    /// Returns true if the cache is enabled.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.capacity.get() != 0
+    }
+
+    /// Looks up a program in the cache.
+    pub fn get(&self, key: &ProgramCacheKey) -> Option<CachedProgram> {
+        let now = self.clock.get() + 1;
+        self.clock.set(now);
+
+        match self.programs.borrow_mut().get_mut(key) {
+            Some(&mut (ref program, ref mut last_used)) => {
+                *last_used = now;
+                self.hits.set(self.hits.get() + 1);
+                Some(program.clone())
+            },
+            None => {
+                self.misses.set(self.misses.get() + 1);
+                None
+            },
+        }
+    }
+
+    /// Inserts a program in the cache, destroying the least recently used programs if the cache
+    /// is full.
+    pub fn insert(&self, key: ProgramCacheKey, program: CachedProgram) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut programs = self.programs.borrow_mut();
+        ProgramCache::evict(&mut programs, self.capacity.get() - 1);
+        programs.insert(key, (program, self.clock.get()));
+    }
+
+    /// Changes the maximum number of programs in the cache. `0` disables the cache.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.set(capacity);
+        ProgramCache::evict(&mut self.programs.borrow_mut(), capacity);
+    }
+
+    /// Removes all the programs from the cache.
+    pub fn purge(&self) {
+        self.programs.borrow_mut().clear();
+    }
+
+    /// Destroys all the programs of the cache. Must only be called when the context is
+    /// destroyed, as the programs can no longer access it.
+    pub fn cleanup(&self, ctxt: &mut CommandContext) {
+        for (_, (program, _)) in self.programs.borrow_mut().drain() {
+            program.destroy(ctxt);
+        }
+    }
+
+    /// Returns statistics about the cache.
+    #[inline]
+    pub fn get_statistics(&self) -> CacheStatistics {
+        CacheStatistics {
+            entries: self.programs.borrow().len(),
+            capacity: self.capacity.get(),
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+        }
+    }
+
+    /// Removes the least recently used programs until there are at most `max` programs left.
+    fn evict(programs: &mut HashMap<ProgramCacheKey, (CachedProgram, u64),
+                                    BuildHasherDefault<FnvHasher>>,
+             max: usize)
+    {
+        while programs.len() > max {
+            let key = programs.iter().min_by_key(|&(_, &(_, last_used))| last_used).unwrap()
+                              .0.clone();
+            programs.remove(&key);
+        }
+    }
+}
@@ -92,6 +92,10 @@ pub struct GlState {
     pub color_mask: (gl::types::GLboolean, gl::types::GLboolean,
                      gl::types::GLboolean, gl::types::GLboolean),
 
+    /// True if `glColorMaski` has been called since the latest call to `glColorMask`, in which
+    /// case `color_mask` doesn't apply to all the draw buffers.
+    pub color_mask_indexed: bool,
+
     /// The latest buffer bound to `GL_ARRAY_BUFFER`.
     pub array_buffer_binding: gl::types::GLuint,
 
@@ -119,6 +123,9 @@ pub struct GlState {
     /// The latest buffer bound to `GL_DRAW_INDIRECT_BUFFER`.
     pub draw_indirect_buffer_binding: gl::types::GLuint,
 
+    /// The latest buffer bound to `GL_PARAMETER_BUFFER`.
+    pub parameter_buffer_binding: gl::types::GLuint,
+
     /// The latest buffer bound to `GL_QUERY_BUFFER`.
     pub query_buffer_binding: gl::types::GLuint,
 
@@ -150,6 +157,10 @@ pub struct GlState {
     /// `None` means "unknown".
     pub default_framebuffer_read: Option<gl::types::GLenum>,
 
+    /// The latest value passed to `glDrawBuffer` or `glDrawBuffers` with the default
+    /// framebuffer. `None` means "unknown".
+    pub default_framebuffer_draw: Option<gl::types::GLenum>,
+
     /// The latest render buffer bound with `glBindRenderbuffer`.
     pub renderbuffer: gl::types::GLuint,
 
@@ -195,6 +206,11 @@ pub struct GlState {
     pub viewport: Option<(gl::types::GLint, gl::types::GLint,
                           gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// The latest values passed to `glViewportIndexedf` for the viewport 0. `None` means
+    /// unknown or that `glViewport` was called afterwards.
+    pub viewport_indexed: Option<(gl::types::GLfloat, gl::types::GLfloat,
+                                  gl::types::GLfloat, gl::types::GLfloat)>,
+
     /// The latest values passed to `glScissor`. `None` means unknown.
     pub scissor: Option<(gl::types::GLint, gl::types::GLint,
                          gl::types::GLsizei, gl::types::GLsizei)>,
@@ -229,6 +245,12 @@ pub struct GlState {
     /// The latest value passed to `glPatchParameter` with `GL_PATCH_VERTICES`.
     pub patch_patch_vertices: gl::types::GLint,
 
+    /// The latest value passed to `glPatchParameter` with `GL_PATCH_DEFAULT_OUTER_LEVEL`.
+    pub patch_default_outer_level: [f32; 4],
+
+    /// The latest value passed to `glPatchParameter` with `GL_PATCH_DEFAULT_INNER_LEVEL`.
+    pub patch_default_inner_level: [f32; 2],
+
     /// The id of the active texture unit.
     /// IMPORTANT: this is a raw number (0, 1, 2, ...), not an
     ///            enumeration (GL_TEXTURE0, GL_TEXTURE1, ...).
@@ -258,14 +280,20 @@ pub struct GlState {
     /// Latest value passed to `glBeginConditionalRender​`.
     pub conditional_render: Option<(gl::types::GLuint, gl::types::GLenum)>,
 
-    /// If `glBeginTransformFeedback​` has been called, the current primitive types. Otherwise None.
-    // TODO: move this inside transform feedback objects
+    /// If `glBeginTransformFeedback​` has been called on the bound transform feedback object, the
+    /// current primitive types. Otherwise None.
     pub transform_feedback_enabled: Option<gl::types::GLenum>,
 
-    /// True if `glPauseTransformFeedback` has been called.
-    // TODO: move this inside transform feedback objects
+    /// True if `glPauseTransformFeedback` has been called on the bound transform feedback object.
     pub transform_feedback_paused: bool,
 
+    /// Transform feedback object currently bound to `GL_TRANSFORM_FEEDBACK`.
+    pub transform_feedback_object: gl::types::GLuint,
+
+    /// Captures of the transform feedback objects that aren't bound. These captures are always
+    /// paused.
+    pub saved_transform_feedback_captures: SmallVec<[SavedTransformFeedbackCapture; 4]>,
+
     /// The latest value passed to `glPrimitiveBoundingBox`.
     pub primitive_bounding_box: (f32, f32, f32, f32, f32, f32, f32, f32),
 
@@ -328,6 +356,12 @@ pub struct GlState {
     /// The draw call ID of the latest call to `glMemoryBarrier` with
     /// `GL_QUERY_BUFFER_BARRIER_BIT`.
     pub latest_memory_barrier_query_buffer: u64,
+
+    /// Bindings that have been written in this struct but not sent to OpenGL yet.
+    pub pending_multi_bind: PendingMultiBind,
+
+    /// Number of calls that have been made to bind resources. Not part of the OpenGL state.
+    pub statistics: StateStatistics,
 }
 
 /// State of a texture unit (the one designated by `glActiveTexture`).
@@ -338,6 +372,57 @@ pub struct TextureUnitState {
 
     /// Id of the sampler.
     pub sampler: gl::types::GLuint,
+
+    /// The draw call ID of the latest draw call that used this texture unit. Used to determine
+    /// which unit to reuse when all of them are occupied.
+    pub last_used: u64,
+}
+
+/// Bindings that are applied to the `GlState` first and sent to OpenGL later all at once, with
+/// the functions of `GL_ARB_multi_bind`.
+///
+/// While `active` is true, the texture units and the indexed uniform and shader storage buffer
+/// bindings that are modified are only recorded here. The `GlState` then doesn't match the
+/// real state until the bindings are sent.
+#[derive(Clone, Debug, Default)]
+pub struct PendingMultiBind {
+    /// True if bindings must be recorded instead of being sent.
+    pub active: bool,
+
+    /// Texture units whose texture or sampler must be sent.
+    pub texture_units: SmallVec<[u16; 16]>,
+
+    /// Indices of the indexed `GL_UNIFORM_BUFFER` bindings that must be sent.
+    pub uniform_buffers: SmallVec<[u16; 8]>,
+
+    /// Indices of the indexed `GL_SHADER_STORAGE_BUFFER` bindings that must be sent.
+    pub shader_storage_buffers: SmallVec<[u16; 8]>,
+}
+
+/// Number of OpenGL calls that glium has made to bind resources.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateStatistics {
+    /// Number of calls to `glBindTexture` and `glBindTextures` made when drawing.
+    pub texture_bind_calls: u64,
+
+    /// Number of calls to `glBindSampler` and `glBindSamplers` made when drawing.
+    pub sampler_bind_calls: u64,
+
+    /// Number of calls to `glBindBufferRange` and `glBindBuffersRange`.
+    pub indexed_buffer_bind_calls: u64,
+}
+
+/// State of a transform feedback object whose capture is active while another object is bound.
+#[derive(Clone, Debug)]
+pub struct SavedTransformFeedbackCapture {
+    /// Id of the transform feedback object.
+    pub object: gl::types::GLuint,
+
+    /// Value that was passed to `glBeginTransformFeedback`.
+    pub primitives: gl::types::GLenum,
+
+    /// Buffers bound to the indexed `GL_TRANSFORM_FEEDBACK_BUFFER` of the object.
+    pub buffers: SmallVec<[IndexedBufferState; 4]>,
 }
 
 /// State of an indexed buffer target (`glBindBufferRange`/`glBindBufferBase`).
@@ -353,6 +438,44 @@ pub struct IndexedBufferState {
     pub size: gl::types::GLsizeiptr,
 }
 
+impl GlState {
+    /// Removes a buffer from all the binding points of the cache.
+    ///
+    /// This doesn't call any OpenGL function. The buffer may still be bound in the actual
+    /// OpenGL state, but the next time glium needs a buffer with this id it will bind it again.
+    pub fn forget_buffer(&mut self, id: gl::types::GLuint) {
+        {
+            let mut bindings = [
+                &mut self.array_buffer_binding, &mut self.pixel_pack_buffer_binding,
+                &mut self.pixel_unpack_buffer_binding, &mut self.uniform_buffer_binding,
+                &mut self.copy_read_buffer_binding, &mut self.copy_write_buffer_binding,
+                &mut self.dispatch_indirect_buffer_binding, &mut self.draw_indirect_buffer_binding,
+                &mut self.parameter_buffer_binding,
+                &mut self.query_buffer_binding, &mut self.texture_buffer_binding,
+                &mut self.atomic_counter_buffer_binding, &mut self.shader_storage_buffer_binding,
+            ];
+
+            for binding in bindings.iter_mut() {
+                if **binding == id {
+                    **binding = 0;
+                }
+            }
+        }
+
+        let indexed = self.indexed_atomic_counter_buffer_bindings.iter_mut()
+                          .chain(self.indexed_shader_storage_buffer_bindings.iter_mut())
+                          .chain(self.indexed_uniform_buffer_bindings.iter_mut())
+                          .chain(self.indexed_transform_feedback_buffer_bindings.iter_mut());
+
+        for point in indexed {
+            // FIXME: end transform feedback if it is active
+            if point.buffer == id {
+                point.buffer = 0;
+            }
+        }
+    }
+}
+
 /// Builds the `GlState` corresponding to a newly-created OpenGL context.
 impl Default for GlState {
     fn default() -> GlState {
@@ -392,6 +515,7 @@ impl Default for GlState {
             clear_depth: 1.0,
             clear_stencil: 0,
             color_mask: (1, 1, 1, 1),
+            color_mask_indexed: false,
             array_buffer_binding: 0,
             pixel_pack_buffer_binding: 0,
             pixel_unpack_buffer_binding: 0,
@@ -401,6 +525,7 @@ impl Default for GlState {
             copy_write_buffer_binding: 0,
             dispatch_indirect_buffer_binding: 0,
             draw_indirect_buffer_binding: 0,
+            parameter_buffer_binding: 0,
             query_buffer_binding: 0,
             texture_buffer_binding: 0,
             atomic_counter_buffer_binding: 0,
@@ -411,6 +536,7 @@ impl Default for GlState {
             read_framebuffer: 0,
             draw_framebuffer: 0,
             default_framebuffer_read: None,
+            default_framebuffer_draw: None,
             renderbuffer: 0,
             depth_func: gl::LESS,
             depth_mask: true,
@@ -425,6 +551,7 @@ impl Default for GlState {
             blend_func: (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
             blend_color: (0.0, 0.0, 0.0, 0.0),
             viewport: None,
+            viewport_indexed: None,
             scissor: None,
             line_width: 1.0,
             point_size: 1.0,
@@ -436,6 +563,8 @@ impl Default for GlState {
             pixel_store_pack_alignment: 4,
             clamp_color: gl::FIXED_ONLY,
             patch_patch_vertices: 3,
+            patch_default_outer_level: [1.0, 1.0, 1.0, 1.0],
+            patch_default_inner_level: [1.0, 1.0],
             active_texture: 0,
             texture_units: small_vec_one(),
             samples_passed_query: 0,
@@ -447,6 +576,8 @@ impl Default for GlState {
             conditional_render: None,
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
+            transform_feedback_object: 0,
+            saved_transform_feedback_captures: SmallVec::new(),
             primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
 
             next_draw_call_id: 1,
@@ -464,6 +595,9 @@ impl Default for GlState {
             latest_memory_barrier_atomic_counter: 1,
             latest_memory_barrier_shader_storage: 1,
             latest_memory_barrier_query_buffer: 1,
+
+            pending_multi_bind: Default::default(),
+            statistics: Default::default(),
         }
     }
 }
@@ -474,6 +608,7 @@ impl Default for TextureUnitState {
         TextureUnitState {
             texture: 0,
             sampler: 0,
+            last_used: 0,
         }
     }
 }
@@ -0,0 +1,419 @@
+/*!
+Frustum culling on the GPU, with a compute shader that writes the indirect draw commands.
+
+A `GpuCuller` stores the bounding sphere and the draw command of each object in a shader storage
+buffer. Each call to `cull` runs a compute shader that tests every sphere against the frustum of
+a view-projection matrix and appends the commands of the visible objects to a buffer of indirect
+draw commands. The visible objects are counted with an atomic counter, and `draw` issues a
+single multidraw that executes exactly the surviving commands. Neither the commands nor their
+number are ever read back on the CPU.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let view_projection: [[f32; 4]; 4] = unsafe { std::mem::uninitialized() };
+# let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { std::mem::uninitialized() };
+# let index_buffer: glium::IndexBuffer<u16> = unsafe { std::mem::uninitialized() };
+# let program: glium::Program = unsafe { std::mem::uninitialized() };
+use glium::Surface;
+use glium::culling::{CulledObject, GpuCuller};
+use glium::index::DrawCommandIndices;
+
+let mut culler = GpuCuller::new(&display, 1024).unwrap();
+
+// each object is a range of the index buffer
+culler.set_objects(&[
+    CulledObject::new([0.0, 0.0, -5.0], 1.0, DrawCommandIndices {
+        count: 36, instance_count: 1, first_index: 0, base_vertex: 0, base_instance: 0,
+    }),
+]);
+
+// each frame
+culler.cull(view_projection);
+
+let mut frame = display.draw();
+frame.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+culler.draw(&mut frame, &vertex_buffer, &index_buffer, &program,
+            &uniform! { matrix: view_projection }, &Default::default()).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+The `base_instance` of the commands is left untouched and can be used to identify the object in
+the vertex shader, for example to fetch its model matrix from a per-instance vertex buffer.
+
+If the backend doesn't support reading the number of commands from a buffer (see
+`index::is_draw_count_supported`), the commands are not compacted. Instead, the command of each
+object stays at the same position and the commands of the hidden objects draw zero instances.
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use buffer::{Buffer, BufferCreationError, BufferMode, BufferType};
+use index::{self, DrawCommandIndices, DrawCommandsIndicesBuffer, IndicesSource};
+use program::{ComputeShader, Program, ProgramCreationError};
+use uniforms::Uniforms;
+use vertex::MultiVerticesSource;
+
+use DrawError;
+use DrawParameters;
+use Surface;
+
+/// Number of invocations in a work group of the culling shader.
+const WORK_GROUP_SIZE: u32 = 64;
+
+/// Error that can happen while creating a `GpuCuller`.
+#[derive(Clone, Debug)]
+pub enum GpuCullerCreationError {
+    /// The backend doesn't support compute shaders, shader storage buffers or multidraw
+    /// indirect.
+    NotSupported,
+
+    /// Failed to compile the compute shader.
+    ProgramCreationError(ProgramCreationError),
+
+    /// Failed to create the buffers.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for GpuCullerCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for GpuCullerCreationError {
+    fn description(&self) -> &str {
+        use self::GpuCullerCreationError::*;
+        match *self {
+            NotSupported =>
+                "The backend doesn't support compute shaders, shader storage buffers or \
+                 multidraw indirect",
+            ProgramCreationError(_) =>
+                "Failed to compile the compute shader",
+            BufferCreationError(_) =>
+                "Failed to create the buffers",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::GpuCullerCreationError::*;
+        match *self {
+            NotSupported => None,
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramCreationError> for GpuCullerCreationError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> GpuCullerCreationError {
+        GpuCullerCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for GpuCullerCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> GpuCullerCreationError {
+        GpuCullerCreationError::BufferCreationError(err)
+    }
+}
+
+/// An object to cull, as stored in the shader storage buffer.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CulledObject {
+    /// Center of the bounding sphere in world space. The fourth component is the radius.
+    pub bounding_sphere: [f32; 4],
+
+    /// The command that draws the object.
+    pub command: DrawCommandIndices,
+
+    // the std430 layout rounds the size of the struct to the alignment of the `vec4`
+    _padding: [u32; 3],
+}
+
+implement_uniform_block!(CulledObject, bounding_sphere, command);
+
+impl CulledObject {
+    /// Builds an object from its bounding sphere and its draw command.
+    #[inline]
+    pub fn new(center: [f32; 3], radius: f32, command: DrawCommandIndices) -> CulledObject {
+        CulledObject {
+            bounding_sphere: [center[0], center[1], center[2], radius],
+            command: command,
+            _padding: [0; 3],
+        }
+    }
+}
+
+/// Culls objects against a frustum with a compute shader and draws the visible ones with a
+/// single multidraw.
+pub struct GpuCuller {
+    shader: ComputeShader,
+    capacity: usize,
+    compact: bool,
+
+    objects: Buffer<[CulledObject]>,
+    object_count: usize,
+
+    // the commands of the visible objects, and their number if `compact` is true
+    commands: DrawCommandsIndicesBuffer,
+    draw_count: Buffer<[u32]>,
+}
+
+impl GpuCuller {
+    /// Builds a new `GpuCuller` that can hold up to `capacity` objects.
+    pub fn new<F: ?Sized>(facade: &F, capacity: usize)
+                          -> Result<GpuCuller, GpuCullerCreationError>
+                          where F: Facade
+    {
+        if !ComputeShader::is_supported(facade.get_context()) {
+            return Err(GpuCullerCreationError::NotSupported);
+        }
+
+        let shader = try!(ComputeShader::from_source(facade, "
+            #version 430
+
+            layout(local_size_x = 64) in;
+
+            struct DrawCommand {
+                uint count;
+                uint instance_count;
+                uint first_index;
+                uint base_vertex;
+                uint base_instance;
+            };
+
+            struct Object {
+                vec4 bounding_sphere;
+                DrawCommand command;
+            };
+
+            layout(std430) buffer Objects {
+                Object objects[];
+            };
+
+            layout(std430) buffer Commands {
+                DrawCommand commands[];
+            };
+
+            layout(std430) buffer DrawCount {
+                uint draw_count[];
+            };
+
+            uniform uint object_count;
+            uniform mat4 view_projection;
+            uniform bool compact;
+
+            void main() {
+                uint id = gl_GlobalInvocationID.x;
+
+                if (id >= object_count) {
+                    if (!compact && id < uint(commands.length())) {
+                        commands[id] = DrawCommand(0u, 0u, 0u, 0u, 0u);
+                    }
+                    return;
+                }
+
+                Object object = objects[id];
+
+                // the planes of the frustum, extracted from the rows of the matrix
+                mat4 rows = transpose(view_projection);
+                vec4 planes[6] = vec4[6](rows[3] + rows[0], rows[3] - rows[0],
+                                         rows[3] + rows[1], rows[3] - rows[1],
+                                         rows[3] + rows[2], rows[3] - rows[2]);
+
+                bool visible = true;
+                for (int i = 0; i < 6; ++i) {
+                    float distance = dot(planes[i].xyz, object.bounding_sphere.xyz) + planes[i].w;
+                    if (distance < -object.bounding_sphere.w * length(planes[i].xyz)) {
+                        visible = false;
+                    }
+                }
+
+                if (compact) {
+                    if (visible) {
+                        commands[atomicAdd(draw_count[0], 1u)] = object.command;
+                    }
+                } else {
+                    DrawCommand command = object.command;
+                    if (!visible) {
+                        command.instance_count = 0u;
+                    }
+                    commands[id] = command;
+                }
+            }
+        "));
+
+        let capacity = if capacity == 0 { 1 } else { capacity };
+
+        let objects = try!(Buffer::empty_array(facade, BufferType::ShaderStorageBuffer, capacity,
+                                               BufferMode::Default));
+        let commands = try!(DrawCommandsIndicesBuffer::empty(facade, capacity));
+        commands.write(&vec![empty_command(); capacity][..]);
+        let draw_count = try!(Buffer::new(facade, &[0u32][..], BufferType::ShaderStorageBuffer,
+                                          BufferMode::Default));
+
+        Ok(GpuCuller {
+            shader: shader,
+            capacity: capacity,
+            compact: index::is_draw_count_supported(facade.get_context()),
+            objects: objects,
+            object_count: 0,
+            commands: commands,
+            draw_count: draw_count,
+        })
+    }
+
+    /// Returns the maximum number of objects.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns true if the commands of the visible objects are compacted and counted.
+    ///
+    /// If false, the buffer of commands contains one command per object, and the commands of
+    /// the hidden objects draw zero instances.
+    #[inline]
+    pub fn is_compacting(&self) -> bool {
+        self.compact
+    }
+
+    /// Replaces the list of objects.
+    ///
+    /// # Panic
+    ///
+    /// Panics if there are more objects than the capacity.
+    pub fn set_objects(&mut self, objects: &[CulledObject]) {
+        assert!(objects.len() <= self.capacity);
+
+        if !objects.is_empty() {
+            self.objects.slice(0 .. objects.len()).unwrap().write(objects);
+        }
+
+        self.object_count = objects.len();
+    }
+
+    /// Returns the buffer that contains the objects. Only the first `object_count` objects are
+    /// culled.
+    ///
+    /// The objects can be modified directly on the GPU, for example by another compute shader.
+    #[inline]
+    pub fn objects(&self) -> &Buffer<[CulledObject]> {
+        &self.objects
+    }
+
+    /// Returns the number of objects.
+    #[inline]
+    pub fn object_count(&self) -> usize {
+        self.object_count
+    }
+
+    /// Tests the objects against the frustum of `view_projection`, which transforms world
+    /// coordinates to clip space, and writes the commands of the visible objects.
+    ///
+    /// This only dispatches a compute shader and never waits for the GPU.
+    pub fn cull(&mut self, view_projection: [[f32; 4]; 4]) {
+        if self.compact {
+            self.draw_count.write(&[0][..]);
+        }
+
+        let uniforms = uniform! {
+            Objects: &self.objects,
+            Commands: &*self.commands,
+            DrawCount: &self.draw_count,
+            object_count: self.object_count as u32,
+            view_projection: view_projection,
+            compact: self.compact,
+        };
+
+        // without compaction, the commands beyond the objects must be cleared as well
+        let invocations = (if self.compact { self.object_count } else { self.capacity }) as u32;
+        if invocations == 0 {
+            return;
+        }
+
+        let groups = (invocations + WORK_GROUP_SIZE - 1) / WORK_GROUP_SIZE;
+        self.shader.execute(uniforms, groups, 1, 1);
+    }
+
+    /// Returns the commands written by the latest `cull` as a source of indices, which can be
+    /// passed to `Surface::draw`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `index_buffer` isn't an index buffer or a slice of an index buffer.
+    pub fn indices<'a, I>(&'a self, index_buffer: I) -> IndicesSource<'a>
+        where I: Into<IndicesSource<'a>>
+    {
+        let (buffer, data_type, primitives) = match index_buffer.into() {
+            IndicesSource::IndexBuffer { buffer, data_type, primitives } => {
+                (buffer, data_type, primitives)
+            },
+            _ => panic!("The culled objects must be drawn with an index buffer"),
+        };
+
+        if self.compact {
+            IndicesSource::MultidrawElementCount {
+                commands: self.commands.as_slice_any(),
+                indices: buffer,
+                count: self.draw_count.as_slice_any(),
+                data_type: data_type,
+                primitives: primitives,
+            }
+        } else {
+            IndicesSource::MultidrawElement {
+                commands: self.commands.as_slice_any(),
+                indices: buffer,
+                data_type: data_type,
+                primitives: primitives,
+            }
+        }
+    }
+
+    /// Draws the objects that were visible during the latest `cull`.
+    pub fn draw<'a, 'b, S: ?Sized, V, I, U>(&'a self, surface: &mut S, vertices: V,
+                                            index_buffer: I, program: &Program, uniforms: &U,
+                                            parameters: &DrawParameters)
+                                            -> Result<(), DrawError>
+        where S: Surface, V: MultiVerticesSource<'b>, I: Into<IndicesSource<'a>>, U: Uniforms
+    {
+        surface.draw(vertices, self.indices(index_buffer), program, uniforms, parameters)
+    }
+
+    /// Reads the number of objects that were visible during the latest `cull` back from the
+    /// GPU.
+    ///
+    /// This waits for the culling to finish and should only be used for debugging.
+    pub fn read_visible_count(&self) -> usize {
+        if self.compact {
+            self.draw_count.read().unwrap()[0] as usize
+        } else {
+            self.commands.read().unwrap().iter().filter(|c| c.instance_count != 0).count()
+        }
+    }
+}
+
+impl fmt::Debug for GpuCuller {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "GpuCuller(capacity: {}, objects: {})", self.capacity, self.object_count)
+    }
+}
+
+/// Draw command that draws nothing.
+#[inline]
+fn empty_command() -> DrawCommandIndices {
+    DrawCommandIndices {
+        count: 0,
+        instance_count: 0,
+        first_index: 0,
+        base_vertex: 0,
+        base_instance: 0,
+    }
+}
@@ -9,10 +9,10 @@ use ContextExt;
 use version::Api;
 use version::Version;
 use gl;
+use utils::gl_get::get_output;
 use std::rc::Rc;
-use std::mem;
 
-pub use context::DebugCallbackBehavior;
+pub use context::{DebugCallbackBehavior, ErrorCheckPolicy};
 
 /// Represents a callback that can be used for the debug output feature of OpenGL.
 ///
@@ -128,8 +128,7 @@ impl TimestampQuery {
 
         let id = if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
             unsafe {
-                let mut id = mem::uninitialized();
-                ctxt.gl.GenQueries(1, &mut id);
+                let id = get_output(|ptr| ctxt.gl.GenQueries(1, ptr));
 
                 ctxt.gl.QueryCounter(id, gl::TIMESTAMP);
 
@@ -138,8 +137,7 @@ impl TimestampQuery {
 
         } else if ctxt.extensions.gl_ext_disjoint_timer_query {
             unsafe {
-                let mut id = mem::uninitialized();
-                ctxt.gl.GenQueriesEXT(1, &mut id);
+                let id = get_output(|ptr| ctxt.gl.GenQueriesEXT(1, ptr));
 
                 ctxt.gl.QueryCounterEXT(id, gl::TIMESTAMP);
 
@@ -161,21 +159,21 @@ impl TimestampQuery {
     /// It takes some time to retrieve the value, during which you can execute other
     /// functions.
     pub fn is_ready(&self) -> bool {
-        use std::mem;
-
         let ctxt = self.context.make_current();
 
         if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
             unsafe {
-                let mut value = mem::uninitialized();
-                ctxt.gl.GetQueryObjectiv(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
+                let value = get_output(|ptr| {
+                    ctxt.gl.GetQueryObjectiv(self.id, gl::QUERY_RESULT_AVAILABLE, ptr)
+                });
                 value != 0
             }
 
         } else if ctxt.extensions.gl_ext_disjoint_timer_query {
             unsafe {
-                let mut value = mem::uninitialized();
-                ctxt.gl.GetQueryObjectivEXT(self.id, gl::QUERY_RESULT_AVAILABLE_EXT, &mut value);
+                let value = get_output(|ptr| {
+                    ctxt.gl.GetQueryObjectivEXT(self.id, gl::QUERY_RESULT_AVAILABLE_EXT, ptr)
+                });
                 value != 0
             }
 
@@ -188,22 +186,22 @@ impl TimestampQuery {
     ///
     /// This function doesn't block if `is_ready` returns true.
     pub fn get(self) -> u64 {
-        use std::mem;
-
         let ctxt = self.context.make_current();
 
         if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
             unsafe {
-                let mut value = mem::uninitialized();
-                ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, &mut value);
+                let value = get_output(|ptr| {
+                    ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, ptr)
+                });
                 ctxt.gl.DeleteQueries(1, [self.id].as_ptr());
                 value
             }
 
         } else if ctxt.extensions.gl_ext_disjoint_timer_query {
             unsafe {
-                let mut value = mem::uninitialized();
-                ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT_EXT, &mut value);
+                let value = get_output(|ptr| {
+                    ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT_EXT, ptr)
+                });
                 ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
                 value
             }
@@ -213,3 +211,80 @@ impl TimestampQuery {
         }
     }
 }
+
+/// Kind of an OpenGL object reported by `Context::debug_dump`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum ObjectKind {
+    /// A buffer, including vertex buffers, index buffers, uniform buffers, etc.
+    Buffer,
+    /// A texture.
+    Texture,
+    /// A render buffer.
+    RenderBuffer,
+    /// A linked program.
+    Program,
+}
+
+/// Description of an OpenGL object that is alive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct ObjectReport {
+    /// Kind of the object.
+    pub kind: ObjectKind,
+
+    /// OpenGL identifier of the object.
+    pub id: u32,
+
+    /// Label attached to the object with `glObjectLabel`, if any. Always `None` if
+    /// `GL_KHR_debug` isn't supported.
+    pub label: Option<String>,
+
+    /// Size in bytes of the storage of the object, including all the mipmaps of textures.
+    ///
+    /// For textures and render buffers, this is computed from the format chosen by the
+    /// implementation, which can be larger than the requested one. `None` if the backend can't
+    /// report it, and for programs.
+    pub size: Option<usize>,
+
+    /// Format of a texture or of a render buffer, or type of a buffer.
+    pub format: Option<String>,
+
+    /// Dimensions of a texture or of a render buffer.
+    pub dimensions: Option<::texture::Dimensions>,
+
+    /// Number of times `swap_buffers` had been called when the object was created.
+    pub created_frame: u64,
+
+    /// Number of times `swap_buffers` had been called when the object was last bound by glium.
+    pub last_used_frame: u64,
+}
+
+/// List of the OpenGL objects of a context that are alive. Returned by `Context::debug_dump`.
+///
+/// With the `serialize` feature, this report can be serialized in order to compare it with
+/// another one later, or to be inspected with an external tool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct DebugDump {
+    /// Number of times `swap_buffers` has been called on the context.
+    pub frame: u64,
+
+    /// The objects, sorted by kind then by identifier.
+    pub objects: Vec<ObjectReport>,
+}
+
+impl DebugDump {
+    /// Returns the sum of the sizes of all the objects whose size is known.
+    #[inline]
+    pub fn total_size(&self) -> usize {
+        self.objects.iter().filter_map(|o| o.size).sum()
+    }
+
+    /// Returns the objects that haven't been used during the last `frames` frames.
+    pub fn unused_since(&self, frames: u64) -> Vec<&ObjectReport> {
+        self.objects.iter()
+                    .filter(|o| o.last_used_frame + frames < self.frame)
+                    .collect()
+    }
+}
@@ -0,0 +1,651 @@
+/*!
+Drawing lines, boxes and text for quick diagnostics.
+
+A `DebugDraw` collects primitives during the frame and draws them all at once with its own
+programs. Lines and boxes are in world space and are transformed by the matrix passed to `draw`,
+while text is positioned in pixels from the top-left corner of the surface and uses an embedded
+5x7 bitmap font that only contains the printable ASCII characters.
+
+Primitives are always drawn on top of the content of the surface, without depth testing.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let view_projection = [[0.0; 4]; 4];
+use glium::debug_draw::DebugDraw;
+
+let mut debug_draw = DebugDraw::new(&display).unwrap();
+
+// each frame
+let mut target = display.draw();
+debug_draw.line([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 0.0, 0.0, 1.0]);
+debug_draw.wire_box([-0.5, -0.5, -0.5], [0.5, 0.5, 0.5], [0.0, 1.0, 0.0, 1.0]);
+debug_draw.text([10.0, 10.0], "hello", [1.0, 1.0, 1.0, 1.0]);
+debug_draw.draw(&mut target, view_projection).unwrap();
+target.finish().unwrap();
+# }
+```
+
+*/
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use index::{NoIndices, PrimitiveType};
+use program::{Program, ProgramChooserCreationError};
+use texture::{ClientFormat, MipmapsOption, RawImage2d, Texture2d, TextureCreationError};
+use texture::UncompressedFloatFormat;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter};
+use vertex::VertexBuffer;
+use vertex::BufferCreationError;
+
+use Blend;
+use DrawError;
+use DrawParameters;
+use Surface;
+
+/// Width in pixels of a character, including the spacing.
+const GLYPH_WIDTH: u32 = 6;
+
+/// Height in pixels of a character cell.
+const GLYPH_HEIGHT: u32 = 8;
+
+/// Distance in pixels between two lines of text.
+const LINE_HEIGHT: u32 = 9;
+
+/// Number of characters per row of the font texture.
+const ATLAS_COLUMNS: u32 = 16;
+
+/// Number of rows of the font texture.
+const ATLAS_ROWS: u32 = 6;
+
+/// 5x7 glyphs of the characters from `' '` to `'~'`. Each byte is a row, from top to bottom,
+/// and bit 4 is the leftmost pixel.
+const FONT: [[u8; 7]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],  // space
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],  // !
+    [0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00],  // "
+    [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],  // #
+    [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04],  // $
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],  // %
+    [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],  // &
+    [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],  // '
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],  // (
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],  // )
+    [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00],  // *
+    [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],  // +
+    [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],  // ,
+    [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],  // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],  // .
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],  // /
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],  // 0
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],  // 1
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],  // 2
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],  // 3
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],  // 4
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],  // 5
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],  // 6
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],  // 7
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],  // 8
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],  // 9
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],  // :
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08],  // ;
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],  // <
+    [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],  // =
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08],  // >
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],  // ?
+    [0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E],  // @
+    [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11],  // A
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],  // B
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],  // C
+    [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],  // D
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],  // E
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],  // F
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],  // G
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],  // H
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],  // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],  // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],  // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],  // L
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],  // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],  // N
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],  // O
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],  // P
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],  // Q
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],  // R
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],  // S
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],  // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],  // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],  // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],  // W
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],  // X
+    [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],  // Y
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],  // Z
+    [0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E],  // [
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00],  // backslash
+    [0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E],  // ]
+    [0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00],  // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],  // _
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00],  // `
+    [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F],  // a
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E],  // b
+    [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E],  // c
+    [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F],  // d
+    [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E],  // e
+    [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08],  // f
+    [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E],  // g
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11],  // h
+    [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E],  // i
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C],  // j
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12],  // k
+    [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],  // l
+    [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11],  // m
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11],  // n
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E],  // o
+    [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10],  // p
+    [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01],  // q
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10],  // r
+    [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E],  // s
+    [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06],  // t
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D],  // u
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04],  // v
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A],  // w
+    [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11],  // x
+    [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E],  // y
+    [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F],  // z
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02],  // {
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],  // |
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08],  // }
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00],  // ~
+];
+
+/// Error that can happen while creating a `DebugDraw`.
+#[derive(Clone, Debug)]
+pub enum DebugDrawCreationError {
+    /// Failed to compile one of the programs.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to create the font texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl fmt::Display for DebugDrawCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for DebugDrawCreationError {
+    fn description(&self) -> &str {
+        use self::DebugDrawCreationError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile one of the programs",
+            TextureCreationError(_) =>
+                "Failed to create the font texture",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::DebugDrawCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            TextureCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for DebugDrawCreationError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> DebugDrawCreationError {
+        DebugDrawCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<TextureCreationError> for DebugDrawCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> DebugDrawCreationError {
+        DebugDrawCreationError::TextureCreationError(err)
+    }
+}
+
+/// Error that can happen while drawing the primitives of a `DebugDraw`.
+#[derive(Clone, Debug)]
+pub enum DebugDrawError {
+    /// Failed to create a vertex buffer.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to draw.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for DebugDrawError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for DebugDrawError {
+    fn description(&self) -> &str {
+        use self::DebugDrawError::*;
+        match *self {
+            BufferCreationError(_) =>
+                "Failed to create a vertex buffer",
+            DrawError(_) =>
+                "Failed to draw",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::DebugDrawError::*;
+        match *self {
+            BufferCreationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<BufferCreationError> for DebugDrawError {
+    #[inline]
+    fn from(err: BufferCreationError) -> DebugDrawError {
+        DebugDrawError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for DebugDrawError {
+    #[inline]
+    fn from(err: DrawError) -> DebugDrawError {
+        DebugDrawError::DrawError(err)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct LineVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+implement_vertex!(LineVertex, position, color);
+
+#[derive(Copy, Clone)]
+struct TextVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+    color: [f32; 4],
+}
+
+implement_vertex!(TextVertex, position, tex_coords, color);
+
+/// Collects lines, boxes and text and draws them.
+///
+/// Creating a `DebugDraw` compiles two programs and uploads the font, so you are encouraged to
+/// keep it around. The vertex buffers are reused between frames and only grow.
+pub struct DebugDraw {
+    context: Rc<Context>,
+    lines_program: Program,
+    text_program: Program,
+    font: Texture2d,
+    lines: Vec<LineVertex>,
+    text: Vec<TextVertex>,
+    lines_buffer: Option<VertexBuffer<LineVertex>>,
+    text_buffer: Option<VertexBuffer<TextVertex>>,
+    text_scale: f32,
+}
+
+impl DebugDraw {
+    /// Builds a new `DebugDraw`.
+    ///
+    /// Requires GLSL 1.40 or GLSL ES 1.00.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<DebugDraw, DebugDrawCreationError>
+                          where F: Facade
+    {
+        let lines_program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform mat4 matrix;
+
+                    in vec3 position;
+                    in vec4 color;
+                    out vec4 v_color;
+
+                    void main() {
+                        gl_Position = matrix * vec4(position, 1.0);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    in vec4 v_color;
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = v_color;
+                    }
+                ",
+            },
+
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform mat4 matrix;
+
+                    attribute vec3 position;
+                    attribute vec4 color;
+                    varying vec4 v_color;
+
+                    void main() {
+                        gl_Position = matrix * vec4(position, 1.0);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    precision mediump float;
+
+                    varying vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = v_color;
+                    }
+                ",
+            },
+        ));
+
+        let text_program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform vec2 screen_size;
+
+                    in vec2 position;
+                    in vec2 tex_coords;
+                    in vec4 color;
+                    out vec2 v_tex_coords;
+                    out vec4 v_color;
+
+                    void main() {
+                        vec2 coords = position / screen_size * 2.0 - 1.0;
+                        gl_Position = vec4(coords.x, -coords.y, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D font;
+
+                    in vec2 v_tex_coords;
+                    in vec4 v_color;
+                    out vec4 f_color;
+
+                    void main() {
+                        if (texture(font, v_tex_coords).r < 0.5) {
+                            discard;
+                        }
+
+                        f_color = v_color;
+                    }
+                ",
+            },
+
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform vec2 screen_size;
+
+                    attribute vec2 position;
+                    attribute vec2 tex_coords;
+                    attribute vec4 color;
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        vec2 coords = position / screen_size * 2.0 - 1.0;
+                        gl_Position = vec4(coords.x, -coords.y, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    precision mediump float;
+
+                    uniform sampler2D font;
+
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        if (texture2D(font, v_tex_coords).r < 0.5) {
+                            discard;
+                        }
+
+                        gl_FragColor = v_color;
+                    }
+                ",
+            },
+        ));
+
+        let font = try!(Texture2d::with_format(facade, build_font_image(),
+                                               UncompressedFloatFormat::U8,
+                                               MipmapsOption::NoMipmap));
+
+        Ok(DebugDraw {
+            context: facade.get_context().clone(),
+            lines_program: lines_program,
+            text_program: text_program,
+            font: font,
+            lines: Vec::new(),
+            text: Vec::new(),
+            lines_buffer: None,
+            text_buffer: None,
+            text_scale: 1.0,
+        })
+    }
+
+    /// Adds a line between two points.
+    pub fn line(&mut self, from: [f32; 3], to: [f32; 3], color: [f32; 4]) {
+        self.lines.push(LineVertex { position: from, color: color });
+        self.lines.push(LineVertex { position: to, color: color });
+    }
+
+    /// Adds the edges of an axis-aligned box.
+    pub fn wire_box(&mut self, min: [f32; 3], max: [f32; 3], color: [f32; 4]) {
+        let corner = |i: usize| {
+            [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ]
+        };
+
+        // each edge links two corners that differ by one coordinate
+        for i in 0 .. 8 {
+            for &bit in &[1, 2, 4] {
+                if i & bit == 0 {
+                    self.line(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+
+    /// Adds a text. The position is the top-left corner of the first character, in pixels from
+    /// the top-left corner of the surface.
+    ///
+    /// `'\n'` starts a new line. Characters that aren't printable ASCII are drawn as `'?'`.
+    pub fn text(&mut self, position: [f32; 2], text: &str, color: [f32; 4]) {
+        let width = (GLYPH_WIDTH as f32) * self.text_scale;
+        let height = (GLYPH_HEIGHT as f32) * self.text_scale;
+
+        let mut x = position[0];
+        let mut y = position[1];
+
+        for c in text.chars() {
+            if c == '\n' {
+                x = position[0];
+                y += (LINE_HEIGHT as f32) * self.text_scale;
+                continue;
+            }
+
+            let glyph = if c >= ' ' && c <= '~' { c as u32 - 32 } else { '?' as u32 - 32 };
+            let column = glyph % ATLAS_COLUMNS;
+            let row = glyph / ATLAS_COLUMNS;
+
+            // the first row of the texture is the bottom of the atlas
+            let atlas_width = (ATLAS_COLUMNS * GLYPH_WIDTH) as f32;
+            let atlas_height = (ATLAS_ROWS * GLYPH_HEIGHT) as f32;
+            let u0 = (column * GLYPH_WIDTH) as f32 / atlas_width;
+            let u1 = ((column + 1) * GLYPH_WIDTH) as f32 / atlas_width;
+            let v0 = 1.0 - (row * GLYPH_HEIGHT) as f32 / atlas_height;
+            let v1 = 1.0 - ((row + 1) * GLYPH_HEIGHT) as f32 / atlas_height;
+
+            let top_left = TextVertex { position: [x, y], tex_coords: [u0, v0], color: color };
+            let top_right = TextVertex { position: [x + width, y], tex_coords: [u1, v0],
+                                         color: color };
+            let bottom_left = TextVertex { position: [x, y + height], tex_coords: [u0, v1],
+                                           color: color };
+            let bottom_right = TextVertex { position: [x + width, y + height],
+                                            tex_coords: [u1, v1], color: color };
+
+            self.text.extend_from_slice(&[top_left, bottom_left, top_right,
+                                          top_right, bottom_left, bottom_right]);
+
+            x += width;
+        }
+    }
+
+    /// Changes the size of the characters of the next calls to `text`. A scale of `1.0`, which
+    /// is the default, draws each character with 6x8 pixels.
+    #[inline]
+    pub fn set_text_scale(&mut self, scale: f32) {
+        self.text_scale = scale;
+    }
+
+    /// Removes all the primitives that have been added since the last call to `draw`.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.text.clear();
+    }
+
+    /// Draws all the primitives that have been added, then removes them.
+    ///
+    /// `matrix` transforms the lines and boxes to clip space. It is usually the product of the
+    /// projection and view matrices used to draw the scene.
+    pub fn draw<S>(&mut self, surface: &mut S, matrix: [[f32; 4]; 4])
+                   -> Result<(), DebugDrawError> where S: Surface
+    {
+        let result = self.draw_impl(surface, matrix);
+        self.clear();
+        result
+    }
+
+    fn draw_impl<S>(&mut self, surface: &mut S, matrix: [[f32; 4]; 4])
+                    -> Result<(), DebugDrawError> where S: Surface
+    {
+        let parameters = DrawParameters {
+            blend: Blend::alpha_blending(),
+            .. Default::default()
+        };
+
+        if !self.lines.is_empty() {
+            let buffer = try!(upload(&self.context, &mut self.lines_buffer, &self.lines));
+            let uniforms = uniform! {
+                matrix: matrix,
+            };
+
+            try!(surface.draw(buffer.slice(0 .. self.lines.len()).unwrap(),
+                              &NoIndices(PrimitiveType::LinesList), &self.lines_program,
+                              &uniforms, &parameters));
+        }
+
+        if !self.text.is_empty() {
+            let (width, height) = surface.get_dimensions();
+
+            let buffer = try!(upload(&self.context, &mut self.text_buffer, &self.text));
+            let uniforms = uniform! {
+                screen_size: [width as f32, height as f32],
+                font: self.font.sampled()
+                               .magnify_filter(MagnifySamplerFilter::Nearest)
+                               .minify_filter(MinifySamplerFilter::Nearest),
+            };
+
+            try!(surface.draw(buffer.slice(0 .. self.text.len()).unwrap(),
+                              &NoIndices(PrimitiveType::TrianglesList), &self.text_program,
+                              &uniforms, &parameters));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for DebugDraw {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "DebugDraw({} line vertices, {} text vertices)", self.lines.len(),
+               self.text.len())
+    }
+}
+
+/// Writes `data` at the beginning of `buffer`, replacing the buffer with a larger one if it is
+/// too small.
+fn upload<'a, T>(context: &Rc<Context>, buffer: &'a mut Option<VertexBuffer<T>>, data: &[T])
+                 -> Result<&'a VertexBuffer<T>, BufferCreationError>
+                 where T: ::vertex::Vertex + Send + 'static
+{
+    let too_small = match *buffer {
+        Some(ref buffer) => buffer.len() < data.len(),
+        None => true,
+    };
+
+    if too_small {
+        *buffer = Some(try!(VertexBuffer::empty_dynamic(context, data.len().next_power_of_two())));
+    }
+
+    let buffer = buffer.as_ref().unwrap();
+    buffer.slice(0 .. data.len()).unwrap().write(data);
+    Ok(buffer)
+}
+
+/// Builds the font texture. Each character occupies a cell of `GLYPH_WIDTH` by `GLYPH_HEIGHT`
+/// pixels, and the characters are sorted from left to right then from top to bottom.
+fn build_font_image() -> RawImage2d<'static, u8> {
+    let width = ATLAS_COLUMNS * GLYPH_WIDTH;
+    let height = ATLAS_ROWS * GLYPH_HEIGHT;
+    let mut data = vec![0u8; (width * height) as usize];
+
+    for (glyph, rows) in FONT.iter().enumerate() {
+        let glyph = glyph as u32;
+        let left = (glyph % ATLAS_COLUMNS) * GLYPH_WIDTH;
+        let top = (glyph / ATLAS_COLUMNS) * GLYPH_HEIGHT;
+
+        for (y, &bits) in rows.iter().enumerate() {
+            for x in 0 .. 5 {
+                if bits & (0x10 >> x) != 0 {
+                    // the data starts with the bottom row
+                    let row = height - 1 - (top + y as u32);
+                    data[(row * width + left + x) as usize] = 255;
+                }
+            }
+        }
+    }
+
+    RawImage2d {
+        data: Cow::Owned(data),
+        width: width,
+        height: height,
+        format: ClientFormat::U8,
+    }
+}
@@ -14,6 +14,7 @@ use gl;
 /// If you want to add transparent objects one over another, use
 /// `Blend::alpha_blending()`.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Blend {
     /// The blending function for color channels.
     pub color: BlendingFunction,
@@ -52,6 +53,7 @@ impl Default for Blend {
 
 /// Function that the GPU will use for blending.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum BlendingFunction {
     /// Simply overwrite the destination pixel with the source pixel.
     ///
@@ -120,6 +122,7 @@ pub enum BlendingFunction {
 
 /// Indicates which value to multiply each component with.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum LinearBlendingFactor {
     /// Multiply the source or destination component by zero, which always
     /// gives `0.0`.
@@ -202,7 +205,9 @@ impl LinearBlendingFactor {
     }
 }
 
-pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), DrawError> {
+pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend,
+                     constant: Option<(f32, f32, f32, f32)>) -> Result<(), DrawError>
+{
     #[inline(always)]
     fn blend_eq(ctxt: &mut CommandContext, blending_function: BlendingFunction)
                 -> Result<gl::types::GLenum, DrawError>
@@ -252,6 +257,20 @@ pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), Draw
         }
     }
 
+    #[inline(always)]
+    fn sync_blend_color(ctxt: &mut CommandContext, color: (f32, f32, f32, f32)) {
+        if ctxt.state.blend_color != color {
+            let (r, g, b, a) = color;
+            unsafe { ctxt.gl.BlendColor(r, g, b, a); }
+            ctxt.state.blend_color = color;
+        }
+    }
+
+    // an explicit constant is synchronized even if no factor uses it
+    if let Some(constant) = constant {
+        sync_blend_color(ctxt, constant);
+    }
+
     if let (BlendingFunction::AlwaysReplace, BlendingFunction::AlwaysReplace) =
            (blend.color, blend.alpha)
     {
@@ -298,11 +317,7 @@ pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), Draw
            alpha_factor_dst == LinearBlendingFactor::ConstantAlpha ||
            alpha_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha
         {
-            if ctxt.state.blend_color != blend.constant_value {
-                let (r, g, b, a) = blend.constant_value;
-                unsafe { ctxt.gl.BlendColor(r, g, b, a); }
-                ctxt.state.blend_color = blend.constant_value;
-            }
+            sync_blend_color(ctxt, constant.unwrap_or(blend.constant_value));
         }
 
         // Updating the blending function if necessary.
@@ -7,6 +7,7 @@ use gl;
 
 /// Represents the depth parameters of a draw command.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Depth {
     /// The function that the GPU will use to determine whether to write over an existing pixel
     /// on the target. Don't forget to set `depth_write` appropriately if you use a depth test.
@@ -78,6 +79,7 @@ impl Default for Depth {
 /// If you don't have a depth buffer available, you can only pass `Overwrite`. Glium detects if
 /// you pass any other value and reports an error.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum DepthTest {
     /// Never replace the target pixel.
     ///
@@ -141,6 +143,7 @@ impl DepthTest {
 
 /// Specifies whether the depth value of samples should be clamped to `0.0` or `1.0`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum DepthClamp {
     /// Do not clamp. Samples with values outside of the `[0.0, 1.0]` range will be discarded.
     ///
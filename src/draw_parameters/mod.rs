@@ -62,6 +62,7 @@
 //! For the same reasons, as soon as you call `is_ready` on a query it will stop being usable.
 //!
 use gl;
+use utils::gl_get::get_integer;
 use context;
 use context::Context;
 use version::Version;
@@ -76,7 +77,6 @@ use Rect;
 use ToGlEnum;
 use vertex::TransformFeedbackSession;
 
-use std::mem;
 use std::ops::Range;
 
 pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
@@ -133,6 +133,7 @@ mod stencil;
 /// facing the screen, and increase your framerate.
 ///
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum BackfaceCullingMode {
     /// All triangles are always drawn.
     CullingDisabled,
@@ -164,6 +165,7 @@ pub enum BackfaceCullingMode {
 /// </svg>
 ///
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum PolygonMode {
     /// Only draw a single point at each vertex.
     ///
@@ -194,6 +196,7 @@ impl ToGlEnum for PolygonMode {
 ///
 /// Note that this is just a hint and the driver may disregard it.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Smooth {
     /// The most efficient option should be chosen.
     Fastest,
@@ -218,6 +221,7 @@ impl ToGlEnum for Smooth {
 
 /// The vertex to use for flat shading.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ProvokingVertex {
     /// Use the last vertex of each primitive.
     LastVertex,
@@ -228,6 +232,80 @@ pub enum ProvokingVertex {
     FirstVertex,
 }
 
+/// Default tessellation levels, used when a program has a tessellation evaluation shader but no
+/// tessellation control shader.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TessellationLevels {
+    /// The four outer tessellation levels. Corresponds to `GL_PATCH_DEFAULT_OUTER_LEVEL`.
+    pub outer: [f32; 4],
+
+    /// The two inner tessellation levels. Corresponds to `GL_PATCH_DEFAULT_INNER_LEVEL`.
+    pub inner: [f32; 2],
+}
+
+impl Default for TessellationLevels {
+    #[inline]
+    fn default() -> TessellationLevels {
+        TessellationLevels {
+            outer: [1.0, 1.0, 1.0, 1.0],
+            inner: [1.0, 1.0],
+        }
+    }
+}
+
+/// A viewport whose coordinates don't need to be integers.
+///
+/// Fractional coordinates are passed as they are to `glViewportIndexedf` if the backend supports
+/// it (OpenGL 4.1, `GL_ARB_viewport_array` or `GL_NV_viewport_array2`). Otherwise they are
+/// rounded to the nearest pixel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum FloatViewport {
+    /// Coordinates in pixels.
+    Pixels {
+        /// Number of pixels between the left border of the surface and the viewport.
+        left: f32,
+        /// Number of pixels between the bottom border of the surface and the viewport.
+        bottom: f32,
+        /// Width of the viewport in pixels.
+        width: f32,
+        /// Height of the viewport in pixels.
+        height: f32,
+    },
+
+    /// Coordinates relative to the dimensions of the surface being drawn to, where `1.0` is the
+    /// whole width or height of the surface.
+    ///
+    /// The pixel values are computed again at each draw, so the viewport follows the surface
+    /// when it is resized.
+    Relative {
+        /// Distance between the left border of the surface and the viewport.
+        left: f32,
+        /// Distance between the bottom border of the surface and the viewport.
+        bottom: f32,
+        /// Width of the viewport.
+        width: f32,
+        /// Height of the viewport.
+        height: f32,
+    },
+}
+
+impl FloatViewport {
+    /// Returns the `(left, bottom, width, height)` of the viewport in pixels when drawing to a
+    /// surface of the given dimensions.
+    #[inline]
+    pub fn to_pixels(&self, surface_dimensions: (u32, u32)) -> (f32, f32, f32, f32) {
+        match *self {
+            FloatViewport::Pixels { left, bottom, width, height } => (left, bottom, width, height),
+            FloatViewport::Relative { left, bottom, width, height } => {
+                let (w, h) = (surface_dimensions.0 as f32, surface_dimensions.1 as f32);
+                (left * w, bottom * h, width * w, height * h)
+            },
+        }
+    }
+}
+
 /// Represents the parameters to use when drawing.
 ///
 /// Example:
@@ -255,6 +333,16 @@ pub struct DrawParameters<'a> {
     /// being written.
     pub blend: Blend,
 
+    /// The constant color that is used by the `ConstantColor`, `OneMinusConstantColor`,
+    /// `ConstantAlpha` and `OneMinusConstantAlpha` blending factors.
+    ///
+    /// If this is `Some`, the value is passed to `glBlendColor` whatever the blending function,
+    /// and it takes precedence over `blend.constant_value`. If this is `None`, the constant of
+    /// `blend` is used when one of the factors requires it. The default value is `None`.
+    ///
+    /// OpenGL only has one blend constant for all the color attachments.
+    pub blend_constant: Option<(f32, f32, f32, f32)>,
+
     /// Allows you to disable some color components.
     ///
     /// This affects all attachments to the framebuffer. It's at the same level as the
@@ -265,6 +353,17 @@ pub struct DrawParameters<'a> {
     /// is `(true, true, true, true)`.
     pub color_mask: (bool, bool, bool, bool),
 
+    /// Allows you to disable some color components of each attachment individually.
+    ///
+    /// The element `i` of the slice is the mask of the `i`th draw buffer, which for a
+    /// `MultiOutputFrameBuffer` is the `i`th output. The draw buffers that have no element in
+    /// the slice use `color_mask`. This is useful for example to write only to some attachments
+    /// of a framebuffer with multiple render targets.
+    ///
+    /// Requires OpenGL 3.0, OpenGL ES 3.2, `GL_EXT_draw_buffers2`, `GL_EXT_draw_buffers_indexed`
+    /// or `GL_OES_draw_buffers_indexed`. The default value is `None`.
+    pub color_masks: Option<&'a [(bool, bool, bool, bool)]>,
+
     /// Width in pixels of the lines to draw when drawing lines.
     ///
     /// `None` means "don't care". Use this when you don't draw lines.
@@ -318,6 +417,12 @@ pub struct DrawParameters<'a> {
     /// `None` means "use the whole surface".
     pub viewport: Option<Rect>,
 
+    /// Same as `viewport`, but with coordinates that can be fractional or relative to the
+    /// dimensions of the surface. Default is `None`.
+    ///
+    /// If set, this value takes precedence over `viewport`.
+    pub float_viewport: Option<FloatViewport>,
+
     /// If specified, only pixels in this rect will be displayed. Default is `None`.
     ///
     /// This is different from a viewport. The image will stretch to fill the viewport, but
@@ -384,7 +489,7 @@ pub struct DrawParameters<'a> {
     /// This field is useless if you're not using a geometry shader or tessellation shader.
     ///
     /// Since this is purely an optimization, this parameter is ignored if the backend doesn't
-    /// support it.
+    /// support it. Check `Capabilities::primitive_bounding_box` to know whether it is supported.
     pub primitive_bounding_box: (Range<f32>, Range<f32>, Range<f32>, Range<f32>),
     
     /// If enabled, will split the index buffer (if any is used in the draw call) 
@@ -393,6 +498,17 @@ pub struct DrawParameters<'a> {
     /// If the backend does not support GL_PRIMITIVE_RESTART_FIXED_INDEX, an Error 
     /// of type `FixedIndexRestartingNotSupported` will be returned.
     pub primitive_restart_index: bool,
+
+    /// The tessellation levels to use if the program has a tessellation evaluation shader but
+    /// no tessellation control shader.
+    ///
+    /// If `None`, the levels that were used during the previous draw are kept. OpenGL's initial
+    /// value is `1.0` for all the levels. Setting this to `Some` when the backend doesn't
+    /// support tessellation results in a `TessellationNotSupported` error.
+    ///
+    /// OpenGL ES doesn't support default tessellation levels. The program must then have a
+    /// tessellation control shader.
+    pub tessellation_levels: Option<TessellationLevels>,
 }
 
 /// Condition whether to render or not.
@@ -439,7 +555,9 @@ impl<'a> Default for DrawParameters<'a> {
             depth: Depth::default(),
             stencil: Default::default(),
             blend: Default::default(),
+            blend_constant: None,
             color_mask: (true, true, true, true),
+            color_masks: None,
             line_width: None,
             point_size: None,
             backface_culling: BackfaceCullingMode::CullingDisabled,
@@ -448,6 +566,7 @@ impl<'a> Default for DrawParameters<'a> {
             multisampling: true,
             dithering: true,
             viewport: None,
+            float_viewport: None,
             scissor: None,
             draw_primitives: true,
             samples_passed_query: None,
@@ -460,6 +579,7 @@ impl<'a> Default for DrawParameters<'a> {
             provoking_vertex: ProvokingVertex::LastVertex,
             primitive_bounding_box: (-1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0),
             primitive_restart_index: false,
+            tessellation_levels: None,
         }
     }
 }
@@ -487,16 +607,16 @@ pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters
 {
     try!(depth::sync_depth(ctxt, &draw_parameters.depth));
     stencil::sync_stencil(ctxt, &draw_parameters.stencil);
-    try!(blend::sync_blending(ctxt, draw_parameters.blend));
-    sync_color_mask(ctxt, draw_parameters.color_mask);
+    try!(blend::sync_blending(ctxt, draw_parameters.blend, draw_parameters.blend_constant));
+    try!(sync_color_mask(ctxt, draw_parameters.color_mask, draw_parameters.color_masks));
     sync_line_width(ctxt, draw_parameters.line_width);
     sync_point_size(ctxt, draw_parameters.point_size);
     sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
     try!(sync_clip_planes_bitmask(ctxt, draw_parameters.clip_planes_bitmask));
     sync_multisampling(ctxt, draw_parameters.multisampling);
     sync_dithering(ctxt, draw_parameters.dithering);
-    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
-                          dimensions);
+    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.float_viewport,
+                          draw_parameters.scissor, dimensions);
     try!(sync_rasterizer_discard(ctxt, draw_parameters.draw_primitives));
     try!(sync_queries(ctxt, draw_parameters.samples_passed_query,
                       draw_parameters.time_elapsed_query,
@@ -507,25 +627,83 @@ pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters
     try!(sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex));
     sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
     try!(sync_primitive_restart_index(ctxt, draw_parameters.primitive_restart_index));
+    try!(sync_tessellation_levels(ctxt, draw_parameters.tessellation_levels));
 
     Ok(())
 }
 
-fn sync_color_mask(ctxt: &mut context::CommandContext, mask: (bool, bool, bool, bool)) {
-    let mask = (
-        if mask.0 { 1 } else { 0 },
-        if mask.1 { 1 } else { 0 },
-        if mask.2 { 1 } else { 0 },
-        if mask.3 { 1 } else { 0 },
-    );
+fn sync_color_mask(ctxt: &mut context::CommandContext, mask: (bool, bool, bool, bool),
+                   masks: Option<&[(bool, bool, bool, bool)]>) -> Result<(), DrawError>
+{
+    #[inline]
+    fn to_gl(mask: (bool, bool, bool, bool)) -> (gl::types::GLboolean, gl::types::GLboolean,
+                                                 gl::types::GLboolean, gl::types::GLboolean)
+    {
+        (
+            if mask.0 { 1 } else { 0 },
+            if mask.1 { 1 } else { 0 },
+            if mask.2 { 1 } else { 0 },
+            if mask.3 { 1 } else { 0 },
+        )
+    }
+
+    let mask = to_gl(mask);
 
-    if ctxt.state.color_mask != mask {
+    if ctxt.state.color_mask != mask || ctxt.state.color_mask_indexed {
         unsafe {
             ctxt.gl.ColorMask(mask.0, mask.1, mask.2, mask.3);
         }
 
         ctxt.state.color_mask = mask;
+        ctxt.state.color_mask_indexed = false;
+    }
+
+    let masks = match masks {
+        Some(m) => m,
+        None => return Ok(())
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+         ctxt.extensions.gl_ext_draw_buffers2 || ctxt.extensions.gl_oes_draw_buffers_indexed ||
+         ctxt.extensions.gl_ext_draw_buffers_indexed)
+    {
+        return Err(DrawError::ColorMasksNotSupported);
     }
+
+    if masks.len() > ctxt.capabilities.max_draw_buffers as usize {
+        return Err(DrawError::TooManyColorMasks);
+    }
+
+    for (index, &buffer_mask) in masks.iter().enumerate() {
+        let buffer_mask = to_gl(buffer_mask);
+        if buffer_mask == mask {
+            continue;
+        }
+
+        let index = index as gl::types::GLuint;
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2)
+            {
+                ctxt.gl.ColorMaski(index, buffer_mask.0, buffer_mask.1, buffer_mask.2,
+                                   buffer_mask.3);
+            } else if ctxt.extensions.gl_ext_draw_buffers2 {
+                ctxt.gl.ColorMaskIndexedEXT(index, buffer_mask.0, buffer_mask.1, buffer_mask.2,
+                                            buffer_mask.3);
+            } else if ctxt.extensions.gl_oes_draw_buffers_indexed {
+                ctxt.gl.ColorMaskiOES(index, buffer_mask.0, buffer_mask.1, buffer_mask.2,
+                                      buffer_mask.3);
+            } else {
+                ctxt.gl.ColorMaskiEXT(index, buffer_mask.0, buffer_mask.1, buffer_mask.2,
+                                      buffer_mask.3);
+            }
+        }
+
+        ctxt.state.color_mask_indexed = true;
+    }
+
+    Ok(())
 }
 
 fn sync_line_width(ctxt: &mut context::CommandContext, line_width: Option<f32>) {
@@ -598,8 +776,7 @@ fn sync_polygon_mode(ctxt: &mut context::CommandContext, backface_culling: Backf
 fn sync_clip_planes_bitmask(ctxt: &mut context::CommandContext, clip_planes_bitmask: u32)
                             -> Result<(), DrawError> {
     unsafe {
-        let mut max_clip_planes: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetIntegerv(gl::MAX_CLIP_DISTANCES, &mut max_clip_planes);
+        let max_clip_planes: gl::types::GLint = get_integer(ctxt.gl, gl::MAX_CLIP_DISTANCES);
         for i in 0..32 {
             if clip_planes_bitmask & (1 << i) != 0 {
                 if i < max_clip_planes {
@@ -646,10 +823,43 @@ fn sync_dithering(ctxt: &mut context::CommandContext, dithering: bool) {
 }
 
 fn sync_viewport_scissor(ctxt: &mut context::CommandContext, viewport: Option<Rect>,
-                         scissor: Option<Rect>, surface_dimensions: (u32, u32))
+                         float_viewport: Option<FloatViewport>, scissor: Option<Rect>,
+                         surface_dimensions: (u32, u32))
 {
     // viewport
-    if let Some(viewport) = viewport {
+    if let Some(float_viewport) = float_viewport {
+        let viewport = float_viewport.to_pixels(surface_dimensions);
+
+        assert!(viewport.2 <= ctxt.capabilities.max_viewport_dims.0 as f32,
+                "Viewport dimensions are too large");
+        assert!(viewport.3 <= ctxt.capabilities.max_viewport_dims.1 as f32,
+                "Viewport dimensions are too large");
+
+        if ctxt.version >= &Version(Api::Gl, 4, 1) || ctxt.extensions.gl_arb_viewport_array ||
+           ctxt.extensions.gl_nv_viewport_array2
+        {
+            if ctxt.state.viewport_indexed != Some(viewport) {
+                unsafe {
+                    ctxt.gl.ViewportIndexedf(0, viewport.0, viewport.1, viewport.2, viewport.3);
+                }
+                ctxt.state.viewport_indexed = Some(viewport);
+                ctxt.state.viewport = None;
+            }
+
+        } else {
+            let viewport = (viewport.0.round() as gl::types::GLint,
+                            viewport.1.round() as gl::types::GLint,
+                            viewport.2.round() as gl::types::GLsizei,
+                            viewport.3.round() as gl::types::GLsizei);
+
+            if ctxt.state.viewport != Some(viewport) {
+                unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
+                ctxt.state.viewport = Some(viewport);
+                ctxt.state.viewport_indexed = None;
+            }
+        }
+
+    } else if let Some(viewport) = viewport {
         assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
                 "Viewport dimensions are too large");
         assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
@@ -662,6 +872,7 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext, viewport: Option<Re
         if ctxt.state.viewport != Some(viewport) {
             unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
             ctxt.state.viewport = Some(viewport);
+            ctxt.state.viewport_indexed = None;
         }
 
     } else {
@@ -676,6 +887,7 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext, viewport: Option<Re
         if ctxt.state.viewport != Some(viewport) {
             unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
             ctxt.state.viewport = Some(viewport);
+            ctxt.state.viewport_indexed = None;
         }
     }
 
@@ -898,7 +1110,7 @@ fn sync_primitive_bounding_box(ctxt: &mut context::CommandContext,
     let value = (bb.0.start, bb.1.start, bb.2.start, bb.3.start,
                  bb.0.end, bb.1.end, bb.2.end, bb.3.end);
 
-    if ctxt.state.primitive_bounding_box == value {
+    if !ctxt.capabilities.primitive_bounding_box || ctxt.state.primitive_bounding_box == value {
         return;
     }
 
@@ -948,5 +1160,34 @@ fn sync_primitive_restart_index(ctxt: &mut context::CommandContext,
     }
     
 
+    Ok(())
+}
+
+fn sync_tessellation_levels(ctxt: &mut context::CommandContext, levels: Option<TessellationLevels>)
+                            -> Result<(), DrawError>
+{
+    let levels = match levels {
+        Some(l) => l,
+        None => return Ok(())
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.extensions.gl_arb_tessellation_shader) {
+        return Err(DrawError::TessellationNotSupported);
+    }
+
+    if ctxt.state.patch_default_outer_level != levels.outer {
+        unsafe {
+            ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_OUTER_LEVEL, levels.outer.as_ptr());
+        }
+        ctxt.state.patch_default_outer_level = levels.outer;
+    }
+
+    if ctxt.state.patch_default_inner_level != levels.inner {
+        unsafe {
+            ctxt.gl.PatchParameterfv(gl::PATCH_DEFAULT_INNER_LEVEL, levels.inner.as_ptr());
+        }
+        ctxt.state.patch_default_inner_level = levels.inner;
+    }
+
     Ok(())
 }
@@ -9,7 +9,6 @@ use QueryExt;
 
 use std::cell::Cell;
 use std::fmt;
-use std::mem;
 use std::rc::Rc;
 use std::error::Error;
 
@@ -22,6 +21,9 @@ use gl;
 use version::Api;
 use version::Version;
 
+/// Maximum number of unused query objects that are kept around by the context.
+const MAX_POOLED_QUERY_OBJECTS: usize = 256;
+
 pub struct RawQuery {
     context: Rc<Context>,
     id: gl::types::GLuint,
@@ -109,12 +111,24 @@ impl RawQuery {
                   where F: Facade
     {
         let context = facade.get_context().clone();
-        let ctxt = facade.get_context().make_current();
+        let mut ctxt = facade.get_context().make_current();
+
+        // reusing a query object of the same type from the pool if possible
+        let target = ty.to_glenum();
+        if let Some(pos) = ctxt.query_objects_pool.iter().position(|&(t, _)| t == target) {
+            let (_, id) = ctxt.query_objects_pool.swap_remove(pos);
+            return Ok(RawQuery {
+                context: context,
+                id: id,
+                ty: ty,
+                has_been_used: Cell::new(false),
+            });
+        }
 
         // FIXME: handle Timestamp separately
 
         let id = unsafe {
-            let mut id = mem::uninitialized();
+            let mut id = 0;
 
             if ctxt.version >= &Version(Api::Gl, 3, 3) {
                 match ty {
@@ -211,7 +225,7 @@ impl RawQuery {
         Buffer::<u8>::unbind_query(&mut ctxt);
 
         unsafe {
-            let mut value = mem::uninitialized();
+            let mut value = 0;
 
             if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                ctxt.version >= &Version(Api::GlEs, 3, 0)
@@ -248,7 +262,7 @@ impl RawQuery {
         Buffer::<u8>::unbind_query(&mut ctxt);
 
         unsafe {
-            let mut value = mem::uninitialized();
+            let mut value = 0;
             self.raw_get_u32(&mut ctxt, &mut value);
             value
         }
@@ -313,12 +327,12 @@ impl RawQuery {
         Buffer::<u8>::unbind_query(&mut ctxt);
 
         unsafe {
-            let mut value = mem::uninitialized();
+            let mut value = 0;
             if let Ok(_) = self.raw_get_u64(&mut ctxt, &mut value) {
                 return value;
             }
 
-            let mut value = mem::uninitialized();
+            let mut value = 0;
             self.raw_get_u32(&mut ctxt, &mut value);
             value as u64
         }
@@ -389,6 +403,12 @@ impl Drop for RawQuery {
             }
         }
 
+        // putting the query object back in the pool so that it can be reused
+        if ctxt.query_objects_pool.len() < MAX_POOLED_QUERY_OBJECTS {
+            ctxt.query_objects_pool.push((self.ty.to_glenum(), self.id));
+            return;
+        }
+
         unsafe {
             if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                ctxt.version >= &Version(Api::GlEs, 3, 0)
@@ -861,6 +881,10 @@ impl_helper!(TimeElapsedQuery, u32, get_u32);
 /// However if the backend doesn't support conservative queries, glium will automatically fall
 /// back to a non-conservative query. If the backend doesn't support either types but supports
 /// `GL_SAMPLES_PASSED`, then glium will automatically use a `GL_SAMPLES_PASSED` query instead.
+///
+/// Destroying a query doesn't destroy the underlying query object. Instead it is kept by the
+/// context and reused the next time a query of the same type is created. Creating one query per
+/// object and per frame for occlusion tests is therefore cheap.
 #[derive(Debug)]
 pub struct AnySamplesPassedQuery {
     query: RawQuery,
@@ -889,6 +913,18 @@ impl AnySamplesPassedQuery {
             return Err(QueryCreationError::NotSupported);
         }
     }
+
+    /// Returns true if the query is a `GL_ANY_SAMPLES_PASSED_CONSERVATIVE` query.
+    ///
+    /// This can be `false` even if you requested a conservative query, if the backend doesn't
+    /// support them.
+    #[inline]
+    pub fn is_conservative(&self) -> bool {
+        match self.query.ty {
+            QueryType::AnySamplesPassedConservative => true,
+            _ => false,
+        }
+    }
 }
 
 impl_helper!(AnySamplesPassedQuery, bool, get_bool);
@@ -4,6 +4,7 @@ use ToGlEnum;
 
 /// Describes the parameters that must be used for the stencil operations when drawing.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Stencil {
     /// A comparison against the existing value in the stencil buffer.
     ///
@@ -135,6 +136,7 @@ impl Default for Stencil {
 /// value (`stencil_reference_value_clockwise` or `stencil_reference_value_counter_clockwise`),
 /// `CMP` is the comparison chosen, and `stencil` is the current value in the stencil buffer.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum StencilTest {
     /// The stencil test always passes.
     AlwaysPass,
@@ -181,6 +183,7 @@ pub enum StencilTest {
 
 /// Specificies which operation the GPU will do depending on the result of the stencil test.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[repr(u32)]    // GLenum
 pub enum StencilOperation {
     /// Keeps the value currently in the stencil buffer.
@@ -0,0 +1,383 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+#[cfg(feature = "glutin")]
+use backend::glutin::DisplayCreationError;
+use buffer::{BufferCreationError, ReadError, CopyError};
+use capture::ScreenshotError;
+use culling::GpuCullerCreationError;
+use debug_draw::{DebugDrawCreationError, DebugDrawError};
+use draw_parameters::QueryCreationError;
+use framebuffer::{ValidationError, OffscreenTargetCreationError, RenderBufferCreationError};
+use framebuffer::{BufferSelectionError, PingPongTargetsCreationError, BlitError};
+use framebuffer::TargetPoolError;
+use index::BufferCreationError as IndexBufferCreationError;
+use index::generate::GenerationError as IndexGenerationError;
+use interop::ImportError;
+use material::MaterialBufferCreationError;
+use occlusion::OcclusionCullerCreationError;
+use pacing::SwapIntervalError;
+use particles::ParticleSystemCreationError;
+use picking::PickingError;
+use pipeline::PipelineCreationError;
+use post_process::{FullscreenPassCreationError, PostProcessError};
+#[cfg(target_os = "windows")]
+use interop::d3d::D3DInteropError;
+use program::{ProgramCreationError, ProgramChooserCreationError, GetBinaryError};
+use program::BlockBindingError;
+use render_graph::RenderGraphError;
+use texture::{TextureCreationError, GetFormatError};
+use texture::buffer_texture::CreationError as BufferTextureCreationError;
+use texture::buffer_texture::ImageUnitError;
+use texture::external::CreationError as ExternalTextureCreationError;
+#[cfg(target_os = "macos")]
+use texture::iosurface::CreationError as IOSurfaceTextureCreationError;
+use uniforms::UniformHandleError;
+use vertex::BufferCreationError as VertexBufferCreationError;
+
+use ContextCreationError;
+use DrawError;
+use IncompatibleOpenGl;
+use SwapBuffersError;
+
+/// Any error that can be returned by glium.
+///
+/// Each function of glium returns its own error type, which describes precisely what can go
+/// wrong. Applications that don't need to handle each error differently can instead convert them
+/// all to this type with `try!` or `From`.
+///
+/// Displaying this error only gives a short message, like "Failed to draw". The original error
+/// and its details are always available through `source()`.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to create a display.
+    #[cfg(feature = "glutin")]
+    DisplayCreationError(DisplayCreationError),
+
+    /// The OpenGL implementation is too old.
+    IncompatibleOpenGl(IncompatibleOpenGl),
+
+    /// Failed to create a context.
+    ContextCreationError(ContextCreationError),
+
+    /// Failed to swap buffers.
+    SwapBuffersError(SwapBuffersError),
+
+    /// Failed to change the swap interval.
+    SwapIntervalError(SwapIntervalError),
+
+    /// Failed to draw.
+    DrawError(DrawError),
+
+    /// Failed to create a program.
+    ProgramCreationError(ProgramCreationError),
+
+    /// Failed to create a program with the `program!` macro.
+    ProgramChooserCreationError(ProgramChooserCreationError),
+
+    /// Failed to get the binary of a program.
+    GetBinaryError(GetBinaryError),
+
+    /// Failed to assign a binding point to a block of a program.
+    BlockBindingError(BlockBindingError),
+
+    /// Failed to create a buffer.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to create a vertex buffer.
+    VertexBufferCreationError(VertexBufferCreationError),
+
+    /// Failed to create an index buffer.
+    IndexBufferCreationError(IndexBufferCreationError),
+
+    /// Failed to generate an index buffer.
+    IndexGenerationError(IndexGenerationError),
+
+    /// Failed to read the content of a buffer.
+    BufferReadError(ReadError),
+
+    /// Failed to copy the content of a buffer.
+    BufferCopyError(CopyError),
+
+    /// Failed to create a texture.
+    TextureCreationError(TextureCreationError),
+
+    /// Failed to create a buffer texture.
+    BufferTextureCreationError(BufferTextureCreationError),
+
+    /// Failed to bind a buffer texture as an image.
+    ImageUnitError(ImageUnitError),
+
+    /// Failed to create an external texture.
+    ExternalTextureCreationError(ExternalTextureCreationError),
+
+    /// Failed to create an IOSurface texture.
+    #[cfg(target_os = "macos")]
+    IOSurfaceTextureCreationError(IOSurfaceTextureCreationError),
+
+    /// Failed to get the format of a texture.
+    GetFormatError(GetFormatError),
+
+    /// The attachments of a framebuffer are invalid.
+    ValidationError(ValidationError),
+
+    /// Failed to create a render buffer.
+    RenderBufferCreationError(RenderBufferCreationError),
+
+    /// Failed to create an offscreen target.
+    OffscreenTargetCreationError(OffscreenTargetCreationError),
+
+    /// Failed to create or resize ping-pong targets.
+    PingPongTargetsCreationError(PingPongTargetsCreationError),
+
+    /// Failed to acquire a texture from a target pool.
+    TargetPoolError(TargetPoolError),
+
+    /// Failed to execute a render graph.
+    RenderGraphError(RenderGraphError),
+
+    /// Failed to choose the draw or read buffer of the default framebuffer.
+    BufferSelectionError(BufferSelectionError),
+
+    /// Failed to blit between two surfaces.
+    BlitError(BlitError),
+
+    /// Failed to create a query.
+    QueryCreationError(QueryCreationError),
+
+    /// Failed to get a uniform handle.
+    UniformHandleError(UniformHandleError),
+
+    /// Failed to import a memory object or a semaphore.
+    ImportError(ImportError),
+
+    /// Failed to save a screenshot.
+    ScreenshotError(ScreenshotError),
+
+    /// Failed to create the target of a picker.
+    PickingError(PickingError),
+
+    /// Failed to create a buffer of materials.
+    MaterialBufferCreationError(MaterialBufferCreationError),
+
+    /// Failed to create a pipeline descriptor.
+    PipelineCreationError(PipelineCreationError),
+
+    /// Failed to create an occlusion culler.
+    OcclusionCullerCreationError(OcclusionCullerCreationError),
+
+    /// Failed to create a particle system.
+    ParticleSystemCreationError(ParticleSystemCreationError),
+
+    /// Failed to create a `GpuCuller`.
+    GpuCullerCreationError(GpuCullerCreationError),
+
+    /// Failed to create a `DebugDraw`.
+    DebugDrawCreationError(DebugDrawCreationError),
+
+    /// Failed to draw the primitives of a `DebugDraw`.
+    DebugDrawError(DebugDrawError),
+
+    /// Failed to create a `FullscreenPass`.
+    FullscreenPassCreationError(FullscreenPassCreationError),
+
+    /// Failed to apply a chain of passes.
+    PostProcessError(PostProcessError),
+
+    /// Failed to share a texture with Direct3D.
+    #[cfg(target_os = "windows")]
+    D3DInteropError(D3DInteropError),
+}
+
+impl Error {
+    /// Returns a short message describing what failed. The details are in the wrapped error.
+    fn message(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "glutin")]
+            Error::DisplayCreationError(_) => "Failed to create a display",
+            Error::IncompatibleOpenGl(_) => "The OpenGL implementation is too old",
+            Error::ContextCreationError(_) => "Failed to create a context",
+            Error::SwapBuffersError(_) => "Failed to swap buffers",
+            Error::SwapIntervalError(_) => "Failed to change the swap interval",
+            Error::DrawError(_) => "Failed to draw",
+            Error::ProgramCreationError(_) => "Failed to create a program",
+            Error::ProgramChooserCreationError(_) =>
+                "Failed to create a program with the `program!` macro",
+            Error::GetBinaryError(_) => "Failed to get the binary of a program",
+            Error::BlockBindingError(_) =>
+                "Failed to assign a binding point to a block of a program",
+            Error::BufferCreationError(_) => "Failed to create a buffer",
+            Error::VertexBufferCreationError(_) => "Failed to create a vertex buffer",
+            Error::IndexBufferCreationError(_) => "Failed to create an index buffer",
+            Error::IndexGenerationError(_) => "Failed to generate an index buffer",
+            Error::BufferReadError(_) => "Failed to read the content of a buffer",
+            Error::BufferCopyError(_) => "Failed to copy the content of a buffer",
+            Error::TextureCreationError(_) => "Failed to create a texture",
+            Error::BufferTextureCreationError(_) => "Failed to create a buffer texture",
+            Error::ImageUnitError(_) => "Failed to bind a buffer texture as an image",
+            Error::ExternalTextureCreationError(_) => "Failed to create an external texture",
+            #[cfg(target_os = "macos")]
+            Error::IOSurfaceTextureCreationError(_) => "Failed to create an IOSurface texture",
+            Error::GetFormatError(_) => "Failed to get the format of a texture",
+            Error::ValidationError(_) => "The attachments of a framebuffer are invalid",
+            Error::RenderBufferCreationError(_) => "Failed to create a render buffer",
+            Error::OffscreenTargetCreationError(_) => "Failed to create an offscreen target",
+            Error::PingPongTargetsCreationError(_) =>
+                "Failed to create or resize ping-pong targets",
+            Error::TargetPoolError(_) => "Failed to acquire a texture from a target pool",
+            Error::RenderGraphError(_) => "Failed to execute a render graph",
+            Error::BufferSelectionError(_) =>
+                "Failed to choose the draw or read buffer of the default framebuffer",
+            Error::BlitError(_) => "Failed to blit between two surfaces",
+            Error::QueryCreationError(_) => "Failed to create a query",
+            Error::UniformHandleError(_) => "Failed to get a uniform handle",
+            Error::ImportError(_) => "Failed to import a memory object or a semaphore",
+            Error::ScreenshotError(_) => "Failed to save a screenshot",
+            Error::PickingError(_) => "Failed to create the target of a picker",
+            Error::MaterialBufferCreationError(_) => "Failed to create a buffer of materials",
+            Error::PipelineCreationError(_) => "Failed to create a pipeline descriptor",
+            Error::OcclusionCullerCreationError(_) => "Failed to create an occlusion culler",
+            Error::ParticleSystemCreationError(_) => "Failed to create a particle system",
+            Error::GpuCullerCreationError(_) => "Failed to create a `GpuCuller`",
+            Error::DebugDrawCreationError(_) => "Failed to create a `DebugDraw`",
+            Error::DebugDrawError(_) => "Failed to draw the primitives of a `DebugDraw`",
+            Error::FullscreenPassCreationError(_) => "Failed to create a `FullscreenPass`",
+            Error::PostProcessError(_) => "Failed to apply a chain of passes",
+            #[cfg(target_os = "windows")]
+            Error::D3DInteropError(_) => "Failed to share a texture with Direct3D",
+        }
+    }
+
+    /// Returns the wrapped error.
+    fn inner(&self) -> &(StdError + 'static) {
+        match *self {
+            #[cfg(feature = "glutin")]
+            Error::DisplayCreationError(ref err) => err,
+            Error::IncompatibleOpenGl(ref err) => err,
+            Error::ContextCreationError(ref err) => err,
+            Error::SwapBuffersError(ref err) => err,
+            Error::SwapIntervalError(ref err) => err,
+            Error::DrawError(ref err) => err,
+            Error::ProgramCreationError(ref err) => err,
+            Error::ProgramChooserCreationError(ref err) => err,
+            Error::GetBinaryError(ref err) => err,
+            Error::BlockBindingError(ref err) => err,
+            Error::BufferCreationError(ref err) => err,
+            Error::VertexBufferCreationError(ref err) => err,
+            Error::IndexBufferCreationError(ref err) => err,
+            Error::IndexGenerationError(ref err) => err,
+            Error::BufferReadError(ref err) => err,
+            Error::BufferCopyError(ref err) => err,
+            Error::TextureCreationError(ref err) => err,
+            Error::BufferTextureCreationError(ref err) => err,
+            Error::ImageUnitError(ref err) => err,
+            Error::ExternalTextureCreationError(ref err) => err,
+            #[cfg(target_os = "macos")]
+            Error::IOSurfaceTextureCreationError(ref err) => err,
+            Error::GetFormatError(ref err) => err,
+            Error::ValidationError(ref err) => err,
+            Error::RenderBufferCreationError(ref err) => err,
+            Error::OffscreenTargetCreationError(ref err) => err,
+            Error::PingPongTargetsCreationError(ref err) => err,
+            Error::TargetPoolError(ref err) => err,
+            Error::RenderGraphError(ref err) => err,
+            Error::BufferSelectionError(ref err) => err,
+            Error::BlitError(ref err) => err,
+            Error::QueryCreationError(ref err) => err,
+            Error::UniformHandleError(ref err) => err,
+            Error::ImportError(ref err) => err,
+            Error::ScreenshotError(ref err) => err,
+            Error::PickingError(ref err) => err,
+            Error::MaterialBufferCreationError(ref err) => err,
+            Error::PipelineCreationError(ref err) => err,
+            Error::OcclusionCullerCreationError(ref err) => err,
+            Error::ParticleSystemCreationError(ref err) => err,
+            Error::GpuCullerCreationError(ref err) => err,
+            Error::DebugDrawCreationError(ref err) => err,
+            Error::DebugDrawError(ref err) => err,
+            Error::FullscreenPassCreationError(ref err) => err,
+            Error::PostProcessError(ref err) => err,
+            #[cfg(target_os = "windows")]
+            Error::D3DInteropError(ref err) => err,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.message())
+    }
+}
+
+impl StdError for Error {
+    #[inline]
+    fn description(&self) -> &str {
+        self.message()
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        Some(self.inner())
+    }
+}
+
+macro_rules! impl_from_error {
+    ($ty:ty, $variant:ident) => (
+        impl From<$ty> for Error {
+            #[inline]
+            fn from(err: $ty) -> Error {
+                Error::$variant(err)
+            }
+        }
+    );
+}
+
+#[cfg(feature = "glutin")]
+impl_from_error!(DisplayCreationError, DisplayCreationError);
+impl_from_error!(IncompatibleOpenGl, IncompatibleOpenGl);
+impl_from_error!(ContextCreationError, ContextCreationError);
+impl_from_error!(SwapBuffersError, SwapBuffersError);
+impl_from_error!(SwapIntervalError, SwapIntervalError);
+impl_from_error!(DrawError, DrawError);
+impl_from_error!(ProgramCreationError, ProgramCreationError);
+impl_from_error!(ProgramChooserCreationError, ProgramChooserCreationError);
+impl_from_error!(GetBinaryError, GetBinaryError);
+impl_from_error!(BlockBindingError, BlockBindingError);
+impl_from_error!(BufferCreationError, BufferCreationError);
+impl_from_error!(VertexBufferCreationError, VertexBufferCreationError);
+impl_from_error!(IndexBufferCreationError, IndexBufferCreationError);
+impl_from_error!(IndexGenerationError, IndexGenerationError);
+impl_from_error!(ReadError, BufferReadError);
+impl_from_error!(CopyError, BufferCopyError);
+impl_from_error!(TextureCreationError, TextureCreationError);
+impl_from_error!(BufferTextureCreationError, BufferTextureCreationError);
+impl_from_error!(ImageUnitError, ImageUnitError);
+impl_from_error!(ExternalTextureCreationError, ExternalTextureCreationError);
+#[cfg(target_os = "macos")]
+impl_from_error!(IOSurfaceTextureCreationError, IOSurfaceTextureCreationError);
+impl_from_error!(GetFormatError, GetFormatError);
+impl_from_error!(ValidationError, ValidationError);
+impl_from_error!(RenderBufferCreationError, RenderBufferCreationError);
+impl_from_error!(OffscreenTargetCreationError, OffscreenTargetCreationError);
+impl_from_error!(PingPongTargetsCreationError, PingPongTargetsCreationError);
+impl_from_error!(TargetPoolError, TargetPoolError);
+impl_from_error!(RenderGraphError, RenderGraphError);
+impl_from_error!(BufferSelectionError, BufferSelectionError);
+impl_from_error!(BlitError, BlitError);
+impl_from_error!(QueryCreationError, QueryCreationError);
+impl_from_error!(UniformHandleError, UniformHandleError);
+impl_from_error!(ImportError, ImportError);
+impl_from_error!(ScreenshotError, ScreenshotError);
+impl_from_error!(PickingError, PickingError);
+impl_from_error!(MaterialBufferCreationError, MaterialBufferCreationError);
+impl_from_error!(PipelineCreationError, PipelineCreationError);
+impl_from_error!(OcclusionCullerCreationError, OcclusionCullerCreationError);
+impl_from_error!(ParticleSystemCreationError, ParticleSystemCreationError);
+impl_from_error!(GpuCullerCreationError, GpuCullerCreationError);
+impl_from_error!(DebugDrawCreationError, DebugDrawCreationError);
+impl_from_error!(DebugDrawError, DebugDrawError);
+impl_from_error!(FullscreenPassCreationError, FullscreenPassCreationError);
+impl_from_error!(PostProcessError, PostProcessError);
+#[cfg(target_os = "windows")]
+impl_from_error!(D3DInteropError, D3DInteropError);
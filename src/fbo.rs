@@ -64,6 +64,7 @@ use GlObject;
 use TextureExt;
 
 use texture::CubeLayer;
+use texture::Dimensions;
 use texture::TextureAnyImage;
 use texture::TextureAnyMipmap;
 use texture::TextureKind;
@@ -85,6 +86,68 @@ pub fn is_dimensions_mismatch_supported<C: ?Sized>(context: &C) -> bool where C:
     context.get_extensions().gl_arb_framebuffer_object
 }
 
+/// Returns true if the backend supports attaching all the layers of an array, cubemap or 3D
+/// texture at once.
+///
+/// The layer to draw to is then chosen by the geometry shader with `gl_Layer`.
+#[inline]
+pub fn is_layered_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 3, 2) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+    context.get_extensions().gl_arb_direct_state_access ||
+    (context.get_extensions().gl_ext_direct_state_access &&
+     context.get_extensions().gl_ext_geometry_shader4)
+}
+
+/// Returns true if the backend supports rendering to textures through an implicit multisample
+/// buffer that is resolved automatically.
+///
+/// This is provided by `GL_EXT_multisampled_render_to_texture`, which is usually only available
+/// on OpenGL ES.
+#[inline]
+pub fn is_implicit_multisampling_supported<C: ?Sized>(context: &C) -> bool
+    where C: CapabilitiesSource
+{
+    context.get_extensions().gl_ext_multisampled_render_to_texture
+}
+
+/// Returns true if the backend supports choosing the color attachments that are drawn to with
+/// `glDrawBuffers`.
+///
+/// Otherwise only the first color attachment of a framebuffer can be drawn to.
+#[inline]
+pub fn is_draw_buffers_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 2, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_direct_state_access ||
+    context.get_extensions().gl_arb_draw_buffers ||
+    context.get_extensions().gl_ati_draw_buffers ||
+    context.get_extensions().gl_ext_draw_buffers
+}
+
+/// Returns true if the backend supports copying pixels between framebuffers with
+/// `glBlitFramebuffer`.
+///
+/// OpenGL ES 2 only supports it through `GL_NV_framebuffer_blit`.
+#[inline]
+pub fn is_blit_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 3, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_ext_framebuffer_blit ||
+    context.get_extensions().gl_nv_framebuffer_blit
+}
+
+/// Returns true if the backend supports choosing the color attachment that is read from with
+/// `glReadBuffer`.
+///
+/// Otherwise only the first color attachment of a framebuffer can be read from.
+#[inline]
+pub fn is_read_buffer_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 1, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_direct_state_access
+}
+
 /// Represents the attachments to use for an OpenGL framebuffer.
 #[derive(Clone)]
 pub enum FramebufferAttachments<'a> {
@@ -128,6 +191,25 @@ impl<'a> RegularAttachment<'a> {
 #[derive(Copy, Clone)]
 pub struct LayeredAttachment<'a>(TextureAnyMipmap<'a>);
 
+impl<'a> LayeredAttachment<'a> {
+    /// Builds an attachment that contains all the layers of a mipmap level.
+    #[inline]
+    pub fn new(mipmap: TextureAnyMipmap<'a>) -> LayeredAttachment<'a> {
+        LayeredAttachment(mipmap)
+    }
+
+    /// Returns the number of layers of the attachment. The faces of cubemaps count as layers.
+    fn get_layers(&self) -> u32 {
+        let texture = self.0.get_texture();
+        match texture.get_texture_type() {
+            Dimensions::Texture3d { .. } => self.0.get_depth().unwrap_or(1),
+            Dimensions::Cubemap { .. } => 6,
+            Dimensions::CubemapArray { array_size, .. } => array_size * 6,
+            _ => texture.get_array_size().unwrap_or(1),
+        }
+    }
+}
+
 /// Depth and/or stencil attachment to use.
 #[derive(Copy, Clone)]
 pub enum DepthStencilAttachments<T> {
@@ -166,7 +248,9 @@ impl<'a> FramebufferAttachments<'a> {
                        where C: CapabilitiesSource
     {
         match self {
-            FramebufferAttachments::Regular(a) => FramebufferAttachments::validate_regular(context, a),
+            FramebufferAttachments::Regular(a) => {
+                FramebufferAttachments::validate_regular(context, a, None)
+            },
             FramebufferAttachments::Layered(a) => FramebufferAttachments::validate_layered(context, a),
 
             FramebufferAttachments::Empty { width, height, layers, samples, fixed_samples } => {
@@ -191,7 +275,7 @@ impl<'a> FramebufferAttachments<'a> {
 
                     Ok(ValidatedAttachments {
                         raw: RawAttachments {
-                            color: Vec::new(),
+                            color: SmallVec::new(),
                             depth: None,
                             stencil: None,
                             depth_stencil: None,
@@ -200,11 +284,13 @@ impl<'a> FramebufferAttachments<'a> {
                             default_layers: if context.get_version() <= &Version(Api::GlEs, 3, 1) { None } else { Some(layers.unwrap_or(0)) },
                             default_samples: Some(samples.unwrap_or(0)),
                             default_samples_fixed: Some(fixed_samples),
+                            implicit_samples: None,
                         },
                         dimensions: (width, height),
                         layers: layers,
                         depth_buffer_bits: None,
                         stencil_buffer_bits: None,
+                        samples: samples.unwrap_or(0),
                         marker: PhantomData,
                     })
 
@@ -215,6 +301,33 @@ impl<'a> FramebufferAttachments<'a> {
         }
     }
 
+    /// Same as `validate`, except that the color textures are rendered to through an implicit
+    /// multisample buffer with `samples` samples, which is resolved automatically into the
+    /// texture.
+    ///
+    /// Only the first color attachment can be a texture, and it must be a regular 2D texture.
+    /// Other attachments must be render buffers with the same number of samples.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the attachments are not regular attachments.
+    pub fn validate_implicit_multisample<C: ?Sized>(self, context: &C, samples: u32)
+                                                    -> Result<ValidatedAttachments<'a>,
+                                                              ValidationError>
+        where C: CapabilitiesSource
+    {
+        if !is_implicit_multisampling_supported(context) {
+            return Err(ValidationError::ImplicitMultisamplingNotSupported);
+        }
+
+        match self {
+            FramebufferAttachments::Regular(a) => {
+                FramebufferAttachments::validate_regular(context, a, Some(samples))
+            },
+            _ => panic!("Implicit multisampling is only possible with regular attachments"),
+        }
+    }
+
     fn validate_layered<C: ?Sized>(context: &C, FramebufferSpecificAttachments { colors, depth_stencil }:
                            FramebufferSpecificAttachments<LayeredAttachment<'a>>)
                            -> Result<ValidatedAttachments<'a>, ValidationError>
@@ -222,6 +335,23 @@ impl<'a> FramebufferAttachments<'a> {
     {
         // TODO: make sure that all attachments are layered
 
+        if !is_layered_supported(context) {
+            return Err(ValidationError::LayeredFramebuffersNotSupported);
+        }
+
+        // the number of layers is the minimum of the number of layers of the attachments
+        let layers = colors.iter().map(|&(_, ref a)| a)
+                           .chain(match depth_stencil {
+                               DepthStencilAttachments::None => vec![],
+                               DepthStencilAttachments::DepthAttachment(ref a) => vec![a],
+                               DepthStencilAttachments::StencilAttachment(ref a) => vec![a],
+                               DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) =>
+                                   vec![d, s],
+                               DepthStencilAttachments::DepthStencilAttachment(ref a) => vec![a],
+                           })
+                           .map(|a| a.get_layers())
+                           .min();
+
         macro_rules! handle_tex {
             ($tex:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
                 $num_bits = Some($tex.get_texture().get_internal_format()
@@ -273,7 +403,12 @@ impl<'a> FramebufferAttachments<'a> {
             });
         }
 
-        let max_color_attachments = context.get_capabilities().max_color_attachments;
+        // without `glDrawBuffers`, only the first color attachment can be drawn to
+        let max_color_attachments = if is_draw_buffers_supported(context) {
+            context.get_capabilities().max_color_attachments
+        } else {
+            1
+        };
         if colors.len() > max_color_attachments as usize {
             return Err(ValidationError::TooManyColorAttachments{
                 maximum: max_color_attachments as usize,
@@ -282,7 +417,7 @@ impl<'a> FramebufferAttachments<'a> {
         }
 
         let mut raw_attachments = RawAttachments {
-            color: Vec::with_capacity(colors.len()),
+            color: SmallVec::with_capacity(colors.len()),
             depth: None,
             stencil: None,
             depth_stencil: None,
@@ -291,6 +426,7 @@ impl<'a> FramebufferAttachments<'a> {
             default_layers: None,
             default_samples: None,
             default_samples_fixed: None,
+            implicit_samples: None,
         };
 
         let mut dimensions = None;
@@ -340,15 +476,17 @@ impl<'a> FramebufferAttachments<'a> {
         Ok(ValidatedAttachments {
             raw: raw_attachments,
             dimensions: dimensions,
-            layers: None,       // FIXME: count layers
+            layers: layers,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
+            samples: samples.unwrap_or(0),
             marker: PhantomData,
         })
     }
 
     fn validate_regular<C: ?Sized>(context: &C, FramebufferSpecificAttachments { colors, depth_stencil }:
-                        FramebufferSpecificAttachments<RegularAttachment<'a>>)
+                        FramebufferSpecificAttachments<RegularAttachment<'a>>,
+                        implicit_samples: Option<u32>)
                         -> Result<ValidatedAttachments<'a>, ValidationError>
                         where C: CapabilitiesSource
     {
@@ -363,14 +501,26 @@ impl<'a> FramebufferAttachments<'a> {
                 // TODO: check that internal format is renderable
                 let context = $tex.get_texture().get_context();
 
+                // with implicit multisampling, the texture is rendered to through a buffer
+                // that has the requested number of samples
+                let tex_samples = match implicit_samples {
+                    Some(samples) => {
+                        if $tex.get_texture().get_bind_point() != gl::TEXTURE_2D {
+                            return Err(ValidationError::ImplicitMultisamplingIncompatibleAttachment);
+                        }
+                        samples
+                    },
+                    None => $tex.get_samples().unwrap_or(0),
+                };
+
                 match &mut $samples {
                     &mut Some(samples) => {
-                        if samples != $tex.get_samples().unwrap_or(0) {
+                        if samples != tex_samples {
                             return Err(ValidationError::SamplesCountMismatch);
                         }
                     },
                     s @ &mut None => {
-                        *s = Some($tex.get_samples().unwrap_or(0));
+                        *s = Some(tex_samples);
                     }
                 }
 
@@ -456,7 +606,12 @@ impl<'a> FramebufferAttachments<'a> {
             );
         }
 
-        let max_color_attachments = context.get_capabilities().max_color_attachments;
+        // without `glDrawBuffers`, only the first color attachment can be drawn to
+        let max_color_attachments = if is_draw_buffers_supported(context) {
+            context.get_capabilities().max_color_attachments
+        } else {
+            1
+        };
         if colors.len() > max_color_attachments as usize {
             return Err(ValidationError::TooManyColorAttachments{
                 maximum: max_color_attachments as usize,
@@ -465,7 +620,7 @@ impl<'a> FramebufferAttachments<'a> {
         }
 
         let mut raw_attachments = RawAttachments {
-            color: Vec::with_capacity(colors.len()),
+            color: SmallVec::with_capacity(colors.len()),
             depth: None,
             stencil: None,
             depth_stencil: None,
@@ -474,6 +629,7 @@ impl<'a> FramebufferAttachments<'a> {
             default_layers: None,
             default_samples: None,
             default_samples_fixed: None,
+            implicit_samples: implicit_samples,
         };
 
         let mut dimensions = None;
@@ -481,6 +637,30 @@ impl<'a> FramebufferAttachments<'a> {
         let mut stencil_bits = None;
         let mut samples = None;     // contains `0` if not multisampling and `None` if unknown
 
+        // with implicit multisampling, only the first color attachment can be a texture
+        if implicit_samples.is_some() {
+            let depth_stencil_atchs = match depth_stencil {
+                DepthStencilAttachments::None => [None, None],
+                DepthStencilAttachments::DepthAttachment(ref a) => [Some(a), None],
+                DepthStencilAttachments::StencilAttachment(ref a) => [Some(a), None],
+                DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) => {
+                    [Some(d), Some(s)]
+                },
+                DepthStencilAttachments::DepthStencilAttachment(ref a) => [Some(a), None],
+            };
+
+            let extra_textures = colors.iter().skip(1).map(|&(_, ref a)| a)
+                                       .chain(depth_stencil_atchs.iter().filter_map(|a| *a))
+                                       .any(|a| match a {
+                                           &RegularAttachment::Texture(_) => true,
+                                           &RegularAttachment::RenderBuffer(_) => false,
+                                       });
+
+            if extra_textures {
+                return Err(ValidationError::ImplicitMultisamplingIncompatibleAttachment);
+            }
+        }
+
         for &(index, ref attachment) in colors.iter() {
             if index >= max_color_attachments as u32 {
                 return Err(ValidationError::TooManyColorAttachments{
@@ -529,12 +709,17 @@ impl<'a> FramebufferAttachments<'a> {
             layers: None,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
+            // framebuffers with implicit multisampling are resolved when they are read from
+            samples: if implicit_samples.is_some() { 0 } else { samples.unwrap_or(0) },
             marker: PhantomData,
         })
     }
 }
 
 /// Represents attachments that have been validated and are usable.
+///
+/// Objects of this type are returned by `FboAttachments::get_attachments` and can't be built
+/// outside of glium.
 #[derive(Clone)]
 pub struct ValidatedAttachments<'a> {
     raw: RawAttachments,
@@ -542,6 +727,7 @@ pub struct ValidatedAttachments<'a> {
     layers: Option<u32>,
     depth_buffer_bits: Option<u16>,
     stencil_buffer_bits: Option<u16>,
+    samples: u32,
     marker: PhantomData<&'a ()>,
 }
 
@@ -571,6 +757,34 @@ impl<'a> ValidatedAttachments<'a> {
     pub fn get_stencil_buffer_bits(&self) -> Option<u16> {
         self.stencil_buffer_bits
     }
+
+    /// Returns the number of samples of the attachments, or `0` if they are not multisampled.
+    ///
+    /// Attachments that use implicit multisampling are resolved when they are read from, and
+    /// return `0`.
+    #[inline]
+    pub fn get_samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Returns the number of color attachments. The attachment whose index is `n` is bound to
+    /// `GL_COLOR_ATTACHMENT0 + n`.
+    #[inline]
+    pub fn get_color_attachments_count(&self) -> usize {
+        self.raw.color.len()
+    }
+
+    /// Returns the list of values that are passed to `glDrawBuffers` when the framebuffer object
+    /// is created.
+    #[doc(hidden)]
+    pub fn get_draw_buffers(&self) -> SmallVec<[gl::types::GLenum; 8]> {
+        let mut draw_buffers = SmallVec::new();
+        for (attachment_pos, &(pos_in_drawbuffers, _)) in self.raw.color.iter().enumerate() {
+            while draw_buffers.len() <= pos_in_drawbuffers as usize { draw_buffers.push(gl::NONE); }
+            draw_buffers[pos_in_drawbuffers as usize] = gl::COLOR_ATTACHMENT0 + attachment_pos as u32;
+        }
+        draw_buffers
+    }
 }
 
 /// An error that can happen while validating attachments.
@@ -598,6 +812,32 @@ pub enum ValidationError {
         /// Number of attachments that were given.
         obtained: usize,
     },
+
+    /// You requested implicit multisampling, but it is not supported by the backend.
+    ImplicitMultisamplingNotSupported,
+
+    /// With implicit multisampling, only the first color attachment can be a texture and it
+    /// must be a non-multisampled 2D texture.
+    ImplicitMultisamplingIncompatibleAttachment,
+
+    /// The requested mipmap level doesn't exist in the texture.
+    MipmapLevelOutOfRange {
+        /// Level that was requested.
+        level: u32,
+        /// Number of mipmap levels of the texture.
+        levels: u32,
+    },
+
+    /// The requested layer or slice doesn't exist in the texture.
+    LayerOutOfRange {
+        /// Layer that was requested.
+        layer: u32,
+        /// Number of layers or slices of the mipmap level.
+        layers: u32,
+    },
+
+    /// You requested a layered framebuffer, but they are not supported by the backend.
+    LayeredFramebuffersNotSupported,
 }
 
 impl fmt::Display for ValidationError {
@@ -606,6 +846,10 @@ impl fmt::Display for ValidationError {
         match *self {
             TooManyColorAttachments{ ref maximum, ref obtained } =>
                 write!(fmt, "{}: found {}, maximum: {}", self.description(), obtained, maximum),
+            MipmapLevelOutOfRange{ ref level, ref levels } =>
+                write!(fmt, "{}: level {}, levels: {}", self.description(), level, levels),
+            LayerOutOfRange{ ref layer, ref layers } =>
+                write!(fmt, "{}: layer {}, layers: {}", self.description(), layer, layers),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -626,6 +870,16 @@ impl Error for ValidationError {
                 "All attachments must have the same number of samples",
             TooManyColorAttachments {..} =>
                 "Backends only support a certain number of color attachments",
+            ImplicitMultisamplingNotSupported =>
+                "Implicit multisampling is not supported by the backend",
+            ImplicitMultisamplingIncompatibleAttachment =>
+                "One of the attachments can't be used with implicit multisampling",
+            MipmapLevelOutOfRange {..} =>
+                "The requested mipmap level doesn't exist in the texture",
+            LayerOutOfRange {..} =>
+                "The requested layer or slice doesn't exist in the texture",
+            LayeredFramebuffersNotSupported =>
+                "Layered framebuffers are not supported by the backend",
         }
     }
 }
@@ -636,7 +890,7 @@ impl Error for ValidationError {
 #[derive(Hash, Clone, Eq, PartialEq)]
 struct RawAttachments {
     // for each frag output the location, the attachment to use
-    color: Vec<(u32, RawAttachment)>,
+    color: SmallVec<[(u32, RawAttachment); 8]>,
     depth: Option<RawAttachment>,
     stencil: Option<RawAttachment>,
     depth_stencil: Option<RawAttachment>,
@@ -647,6 +901,9 @@ struct RawAttachments {
     default_layers: Option<u32>,
     default_samples: Option<u32>,
     default_samples_fixed: Option<bool>,
+
+    // if `Some`, the color texture is attached with `glFramebufferTexture2DMultisampleEXT`
+    implicit_samples: Option<u32>,
 }
 
 /// Single attachment of `RawAttachments`.
@@ -830,7 +1087,42 @@ impl FramebuffersContainer {
                                                 read_buffer: gl::types::GLenum)
     {
         unsafe { bind_framebuffer(ctxt, 0, false, true) };
-        unsafe { ctxt.gl.ReadBuffer(read_buffer) };     // TODO: cache
+
+        if ctxt.state.default_framebuffer_read != Some(read_buffer) {
+            unsafe { ctxt.gl.ReadBuffer(read_buffer) };
+            ctxt.state.default_framebuffer_read = Some(read_buffer);
+        }
+    }
+
+    /// Chooses the buffer of the default framebuffer that drawing commands write to.
+    ///
+    /// The backend must support `glDrawBuffer`, or `glDrawBuffers` with OpenGL ES 3.
+    pub fn set_default_framebuffer_draw_buffer(ctxt: &mut CommandContext,
+                                               draw_buffer: gl::types::GLenum)
+    {
+        if ctxt.state.default_framebuffer_draw == Some(draw_buffer) {
+            return;
+        }
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.NamedFramebufferDrawBuffer(0, draw_buffer);
+
+            } else if ctxt.version >= &Version(Api::Gl, 1, 0) {
+                bind_framebuffer(ctxt, 0, true, false);
+                ctxt.gl.DrawBuffer(draw_buffer);
+
+            } else {
+                // OpenGL ES only has `glDrawBuffers`
+                debug_assert!(ctxt.version >= &Version(Api::GlEs, 3, 0));
+                bind_framebuffer(ctxt, 0, true, false);
+                ctxt.gl.DrawBuffers(1, &draw_buffer);
+            }
+        }
+
+        ctxt.state.default_framebuffer_draw = Some(draw_buffer);
     }
 
     /// Binds a framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` so that it becomes the
@@ -850,14 +1142,35 @@ impl FramebuffersContainer {
             }
         }*/
 
+        // depth and stencil attachments are read with `glReadBuffer(GL_NONE)`
+        let (colors, depth_stencil, read_buffer) = match attachment.kind() {
+            TextureKind::Float | TextureKind::Integral | TextureKind::Unsigned => {
+                let mut v = SmallVec::new();
+                v.push((0, attachment.clone()));
+                (v, DepthStencilAttachments::None, gl::COLOR_ATTACHMENT0)
+            },
+            TextureKind::Depth => {
+                (SmallVec::new(), DepthStencilAttachments::DepthAttachment(attachment.clone()),
+                 gl::NONE)
+            },
+            TextureKind::Stencil => {
+                (SmallVec::new(), DepthStencilAttachments::StencilAttachment(attachment.clone()),
+                 gl::NONE)
+            },
+            TextureKind::DepthStencil => {
+                (SmallVec::new(),
+                 DepthStencilAttachments::DepthStencilAttachment(attachment.clone()), gl::NONE)
+            },
+        };
+
         let attachments = FramebufferAttachments::Regular(FramebufferSpecificAttachments {
-            colors: { let mut v = SmallVec::new(); v.push((0, attachment.clone())); v },
-            depth_stencil: DepthStencilAttachments::None,
+            colors: colors,
+            depth_stencil: depth_stencil,
         }).validate(ctxt).unwrap();
 
         let framebuffer = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, Some(&attachments));
         bind_framebuffer(ctxt, framebuffer, false, true);
-        ctxt.gl.ReadBuffer(gl::COLOR_ATTACHMENT0);     // TODO: cache
+        ctxt.gl.ReadBuffer(read_buffer);     // TODO: cache
     }
 
     /// Calls `glClearBuffer` on a framebuffer that contains the attachment.
@@ -965,7 +1278,7 @@ impl FrameBufferObject {
 
         // building the FBO
         let id = unsafe {
-            let mut id = mem::uninitialized();
+            let mut id = 0;
 
             if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                 ctxt.extensions.gl_arb_direct_state_access
@@ -1066,7 +1379,13 @@ impl FrameBufferObject {
                 panic!("Trying to attach a color buffer to slot {}, but the hardware only supports {} bind points",
                     attachment_pos, ctxt.capabilities.max_color_attachments);
             }
-            unsafe { attach(&mut ctxt, gl::COLOR_ATTACHMENT0 + attachment_pos as u32, id, atchmnt) };
+            let slot = gl::COLOR_ATTACHMENT0 + attachment_pos as u32;
+            match (attachments.implicit_samples, atchmnt) {
+                (Some(samples), RawAttachment::Texture { .. }) => unsafe {
+                    attach_implicit_multisample(&mut ctxt, slot, id, atchmnt, samples)
+                },
+                _ => unsafe { attach(&mut ctxt, slot, id, atchmnt) },
+            }
 
             while raw_attachments.len() <= pos_in_drawbuffers as usize { raw_attachments.push(gl::NONE); }
             raw_attachments[pos_in_drawbuffers as usize] = gl::COLOR_ATTACHMENT0 + attachment_pos as u32;
@@ -1078,49 +1397,24 @@ impl FrameBufferObject {
             unsafe { attach(&mut ctxt, gl::STENCIL_ATTACHMENT, id, stencil) };
         }
         if let Some(depth_stencil) = attachments.depth_stencil {
-            unsafe { attach(&mut ctxt, gl::DEPTH_STENCIL_ATTACHMENT, id, depth_stencil) };
-        }
-
-        // calling `glDrawBuffers` if necessary
-        if raw_attachments != &[gl::COLOR_ATTACHMENT0] {
-            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
-               ctxt.extensions.gl_arb_direct_state_access
-            {
-                unsafe {
-                    ctxt.gl.NamedFramebufferDrawBuffers(id, raw_attachments.len()
-                                                        as gl::types::GLsizei,
-                                                        raw_attachments.as_ptr());
-                }
-
-            } else if ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                      ctxt.version >= &Version(Api::GlEs, 3, 0)
+            // OpenGL ES 2 doesn't have `GL_DEPTH_STENCIL_ATTACHMENT`, the same image must be
+            // attached to both attachment points instead
+            if ctxt.version >= &Version(Api::GlEs, 2, 0) &&
+               !(ctxt.version >= &Version(Api::GlEs, 3, 0))
             {
                 unsafe {
-                    bind_framebuffer(&mut ctxt, id, true, false);
-                    ctxt.gl.DrawBuffers(raw_attachments.len() as gl::types::GLsizei,
-                                        raw_attachments.as_ptr());
+                    attach(&mut ctxt, gl::DEPTH_ATTACHMENT, id, depth_stencil);
+                    attach(&mut ctxt, gl::STENCIL_ATTACHMENT, id, depth_stencil);
                 }
-
-            } else if ctxt.extensions.gl_arb_draw_buffers {
-                unsafe {
-                    bind_framebuffer(&mut ctxt, id, true, false);
-                    ctxt.gl.DrawBuffersARB(raw_attachments.len() as gl::types::GLsizei,
-                                           raw_attachments.as_ptr());
-                }
-
-            } else if ctxt.extensions.gl_ati_draw_buffers {
-                unsafe {
-                    bind_framebuffer(&mut ctxt, id, true, false);
-                    ctxt.gl.DrawBuffersATI(raw_attachments.len() as gl::types::GLsizei,
-                                           raw_attachments.as_ptr());
-                }
-
             } else {
-                // OpenGL ES 2 and OpenGL 1 don't support calling `glDrawBuffers`
-                panic!("Using more than one attachment is not supported by the backend");
+                unsafe { attach(&mut ctxt, gl::DEPTH_STENCIL_ATTACHMENT, id, depth_stencil) };
             }
         }
 
+        // calling `glDrawBuffers` if necessary
+        if raw_attachments != &[gl::COLOR_ATTACHMENT0] && is_draw_buffers_supported(ctxt) {
+            unsafe { set_draw_buffers(&mut ctxt, id, &raw_attachments) };
+        }
 
         FrameBufferObject {
             id: id,
@@ -1233,6 +1527,94 @@ pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext, fbo_id: gl::types::GLu
     }
 }
 
+/// Calls `glDrawBuffers` on a framebuffer object.
+///
+/// # Panic
+///
+/// Panics if `glDrawBuffers` is not supported. Check with `is_draw_buffers_supported` first.
+///
+/// # Safety
+///
+/// The id of the FBO must be valid.
+///
+pub unsafe fn set_draw_buffers(ctxt: &mut CommandContext, fbo_id: gl::types::GLuint,
+                               buffers: &[gl::types::GLenum])
+{
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        ctxt.gl.NamedFramebufferDrawBuffers(fbo_id, buffers.len() as gl::types::GLsizei,
+                                            buffers.as_ptr());
+
+    } else if ctxt.version >= &Version(Api::Gl, 2, 0) ||
+              ctxt.version >= &Version(Api::GlEs, 3, 0)
+    {
+        bind_framebuffer(ctxt, fbo_id, true, false);
+        ctxt.gl.DrawBuffers(buffers.len() as gl::types::GLsizei, buffers.as_ptr());
+
+    } else if ctxt.extensions.gl_arb_draw_buffers {
+        bind_framebuffer(ctxt, fbo_id, true, false);
+        ctxt.gl.DrawBuffersARB(buffers.len() as gl::types::GLsizei, buffers.as_ptr());
+
+    } else if ctxt.extensions.gl_ati_draw_buffers {
+        bind_framebuffer(ctxt, fbo_id, true, false);
+        ctxt.gl.DrawBuffersATI(buffers.len() as gl::types::GLsizei, buffers.as_ptr());
+
+    } else if ctxt.extensions.gl_ext_draw_buffers {
+        bind_framebuffer(ctxt, fbo_id, true, false);
+        ctxt.gl.DrawBuffersEXT(buffers.len() as gl::types::GLsizei, buffers.as_ptr());
+
+    } else {
+        // OpenGL ES 2 and OpenGL 1 don't support calling `glDrawBuffers`, but the validation
+        // of the attachments and the callers of this function make sure that this is unreachable
+        unreachable!("glDrawBuffers is not supported by the backend");
+    }
+}
+
+/// Calls `glReadBuffer` on a framebuffer object.
+///
+/// Does nothing if the backend doesn't support choosing the read buffer, in which case the first
+/// color attachment is always used.
+///
+/// # Safety
+///
+/// The id of the FBO must be valid.
+///
+pub unsafe fn set_read_buffer(ctxt: &mut CommandContext, fbo_id: gl::types::GLuint,
+                              buffer: gl::types::GLenum)
+{
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+        ctxt.gl.NamedFramebufferReadBuffer(fbo_id, buffer);
+
+    } else if ctxt.version >= &Version(Api::Gl, 1, 0) ||
+              ctxt.version >= &Version(Api::GlEs, 3, 0)
+    {
+        bind_framebuffer(ctxt, fbo_id, false, true);
+        ctxt.gl.ReadBuffer(buffer);
+    }
+}
+
+/// Attaches a 2D texture to a framebuffer object through an implicit multisample buffer.
+///
+/// # Safety
+///
+/// All parameters must be valid, and `GL_EXT_multisampled_render_to_texture` must be supported.
+///
+unsafe fn attach_implicit_multisample(ctxt: &mut CommandContext, slot: gl::types::GLenum,
+                                      id: gl::types::GLuint, attachment: RawAttachment,
+                                      samples: u32)
+{
+    match attachment {
+        RawAttachment::Texture { texture: tex_id, level, bind_point, .. } => {
+            debug_assert_eq!(bind_point, gl::TEXTURE_2D);
+            assert!(ctxt.extensions.gl_ext_multisampled_render_to_texture);
+            bind_framebuffer(ctxt, id, true, true);
+            ctxt.gl.FramebufferTexture2DMultisampleEXT(gl::FRAMEBUFFER, slot, bind_point, tex_id,
+                                                       level as gl::types::GLint,
+                                                       samples as gl::types::GLsizei);
+        },
+        RawAttachment::RenderBuffer(_) => unreachable!(),
+    }
+}
+
 /// Attaches something to a framebuffer object.
 ///
 /// # Panic
@@ -1355,7 +1737,7 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
 
                         match bind_point {
                             gl::TEXTURE_1D_ARRAY | gl::TEXTURE_2D_ARRAY |
-                            gl::TEXTURE_2D_MULTISAMPLE_ARRAY => {
+                            gl::TEXTURE_2D_MULTISAMPLE_ARRAY | gl::TEXTURE_CUBE_MAP_ARRAY => {
                                 ctxt.gl.FramebufferTextureLayer(gl::DRAW_FRAMEBUFFER,
                                                                 slot, tex_id,
                                                                 level as gl::types::GLint,
@@ -1373,6 +1755,16 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
                             _ => unreachable!()
                         }
 
+                    } else if ctxt.version >= &Version(Api::GlEs, 3, 0) {
+                        // OpenGL ES has no `glFramebufferTexture3D`, slices of 3D textures
+                        // are attached like the layers of arrays ; cubemap arrays only exist
+                        // with OpenGL ES 3.2, where their faces are attached the same way
+                        bind_framebuffer(ctxt, id, true, false);
+                        ctxt.gl.FramebufferTextureLayer(gl::DRAW_FRAMEBUFFER,
+                                                        slot, tex_id,
+                                                        level as gl::types::GLint,
+                                                        layer as gl::types::GLint);
+
                     } else if ctxt.extensions.gl_ext_framebuffer_object &&
                               bind_point == gl::TEXTURE_3D
                     {
@@ -1383,9 +1775,9 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
                                                         layer as gl::types::GLint);
 
                     } else if ctxt.extensions.gl_ext_texture_array &&
-                              bind_point == gl::TEXTURE_1D_ARRAY ||
-                              bind_point == gl::TEXTURE_2D_ARRAY ||
-                              bind_point == gl::TEXTURE_2D_MULTISAMPLE_ARRAY
+                              (bind_point == gl::TEXTURE_1D_ARRAY ||
+                               bind_point == gl::TEXTURE_2D_ARRAY ||
+                               bind_point == gl::TEXTURE_2D_MULTISAMPLE_ARRAY)
                     {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTextureLayerEXT(gl::DRAW_FRAMEBUFFER,
@@ -1414,7 +1806,9 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
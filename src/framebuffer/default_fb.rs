@@ -1,6 +1,8 @@
 //! Contains everything related to the default framebuffer.
 
 use std::rc::Rc;
+use std::fmt;
+use std::error::Error;
 
 use backend::Facade;
 use context::Context;
@@ -34,6 +36,54 @@ pub enum DefaultFramebufferAttachment {
     FrontLeft,
     /// The frontbuffer for the right eye. May not be present or accessible.
     FrontRight,
+    /// Both frontbuffers when drawing, or the left frontbuffer when reading.
+    Front,
+    /// Both backbuffers when drawing, or the left backbuffer when reading.
+    Back,
+}
+
+impl ToGlEnum for DefaultFramebufferAttachment {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            DefaultFramebufferAttachment::BackLeft => gl::BACK_LEFT,
+            DefaultFramebufferAttachment::BackRight => gl::BACK_RIGHT,
+            DefaultFramebufferAttachment::FrontLeft => gl::FRONT_LEFT,
+            DefaultFramebufferAttachment::FrontRight => gl::FRONT_RIGHT,
+            DefaultFramebufferAttachment::Front => gl::FRONT,
+            DefaultFramebufferAttachment::Back => gl::BACK,
+        }
+    }
+}
+
+/// Error that can happen when choosing the draw or read buffer of the default framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BufferSelectionError {
+    /// The backend doesn't allow choosing the draw or read buffer. This is the case with
+    /// OpenGL ES 2.
+    NotSupported,
+
+    /// The backend doesn't give access to this buffer. OpenGL ES only allows choosing the
+    /// backbuffer or no buffer at all.
+    BufferNotSupported,
+}
+
+impl fmt::Display for BufferSelectionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for BufferSelectionError {
+    fn description(&self) -> &str {
+        use self::BufferSelectionError::*;
+        match *self {
+            NotSupported =>
+                "The backend doesn't allow choosing the draw or read buffer",
+            BufferNotSupported =>
+                "The backend doesn't give access to this buffer",
+        }
+    }
 }
 
 /// A framebuffer which has only one color attachment.
@@ -99,6 +149,14 @@ impl Surface for DefaultFramebuffer {
             }
         }
 
+        if let Some(viewport) = draw_parameters.float_viewport {
+            let (_, _, width, height) = viewport.to_pixels(self.get_dimensions());
+            let max_dims = self.context.capabilities().max_viewport_dims;
+            if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         // TODO: wrong attachment
         ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
                   uniforms, draw_parameters, self.get_dimensions())
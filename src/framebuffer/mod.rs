@@ -33,8 +33,36 @@ let framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display, outp
 //     }
 ```
 
+Instead of a name, you can also pass the location of the output, which is useful if your shader
+declares its outputs with `layout(location = ...)` or if you bound them to explicit locations when
+creating the program with `ProgramCreationInput::SourceCode::fragment_output_locations`. The list
+of outputs of a program can be retrieved with `Program::fragment_outputs`.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let texture1: glium::texture::Texture2d = unsafe { ::std::mem::uninitialized() };
+# let texture2: glium::texture::Texture2d = unsafe { ::std::mem::uninitialized() };
+let output = [ (0u32, &texture1), (1u32, &texture2) ];
+let framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display, output.iter().cloned());
+```
+
 **Note**: depth-stencil attachments are not yet implemented.
 
+If you don't need to sample from what you draw, an `OffscreenTarget` bundles a color render
+buffer and an optional depth render buffer, and can later be resolved to a texture.
+
+For iterative algorithms that repeatedly read the result of the previous step, `PingPongTargets`
+manages two textures and swaps them after each step.
+
+The intermediate textures of a post-processing chain can be requested from a `TargetPool`, which
+reuses the textures that have been released and follows the dimensions of the window when it is
+resized.
+
+To draw on a mipmap level other than the main one, pass the level to
+`SimpleFrameBuffer::with_mipmap_level`. `texture::mipmaps::render_levels` renders all the levels
+of a texture one after the other, each from the previous one, which is how bloom chains and
+hierarchical depth buffers are usually built.
+
 # A note on restrictions
 
 Some restrictions apply when you use framebuffers:
@@ -54,6 +82,14 @@ Some restrictions apply when you use framebuffers:
    framebuffer that you are using. This is not enforced by glium as it depends on your shader's
    source code.
 
+# Implicit multisampling
+
+On OpenGL ES, `GL_EXT_multisampled_render_to_texture` allows drawing on a texture through an
+implicit multisample buffer that is resolved automatically. On tiled GPUs this is much cheaper
+than drawing on a multisampled render buffer and blitting it. Use
+`SimpleFrameBuffer::with_implicit_multisampling` and check for support with
+`is_implicit_multisampling_supported`.
+
 # Empty framebuffers
 
 Modern OpenGL implementations support empty framebuffers. This is handled by glium with the
@@ -61,15 +97,26 @@ Modern OpenGL implementations support empty framebuffers. This is handled by gli
 
 You can check whether they are supported by calling `EmptyFrameBuffer::is_supported(&display)`.
 
-# Layered framebuffers
+# Layers and slices
 
-Not yet supported
+A single layer of an array texture, or a single Z-slice of a 3D texture, can be drawn to with
+`SimpleFrameBuffer::with_layer`. A face of a cubemap or of a cubemap array is drawn to with
+`SimpleFrameBuffer::with_cubemap_face`. Depth textures are attached as the depth buffer, which
+makes it possible to render shadow maps to the layers of a `DepthTexture2dArray` or to the faces
+of a `DepthCubemap`.
+
+`SimpleFrameBuffer::layered` instead attaches all the layers at once, and the geometry shader
+chooses the layer of each primitive by writing to `gl_Layer`. This requires OpenGL 3.2 or
+OpenGL ES 3.2, see `is_layered_supported`.
 
 */
 use std::rc::Rc;
+use std::fmt;
+use std::error::Error;
 use smallvec::SmallVec;
 
-use texture::TextureAnyImage;
+use texture::{CubeLayer, Dimensions, TextureAny, TextureAnyImage, TextureAnyMipmap};
+use texture::TextureKind;
 
 use backend::Facade;
 use context::Context;
@@ -91,14 +138,24 @@ use DrawError;
 use {fbo, gl};
 
 pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
+pub use self::default_fb::BufferSelectionError;
+pub use self::offscreen::{OffscreenTarget, OffscreenTargetCreationError, ResolveError};
+pub use self::ping_pong::{PingPongTargets, PingPongTargetsCreationError};
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
+pub use self::target_pool::{TargetPool, TargetPoolError, PooledTarget};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
+pub use self::render_buffer::{IntegralRenderBuffer, UnsignedRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
-pub use fbo::is_dimensions_mismatch_supported;
-pub use fbo::ValidationError;
+pub use fbo::{is_dimensions_mismatch_supported, is_implicit_multisampling_supported};
+pub use fbo::is_layered_supported;
+pub use fbo::{is_blit_supported, is_draw_buffers_supported, is_read_buffer_supported};
+pub use fbo::{ValidationError, ValidatedAttachments};
 
 mod default_fb;
+mod offscreen;
+mod ping_pong;
 mod render_buffer;
+mod target_pool;
 
 /// A framebuffer which has only one color attachment.
 pub struct SimpleFrameBuffer<'a> {
@@ -113,7 +170,200 @@ impl<'a> SimpleFrameBuffer<'a> {
     pub fn new<F: ?Sized, C>(facade: &F, color: C) -> Result<SimpleFrameBuffer<'a>, ValidationError>
                      where C: ToColorAttachment<'a>, F: Facade
     {
-        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None)
+        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None,
+                                    None)
+    }
+
+    /// Creates a `SimpleFrameBuffer` with a single color attachment that is rendered to through
+    /// an implicit multisample buffer with `samples` samples.
+    ///
+    /// The multisample buffer is resolved into the texture automatically, which is much cheaper
+    /// than a separate blit on tiled GPUs. This requires `GL_EXT_multisampled_render_to_texture`,
+    /// see `is_implicit_multisampling_supported`. The color attachment must be a regular
+    /// non-multisampled 2D texture.
+    #[inline]
+    pub fn with_implicit_multisampling<F: ?Sized, C>(facade: &F, color: C, samples: u32)
+                                                     -> Result<SimpleFrameBuffer<'a>,
+                                                               ValidationError>
+                                                     where C: ToColorAttachment<'a>, F: Facade
+    {
+        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None,
+                                    Some(samples))
+    }
+
+    /// Creates a `SimpleFrameBuffer` that draws to a mipmap level of a texture.
+    ///
+    /// Color textures are attached as the color buffer, and depth textures as the depth buffer
+    /// with no color buffer. The dimensions of the framebuffer are the dimensions of the level.
+    ///
+    /// Returns `MipmapLevelOutOfRange` if the texture doesn't have this level.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture is a cubemap, or if it is a stencil or depth-stencil texture.
+    pub fn with_mipmap_level<F: ?Sized>(facade: &F, texture: &'a TextureAny, level: u32)
+                                        -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                        where F: Facade
+    {
+        let image = try!(get_mipmap(texture, level)).first_layer().into_image(None)
+                        .expect("Cubemaps can't be attached with `with_mipmap_level`");
+        SimpleFrameBuffer::with_image(facade, image)
+    }
+
+    /// Creates a `SimpleFrameBuffer` that draws to a single layer of a mipmap level of a
+    /// texture, which is a Z-slice for 3D textures.
+    ///
+    /// Volumetric data such as color lookup tables can be generated by drawing each slice one
+    /// after the other. Like with `with_mipmap_level`, depth textures are attached as the depth
+    /// buffer, so that each layer of a depth texture array can hold the shadow map of a
+    /// different light.
+    ///
+    /// Returns `MipmapLevelOutOfRange` if the texture doesn't have this level, and
+    /// `LayerOutOfRange` if the level doesn't have this layer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture is a cubemap or a cubemap array, or if it is a stencil or
+    /// depth-stencil texture.
+    pub fn with_layer<F: ?Sized>(facade: &F, texture: &'a TextureAny, level: u32, layer: u32)
+                                 -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                 where F: Facade
+    {
+        let mipmap = try!(get_mipmap(texture, level));
+
+        let image = match mipmap.layer(layer) {
+            Some(l) => l.into_image(None).expect("Cubemaps can't be attached with `with_layer`"),
+            None => return Err(ValidationError::LayerOutOfRange {
+                layer: layer,
+                layers: mipmap.get_depth().or(texture.get_array_size()).unwrap_or(1),
+            }),
+        };
+
+        SimpleFrameBuffer::with_image(facade, image)
+    }
+
+    /// Creates a `SimpleFrameBuffer` that draws to a face of a cubemap, or to a face of a layer
+    /// of a cubemap array. `layer` must be 0 for regular cubemaps.
+    ///
+    /// Depth cubemaps are attached as the depth buffer, which is how omnidirectional shadow maps
+    /// are rendered one face at a time.
+    ///
+    /// Returns `MipmapLevelOutOfRange` if the texture doesn't have this level, and
+    /// `LayerOutOfRange` if the texture doesn't have this layer.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture is not a cubemap or a cubemap array, or if it is a stencil or
+    /// depth-stencil texture.
+    pub fn with_cubemap_face<F: ?Sized>(facade: &F, texture: &'a TextureAny, level: u32,
+                                        layer: u32, face: CubeLayer)
+                                        -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                        where F: Facade
+    {
+        match texture.get_texture_type() {
+            Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => (),
+            _ => panic!("Only cubemaps can be attached with `with_cubemap_face`"),
+        }
+
+        let mipmap = try!(get_mipmap(texture, level));
+
+        let image = match mipmap.layer(layer) {
+            Some(l) => l.into_image(Some(face)).unwrap(),
+            None => return Err(ValidationError::LayerOutOfRange {
+                layer: layer,
+                layers: texture.get_array_size().unwrap_or(1),
+            }),
+        };
+
+        SimpleFrameBuffer::with_image(facade, image)
+    }
+
+    /// Creates a layered `SimpleFrameBuffer` that contains all the layers of a mipmap level of
+    /// an array, cubemap or 3D texture.
+    ///
+    /// Each primitive is drawn to the layer chosen by the geometry shader with `gl_Layer`, so
+    /// that a whole volume can be generated with a single draw call. The faces of cubemaps
+    /// count as layers. Check for support with `is_layered_supported`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture doesn't have layers, or if it is a stencil or depth-stencil
+    /// texture.
+    pub fn layered<F: ?Sized>(facade: &F, texture: &'a TextureAny, level: u32)
+                              -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                              where F: Facade
+    {
+        match texture.get_texture_type() {
+            Dimensions::Texture1dArray { .. } | Dimensions::Texture2dArray { .. } |
+            Dimensions::Texture2dMultisampleArray { .. } | Dimensions::Texture3d { .. } |
+            Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => (),
+            _ => panic!("Only array, cubemap and 3D textures can be attached with `layered`"),
+        }
+
+        let attachment = fbo::LayeredAttachment::new(try!(get_mipmap(texture, level)));
+
+        let attachments = match texture.kind() {
+            TextureKind::Float | TextureKind::Integral | TextureKind::Unsigned => {
+                let mut colors = SmallVec::new();
+                colors.push((0, attachment));
+                fbo::FramebufferSpecificAttachments {
+                    colors: colors,
+                    depth_stencil: fbo::DepthStencilAttachments::None,
+                }
+            },
+            TextureKind::Depth => {
+                fbo::FramebufferSpecificAttachments {
+                    colors: SmallVec::new(),
+                    depth_stencil: fbo::DepthStencilAttachments::DepthAttachment(attachment),
+                }
+            },
+            TextureKind::Stencil | TextureKind::DepthStencil => {
+                panic!("Stencil textures can't be attached with `layered`")
+            },
+        };
+
+        let attachments = try!(fbo::FramebufferAttachments::Layered(attachments).validate(facade));
+
+        Ok(SimpleFrameBuffer {
+            context: facade.get_context().clone(),
+            attachments: attachments,
+        })
+    }
+
+    /// Attaches an image as the color buffer or as the depth buffer depending on the kind of
+    /// its texture.
+    fn with_image<F: ?Sized>(facade: &F, image: TextureAnyImage<'a>)
+                             -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                             where F: Facade
+    {
+        match image.get_texture().kind() {
+            TextureKind::Float | TextureKind::Integral | TextureKind::Unsigned => {
+                SimpleFrameBuffer::new_impl(facade, Some(ColorAttachment::Texture(image)), None,
+                                            None, None, None)
+            },
+            TextureKind::Depth => {
+                SimpleFrameBuffer::new_impl(facade, None, Some(DepthAttachment::Texture(image)),
+                                            None, None, None)
+            },
+            TextureKind::Stencil | TextureKind::DepthStencil => {
+                panic!("Stencil textures can't be attached to a `SimpleFrameBuffer` this way")
+            },
+        }
+    }
+
+    /// Same as `with_implicit_multisampling`, but with a depth buffer.
+    ///
+    /// The depth buffer must be a render buffer created with `samples` samples. Its content
+    /// is discarded at the end of the rendering.
+    #[inline]
+    pub fn with_depth_buffer_and_implicit_multisampling<F: ?Sized, C, D>(facade: &F, color: C,
+                                                                         depth: D, samples: u32)
+                                      -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                      where C: ToColorAttachment<'a>,
+                                            D: ToDepthAttachment<'a>, F: Facade
+    {
+        SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()),
+                                    Some(depth.to_depth_attachment()), None, None, Some(samples))
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth
@@ -125,7 +375,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                             D: ToDepthAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()),
-                                    Some(depth.to_depth_attachment()), None, None)
+                                    Some(depth.to_depth_attachment()), None, None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and no depth
@@ -135,7 +385,8 @@ impl<'a> SimpleFrameBuffer<'a> {
                             -> Result<SimpleFrameBuffer<'a>, ValidationError>
         where D: ToDepthAttachment<'a>, F: Facade
     {
-        SimpleFrameBuffer::new_impl(facade, None, Some(depth.to_depth_attachment()), None, None)
+        SimpleFrameBuffer::new_impl(facade, None, Some(depth.to_depth_attachment()), None, None,
+                                    None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment, a depth
@@ -151,7 +402,7 @@ impl<'a> SimpleFrameBuffer<'a> {
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()),
                                     Some(depth.to_depth_attachment()),
-                                    Some(stencil.to_stencil_attachment()), None)
+                                    Some(stencil.to_stencil_attachment()), None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and no depth
@@ -163,7 +414,7 @@ impl<'a> SimpleFrameBuffer<'a> {
               S: ToStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, None, Some(depth.to_depth_attachment()),
-                                    Some(stencil.to_stencil_attachment()), None)
+                                    Some(stencil.to_stencil_attachment()), None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a stencil
@@ -175,7 +426,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                               F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None,
-                                    Some(stencil.to_stencil_attachment()), None)
+                                    Some(stencil.to_stencil_attachment()), None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a stencil
@@ -186,7 +437,7 @@ impl<'a> SimpleFrameBuffer<'a> {
         where S: ToStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, None, None, Some(stencil.to_stencil_attachment()),
-                                    None)
+                                    None, None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth-stencil buffer.
@@ -197,7 +448,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                                     D: ToDepthStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None,
-                                    Some(depthstencil.to_depth_stencil_attachment()))
+                                    Some(depthstencil.to_depth_stencil_attachment()), None)
     }
 
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth-stencil buffer.
@@ -207,18 +458,25 @@ impl<'a> SimpleFrameBuffer<'a> {
         where D: ToDepthStencilAttachment<'a>, F: Facade
     {
         SimpleFrameBuffer::new_impl(facade, None, None, None,
-                                    Some(depthstencil.to_depth_stencil_attachment()))
+                                    Some(depthstencil.to_depth_stencil_attachment()), None)
     }
 
 
     fn new_impl<F: ?Sized>(facade: &F, color: Option<ColorAttachment<'a>>,
                    depth: Option<DepthAttachment<'a>>, stencil: Option<StencilAttachment<'a>>,
-                   depthstencil: Option<DepthStencilAttachment<'a>>)
+                   depthstencil: Option<DepthStencilAttachment<'a>>,
+                   implicit_samples: Option<u32>)
                    -> Result<SimpleFrameBuffer<'a>, ValidationError> where F: Facade
     {
         let color = color.map(|color| match color {
             ColorAttachment::Texture(tex) => fbo::RegularAttachment::Texture(tex),
             ColorAttachment::RenderBuffer(buffer) => fbo::RegularAttachment::RenderBuffer(buffer),
+            ColorAttachment::IntegralRenderBuffer(buffer) => {
+                fbo::RegularAttachment::RenderBuffer(buffer)
+            },
+            ColorAttachment::UnsignedRenderBuffer(buffer) => {
+                fbo::RegularAttachment::RenderBuffer(buffer)
+            },
         });
 
         let depth = depth.map(|depth| match depth {
@@ -255,7 +513,10 @@ impl<'a> SimpleFrameBuffer<'a> {
             }
         });
 
-        let attachments = try!(attachments.validate(facade));
+        let attachments = match implicit_samples {
+            Some(samples) => try!(attachments.validate_implicit_multisample(facade, samples)),
+            None => try!(attachments.validate(facade)),
+        };
 
         Ok(SimpleFrameBuffer {
             context: facade.get_context().clone(),
@@ -264,6 +525,14 @@ impl<'a> SimpleFrameBuffer<'a> {
     }
 }
 
+/// Returns a mipmap level of a texture, or `MipmapLevelOutOfRange` if it doesn't exist.
+fn get_mipmap(texture: &TextureAny, level: u32) -> Result<TextureAnyMipmap, ValidationError> {
+    texture.mipmap(level).ok_or(ValidationError::MipmapLevelOutOfRange {
+        level: level,
+        levels: texture.get_mipmap_levels(),
+    })
+}
+
 impl<'a> Surface for SimpleFrameBuffer<'a> {
     #[inline]
     fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
@@ -311,6 +580,14 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
             }
         }
 
+        if let Some(viewport) = draw_parameters.float_viewport {
+            let (_, _, width, height) = viewport.to_pixels(self.get_dimensions());
+            let max_dims = self.context.capabilities().max_viewport_dims;
+            if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, Some(&self.attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
@@ -356,11 +633,44 @@ impl<'a> FboAttachments for SimpleFrameBuffer<'a> {
     }
 }
 
-/// This struct is useless for the moment.
+/// Describes which output of the fragment shader is written to a color attachment of a
+/// `MultiOutputFrameBuffer`.
+///
+/// You usually don't need to build this enum yourself, as it can be obtained from a `&str`
+/// or from a `u32`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FragmentOutputBinding<'a> {
+    /// The output with the given name. Its location is queried from the program when drawing.
+    Name(&'a str),
+
+    /// The output at the given location, either declared with `layout(location = ...)` in the
+    /// shader or bound when creating the program.
+    Location(u32),
+}
+
+impl<'a> From<&'a str> for FragmentOutputBinding<'a> {
+    #[inline]
+    fn from(name: &'a str) -> FragmentOutputBinding<'a> {
+        FragmentOutputBinding::Name(name)
+    }
+}
+
+impl<'a> From<u32> for FragmentOutputBinding<'a> {
+    #[inline]
+    fn from(location: u32) -> FragmentOutputBinding<'a> {
+        FragmentOutputBinding::Location(location)
+    }
+}
+
+/// A framebuffer with multiple color attachments, each of them receiving one output of the
+/// fragment shader.
+///
+/// The attachments are matched with the outputs of the program when drawing. An error is
+/// returned if an output can't be found or if several attachments end up on the same location.
 pub struct MultiOutputFrameBuffer<'a> {
     context: Rc<Context>,
     example_attachments: fbo::ValidatedAttachments<'a>,
-    color_attachments: Vec<(String, fbo::RegularAttachment<'a>)>,
+    color_attachments: Vec<(FragmentOutputBinding<'a>, fbo::RegularAttachment<'a>)>,
     depth_stencil_attachments: fbo::DepthStencilAttachments<fbo::RegularAttachment<'a>>,
 }
 
@@ -371,10 +681,11 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn new<F: ?Sized, I, A>(facade: &F, color_attachments: I)
+    pub fn new<F: ?Sized, I, N, A>(facade: &F, color_attachments: I)
                         -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (N, A)>,
+              N: Into<FragmentOutputBinding<'a>>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color_attachments, None, None, None)
@@ -386,11 +697,12 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_depth_buffer<F: ?Sized, D, I, A>(facade: &F, color_attachments: I, depth: D)
+    pub fn with_depth_buffer<F: ?Sized, D, I, N, A>(facade: &F, color_attachments: I, depth: D)
                                          -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where F: Facade,
               D: ToDepthAttachment<'a>, 
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (N, A)>,
+              N: Into<FragmentOutputBinding<'a>>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color_attachments,
@@ -403,11 +715,12 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_depth_and_stencil_buffer<A, F: ?Sized, I, D, S>(facade: &F, color: I, depth: D, stencil: S)
+    pub fn with_depth_and_stencil_buffer<A, F: ?Sized, I, N, D, S>(facade: &F, color: I, depth: D, stencil: S)
                                                         -> Result<MultiOutputFrameBuffer<'a>,
                                                                   ValidationError>
         where D: ToDepthAttachment<'a>,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (N, A)>,
+              N: Into<FragmentOutputBinding<'a>>,
               S: ToStencilAttachment<'a>,
               A: ToColorAttachment<'a>,
               F: Facade
@@ -423,11 +736,12 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_stencil_buffer<A, F: ?Sized, I, S>(facade: &F, color: I, stencil: S)
+    pub fn with_stencil_buffer<A, F: ?Sized, I, N, S>(facade: &F, color: I, stencil: S)
                                            -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where S: ToStencilAttachment<'a>,
               F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (N, A)>,
+              N: Into<FragmentOutputBinding<'a>>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color, None,
@@ -440,28 +754,30 @@ impl<'a> MultiOutputFrameBuffer<'a> {
     ///
     /// Panics if all attachments don't have the same dimensions.
     #[inline]
-    pub fn with_depth_stencil_buffer<A, F: ?Sized, I, D>(facade: &F, color: I, depthstencil: D)
+    pub fn with_depth_stencil_buffer<A, F: ?Sized, I, N, D>(facade: &F, color: I, depthstencil: D)
                                                  -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where D: ToDepthStencilAttachment<'a>, F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (N, A)>,
+              N: Into<FragmentOutputBinding<'a>>,
               A: ToColorAttachment<'a>,
     {
         MultiOutputFrameBuffer::new_impl(facade, color, None, None,
                                     Some(depthstencil.to_depth_stencil_attachment()))
     }
 
-    fn new_impl<F: ?Sized, I, A>(facade: &F, color: I, depth: Option<DepthAttachment<'a>>,
+    fn new_impl<F: ?Sized, I, N, A>(facade: &F, color: I, depth: Option<DepthAttachment<'a>>,
                          stencil: Option<StencilAttachment<'a>>,
                          depthstencil: Option<DepthStencilAttachment<'a>>)
                          -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
         where F: Facade,
-              I: IntoIterator<Item = (&'a str, A)>,
+              I: IntoIterator<Item = (N, A)>,
+              N: Into<FragmentOutputBinding<'a>>,
               A: ToColorAttachment<'a>,
     {
         let color = color.into_iter().map(|(name, tex)| {
             let atch = tex.to_color_attachment();
             let atch = if let ColorAttachment::Texture(t) = atch { t } else { panic!() };
-            (name.to_owned(), fbo::RegularAttachment::Texture(atch))
+            (name.into(), fbo::RegularAttachment::Texture(atch))
         }).collect::<Vec<_>>();
 
         let example_color = {
@@ -512,22 +828,38 @@ impl<'a> MultiOutputFrameBuffer<'a> {
         })
     }
 
-    fn build_attachments(&self, program: &Program) -> fbo::ValidatedAttachments {
-        let mut colors = SmallVec::new();
-
-        for &(ref name, attachment) in self.color_attachments.iter() {
-            let location = match program.get_frag_data_location(&name) {
-                Some(l) => l,
-                None => panic!("The fragment output `{}` was not found in the program", name)
+    fn build_attachments(&self, program: &Program)
+                         -> Result<fbo::ValidatedAttachments, DrawError>
+    {
+        let max_location = self.context.capabilities().max_draw_buffers as u32;
+        let mut colors: SmallVec<[_; 5]> = SmallVec::new();
+
+        for &(binding, attachment) in self.color_attachments.iter() {
+            let location = match binding {
+                FragmentOutputBinding::Name(name) => match program.get_frag_data_location(name) {
+                    Some(l) => l,
+                    None => return Err(DrawError::FragmentOutputNotFound {
+                        name: name.to_owned(),
+                    }),
+                },
+                FragmentOutputBinding::Location(l) => l,
             };
 
+            if location >= max_location {
+                return Err(DrawError::FragmentOutputLocationOutOfRange { location: location });
+            }
+
+            if colors.iter().any(|&(l, _)| l == location) {
+                return Err(DrawError::FragmentOutputLocationConflict { location: location });
+            }
+
             colors.push((location, attachment));
         }
 
         fbo::FramebufferAttachments::Regular(fbo::FramebufferSpecificAttachments {
             colors: colors,
             depth_stencil: self.depth_stencil_attachments,
-        }).validate(&self.context).unwrap()
+        }).validate(&self.context).map_err(DrawError::FramebufferValidation)
     }
 }
 
@@ -579,7 +911,17 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
             }
         }
 
-        ops::draw(&self.context, Some(&self.build_attachments(program)), vb,
+        if let Some(viewport) = draw_parameters.float_viewport {
+            let (_, _, width, height) = viewport.to_pixels(self.get_dimensions());
+            let max_dims = self.context.capabilities().max_viewport_dims;
+            if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
+        let attachments = try!(self.build_attachments(program));
+
+        ops::draw(&self.context, Some(&attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
 
@@ -620,7 +962,9 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
 impl<'a> FboAttachments for MultiOutputFrameBuffer<'a> {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments> {
-        unimplemented!();
+        // the color attachments are in the order in which they were passed when creating the
+        // framebuffer
+        Some(&self.example_attachments)
     }
 }
 
@@ -748,6 +1092,14 @@ impl Surface for EmptyFrameBuffer {
             }
         }
 
+        if let Some(viewport) = draw_parameters.float_viewport {
+            let (_, _, width, height) = viewport.to_pixels(self.get_dimensions());
+            let max_dims = self.context.capabilities().max_viewport_dims;
+            if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, Some(&self.attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
@@ -793,6 +1145,237 @@ impl FboAttachments for EmptyFrameBuffer {
     }
 }
 
+/// Describes which attachments are copied by `blit_and_resolve`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BlitMask {
+    /// If `Some`, the color attachment whose index is the first element of the tuple in the source
+    /// is copied to the color attachment whose index is the second element in the target.
+    ///
+    /// The index of an attachment is its position in the list of color attachments passed when
+    /// creating the framebuffer. Indices are ignored for the default framebuffer.
+    pub color: Option<(u32, u32)>,
+
+    /// If true, the depth buffer is copied.
+    pub depth: bool,
+
+    /// If true, the stencil buffer is copied.
+    pub stencil: bool,
+}
+
+impl BlitMask {
+    /// Copies the first color attachment of the source to the first color attachment of the
+    /// target.
+    #[inline]
+    pub fn color() -> BlitMask {
+        BlitMask {
+            color: Some((0, 0)),
+            depth: false,
+            stencil: false,
+        }
+    }
+
+    /// Copies the color attachment `source` of the source to the color attachment `target` of
+    /// the target.
+    #[inline]
+    pub fn color_attachment(source: u32, target: u32) -> BlitMask {
+        BlitMask {
+            color: Some((source, target)),
+            depth: false,
+            stencil: false,
+        }
+    }
+
+    /// Copies the depth buffer.
+    #[inline]
+    pub fn depth() -> BlitMask {
+        BlitMask {
+            color: None,
+            depth: true,
+            stencil: false,
+        }
+    }
+
+    /// Copies the stencil buffer.
+    #[inline]
+    pub fn stencil() -> BlitMask {
+        BlitMask {
+            color: None,
+            depth: false,
+            stencil: true,
+        }
+    }
+}
+
+/// Copies a rectangle of pixels from a framebuffer to another, resolving multisampled
+/// attachments in the process.
+///
+/// Contrary to `Surface::blit_color`, this function lets you choose which attachments are copied
+/// with `mask`. For example you can resolve only the second attachment of a multisampled
+/// `MultiOutputFrameBuffer` into a `SimpleFrameBuffer`, or copy its depth buffer.
+///
+/// If the source is multisampled, the source and target rectangles must have the same
+/// dimensions.
+///
+/// This is the same as calling `blit_rects` with a single pair of rectangles. See its
+/// documentation for the errors that can be returned.
+///
+pub fn blit_and_resolve<F: ?Sized, S: ?Sized, T: ?Sized>(facade: &F, source: &S, target: &T,
+                                                       source_rect: &Rect,
+                                                       target_rect: &BlitTarget,
+                                                       filter: uniforms::MagnifySamplerFilter,
+                                                       mask: BlitMask)
+                                                       -> Result<(), BlitError>
+    where F: Facade, S: FboAttachments, T: FboAttachments
+{
+    blit_rects(facade, source, target, &[(*source_rect, *target_rect)], filter, mask)
+}
+
+/// Error that can happen when calling `blit_and_resolve` or `blit_rects`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlitError {
+    /// Blitting is not supported by the backend. See `is_blit_supported`.
+    NotSupported,
+
+    /// The depth or stencil buffer can only be copied with the `Nearest` filter.
+    DepthStencilFilterNotNearest,
+
+    /// The source doesn't have the requested color attachment.
+    SourceColorAttachmentOutOfRange(u32),
+
+    /// The target doesn't have the requested color attachment.
+    TargetColorAttachmentOutOfRange(u32),
+
+    /// A color attachment other than the first one was requested, but the backend doesn't
+    /// support choosing the attachment to read from or to draw to. See
+    /// `is_read_buffer_supported` and `is_draw_buffers_supported`.
+    ColorAttachmentSelectionNotSupported,
+
+    /// The target is multisampled. Multisampled surfaces can only be copied from.
+    MultisampledTarget,
+
+    /// The source is multisampled and one of the target rectangles doesn't have the same
+    /// dimensions as its source rectangle. OpenGL ES also requires the rectangles to have
+    /// the same position.
+    MultisampledRectsMismatch,
+}
+
+impl fmt::Display for BlitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::BlitError::*;
+        match *self {
+            SourceColorAttachmentOutOfRange(index) |
+            TargetColorAttachmentOutOfRange(index) => {
+                write!(fmt, "{}: {}", self.description(), index)
+            },
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for BlitError {
+    fn description(&self) -> &str {
+        use self::BlitError::*;
+        match *self {
+            NotSupported =>
+                "Blitting is not supported by the backend",
+            DepthStencilFilterNotNearest =>
+                "The depth or stencil buffer can only be copied with the `Nearest` filter",
+            SourceColorAttachmentOutOfRange(_) =>
+                "The source doesn't have the requested color attachment",
+            TargetColorAttachmentOutOfRange(_) =>
+                "The target doesn't have the requested color attachment",
+            ColorAttachmentSelectionNotSupported =>
+                "The backend doesn't support choosing a color attachment other than the first one",
+            MultisampledTarget =>
+                "Multisampled surfaces can't be blitted to",
+            MultisampledRectsMismatch =>
+                "The rectangles of a blit from a multisampled surface must match",
+        }
+    }
+}
+
+/// Copies several rectangles of pixels from a surface to another, resolving multisampled
+/// attachments in the process.
+///
+/// This is a more general version of `blit_and_resolve`. Each element of `rects` is a source
+/// rectangle and the area of the target where it is copied. The image is scaled with `filter`
+/// when the dimensions of the two rectangles are different. `mask` chooses the attachments
+/// that are copied.
+///
+/// The source and the target can be any surface that provides its attachments, including
+/// the `Frame`. Use `as_surface()` to blit from or to an `OffscreenTarget`.
+///
+/// The parameters are checked against the restrictions of OpenGL before anything is copied,
+/// and an error is returned if they are not respected. The number of samples of the default
+/// framebuffer is unknown to glium, and isn't checked.
+pub fn blit_rects<F: ?Sized, S: ?Sized, T: ?Sized>(facade: &F, source: &S, target: &T,
+                                                 rects: &[(Rect, BlitTarget)],
+                                                 filter: uniforms::MagnifySamplerFilter,
+                                                 mask: BlitMask) -> Result<(), BlitError>
+    where F: Facade, S: FboAttachments, T: FboAttachments
+{
+    if !fbo::is_blit_supported(facade.get_context()) {
+        return Err(BlitError::NotSupported);
+    }
+
+    if (mask.depth || mask.stencil) && filter != uniforms::MagnifySamplerFilter::Nearest {
+        return Err(BlitError::DepthStencilFilterNotNearest);
+    }
+
+    let (source_color, target_color) = mask.color.unwrap_or((0, 0));
+
+    if let (Some(attachments), true) = (source.get_attachments(), mask.color.is_some()) {
+        if source_color as usize >= attachments.get_color_attachments_count() {
+            return Err(BlitError::SourceColorAttachmentOutOfRange(source_color));
+        }
+    }
+
+    if let (Some(attachments), true) = (target.get_attachments(), mask.color.is_some()) {
+        if target_color as usize >= attachments.get_color_attachments_count() {
+            return Err(BlitError::TargetColorAttachmentOutOfRange(target_color));
+        }
+    }
+
+    if mask.color.is_some() {
+        let context = facade.get_context();
+        if (source_color != 0 && source.get_attachments().is_some() &&
+            !fbo::is_read_buffer_supported(context)) ||
+           (target_color != 0 && target.get_attachments().is_some() &&
+            !fbo::is_draw_buffers_supported(context))
+        {
+            return Err(BlitError::ColorAttachmentSelectionNotSupported);
+        }
+    }
+
+    if target.get_attachments().map(|a| a.get_samples()).unwrap_or(0) != 0 {
+        return Err(BlitError::MultisampledTarget);
+    }
+
+    if source.get_attachments().map(|a| a.get_samples()).unwrap_or(0) != 0 {
+        let positions_may_differ = facade.get_context().get_version() >= &Version(Api::Gl, 1, 0);
+
+        for &(ref src, ref dest) in rects {
+            if src.width as i32 != dest.width || src.height as i32 != dest.height {
+                return Err(BlitError::MultisampledRectsMismatch);
+            }
+
+            if !positions_may_differ && (src.left != dest.left || src.bottom != dest.bottom) {
+                return Err(BlitError::MultisampledRectsMismatch);
+            }
+        }
+    }
+
+    let mut gl_mask = 0;
+    if mask.color.is_some() { gl_mask |= gl::COLOR_BUFFER_BIT; }
+    if mask.depth { gl_mask |= gl::DEPTH_BUFFER_BIT; }
+    if mask.stencil { gl_mask |= gl::STENCIL_BUFFER_BIT; }
+
+    ops::blit_attachments(facade.get_context(), source.get_attachments(),
+                          target.get_attachments(), gl_mask, source_color, target_color,
+                          rects, filter.to_glenum());
+    Ok(())
+}
+
 /// Describes an attachment for a color buffer.
 #[derive(Copy, Clone)]
 pub enum ColorAttachment<'a> {
@@ -800,6 +1383,10 @@ pub enum ColorAttachment<'a> {
     Texture(TextureAnyImage<'a>),
     /// A render buffer.
     RenderBuffer(&'a RenderBuffer),
+    /// A render buffer that contains signed integral data.
+    IntegralRenderBuffer(&'a IntegralRenderBuffer),
+    /// A render buffer that contains unsigned integral data.
+    UnsignedRenderBuffer(&'a UnsignedRenderBuffer),
 }
 
 /// Trait for objects that can be used as color attachments.
@@ -0,0 +1,296 @@
+/*!
+
+An offscreen target is a color render buffer and an optional depth render buffer, bundled
+together and usable as a `Surface`.
+
+This is the most straight-forward way to draw somewhere else than on the screen when you don't
+need to sample from the result directly. Once you have finished drawing, you can copy (and resolve
+if the target is multisampled) the content of the color buffer to a texture with
+`resolve_to_texture`.
+
+```no_run
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let texture: glium::texture::Texture2d = unsafe { ::std::mem::uninitialized() };
+let mut target = glium::framebuffer::OffscreenTarget::new(&display,
+                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                            Some(glium::texture::DepthFormat::I24), 1024, 768, Some(4)).unwrap();
+target.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+// target.draw(...);
+target.resolve_to_texture(&texture).unwrap();
+```
+
+*/
+use std::rc::Rc;
+use std::fmt;
+use std::error::Error;
+
+use framebuffer::{RenderBuffer, DepthRenderBuffer, SimpleFrameBuffer, MultiOutputFrameBuffer};
+use framebuffer::{ValidationError, RenderBufferCreationError};
+use texture::{Texture2d, UncompressedFloatFormat, DepthFormat};
+
+use backend::Facade;
+use context::Context;
+
+use Rect;
+use BlitTarget;
+use DrawError;
+use Surface;
+use uniforms;
+
+/// Error that can happen while creating an `OffscreenTarget`.
+#[derive(Copy, Clone, Debug)]
+pub enum OffscreenTargetCreationError {
+    /// Error while creating one of the render buffers.
+    RenderBufferCreationError(RenderBufferCreationError),
+
+    /// The render buffers can't be used together as a framebuffer.
+    ValidationError(ValidationError),
+}
+
+impl fmt::Display for OffscreenTargetCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::OffscreenTargetCreationError::*;
+        match *self {
+            RenderBufferCreationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+            ValidationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+        }
+    }
+}
+
+impl Error for OffscreenTargetCreationError {
+    fn description(&self) -> &str {
+        use self::OffscreenTargetCreationError::*;
+        match *self {
+            RenderBufferCreationError(_) => "Error while creating one of the render buffers",
+            ValidationError(_) => "The render buffers can't be used together as a framebuffer",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::OffscreenTargetCreationError::*;
+        match *self {
+            RenderBufferCreationError(ref err) => Some(err),
+            ValidationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<RenderBufferCreationError> for OffscreenTargetCreationError {
+    #[inline]
+    fn from(err: RenderBufferCreationError) -> OffscreenTargetCreationError {
+        OffscreenTargetCreationError::RenderBufferCreationError(err)
+    }
+}
+
+impl From<ValidationError> for OffscreenTargetCreationError {
+    #[inline]
+    fn from(err: ValidationError) -> OffscreenTargetCreationError {
+        OffscreenTargetCreationError::ValidationError(err)
+    }
+}
+
+/// Error that can happen when calling `resolve_to_texture`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The target is multisampled and the texture doesn't have the same dimensions.
+    DimensionsMismatch {
+        /// Dimensions of the offscreen target.
+        target: (u32, u32),
+        /// Dimensions of the texture.
+        texture: (u32, u32),
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveError::DimensionsMismatch { target, texture } => {
+                write!(fmt, "{}: target is {:?}, texture is {:?}", self.description(), target,
+                       texture)
+            },
+        }
+    }
+}
+
+impl Error for ResolveError {
+    fn description(&self) -> &str {
+        match *self {
+            ResolveError::DimensionsMismatch { .. } =>
+                "A multisampled target can only be resolved to a texture of the same dimensions",
+        }
+    }
+}
+
+/// A color render buffer and an optional depth render buffer that can be drawn upon.
+pub struct OffscreenTarget {
+    context: Rc<Context>,
+    color: RenderBuffer,
+    depth: Option<DepthRenderBuffer>,
+}
+
+impl OffscreenTarget {
+    /// Builds a new offscreen target.
+    ///
+    /// If `depth` is `Some`, a depth buffer of the given format is created as well. If `samples`
+    /// is `Some`, all the render buffers are multisampled.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, depth: Option<DepthFormat>,
+                          width: u32, height: u32, samples: Option<u32>)
+                          -> Result<OffscreenTarget, OffscreenTargetCreationError>
+        where F: Facade
+    {
+        let color = match samples {
+            Some(samples) => try!(RenderBuffer::new_multisample(facade, format, width, height,
+                                                                samples)),
+            None => try!(RenderBuffer::new(facade, format, width, height)),
+        };
+
+        let depth = match (depth, samples) {
+            (Some(depth), Some(samples)) => {
+                Some(try!(DepthRenderBuffer::new_multisample(facade, depth, width, height,
+                                                             samples)))
+            },
+            (Some(depth), None) => Some(try!(DepthRenderBuffer::new(facade, depth, width, height))),
+            (None, _) => None,
+        };
+
+        let target = OffscreenTarget {
+            context: facade.get_context().clone(),
+            color: color,
+            depth: depth,
+        };
+
+        // making sure that the attachments are valid together, so that `as_surface` never fails
+        try!(target.build_framebuffer());
+
+        Ok(target)
+    }
+
+    /// Returns the color render buffer of this target.
+    #[inline]
+    pub fn get_color_buffer(&self) -> &RenderBuffer {
+        &self.color
+    }
+
+    /// Returns the depth render buffer of this target, if any.
+    #[inline]
+    pub fn get_depth_buffer(&self) -> Option<&DepthRenderBuffer> {
+        self.depth.as_ref()
+    }
+
+    /// Returns the number of samples of the render buffers, or `None` if multisampling isn't
+    /// enabled.
+    #[inline]
+    pub fn get_samples(&self) -> Option<u32> {
+        self.color.get_samples()
+    }
+
+    /// Returns a `SimpleFrameBuffer` that draws on the render buffers of this target.
+    #[inline]
+    pub fn as_surface<'a>(&'a self) -> SimpleFrameBuffer<'a> {
+        self.build_framebuffer().unwrap()
+    }
+
+    /// Copies the content of the color buffer to the first mipmap level of a texture, resolving
+    /// it if the target is multisampled.
+    ///
+    /// If the target is multisampled, the texture must have the same dimensions as the target
+    /// or an error is returned. Otherwise the content is stretched to cover the whole texture.
+    pub fn resolve_to_texture(&self, texture: &Texture2d) -> Result<(), ResolveError> {
+        let src_dim = self.color.get_dimensions();
+        let tex_dim = (texture.get_width(), texture.get_height().unwrap_or(1));
+
+        if self.get_samples().is_some() && src_dim != tex_dim {
+            return Err(ResolveError::DimensionsMismatch { target: src_dim, texture: tex_dim });
+        }
+
+        let src_rect = Rect { left: 0, bottom: 0, width: src_dim.0, height: src_dim.1 };
+        let target = BlitTarget {
+            left: 0,
+            bottom: 0,
+            width: tex_dim.0 as i32,
+            height: tex_dim.1 as i32,
+        };
+
+        let filter = if src_dim == (target.width as u32, target.height as u32) {
+            uniforms::MagnifySamplerFilter::Nearest
+        } else {
+            uniforms::MagnifySamplerFilter::Linear
+        };
+
+        self.as_surface().blit_color(&src_rect, &texture.as_surface(), &target, filter);
+        Ok(())
+    }
+
+    fn build_framebuffer<'a>(&'a self) -> Result<SimpleFrameBuffer<'a>, ValidationError> {
+        match self.depth {
+            Some(ref depth) => SimpleFrameBuffer::with_depth_buffer(&self.context, &self.color,
+                                                                    depth),
+            None => SimpleFrameBuffer::new(&self.context, &self.color),
+        }
+    }
+}
+
+impl Surface for OffscreenTarget {
+    #[inline]
+    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
+             depth: Option<f32>, stencil: Option<i32>)
+    {
+        self.as_surface().clear(rect, color, color_srgb, depth, stencil)
+    }
+
+    #[inline]
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.color.get_dimensions()
+    }
+
+    #[inline]
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.as_surface().get_depth_buffer_bits()
+    }
+
+    #[inline]
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        None
+    }
+
+    #[inline]
+    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &::Program,
+        uniforms: &U, draw_parameters: &::DrawParameters) -> Result<(), DrawError>
+        where I: Into<::index::IndicesSource<'b>>, U: ::uniforms::Uniforms,
+        V: ::vertex::MultiVerticesSource<'v>
+    {
+        self.as_surface().draw(vb, ib, program, uniforms, draw_parameters)
+    }
+
+    #[inline]
+    fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    {
+        target.blit_from_simple_framebuffer(&self.as_surface(), source_rect, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter)
+    {
+        self.as_surface().blit_from_frame(source_rect, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
+                                    source_rect: &Rect, target_rect: &BlitTarget,
+                                    filter: uniforms::MagnifySamplerFilter)
+    {
+        self.as_surface().blit_from_simple_framebuffer(source, source_rect, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+                                         source_rect: &Rect, target_rect: &BlitTarget,
+                                         filter: uniforms::MagnifySamplerFilter)
+    {
+        self.as_surface().blit_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                           filter)
+    }
+}
@@ -0,0 +1,196 @@
+/*!
+
+Ping-pong targets are two textures of the same format and dimensions, one that is read from and
+one that is written to. After each step, the two textures are swapped so that the result of the
+step becomes the input of the next one.
+
+This is the usual way to implement iterative algorithms on the GPU, such as separable blurs,
+fluid simulations or reaction-diffusion, as a texture can't be sampled while it is rendered to.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let vertex_buffer: glium::VertexBuffer<u8> = unsafe { ::std::mem::uninitialized() };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+# let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+let mut targets = glium::framebuffer::PingPongTargets::new(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            512, 512).unwrap();
+
+for _ in 0 .. 16 {
+    targets.write_surface().draw(&vertex_buffer, &indices, &program,
+                                 &uniform! { state: targets.read() },
+                                 &Default::default()).unwrap();
+    targets.swap();
+}
+
+// `targets.read()` now contains the result of the last step
+# }
+```
+
+*/
+use std::rc::Rc;
+use std::fmt;
+use std::error::Error;
+
+use framebuffer::{SimpleFrameBuffer, ValidationError};
+use texture::{Texture2d, UncompressedFloatFormat, MipmapsOption, TextureCreationError};
+
+use backend::Facade;
+use context::Context;
+
+/// Error that can happen while creating or resizing `PingPongTargets`.
+#[derive(Copy, Clone, Debug)]
+pub enum PingPongTargetsCreationError {
+    /// Error while creating one of the textures.
+    TextureCreationError(TextureCreationError),
+
+    /// The textures can't be used as framebuffer attachments.
+    ValidationError(ValidationError),
+}
+
+impl fmt::Display for PingPongTargetsCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::PingPongTargetsCreationError::*;
+        match *self {
+            TextureCreationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+            ValidationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+        }
+    }
+}
+
+impl Error for PingPongTargetsCreationError {
+    fn description(&self) -> &str {
+        use self::PingPongTargetsCreationError::*;
+        match *self {
+            TextureCreationError(_) => "Error while creating one of the textures",
+            ValidationError(_) => "The textures can't be used as framebuffer attachments",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::PingPongTargetsCreationError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            ValidationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for PingPongTargetsCreationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> PingPongTargetsCreationError {
+        PingPongTargetsCreationError::TextureCreationError(err)
+    }
+}
+
+impl From<ValidationError> for PingPongTargetsCreationError {
+    #[inline]
+    fn from(err: ValidationError) -> PingPongTargetsCreationError {
+        PingPongTargetsCreationError::ValidationError(err)
+    }
+}
+
+/// Two textures of the same format and dimensions, one to read from and one to write to.
+pub struct PingPongTargets {
+    context: Rc<Context>,
+    format: UncompressedFloatFormat,
+    textures: [Texture2d; 2],
+
+    // index of the texture to read from ; the other one is written to
+    read: usize,
+}
+
+impl PingPongTargets {
+    /// Builds two textures of the given format and dimensions.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32)
+                          -> Result<PingPongTargets, PingPongTargetsCreationError>
+        where F: Facade
+    {
+        let context = facade.get_context().clone();
+        let textures = try!(build_textures(&context, format, width, height));
+
+        Ok(PingPongTargets {
+            context: context,
+            format: format,
+            textures: textures,
+            read: 0,
+        })
+    }
+
+    /// Returns the format of the textures.
+    #[inline]
+    pub fn get_format(&self) -> UncompressedFloatFormat {
+        self.format
+    }
+
+    /// Returns the width and height of the textures.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.textures[0].dimensions()
+    }
+
+    /// Returns the texture to read from, which contains the result of the latest step.
+    #[inline]
+    pub fn read(&self) -> &Texture2d {
+        &self.textures[self.read]
+    }
+
+    /// Returns the texture to write to.
+    #[inline]
+    pub fn write(&self) -> &Texture2d {
+        &self.textures[1 - self.read]
+    }
+
+    /// Returns a framebuffer that draws to the texture to write to.
+    #[inline]
+    pub fn write_surface(&self) -> SimpleFrameBuffer {
+        // the attachments have been validated when the textures were created
+        SimpleFrameBuffer::new(&self.context, self.write()).unwrap()
+    }
+
+    /// Exchanges the texture to read from and the texture to write to.
+    #[inline]
+    pub fn swap(&mut self) {
+        self.read = 1 - self.read;
+    }
+
+    /// Rebuilds the textures with new dimensions. Does nothing if the dimensions are the same.
+    ///
+    /// The content of the textures is lost after a resize.
+    pub fn resize(&mut self, width: u32, height: u32)
+                  -> Result<(), PingPongTargetsCreationError>
+    {
+        if self.dimensions() == (width, height) {
+            return Ok(());
+        }
+
+        self.textures = try!(build_textures(&self.context, self.format, width, height));
+        self.read = 0;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PingPongTargets {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "PingPongTargets({:?}, {:?})", self.format, self.dimensions())
+    }
+}
+
+/// Builds the two textures and makes sure that they can be rendered to, so that `write_surface`
+/// never fails.
+fn build_textures(context: &Rc<Context>, format: UncompressedFloatFormat, width: u32,
+                  height: u32) -> Result<[Texture2d; 2], PingPongTargetsCreationError>
+{
+    let first = try!(Texture2d::empty_with_format(context, format, MipmapsOption::NoMipmap,
+                                                  width, height));
+    let second = try!(Texture2d::empty_with_format(context, format, MipmapsOption::NoMipmap,
+                                                   width, height));
+
+    try!(SimpleFrameBuffer::new(context, &first));
+    try!(SimpleFrameBuffer::new(context, &second));
+
+    Ok([first, second])
+}
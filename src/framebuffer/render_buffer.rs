@@ -9,23 +9,27 @@ the data of the render buffer.
 */
 use std::rc::Rc;
 use std::ops::{Deref, DerefMut};
-use std::{ mem, fmt };
+use std::fmt;
 use std::error::Error;
 
 use framebuffer::{ColorAttachment, ToColorAttachment};
 use framebuffer::{DepthAttachment, ToDepthAttachment};
 use framebuffer::{StencilAttachment, ToStencilAttachment};
 use framebuffer::{DepthStencilAttachment, ToDepthStencilAttachment};
-use texture::{UncompressedFloatFormat, DepthFormat, StencilFormat, DepthStencilFormat, TextureKind};
+use texture::{UncompressedFloatFormat, UncompressedIntFormat, UncompressedUintFormat};
+use texture::{DepthFormat, StencilFormat, DepthStencilFormat, TextureKind};
+use texture::Dimensions;
 
 use image_format;
 
 use gl;
 use GlObject;
 use fbo::FramebuffersContainer;
+use debug::ObjectKind;
 use backend::Facade;
 use context::Context;
 use ContextExt;
+use CapabilitiesSource;
 use version::Version;
 use version::Api;
 
@@ -34,6 +38,10 @@ use version::Api;
 pub enum CreationError {
     /// The requested format is not supported.
     FormatNotSupported,
+
+    /// Multisampling was requested, but multisample render buffers are not supported
+    /// by the backend.
+    MultisamplingNotSupported,
 }
 
 impl fmt::Display for CreationError {
@@ -47,6 +55,8 @@ impl Error for CreationError {
         use self::CreationError::*;
         match *self {
             FormatNotSupported => "The requested format is not supported",
+            MultisamplingNotSupported =>
+                "Multisample render buffers are not supported by the backend",
         }
     }
 }
@@ -69,11 +79,29 @@ impl RenderBuffer {
     pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32)
                   -> Result<RenderBuffer, CreationError> where F: Facade
     {
-        let format = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedFloat(format));
-        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedFloat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
 
         Ok(RenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::Float, width, height, None)
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Float, width, height, None)
+        })
+    }
+
+    /// Builds a new multisample render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32,
+                                      height: u32, samples: u32)
+                                      -> Result<RenderBuffer, CreationError>
+        where F: Facade
+    {
+        if !is_multisampling_supported(facade.get_context()) {
+            return Err(CreationError::MultisamplingNotSupported);
+        }
+
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedFloat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(RenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Float, width, height, Some(samples))
         })
     }
 }
@@ -110,6 +138,148 @@ impl GlObject for RenderBuffer {
     }
 }
 
+/// A render buffer that contains signed integral data.
+///
+/// Contrary to a texture, you can't sample or modify the content of the `IntegralRenderBuffer` directly.
+pub struct IntegralRenderBuffer {
+    buffer: RenderBufferAny,
+}
+
+impl IntegralRenderBuffer {
+    /// Builds a new render buffer.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedIntFormat, width: u32, height: u32)
+                  -> Result<IntegralRenderBuffer, CreationError> where F: Facade
+    {
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedIntegral(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(IntegralRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Integral, width, height, None)
+        })
+    }
+
+    /// Builds a new multisample render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: UncompressedIntFormat, width: u32,
+                                      height: u32, samples: u32)
+                                      -> Result<IntegralRenderBuffer, CreationError>
+        where F: Facade
+    {
+        if !is_multisampling_supported(facade.get_context()) {
+            return Err(CreationError::MultisamplingNotSupported);
+        }
+
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedIntegral(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(IntegralRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Integral, width, height, Some(samples))
+        })
+    }
+}
+
+impl<'a> ToColorAttachment<'a> for &'a IntegralRenderBuffer {
+    #[inline]
+    fn to_color_attachment(self) -> ColorAttachment<'a> {
+        ColorAttachment::IntegralRenderBuffer(self)
+    }
+}
+
+impl Deref for IntegralRenderBuffer {
+    type Target = RenderBufferAny;
+
+    #[inline]
+    fn deref(&self) -> &RenderBufferAny {
+        &self.buffer
+    }
+}
+
+impl DerefMut for IntegralRenderBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut RenderBufferAny {
+        &mut self.buffer
+    }
+}
+
+impl GlObject for IntegralRenderBuffer {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.buffer.get_id()
+    }
+}
+
+/// A render buffer that contains unsigned integral data.
+///
+/// Contrary to a texture, you can't sample or modify the content of the `UnsignedRenderBuffer` directly.
+pub struct UnsignedRenderBuffer {
+    buffer: RenderBufferAny,
+}
+
+impl UnsignedRenderBuffer {
+    /// Builds a new render buffer.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedUintFormat, width: u32, height: u32)
+                  -> Result<UnsignedRenderBuffer, CreationError> where F: Facade
+    {
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedUnsigned(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(UnsignedRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Unsigned, width, height, None)
+        })
+    }
+
+    /// Builds a new multisample render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: UncompressedUintFormat, width: u32,
+                                      height: u32, samples: u32)
+                                      -> Result<UnsignedRenderBuffer, CreationError>
+        where F: Facade
+    {
+        if !is_multisampling_supported(facade.get_context()) {
+            return Err(CreationError::MultisamplingNotSupported);
+        }
+
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::UncompressedUnsigned(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(UnsignedRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Unsigned, width, height, Some(samples))
+        })
+    }
+}
+
+impl<'a> ToColorAttachment<'a> for &'a UnsignedRenderBuffer {
+    #[inline]
+    fn to_color_attachment(self) -> ColorAttachment<'a> {
+        ColorAttachment::UnsignedRenderBuffer(self)
+    }
+}
+
+impl Deref for UnsignedRenderBuffer {
+    type Target = RenderBufferAny;
+
+    #[inline]
+    fn deref(&self) -> &RenderBufferAny {
+        &self.buffer
+    }
+}
+
+impl DerefMut for UnsignedRenderBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut RenderBufferAny {
+        &mut self.buffer
+    }
+}
+
+impl GlObject for UnsignedRenderBuffer {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.buffer.get_id()
+    }
+}
+
 /// A render buffer is similar to a texture, but is optimized for usage as a draw target.
 ///
 /// Contrary to a texture, you can't sample or modify the content of the `DepthRenderBuffer` directly.
@@ -122,11 +292,28 @@ impl DepthRenderBuffer {
     pub fn new<F: ?Sized>(facade: &F, format: DepthFormat, width: u32, height: u32)
                   -> Result<DepthRenderBuffer, CreationError> where F: Facade
     {
-        let format = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthFormat(format));
-        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthFormat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
 
         Ok(DepthRenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::Depth, width, height, None)
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Depth, width, height, None)
+        })
+    }
+
+    /// Builds a new multisample render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: DepthFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<DepthRenderBuffer, CreationError>
+        where F: Facade
+    {
+        if !is_multisampling_supported(facade.get_context()) {
+            return Err(CreationError::MultisamplingNotSupported);
+        }
+
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthFormat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(DepthRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Depth, width, height, Some(samples))
         })
     }
 }
@@ -174,11 +361,28 @@ impl StencilRenderBuffer {
     pub fn new<F: ?Sized>(facade: &F, format: StencilFormat, width: u32, height: u32)
                   -> Result<StencilRenderBuffer, CreationError> where F: Facade
     {
-        let format = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::StencilFormat(format));
-        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::StencilFormat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
 
         Ok(StencilRenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::Stencil, width, height, None)
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Stencil, width, height, None)
+        })
+    }
+
+    /// Builds a new multisample render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: StencilFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<StencilRenderBuffer, CreationError>
+        where F: Facade
+    {
+        if !is_multisampling_supported(facade.get_context()) {
+            return Err(CreationError::MultisamplingNotSupported);
+        }
+
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::StencilFormat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(StencilRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::Stencil, width, height, Some(samples))
         })
     }
 }
@@ -227,11 +431,28 @@ impl DepthStencilRenderBuffer {
     pub fn new<F: ?Sized>(facade: &F, format: DepthStencilFormat, width: u32, height: u32)
                   -> Result<DepthStencilRenderBuffer, CreationError> where F: Facade
     {
-        let format = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthStencilFormat(format));
-        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthStencilFormat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
+
+        Ok(DepthStencilRenderBuffer {
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::DepthStencil, width, height, None)
+        })
+    }
+
+    /// Builds a new multisample render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: DepthStencilFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<DepthStencilRenderBuffer, CreationError>
+        where F: Facade
+    {
+        if !is_multisampling_supported(facade.get_context()) {
+            return Err(CreationError::MultisamplingNotSupported);
+        }
+
+        let request = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthStencilFormat(format));
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), request, image_format::RequestType::Renderbuffer));
 
         Ok(DepthStencilRenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::DepthStencil, width, height, None)
+            buffer: RenderBufferAny::new(facade, request, format, TextureKind::DepthStencil, width, height, Some(samples))
         })
     }
 }
@@ -268,6 +489,19 @@ impl GlObject for DepthStencilRenderBuffer {
     }
 }
 
+/// Returns true if the backend supports creating multisample render buffers.
+fn is_multisampling_supported(context: &Context) -> bool {
+    context.get_version() >= &Version(Api::Gl, 3, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_direct_state_access ||
+    context.get_extensions().gl_apple_framebuffer_multisample ||
+    context.get_extensions().gl_angle_framebuffer_multisample ||
+    context.get_extensions().gl_ext_multisampled_render_to_texture ||
+    context.get_extensions().gl_nv_framebuffer_multisample ||
+    (context.get_extensions().gl_ext_framebuffer_object &&
+     context.get_extensions().gl_ext_framebuffer_multisample)
+}
+
 /// A RenderBuffer of indeterminate type.
 pub struct RenderBufferAny {
     context: Rc<Context>,
@@ -280,7 +514,8 @@ pub struct RenderBufferAny {
 
 impl RenderBufferAny {
     /// Builds a new render buffer.
-    fn new<F: ?Sized>(facade: &F, format: gl::types::GLenum, kind: TextureKind, width: u32, height: u32,
+    fn new<F: ?Sized>(facade: &F, request: image_format::TextureFormatRequest,
+              format: gl::types::GLenum, kind: TextureKind, width: u32, height: u32,
               samples: Option<u32>) -> RenderBufferAny
         where F: Facade
     {
@@ -288,7 +523,7 @@ impl RenderBufferAny {
             // TODO: check that dimensions don't exceed GL_MAX_RENDERBUFFER_SIZE
             // FIXME: gles2 only supports very few formats
             let mut ctxt = facade.get_context().make_current();
-            let mut id = mem::uninitialized();
+            let mut id = 0;
 
             if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                ctxt.extensions.gl_arb_direct_state_access
@@ -393,6 +628,15 @@ impl RenderBufferAny {
                 unreachable!();
             }
 
+            let dimensions = match samples {
+                Some(samples) => Dimensions::Texture2dMultisample {
+                    width: width, height: height, samples: samples
+                },
+                None => Dimensions::Texture2d { width: width, height: height },
+            };
+            ctxt.live_objects.insert(ObjectKind::RenderBuffer, id, gl::RENDERBUFFER, None,
+                                     Some(format!("{:?}", request)), Some(dimensions));
+
             RenderBufferAny {
                 context: facade.get_context().clone(),
                 id: id,
@@ -433,8 +677,8 @@ impl RenderBufferAny {
     pub fn get_depth_stencil_bits(&self) -> (u16, u16) {
         unsafe {
             let mut ctxt = self.context.make_current();
-            let mut depth_bits: gl::types::GLint = mem::uninitialized();
-            let mut stencil_bits: gl::types::GLint = mem::uninitialized();
+            let mut depth_bits: gl::types::GLint = 0;
+            let mut stencil_bits: gl::types::GLint = 0;
             ctxt.gl.BindRenderbuffer(gl::RENDERBUFFER, self.id);
             // FIXME: GL version considerations
             ctxt.gl.GetRenderbufferParameteriv(gl::RENDERBUFFER, gl::RENDERBUFFER_DEPTH_SIZE, &mut depth_bits);
@@ -452,6 +696,7 @@ impl Drop for RenderBufferAny {
 
             // removing FBOs which contain this buffer
             FramebuffersContainer::purge_renderbuffer(&mut ctxt, self.id);
+            ctxt.live_objects.remove(ObjectKind::RenderBuffer, self.id);
 
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                ctxt.version >= &Version(Api::GlEs, 2, 0)
@@ -473,6 +718,8 @@ impl Drop for RenderBufferAny {
                 unreachable!();
             }
         }
+
+        self.context.purge_shared_object(ObjectKind::RenderBuffer, self.id);
     }
 }
 
@@ -0,0 +1,259 @@
+/*!
+
+A target pool hands out transient textures to render to, such as the intermediate targets of a
+post-processing chain.
+
+Textures are requested by format and by size relative to the dimensions of the default
+framebuffer. A texture is returned to the pool when its `PooledTarget` is dropped, and can then
+be handed out again by a later request with the same format and relative size. When the window
+is resized, requests automatically build textures with the new dimensions, and the textures of
+the old dimensions are destroyed by the next calls to `end_frame`.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::framebuffer::TargetPool;
+use glium::texture::UncompressedFloatFormat;
+
+let mut pool = TargetPool::new(&display);
+
+// each frame
+{
+    let scene = pool.acquire(UncompressedFloatFormat::F16F16F16F16, 1.0).unwrap();
+    let half = pool.acquire(UncompressedFloatFormat::F16F16F16F16, 0.5).unwrap();
+    scene.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+    half.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+    // draw the scene, downsample it, etc.
+}
+pool.end_frame();
+# }
+```
+
+*/
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use framebuffer::{SimpleFrameBuffer, ValidationError};
+use texture::{Texture2d, UncompressedFloatFormat, MipmapsOption, TextureCreationError};
+
+use backend::Facade;
+use context::Context;
+
+/// Error that can happen while acquiring a target from a `TargetPool`.
+#[derive(Copy, Clone, Debug)]
+pub enum TargetPoolError {
+    /// Error while creating the texture.
+    TextureCreationError(TextureCreationError),
+
+    /// The texture can't be used as a framebuffer attachment.
+    ValidationError(ValidationError),
+}
+
+impl fmt::Display for TargetPoolError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::TargetPoolError::*;
+        match *self {
+            TextureCreationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+            ValidationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+        }
+    }
+}
+
+impl Error for TargetPoolError {
+    fn description(&self) -> &str {
+        use self::TargetPoolError::*;
+        match *self {
+            TextureCreationError(_) => "Error while creating the texture",
+            ValidationError(_) => "The texture can't be used as a framebuffer attachment",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::TargetPoolError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            ValidationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for TargetPoolError {
+    #[inline]
+    fn from(err: TextureCreationError) -> TargetPoolError {
+        TargetPoolError::TextureCreationError(err)
+    }
+}
+
+impl From<ValidationError> for TargetPoolError {
+    #[inline]
+    fn from(err: ValidationError) -> TargetPoolError {
+        TargetPoolError::ValidationError(err)
+    }
+}
+
+/// A texture of the pool.
+struct Entry {
+    format: UncompressedFloatFormat,
+    texture: Rc<Texture2d>,
+
+    // true while a `PooledTarget` that points to this texture is alive
+    in_use: Rc<Cell<bool>>,
+
+    // true if the texture has been acquired since the latest call to `end_frame`
+    used: bool,
+}
+
+/// Hands out textures to render to, and reuses them once they have been released.
+pub struct TargetPool {
+    context: Rc<Context>,
+    entries: Vec<Entry>,
+}
+
+impl TargetPool {
+    /// Builds an empty pool. Textures are only created when they are requested.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F) -> TargetPool where F: Facade {
+        TargetPool {
+            context: facade.get_context().clone(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the context used to create this pool.
+    #[inline]
+    pub fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    /// Returns the dimensions that a texture acquired with the given relative size would have.
+    ///
+    /// The dimensions are the ones of the default framebuffer multiplied by `scale`, rounded to
+    /// the nearest pixel and with a minimum of one pixel.
+    #[inline]
+    pub fn target_dimensions(&self, scale: f32) -> (u32, u32) {
+        let (width, height) = self.context.get_framebuffer_dimensions();
+        let width = (width as f32 * scale).round().max(1.0) as u32;
+        let height = (height as f32 * scale).round().max(1.0) as u32;
+        (width, height)
+    }
+
+    /// Returns a texture of the given format, whose dimensions are the ones of the default
+    /// framebuffer multiplied by `scale`.
+    ///
+    /// A texture that has been released is reused if possible. Otherwise a new texture is
+    /// created. The content of the texture is undefined.
+    pub fn acquire(&mut self, format: UncompressedFloatFormat, scale: f32)
+                   -> Result<PooledTarget, TargetPoolError>
+    {
+        let dimensions = self.target_dimensions(scale);
+
+        let existing = self.entries.iter_mut().find(|entry| {
+            !entry.in_use.get() && entry.format == format &&
+                entry.texture.dimensions() == dimensions
+        });
+
+        if let Some(entry) = existing {
+            entry.in_use.set(true);
+            entry.used = true;
+            return Ok(PooledTarget {
+                texture: entry.texture.clone(),
+                in_use: entry.in_use.clone(),
+            });
+        }
+
+        let texture = try!(Texture2d::empty_with_format(&self.context, format,
+                                                        MipmapsOption::NoMipmap,
+                                                        dimensions.0, dimensions.1));
+
+        // making sure that `as_surface` never fails
+        try!(SimpleFrameBuffer::new(&self.context, &texture));
+
+        let texture = Rc::new(texture);
+        let in_use = Rc::new(Cell::new(true));
+
+        self.entries.push(Entry {
+            format: format,
+            texture: texture.clone(),
+            in_use: in_use.clone(),
+            used: true,
+        });
+
+        Ok(PooledTarget {
+            texture: texture,
+            in_use: in_use,
+        })
+    }
+
+    /// Destroys the released textures that haven't been acquired since the previous call to
+    /// `end_frame`.
+    ///
+    /// You should call this function once per frame. This is what destroys the textures whose
+    /// dimensions no longer match the ones of the window after a resize.
+    pub fn end_frame(&mut self) {
+        self.entries.retain(|entry| entry.used || entry.in_use.get());
+
+        for entry in self.entries.iter_mut() {
+            entry.used = false;
+        }
+    }
+
+    /// Destroys all the textures that are not in use.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.retain(|entry| entry.in_use.get());
+    }
+
+    /// Returns the number of textures owned by the pool, including the ones in use.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the pool doesn't own any texture.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Debug for TargetPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "TargetPool({} textures)", self.entries.len())
+    }
+}
+
+/// A texture acquired from a `TargetPool`. The texture is returned to the pool when this object
+/// is dropped.
+///
+/// Dereferences to a `Texture2d`, whose `as_surface` method never panics.
+pub struct PooledTarget {
+    texture: Rc<Texture2d>,
+    in_use: Rc<Cell<bool>>,
+}
+
+impl Deref for PooledTarget {
+    type Target = Texture2d;
+
+    #[inline]
+    fn deref(&self) -> &Texture2d {
+        &self.texture
+    }
+}
+
+impl Drop for PooledTarget {
+    #[inline]
+    fn drop(&mut self) {
+        self.in_use.set(false);
+    }
+}
+
+impl fmt::Debug for PooledTarget {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "PooledTarget({:?})", self.texture.dimensions())
+    }
+}
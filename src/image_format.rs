@@ -71,6 +71,7 @@ pub enum TextureFormatRequest {
 /// These are all the possible formats of input data when uploading to a texture.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ClientFormat {
     U8,
     U8U8,
@@ -109,6 +110,8 @@ pub enum ClientFormat {
     F32F32,
     F32F32F32,
     F32F32F32F32,
+    F11F11F10,
+    F9F9F9,
 }
 
 impl ClientFormat {
@@ -154,6 +157,8 @@ impl ClientFormat {
             ClientFormat::F32F32 => 2 * mem::size_of::<f32>(),
             ClientFormat::F32F32F32 => 3 * mem::size_of::<f32>(),
             ClientFormat::F32F32F32F32 => 4 * mem::size_of::<f32>(),
+            ClientFormat::F11F11F10 => (11 + 11 + 10) / 8,
+            ClientFormat::F9F9F9 => (9 + 9 + 9 + 5) / 8,
         }
     }
 
@@ -197,6 +202,8 @@ impl ClientFormat {
             ClientFormat::F32F32 => 2,
             ClientFormat::F32F32F32 => 3,
             ClientFormat::F32F32F32F32 => 4,
+            ClientFormat::F11F11F10 => 3,
+            ClientFormat::F9F9F9 => 3,
         }
     }
 }
@@ -208,6 +215,7 @@ impl ClientFormat {
 /// is not supported by the backend, it will automatically fall back to a larger format.
 // TODO: missing RGB565
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum UncompressedFloatFormat {
     ///
     ///
@@ -327,15 +335,18 @@ pub enum UncompressedFloatFormat {
     ///
     /// Guaranteed to be supported for both textures and renderbuffers.
     F32F32F32F32,
+    /// Uses three unsigned floating-point components of 11, 11 and 10 bits.
     ///
+    /// Negative values can't be represented. Can be uploaded with `ClientFormat::F11F11F10`.
     ///
     /// Guaranteed to be supported for both textures and renderbuffers.
     F11F11F10,
     /// Uses three components of 9 bits of precision that all share the same exponent.
     ///
-    /// Use this format only if all the components are approximately equal.
+    /// Use this format only if all the components are approximately equal. Negative values
+    /// can't be represented. Can be uploaded with `ClientFormat::F9F9F9`.
     ///
-    /// Guaranteed to be supported for textures.
+    /// Guaranteed to be supported for textures. Never renderable.
     F9F9F9,
 }
 
@@ -404,12 +415,12 @@ impl UncompressedFloatFormat {
                     extensions.gl_ext_texture_snorm
             },
             &UncompressedFloatFormat::U16 => {
-                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
-                    extensions.gl_arb_texture_rg
+                version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_texture_rg ||
+                    (version >= &Version(Api::GlEs, 3, 0) && extensions.gl_ext_texture_norm16)
             },
             &UncompressedFloatFormat::I16 => {
-                version >= &Version(Api::Gl, 3, 2) || version >= &Version(Api::GlEs, 3, 0) ||
-                    extensions.gl_ext_texture_snorm
+                version >= &Version(Api::Gl, 3, 2) || extensions.gl_ext_texture_snorm ||
+                    (version >= &Version(Api::GlEs, 3, 0) && extensions.gl_ext_texture_norm16)
             },
             &UncompressedFloatFormat::U8U8 => {
                 version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
@@ -420,12 +431,12 @@ impl UncompressedFloatFormat {
                     extensions.gl_ext_texture_snorm
             },
             &UncompressedFloatFormat::U16U16 => {
-                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
-                    extensions.gl_arb_texture_rg
+                version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_texture_rg ||
+                    (version >= &Version(Api::GlEs, 3, 0) && extensions.gl_ext_texture_norm16)
             },
             &UncompressedFloatFormat::I16I16 => {
-                version >= &Version(Api::Gl, 3, 2) || version >= &Version(Api::GlEs, 3, 0) ||
-                    extensions.gl_ext_texture_snorm
+                version >= &Version(Api::Gl, 3, 2) || extensions.gl_ext_texture_snorm ||
+                    (version >= &Version(Api::GlEs, 3, 0) && extensions.gl_ext_texture_norm16)
             },
             &UncompressedFloatFormat::U3U3U2 => {
                 version >= &Version(Api::Gl, 1, 1) || version >= &Version(Api::GlEs, 3, 0)
@@ -518,7 +529,7 @@ impl UncompressedFloatFormat {
                     extensions.gl_arb_texture_float || extensions.gl_ati_texture_float
             },
             &UncompressedFloatFormat::F11F11F10 => {
-                version >= &Version(Api::Gl, 3, 2) || version >= &Version(Api::GlEs, 3, 0) ||
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
                     extensions.gl_ext_packed_float
             },
             &UncompressedFloatFormat::F9F9F9 => {
@@ -569,13 +580,21 @@ impl UncompressedFloatFormat {
                 extensions.gl_oes_rgb8_rgba8
             },
             &UncompressedFloatFormat::U10U10U10U2 => version >= &Version(Api::GlEs, 3, 0),
-            &UncompressedFloatFormat::F16 => version >= &Version(Api::GlEs, 3, 2),
-            &UncompressedFloatFormat::F16F16 => version >= &Version(Api::GlEs, 3, 2),
-            &UncompressedFloatFormat::F16F16F16F16 => version >= &Version(Api::GlEs, 3, 2),
-            &UncompressedFloatFormat::F32 => version >= &Version(Api::GlEs, 3, 2),
-            &UncompressedFloatFormat::F32F32 => version >= &Version(Api::GlEs, 3, 2),
-            &UncompressedFloatFormat::F32F32F32F32 => version >= &Version(Api::GlEs, 3, 2),
-            &UncompressedFloatFormat::F11F11F10 => version >= &Version(Api::GlEs, 3, 2),
+            &UncompressedFloatFormat::U16 => extensions.gl_ext_texture_norm16,
+            &UncompressedFloatFormat::U16U16 => extensions.gl_ext_texture_norm16,
+            &UncompressedFloatFormat::U16U16U16U16 => extensions.gl_ext_texture_norm16,
+            &UncompressedFloatFormat::F16 |
+            &UncompressedFloatFormat::F16F16 |
+            &UncompressedFloatFormat::F16F16F16F16 => {
+                version >= &Version(Api::GlEs, 3, 2) || extensions.gl_ext_color_buffer_float ||
+                    extensions.gl_ext_color_buffer_half_float
+            },
+            &UncompressedFloatFormat::F32 |
+            &UncompressedFloatFormat::F32F32 |
+            &UncompressedFloatFormat::F32F32F32F32 |
+            &UncompressedFloatFormat::F11F11F10 => {
+                version >= &Version(Api::GlEs, 3, 2) || extensions.gl_ext_color_buffer_float
+            },
             _ => false
         }
     }
@@ -625,6 +644,7 @@ impl UncompressedFloatFormat {
 /// List of uncompressed pixel formats that contain floating-point data in the sRGB color space.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SrgbFormat {
     U8U8U8,
     U8U8U8U8,
@@ -693,6 +713,7 @@ impl SrgbFormat {
 /// List of uncompressed pixel formats that contain signed integral data.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum UncompressedIntFormat {
     I8,
     I16,
@@ -744,57 +765,63 @@ impl UncompressedIntFormat {
 
         match self {
             &UncompressedIntFormat::I8 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedIntFormat::I16 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedIntFormat::I32 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedIntFormat::I8I8 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedIntFormat::I16I16 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedIntFormat::I32I32 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedIntFormat::I8I8I8 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedIntFormat::I16I16I16 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedIntFormat::I32I32I32 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedIntFormat::I8I8I8I8 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedIntFormat::I16I16I16I16 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedIntFormat::I32I32I32I32 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
         }
     }
@@ -852,6 +879,7 @@ impl UncompressedIntFormat {
 /// List of uncompressed pixel formats that contain unsigned integral data.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum UncompressedUintFormat {
     U8,
     U16,
@@ -905,61 +933,68 @@ impl UncompressedUintFormat {
 
         match self {
             &UncompressedUintFormat::U8 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedUintFormat::U16 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedUintFormat::U32 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedUintFormat::U8U8 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedUintFormat::U16U16 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedUintFormat::U32U32 => {
-                version >= &Version(Api::Gl, 3, 0) || (extensions.gl_ext_texture_integer &&
-                                                       extensions.gl_arb_texture_rg)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    (extensions.gl_ext_texture_integer && extensions.gl_arb_texture_rg)
             },
 
             &UncompressedUintFormat::U8U8U8 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedUintFormat::U16U16U16 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedUintFormat::U32U32U32 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedUintFormat::U8U8U8U8 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedUintFormat::U16U16U16U16 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedUintFormat::U32U32U32U32 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_texture_integer
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_texture_integer
             },
 
             &UncompressedUintFormat::U10U10U10U2 => {
-                version >= &Version(Api::Gl, 3, 3) || extensions.gl_arb_texture_rgb10_a2ui
+                version >= &Version(Api::Gl, 3, 3) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_arb_texture_rgb10_a2ui
             },
         }
     }
@@ -1018,6 +1053,7 @@ impl UncompressedUintFormat {
 
 /// List of compressed texture formats.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum CompressedFormat {
     /// Red/green compressed texture with one unsigned component.
     RgtcFormatU,
@@ -1132,6 +1168,7 @@ impl CompressedFormat {
 /// List of compressed pixel formats in the sRGB color space.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum CompressedSrgbFormat {
     /// BPTC format. sRGB with alpha. Also called `BC7` by DirectX.
     Bptc,
@@ -1201,6 +1238,7 @@ impl CompressedSrgbFormat {
 /// Only the internal representation is integral.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum DepthFormat {
     I16,
     I24,
@@ -1234,23 +1272,46 @@ impl DepthFormat {
 
         match self {
             &DepthFormat::I16 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_depth_texture
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_arb_depth_texture || extensions.gl_oes_depth_texture
             },
 
             &DepthFormat::I24 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_depth_texture
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_arb_depth_texture ||
+                    (extensions.gl_oes_depth_texture && extensions.gl_oes_depth24)
             },
 
             &DepthFormat::I32 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_depth_texture
+                version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_depth_texture ||
+                    (extensions.gl_oes_depth_texture && extensions.gl_oes_depth32)
             },
 
             &DepthFormat::F32 => {
-                version >= &Version(Api::Gl, 3, 0)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_arb_depth_buffer_float
             },
         }
     }
 
+    /// Returns true if a texture or renderbuffer with this format can be used as the depth
+    /// attachment of a framebuffer.
+    ///
+    /// This only checks the version and extensions. The `renderable` member of the `FormatInfos`
+    /// found in the capabilities tells whether the current driver actually supports it.
+    pub fn is_renderable<C: ?Sized>(&self, context: &C) -> bool where C: CapabilitiesSource {
+        if !self.is_supported(context) {
+            return false;
+        }
+
+        let version = context.get_version();
+        let extensions = context.get_extensions();
+
+        // depth textures are always renderable, as long as framebuffer objects are supported
+        version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 2, 0) ||
+            extensions.gl_arb_framebuffer_object || extensions.gl_ext_framebuffer_object
+    }
+
     fn to_glenum(&self) -> gl::types::GLenum {
         match self {
             &DepthFormat::I16 => gl::DEPTH_COMPONENT16,
@@ -1266,6 +1327,7 @@ impl DepthFormat {
 //       textures are treated by samplers exactly like depth-only textures
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum DepthStencilFormat {
     I24I8,
     F32I8,
@@ -1294,16 +1356,32 @@ impl DepthStencilFormat {
 
         match self {
             &DepthStencilFormat::I24I8 => {
-                version >= &Version(Api::Gl, 3, 0) || extensions.gl_ext_packed_depth_stencil ||
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_ext_packed_depth_stencil ||
                     extensions.gl_oes_packed_depth_stencil
             },
 
             &DepthStencilFormat::F32I8 => {
-                version >= &Version(Api::Gl, 3, 0)
+                version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+                    extensions.gl_arb_depth_buffer_float
             },
         }
     }
 
+    /// Returns true if a texture or renderbuffer with this format can be used as the
+    /// depth-stencil attachment of a framebuffer.
+    pub fn is_renderable<C: ?Sized>(&self, context: &C) -> bool where C: CapabilitiesSource {
+        if !self.is_supported(context) {
+            return false;
+        }
+
+        let version = context.get_version();
+        let extensions = context.get_extensions();
+
+        version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 2, 0) ||
+            extensions.gl_arb_framebuffer_object || extensions.gl_ext_framebuffer_object
+    }
+
     fn to_glenum(&self) -> gl::types::GLenum {
         match self {
             &DepthStencilFormat::I24I8 => gl::DEPTH24_STENCIL8,
@@ -1320,6 +1398,7 @@ impl DepthStencilFormat {
 /// Only `I8` is supported for textures. All the other formats can only be used with renderbuffers.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum StencilFormat {
     I1,
     I4,
@@ -1380,6 +1459,7 @@ impl StencilFormat {
 
             &StencilFormat::I8 => {
                 version >= &Version(Api::Gl, 3, 0) || extensions.gl_arb_texture_stencil8 ||
+                    extensions.gl_ext_framebuffer_object || extensions.gl_arb_framebuffer_object ||
                     version >= &Version(Api::GlEs, 2, 0)
             },
 
@@ -1402,6 +1482,7 @@ impl StencilFormat {
 
 /// Format of the internal representation of a texture.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[allow(missing_docs)]
 pub enum TextureFormat {
     UncompressedFloat(UncompressedFloatFormat),
@@ -1449,15 +1530,17 @@ impl TextureFormat {
     }
 
     /// Returns true if this format is supported by the backend for renderbuffers.
+    ///
+    /// Color formats must be color-renderable in order to be used with renderbuffers.
     #[inline]
     pub fn is_supported_for_renderbuffers<C: ?Sized>(&self, c: &C) -> bool where C: CapabilitiesSource {
         match self {
-            &TextureFormat::UncompressedFloat(format) => format.is_supported(c),
-            &TextureFormat::UncompressedIntegral(format) => format.is_supported(c),
-            &TextureFormat::UncompressedUnsigned(format) => format.is_supported(c),
-            &TextureFormat::Srgb(format) => format.is_supported(c),
-            &TextureFormat::CompressedFormat(format) => format.is_supported(c),
-            &TextureFormat::CompressedSrgbFormat(format) => format.is_supported(c),
+            &TextureFormat::UncompressedFloat(format) => format.is_color_renderable(c),
+            &TextureFormat::UncompressedIntegral(format) => format.is_color_renderable(c),
+            &TextureFormat::UncompressedUnsigned(format) => format.is_color_renderable(c),
+            &TextureFormat::Srgb(format) => format.is_color_renderable(c),
+            &TextureFormat::CompressedFormat(_) => false,
+            &TextureFormat::CompressedSrgbFormat(_) => false,
             &TextureFormat::DepthFormat(format) => format.is_supported(c),
             &TextureFormat::StencilFormat(format) => format.is_supported_for_renderbuffers(c),
             &TextureFormat::DepthStencilFormat(format) => format.is_supported(c),
@@ -1475,9 +1558,9 @@ impl TextureFormat {
             &TextureFormat::Srgb(format) => format.is_color_renderable(c),
             &TextureFormat::CompressedFormat(_) => false,
             &TextureFormat::CompressedSrgbFormat(_) => false,
-            &TextureFormat::DepthFormat(_) => true,
+            &TextureFormat::DepthFormat(format) => format.is_renderable(c),
             &TextureFormat::StencilFormat(_) => true,
-            &TextureFormat::DepthStencilFormat(_) => true,
+            &TextureFormat::DepthStencilFormat(format) => format.is_renderable(c),
         }
     }
 }
@@ -1728,7 +1811,12 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::UncompressedFloat(format)) => {
-            if format.is_supported(context) {
+            let supported = match rq_ty {
+                RequestType::Renderbuffer => format.is_color_renderable(context),
+                _ => format.is_supported(context),
+            };
+
+            if supported {
                 format.to_glenum()
             } else {
                 return Err(FormatNotSupportedError);
@@ -1819,7 +1907,12 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::Srgb(format)) => {
-            if format.is_supported(context) {
+            let supported = match rq_ty {
+                RequestType::Renderbuffer => format.is_color_renderable(context),
+                _ => format.is_supported(context),
+            };
+
+            if supported {
                 format.to_glenum()
             } else {
                 return Err(FormatNotSupportedError);
@@ -1873,7 +1966,7 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         TextureFormatRequest::AnyIntegral => {
             let size = rq_ty.get_client_format().map(|c| c.get_num_components());
 
-            if version >= &Version(Api::Gl, 3, 0) {
+            if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) {
                 match size {  // FIXME: choose between 8, 16 and 32 depending on the client format
                     Some(1) => gl::R32I,
                     Some(2) => gl::RG32I,
@@ -1908,7 +2001,12 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::UncompressedIntegral(format)) => {
-            if format.is_supported(context) {
+            let supported = match rq_ty {
+                RequestType::Renderbuffer => format.is_color_renderable(context),
+                _ => format.is_supported(context),
+            };
+
+            if supported {
                 format.to_glenum()
             } else {
                 return Err(FormatNotSupportedError);
@@ -1921,7 +2019,7 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         TextureFormatRequest::AnyUnsigned => {
             let size = rq_ty.get_client_format().map(|c| c.get_num_components());
 
-            if version >= &Version(Api::Gl, 3, 0) {
+            if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) {
                 match size {  // FIXME: choose between 8, 16 and 32 depending on the client format
                     Some(1) => gl::R32UI,
                     Some(2) => gl::RG32UI,
@@ -1956,7 +2054,12 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::UncompressedUnsigned(format)) => {
-            if format.is_supported(context) {
+            let supported = match rq_ty {
+                RequestType::Renderbuffer => format.is_color_renderable(context),
+                _ => format.is_supported(context),
+            };
+
+            if supported {
                 format.to_glenum()
             } else {
                 return Err(FormatNotSupportedError);
@@ -2156,7 +2259,7 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
                 ClientFormatAny::ClientFormat(ClientFormat::U5U6U5) => Ok((gl::RGB, gl::UNSIGNED_SHORT_5_6_5)),
                 ClientFormatAny::ClientFormat(ClientFormat::U4U4U4U4) => Ok((gl::RGBA, gl::UNSIGNED_SHORT_4_4_4_4)),
                 ClientFormatAny::ClientFormat(ClientFormat::U5U5U5U1) => Ok((gl::RGBA, gl::UNSIGNED_SHORT_5_5_5_1)),
-                ClientFormatAny::ClientFormat(ClientFormat::U10U10U10U2) => Ok((gl::RGBA, gl::UNSIGNED_INT_10_10_10_2)),
+                ClientFormatAny::ClientFormat(ClientFormat::U10U10U10U2) => Ok((gl::RGBA, gl::UNSIGNED_INT_2_10_10_10_REV)),
                 ClientFormatAny::ClientFormat(ClientFormat::F16) => Ok((gl::RED, gl::HALF_FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F16F16) => Ok((gl::RG, gl::HALF_FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F16F16F16) => Ok((gl::RGB, gl::HALF_FLOAT)),
@@ -2165,6 +2268,8 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
                 ClientFormatAny::ClientFormat(ClientFormat::F32F32) => Ok((gl::RG, gl::FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F32F32F32) => Ok((gl::RGB, gl::FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F32F32F32F32) => Ok((gl::RGBA, gl::FLOAT)),
+                ClientFormatAny::ClientFormat(ClientFormat::F11F11F10) => Ok((gl::RGB, gl::UNSIGNED_INT_10F_11F_11F_REV)),
+                ClientFormatAny::ClientFormat(ClientFormat::F9F9F9) => Ok((gl::RGB, gl::UNSIGNED_INT_5_9_9_9_REV)),
 
                 // this kind of situation shouldn't happen, it should have a special handling when
                 // client is compressed.
@@ -2206,7 +2311,7 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
                 ClientFormatAny::ClientFormat(ClientFormat::U5U6U5) => Ok((gl::RGB_INTEGER, gl::UNSIGNED_SHORT_5_6_5)),
                 ClientFormatAny::ClientFormat(ClientFormat::U4U4U4U4) => Ok((gl::RGBA_INTEGER, gl::UNSIGNED_SHORT_4_4_4_4)),
                 ClientFormatAny::ClientFormat(ClientFormat::U5U5U5U1) => Ok((gl::RGBA_INTEGER, gl::UNSIGNED_SHORT_5_5_5_1)),
-                ClientFormatAny::ClientFormat(ClientFormat::U10U10U10U2) => Ok((gl::RGBA_INTEGER, gl::UNSIGNED_INT_10_10_10_2)),
+                ClientFormatAny::ClientFormat(ClientFormat::U10U10U10U2) => Ok((gl::RGBA_INTEGER, gl::UNSIGNED_INT_2_10_10_10_REV)),
                 ClientFormatAny::ClientFormat(ClientFormat::F16) => Ok((gl::RED_INTEGER, gl::HALF_FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F16F16) => Ok((gl::RG_INTEGER, gl::HALF_FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F16F16F16) => Ok((gl::RGB_INTEGER, gl::HALF_FLOAT)),
@@ -2216,6 +2321,10 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
                 ClientFormatAny::ClientFormat(ClientFormat::F32F32F32) => Ok((gl::RGB_INTEGER, gl::FLOAT)),
                 ClientFormatAny::ClientFormat(ClientFormat::F32F32F32F32) => Ok((gl::RGBA_INTEGER, gl::FLOAT)),
 
+                // packed floats can't be uploaded to integral textures
+                ClientFormatAny::ClientFormat(ClientFormat::F11F11F10) => Err(FormatNotSupportedError),
+                ClientFormatAny::ClientFormat(ClientFormat::F9F9F9) => Err(FormatNotSupportedError),
+
                 // this kind of situation shouldn't happen, it should have a special handling when
                 // client is compressed.
                 ClientFormatAny::CompressedFormat(_) => unreachable!(),
@@ -2237,14 +2346,14 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
         TextureFormatRequest::Specific(TextureFormat::StencilFormat(_)) =>
         {
             match client {
-                ClientFormatAny::ClientFormat(ClientFormat::U8) => Ok((gl::RED_INTEGER, gl::UNSIGNED_BYTE)),
-                ClientFormatAny::ClientFormat(ClientFormat::I8) => Ok((gl::RED_INTEGER, gl::BYTE)),
-                ClientFormatAny::ClientFormat(ClientFormat::U16) => Ok((gl::RED_INTEGER, gl::UNSIGNED_SHORT)),
-                ClientFormatAny::ClientFormat(ClientFormat::I16) => Ok((gl::RED_INTEGER, gl::SHORT)),
-                ClientFormatAny::ClientFormat(ClientFormat::U32) => Ok((gl::RED_INTEGER, gl::UNSIGNED_INT)),
-                ClientFormatAny::ClientFormat(ClientFormat::I32) => Ok((gl::RED_INTEGER, gl::INT)),
-                ClientFormatAny::ClientFormat(ClientFormat::F16) => Ok((gl::RED_INTEGER, gl::HALF_FLOAT)),
-                ClientFormatAny::ClientFormat(ClientFormat::F32) => Ok((gl::RED_INTEGER, gl::FLOAT)),
+                ClientFormatAny::ClientFormat(ClientFormat::U8) => Ok((gl::STENCIL_INDEX, gl::UNSIGNED_BYTE)),
+                ClientFormatAny::ClientFormat(ClientFormat::I8) => Ok((gl::STENCIL_INDEX, gl::BYTE)),
+                ClientFormatAny::ClientFormat(ClientFormat::U16) => Ok((gl::STENCIL_INDEX, gl::UNSIGNED_SHORT)),
+                ClientFormatAny::ClientFormat(ClientFormat::I16) => Ok((gl::STENCIL_INDEX, gl::SHORT)),
+                ClientFormatAny::ClientFormat(ClientFormat::U32) => Ok((gl::STENCIL_INDEX, gl::UNSIGNED_INT)),
+                ClientFormatAny::ClientFormat(ClientFormat::I32) => Ok((gl::STENCIL_INDEX, gl::INT)),
+                ClientFormatAny::ClientFormat(ClientFormat::F16) => Ok((gl::STENCIL_INDEX, gl::HALF_FLOAT)),
+                ClientFormatAny::ClientFormat(ClientFormat::F32) => Ok((gl::STENCIL_INDEX, gl::FLOAT)),
                 _ => panic!("Can't upload to a stencil texture with more than one channel")
             }
         }
@@ -2258,10 +2367,13 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
 
     if inverted {
         value.and_then(|(format, ty)| {
-            let format = match format {
-                gl::RGB => gl::BGR,
-                gl::RGBA => gl::BGRA,
-                f => return Err(FormatNotSupportedError)
+            let format = match (format, ty) {
+                // packed floats are always in the RGB order
+                (gl::RGB, gl::UNSIGNED_INT_10F_11F_11F_REV) |
+                (gl::RGB, gl::UNSIGNED_INT_5_9_9_9_REV) => return Err(FormatNotSupportedError),
+                (gl::RGB, _) => gl::BGR,
+                (gl::RGBA, _) => gl::BGRA,
+                _ => return Err(FormatNotSupportedError)
             };
 
             Ok((format, ty))
@@ -2,6 +2,7 @@ use buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType};
 use buffer::{BufferMode, BufferCreationError};
 use gl;
 use GlObject;
+use CapabilitiesSource;
 
 use backend::Facade;
 
@@ -47,7 +48,7 @@ impl Error for CreationError {
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref err) => Some(err),
@@ -347,6 +348,26 @@ pub struct IndexBufferAny {
 }
 
 impl IndexBufferAny {
+    /// Builds a new index buffer from a list of `u8` indices and a primitive type.
+    ///
+    /// The indices are stored as `u8` if the backend handles them natively, which reduces the
+    /// memory used by small meshes. Otherwise they are promoted to `u16`. You can call
+    /// `get_indices_type` to know which type has been chosen.
+    ///
+    /// Note that a promoted index of `255` is no longer a primitive restart index. Use `u16`
+    /// indices directly if you enable `primitive_restart_index`.
+    pub fn from_u8<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[u8])
+                              -> Result<IndexBufferAny, CreationError>
+                              where F: Facade
+    {
+        if IndexType::U8.is_supported(facade) && facade.get_capabilities().native_u8_indices {
+            return Ok(try!(IndexBuffer::new(facade, prim, data)).into());
+        }
+
+        let data = data.iter().map(|&index| index as u16).collect::<Vec<_>>();
+        Ok(try!(IndexBuffer::new(facade, prim, &data)).into())
+    }
+
     /// Returns the type of primitives associated with this index buffer.
     #[inline]
     pub fn get_primitives_type(&self) -> PrimitiveType {
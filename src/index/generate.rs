@@ -0,0 +1,261 @@
+/*!
+Functions that build index buffers for common topologies.
+
+```no_run
+# fn main() {
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+use glium::index::generate;
+
+// two triangles per cell of a 16x16 grid of 17x17 vertices
+let terrain: glium::IndexBuffer<u16> = generate::grid(&display, 16, 16).unwrap();
+
+// two triangles for each group of four vertices, for example sprites
+let sprites: glium::IndexBuffer<u32> = generate::quad_list(&display, 1000).unwrap();
+# }
+```
+
+*/
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+
+use index::Index;
+use index::IndexBuffer;
+use index::PrimitiveType;
+use index::buffer::CreationError;
+
+/// Error that can happen while generating an index buffer.
+#[derive(Debug, Copy, Clone)]
+pub enum GenerationError {
+    /// Some of the indices don't fit in the requested type of index.
+    TooManyVertices,
+
+    /// The number of indices given as input doesn't match the type of primitives.
+    InvalidIndicesCount,
+
+    /// An error happened while creating the index buffer.
+    CreationError(CreationError),
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::GenerationError::*;
+        match *self {
+            CreationError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for GenerationError {
+    fn description(&self) -> &str {
+        use self::GenerationError::*;
+        match *self {
+            TooManyVertices =>
+                "Some of the indices don't fit in the requested type of index",
+            InvalidIndicesCount =>
+                "The number of indices given as input doesn't match the type of primitives",
+            CreationError(_) =>
+                "An error happened while creating the index buffer",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::GenerationError::*;
+        match *self {
+            CreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<CreationError> for GenerationError {
+    #[inline]
+    fn from(err: CreationError) -> GenerationError {
+        GenerationError::CreationError(err)
+    }
+}
+
+/// A type of index that can be produced by the functions of this module.
+pub trait GeneratedIndex: Index {
+    /// Converts the number of a vertex to an index. Returns `None` if it doesn't fit.
+    fn from_vertex(vertex: usize) -> Option<Self>;
+
+    /// Returns the number of the vertex.
+    fn to_vertex(self) -> usize;
+}
+
+impl GeneratedIndex for u8 {
+    #[inline]
+    fn from_vertex(vertex: usize) -> Option<u8> {
+        if vertex <= u8::max_value() as usize { Some(vertex as u8) } else { None }
+    }
+
+    #[inline]
+    fn to_vertex(self) -> usize {
+        self as usize
+    }
+}
+
+impl GeneratedIndex for u16 {
+    #[inline]
+    fn from_vertex(vertex: usize) -> Option<u16> {
+        if vertex <= u16::max_value() as usize { Some(vertex as u16) } else { None }
+    }
+
+    #[inline]
+    fn to_vertex(self) -> usize {
+        self as usize
+    }
+}
+
+impl GeneratedIndex for u32 {
+    #[inline]
+    fn from_vertex(vertex: usize) -> Option<u32> {
+        if vertex <= u32::max_value() as usize { Some(vertex as u32) } else { None }
+    }
+
+    #[inline]
+    fn to_vertex(self) -> usize {
+        self as usize
+    }
+}
+
+/// Builds a list of triangles for a grid of `columns` by `rows` cells.
+///
+/// The grid has `(columns + 1) * (rows + 1)` vertices, one row after the other. The vertex at
+/// column `x` of row `y` is the vertex number `y * (columns + 1) + x`. Each cell is made of two
+/// triangles that are counter-clockwise if the columns go to the right and the rows go upwards.
+pub fn grid<T, F: ?Sized>(facade: &F, columns: u32, rows: u32)
+                          -> Result<IndexBuffer<T>, GenerationError>
+                          where T: GeneratedIndex, F: Facade
+{
+    let columns = columns as usize;
+    let rows = rows as usize;
+    let width = columns + 1;
+
+    if T::from_vertex(width * (rows + 1) - 1).is_none() {
+        return Err(GenerationError::TooManyVertices);
+    }
+
+    let mut data = Vec::with_capacity(columns * rows * 6);
+    for y in 0 .. rows {
+        for x in 0 .. columns {
+            let bottom_left = y * width + x;
+            let bottom_right = bottom_left + 1;
+            let top_left = bottom_left + width;
+            let top_right = top_left + 1;
+
+            for &vertex in &[bottom_left, bottom_right, top_right,
+                             bottom_left, top_right, top_left]
+            {
+                data.push(T::from_vertex(vertex).unwrap());
+            }
+        }
+    }
+
+    Ok(try!(IndexBuffer::new(facade, PrimitiveType::TrianglesList, &data)))
+}
+
+/// Builds a list of triangles for `quads` quadrilaterals.
+///
+/// Each group of four consecutive vertices is a quadrilateral whose vertices are in order around
+/// it. The quadrilateral made of the vertices `0`, `1`, `2` and `3` is split into the triangles
+/// `0, 1, 2` and `0, 2, 3`, which have the same winding as the quadrilateral.
+pub fn quad_list<T, F: ?Sized>(facade: &F, quads: usize)
+                               -> Result<IndexBuffer<T>, GenerationError>
+                               where T: GeneratedIndex, F: Facade
+{
+    if quads != 0 && T::from_vertex(quads * 4 - 1).is_none() {
+        return Err(GenerationError::TooManyVertices);
+    }
+
+    let mut data = Vec::with_capacity(quads * 6);
+    for quad in 0 .. quads {
+        let first = quad * 4;
+        for &vertex in &[first, first + 1, first + 2, first, first + 2, first + 3] {
+            data.push(T::from_vertex(vertex).unwrap());
+        }
+    }
+
+    Ok(try!(IndexBuffer::new(facade, PrimitiveType::TrianglesList, &data)))
+}
+
+/// Converts the indices of a triangle strip to a list of triangles.
+///
+/// The winding of the triangles is preserved, and the degenerate triangles that are used to
+/// join several strips together are removed. Primitive restart indices are not handled.
+pub fn triangle_strip_to_list<T, F: ?Sized>(facade: &F, strip: &[T])
+                                            -> Result<IndexBuffer<T>, GenerationError>
+                                            where T: GeneratedIndex, F: Facade
+{
+    let mut data = Vec::with_capacity(strip.len().saturating_sub(2) * 3);
+
+    for (num, triangle) in strip.windows(3).enumerate() {
+        let (a, b, c) = if num % 2 == 0 {
+            (triangle[0], triangle[1], triangle[2])
+        } else {
+            (triangle[1], triangle[0], triangle[2])
+        };
+
+        let (va, vb, vc) = (a.to_vertex(), b.to_vertex(), c.to_vertex());
+        if va == vb || vb == vc || va == vc {
+            continue;
+        }
+
+        data.push(a);
+        data.push(b);
+        data.push(c);
+    }
+
+    Ok(try!(IndexBuffer::new(facade, PrimitiveType::TrianglesList, &data)))
+}
+
+/// Builds a list of triangles with adjacency information from a list of triangles.
+///
+/// For each edge of each triangle, the adjacent vertex is the vertex opposite to this edge in
+/// the other triangle that shares the edge. If no other triangle shares the edge, the vertex of
+/// the triangle itself that is opposite to the edge is used instead, which geometry shaders can
+/// detect to find the borders of the mesh.
+///
+/// Returns `InvalidIndicesCount` if the number of indices is not a multiple of three.
+pub fn triangles_adjacency<T, F: ?Sized>(facade: &F, triangles: &[T])
+                                         -> Result<IndexBuffer<T>, GenerationError>
+                                         where T: GeneratedIndex, F: Facade
+{
+    if triangles.len() % 3 != 0 {
+        return Err(GenerationError::InvalidIndicesCount);
+    }
+
+    // for each edge, the triangles that contain it and the vertex opposite to it
+    let mut edges: HashMap<(usize, usize), Vec<(usize, T)>> = HashMap::new();
+    for (num, triangle) in triangles.chunks(3).enumerate() {
+        for corner in 0 .. 3 {
+            let key = edge_key(triangle[corner], triangle[(corner + 1) % 3]);
+            edges.entry(key).or_insert_with(Vec::new).push((num, triangle[(corner + 2) % 3]));
+        }
+    }
+
+    let mut data = Vec::with_capacity(triangles.len() * 2);
+    for (num, triangle) in triangles.chunks(3).enumerate() {
+        for corner in 0 .. 3 {
+            let key = edge_key(triangle[corner], triangle[(corner + 1) % 3]);
+            let own = triangle[(corner + 2) % 3];
+            let adjacent = edges[&key].iter().find(|&&(other, _)| other != num)
+                                      .map(|&(_, vertex)| vertex).unwrap_or(own);
+
+            data.push(triangle[corner]);
+            data.push(adjacent);
+        }
+    }
+
+    Ok(try!(IndexBuffer::new(facade, PrimitiveType::TrianglesListAdjacency, &data)))
+}
+
+#[inline]
+fn edge_key<T>(a: T, b: T) -> (usize, usize) where T: GeneratedIndex {
+    let (a, b) = (a.to_vertex(), b.to_vertex());
+    if a < b { (a, b) } else { (b, a) }
+}
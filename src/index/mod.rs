@@ -22,12 +22,30 @@ There are two ways to specify the indices that must be used:
  - `NoIndices`, in which case the vertices will be used in the order in which they are in the
    vertex buffer.
 
+## Generating indices
+
+The `generate` module contains functions that build index buffers for common topologies, such as
+grids, lists of quadrilaterals or triangles with adjacency information.
+
 ## Multidraw indirect
 
 In addition to indices, you can also use **multidraw indirect** rendering.
 
 The idea is to put a list of things to render in a buffer, and pass that buffer to OpenGL.
 
+The number of vertices or of instances of a command can be written by the GPU itself, for
+example with the result of a query. See `count_slice` and `instance_count_slice`.
+
+The number of commands to execute can also be read from a buffer, so that a compute shader can
+both write the commands and count them. See `with_primitive_type_and_count` and
+`with_index_buffer_and_count`. This requires the `GL_ARB_indirect_parameters` extension, which
+can be checked with `is_draw_count_supported`.
+
+## Transform feedback
+
+`IndicesSource::TransformFeedback` draws the vertices that have been written by a transform
+feedback session, without reading their number back on the CPU.
+
 */
 use gl;
 use ToGlEnum;
@@ -38,11 +56,16 @@ use version::Version;
 use std::mem;
 
 use buffer::BufferAnySlice;
+use vertex::TransformFeedbackCounter;
 
 pub use self::buffer::{IndexBuffer, IndexBufferSlice, IndexBufferAny};
 pub use self::buffer::CreationError as BufferCreationError;
 pub use self::multidraw::{DrawCommandsNoIndicesBuffer, DrawCommandNoIndices};
 pub use self::multidraw::{DrawCommandsIndicesBuffer, DrawCommandIndices};
+pub use self::multidraw::{DrawArraysIndirectCommand, DrawElementsIndirectCommand};
+pub use self::multidraw::is_draw_count_supported;
+
+pub mod generate;
 
 mod buffer;
 mod multidraw;
@@ -80,12 +103,59 @@ pub enum IndicesSource<'a> {
         primitives: PrimitiveType,
     },
 
+    /// Use a multidraw indirect buffer without indices, and read the number of commands to
+    /// execute from another buffer.
+    MultidrawArrayCount {
+        /// The buffer of the commands.
+        buffer: BufferAnySlice<'a>,
+        /// A buffer that contains the number of commands to execute as a `u32`. Commands that
+        /// are beyond the end of `buffer` are never executed.
+        count: BufferAnySlice<'a>,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
+    /// Use a multidraw indirect buffer with indices, and read the number of commands to
+    /// execute from another buffer.
+    MultidrawElementCount {
+        /// The buffer of the commands.
+        commands: BufferAnySlice<'a>,
+        /// The buffer of the indices.
+        indices: BufferAnySlice<'a>,
+        /// A buffer that contains the number of commands to execute as a `u32`. Commands that
+        /// are beyond the end of `commands` are never executed.
+        count: BufferAnySlice<'a>,
+        /// Type of indices in the buffer.
+        data_type: IndexType,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
     /// Don't use indices. Assemble primitives by using the order in which the vertices are in
     /// the vertices source.
     NoIndices {
         /// Type of primitives contained in the vertex source.
         primitives: PrimitiveType,
     },
+
+    /// Don't use indices, and draw the number of vertices that has been written by the latest
+    /// transform feedback session that used this counter. The number of vertices never has to
+    /// be read back on the CPU.
+    TransformFeedback {
+        /// The counter of a transform feedback session that is over.
+        counter: &'a TransformFeedbackCounter,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
+    /// Launch mesh tasks instead of drawing vertices. The program must contain a mesh shader.
+    /// See `Surface::draw_mesh_tasks`.
+    MeshTasks {
+        /// Index of the first task.
+        first: u32,
+        /// Number of tasks to launch.
+        count: u32,
+    },
 }
 
 impl<'a> IndicesSource<'a> {
@@ -96,7 +166,12 @@ impl<'a> IndicesSource<'a> {
             &IndicesSource::IndexBuffer { primitives, .. } => primitives,
             &IndicesSource::MultidrawArray { primitives, .. } => primitives,
             &IndicesSource::MultidrawElement { primitives, .. } => primitives,
+            &IndicesSource::MultidrawArrayCount { primitives, .. } => primitives,
+            &IndicesSource::MultidrawElementCount { primitives, .. } => primitives,
             &IndicesSource::NoIndices { primitives } => primitives,
+            &IndicesSource::TransformFeedback { primitives, .. } => primitives,
+            // the actual type of primitives is declared in the mesh shader
+            &IndicesSource::MeshTasks { .. } => PrimitiveType::TrianglesList,
         }
     }
 }
@@ -2,12 +2,24 @@
 //!
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::mem;
 use std::os::raw;
 
 use backend::Facade;
 use buffer::{BufferCreationError, BufferType, BufferMode, Buffer};
 use buffer::{BufferSlice, BufferMutSlice};
 use index::{IndicesSource, PrimitiveType, IndexBuffer, Index};
+use BufferArraySliceExt;
+use CapabilitiesSource;
+
+/// Returns true if the backend supports reading the number of commands of a multidraw from a
+/// buffer.
+///
+/// This requires the `GL_ARB_indirect_parameters` extension.
+#[inline]
+pub fn is_draw_count_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_extensions().gl_arb_indirect_parameters
+}
 
 /// Represents an element in a list of draw commands.
 #[repr(C)]
@@ -26,6 +38,10 @@ pub struct DrawCommandNoIndices {
 implement_uniform_block!(DrawCommandNoIndices, count, instance_count,
                          first_index, base_instance);
 
+/// Name of `DrawCommandNoIndices` in the OpenGL specifications. This is the layout expected by
+/// `glMultiDrawArraysIndirect`.
+pub type DrawArraysIndirectCommand = DrawCommandNoIndices;
+
 /// Represents an element in a list of draw commands.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -45,6 +61,10 @@ pub struct DrawCommandIndices {
 implement_uniform_block!(DrawCommandIndices, count, instance_count, first_index,
                          base_vertex, base_instance);
 
+/// Name of `DrawCommandIndices` in the OpenGL specifications. This is the layout expected by
+/// `glMultiDrawElementsIndirect`.
+pub type DrawElementsIndirectCommand = DrawCommandIndices;
+
 /// A buffer containing a list of draw commands.
 pub struct DrawCommandsNoIndicesBuffer {
     buffer: Buffer<[DrawCommandNoIndices]>,
@@ -112,6 +132,42 @@ impl DrawCommandsNoIndicesBuffer {
             primitives: primitives,
         }
     }
+
+    /// Builds an indices source that executes the first `count` commands of this buffer, where
+    /// `count` is read from a buffer when drawing.
+    ///
+    /// See `is_draw_count_supported`.
+    #[inline]
+    pub fn with_primitive_type_and_count<'a, S>(&'a self, primitives: PrimitiveType, count: S)
+                                                -> IndicesSource<'a>
+        where S: Into<BufferSlice<'a, u32>>
+    {
+        IndicesSource::MultidrawArrayCount {
+            buffer: self.buffer.as_slice_any(),
+            count: count.into().as_slice_any(),
+            primitives: primitives,
+        }
+    }
+
+    /// Returns a slice that contains the `count` field of the command `index`, or `None` if
+    /// out of range.
+    ///
+    /// The result of a query can be written here with `to_buffer_u32`, so that the number of
+    /// vertices to draw doesn't need to be read back on the CPU.
+    #[inline]
+    pub fn count_slice(&self, index: usize) -> Option<BufferSlice<u32>> {
+        self.buffer.as_slice().element_field(index, 0)
+    }
+
+    /// Returns a slice that contains the `instance_count` field of the command `index`, or
+    /// `None` if out of range.
+    ///
+    /// The result of a query can be written here with `to_buffer_u32`, so that the number of
+    /// instances to draw doesn't need to be read back on the CPU.
+    #[inline]
+    pub fn instance_count_slice(&self, index: usize) -> Option<BufferSlice<u32>> {
+        self.buffer.as_slice().element_field(index, mem::size_of::<raw::c_uint>())
+    }
 }
 
 impl Deref for DrawCommandsNoIndicesBuffer {
@@ -217,6 +273,44 @@ impl DrawCommandsIndicesBuffer {
             primitives: index_buffer.get_primitives_type(),
         }
     }
+
+    /// Builds an indices source that executes the first `count` commands of this buffer with
+    /// an index buffer, where `count` is read from a buffer when drawing.
+    ///
+    /// See `is_draw_count_supported`.
+    #[inline]
+    pub fn with_index_buffer_and_count<'a, T, S>(&'a self, index_buffer: &'a IndexBuffer<T>,
+                                                 count: S) -> IndicesSource<'a>
+        where T: Index, S: Into<BufferSlice<'a, u32>>
+    {
+        IndicesSource::MultidrawElementCount {
+            commands: self.buffer.as_slice_any(),
+            indices: index_buffer.as_slice_any(),
+            count: count.into().as_slice_any(),
+            data_type: index_buffer.get_indices_type(),
+            primitives: index_buffer.get_primitives_type(),
+        }
+    }
+
+    /// Returns a slice that contains the `count` field of the command `index`, or `None` if
+    /// out of range.
+    ///
+    /// The result of a query can be written here with `to_buffer_u32`, so that the number of
+    /// indices to draw doesn't need to be read back on the CPU.
+    #[inline]
+    pub fn count_slice(&self, index: usize) -> Option<BufferSlice<u32>> {
+        self.buffer.as_slice().element_field(index, 0)
+    }
+
+    /// Returns a slice that contains the `instance_count` field of the command `index`, or
+    /// `None` if out of range.
+    ///
+    /// The result of a query can be written here with `to_buffer_u32`, so that the number of
+    /// instances to draw doesn't need to be read back on the CPU.
+    #[inline]
+    pub fn instance_count_slice(&self, index: usize) -> Option<BufferSlice<u32>> {
+        self.buffer.as_slice().element_field(index, mem::size_of::<raw::c_uint>())
+    }
 }
 
 impl Deref for DrawCommandsIndicesBuffer {
@@ -0,0 +1,305 @@
+/*!
+Sharing Direct3D 11 textures with OpenGL through `WGL_NV_DX_interop2`. Only available on Windows.
+
+First open the Direct3D device with `D3DDevice::open`, then register each texture with
+`D3DDevice::register_texture_2d`. A registered texture belongs to Direct3D until it is locked.
+`D3DTexture::lock` returns a guard that gives access to the OpenGL texture, and the texture is
+given back to Direct3D when the guard is destroyed.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let d3d_device: *mut std::os::raw::c_void = std::ptr::null_mut();
+# let d3d_texture: *mut std::os::raw::c_void = std::ptr::null_mut();
+use glium::Surface;
+use glium::interop::d3d::{D3DDevice, Access};
+
+let device = unsafe { D3DDevice::open(&display, d3d_device).unwrap() };
+let mut texture = unsafe {
+    device.register_texture_2d(d3d_texture, glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                               1920, 1080, Access::ReadWrite).unwrap()
+};
+
+{
+    let locked = texture.lock().unwrap();
+    locked.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+}   // the texture is unlocked here and can be used by Direct3D again
+```
+
+*/
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::error::Error;
+use std::os::raw::{c_int, c_void};
+
+use gl;
+use utils::gl_get::get_output;
+use backend::Facade;
+use context::Context;
+use ContextExt;
+
+use texture::{Dimensions, MipmapsOption, Texture2d, UncompressedFloatFormat};
+
+const WGL_ACCESS_READ_ONLY_NV: gl::types::GLenum = 0x0000;
+const WGL_ACCESS_READ_WRITE_NV: gl::types::GLenum = 0x0001;
+const WGL_ACCESS_WRITE_DISCARD_NV: gl::types::GLenum = 0x0002;
+
+type WglDxOpenDeviceNv = extern "system" fn(*mut c_void) -> *mut c_void;
+type WglDxCloseDeviceNv = extern "system" fn(*mut c_void) -> c_int;
+type WglDxRegisterObjectNv = extern "system" fn(*mut c_void, *mut c_void, gl::types::GLuint,
+                                                gl::types::GLenum, gl::types::GLenum)
+                                                -> *mut c_void;
+type WglDxUnregisterObjectNv = extern "system" fn(*mut c_void, *mut c_void) -> c_int;
+type WglDxLockObjectsNv = extern "system" fn(*mut c_void, gl::types::GLint, *mut *mut c_void)
+                                             -> c_int;
+
+/// Error that can happen when sharing textures with Direct3D.
+#[derive(Copy, Clone, Debug)]
+pub enum D3DInteropError {
+    /// `WGL_NV_DX_interop2` is not supported by the backend.
+    NotSupported,
+
+    /// Failed to open the Direct3D device.
+    OpenDeviceFailed,
+
+    /// Failed to register the Direct3D texture.
+    RegisterFailed,
+
+    /// Failed to lock the texture. It is probably in use by Direct3D.
+    LockFailed,
+}
+
+impl fmt::Display for D3DInteropError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for D3DInteropError {
+    fn description(&self) -> &str {
+        use self::D3DInteropError::*;
+        match *self {
+            NotSupported =>
+                "`WGL_NV_DX_interop2` is not supported by the backend",
+            OpenDeviceFailed =>
+                "Failed to open the Direct3D device",
+            RegisterFailed =>
+                "Failed to register the Direct3D texture",
+            LockFailed =>
+                "Failed to lock the texture",
+        }
+    }
+}
+
+/// How OpenGL is going to access a registered texture.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    /// OpenGL only reads the texture.
+    ReadOnly,
+
+    /// OpenGL reads and writes the texture.
+    ReadWrite,
+
+    /// OpenGL writes the whole texture, and its previous content is discarded when locking.
+    WriteDiscard,
+}
+
+impl Access {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            Access::ReadOnly => WGL_ACCESS_READ_ONLY_NV,
+            Access::ReadWrite => WGL_ACCESS_READ_WRITE_NV,
+            Access::WriteDiscard => WGL_ACCESS_WRITE_DISCARD_NV,
+        }
+    }
+}
+
+/// The WGL functions and the device handle, shared by the device and its textures.
+struct DeviceInner {
+    context: Rc<Context>,
+    handle: *mut c_void,
+    close: WglDxCloseDeviceNv,
+    register: WglDxRegisterObjectNv,
+    unregister: WglDxUnregisterObjectNv,
+    lock: WglDxLockObjectsNv,
+    unlock: WglDxLockObjectsNv,
+}
+
+impl Drop for DeviceInner {
+    fn drop(&mut self) {
+        let _ctxt = self.context.make_current();
+        (self.close)(self.handle);
+    }
+}
+
+/// A Direct3D device opened for interoperability with OpenGL.
+///
+/// The device is closed when this object and all the textures registered with it are destroyed.
+pub struct D3DDevice {
+    inner: Rc<DeviceInner>,
+}
+
+impl D3DDevice {
+    /// Opens a Direct3D device for interoperability.
+    ///
+    /// # Unsafety
+    ///
+    /// `d3d_device` must be a valid pointer to an `ID3D11Device` that outlives this object.
+    pub unsafe fn open<F: ?Sized>(facade: &F, d3d_device: *mut c_void)
+                                  -> Result<D3DDevice, D3DInteropError> where F: Facade
+    {
+        let context = facade.get_context();
+
+        let open = context.get_proc_address("wglDXOpenDeviceNV");
+        let close = context.get_proc_address("wglDXCloseDeviceNV");
+        let register = context.get_proc_address("wglDXRegisterObjectNV");
+        let unregister = context.get_proc_address("wglDXUnregisterObjectNV");
+        let lock = context.get_proc_address("wglDXLockObjectsNV");
+        let unlock = context.get_proc_address("wglDXUnlockObjectsNV");
+
+        if open.is_null() || close.is_null() || register.is_null() || unregister.is_null() ||
+           lock.is_null() || unlock.is_null()
+        {
+            return Err(D3DInteropError::NotSupported);
+        }
+
+        let open: WglDxOpenDeviceNv = mem::transmute(open);
+
+        let handle = {
+            let _ctxt = context.make_current();
+            open(d3d_device)
+        };
+
+        if handle.is_null() {
+            return Err(D3DInteropError::OpenDeviceFailed);
+        }
+
+        Ok(D3DDevice {
+            inner: Rc::new(DeviceInner {
+                context: context.clone(),
+                handle: handle,
+                close: mem::transmute(close),
+                register: mem::transmute(register),
+                unregister: mem::transmute(unregister),
+                lock: mem::transmute(lock),
+                unlock: mem::transmute(unlock),
+            }),
+        })
+    }
+
+    /// Registers an `ID3D11Texture2D` and builds an OpenGL texture that shares its content.
+    ///
+    /// # Unsafety
+    ///
+    /// `d3d_texture` must be a valid pointer to an `ID3D11Texture2D` created with the device,
+    /// that outlives the returned object. `format`, `width` and `height` must match the texture.
+    pub unsafe fn register_texture_2d(&self, d3d_texture: *mut c_void,
+                                      format: UncompressedFloatFormat, width: u32, height: u32,
+                                      access: Access) -> Result<D3DTexture, D3DInteropError>
+    {
+        let id = {
+            let mut ctxt = self.inner.context.make_current();
+
+            let id = get_output(|ptr| ctxt.gl.GenTextures(1, ptr));
+            id
+        };
+
+        let handle = {
+            let _ctxt = self.inner.context.make_current();
+            (self.inner.register)(self.inner.handle, d3d_texture, id, gl::TEXTURE_2D,
+                                  access.to_glenum())
+        };
+
+        if handle.is_null() {
+            let ctxt = self.inner.context.make_current();
+            ctxt.gl.DeleteTextures(1, [ id ].as_ptr());
+            return Err(D3DInteropError::RegisterFailed);
+        }
+
+        let texture = Texture2d::from_id(&self.inner.context, format, id, true,
+                                         MipmapsOption::NoMipmap,
+                                         Dimensions::Texture2d { width: width, height: height });
+
+        Ok(D3DTexture {
+            device: self.inner.clone(),
+            handle: handle,
+            texture: texture,
+        })
+    }
+}
+
+impl fmt::Debug for D3DDevice {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "D3DDevice({:?})", self.inner.handle)
+    }
+}
+
+/// A Direct3D texture registered for use with OpenGL.
+///
+/// The texture can only be accessed by OpenGL while it is locked.
+pub struct D3DTexture {
+    device: Rc<DeviceInner>,
+    handle: *mut c_void,
+    texture: Texture2d,
+}
+
+impl D3DTexture {
+    /// Takes ownership of the texture from Direct3D until the returned guard is destroyed.
+    ///
+    /// Locking waits for Direct3D to finish using the texture.
+    pub fn lock(&mut self) -> Result<D3DTextureLock, D3DInteropError> {
+        let success = {
+            let _ctxt = self.device.context.make_current();
+            (self.device.lock)(self.device.handle, 1, &mut self.handle)
+        };
+
+        if success == 0 {
+            return Err(D3DInteropError::LockFailed);
+        }
+
+        Ok(D3DTextureLock { texture: self })
+    }
+}
+
+impl Drop for D3DTexture {
+    fn drop(&mut self) {
+        let _ctxt = self.device.context.make_current();
+        (self.device.unregister)(self.device.handle, self.handle);
+    }
+}
+
+impl fmt::Debug for D3DTexture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "D3DTexture({:?})", self.handle)
+    }
+}
+
+/// A locked `D3DTexture`. Derefs to the OpenGL texture, and unlocks it when destroyed.
+pub struct D3DTextureLock<'a> {
+    texture: &'a mut D3DTexture,
+}
+
+impl<'a> Deref for D3DTextureLock<'a> {
+    type Target = Texture2d;
+
+    #[inline]
+    fn deref(&self) -> &Texture2d {
+        &self.texture.texture
+    }
+}
+
+impl<'a> Drop for D3DTextureLock<'a> {
+    fn drop(&mut self) {
+        let device = &self.texture.device;
+        let _ctxt = device.context.make_current();
+        (device.unlock)(device.handle, 1, &mut self.texture.handle);
+    }
+}
+
+impl<'a> fmt::Debug for D3DTextureLock<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "D3DTextureLock({:?})", self.texture.handle)
+    }
+}
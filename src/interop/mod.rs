@@ -0,0 +1,403 @@
+/*!
+Sharing memory and synchronization primitives with other APIs, like Vulkan.
+
+This module exposes the `GL_EXT_memory_object` and `GL_EXT_semaphore` extensions. They allow
+glium to use memory allocated by another API and to synchronize with it without going through
+the CPU.
+
+# Memory objects
+
+A `MemoryObject` is a handle to memory that has been exported by another API, for example with
+`vkGetMemoryFdKHR`. Once imported, you can create textures with `MemoryObject::texture_2d` and
+buffers with `Buffer::from_memory_object_unsized` or `Buffer::from_memory_object_array` that use
+this memory as storage.
+
+Textures and buffers keep the memory alive, so the `MemoryObject` can be destroyed as soon as
+they have been created.
+
+# Semaphores
+
+A `Semaphore` is a handle to a semaphore that has been exported by another API. Calling `wait`
+makes the OpenGL commands that are submitted afterwards wait until the semaphore is signaled by
+the other API, and calling `signal` signals the semaphore once the commands that have been
+submitted before are finished.
+
+Both functions take the list of buffers and textures that are shared with the other API. For
+textures you also need to pass the Vulkan layout of the image.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let vulkan_finished: glium::interop::Semaphore = unsafe { std::mem::uninitialized() };
+# let gl_finished: glium::interop::Semaphore = unsafe { std::mem::uninitialized() };
+# let texture: glium::Texture2d = unsafe { std::mem::uninitialized() };
+use glium::interop::ImageLayout;
+
+// waiting for the Vulkan compute pipeline to write the texture
+vulkan_finished.wait(&[], &[(&*texture, ImageLayout::General)]);
+
+// ... draw with the texture ...
+
+// giving the texture back to Vulkan
+gl_finished.signal(&[], &[(&*texture, ImageLayout::General)]);
+```
+
+# Direct3D
+
+On Windows, the `d3d` module allows you to share Direct3D 11 textures with OpenGL through the
+`WGL_NV_DX_interop2` extension.
+
+*/
+use std::fmt;
+use std::rc::Rc;
+use std::error::Error;
+use std::os::raw::{c_int, c_void};
+
+use gl;
+use utils::gl_get::get_output;
+use backend::Facade;
+use context::Context;
+use ContextExt;
+use GlObject;
+
+use buffer::BufferAnySlice;
+use image_format::{self, TextureFormatRequest};
+use texture::{Dimensions, MipmapsOption, TextureAny, TextureCreationError};
+use texture::{Texture2d, UncompressedFloatFormat};
+
+#[cfg(target_os = "windows")]
+pub mod d3d;
+
+/// A handle exported by another API.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExternalHandle {
+    /// A POSIX file descriptor, for example obtained with `vkGetMemoryFdKHR` or
+    /// `vkGetSemaphoreFdKHR`. Ownership of the file descriptor is transferred to the OpenGL
+    /// implementation, and you must not close it.
+    OpaqueFd(c_int),
+
+    /// A Windows NT handle. Ownership of the handle isn't transferred.
+    OpaqueWin32(*mut c_void),
+
+    /// A Windows global share handle.
+    OpaqueWin32Kmt(*mut c_void),
+}
+
+impl ExternalHandle {
+    /// Returns the value of the `GL_HANDLE_TYPE_*` enum that corresponds to this handle.
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ExternalHandle::OpaqueFd(_) => gl::HANDLE_TYPE_OPAQUE_FD_EXT,
+            ExternalHandle::OpaqueWin32(_) => gl::HANDLE_TYPE_OPAQUE_WIN32_EXT,
+            ExternalHandle::OpaqueWin32Kmt(_) => gl::HANDLE_TYPE_OPAQUE_WIN32_KMT_EXT,
+        }
+    }
+}
+
+/// Error that can happen while importing a memory object or a semaphore.
+#[derive(Copy, Clone, Debug)]
+pub enum ImportError {
+    /// Memory objects or semaphores are not supported by the backend.
+    NotSupported,
+
+    /// This kind of handle is not supported by the backend.
+    HandleTypeNotSupported,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ImportError {
+    fn description(&self) -> &str {
+        use self::ImportError::*;
+        match *self {
+            NotSupported =>
+                "Memory objects or semaphores are not supported by the backend",
+            HandleTypeNotSupported =>
+                "This kind of handle is not supported by the backend",
+        }
+    }
+}
+
+/// Memory allocated by another API and imported in OpenGL.
+pub struct MemoryObject {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    size: u64,
+}
+
+impl MemoryObject {
+    /// Imports memory of `size` bytes from another API.
+    ///
+    /// `dedicated` must be true if the memory has been allocated as a dedicated allocation, for
+    /// example with `VkMemoryDedicatedAllocateInfo`.
+    ///
+    /// # Unsafety
+    ///
+    /// The handle must be valid and `size` must be the size of the allocation.
+    pub unsafe fn import<F: ?Sized>(facade: &F, handle: ExternalHandle, size: u64,
+                                    dedicated: bool) -> Result<MemoryObject, ImportError>
+                                    where F: Facade
+    {
+        let ctxt = facade.get_context().make_current();
+
+        if !ctxt.extensions.gl_ext_memory_object {
+            return Err(ImportError::NotSupported);
+        }
+
+        let supported = match handle {
+            ExternalHandle::OpaqueFd(_) => ctxt.extensions.gl_ext_memory_object_fd,
+            ExternalHandle::OpaqueWin32(_) |
+            ExternalHandle::OpaqueWin32Kmt(_) => ctxt.extensions.gl_ext_memory_object_win32,
+        };
+
+        if !supported {
+            return Err(ImportError::HandleTypeNotSupported);
+        }
+
+        let id = get_output(|ptr| ctxt.gl.CreateMemoryObjectsEXT(1, ptr));
+
+        if dedicated {
+            let value = gl::TRUE as gl::types::GLint;
+            ctxt.gl.MemoryObjectParameterivEXT(id, gl::DEDICATED_MEMORY_OBJECT_EXT, &value);
+        }
+
+        match handle {
+            ExternalHandle::OpaqueFd(fd) => {
+                ctxt.gl.ImportMemoryFdEXT(id, size, handle.to_glenum(), fd);
+            },
+            ExternalHandle::OpaqueWin32(h) | ExternalHandle::OpaqueWin32Kmt(h) => {
+                ctxt.gl.ImportMemoryWin32HandleEXT(id, size, handle.to_glenum(), h as *mut _);
+            },
+        }
+
+        Ok(MemoryObject {
+            context: facade.get_context().clone(),
+            id: id,
+            size: size,
+        })
+    }
+
+    /// Returns the size in bytes of the memory.
+    #[inline]
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Builds a two-dimensional texture without mipmaps whose storage starts at `offset` bytes
+    /// within the memory.
+    ///
+    /// # Unsafety
+    ///
+    /// The format and dimensions must match the ones of the image that the other API has
+    /// created in this memory.
+    pub unsafe fn texture_2d(&self, format: UncompressedFloatFormat, width: u32, height: u32,
+                             offset: u64) -> Result<Texture2d, TextureCreationError>
+    {
+        let request = TextureFormatRequest::Specific(format.to_texture_format());
+        let internal_format = try!(image_format::format_request_to_glenum(&self.context,
+                                            request, image_format::RequestType::TexStorage));
+
+        let mut ctxt = self.context.make_current();
+
+        let id = get_output(|ptr| ctxt.gl.GenTextures(1, ptr));
+
+        ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+        let act = ctxt.state.active_texture as usize;
+        ctxt.state.texture_units[act].texture = id;
+
+        ctxt.gl.TexStorageMem2DEXT(gl::TEXTURE_2D, 1, internal_format,
+                                   width as gl::types::GLsizei, height as gl::types::GLsizei,
+                                   self.id, offset);
+
+        Ok(Texture2d::from_id(&self.context, format, id, true, MipmapsOption::NoMipmap,
+                              Dimensions::Texture2d { width: width, height: height }))
+    }
+}
+
+impl fmt::Debug for MemoryObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "MemoryObject #{} ({} bytes)", self.id, self.size)
+    }
+}
+
+impl GlObject for MemoryObject {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl Drop for MemoryObject {
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+        unsafe { ctxt.gl.DeleteMemoryObjectsEXT(1, &self.id); }
+    }
+}
+
+/// Layout of an image, as understood by Vulkan.
+///
+/// When waiting on a semaphore, this is the layout the other API has left the image in. When
+/// signaling a semaphore, this is the layout the image will be transitioned to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageLayout {
+    /// `VK_IMAGE_LAYOUT_UNDEFINED`. The content of the image is discarded.
+    Undefined,
+    /// `VK_IMAGE_LAYOUT_GENERAL`.
+    General,
+    /// `VK_IMAGE_LAYOUT_COLOR_ATTACHMENT_OPTIMAL`.
+    ColorAttachment,
+    /// `VK_IMAGE_LAYOUT_DEPTH_STENCIL_ATTACHMENT_OPTIMAL`.
+    DepthStencilAttachment,
+    /// `VK_IMAGE_LAYOUT_DEPTH_STENCIL_READ_ONLY_OPTIMAL`.
+    DepthStencilReadOnly,
+    /// `VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL`.
+    ShaderReadOnly,
+    /// `VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL`.
+    TransferSrc,
+    /// `VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL`.
+    TransferDst,
+    /// `VK_IMAGE_LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_OPTIMAL`.
+    DepthReadOnlyStencilAttachment,
+    /// `VK_IMAGE_LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_OPTIMAL`.
+    DepthAttachmentStencilReadOnly,
+}
+
+impl ImageLayout {
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageLayout::Undefined => gl::NONE,
+            ImageLayout::General => gl::LAYOUT_GENERAL_EXT,
+            ImageLayout::ColorAttachment => gl::LAYOUT_COLOR_ATTACHMENT_EXT,
+            ImageLayout::DepthStencilAttachment => gl::LAYOUT_DEPTH_STENCIL_ATTACHMENT_EXT,
+            ImageLayout::DepthStencilReadOnly => gl::LAYOUT_DEPTH_STENCIL_READ_ONLY_EXT,
+            ImageLayout::ShaderReadOnly => gl::LAYOUT_SHADER_READ_ONLY_EXT,
+            ImageLayout::TransferSrc => gl::LAYOUT_TRANSFER_SRC_EXT,
+            ImageLayout::TransferDst => gl::LAYOUT_TRANSFER_DST_EXT,
+            ImageLayout::DepthReadOnlyStencilAttachment => {
+                gl::LAYOUT_DEPTH_READ_ONLY_STENCIL_ATTACHMENT_EXT
+            },
+            ImageLayout::DepthAttachmentStencilReadOnly => {
+                gl::LAYOUT_DEPTH_ATTACHMENT_STENCIL_READ_ONLY_EXT
+            },
+        }
+    }
+}
+
+/// A semaphore shared with another API.
+pub struct Semaphore {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+}
+
+impl Semaphore {
+    /// Imports a semaphore from another API.
+    ///
+    /// # Unsafety
+    ///
+    /// The handle must be a valid semaphore handle.
+    pub unsafe fn import<F: ?Sized>(facade: &F, handle: ExternalHandle)
+                                    -> Result<Semaphore, ImportError> where F: Facade
+    {
+        let ctxt = facade.get_context().make_current();
+
+        if !ctxt.extensions.gl_ext_semaphore {
+            return Err(ImportError::NotSupported);
+        }
+
+        let supported = match handle {
+            ExternalHandle::OpaqueFd(_) => ctxt.extensions.gl_ext_semaphore_fd,
+            ExternalHandle::OpaqueWin32(_) |
+            ExternalHandle::OpaqueWin32Kmt(_) => ctxt.extensions.gl_ext_semaphore_win32,
+        };
+
+        if !supported {
+            return Err(ImportError::HandleTypeNotSupported);
+        }
+
+        let id = get_output(|ptr| ctxt.gl.GenSemaphoresEXT(1, ptr));
+
+        match handle {
+            ExternalHandle::OpaqueFd(fd) => {
+                ctxt.gl.ImportSemaphoreFdEXT(id, handle.to_glenum(), fd);
+            },
+            ExternalHandle::OpaqueWin32(h) | ExternalHandle::OpaqueWin32Kmt(h) => {
+                ctxt.gl.ImportSemaphoreWin32HandleEXT(id, handle.to_glenum(), h as *mut _);
+            },
+        }
+
+        Ok(Semaphore {
+            context: facade.get_context().clone(),
+            id: id,
+        })
+    }
+
+    /// Makes the commands submitted after this call wait until the semaphore is signaled.
+    ///
+    /// `buffers` and `textures` are the objects that the other API has written to. Each texture
+    /// is accompanied by the layout the other API has left it in.
+    pub fn wait(&self, buffers: &[BufferAnySlice], textures: &[(&TextureAny, ImageLayout)]) {
+        let (buffers, textures, layouts) = barriers_lists(buffers, textures);
+
+        let ctxt = self.context.make_current();
+        unsafe {
+            ctxt.gl.WaitSemaphoreEXT(self.id, buffers.len() as gl::types::GLuint,
+                                     buffers.as_ptr(), textures.len() as gl::types::GLuint,
+                                     textures.as_ptr(), layouts.as_ptr());
+        }
+    }
+
+    /// Signals the semaphore once the commands submitted before this call have finished.
+    ///
+    /// `buffers` and `textures` are the objects that the other API is going to use. Each texture
+    /// is accompanied by the layout it must be transitioned to.
+    ///
+    /// The commands are flushed so that the other API doesn't wait forever.
+    pub fn signal(&self, buffers: &[BufferAnySlice], textures: &[(&TextureAny, ImageLayout)]) {
+        let (buffers, textures, layouts) = barriers_lists(buffers, textures);
+
+        let ctxt = self.context.make_current();
+        unsafe {
+            ctxt.gl.SignalSemaphoreEXT(self.id, buffers.len() as gl::types::GLuint,
+                                       buffers.as_ptr(), textures.len() as gl::types::GLuint,
+                                       textures.as_ptr(), layouts.as_ptr());
+            ctxt.gl.Flush();
+        }
+    }
+}
+
+/// Turns the lists of objects passed to `wait` and `signal` into lists of OpenGL values.
+fn barriers_lists(buffers: &[BufferAnySlice], textures: &[(&TextureAny, ImageLayout)])
+                  -> (Vec<gl::types::GLuint>, Vec<gl::types::GLuint>, Vec<gl::types::GLenum>)
+{
+    let buffers = buffers.iter().map(|b| b.get_id()).collect();
+    let layouts = textures.iter().map(|&(_, layout)| layout.to_glenum()).collect();
+    let textures = textures.iter().map(|&(t, _)| t.get_id()).collect();
+    (buffers, textures, layouts)
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Semaphore #{}", self.id)
+    }
+}
+
+impl GlObject for Semaphore {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl Drop for Semaphore {
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+        unsafe { ctxt.gl.DeleteSemaphoresEXT(1, &self.id); }
+    }
+}
@@ -104,12 +104,33 @@ extern crate backtrace;
 extern crate smallvec;
 extern crate fnv;
 
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "screenshot_exr")]
+extern crate exr;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "screenshot_png")]
+extern crate image;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "serialize")]
+extern crate serde;
+#[cfg(feature = "serialize")]
+#[macro_use]
+extern crate serde_derive;
+
 #[cfg(feature = "glutin")]
 pub use backend::glutin::glutin;
-pub use context::Profile;
+pub use context::{Profile, FramebufferFetch, VertexShaderLayer, FragmentShaderInterlock};
+pub use context::{ImageFormatSupport, TextureGather};
+pub use context::{CacheStatistics, StateStatistics};
+pub use error::Error;
 pub use draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor, BackfaceCullingMode};
 pub use draw_parameters::{Depth, DepthTest, PolygonMode, DrawParameters, StencilTest, StencilOperation};
-pub use draw_parameters::{Smooth};
+pub use draw_parameters::{Smooth, TessellationLevels, FloatViewport};
 pub use index::IndexBuffer;
 pub use vertex::{VertexBuffer, Vertex, VertexFormat};
 pub use program::{Program, ProgramCreationError};
@@ -120,7 +141,7 @@ pub use version::{Api, Version, get_supported_glsl_version};
 
 use std::rc::Rc;
 use std::thread;
-use std::error::Error;
+use std::error::Error as StdError;
 use std::fmt;
 use std::hash::BuildHasherDefault;
 use std::collections::HashMap;
@@ -135,17 +156,33 @@ mod macros;
 
 pub mod backend;
 pub mod buffer;
+pub mod capture;
+pub mod color;
+pub mod culling;
 pub mod debug;
+pub mod debug_draw;
 pub mod draw_parameters;
 pub mod framebuffer;
 pub mod index;
+pub mod interop;
+pub mod material;
+pub mod occlusion;
+pub mod pacing;
+pub mod particles;
+pub mod picking;
+pub mod pipeline;
 pub mod pixel_buffer;
+pub mod post_process;
 pub mod program;
+pub mod render_graph;
+#[cfg(feature = "renderdoc")]
+pub mod renderdoc;
 pub mod uniforms;
 pub mod vertex;
 pub mod texture;
 
 mod context;
+mod error;
 mod fbo;
 mod image_format;
 mod ops;
@@ -247,6 +284,10 @@ trait BufferExt {
     /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
     fn prepare_and_bind_for_draw_indirect(&self, &mut CommandContext);
 
+    /// Makes sure that the buffer is bound to the `GL_PARAMETER_BUFFER` and calls
+    /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
+    fn prepare_and_bind_for_draw_parameters(&self, &mut CommandContext);
+
     /// Makes sure that the buffer is bound to the `GL_DISPATCH_INDIRECT_BUFFER` and calls
     /// `glMemoryBarrier(GL_COMMAND_BARRIER_BIT)` if necessary.
     fn prepare_and_bind_for_dispatch_indirect(&self, &mut CommandContext);
@@ -259,6 +300,13 @@ trait BufferExt {
     /// `glMemoryBarrier(GL_SHADER_STORAGE_BARRIER_BIT)` if necessary.
     fn prepare_and_bind_for_shared_storage(&self, &mut CommandContext, index: gl::types::GLuint);
 
+    /// Calls `glMemoryBarrier(GL_TEXTURE_FETCH_BARRIER_BIT)` if necessary.
+    fn prepare_for_texture_fetch(&self, &mut CommandContext);
+
+    /// Calls `glMemoryBarrier(GL_SHADER_IMAGE_ACCESS_BARRIER_BIT)` if necessary, and marks the
+    /// buffer as written by the next draw call if `write` is true.
+    fn prepare_for_shader_image_access(&self, &mut CommandContext, write: bool);
+
     /// Binds the buffer to `GL_TRANSFORM_FEEDBACk_BUFFER` regardless of the current transform
     /// feedback object.
     fn bind_to_transform_feedback(&self, &mut CommandContext, index: gl::types::GLuint);
@@ -272,6 +320,14 @@ trait BufferSliceExt<'a> {
     fn add_fence(&self) -> Option<buffer::Inserter<'a>>;
 }
 
+/// Internal trait for slices of arrays.
+trait BufferArraySliceExt<'a> {
+    /// Builds a slice that covers a single field of the element `index`. The field is `offset`
+    /// bytes after the start of the element. Returns `None` if the element is out of range.
+    fn element_field<F>(&self, index: usize, offset: usize) -> Option<buffer::BufferSlice<'a, F>>
+                        where F: buffer::Content + Copy;
+}
+
 /// Internal trait for contexts.
 trait ContextExt {
     /// Sets whether the context's debug output callback should take errors into account.
@@ -282,6 +338,13 @@ trait ContextExt {
 
     /// Returns the capabilities of the backend.
     fn capabilities(&self) -> &context::Capabilities;
+
+    /// Returns the cache of programs of the context.
+    fn get_program_cache(&self) -> &context::ProgramCache;
+
+    /// Removes an object that has just been destroyed from the caches of the other contexts
+    /// that share their objects with this one.
+    fn purge_shared_object(&self, kind: debug::ObjectKind, id: gl::types::GLuint);
 }
 
 /// Internal trait for programs.
@@ -303,18 +366,36 @@ trait ProgramExt {
                                         block_location: gl::types::GLuint,
                                         value: gl::types::GLuint);
 
+    /// Returns the binding point that the user assigned to a uniform block of the program.
+    fn get_fixed_uniform_block_binding(&self, block_location: gl::types::GLuint)
+                                       -> Option<gl::types::GLuint>;
+
+    /// Returns the binding point that the user assigned to a shader storage block of the program.
+    fn get_fixed_shader_storage_block_binding(&self, block_location: gl::types::GLuint)
+                                              -> Option<gl::types::GLuint>;
+
     /// Changes the subroutine uniform bindings of a program.
     fn set_subroutine_uniforms_for_stage(&self, ctxt: &mut context::CommandContext,
                                          stage: program::ShaderStage,
                                          indices: &[gl::types::GLuint]);
 
+    /// Returns true if drawing must fail when the uniforms don't exactly match the active
+    /// uniforms of the program.
+    fn uses_strict_uniforms(&self) -> bool;
+
     fn get_uniform(&self, name: &str) -> Option<&program::Uniform>;
 
+    fn get_uniforms(&self) -> &HashMap<String, program::Uniform, BuildHasherDefault<FnvHasher>>;
+
     fn get_uniform_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
     fn get_shader_storage_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
     fn get_subroutine_data(&self) -> &program::SubroutineData;
+
+    /// Returns a value that identifies the names, types and locations of the attributes of the
+    /// program. Programs that return the same value can share vertex array objects.
+    fn get_attributes_layout(&self) -> u64;
 }
 
 /// Internal trait for queries.
@@ -376,6 +457,10 @@ trait TransformFeedbackSessionExt {
     /// Ensures that transform feedback is disabled.
     fn unbind(&mut CommandContext);
 
+    /// Ensures that nothing is captured by the next commands, by pausing the current capture if
+    /// possible or by ending it otherwise.
+    fn suspend(&mut CommandContext);
+
     /// Ensures that a buffer isn't used by transform feedback.
     fn ensure_buffer_out_of_transform_feedback(&mut CommandContext, gl::types::GLuint);
 }
@@ -386,7 +471,8 @@ trait UniformsExt {
     ///
     /// Will replace texture and buffer bind points.
     fn bind_uniforms<'a, P>(&'a self, &mut CommandContext, &P, &mut Vec<buffer::Inserter<'a>>)
-                            -> Result<(), DrawError> where P: ProgramExt;
+                            -> Result<(), DrawError>
+                            where P: ProgramExt + GlObject<Id = Handle>;
 }
 
 
@@ -438,6 +524,7 @@ pub enum RawUniformValue {
 ///
 /// In the OpenGL ecosystem, the (0,0) coordinate is at the bottom-left hand corner of the images.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Rect {
     /// Number of pixels between the left border of the surface and the left border of
     /// the rectangle.
@@ -455,6 +542,7 @@ pub struct Rect {
 ///
 /// In the OpenGL ecosystem, the (0,0) coordinate is at the bottom-left hand corner of the images.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct BlitTarget {
     /// Number of pixels between the left border of the surface and the left border of
     /// the rectangle.
@@ -677,6 +765,67 @@ pub struct BlitTarget {
 /// to specify whether each color component (red, green, blue and alpha) is written to the color
 /// buffer.
 ///
+/// # Implementing this trait
+///
+/// You can implement `Surface` for your own types, for example to wrap a framebuffer and keep
+/// track of what is drawn on it. Your implementation must forward the calls to one of the
+/// surfaces provided by glium.
+///
+/// Blitting between surfaces is implemented with double dispatch: `blit_color` calls one of the
+/// `blit_from_*` methods of the target. Forwarding `blit_color` and the `blit_from_*` methods
+/// to the wrapped surface is enough for blitting to work in both directions.
+///
+/// If you also implement `FboAttachments` by forwarding to the wrapped surface, your type can be
+/// used with functions that need the attachments, such as `Program::validate` or
+/// `framebuffer::blit_and_resolve`.
+///
+/// ```no_run
+/// # use glium::{Surface, Rect, BlitTarget, DrawParameters, DrawError, Program};
+/// # use glium::{uniforms, index, vertex};
+/// # use glium::framebuffer::{SimpleFrameBuffer, MultiOutputFrameBuffer};
+/// struct CountingTarget<'a> {
+///     inner: SimpleFrameBuffer<'a>,
+///     draw_calls: usize,
+/// }
+///
+/// impl<'a> Surface for CountingTarget<'a> {
+///     fn draw<'b, 'c, V, I, U>(&mut self, vertices: V, indices: I, program: &Program,
+///                              uniforms: &U, draw_parameters: &DrawParameters)
+///                              -> Result<(), DrawError>
+///         where V: vertex::MultiVerticesSource<'c>, I: Into<index::IndicesSource<'b>>,
+///               U: uniforms::Uniforms
+///     {
+///         self.draw_calls += 1;
+///         self.inner.draw(vertices, indices, program, uniforms, draw_parameters)
+///     }
+///
+///     // the other methods are forwarded to `self.inner` too
+/// #   fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>,
+/// #            color_srgb: bool, depth: Option<f32>, stencil: Option<i32>)
+/// #   { self.inner.clear(rect, color, color_srgb, depth, stencil) }
+/// #   fn get_dimensions(&self) -> (u32, u32) { self.inner.get_dimensions() }
+/// #   fn get_depth_buffer_bits(&self) -> Option<u16> { self.inner.get_depth_buffer_bits() }
+/// #   fn get_stencil_buffer_bits(&self) -> Option<u16> { self.inner.get_stencil_buffer_bits() }
+/// #   fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+/// #                      filter: uniforms::MagnifySamplerFilter)
+/// #   { self.inner.blit_from_frame(source_rect, target_rect, filter) }
+/// #   fn blit_from_simple_framebuffer(&self, source: &SimpleFrameBuffer, source_rect: &Rect,
+/// #                                   target_rect: &BlitTarget,
+/// #                                   filter: uniforms::MagnifySamplerFilter)
+/// #   { self.inner.blit_from_simple_framebuffer(source, source_rect, target_rect, filter) }
+/// #   fn blit_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+/// #                                        source_rect: &Rect, target_rect: &BlitTarget,
+/// #                                        filter: uniforms::MagnifySamplerFilter)
+/// #   { self.inner.blit_from_multioutput_framebuffer(source, source_rect, target_rect, filter) }
+/// #   fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+/// #                    filter: uniforms::MagnifySamplerFilter) where S: Surface
+/// #   { self.inner.blit_color(source_rect, target, target_rect, filter) }
+/// }
+/// ```
+///
+/// Note that `Surface` can't be used as a trait object, because `draw` is generic over the
+/// vertices, indices and uniforms. Write functions that are generic over `S: Surface` instead.
+///
 pub trait Surface {
     /// Clears some attachments of the target.
     fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
@@ -771,6 +920,20 @@ pub trait Surface {
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
         U: uniforms::Uniforms;
 
+    /// Launches `count` mesh tasks starting at `first` with a program that contains a mesh
+    /// shader. See `ProgramCreationInput::MeshShaders`.
+    ///
+    /// Requires the `GL_NV_mesh_shader` extension. `count` must not be larger than the
+    /// `max_draw_mesh_tasks_count` of the capabilities.
+    fn draw_mesh_tasks<U>(&mut self, program: &Program, uniforms: &U,
+                          draw_parameters: &DrawParameters, first: u32, count: u32)
+                          -> Result<(), DrawError> where U: uniforms::Uniforms, Self: Sized
+    {
+        self.draw(vertex::EmptyVertexAttributes { len: 0 },
+                  index::IndicesSource::MeshTasks { first: first, count: count },
+                  program, uniforms, draw_parameters)
+    }
+
     /// Blits from the default framebuffer.
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
                        filter: uniforms::MagnifySamplerFilter);
@@ -797,6 +960,11 @@ pub trait Surface {
     ///
     /// Note that there is no alpha blending, depth/stencil checking, etc. This function just
     /// copies pixels.
+    ///
+    /// # Panic
+    ///
+    /// Panics if blitting is not supported by the backend. See
+    /// `framebuffer::is_blit_supported`.
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface;
 
@@ -821,9 +989,15 @@ pub trait Surface {
     }
 }
 
-/// Private trait for framebuffer-like objects that provide attachments.
-trait FboAttachments {
+/// Trait for framebuffer-like objects that provide attachments.
+///
+/// This trait is implemented by all the framebuffer types of the `framebuffer` module, and by
+/// `Frame`. It is used by functions such as `framebuffer::blit_and_resolve`.
+pub trait FboAttachments {
     /// Returns the list of attachments of this FBO, or `None` if it is the default framebuffer.
+    ///
+    /// The returned `framebuffer::ValidatedAttachments` can be inspected but not built outside
+    /// of glium.
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments>;
 }
 
@@ -835,13 +1009,30 @@ pub enum DrawError {
 
     /// The type of a vertex attribute in the vertices source doesn't match what the
     /// program requires.
-    AttributeTypeMismatch,
+    AttributeTypeMismatch {
+        /// Name of the attribute.
+        name: String,
+        /// The type of the attribute in the program.
+        expected: vertex::AttributeType,
+        /// The type of the attribute in the vertex format.
+        obtained: vertex::AttributeType,
+    },
 
     /// One of the attributes required by the program is missing from the vertex format.
     ///
     /// Note that it is perfectly valid to have an attribute in the vertex format that is
     /// not used by the program.
-    AttributeMissing,
+    AttributeMissing {
+        /// Name of the attribute.
+        name: String,
+    },
+
+    /// An integer attribute of the program is fed with values that the vertex format asks to
+    /// normalize to floating-point.
+    NormalizedIntegerAttribute {
+        /// Name of the attribute.
+        name: String,
+    },
 
     /// The viewport's dimensions are not supported by the backend.
     ViewportTooLarge,
@@ -857,6 +1048,21 @@ pub enum DrawError {
         expected: uniforms::UniformType,
     },
 
+    /// A uniform handle has been used with a program other than the one it was obtained from.
+    UniformHandleProgramMismatch {
+        /// Name of the uniform you are trying to bind.
+        name: String,
+    },
+
+    /// Strict uniform checking is enabled for the program (see `Program::set_strict_uniforms`)
+    /// and the uniforms don't match the active uniforms of the program.
+    UniformsMismatch {
+        /// Names of the values that don't correspond to anything in the program.
+        unknown: Vec<String>,
+        /// Names of the active uniforms and blocks of the program that have no value.
+        missing: Vec<String>,
+    },
+
     /// Tried to bind a uniform buffer to a single uniform value.
     UniformBufferToValue {
         /// Name of the uniform you are trying to bind.
@@ -946,24 +1152,105 @@ pub enum DrawError {
 
     /// Tried to enable a clip plane that does not exist.
     ClipPlaneIndexOutOfBounds,
+
+    /// A color attachment of the framebuffer refers to a fragment output that doesn't exist
+    /// in the program.
+    FragmentOutputNotFound {
+        /// Name of the fragment output.
+        name: String,
+    },
+
+    /// Several color attachments of the framebuffer are bound to the same fragment output
+    /// location.
+    FragmentOutputLocationConflict {
+        /// The location that is used multiple times.
+        location: u32,
+    },
+
+    /// A color attachment of the framebuffer is bound to a fragment output location that is
+    /// not supported by the backend.
+    FragmentOutputLocationOutOfRange {
+        /// The requested location.
+        location: u32,
+    },
+
+    /// The color attachments of the framebuffer, once bound to the outputs of the program,
+    /// can't be used together.
+    FramebufferValidation(framebuffer::ValidationError),
+
+    /// The buffer of indirect draw commands doesn't contain a whole number of the commands
+    /// expected by the draw call, or its elements are neither commands nor smaller values that
+    /// commands are made of, like `u32`s.
+    IndirectCommandsStrideMismatch {
+        /// Size in bytes of the commands expected by the draw call.
+        expected: usize,
+        /// Size in bytes of the elements of the buffer.
+        obtained: usize,
+    },
+
+    /// The offset of the buffer of indirect commands is not a multiple of four bytes.
+    IndirectCommandsMisaligned,
+
+    /// Reading the number of commands of a multidraw from a buffer is not supported by the
+    /// backend.
+    DrawCountNotSupported,
+
+    /// Drawing from a `TransformFeedbackCounter` is not supported by the backend, or drawing it
+    /// with multiple instances is not supported.
+    TransformFeedbackCounterNotSupported,
+
+    /// The program captures transform feedback varyings to more buffers than have been added to
+    /// the `TransformFeedbackSession`.
+    TransformFeedbackBufferMissing,
+
+    /// Trying to launch mesh tasks, but mesh shaders are not supported by the backend.
+    MeshShadersNotSupported,
+
+    /// The program contains a mesh shader but is drawn with vertices, or the program doesn't
+    /// contain a mesh shader but is used to launch mesh tasks.
+    MeshShaderMismatch,
+
+    /// The number of mesh tasks to launch is larger than `GL_MAX_DRAW_MESH_TASKS_COUNT_NV`.
+    TooManyMeshTasks,
+
+    /// Per-attachment color masks have been requested, but they are not supported by the
+    /// backend.
+    ColorMasksNotSupported,
+
+    /// More per-attachment color masks have been requested than the maximum number of draw
+    /// buffers.
+    TooManyColorMasks,
+
+    /// Drawing multiple instances has been requested, but instancing is not supported by the
+    /// backend.
+    InstancingNotSupported,
+
+    /// More images are used by the uniforms than the number of image units of the backend.
+    TooManyImageUnits,
 }
 
-impl Error for DrawError {
+impl StdError for DrawError {
     fn description(&self) -> &str {
         use self::DrawError::*;
         match *self {
             NoDepthBuffer =>
                 "A depth function has been requested but no depth buffer is available",
-            AttributeTypeMismatch =>
+            AttributeTypeMismatch { .. } =>
                 "The type of a vertex attribute in the vertices source doesn't match what the program requires",
-            AttributeMissing =>
+            AttributeMissing { .. } =>
                 "One of the attributes required by the program is missing from the vertex format",
+            NormalizedIntegerAttribute { .. } =>
+                "An integer attribute of the program is fed with normalized values",
             ViewportTooLarge =>
                 "The viewport's dimensions are not supported by the backend",
             InvalidDepthRange =>
                 "The depth range is outside of the `(0, 1)` range",
             UniformTypeMismatch { .. } =>
                 "The type of a uniform doesn't match what the program requires",
+            UniformHandleProgramMismatch { .. } =>
+                "A uniform handle has been used with a program other than the one it was obtained from",
+            UniformsMismatch { .. } =>
+                "The uniforms don't match the active uniforms of the program",
             UniformBufferToValue { .. } =>
                 "Tried to bind a uniform buffer to a single uniform value",
             UniformValueToBlock { .. } =>
@@ -1005,14 +1292,47 @@ impl Error for DrawError {
             FixedIndexRestartingNotSupported =>
                 "Restarting indices (multiple objects per draw call) is not supported by the backend",
             ClipPlaneIndexOutOfBounds =>
-                "Tried to enable a clip plane that does not exist."
+                "Tried to enable a clip plane that does not exist.",
+            FragmentOutputNotFound { .. } =>
+                "A color attachment refers to a fragment output that doesn't exist in the program",
+            FragmentOutputLocationConflict { .. } =>
+                "Several color attachments are bound to the same fragment output location",
+            FragmentOutputLocationOutOfRange { .. } =>
+                "A color attachment is bound to a fragment output location that is not supported",
+            FramebufferValidation(_) =>
+                "The attachments of the framebuffer can't be used with the outputs of the program",
+            IndirectCommandsStrideMismatch { .. } =>
+                "The buffer of indirect commands doesn't contain whole commands of the kind expected by the draw call",
+            IndirectCommandsMisaligned =>
+                "The offset of the buffer of indirect commands is not a multiple of four bytes",
+            DrawCountNotSupported =>
+                "Reading the number of commands of a multidraw from a buffer is not supported by the backend",
+            TransformFeedbackCounterNotSupported =>
+                "Drawing from a transform feedback counter is not supported by the backend",
+            TransformFeedbackBufferMissing =>
+                "A buffer is missing in the transform feedback session",
+            MeshShadersNotSupported =>
+                "Mesh shaders are not supported by the backend",
+            MeshShaderMismatch =>
+                "Programs with a mesh shader must be used with mesh tasks and only with them",
+            TooManyMeshTasks =>
+                "The number of mesh tasks is larger than the maximum supported by the backend",
+            ColorMasksNotSupported =>
+                "Per-attachment color masks are not supported by the backend",
+            TooManyColorMasks =>
+                "More color masks have been requested than the maximum number of draw buffers",
+            InstancingNotSupported =>
+                "Instancing is not supported by the backend",
+            TooManyImageUnits =>
+                "More images are used than the number of image units of the backend",
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(StdError + 'static)> {
         use self::DrawError::*;
         match *self {
             UniformBlockLayoutMismatch { ref err, .. } => Some(err),
+            FramebufferValidation(ref err) => Some(err),
             _ => None,
         }
     }
@@ -1023,6 +1343,29 @@ impl fmt::Display for DrawError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         use self::DrawError::*;
         match *self {
+            AttributeTypeMismatch { ref name, ref expected, ref obtained } =>
+                write!(
+                    fmt,
+                    "{}: {}, got: {:?}, expected: {:?}",
+                    self.description(),
+                    name,
+                    obtained,
+                    expected,
+                ),
+            AttributeMissing { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            NormalizedIntegerAttribute { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
             UniformTypeMismatch { ref name, ref expected } =>
                 write!(
                     fmt,
@@ -1031,6 +1374,21 @@ impl fmt::Display for DrawError {
                     name,
                     expected,
                 ),
+            UniformHandleProgramMismatch { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            UniformsMismatch { ref unknown, ref missing } =>
+                write!(
+                    fmt,
+                    "{}, unknown: {:?}, missing: {:?}",
+                    self.description(),
+                    unknown,
+                    missing,
+                ),
             UniformBufferToValue { ref name } =>
                 write!(
                     fmt,
@@ -1053,6 +1411,36 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            FragmentOutputNotFound { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            FragmentOutputLocationConflict { location } |
+            FragmentOutputLocationOutOfRange { location } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    location,
+                ),
+            FramebufferValidation(ref err) =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    err,
+                ),
+            IndirectCommandsStrideMismatch { expected, obtained } =>
+                write!(
+                    fmt,
+                    "{}, got: {} bytes, expected: {} bytes",
+                    self.description(),
+                    obtained,
+                    expected,
+                ),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -1080,7 +1468,7 @@ pub enum SwapBuffersError {
     AlreadySwapped,
 }
 
-impl Error for SwapBuffersError {
+impl StdError for SwapBuffersError {
     fn description(&self) -> &str {
         use self::SwapBuffersError::*;
         match *self {
@@ -1141,6 +1529,33 @@ impl Frame {
         self.destroyed = true;
         self.context.swap_buffers()
     }
+
+    /// Stop drawing, swap the buffers while reporting which regions have changed, and consume
+    /// the Frame.
+    ///
+    /// Applications that only redraw small parts of the window can use this to let the
+    /// compositor save work and power. The damage is ignored if the windowing system doesn't
+    /// support it (for example `EGL_KHR_swap_buffers_with_damage` is required with EGL). Note
+    /// that the frame must still contain the whole image, not only the damaged regions.
+    ///
+    /// An empty list means that the whole frame has changed.
+    #[inline]
+    pub fn finish_with_damage(mut self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        self.set_finish_with_damage(damage)
+    }
+
+    /// Stop drawing, swap the buffers while reporting which regions have changed.
+    ///
+    /// See `finish_with_damage` and `set_finish`.
+    #[inline]
+    pub fn set_finish_with_damage(&mut self, damage: &[Rect]) -> Result<(), SwapBuffersError> {
+        if self.destroyed {
+            return Err(SwapBuffersError::AlreadySwapped);
+        }
+
+        self.destroyed = true;
+        self.context.swap_buffers_with_damage(damage)
+    }
 }
 
 impl Surface for Frame {
@@ -1188,6 +1603,15 @@ impl Surface for Frame {
             }
         }
 
+        if let Some(viewport) = draw_parameters.float_viewport {
+            let dimensions = (self.dimensions.0 as u32, self.dimensions.1 as u32);
+            let (_, _, width, height) = viewport.to_pixels(dimensions);
+            let max_dims = self.context.capabilities().max_viewport_dims;
+            if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
                   uniforms, draw_parameters, (self.dimensions.0 as u32, self.dimensions.1 as u32))
     }
@@ -1253,13 +1677,79 @@ impl fmt::Display for IncompatibleOpenGl {
     }
 }
 
-impl Error for IncompatibleOpenGl {
+impl StdError for IncompatibleOpenGl {
     #[inline]
     fn description(&self) -> &str {
         "The OpenGL implementation is too old to work with glium"
     }
 }
 
+/// Error that can happen while creating a `Context`.
+///
+/// Each variant describes a different reason, so that applications can for example try again
+/// with a different version of OpenGL or a different backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContextCreationError {
+    /// The version of OpenGL is too old, and the extensions that would make up for it are
+    /// missing.
+    UnsupportedVersion(IncompatibleOpenGl),
+
+    /// The OpenGL implementation doesn't support an extension that glium needs. Contains the
+    /// name of the extension.
+    MissingExtension(&'static str),
+
+    /// The OpenGL implementation returned a value that doesn't follow the specifications.
+    /// Contains a description of the problem.
+    DriverBug(String),
+
+    /// An OpenGL error that makes the context unusable was triggered during its initialization.
+    /// Contains the name of the error.
+    GlError(&'static str),
+}
+
+impl fmt::Display for ContextCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::ContextCreationError::*;
+        match *self {
+            UnsupportedVersion(ref err) => write!(fmt, "{}: {}", self.description(), err.0),
+            MissingExtension(name) => write!(fmt, "{}: {}", self.description(), name),
+            DriverBug(ref desc) => write!(fmt, "{}: {}", self.description(), desc),
+            GlError(name) => write!(fmt, "{}: {}", self.description(), name),
+        }
+    }
+}
+
+impl StdError for ContextCreationError {
+    fn description(&self) -> &str {
+        use self::ContextCreationError::*;
+        match *self {
+            UnsupportedVersion(_) =>
+                "The OpenGL implementation is too old to work with glium",
+            MissingExtension(_) =>
+                "The OpenGL implementation doesn't support an extension that glium needs",
+            DriverBug(_) =>
+                "The OpenGL implementation returned a value that doesn't follow the specifications",
+            GlError(_) =>
+                "An OpenGL error was triggered during the initialization of the context",
+        }
+    }
+
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        use self::ContextCreationError::*;
+        match *self {
+            UnsupportedVersion(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<IncompatibleOpenGl> for ContextCreationError {
+    #[inline]
+    fn from(err: IncompatibleOpenGl) -> ContextCreationError {
+        ContextCreationError::UnsupportedVersion(err)
+    }
+}
+
 #[allow(dead_code)]
 #[inline]
 fn get_gl_error(ctxt: &mut context::CommandContext) -> Option<&'static str> {
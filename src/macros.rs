@@ -426,6 +426,48 @@ macro_rules! implement_uniform_block {
     );
 }
 
+/// Implements the `glium::material::Material` and `glium::uniforms::UniformBlock` traits for
+/// the given type.
+///
+/// The parameters must be the name of the struct and the names of the fields that are part of
+/// the material. The GLSL struct has the same name and the same members as the Rust struct.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// #[repr(C)]
+/// struct Material {
+///     color: [f32; 4],
+///     shininess: f32,
+///     _padding: [f32; 3],
+/// }
+///
+/// implement_material!(Material, color, shininess);
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_material {
+    ($struct_name:ident, $($field_name:ident),+,) => (
+        implement_material!($struct_name, $($field_name),+);
+    );
+
+    ($struct_name:ident, $($field_name:ident),+) => (
+        implement_uniform_block!($struct_name, $($field_name),+);
+
+        impl $crate::material::Material for $struct_name {
+            #[inline]
+            fn glsl_struct_name() -> &'static str {
+                stringify!($struct_name)
+            }
+        }
+    );
+}
+
 /// Builds a program depending on the GLSL version supported by the backend.
 ///
 /// This is implemented with successive calls to `is_glsl_version_supported()`.
@@ -542,6 +584,10 @@ macro_rules! program {
                 geometry_shader: __geometry_shader,
                 fragment_shader: __fragment_shader,
                 transform_feedback_varyings: None,
+                fragment_output_locations: Vec::new(),
+                attribute_locations: Vec::new(),
+                defines: Vec::new(),
+                adapt_glsl_version: false,
                 outputs_srgb: __outputs_srgb,
                 uses_point_size: __uses_point_size,
             };
@@ -555,7 +601,7 @@ macro_rules! program {
     );
 
     (_inner, $context:ident, $vers:ident, {$($ty:ident:$src:expr),+,}$($rest:tt)*) => (
-        program!(_inner, $context, $vers, {$($ty:$src),+} $($rest)*);
+        program!(_inner, $context, $vers, {$($ty:$src),+} $($rest)*)
     );
 
     (_program_ty vertex, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
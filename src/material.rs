@@ -0,0 +1,418 @@
+/*!
+Storing the parameters of all the materials in a single shader storage buffer.
+
+Instead of uploading the parameters of a material with uniforms before each draw call, all the
+materials are stored in an array inside a SSBO and each draw call only sets the index of its
+material. Switching between materials is then as cheap as changing an integer uniform.
+
+The structs that contain the parameters of a material must implement the `Material` trait with
+the `implement_material!` macro, which also implements `UniformBlock`. The GLSL declarations
+of the struct, of the buffer and of the index uniform are generated from the Rust struct by
+`MaterialBuffer::glsl_declarations`, and can be included in the shaders.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { std::mem::uninitialized() };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+# let vertex_shader = "";
+use glium::Surface;
+use glium::material::MaterialBuffer;
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+struct PbrMaterial {
+    color: [f32; 4],
+    roughness: f32,
+    metalness: f32,
+    _padding: [f32; 2],
+}
+
+implement_material!(PbrMaterial, color, roughness, metalness);
+
+let mut materials = MaterialBuffer::new(&display, 16).unwrap();
+let red = materials.push(PbrMaterial {
+    color: [1.0, 0.0, 0.0, 1.0], roughness: 0.5, metalness: 0.0, _padding: [0.0; 2]
+}).unwrap();
+
+let fragment_shader = format!("
+    #version 430
+    {}
+
+    out vec4 f_color;
+
+    void main() {{
+        f_color = materials[material_index].color;
+    }}
+", MaterialBuffer::<PbrMaterial>::glsl_declarations());
+
+let program = glium::Program::from_source(&display, vertex_shader, &fragment_shader,
+                                          None).unwrap();
+
+let mut frame = display.draw();
+frame.draw(&vertex_buffer, &indices, &program,
+           &materials.uniforms(red, &glium::uniforms::EmptyUniforms),
+           &Default::default()).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+## Layout
+
+The buffer uses the `std430` layout. The fields of the Rust struct must have the same offsets
+as the GLSL members, which is checked when drawing like for any other block. In addition, the
+size of the struct must be a multiple of its `std430` alignment, which is the largest alignment
+of its members (16 bytes for a `vec3` or a `vec4`). This usually requires adding padding fields
+at the end of the struct, that must not be passed to `implement_material!`.
+
+Only fields of basic types and arrays of basic types are supported.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+
+use backend::Facade;
+use buffer::{Buffer, BufferCreationError, BufferMode, BufferType};
+use context::Context;
+use program::BlockLayout;
+use uniforms::{AsUniformValue, RawUniformHandle, UniformBlock, UniformType, UniformValue};
+use uniforms::Uniforms;
+
+/// Name of the storage block that contains the materials.
+pub const MATERIALS_BLOCK: &'static str = "Materials";
+
+/// Name of the array of materials inside the storage block.
+pub const MATERIALS_ARRAY: &'static str = "materials";
+
+/// Name of the uniform that contains the index of the material of a draw call.
+pub const MATERIAL_INDEX_UNIFORM: &'static str = "material_index";
+
+/// A struct that contains the parameters of a material.
+///
+/// Implement this trait with the `implement_material!` macro.
+pub trait Material: UniformBlock + Copy {
+    /// Returns the name of the struct in GLSL.
+    fn glsl_struct_name() -> &'static str;
+}
+
+/// Returns the GLSL declaration of the struct of a material.
+///
+/// Returns `None` if one of the members isn't a basic type or an array of basic types.
+pub fn glsl_struct<M>() -> Option<String> where M: Material {
+    let members = match M::build_layout(0) {
+        BlockLayout::Struct { members } => members,
+        _ => return None,
+    };
+
+    let mut result = format!("struct {} {{\n", M::glsl_struct_name());
+    for (name, layout) in members {
+        match layout {
+            BlockLayout::BasicType { ty, .. } => {
+                let ty = match glsl_type(ty) { Some(t) => t.0, None => return None };
+                result.push_str(&format!("    {} {};\n", ty, name));
+            },
+            BlockLayout::Array { content, length } => {
+                let ty = match *content {
+                    BlockLayout::BasicType { ty, .. } => ty,
+                    _ => return None,
+                };
+                let ty = match glsl_type(ty) { Some(t) => t.0, None => return None };
+                result.push_str(&format!("    {} {}[{}];\n", ty, name, length));
+            },
+            _ => return None,
+        }
+    }
+    result.push_str("};\n");
+
+    Some(result)
+}
+
+/// Returns the alignment of a material in the `std430` layout.
+fn std430_alignment<M>() -> Option<usize> where M: Material {
+    let members = match M::build_layout(0) {
+        BlockLayout::Struct { members } => members,
+        _ => return None,
+    };
+
+    let mut alignment = 1;
+    for (_, layout) in members {
+        let ty = match layout {
+            BlockLayout::BasicType { ty, .. } => ty,
+            BlockLayout::Array { content, .. } => match *content {
+                BlockLayout::BasicType { ty, .. } => ty,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let member_alignment = match glsl_type(ty) { Some(t) => t.1, None => return None };
+        if member_alignment > alignment {
+            alignment = member_alignment;
+        }
+    }
+
+    Some(alignment)
+}
+
+/// Returns the GLSL name and the `std430` alignment of a basic type.
+fn glsl_type(ty: UniformType) -> Option<(&'static str, usize)> {
+    use uniforms::UniformType::*;
+
+    Some(match ty {
+        Float => ("float", 4),
+        FloatVec2 => ("vec2", 8),
+        FloatVec3 => ("vec3", 16),
+        FloatVec4 => ("vec4", 16),
+        Double => ("double", 8),
+        DoubleVec2 => ("dvec2", 16),
+        DoubleVec3 => ("dvec3", 32),
+        DoubleVec4 => ("dvec4", 32),
+        Int => ("int", 4),
+        IntVec2 => ("ivec2", 8),
+        IntVec3 => ("ivec3", 16),
+        IntVec4 => ("ivec4", 16),
+        UnsignedInt => ("uint", 4),
+        UnsignedIntVec2 => ("uvec2", 8),
+        UnsignedIntVec3 => ("uvec3", 16),
+        UnsignedIntVec4 => ("uvec4", 16),
+        Int64 => ("int64_t", 8),
+        Int64Vec2 => ("i64vec2", 16),
+        Int64Vec3 => ("i64vec3", 32),
+        Int64Vec4 => ("i64vec4", 32),
+        UnsignedInt64 => ("uint64_t", 8),
+        UnsignedInt64Vec2 => ("u64vec2", 16),
+        UnsignedInt64Vec3 => ("u64vec3", 32),
+        UnsignedInt64Vec4 => ("u64vec4", 32),
+        Bool => ("bool", 4),
+        BoolVec2 => ("bvec2", 8),
+        BoolVec3 => ("bvec3", 16),
+        BoolVec4 => ("bvec4", 16),
+        // the alignment of a matrix is the alignment of one of its columns
+        FloatMat2 => ("mat2", 8),
+        FloatMat3 => ("mat3", 16),
+        FloatMat4 => ("mat4", 16),
+        FloatMat2x3 => ("mat2x3", 16),
+        FloatMat2x4 => ("mat2x4", 16),
+        FloatMat3x2 => ("mat3x2", 8),
+        FloatMat3x4 => ("mat3x4", 16),
+        FloatMat4x2 => ("mat4x2", 8),
+        FloatMat4x3 => ("mat4x3", 16),
+        DoubleMat2 => ("dmat2", 16),
+        DoubleMat3 => ("dmat3", 32),
+        DoubleMat4 => ("dmat4", 32),
+        DoubleMat2x3 => ("dmat2x3", 32),
+        DoubleMat2x4 => ("dmat2x4", 32),
+        DoubleMat3x2 => ("dmat3x2", 16),
+        DoubleMat3x4 => ("dmat3x4", 32),
+        DoubleMat4x2 => ("dmat4x2", 16),
+        DoubleMat4x3 => ("dmat4x3", 32),
+        _ => return None,
+    })
+}
+
+/// Error that can happen while creating a `MaterialBuffer`.
+#[derive(Clone, Debug)]
+pub enum MaterialBufferCreationError {
+    /// Failed to create the buffer. Shader storage buffers may not be supported.
+    BufferCreationError(BufferCreationError),
+
+    /// One of the members of the material isn't a basic type or an array of basic types.
+    UnsupportedMember,
+
+    /// The size of the material isn't a multiple of its alignment in the `std430` layout.
+    WrongSize {
+        /// Size of the Rust struct.
+        size: usize,
+        /// Alignment of the GLSL struct.
+        alignment: usize,
+    },
+}
+
+impl fmt::Display for MaterialBufferCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for MaterialBufferCreationError {
+    fn description(&self) -> &str {
+        use self::MaterialBufferCreationError::*;
+        match *self {
+            BufferCreationError(_) =>
+                "Failed to create the buffer of the materials",
+            UnsupportedMember =>
+                "One of the members of the material isn't a basic type or an array of basic \
+                 types",
+            WrongSize { .. } =>
+                "The size of the material isn't a multiple of its alignment in the std430 layout",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            MaterialBufferCreationError::BufferCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<BufferCreationError> for MaterialBufferCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> MaterialBufferCreationError {
+        MaterialBufferCreationError::BufferCreationError(err)
+    }
+}
+
+/// A shader storage buffer that contains an array of materials.
+///
+/// The materials are identified by their index in the array. A copy of the materials is kept
+/// in memory so that the buffer can be grown when it is full.
+pub struct MaterialBuffer<M> where M: Material {
+    context: Rc<Context>,
+    buffer: Buffer<[M]>,
+    materials: Vec<M>,
+}
+
+impl<M> MaterialBuffer<M> where M: Material {
+    /// Builds an empty buffer with room for `capacity` materials.
+    pub fn new<F: ?Sized>(facade: &F, capacity: usize)
+                          -> Result<MaterialBuffer<M>, MaterialBufferCreationError>
+        where F: Facade
+    {
+        let alignment = match std430_alignment::<M>() {
+            Some(a) => a,
+            None => return Err(MaterialBufferCreationError::UnsupportedMember),
+        };
+
+        if glsl_struct::<M>().is_none() {
+            return Err(MaterialBufferCreationError::UnsupportedMember);
+        }
+
+        let size = mem::size_of::<M>();
+        if size % alignment != 0 {
+            return Err(MaterialBufferCreationError::WrongSize {
+                size: size,
+                alignment: alignment,
+            });
+        }
+
+        let capacity = if capacity == 0 { 1 } else { capacity };
+        let buffer = try!(Buffer::empty_array(facade, BufferType::ShaderStorageBuffer, capacity,
+                                              BufferMode::Default));
+
+        Ok(MaterialBuffer {
+            context: facade.get_context().clone(),
+            buffer: buffer,
+            materials: Vec::with_capacity(capacity),
+        })
+    }
+
+    /// Returns the GLSL code that declares the struct of the materials, the storage block that
+    /// contains them and the uniform that contains the index of the current material.
+    ///
+    /// The material of the current draw call is `materials[material_index]`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if one of the members of the material isn't a basic type or an array of basic
+    /// types. `new` returns an error in this situation.
+    pub fn glsl_declarations() -> String {
+        let declaration = glsl_struct::<M>().expect("Unsupported member in the material");
+
+        format!("{}\nlayout(std430) readonly buffer {} {{\n    {} {}[];\n}};\n\n\
+                 uniform uint {};\n",
+                declaration, MATERIALS_BLOCK, M::glsl_struct_name(), MATERIALS_ARRAY,
+                MATERIAL_INDEX_UNIFORM)
+    }
+
+    /// Adds a material at the end of the array and returns its index.
+    ///
+    /// If the buffer is full, it is replaced with a buffer twice as large.
+    pub fn push(&mut self, material: M) -> Result<u32, BufferCreationError> {
+        if self.materials.len() == self.buffer.len() {
+            let buffer = try!(Buffer::empty_array(&self.context, BufferType::ShaderStorageBuffer,
+                                                  self.buffer.len() * 2, BufferMode::Default));
+            buffer.slice(0 .. self.materials.len()).unwrap().write(&self.materials[..]);
+            self.buffer = buffer;
+        }
+
+        let index = self.materials.len();
+        self.buffer.slice(index .. index + 1).unwrap().write(&[material]);
+        self.materials.push(material);
+        Ok(index as u32)
+    }
+
+    /// Returns a material.
+    #[inline]
+    pub fn get(&self, index: u32) -> Option<&M> {
+        self.materials.get(index as usize)
+    }
+
+    /// Replaces a material.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the index is out of range.
+    pub fn set(&mut self, index: u32, material: M) {
+        let index = index as usize;
+        assert!(index < self.materials.len());
+
+        self.buffer.slice(index .. index + 1).unwrap().write(&[material]);
+        self.materials[index] = material;
+    }
+
+    /// Returns the number of materials.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    /// Returns the number of materials that the buffer can contain before being grown.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the buffer that contains the materials.
+    #[inline]
+    pub fn get_buffer(&self) -> &Buffer<[M]> {
+        &self.buffer
+    }
+
+    /// Adds the storage block of the materials and the index of a material to a list of
+    /// uniforms.
+    #[inline]
+    pub fn uniforms<'a, U>(&'a self, index: u32, uniforms: &'a U) -> MaterialUniforms<'a, M, U>
+        where U: Uniforms
+    {
+        MaterialUniforms {
+            buffer: &self.buffer,
+            index: index,
+            uniforms: uniforms,
+        }
+    }
+}
+
+/// Uniforms that contain the storage block of a `MaterialBuffer` and the index of a material,
+/// in addition to the uniforms of the user.
+pub struct MaterialUniforms<'a, M: 'a, U: 'a> where M: Material {
+    buffer: &'a Buffer<[M]>,
+    index: u32,
+    uniforms: &'a U,
+}
+
+impl<'a, M, U> Uniforms for MaterialUniforms<'a, M, U> where M: Material, U: Uniforms {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        output(MATERIALS_BLOCK, self.buffer.as_uniform_value());
+        output(MATERIAL_INDEX_UNIFORM, UniformValue::UnsignedInt(self.index));
+        self.uniforms.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_handles<'b, F: FnMut(&RawUniformHandle, UniformValue<'b>)>(&'b self, output: F) {
+        self.uniforms.visit_handles(output);
+    }
+}
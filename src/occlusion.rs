@@ -0,0 +1,355 @@
+/*!
+Skipping the objects that are hidden behind other objects, with occlusion queries.
+
+For each object, the `OcclusionCuller` draws its bounding box after the rest of the scene, without
+writing any color or depth, and counts with an `AnySamplesPassedQuery` whether any part of the
+box passed the depth test. Waiting for the result of a query in the same frame would stall the
+pipeline, so the result is read during the next frame: `is_visible` returns what happened during
+the previous frame.
+
+Objects are identified by an index chosen by the user. Objects that haven't been tested yet, or
+whose latest result isn't available yet, are considered visible.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let view_projection: [[f32; 4]; 4] = unsafe { std::mem::uninitialized() };
+# struct Object { min: [f32; 3], max: [f32; 3] }
+# impl Object { fn draw(&self, _: &mut glium::Frame) {} }
+# let objects: Vec<Object> = vec![];
+use glium::Surface;
+use glium::occlusion::OcclusionCuller;
+
+let mut culler = OcclusionCuller::new(&display, true).unwrap();
+
+// each frame
+culler.next_frame();
+let mut frame = display.draw();
+frame.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+
+for (id, object) in objects.iter().enumerate() {
+    if culler.is_visible(id) {
+        object.draw(&mut frame);
+    }
+}
+
+// the boxes must be tested against the depth buffer of the scene
+for (id, object) in objects.iter().enumerate() {
+    culler.test(&mut frame, id, object.min, object.max, view_projection).unwrap();
+}
+
+frame.finish().unwrap();
+# }
+```
+
+Since the results come from the previous frame, an object that becomes visible is drawn one
+frame late. Testing all the objects, including the ones that are drawn, keeps this delay to a
+single frame.
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use draw_parameters::{AnySamplesPassedQuery, QueryCreationError};
+use index::{IndexBuffer, PrimitiveType};
+use index::BufferCreationError as IndexBufferCreationError;
+use program::{Program, ProgramChooserCreationError};
+use vertex::VertexBuffer;
+use vertex::BufferCreationError;
+
+use Depth;
+use DepthTest;
+use DrawError;
+use DrawParameters;
+use Surface;
+
+/// Error that can happen while creating an `OcclusionCuller`.
+#[derive(Clone, Debug)]
+pub enum OcclusionCullerCreationError {
+    /// Failed to compile the program that draws the boxes.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to create the vertex buffer of the box.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to create the index buffer of the box.
+    IndexBufferCreationError(IndexBufferCreationError),
+
+    /// The backend doesn't support occlusion queries.
+    QueryCreationError(QueryCreationError),
+}
+
+impl fmt::Display for OcclusionCullerCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for OcclusionCullerCreationError {
+    fn description(&self) -> &str {
+        use self::OcclusionCullerCreationError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile the program that draws the boxes",
+            BufferCreationError(_) =>
+                "Failed to create the vertex buffer of the box",
+            IndexBufferCreationError(_) =>
+                "Failed to create the index buffer of the box",
+            QueryCreationError(_) =>
+                "The backend doesn't support occlusion queries",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::OcclusionCullerCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+            IndexBufferCreationError(ref err) => Some(err),
+            QueryCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for OcclusionCullerCreationError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> OcclusionCullerCreationError {
+        OcclusionCullerCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for OcclusionCullerCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> OcclusionCullerCreationError {
+        OcclusionCullerCreationError::BufferCreationError(err)
+    }
+}
+
+impl From<IndexBufferCreationError> for OcclusionCullerCreationError {
+    #[inline]
+    fn from(err: IndexBufferCreationError) -> OcclusionCullerCreationError {
+        OcclusionCullerCreationError::IndexBufferCreationError(err)
+    }
+}
+
+impl From<QueryCreationError> for OcclusionCullerCreationError {
+    #[inline]
+    fn from(err: QueryCreationError) -> OcclusionCullerCreationError {
+        OcclusionCullerCreationError::QueryCreationError(err)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct BoxVertex {
+    position: [f32; 3],
+}
+
+implement_vertex!(BoxVertex, position);
+
+/// Occlusion state of an object.
+#[derive(Default)]
+struct ObjectState {
+    // queries of the current frame and of the previous frame, indexed by the parity of the frame
+    queries: [Option<AnySamplesPassedQuery>; 2],
+
+    // result of the latest query that has finished
+    visible: Option<bool>,
+}
+
+/// Tests the bounding boxes of objects against the depth buffer and remembers which ones are
+/// hidden.
+pub struct OcclusionCuller {
+    context: Rc<Context>,
+    program: Program,
+    vertex_buffer: VertexBuffer<BoxVertex>,
+    index_buffer: IndexBuffer<u16>,
+    conservative: bool,
+
+    // indexed by the identifier of the object
+    objects: Vec<ObjectState>,
+
+    // parity of the current frame
+    frame: usize,
+}
+
+impl OcclusionCuller {
+    /// Builds a new `OcclusionCuller`.
+    ///
+    /// If `conservative` is true, the queries may use a faster algorithm that gives more false
+    /// positives, which only means that more objects are considered visible.
+    pub fn new<F: ?Sized>(facade: &F, conservative: bool)
+                          -> Result<OcclusionCuller, OcclusionCullerCreationError>
+                          where F: Facade
+    {
+        // making sure that queries are supported, so that `test` never fails because of them
+        try!(AnySamplesPassedQuery::new(facade, conservative));
+
+        let program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform mat4 matrix;
+                    uniform vec3 box_min;
+                    uniform vec3 box_max;
+
+                    in vec3 position;
+
+                    void main() {
+                        gl_Position = matrix * vec4(mix(box_min, box_max, position), 1.0);
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = vec4(1.0);
+                    }
+                ",
+            },
+
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform mat4 matrix;
+                    uniform vec3 box_min;
+                    uniform vec3 box_max;
+
+                    attribute vec3 position;
+
+                    void main() {
+                        gl_Position = matrix * vec4(mix(box_min, box_max, position), 1.0);
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    void main() {
+                        gl_FragColor = vec4(1.0);
+                    }
+                ",
+            },
+        ));
+
+        let vertex_buffer = try!(VertexBuffer::new(facade, &[
+            BoxVertex { position: [0.0, 0.0, 0.0] }, BoxVertex { position: [1.0, 0.0, 0.0] },
+            BoxVertex { position: [0.0, 1.0, 0.0] }, BoxVertex { position: [1.0, 1.0, 0.0] },
+            BoxVertex { position: [0.0, 0.0, 1.0] }, BoxVertex { position: [1.0, 0.0, 1.0] },
+            BoxVertex { position: [0.0, 1.0, 1.0] }, BoxVertex { position: [1.0, 1.0, 1.0] },
+        ]));
+
+        // two triangles per face ; the winding doesn't matter as culling is disabled, so that
+        // the box is still drawn when the camera is inside it
+        let index_buffer = try!(IndexBuffer::new(facade, PrimitiveType::TrianglesList, &[
+            0, 1, 3, 0, 3, 2,   4, 5, 7, 4, 7, 6,
+            0, 1, 5, 0, 5, 4,   2, 3, 7, 2, 7, 6,
+            0, 2, 6, 0, 6, 4,   1, 3, 7, 1, 7, 5u16,
+        ]));
+
+        Ok(OcclusionCuller {
+            context: facade.get_context().clone(),
+            program: program,
+            vertex_buffer: vertex_buffer,
+            index_buffer: index_buffer,
+            conservative: conservative,
+            objects: Vec::new(),
+            frame: 0,
+        })
+    }
+
+    /// Starts a new frame. Must be called once per frame, before `is_visible`.
+    ///
+    /// The results of the queries of the previous frame that have finished are retrieved. This
+    /// never blocks.
+    pub fn next_frame(&mut self) {
+        self.frame = 1 - self.frame;
+        let previous = 1 - self.frame;
+
+        for object in self.objects.iter_mut() {
+            if object.queries[previous].as_ref().map(|q| q.is_ready()).unwrap_or(false) {
+                let query = object.queries[previous].take().unwrap();
+                object.visible = Some(query.get());
+            }
+
+            // queries of two frames ago that are still not finished are abandoned
+            object.queries[self.frame] = None;
+        }
+    }
+
+    /// Returns true if the object was visible during the latest test whose result is available.
+    ///
+    /// Objects that have never been tested, or whose result isn't available yet, are visible.
+    #[inline]
+    pub fn is_visible(&self, object: usize) -> bool {
+        self.objects.get(object).and_then(|o| o.visible).unwrap_or(true)
+    }
+
+    /// Returns true if the object was hidden during the latest test whose result is available.
+    ///
+    /// This is the opposite of `is_visible`.
+    #[inline]
+    pub fn is_hidden(&self, object: usize) -> bool {
+        !self.is_visible(object)
+    }
+
+    /// Draws the bounding box of an object to `surface` and starts a query that tells whether
+    /// any part of it passes the depth test. Nothing is written to the surface.
+    ///
+    /// `matrix` transforms the corners of the box to clip space. The surface must have a depth
+    /// buffer that already contains the scene, otherwise `DrawError::NoDepthBuffer` is returned.
+    /// Testing the same object twice in a frame replaces the first query.
+    pub fn test<S: ?Sized>(&mut self, surface: &mut S, object: usize, min: [f32; 3],
+                           max: [f32; 3], matrix: [[f32; 4]; 4]) -> Result<(), DrawError>
+                           where S: Surface
+    {
+        // checked when the culler was created
+        let query = AnySamplesPassedQuery::new(&self.context, self.conservative).unwrap();
+
+        {
+            let parameters = DrawParameters {
+                depth: Depth {
+                    test: DepthTest::IfLessOrEqual,
+                    write: false,
+                    .. Default::default()
+                },
+                color_mask: (false, false, false, false),
+                samples_passed_query: Some((&query).into()),
+                .. Default::default()
+            };
+
+            let uniforms = uniform! {
+                matrix: matrix,
+                box_min: min,
+                box_max: max,
+            };
+
+            try!(surface.draw(&self.vertex_buffer, &self.index_buffer, &self.program,
+                              &uniforms, &parameters));
+        }
+
+        while self.objects.len() <= object {
+            self.objects.push(Default::default());
+        }
+
+        self.objects[object].queries[self.frame] = Some(query);
+        Ok(())
+    }
+
+    /// Forgets the state of all objects, which become visible again.
+    pub fn clear(&mut self) {
+        self.objects.clear();
+    }
+}
+
+impl fmt::Debug for OcclusionCuller {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "OcclusionCuller({} objects)", self.objects.len())
+    }
+}
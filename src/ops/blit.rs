@@ -2,8 +2,10 @@ use BlitTarget;
 use Rect;
 
 use context::Context;
+use context::CommandContext;
 use ContextExt;
 
+use fbo;
 use fbo::FramebuffersContainer;
 use fbo::ValidatedAttachments;
 
@@ -15,75 +17,141 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
             target: Option<&ValidatedAttachments>, mask: gl::types::GLbitfield,
             src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
 {
-    unsafe {
-        let mut ctxt = context.make_current();
+    let mut ctxt = context.make_current();
+
+    if !fbo::is_blit_supported(&ctxt) {
+        panic!("Blitting is not supported by the backend");
+    }
 
-        // FIXME: we don't draw on it
-        let source = FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, source);
-        let target = FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, target);
+    // FIXME: we don't draw on it
+    let source = FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, source);
+    let target = FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, target);
 
-        // scissor testing influences blitting
-        if ctxt.state.enabled_scissor_test {
-            ctxt.gl.Disable(gl::SCISSOR_TEST);
-            ctxt.state.enabled_scissor_test = false;
-        }
+    unsafe { blit_framebuffers(&mut ctxt, source, target, mask, src_rect, target_rect, filter) };
+}
+
+/// Same as `blit`, but copies from the color attachment whose index is `source_color` to the
+/// color attachment whose index is `target_color`, once for each pair of rectangles.
+///
+/// These indices are ignored for the default framebuffer.
+pub fn blit_attachments(context: &Context, source: Option<&ValidatedAttachments>,
+                        target: Option<&ValidatedAttachments>, mask: gl::types::GLbitfield,
+                        source_color: u32, target_color: u32, rects: &[(Rect, BlitTarget)],
+                        filter: gl::types::GLenum)
+{
+    let mut ctxt = context.make_current();
+
+    let source_id = FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, source);
+    let target_id = FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, target);
 
-        // trying to do a named blit if possible
-        if ctxt.version >= &Version(Api::Gl, 4, 5) {
-            ctxt.gl.BlitNamedFramebuffer(source, target,
-                src_rect.left as gl::types::GLint,
-                src_rect.bottom as gl::types::GLint,
-                (src_rect.left + src_rect.width) as gl::types::GLint,
-                (src_rect.bottom + src_rect.height) as gl::types::GLint,
-                target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
-                (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
-                (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
-
-            return;
+    let redirect_color = mask & gl::COLOR_BUFFER_BIT != 0;
+
+    // without `glDrawBuffers`, only the first attachment can be drawn to and is always enabled
+    let redirect_draw = redirect_color && fbo::is_draw_buffers_supported(&ctxt);
+
+    unsafe {
+        // only the attachments that must be copied are enabled during the blit
+        if redirect_color && source_id != 0 {
+            fbo::set_read_buffer(&mut ctxt, source_id, gl::COLOR_ATTACHMENT0 + source_color);
+        }
+        if redirect_draw && target_id != 0 {
+            fbo::set_draw_buffers(&mut ctxt, target_id, &[gl::COLOR_ATTACHMENT0 + target_color]);
         }
 
-        // binding source framebuffer
-        if ctxt.state.read_framebuffer != source {
-            if ctxt.version >= &Version(Api::Gl, 3, 0) {
-                ctxt.gl.BindFramebuffer(gl::READ_FRAMEBUFFER, source);
-                ctxt.state.read_framebuffer = source;
+        for &(ref src_rect, ref target_rect) in rects {
+            blit_framebuffers(&mut ctxt, source_id, target_id, mask, src_rect, target_rect,
+                              filter);
+        }
 
-            } else {
-                ctxt.gl.BindFramebufferEXT(gl::READ_FRAMEBUFFER_EXT, source);
-                ctxt.state.read_framebuffer = source;
-            }
+        // restoring the state of the framebuffers
+        if redirect_color && source_id != 0 && source_color != 0 {
+            fbo::set_read_buffer(&mut ctxt, source_id, gl::COLOR_ATTACHMENT0);
+        }
+        if let (true, Some(target)) = (redirect_draw, target) {
+            fbo::set_draw_buffers(&mut ctxt, target_id, &target.get_draw_buffers());
         }
+    }
+}
+
+/// Calls `glBlitFramebuffer` between two framebuffer objects.
+unsafe fn blit_framebuffers(ctxt: &mut CommandContext, source: gl::types::GLuint,
+                            target: gl::types::GLuint, mask: gl::types::GLbitfield,
+                            src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
+{
+    // scissor testing influences blitting
+    if ctxt.state.enabled_scissor_test {
+        ctxt.gl.Disable(gl::SCISSOR_TEST);
+        ctxt.state.enabled_scissor_test = false;
+    }
+
+    // trying to do a named blit if possible
+    if ctxt.version >= &Version(Api::Gl, 4, 5) {
+        ctxt.gl.BlitNamedFramebuffer(source, target,
+            src_rect.left as gl::types::GLint,
+            src_rect.bottom as gl::types::GLint,
+            (src_rect.left + src_rect.width) as gl::types::GLint,
+            (src_rect.bottom + src_rect.height) as gl::types::GLint,
+            target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
+            (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
+            (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+
+        return;
+    }
 
-        // binding target framebuffer
-        if ctxt.state.draw_framebuffer != target {
-            if ctxt.version >= &Version(Api::Gl, 3, 0) {
-                ctxt.gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, target);
-                ctxt.state.draw_framebuffer = target;
+    // OpenGL ES 3 and `GL_NV_framebuffer_blit` use the core functions and enums
+    let core = ctxt.version >= &Version(Api::Gl, 3, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 0);
 
-            } else {
-                ctxt.gl.BindFramebufferEXT(gl::DRAW_FRAMEBUFFER_EXT, target);
-                ctxt.state.draw_framebuffer = target;
-            }
+    // binding source framebuffer
+    if ctxt.state.read_framebuffer != source {
+        if core || ctxt.extensions.gl_nv_framebuffer_blit {
+            ctxt.gl.BindFramebuffer(gl::READ_FRAMEBUFFER, source);
+            ctxt.state.read_framebuffer = source;
+
+        } else {
+            ctxt.gl.BindFramebufferEXT(gl::READ_FRAMEBUFFER_EXT, source);
+            ctxt.state.read_framebuffer = source;
         }
+    }
 
-        // doing the blit
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
-            ctxt.gl.BlitFramebuffer(src_rect.left as gl::types::GLint,
-                src_rect.bottom as gl::types::GLint,
-                (src_rect.left + src_rect.width) as gl::types::GLint,
-                (src_rect.bottom + src_rect.height) as gl::types::GLint,
-                target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
-                (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
-                (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+    // binding target framebuffer
+    if ctxt.state.draw_framebuffer != target {
+        if core || ctxt.extensions.gl_nv_framebuffer_blit {
+            ctxt.gl.BindFramebuffer(gl::DRAW_FRAMEBUFFER, target);
+            ctxt.state.draw_framebuffer = target;
 
         } else {
-            ctxt.gl.BlitFramebufferEXT(src_rect.left as gl::types::GLint,
-                src_rect.bottom as gl::types::GLint,
-                (src_rect.left + src_rect.width) as gl::types::GLint,
-                (src_rect.bottom + src_rect.height) as gl::types::GLint,
-                target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
-                (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
-                (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+            ctxt.gl.BindFramebufferEXT(gl::DRAW_FRAMEBUFFER_EXT, target);
+            ctxt.state.draw_framebuffer = target;
         }
     }
+
+    // doing the blit
+    if core {
+        ctxt.gl.BlitFramebuffer(src_rect.left as gl::types::GLint,
+            src_rect.bottom as gl::types::GLint,
+            (src_rect.left + src_rect.width) as gl::types::GLint,
+            (src_rect.bottom + src_rect.height) as gl::types::GLint,
+            target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
+            (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
+            (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+
+    } else if ctxt.extensions.gl_nv_framebuffer_blit {
+        ctxt.gl.BlitFramebufferNV(src_rect.left as gl::types::GLint,
+            src_rect.bottom as gl::types::GLint,
+            (src_rect.left + src_rect.width) as gl::types::GLint,
+            (src_rect.bottom + src_rect.height) as gl::types::GLint,
+            target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
+            (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
+            (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+
+    } else {
+        ctxt.gl.BlitFramebufferEXT(src_rect.left as gl::types::GLint,
+            src_rect.bottom as gl::types::GLint,
+            (src_rect.left + src_rect.width) as gl::types::GLint,
+            (src_rect.bottom + src_rect.height) as gl::types::GLint,
+            target_rect.left as gl::types::GLint, target_rect.bottom as gl::types::GLint,
+            (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
+            (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
+    }
 }
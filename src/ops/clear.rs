@@ -27,19 +27,22 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments>,
             ctxt.state.enabled_rasterizer_discard = false;
         }
 
-        if ctxt.state.color_mask != (1, 1, 1, 1) {
+        if ctxt.state.color_mask != (1, 1, 1, 1) || ctxt.state.color_mask_indexed {
             ctxt.state.color_mask = (1, 1, 1, 1);
+            ctxt.state.color_mask_indexed = false;
             ctxt.gl.ColorMask(1, 1, 1, 1);
         }
     
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
            ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
         {
-            if !color_srgb && !ctxt.state.enabled_framebuffer_srgb {
+            let convert = !color_srgb && (fbo_id != 0 || ctxt.default_framebuffer_srgb);
+
+            if convert && !ctxt.state.enabled_framebuffer_srgb {
                 ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
                 ctxt.state.enabled_framebuffer_srgb = true;
 
-            } else if color_srgb && ctxt.state.enabled_framebuffer_srgb {
+            } else if !convert && ctxt.state.enabled_framebuffer_srgb {
                 ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
                 ctxt.state.enabled_framebuffer_srgb = false;
             }
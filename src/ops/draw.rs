@@ -1,3 +1,4 @@
+use std::mem;
 use std::ptr;
 
 use BufferExt;
@@ -8,14 +9,17 @@ use UniformsExt;
 
 use context::Context;
 use ContextExt;
+use GlObject;
 use TransformFeedbackSessionExt;
 
 use fbo::{self, ValidatedAttachments};
 
+use buffer::BufferAnySlice;
+
 use uniforms::Uniforms;
 use {Program, ToGlEnum};
 use index::{self, IndicesSource};
-use vertex::{MultiVerticesSource, VerticesSource, TransformFeedbackSession};
+use vertex::{self, MultiVerticesSource, VerticesSource, TransformFeedbackSession};
 use vertex_array_object::VertexAttributesSystem;
 
 use draw_parameters::DrawParameters;
@@ -25,6 +29,12 @@ use version::Version;
 use version::Api;
 
 /// Draws everything.
+///
+/// Once the caches of the context are filled, this function doesn't perform any heap allocation
+/// unless an error is returned or persistent-mapped buffers are involved. This includes visiting
+/// the uniforms, checking them when the program uses strict uniforms, and building the
+/// attachments of a `MultiOutputFrameBuffer`. Errors that contain the name of a uniform or of
+/// an attribute allocate this name, but only once the error is detected.
 pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachments>,
                       vertex_buffers: V, indices: IndicesSource,
                       program: &Program, uniforms: &U, draw_parameters: &DrawParameters,
@@ -65,32 +75,111 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         },
     };
 
+    // mesh shaders can only be used with mesh tasks, and the other way around
+    match indices {
+        IndicesSource::MeshTasks { count, .. } => {
+            let max_count = match context.capabilities().max_draw_mesh_tasks_count {
+                Some(max_count) => max_count,
+                None => return Err(DrawError::MeshShadersNotSupported),
+            };
+
+            if !program.has_mesh_shader() {
+                return Err(DrawError::MeshShaderMismatch);
+            }
+
+            if count as i64 > max_count as i64 {
+                return Err(DrawError::TooManyMeshTasks);
+            }
+        },
+        _ => {
+            if program.has_mesh_shader() {
+                return Err(DrawError::MeshShaderMismatch);
+            }
+        },
+    };
+
+    // checking that the buffers of indirect commands contain the right kind of commands
+    match indices {
+        IndicesSource::MultidrawArray { ref buffer, .. } => {
+            try!(check_indirect_commands::<index::DrawArraysIndirectCommand>(buffer));
+        },
+        IndicesSource::MultidrawElement { ref commands, .. } => {
+            try!(check_indirect_commands::<index::DrawElementsIndirectCommand>(commands));
+        },
+        IndicesSource::MultidrawArrayCount { ref buffer, ref count, .. } => {
+            if !index::is_draw_count_supported(context) {
+                return Err(DrawError::DrawCountNotSupported);
+            }
+            try!(check_indirect_commands::<index::DrawArraysIndirectCommand>(buffer));
+            try!(check_indirect_commands::<u32>(count));
+        },
+        IndicesSource::MultidrawElementCount { ref commands, ref count, .. } => {
+            if !index::is_draw_count_supported(context) {
+                return Err(DrawError::DrawCountNotSupported);
+            }
+            try!(check_indirect_commands::<index::DrawElementsIndirectCommand>(commands));
+            try!(check_indirect_commands::<u32>(count));
+        },
+        _ => ()
+    };
+
+    if let Some(tf) = draw_parameters.transform_feedback {
+        if !tf.is_complete() {
+            return Err(DrawError::TransformFeedbackBufferMissing);
+        }
+    }
+
     // starting the state changes
     let mut ctxt = context.make_current();
 
+    // checking that drawing from a transform feedback counter is supported
+    if let IndicesSource::TransformFeedback { .. } = indices {
+        if !(ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.extensions.gl_arb_transform_feedback2) {
+            return Err(DrawError::TransformFeedbackCounterNotSupported);
+        }
+    }
+
     // handling vertices source
-    let (vertices_count, instances_count, base_vertex) = {
+    let (vertices_count, instances_count, base_vertex, base_instance) = {
         let index_buffer = match indices {
             IndicesSource::IndexBuffer { buffer, .. } => Some(buffer),
             IndicesSource::MultidrawArray { .. } => None,
             IndicesSource::MultidrawElement { indices, .. } => Some(indices),
+            IndicesSource::MultidrawArrayCount { .. } => None,
+            IndicesSource::MultidrawElementCount { indices, .. } => Some(indices),
             IndicesSource::NoIndices { .. } => None,
+            IndicesSource::TransformFeedback { .. } => None,
+            IndicesSource::MeshTasks { .. } => None,
         };
 
         // determining whether we can use the `base_vertex` variants for drawing
         let use_base_vertex = match indices {
             IndicesSource::MultidrawArray { .. } => false,
             IndicesSource::MultidrawElement { .. } => false,
+            IndicesSource::MultidrawArrayCount { .. } => false,
+            IndicesSource::MultidrawElementCount { .. } => false,
             IndicesSource::NoIndices { .. } => true,
+            IndicesSource::TransformFeedback { .. } => false,
+            IndicesSource::MeshTasks { .. } => false,
             _ => ctxt.version >= &Version(Api::Gl, 3, 2) ||
                  ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                  ctxt.extensions.gl_arb_draw_elements_base_vertex ||
                  ctxt.extensions.gl_oes_draw_elements_base_vertex
         };
 
+        // determining whether we can use the `base_instance` variants for drawing, so that
+        // per-instance buffers sliced at different offsets can share the same VAO
+        let use_base_instance = match indices {
+            IndicesSource::IndexBuffer { .. } | IndicesSource::NoIndices { .. } => {
+                ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                ctxt.extensions.gl_arb_base_instance || ctxt.extensions.gl_ext_base_instance
+            },
+            _ => false,
+        };
+
         // object that is used to build the bindings
         let mut binder = VertexAttributesSystem::start(&mut ctxt, program, index_buffer,
-                                                       use_base_vertex);
+                                                       use_base_vertex, use_base_instance);
         // number of vertices in the vertices sources, or `None` if there is a mismatch
         let mut vertices_count: Option<usize> = None;
         // number of instances to draw
@@ -153,7 +242,12 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
             }
         }
 
-        (vertices_count, instances_count, binder.bind().unwrap_or(0))
+        if instances_count.is_some() && !vertex::is_instancing_supported(context) {
+            return Err(DrawError::InstancingNotSupported);
+        }
+
+        let (base_vertex, base_instance) = try!(binder.bind());
+        (vertices_count, instances_count, base_vertex.unwrap_or(0), base_instance.unwrap_or(0))
     };
 
     // binding the FBO to draw upon
@@ -172,11 +266,10 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         sync_vertices_per_patch(&mut ctxt, vertices_per_patch);
 
         // TODO: make sure that the program is the right one
-        // TODO: changing the current transform feedback requires pausing/unbinding before changing the program
         if let Some(ref tf) = draw_parameters.transform_feedback {
             tf.bind(&mut ctxt, indices.get_primitives_type());
         } else {
-            TransformFeedbackSession::unbind(&mut ctxt);
+            TransformFeedbackSession::suspend(&mut ctxt);
         }
     }
 
@@ -194,7 +287,35 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
                 unsafe {
                     if let Some(instances_count) = instances_count {
-                        if base_vertex != 0 {
+                        if base_instance != 0 {
+                            if ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                               ctxt.extensions.gl_arb_base_instance
+                            {
+                                ctxt.gl.DrawElementsInstancedBaseVertexBaseInstance(
+                                                                    primitives.to_glenum(),
+                                                                    buffer.get_elements_count() as
+                                                                        gl::types::GLsizei,
+                                                                    data_type.to_glenum(),
+                                                                    ptr as *const _,
+                                                                    instances_count as
+                                                                        gl::types::GLsizei,
+                                                                    base_vertex, base_instance);
+
+                            } else if ctxt.extensions.gl_ext_base_instance {
+                                ctxt.gl.DrawElementsInstancedBaseVertexBaseInstanceEXT(
+                                                                    primitives.to_glenum(),
+                                                                    buffer.get_elements_count() as
+                                                                        gl::types::GLsizei,
+                                                                    data_type.to_glenum(),
+                                                                    ptr as *const _,
+                                                                    instances_count as
+                                                                        gl::types::GLsizei,
+                                                                    base_vertex, base_instance);
+                            } else {
+                                unreachable!();
+                            }
+
+                        } else if base_vertex != 0 {
                             if ctxt.version >= &Version(Api::Gl, 3, 2) ||
                                ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                                ctxt.extensions.gl_arb_draw_elements_base_vertex
@@ -221,13 +342,36 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                                 unreachable!();
                             }
 
-                        } else {
+                        } else if ctxt.version >= &Version(Api::Gl, 1, 0) ||
+                                  ctxt.version >= &Version(Api::GlEs, 3, 0)
+                        {
                             ctxt.gl.DrawElementsInstanced(primitives.to_glenum(),
                                                           buffer.get_elements_count() as
                                                           gl::types::GLsizei,
                                                           data_type.to_glenum(),
                                                           ptr as *const _,
                                                           instances_count as gl::types::GLsizei);
+
+                        } else if ctxt.extensions.gl_ext_instanced_arrays {
+                            ctxt.gl.DrawElementsInstancedEXT(primitives.to_glenum(),
+                                                             buffer.get_elements_count() as
+                                                             gl::types::GLsizei,
+                                                             data_type.to_glenum(),
+                                                             ptr as *const _,
+                                                             instances_count as
+                                                             gl::types::GLsizei);
+
+                        } else if ctxt.extensions.gl_angle_instanced_arrays {
+                            ctxt.gl.DrawElementsInstancedANGLE(primitives.to_glenum(),
+                                                               buffer.get_elements_count() as
+                                                               gl::types::GLsizei,
+                                                               data_type.to_glenum(),
+                                                               ptr as *const _,
+                                                               instances_count as
+                                                               gl::types::GLsizei);
+
+                        } else {
+                            unreachable!();
                         }
 
                     } else {
@@ -304,6 +448,65 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                 }
             },
 
+            &IndicesSource::MultidrawArrayCount { ref buffer, ref count, primitives } => {
+                let ptr: *const u8 = ptr::null_mut();
+                let ptr = unsafe { ptr.offset(buffer.get_offset_bytes() as isize) };
+
+                debug_assert_eq!(base_vertex, 0);       // enforced earlier in this function
+
+                if let Some(fence) = buffer.add_fence() {
+                    fences.push(fence);
+                }
+
+                if let Some(fence) = count.add_fence() {
+                    fences.push(fence);
+                }
+
+                unsafe {
+                    buffer.prepare_and_bind_for_draw_indirect(&mut ctxt);
+                    count.prepare_and_bind_for_draw_parameters(&mut ctxt);
+                    ctxt.gl.MultiDrawArraysIndirectCountARB(primitives.to_glenum(),
+                                                            ptr as *const _,
+                                                            count.get_offset_bytes() as
+                                                                gl::types::GLintptr,
+                                                            buffer.get_elements_count() as
+                                                                gl::types::GLsizei,
+                                                            0);
+                }
+            },
+
+            &IndicesSource::MultidrawElementCount { ref commands, ref indices, ref count,
+                                                    data_type, primitives } => {
+                let cmd_ptr: *const u8 = ptr::null_mut();
+                let cmd_ptr = unsafe { cmd_ptr.offset(commands.get_offset_bytes() as isize) };
+
+                if let Some(fence) = commands.add_fence() {
+                    fences.push(fence);
+                }
+
+                if let Some(fence) = indices.add_fence() {
+                    fences.push(fence);
+                }
+
+                if let Some(fence) = count.add_fence() {
+                    fences.push(fence);
+                }
+
+                unsafe {
+                    commands.prepare_and_bind_for_draw_indirect(&mut ctxt);
+                    count.prepare_and_bind_for_draw_parameters(&mut ctxt);
+                    debug_assert_eq!(base_vertex, 0);       // enforced earlier in this function
+                    ctxt.gl.MultiDrawElementsIndirectCountARB(primitives.to_glenum(),
+                                                              data_type.to_glenum(),
+                                                              cmd_ptr as *const _,
+                                                              count.get_offset_bytes() as
+                                                                  gl::types::GLintptr,
+                                                              commands.get_elements_count() as
+                                                                  gl::types::GLsizei,
+                                                              0);
+                }
+            },
+
             &IndicesSource::NoIndices { primitives } => {
                 let vertices_count = match vertices_count {
                     Some(c) => c,
@@ -312,18 +515,87 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
                 unsafe {
                     if let Some(instances_count) = instances_count {
-                        ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), base_vertex,
-                                                    vertices_count as gl::types::GLsizei,
-                                                    instances_count as gl::types::GLsizei);
+                        if base_instance != 0 {
+                            if ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                               ctxt.extensions.gl_arb_base_instance
+                            {
+                                ctxt.gl.DrawArraysInstancedBaseInstance(primitives.to_glenum(),
+                                                                base_vertex,
+                                                                vertices_count as
+                                                                    gl::types::GLsizei,
+                                                                instances_count as
+                                                                    gl::types::GLsizei,
+                                                                base_instance);
+
+                            } else if ctxt.extensions.gl_ext_base_instance {
+                                ctxt.gl.DrawArraysInstancedBaseInstanceEXT(primitives.to_glenum(),
+                                                                base_vertex,
+                                                                vertices_count as
+                                                                    gl::types::GLsizei,
+                                                                instances_count as
+                                                                    gl::types::GLsizei,
+                                                                base_instance);
+                            } else {
+                                unreachable!();
+                            }
+
+                        } else if ctxt.version >= &Version(Api::Gl, 1, 0) ||
+                                  ctxt.version >= &Version(Api::GlEs, 3, 0)
+                        {
+                            ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), base_vertex,
+                                                        vertices_count as gl::types::GLsizei,
+                                                        instances_count as gl::types::GLsizei);
+
+                        } else if ctxt.extensions.gl_ext_instanced_arrays {
+                            ctxt.gl.DrawArraysInstancedEXT(primitives.to_glenum(), base_vertex,
+                                                           vertices_count as gl::types::GLsizei,
+                                                           instances_count as gl::types::GLsizei);
+
+                        } else if ctxt.extensions.gl_angle_instanced_arrays {
+                            ctxt.gl.DrawArraysInstancedANGLE(primitives.to_glenum(), base_vertex,
+                                                             vertices_count as gl::types::GLsizei,
+                                                             instances_count as gl::types::GLsizei);
+
+                        } else {
+                            unreachable!();
+                        }
                     } else {
                         ctxt.gl.DrawArrays(primitives.to_glenum(), base_vertex,
                                            vertices_count as gl::types::GLsizei);
                     }
                 }
             },
+
+            &IndicesSource::TransformFeedback { counter, primitives } => {
+                if instances_count.is_some() &&
+                   !(ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                     ctxt.extensions.gl_arb_transform_feedback_instanced)
+                {
+                    return Err(DrawError::TransformFeedbackCounterNotSupported);
+                }
+
+                unsafe {
+                    if let Some(instances_count) = instances_count {
+                        ctxt.gl.DrawTransformFeedbackInstanced(primitives.to_glenum(),
+                                                               counter.get_id(),
+                                                               instances_count as
+                                                                        gl::types::GLsizei);
+                    } else {
+                        ctxt.gl.DrawTransformFeedback(primitives.to_glenum(), counter.get_id());
+                    }
+                }
+            },
+
+            &IndicesSource::MeshTasks { first, count } => {
+                unsafe {
+                    ctxt.gl.DrawMeshTasksNV(first as gl::types::GLuint,
+                                            count as gl::types::GLuint);
+                }
+            },
         };
     };
 
+    context::check_gl_error(&mut ctxt, "Drawing");
     ctxt.state.next_draw_call_id += 1;
 
     // fulfilling the fences
@@ -343,3 +615,28 @@ unsafe fn sync_vertices_per_patch(ctxt: &mut context::CommandContext, vertices_p
         }
     }
 }
+
+/// Checks that a buffer of indirect commands contains whole commands of type `C` and is
+/// correctly aligned.
+///
+/// The elements of the buffer can either be commands or smaller values that commands are made
+/// of, like the `u32`s of commands that are packed by hand.
+fn check_indirect_commands<C>(commands: &BufferAnySlice) -> Result<(), DrawError> {
+    let expected = mem::size_of::<C>();
+    let elements_size = commands.get_elements_size();
+
+    if elements_size == 0 || expected % elements_size != 0 ||
+       commands.get_size() % expected != 0
+    {
+        return Err(DrawError::IndirectCommandsStrideMismatch {
+            expected: expected,
+            obtained: elements_size,
+        });
+    }
+
+    if commands.get_offset_bytes() % 4 != 0 {
+        return Err(DrawError::IndirectCommandsMisaligned);
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-pub use self::blit::blit;
+pub use self::blit::{blit, blit_attachments};
 pub use self::clear::clear;
 pub use self::draw::draw;
 pub use self::read::{read, ReadError, Source, Destination};
@@ -5,6 +5,7 @@ use std::error::Error;
 use pixel_buffer::PixelBuffer;
 use texture::ClientFormat;
 use texture::PixelValue;
+use texture::TextureKind;
 use image_format::{TextureFormatRequest, TextureFormat};
 
 use fbo;
@@ -60,8 +61,9 @@ impl<'a, P> From<&'a PixelBuffer<P>> for Destination<'a, P> where P: PixelValue
 pub enum ReadError {
     /// The implementation doesn't support converting to the requested output format.
     ///
-    /// OpenGL supports every possible format, but OpenGL ES only supports `(u8, u8, u8, u8)` and
-    /// an implementation-defined format.
+    /// OpenGL supports every possible format, except two-components formats before OpenGL 3.
+    /// OpenGL ES only supports `(u8, u8, u8, u8)` and an implementation-defined format that
+    /// depends on the internal format of the source.
     OutputFormatNotSupported,
 
     /// The implementation doesn't support reading a depth, depth-stencil or stencil attachment.
@@ -115,11 +117,12 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     let pixels_to_read = rect.width * rect.height;
 
     // checking that the output format is supported
-    // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 plus an additional
-    // implementation-defined format
-    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 {
-        // TODO: GLES is guaranteed to support GL_RGBA and an implementation-defined format
-        //       queried with GL_IMPLEMENTATION_COLOR_READ_FORMAT. We only handle GL_RGBA.
+    // OpenGL supports everything except `GL_RG` before OpenGL 3, while OpenGL ES only supports
+    // U8U8U8U8 plus an additional implementation-defined format, which is checked once the
+    // framebuffer is bound
+    if ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.version < &Version(Api::Gl, 3, 0) &&
+       !ctxt.extensions.gl_arb_texture_rg && output_pixel_format.get_num_components() == 2
+    {
         return Err(ReadError::OutputFormatNotSupported);
     }
 
@@ -154,9 +157,18 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     };
 
     // determining what kind of data we are reading
+    #[derive(PartialEq, Eq)]
     enum ReadSourceType { Color, Depth, Stencil, DepthStencil }
     let (integer, read_src_type) = match source {
         Source::Attachment(attachment) => {
+            let read_src_type = match attachment.kind() {
+                TextureKind::Float | TextureKind::Integral |
+                TextureKind::Unsigned => ReadSourceType::Color,
+                TextureKind::Depth => ReadSourceType::Depth,
+                TextureKind::Stencil => ReadSourceType::Stencil,
+                TextureKind::DepthStencil => ReadSourceType::DepthStencil,
+            };
+
             match attachment {
                 &fbo::RegularAttachment::Texture(ref tex) => {
                     let integer = match tex.get_texture().get_requested_format() {
@@ -167,15 +179,15 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
                         _ => false,
                     };
 
-                    (integer, ReadSourceType::Color)       // FIXME: wrong
+                    (integer, read_src_type)
                 },
                 &fbo::RegularAttachment::RenderBuffer(ref rb) => {
-                    (false, ReadSourceType::Color)       // FIXME: wrong
+                    (false, read_src_type)       // FIXME: wrong
                 },
             }
         },
         Source::DefaultFramebuffer(read_buffer) => {
-            (false, ReadSourceType::Color)       // FIXME: wrong
+            (false, ReadSourceType::Color)
         },
     };
 
@@ -200,18 +212,45 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
         ReadSourceType::Color => {
             client_format_to_gl_enum(&output_pixel_format, integer)
         },
-        ReadSourceType::Depth => {
-            unimplemented!()        // TODO:
-            // TODO: NV_depth_buffer_float2
-            //(gl::DEPTH_COMPONENT, )
+        // only the depth component of depth-stencil attachments is read, as there's no client
+        // format that corresponds to the packed depth-stencil formats
+        // TODO: NV_depth_buffer_float2
+        ReadSourceType::Depth | ReadSourceType::DepthStencil => {
+            match output_pixel_format {
+                ClientFormat::F32 => (gl::DEPTH_COMPONENT, gl::FLOAT),
+                ClientFormat::U32 => (gl::DEPTH_COMPONENT, gl::UNSIGNED_INT),
+                ClientFormat::U16 => (gl::DEPTH_COMPONENT, gl::UNSIGNED_SHORT),
+                _ => return Err(ReadError::OutputFormatNotSupported),
+            }
         },
-        ReadSourceType::DepthStencil => unimplemented!(),        // FIXME: only 24_8 is possible and there's no client format in the enum that corresponds to 24_8
         ReadSourceType::Stencil => {
-            unimplemented!()        // TODO:
-            //(gl::STENCIL_INDEX, )
+            match output_pixel_format {
+                ClientFormat::U8 => (gl::STENCIL_INDEX, gl::UNSIGNED_BYTE),
+                ClientFormat::U16 => (gl::STENCIL_INDEX, gl::UNSIGNED_SHORT),
+                ClientFormat::U32 => (gl::STENCIL_INDEX, gl::UNSIGNED_INT),
+                _ => return Err(ReadError::OutputFormatNotSupported),
+            }
         },
     };
 
+    // OpenGL ES only supports `GL_RGBA` with `GL_UNSIGNED_BYTE` plus the format and type of the
+    // framebuffer that is bound for reading, which usually match its internal format
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) && output_pixel_format != ClientFormat::U8U8U8U8 &&
+       read_src_type == ReadSourceType::Color
+    {
+        let (implementation_format, implementation_type) = unsafe {
+            let mut format = 0;
+            let mut ty = 0;
+            ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_FORMAT, &mut format);
+            ctxt.gl.GetIntegerv(gl::IMPLEMENTATION_COLOR_READ_TYPE, &mut ty);
+            (format as gl::types::GLenum, ty as gl::types::GLenum)
+        };
+
+        if format != implementation_format || gltype != implementation_type {
+            return Err(ReadError::OutputFormatNotSupported);
+        }
+    }
+
     // reading
     unsafe {
         match dest {
@@ -220,17 +259,9 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
 
                 BufferAny::unbind_pixel_pack(ctxt);
 
-                // adjusting data alignement
-                let ptr = buf.as_mut_ptr() as *mut D;
-                let ptr = ptr as usize;
-                if (ptr % 8) == 0 {
-                } else if (ptr % 4) == 0 && ctxt.state.pixel_store_pack_alignment != 4 {
-                    ctxt.state.pixel_store_pack_alignment = 4;
-                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 4);
-                } else if (ptr % 2) == 0 && ctxt.state.pixel_store_pack_alignment > 2 {
-                    ctxt.state.pixel_store_pack_alignment = 2;
-                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 2);
-                } else if ctxt.state.pixel_store_pack_alignment != 1 {
+                // the rows are tightly packed in `buf` ; the alignment of the pointer doesn't
+                // matter, only the alignment of each row
+                if ctxt.state.pixel_store_pack_alignment != 1 {
                     ctxt.state.pixel_store_pack_alignment = 1;
                     ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
                 }
@@ -247,6 +278,11 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
             Destination::PixelBuffer(pixel_buffer) => {
                 assert!(pixel_buffer.len() >= pixels_to_read as usize);
 
+                if ctxt.state.pixel_store_pack_alignment != 1 {
+                    ctxt.state.pixel_store_pack_alignment = 1;
+                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
+                }
+
                 pixel_buffer.prepare_and_bind_for_pixel_pack(&mut ctxt);
                 ctxt.gl.ReadPixels(rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
                                    rect.width as gl::types::GLsizei,
@@ -293,7 +329,7 @@ fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
         ClientFormat::U5U6U5 => (gl::RGB, gl::UNSIGNED_SHORT_5_6_5),
         ClientFormat::U4U4U4U4 => (gl::RGBA, gl::UNSIGNED_SHORT_4_4_4_4),
         ClientFormat::U5U5U5U1 => (gl::RGBA, gl::UNSIGNED_SHORT_5_5_5_1),
-        ClientFormat::U10U10U10U2 => (gl::RGBA, gl::UNSIGNED_INT_10_10_10_2),
+        ClientFormat::U10U10U10U2 => (gl::RGBA, gl::UNSIGNED_INT_2_10_10_10_REV),
         ClientFormat::F16 => (gl::RED, gl::HALF_FLOAT),
         ClientFormat::F16F16 => (gl::RG, gl::HALF_FLOAT),
         ClientFormat::F16F16F16 => (gl::RGB, gl::HALF_FLOAT),
@@ -302,6 +338,8 @@ fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
         ClientFormat::F32F32 => (gl::RG, gl::FLOAT),
         ClientFormat::F32F32F32 => (gl::RGB, gl::FLOAT),
         ClientFormat::F32F32F32F32 => (gl::RGBA, gl::FLOAT),
+        ClientFormat::F11F11F10 => (gl::RGB, gl::UNSIGNED_INT_10F_11F_11F_REV),
+        ClientFormat::F9F9F9 => (gl::RGB, gl::UNSIGNED_INT_5_9_9_9_REV),
     };
 
     let format = if integer {
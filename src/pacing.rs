@@ -0,0 +1,142 @@
+/*!
+Controlling the rate at which frames are presented.
+
+The swap interval is the number of vertical blanks to wait for before the buffers are swapped.
+It is usually chosen when the context is created, but it can be changed at any time with
+`Context::set_swap_interval`.
+
+```no_run
+# extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+use glium::pacing::{FrameLimiter, SwapInterval};
+
+// synchronize with the screen, but don't wait if a frame is late
+if display.set_swap_interval(SwapInterval::AdaptiveWait(1)).is_err() {
+    display.set_swap_interval(SwapInterval::Wait(1)).ok();
+}
+
+// never go above 60 frames per second, even if vsync isn't available
+let mut limiter = FrameLimiter::new(60);
+
+loop {
+    let frame = display.draw();
+    // ...
+    frame.finish().unwrap();
+    limiter.wait();
+# break;
+}
+# }
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Number of vertical blanks to wait for when swapping buffers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SwapInterval {
+    /// Swap the buffers immediately. Disables vsync.
+    DontWait,
+
+    /// Wait for the given number of vertical blanks before swapping the buffers.
+    Wait(u32),
+
+    /// Wait for the given number of vertical blanks, but swap immediately if the frame is late
+    /// instead of waiting for the next one.
+    ///
+    /// Requires the `EXT_swap_control_tear` extension of the windowing system.
+    AdaptiveWait(u32),
+}
+
+/// Error that can happen when changing the swap interval.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SwapIntervalError {
+    /// The backend doesn't allow changing the swap interval.
+    NotSupported,
+
+    /// The backend doesn't support adaptive vsync.
+    AdaptiveNotSupported,
+}
+
+impl Error for SwapIntervalError {
+    fn description(&self) -> &str {
+        use self::SwapIntervalError::*;
+        match *self {
+            NotSupported =>
+                "the backend doesn't allow changing the swap interval",
+            AdaptiveNotSupported =>
+                "the backend doesn't support adaptive vsync",
+        }
+    }
+}
+
+impl fmt::Display for SwapIntervalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Limits the number of frames per second by sleeping on the CPU.
+///
+/// Call `wait` once per frame, usually after swapping the buffers. If the previous frame was
+/// shorter than the target duration, the current thread sleeps for the remaining time.
+#[derive(Debug, Clone)]
+pub struct FrameLimiter {
+    frame_duration: Duration,
+    next_frame: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// Builds a limiter that doesn't allow more than `max_fps` frames per second.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `max_fps` is 0.
+    #[inline]
+    pub fn new(max_fps: u32) -> FrameLimiter {
+        assert!(max_fps >= 1);
+        FrameLimiter::with_frame_duration(Duration::new(0, 1_000_000_000 / max_fps))
+    }
+
+    /// Builds a limiter that ensures that frames last at least `frame_duration`.
+    #[inline]
+    pub fn with_frame_duration(frame_duration: Duration) -> FrameLimiter {
+        FrameLimiter {
+            frame_duration: frame_duration,
+            next_frame: None,
+        }
+    }
+
+    /// Returns the minimum duration of a frame.
+    #[inline]
+    pub fn get_frame_duration(&self) -> Duration {
+        self.frame_duration
+    }
+
+    /// Changes the minimum duration of a frame. Takes effect after the next call to `wait`.
+    #[inline]
+    pub fn set_frame_duration(&mut self, frame_duration: Duration) {
+        self.frame_duration = frame_duration;
+    }
+
+    /// Sleeps until the current frame has lasted at least the frame duration.
+    ///
+    /// If the frame was too long, returns immediately. The time lost isn't caught up on by
+    /// shortening the next frames.
+    pub fn wait(&mut self) {
+        let now = Instant::now();
+
+        let frame_start = match self.next_frame {
+            Some(next_frame) if next_frame > now => {
+                thread::sleep(next_frame - now);
+                next_frame
+            },
+            _ => now,
+        };
+
+        self.next_frame = Some(frame_start + self.frame_duration);
+    }
+}
@@ -0,0 +1,446 @@
+/*!
+Particles that are simulated and drawn entirely on the GPU.
+
+A `ParticleSystem` stores its particles in shader storage buffers. Each call to `update` runs a
+compute shader that moves the living particles, removes the dead ones and appends the particles
+that were passed to `emit`. The surviving particles are counted with an atomic counter that is
+the `count` field of an indirect draw command, so that `draw` draws exactly the right number of
+points without the number ever being read back on the CPU.
+
+The particles are double-buffered: the compute shader reads the particles of the previous update
+and writes the new ones in the other buffer. The memory barriers between the compute shader, the
+indirect draw and the vertex shader that reads the particles are inserted by glium.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let view_projection: [[f32; 4]; 4] = unsafe { std::mem::uninitialized() };
+use glium::Surface;
+use glium::particles::{Particle, ParticleSystem};
+
+let mut particles = ParticleSystem::new(&display, 100000).unwrap();
+
+// each frame
+particles.emit(&[Particle::new([0.0, 0.0, 0.0], [0.0, 5.0, 0.0], 2.0)]);
+particles.update(0.016, [0.0, -9.81, 0.0]);
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+particles.draw(&mut frame, view_projection, [1.0, 0.5, 0.0, 1.0], &Default::default()).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+## Custom rendering
+
+The buffer that contains the particles is returned by `particles()` and can be bound to a
+shader storage block of your own program. `indices()` returns the indirect draw command, which
+draws one point per living particle ; the vertex shader retrieves its particle with
+`gl_VertexID`.
+
+```glsl
+struct Particle {
+    vec4 position;
+    vec4 velocity;
+};
+
+buffer Particles {
+    Particle particles[];
+};
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use buffer::{Buffer, BufferCreationError, BufferMode, BufferType};
+use index::{DrawCommandNoIndices, DrawCommandsNoIndicesBuffer, IndicesSource, PrimitiveType};
+use program::{ComputeShader, Program, ProgramCreationError};
+use vertex::EmptyVertexAttributes;
+
+use DrawError;
+use DrawParameters;
+use Surface;
+
+/// Number of invocations in a work group of the update shader.
+const WORK_GROUP_SIZE: u32 = 64;
+
+/// Error that can happen while creating a `ParticleSystem`.
+#[derive(Clone, Debug)]
+pub enum ParticleSystemCreationError {
+    /// The backend doesn't support compute shaders.
+    ComputeShaderNotSupported,
+
+    /// Failed to compile the compute shader or the program that draws the particles.
+    ProgramCreationError(ProgramCreationError),
+
+    /// Failed to create the buffers.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for ParticleSystemCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ParticleSystemCreationError {
+    fn description(&self) -> &str {
+        use self::ParticleSystemCreationError::*;
+        match *self {
+            ComputeShaderNotSupported =>
+                "The backend doesn't support compute shaders",
+            ProgramCreationError(_) =>
+                "Failed to compile the compute shader or the program that draws the particles",
+            BufferCreationError(_) =>
+                "Failed to create the buffers",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::ParticleSystemCreationError::*;
+        match *self {
+            ComputeShaderNotSupported => None,
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramCreationError> for ParticleSystemCreationError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> ParticleSystemCreationError {
+        ParticleSystemCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for ParticleSystemCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> ParticleSystemCreationError {
+        ParticleSystemCreationError::BufferCreationError(err)
+    }
+}
+
+/// A particle, as stored in the shader storage buffers.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Particle {
+    /// Position of the particle. The fourth component is the remaining lifetime in seconds. The
+    /// particle is removed when it reaches zero.
+    pub position: [f32; 4],
+
+    /// Velocity of the particle, in units per second. The fourth component is free to be used
+    /// by custom shaders.
+    pub velocity: [f32; 4],
+}
+
+implement_uniform_block!(Particle, position, velocity);
+
+impl Particle {
+    /// Builds a particle from its position, velocity and lifetime in seconds.
+    #[inline]
+    pub fn new(position: [f32; 3], velocity: [f32; 3], lifetime: f32) -> Particle {
+        Particle {
+            position: [position[0], position[1], position[2], lifetime],
+            velocity: [velocity[0], velocity[1], velocity[2], 0.0],
+        }
+    }
+}
+
+/// Particles that are updated with a compute shader and drawn with an indirect draw command.
+pub struct ParticleSystem {
+    update_shader: ComputeShader,
+    program: Program,
+    capacity: usize,
+
+    // the particles and the draw command that counts them, indexed by `current` or `1 - current`
+    particles: [Buffer<[Particle]>; 2],
+    commands: [DrawCommandsNoIndicesBuffer; 2],
+
+    // index of the buffers that contain the result of the latest update
+    current: usize,
+
+    // particles added by `emit` since the latest update
+    spawned: Buffer<[Particle]>,
+    spawned_count: usize,
+}
+
+impl ParticleSystem {
+    /// Builds a new `ParticleSystem` that can hold up to `capacity` particles.
+    ///
+    /// Returns `ComputeShaderNotSupported` if the backend doesn't support compute shaders and
+    /// shader storage buffers.
+    pub fn new<F: ?Sized>(facade: &F, capacity: usize)
+                          -> Result<ParticleSystem, ParticleSystemCreationError>
+                          where F: Facade
+    {
+        if !ComputeShader::is_supported(facade.get_context()) {
+            return Err(ParticleSystemCreationError::ComputeShaderNotSupported);
+        }
+
+        let update_shader = try!(ComputeShader::from_source(facade, "
+            #version 430
+
+            layout(local_size_x = 64) in;
+
+            struct Particle {
+                vec4 position;
+                vec4 velocity;
+            };
+
+            struct DrawCommand {
+                uint count;
+                uint instance_count;
+                uint first_index;
+                uint base_instance;
+            };
+
+            buffer SourceParticles {
+                Particle source_particles[];
+            };
+
+            buffer SpawnedParticles {
+                Particle spawned_particles[];
+            };
+
+            buffer DestinationParticles {
+                Particle destination_particles[];
+            };
+
+            buffer SourceCommand {
+                DrawCommand source_command[];
+            };
+
+            buffer DestinationCommand {
+                DrawCommand destination_command[];
+            };
+
+            uniform uint capacity;
+            uniform uint spawned_count;
+            uniform float delta;
+            uniform vec3 gravity;
+
+            void main() {
+                uint id = gl_GlobalInvocationID.x;
+                uint source_count = source_command[0].count;
+                Particle particle;
+
+                if (id < source_count) {
+                    particle = source_particles[id];
+                    particle.velocity.xyz += gravity * delta;
+                    particle.position.xyz += particle.velocity.xyz * delta;
+                    particle.position.w -= delta;
+
+                    if (particle.position.w <= 0.0) {
+                        return;
+                    }
+
+                } else if (id - source_count < spawned_count) {
+                    particle = spawned_particles[id - source_count];
+
+                } else {
+                    return;
+                }
+
+                // the counter never goes back below `capacity` once it has reached it, so the
+                // slots that are handed out are unique
+                uint slot = atomicAdd(destination_command[0].count, 1u);
+                if (slot >= capacity) {
+                    atomicAdd(destination_command[0].count, 0xffffffffu);
+                    return;
+                }
+
+                destination_particles[slot] = particle;
+            }
+        "));
+
+        let program = try!(Program::from_source(facade,
+            "
+                #version 430
+
+                struct Particle {
+                    vec4 position;
+                    vec4 velocity;
+                };
+
+                buffer Particles {
+                    Particle particles[];
+                };
+
+                uniform mat4 matrix;
+
+                void main() {
+                    gl_Position = matrix * vec4(particles[gl_VertexID].position.xyz, 1.0);
+                }
+            ",
+            "
+                #version 430
+
+                uniform vec4 color;
+
+                out vec4 f_color;
+
+                void main() {
+                    f_color = color;
+                }
+            ",
+            None));
+
+        let particles = [
+            try!(Buffer::empty_array(facade, BufferType::ShaderStorageBuffer, capacity,
+                                     BufferMode::Default)),
+            try!(Buffer::empty_array(facade, BufferType::ShaderStorageBuffer, capacity,
+                                     BufferMode::Default)),
+        ];
+
+        let commands = [
+            try!(DrawCommandsNoIndicesBuffer::empty(facade, 1)),
+            try!(DrawCommandsNoIndicesBuffer::empty(facade, 1)),
+        ];
+
+        for command in commands.iter() {
+            command.write(&[empty_command()]);
+        }
+
+        let spawned = try!(Buffer::empty_array(facade, BufferType::ShaderStorageBuffer, capacity,
+                                               BufferMode::Dynamic));
+
+        Ok(ParticleSystem {
+            update_shader: update_shader,
+            program: program,
+            capacity: capacity,
+            particles: particles,
+            commands: commands,
+            current: 0,
+            spawned: spawned,
+            spawned_count: 0,
+        })
+    }
+
+    /// Returns the maximum number of particles.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds particles. They are appended to the living particles during the next `update`.
+    ///
+    /// Particles beyond `capacity` particles per update are ignored, and so are the ones that
+    /// don't fit in the system once the existing particles have been updated.
+    pub fn emit(&mut self, particles: &[Particle]) {
+        let len = if self.spawned_count + particles.len() > self.capacity {
+            self.capacity - self.spawned_count
+        } else {
+            particles.len()
+        };
+
+        if len == 0 {
+            return;
+        }
+
+        let start = self.spawned_count;
+        self.spawned.slice(start .. start + len).unwrap().write(&particles[.. len]);
+        self.spawned_count += len;
+    }
+
+    /// Moves the particles by `delta` seconds while accelerating them by `gravity`, removes the
+    /// particles whose lifetime is over, and adds the particles that were emitted since the
+    /// previous update.
+    ///
+    /// This only dispatches a compute shader and never waits for the GPU.
+    pub fn update(&mut self, delta: f32, gravity: [f32; 3]) {
+        let source = self.current;
+        let destination = 1 - self.current;
+
+        // the compute shader increments the `count` field of this command for each particle
+        // that it writes
+        self.commands[destination].write(&[empty_command()]);
+
+        let uniforms = uniform! {
+            SourceParticles: &self.particles[source],
+            SpawnedParticles: &self.spawned,
+            DestinationParticles: &self.particles[destination],
+            SourceCommand: &*self.commands[source],
+            DestinationCommand: &*self.commands[destination],
+            capacity: self.capacity as u32,
+            spawned_count: self.spawned_count as u32,
+            delta: delta,
+            gravity: gravity,
+        };
+
+        // one invocation per particle that may be alive, plus one per spawned particle
+        let invocations = (self.capacity + self.spawned_count) as u32;
+        let groups = (invocations + WORK_GROUP_SIZE - 1) / WORK_GROUP_SIZE;
+        self.update_shader.execute(uniforms, groups, 1, 1);
+
+        self.current = destination;
+        self.spawned_count = 0;
+    }
+
+    /// Draws each particle as a point of the given color. `matrix` transforms the positions of
+    /// the particles to clip space.
+    ///
+    /// The size of the points can be chosen with the `point_size` member of the parameters.
+    pub fn draw<S: ?Sized>(&self, surface: &mut S, matrix: [[f32; 4]; 4], color: [f32; 4],
+                           parameters: &DrawParameters) -> Result<(), DrawError>
+                           where S: Surface
+    {
+        let uniforms = uniform! {
+            Particles: self.particles(),
+            matrix: matrix,
+            color: color,
+        };
+
+        surface.draw(EmptyVertexAttributes { len: self.capacity }, self.indices(), &self.program,
+                     &uniforms, parameters)
+    }
+
+    /// Returns the buffer that contains the particles of the latest update.
+    ///
+    /// Only the first particles are alive. Their number is the `count` field of the command
+    /// returned by `indices`.
+    #[inline]
+    pub fn particles(&self) -> &Buffer<[Particle]> {
+        &self.particles[self.current]
+    }
+
+    /// Returns an indirect draw command that draws one point per living particle.
+    ///
+    /// Pass `EmptyVertexAttributes { len: capacity }` as the vertex source.
+    #[inline]
+    pub fn indices(&self) -> IndicesSource {
+        self.commands[self.current].with_primitive_type(PrimitiveType::Points)
+    }
+
+    /// Reads the number of living particles back from the GPU.
+    ///
+    /// This waits for the latest update to finish and should only be used for debugging.
+    pub fn read_count(&self) -> usize {
+        self.commands[self.current].count_slice(0).unwrap().read().unwrap() as usize
+    }
+
+    /// Removes all the particles, including the ones that haven't been added yet.
+    pub fn clear(&mut self) {
+        self.commands[self.current].write(&[empty_command()]);
+        self.spawned_count = 0;
+    }
+}
+
+impl fmt::Debug for ParticleSystem {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ParticleSystem(capacity: {})", self.capacity)
+    }
+}
+
+/// Draw command that draws no particle.
+#[inline]
+fn empty_command() -> DrawCommandNoIndices {
+    DrawCommandNoIndices {
+        count: 0,
+        instance_count: 1,
+        first_index: 0,
+        base_instance: 0,
+    }
+}
@@ -0,0 +1,299 @@
+/*!
+Finding which object is under a given pixel by rendering object identifiers.
+
+The objects are drawn a second time into an unsigned integer texture, with a shader that writes
+the identifier of each object instead of a color. Reading back the pixel under the cursor then
+gives the identifier of the object that is visible there. The identifier `0` is reserved and
+means that there is no object.
+
+The `Picker` owns the identifier texture, its depth buffer and a one-pixel pixel buffer. Reading
+the identifier is asynchronous: `pick` returns the result of the latest read that has finished,
+which usually comes from the previous frame, and never stalls the pipeline.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let vertex_buffer: glium::VertexBuffer<u8> = unsafe { std::mem::uninitialized() };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+# let vertex_shader = "";
+# let objects: Vec<u32> = vec![];
+# let cursor = (0, 0);
+use glium::Surface;
+use glium::picking::{self, Picker};
+
+let program = glium::Program::from_source(&display, vertex_shader,
+                                          picking::FRAGMENT_SHADER, None).unwrap();
+let mut picker = Picker::new(&display);
+
+// each frame
+let (width, height) = display.get_framebuffer_dimensions();
+{
+    let mut target = picker.target(width, height).unwrap();
+    for &id in &objects {
+        target.draw(&vertex_buffer, &indices, &program, &uniform! { picking_id: id },
+                    &Default::default()).unwrap();
+    }
+}
+
+if let Some(id) = picker.pick(cursor.0, cursor.1) {
+    println!("object {} is under the cursor", id);
+}
+# }
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use framebuffer::{DepthRenderBuffer, SimpleFrameBuffer, ValidationError};
+use framebuffer::RenderBufferCreationError;
+use sync::{self, LinearSyncFence};
+use texture::{DepthFormat, MipmapsOption, TextureCreationError, UncompressedUintFormat};
+use texture::UnsignedTexture2d;
+use texture::pixel_buffer::PixelBuffer;
+use ContextExt;
+use Rect;
+use Surface;
+
+/// Name of the uniform that contains the identifier of the object in `FRAGMENT_SHADER` and
+/// `FRAGMENT_SHADER_ES`.
+pub const ID_UNIFORM: &'static str = "picking_id";
+
+/// Fragment shader that writes the `picking_id` uniform. Requires GLSL 1.40.
+pub const FRAGMENT_SHADER: &'static str = "
+    #version 140
+
+    uniform uint picking_id;
+    out uint f_id;
+
+    void main() {
+        f_id = picking_id;
+    }
+";
+
+/// Fragment shader that writes the `picking_id` uniform. Requires GLSL ES 3.00.
+pub const FRAGMENT_SHADER_ES: &'static str = "
+    #version 300 es
+
+    uniform highp uint picking_id;
+    out highp uint f_id;
+
+    void main() {
+        f_id = picking_id;
+    }
+";
+
+/// Error that can happen while creating the target of a `Picker`.
+#[derive(Copy, Clone, Debug)]
+pub enum PickingError {
+    /// Failed to create the identifier texture.
+    TextureCreationError(TextureCreationError),
+
+    /// Failed to create the depth buffer.
+    RenderBufferCreationError(RenderBufferCreationError),
+
+    /// Failed to build the framebuffer.
+    ValidationError(ValidationError),
+}
+
+impl fmt::Display for PickingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for PickingError {
+    fn description(&self) -> &str {
+        use self::PickingError::*;
+        match *self {
+            TextureCreationError(_) =>
+                "Failed to create the identifier texture",
+            RenderBufferCreationError(_) =>
+                "Failed to create the depth buffer",
+            ValidationError(_) =>
+                "Failed to build the framebuffer",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::PickingError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            RenderBufferCreationError(ref err) => Some(err),
+            ValidationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for PickingError {
+    #[inline]
+    fn from(err: TextureCreationError) -> PickingError {
+        PickingError::TextureCreationError(err)
+    }
+}
+
+impl From<RenderBufferCreationError> for PickingError {
+    #[inline]
+    fn from(err: RenderBufferCreationError) -> PickingError {
+        PickingError::RenderBufferCreationError(err)
+    }
+}
+
+impl From<ValidationError> for PickingError {
+    #[inline]
+    fn from(err: ValidationError) -> PickingError {
+        PickingError::ValidationError(err)
+    }
+}
+
+/// Renders object identifiers and reads them back asynchronously.
+pub struct Picker {
+    context: Rc<Context>,
+
+    // the identifier texture and its depth buffer ; rebuilt when the dimensions change
+    attachments: Option<(UnsignedTexture2d, DepthRenderBuffer)>,
+
+    // destination of the reads
+    pixel_buffer: PixelBuffer<u32>,
+
+    // `Some` if a read has been started and its result hasn't been retrieved yet ; the fence is
+    // `None` if fences aren't supported, in which case retrieving the result blocks
+    pending: Option<Option<LinearSyncFence>>,
+
+    // result of the latest read that has finished
+    latest: Option<u32>,
+}
+
+impl Picker {
+    /// Builds a new `Picker`. The identifier texture is created by the first call to `target`.
+    pub fn new<F: ?Sized>(facade: &F) -> Picker where F: Facade {
+        Picker {
+            context: facade.get_context().clone(),
+            attachments: None,
+            pixel_buffer: PixelBuffer::new_empty(facade, 1),
+            pending: None,
+            latest: None,
+        }
+    }
+
+    /// Returns a framebuffer to draw the identifiers of the objects to.
+    ///
+    /// The identifier texture is resized if necessary, then cleared to `0` and its depth buffer
+    /// to `1.0`. The fragment shader must write a `uint` to its single output, for example with
+    /// `FRAGMENT_SHADER`. Use the same depth test as when drawing the scene.
+    ///
+    /// `width` and `height` are usually the dimensions of the window.
+    pub fn target(&mut self, width: u32, height: u32)
+                  -> Result<SimpleFrameBuffer, PickingError>
+    {
+        let resize = match self.attachments {
+            Some((ref texture, _)) => texture.dimensions() != (width, height),
+            None => true,
+        };
+
+        if resize {
+            // the texture is destroyed first in order to free the memory
+            self.attachments = None;
+
+            let texture = try!(UnsignedTexture2d::empty_with_format(&self.context,
+                                                                    UncompressedUintFormat::U32,
+                                                                    MipmapsOption::NoMipmap,
+                                                                    width, height));
+            let depth = try!(DepthRenderBuffer::new(&self.context, DepthFormat::I24,
+                                                    width, height));
+            self.attachments = Some((texture, depth));
+        }
+
+        let &(ref texture, ref depth) = self.attachments.as_ref().unwrap();
+
+        texture.main_level().first_layer().into_image(None).unwrap()
+               .raw_clear_buffer([0u32, 0, 0, 0]);
+
+        let mut framebuffer = try!(SimpleFrameBuffer::with_depth_buffer(&self.context,
+                                                                        texture, depth));
+        framebuffer.clear_depth(1.0);
+        Ok(framebuffer)
+    }
+
+    /// Starts reading the identifier at the given position and returns the result of the
+    /// latest read that has finished.
+    ///
+    /// The position is in pixels, from the top-left corner of the identifier texture, like the
+    /// position of the cursor. If the position is outside of the texture, nothing is read and
+    /// `None` is returned.
+    ///
+    /// Returns `None` if there is no object at the position, or if no read has finished yet.
+    /// A new read is only started once the previous one has finished, so that the GPU is never
+    /// waited for.
+    pub fn pick(&mut self, x: u32, y: u32) -> Option<u32> {
+        self.retrieve();
+
+        let (width, height) = match self.attachments {
+            Some((ref texture, _)) => texture.dimensions(),
+            None => return None,
+        };
+
+        if x >= width || y >= height {
+            self.latest = None;
+            return None;
+        }
+
+        if self.pending.is_none() {
+            let &(ref texture, _) = self.attachments.as_ref().unwrap();
+
+            let rect = Rect { left: x, bottom: height - 1 - y, width: 1, height: 1 };
+            texture.main_level().first_layer().into_image(None).unwrap()
+                   .raw_read_to_pixel_buffer(&rect, &self.pixel_buffer);
+
+            let mut ctxt = self.context.make_current();
+            self.pending = Some(unsafe { sync::new_linear_sync_fence(&mut ctxt).ok() });
+        }
+
+        self.latest
+    }
+
+    /// Retrieves the result of the pending read if it has finished.
+    fn retrieve(&mut self) {
+        let finished = match self.pending {
+            Some(Some(ref fence)) => {
+                let mut ctxt = self.context.make_current();
+                unsafe { sync::is_linear_sync_fence_signaled(&mut ctxt, fence) }
+            },
+            Some(None) => true,
+            None => false,
+        };
+
+        if !finished {
+            return;
+        }
+
+        if let Some(Some(fence)) = self.pending.take() {
+            let mut ctxt = self.context.make_current();
+            unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence) };
+        }
+
+        self.latest = match self.pixel_buffer.read().ok().map(|data| data[0]) {
+            Some(0) | None => None,
+            Some(id) => Some(id),
+        };
+    }
+}
+
+impl fmt::Debug for Picker {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Picker({:?})", self.attachments.as_ref().map(|&(ref t, _)| t.dimensions()))
+    }
+}
+
+impl Drop for Picker {
+    fn drop(&mut self) {
+        if let Some(Some(fence)) = self.pending.take() {
+            let mut ctxt = self.context.make_current();
+            unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence) };
+        }
+    }
+}
@@ -0,0 +1,302 @@
+/*!
+Pipeline descriptors, which bundle everything that describes how to draw except the data.
+
+A `PipelineDescriptor` contains a program, the formats of the vertices that will be fed to it,
+the draw parameters and a description of the surfaces it draws to. The compatibility between
+all these elements is checked once when the descriptor is built, instead of being discovered
+during a draw call. Drawing with a descriptor then only requires the vertices, the indices and
+the uniforms.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let program: glium::Program = unsafe { std::mem::uninitialized() };
+# #[derive(Copy, Clone)] struct Vertex { position: [f32; 2] }
+# implement_vertex!(Vertex, position);
+# let vertex_buffer: glium::VertexBuffer<Vertex> = unsafe { std::mem::uninitialized() };
+# let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+use glium::Surface;
+use glium::pipeline::{PipelineDescriptor, PipelineOutputs};
+use glium::vertex::Vertex as VertexTrait;
+
+let pipeline = PipelineDescriptor::new(&display, &program, &[Vertex::build_bindings()],
+                                       PipelineOutputs::default(), Default::default()).unwrap();
+
+let mut frame = display.draw();
+pipeline.draw(&mut frame, &vertex_buffer, &indices, &glium::uniforms::EmptyUniforms).unwrap();
+frame.finish().unwrap();
+# }
+```
+
+The draw parameters are applied by comparing them with the current state of the context, so
+drawing several times in a row with the same descriptor doesn't produce any redundant state
+change.
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use draw_parameters::{self, FloatViewport, Stencil, StencilOperation, StencilTest};
+use index::IndicesSource;
+use program::Program;
+use uniforms::Uniforms;
+use vertex::{MultiVerticesSource, VertexFormat};
+use vertex_array_object;
+
+use ContextExt;
+use DrawError;
+use DrawParameters;
+use Surface;
+
+/// Describes the surfaces that a pipeline draws to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PipelineOutputs {
+    /// Number of color attachments of the surfaces. The default framebuffer has one.
+    pub color_attachments: u32,
+
+    /// Whether the surfaces have a depth buffer.
+    pub depth_buffer: bool,
+
+    /// Whether the surfaces have a stencil buffer.
+    pub stencil_buffer: bool,
+
+    /// The dimensions of the surfaces, if they are known in advance. Used to check the viewport
+    /// and the scissor box of the draw parameters.
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl Default for PipelineOutputs {
+    /// A single color attachment with a depth buffer, like a default framebuffer created with
+    /// a depth buffer.
+    #[inline]
+    fn default() -> PipelineOutputs {
+        PipelineOutputs {
+            color_attachments: 1,
+            depth_buffer: true,
+            stencil_buffer: false,
+            dimensions: None,
+        }
+    }
+}
+
+impl PipelineOutputs {
+    /// Builds the description of an existing surface.
+    ///
+    /// Surfaces don't report their number of color attachments, so it is set to 1.
+    #[inline]
+    pub fn from_surface<S: ?Sized>(surface: &S) -> PipelineOutputs where S: Surface {
+        let (width, height) = surface.get_dimensions();
+
+        PipelineOutputs {
+            color_attachments: 1,
+            depth_buffer: surface.has_depth_buffer(),
+            stencil_buffer: surface.has_stencil_buffer(),
+            dimensions: Some((width, height)),
+        }
+    }
+}
+
+/// Error that can happen while creating a `PipelineDescriptor`.
+#[derive(Clone, Debug)]
+pub enum PipelineCreationError {
+    /// The program, the vertex formats and the draw parameters aren't compatible, or the draw
+    /// parameters aren't supported by the backend.
+    DrawError(DrawError),
+
+    /// The program writes to a color attachment that the outputs don't have.
+    MissingColorAttachment {
+        /// Name of the output of the fragment shader.
+        name: String,
+        /// Location of the output.
+        location: u32,
+    },
+
+    /// The draw parameters use the stencil buffer, but the outputs don't have one.
+    NoStencilBuffer,
+}
+
+impl fmt::Display for PipelineCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::PipelineCreationError::*;
+        match *self {
+            DrawError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+            MissingColorAttachment { ref name, location } =>
+                write!(fmt, "{} (output `{}` at location {})", self.description(), name,
+                       location),
+            NoStencilBuffer => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for PipelineCreationError {
+    fn description(&self) -> &str {
+        use self::PipelineCreationError::*;
+        match *self {
+            DrawError(_) =>
+                "The elements of the pipeline are not compatible",
+            MissingColorAttachment { .. } =>
+                "The program writes to a color attachment that the outputs don't have",
+            NoStencilBuffer =>
+                "The draw parameters use the stencil buffer, but the outputs don't have one",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        match *self {
+            PipelineCreationError::DrawError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DrawError> for PipelineCreationError {
+    #[inline]
+    fn from(err: DrawError) -> PipelineCreationError {
+        PipelineCreationError::DrawError(err)
+    }
+}
+
+/// A program, vertex formats, draw parameters and outputs whose compatibility has been checked.
+///
+/// See the documentation of the module.
+#[derive(Debug)]
+pub struct PipelineDescriptor<'a> {
+    program: &'a Program,
+    vertex_formats: Vec<VertexFormat>,
+    draw_parameters: DrawParameters<'a>,
+    outputs: PipelineOutputs,
+}
+
+impl<'a> PipelineDescriptor<'a> {
+    /// Builds a new descriptor.
+    ///
+    /// The vertex formats must contain all the attributes of the program with the right types,
+    /// as they would be provided by the vertex sources passed to `draw`. The formats of the
+    /// vertex types can be obtained with `Vertex::build_bindings`.
+    pub fn new<F: ?Sized>(facade: &F, program: &'a Program, vertex_formats: &[VertexFormat],
+                          outputs: PipelineOutputs, draw_parameters: DrawParameters<'a>)
+                          -> Result<PipelineDescriptor<'a>, PipelineCreationError>
+        where F: Facade
+    {
+        let context = facade.get_context();
+
+        try!(draw_parameters::validate(context, &draw_parameters));
+        try!(vertex_array_object::check_attributes(vertex_formats.iter(), program));
+
+        if program.has_mesh_shader() {
+            return Err(DrawError::MeshShaderMismatch.into());
+        }
+
+        if draw_parameters.depth.test.requires_depth_buffer() || draw_parameters.depth.write {
+            if !outputs.depth_buffer {
+                return Err(DrawError::NoDepthBuffer.into());
+            }
+        }
+
+        if uses_stencil_buffer(&draw_parameters.stencil) && !outputs.stencil_buffer {
+            return Err(PipelineCreationError::NoStencilBuffer);
+        }
+
+        for (name, output) in program.fragment_outputs() {
+            if output.location + output.size as u32 > outputs.color_attachments {
+                return Err(PipelineCreationError::MissingColorAttachment {
+                    name: name.clone(),
+                    location: output.location,
+                });
+            }
+        }
+
+        let max_dims = context.capabilities().max_viewport_dims;
+        for rect in draw_parameters.viewport.iter().chain(draw_parameters.scissor.iter()) {
+            if rect.width > max_dims.0 as u32 || rect.height > max_dims.1 as u32 {
+                return Err(DrawError::ViewportTooLarge.into());
+            }
+
+            if let Some((width, height)) = outputs.dimensions {
+                if rect.left + rect.width > width || rect.bottom + rect.height > height {
+                    return Err(DrawError::ViewportTooLarge.into());
+                }
+            }
+        }
+
+        if let Some(viewport) = draw_parameters.float_viewport {
+            if let FloatViewport::Pixels { left, bottom, width, height } = viewport {
+                if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                    return Err(DrawError::ViewportTooLarge.into());
+                }
+
+                if let Some((w, h)) = outputs.dimensions {
+                    if left + width > w as f32 || bottom + height > h as f32 {
+                        return Err(DrawError::ViewportTooLarge.into());
+                    }
+                }
+
+            } else if let Some(dimensions) = outputs.dimensions {
+                let (_, _, width, height) = viewport.to_pixels(dimensions);
+                if width > max_dims.0 as f32 || height > max_dims.1 as f32 {
+                    return Err(DrawError::ViewportTooLarge.into());
+                }
+            }
+        }
+
+        Ok(PipelineDescriptor {
+            program: program,
+            vertex_formats: vertex_formats.to_vec(),
+            draw_parameters: draw_parameters,
+            outputs: outputs,
+        })
+    }
+
+    /// Returns the program of the pipeline.
+    #[inline]
+    pub fn get_program(&self) -> &'a Program {
+        self.program
+    }
+
+    /// Returns the vertex formats that were checked against the program.
+    #[inline]
+    pub fn get_vertex_formats(&self) -> &[VertexFormat] {
+        &self.vertex_formats
+    }
+
+    /// Returns the draw parameters of the pipeline.
+    #[inline]
+    pub fn get_draw_parameters(&self) -> &DrawParameters<'a> {
+        &self.draw_parameters
+    }
+
+    /// Returns the description of the surfaces that the pipeline draws to.
+    #[inline]
+    pub fn get_outputs(&self) -> &PipelineOutputs {
+        &self.outputs
+    }
+
+    /// Draws with this pipeline.
+    ///
+    /// The vertex sources should have the formats given when the descriptor was built. Vertex
+    /// sources with other formats still work, but their compatibility with the program is
+    /// then checked during the draw call.
+    #[inline]
+    pub fn draw<'b, 'c, S: ?Sized, V, I, U>(&self, surface: &mut S, vertices: V, indices: I,
+                                           uniforms: &U) -> Result<(), DrawError>
+        where S: Surface, V: MultiVerticesSource<'c>, I: Into<IndicesSource<'b>>, U: Uniforms
+    {
+        surface.draw(vertices, indices, self.program, uniforms, &self.draw_parameters)
+    }
+}
+
+/// Returns true if the stencil parameters read from or write to the stencil buffer.
+fn uses_stencil_buffer(stencil: &Stencil) -> bool {
+    stencil.test_clockwise != StencilTest::AlwaysPass ||
+    stencil.test_counter_clockwise != StencilTest::AlwaysPass ||
+    [
+        stencil.fail_operation_clockwise,
+        stencil.pass_depth_fail_operation_clockwise,
+        stencil.depth_pass_operation_clockwise,
+        stencil.fail_operation_counter_clockwise,
+        stencil.pass_depth_fail_operation_counter_clockwise,
+        stencil.depth_pass_operation_counter_clockwise,
+    ].iter().any(|&op| op != StencilOperation::Keep)
+}
@@ -0,0 +1,367 @@
+/*!
+Applying full-screen effects such as blurs or tone mapping.
+
+A `FullscreenPass` draws a single triangle that covers the whole target with a fragment shader
+of your choice. The vertex shader is provided and passes the texture coordinates of the target
+to the fragment shader in the `v_tex_coords` varying, and the texture to process is bound to the
+`source` uniform. Its dimensions in pixels are available in the `source_size` uniform.
+
+```glsl
+#version 140
+
+uniform sampler2D source;
+in vec2 v_tex_coords;
+out vec4 f_color;
+
+void main() {
+    f_color = vec4(1.0 - texture(source, v_tex_coords).rgb, 1.0);
+}
+```
+
+Effects that require multiple passes can be chained with a `PassChain`, which alternates between
+the two textures of a `PingPongTargets`. Each pass reads the output of the previous one, and the
+last pass is drawn to the surface of your choice.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let scene: glium::Texture2d = unsafe { std::mem::uninitialized() };
+# let blur_shader = "";
+# let tonemap_shader = "";
+use glium::framebuffer::PingPongTargets;
+use glium::post_process::{FullscreenPass, PassChain};
+use glium::texture::UncompressedFloatFormat;
+
+let blur = FullscreenPass::new(&display, blur_shader).unwrap();
+let tonemap = FullscreenPass::new(&display, tonemap_shader).unwrap();
+let mut targets = PingPongTargets::new(&display, UncompressedFloatFormat::F16F16F16F16,
+                                       1024, 768).unwrap();
+
+// each frame
+let mut frame = display.draw();
+{
+    let mut chain = PassChain::new(&mut targets, &scene).unwrap();
+    chain.apply(&blur, &uniform! { direction: [1.0, 0.0f32] }).unwrap();
+    chain.apply(&blur, &uniform! { direction: [0.0, 1.0f32] }).unwrap();
+    chain.finish(&tonemap, &mut frame, &uniform! { exposure: 1.5f32 }).unwrap();
+}
+frame.finish().unwrap();
+# }
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use framebuffer::{PingPongTargets, PingPongTargetsCreationError};
+use index::{NoIndices, PrimitiveType};
+use program::{Program, ProgramCreationError};
+use texture::Texture2d;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior, SamplerWrapFunction};
+use uniforms::{RawUniformHandle, UniformValue, Uniforms};
+use vertex::VertexBuffer;
+use vertex::BufferCreationError;
+
+use DrawError;
+use Surface;
+
+/// Name of the uniform that contains the texture to process.
+pub const SOURCE_UNIFORM: &'static str = "source";
+
+/// Name of the uniform that contains the dimensions in pixels of the texture to process, as a
+/// `vec2`.
+pub const SOURCE_SIZE_UNIFORM: &'static str = "source_size";
+
+/// Error that can happen while creating a `FullscreenPass`.
+#[derive(Clone, Debug)]
+pub enum FullscreenPassCreationError {
+    /// Failed to compile the program.
+    ProgramCreationError(ProgramCreationError),
+
+    /// Failed to create the vertex buffer.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for FullscreenPassCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for FullscreenPassCreationError {
+    fn description(&self) -> &str {
+        use self::FullscreenPassCreationError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile the program",
+            BufferCreationError(_) =>
+                "Failed to create the vertex buffer",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::FullscreenPassCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramCreationError> for FullscreenPassCreationError {
+    #[inline]
+    fn from(err: ProgramCreationError) -> FullscreenPassCreationError {
+        FullscreenPassCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for FullscreenPassCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> FullscreenPassCreationError {
+        FullscreenPassCreationError::BufferCreationError(err)
+    }
+}
+
+/// Error that can happen while applying a chain of passes.
+#[derive(Clone, Debug)]
+pub enum PostProcessError {
+    /// Failed to resize the intermediate textures.
+    TargetsCreationError(PingPongTargetsCreationError),
+
+    /// Failed to draw a pass.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for PostProcessError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for PostProcessError {
+    fn description(&self) -> &str {
+        use self::PostProcessError::*;
+        match *self {
+            TargetsCreationError(_) =>
+                "Failed to resize the intermediate textures",
+            DrawError(_) =>
+                "Failed to draw a pass",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::PostProcessError::*;
+        match *self {
+            TargetsCreationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<PingPongTargetsCreationError> for PostProcessError {
+    #[inline]
+    fn from(err: PingPongTargetsCreationError) -> PostProcessError {
+        PostProcessError::TargetsCreationError(err)
+    }
+}
+
+impl From<DrawError> for PostProcessError {
+    #[inline]
+    fn from(err: DrawError) -> PostProcessError {
+        PostProcessError::DrawError(err)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct SpriteVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(SpriteVertex, position);
+
+/// A fragment shader applied to the whole target.
+pub struct FullscreenPass {
+    program: Program,
+    vertex_buffer: VertexBuffer<SpriteVertex>,
+}
+
+impl FullscreenPass {
+    /// Builds a new pass from the source code of a fragment shader.
+    ///
+    /// The vertex shader is written with the same GLSL version as the one of the `#version`
+    /// directive of the fragment shader.
+    pub fn new<F: ?Sized>(facade: &F, fragment_shader: &str)
+                          -> Result<FullscreenPass, FullscreenPassCreationError>
+                          where F: Facade
+    {
+        let vertex_shader = vertex_shader(fragment_shader);
+        let program = try!(Program::from_source(facade, &vertex_shader, fragment_shader, None));
+
+        // a single triangle covers the target without the seam of two triangles
+        let vertex_buffer = try!(VertexBuffer::new(facade, &[
+            SpriteVertex { position: [-1.0, -1.0] },
+            SpriteVertex { position: [ 3.0, -1.0] },
+            SpriteVertex { position: [-1.0,  3.0] },
+        ]));
+
+        Ok(FullscreenPass {
+            program: program,
+            vertex_buffer: vertex_buffer,
+        })
+    }
+
+    /// Returns the program of this pass.
+    #[inline]
+    pub fn get_program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Draws the pass to a surface, with `source` as the texture to process.
+    ///
+    /// The texture is sampled with linear filtering and its coordinates are clamped to the
+    /// edges. The uniforms are passed to the fragment shader in addition to `source` and
+    /// `source_size`.
+    pub fn draw<S: ?Sized, U>(&self, surface: &mut S, source: &Texture2d, uniforms: &U)
+                              -> Result<(), DrawError>
+                              where S: Surface, U: Uniforms
+    {
+        let uniforms = SourceUniforms {
+            source: source,
+            uniforms: uniforms,
+        };
+
+        surface.draw(&self.vertex_buffer, &NoIndices(PrimitiveType::TrianglesList),
+                     &self.program, &uniforms, &Default::default())
+    }
+}
+
+impl fmt::Debug for FullscreenPass {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "FullscreenPass({:?})", self.program)
+    }
+}
+
+/// A chain of passes being applied.
+pub struct PassChain<'a> {
+    targets: &'a mut PingPongTargets,
+    source: &'a Texture2d,
+
+    // true once a pass has been applied, in which case its output is the read side of the
+    // targets
+    applied: bool,
+}
+
+impl<'a> PassChain<'a> {
+    /// Starts a chain of passes that processes `source`.
+    ///
+    /// The targets are resized to the dimensions of `source` if necessary.
+    pub fn new(targets: &'a mut PingPongTargets, source: &'a Texture2d)
+               -> Result<PassChain<'a>, PostProcessError>
+    {
+        let (width, height) = source.dimensions();
+        try!(targets.resize(width, height));
+
+        Ok(PassChain {
+            targets: targets,
+            source: source,
+            applied: false,
+        })
+    }
+
+    /// Applies a pass to the output of the previous one, or to the source texture if this is
+    /// the first pass.
+    pub fn apply<U>(&mut self, pass: &FullscreenPass, uniforms: &U)
+                    -> Result<(), PostProcessError>
+                    where U: Uniforms
+    {
+        try!(pass.draw(&mut self.targets.write_surface(), self.output(), uniforms));
+        self.targets.swap();
+        self.applied = true;
+        Ok(())
+    }
+
+    /// Returns the output of the latest pass, or the source texture if no pass has been
+    /// applied.
+    #[inline]
+    pub fn output(&self) -> &Texture2d {
+        if self.applied { self.targets.read() } else { self.source }
+    }
+
+    /// Applies a last pass and draws its result to `surface`.
+    #[inline]
+    pub fn finish<S: ?Sized, U>(self, pass: &FullscreenPass, surface: &mut S, uniforms: &U)
+                                -> Result<(), DrawError>
+                                where S: Surface, U: Uniforms
+    {
+        pass.draw(surface, self.output(), uniforms)
+    }
+}
+
+impl<'a> fmt::Debug for PassChain<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "PassChain({:?}, {})", self.targets, self.applied)
+    }
+}
+
+/// Adds the `source` and `source_size` uniforms to the uniforms of the user.
+struct SourceUniforms<'a, U: 'a> {
+    source: &'a Texture2d,
+    uniforms: &'a U,
+}
+
+impl<'a, U> Uniforms for SourceUniforms<'a, U> where U: Uniforms {
+    fn visit_values<'b, F: FnMut(&str, UniformValue<'b>)>(&'b self, mut output: F) {
+        let behavior = SamplerBehavior {
+            wrap_function: (SamplerWrapFunction::Clamp, SamplerWrapFunction::Clamp,
+                            SamplerWrapFunction::Clamp),
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            .. Default::default()
+        };
+
+        let (width, height) = self.source.dimensions();
+
+        output(SOURCE_UNIFORM, UniformValue::Texture2d(self.source, Some(behavior)));
+        output(SOURCE_SIZE_UNIFORM, UniformValue::Vec2([width as f32, height as f32]));
+        self.uniforms.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_handles<'b, F: FnMut(&RawUniformHandle, UniformValue<'b>)>(&'b self, output: F) {
+        self.uniforms.visit_handles(output);
+    }
+}
+
+/// Builds the vertex shader that goes with a fragment shader.
+fn vertex_shader(fragment_shader: &str) -> String {
+    let version = fragment_shader.lines()
+                                 .map(|line| line.trim())
+                                 .find(|line| line.starts_with("#version"))
+                                 .map(|line| line["#version".len() ..].trim());
+
+    // `in` and `out` replaced `attribute` and `varying` in GLSL 1.30 and GLSL ES 3.00
+    let modern = match version {
+        Some(version) => {
+            let mut words = version.split_whitespace();
+            let number = words.next().and_then(|n| n.parse::<u32>().ok()).unwrap_or(110);
+            if words.next() == Some("es") { number >= 300 } else { number >= 130 }
+        },
+        None => false,
+    };
+
+    let (input, output) = if modern { ("in", "out") } else { ("attribute", "varying") };
+
+    format!("
+        {}
+
+        {} vec2 position;
+        {} vec2 v_tex_coords;
+
+        void main() {{
+            v_tex_coords = position * 0.5 + 0.5;
+            gl_Position = vec4(position, 0.0, 1.0);
+        }}
+    ", version.map(|v| format!("#version {}", v)).unwrap_or(String::new()), input, output)
+}
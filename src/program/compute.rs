@@ -1,12 +1,14 @@
 use gl;
 
 use context::CommandContext;
+use context::Context;
 use backend::Facade;
 
 use std::fmt;
 use std::collections::hash_map::{self, HashMap};
 use std::os::raw;
 use std::hash::BuildHasherDefault;
+use std::rc::Rc;
 
 use fnv::FnvHasher;
 
@@ -17,10 +19,12 @@ use Handle;
 use RawUniformValue;
 
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationError, Binary, GetBinaryError};
+use program::BlockBindingError;
 
 use program::reflection::{Uniform, UniformBlock};
 use program::reflection::{ShaderStage, SubroutineData};
 use program::shader::{build_shader, check_shader_type_compatibility};
+use program::library::{annotate_error, resolve_includes};
 
 use program::raw::RawProgram;
 
@@ -30,6 +34,8 @@ use uniforms::Uniforms;
 /// A combination of compute shaders linked together.
 pub struct ComputeShader {
     raw: RawProgram,
+    // declared after `raw` so that the program is destroyed before the context
+    context: Rc<Context>,
 }
 
 impl ComputeShader {
@@ -44,12 +50,19 @@ impl ComputeShader {
     pub fn from_source<F: ?Sized>(facade: &F, src: &str) -> Result<ComputeShader, ProgramCreationError>
                           where F: Facade
     {
+        let library = facade.get_context().get_shader_library();
+        let mut used_modules = Vec::new();
+        let src = try!(resolve_includes(library, src, &mut used_modules));
+
         let _lock = COMPILER_GLOBAL_LOCK.lock();
 
-        let shader = try!(build_shader(facade, gl::COMPUTE_SHADER, src));
+        let shader = try!(build_shader(facade, gl::COMPUTE_SHADER, &src)
+                            .map_err(|e| annotate_error(library, e, &used_modules)));
 
         Ok(ComputeShader {
-            raw: try!(RawProgram::from_shaders(facade, &[shader], false, false, false, None))
+            raw: try!(RawProgram::from_shaders(facade, &[shader], false, false, false, false, None,
+                                                   Vec::new(), Vec::new())),
+            context: facade.get_context().clone(),
         })
     }
 
@@ -61,7 +74,8 @@ impl ComputeShader {
         let _lock = COMPILER_GLOBAL_LOCK.lock();
 
         Ok(ComputeShader {
-            raw: try!(RawProgram::from_binary(facade, data))
+            raw: try!(RawProgram::from_binary(facade, data)),
+            context: facade.get_context().clone(),
         })
     }
 
@@ -146,6 +160,26 @@ impl ComputeShader {
             -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_shader_storage_blocks()
     }
+
+    /// Assigns a persistent binding point to a uniform block of the compute shader.
+    ///
+    /// See `Program::set_uniform_block_binding`.
+    #[inline]
+    pub fn set_uniform_block_binding(&self, name: &str, binding: u32)
+                                     -> Result<(), BlockBindingError>
+    {
+        self.raw.set_fixed_uniform_block_binding(name, binding)
+    }
+
+    /// Assigns a persistent binding point to a shader storage block of the compute shader.
+    ///
+    /// See `Program::set_uniform_block_binding`.
+    #[inline]
+    pub fn set_shader_storage_block_binding(&self, name: &str, binding: u32)
+                                            -> Result<(), BlockBindingError>
+    {
+        self.raw.set_fixed_shader_storage_block_binding(name, binding)
+    }
 }
 
 impl fmt::Debug for ComputeShader {
@@ -192,6 +226,20 @@ impl ProgramExt for ComputeShader {
         self.raw.set_shader_storage_block_binding(ctxt, block_location, value)
     }
 
+    #[inline]
+    fn get_fixed_uniform_block_binding(&self, block_location: gl::types::GLuint)
+                                       -> Option<gl::types::GLuint>
+    {
+        self.raw.get_fixed_uniform_block_binding(block_location)
+    }
+
+    #[inline]
+    fn get_fixed_shader_storage_block_binding(&self, block_location: gl::types::GLuint)
+                                              -> Option<gl::types::GLuint>
+    {
+        self.raw.get_fixed_shader_storage_block_binding(block_location)
+    }
+
     #[inline]
     fn set_subroutine_uniforms_for_stage(&self, ctxt: &mut CommandContext,
                                          stage: ShaderStage,
@@ -200,11 +248,21 @@ impl ProgramExt for ComputeShader {
         self.raw.set_subroutine_uniforms_for_stage(ctxt, stage, indices);
     }
 
+    #[inline]
+    fn uses_strict_uniforms(&self) -> bool {
+        self.raw.uses_strict_uniforms()
+    }
+
     #[inline]
     fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    #[inline]
+    fn get_uniforms(&self) -> &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>> {
+        ProgramExt::get_uniforms(&self.raw)
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_uniform_blocks()
@@ -220,6 +278,11 @@ impl ProgramExt for ComputeShader {
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn get_attributes_layout(&self) -> u64 {
+        self.raw.get_attributes_layout()
+    }
 }
 
 /// Represents a compute shader command waiting to be dispatched.
@@ -235,3 +298,7 @@ pub struct ComputeCommand {
 }
 
 implement_uniform_block!(ComputeCommand, num_groups_x, num_groups_y, num_groups_z);
+
+/// Name of `ComputeCommand` in the OpenGL specifications. This is the layout expected by
+/// `glDispatchComputeIndirect`.
+pub type DispatchIndirectCommand = ComputeCommand;
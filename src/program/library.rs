@@ -0,0 +1,221 @@
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+
+use program::ProgramCreationError;
+
+/// A list of named GLSL modules that can be included by the shaders of a context.
+///
+/// A shader includes a module by putting an `#include "name"` directive on its own line. When a
+/// program is built, each directive is replaced with the source code of the module, surrounded
+/// with `#line` directives. Modules can include other modules.
+///
+/// Each module is given a source string number equal to its position in the library plus one,
+/// while the shader itself has the number 0. Compilers report errors with these numbers, for
+/// example `2(14)` for the line 14 of the second registered module. The logs of compilation
+/// errors are completed with the names of the modules that correspond to each number.
+///
+/// ```no_run
+/// # extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// display.get_shader_library().register("lighting", "
+///     float lambert(vec3 normal, vec3 light) {
+///         return max(dot(normal, light), 0.0);
+///     }
+/// ");
+///
+/// let fragment_shader = "
+///     #version 330
+///     #include \"lighting\"
+///
+///     in vec3 v_normal;
+///     out vec4 color;
+///
+///     void main() {
+///         color = vec4(vec3(lambert(v_normal, vec3(0.0, 0.0, 1.0))), 1.0);
+///     }
+/// ";
+/// # }
+/// ```
+pub struct ShaderLibrary {
+    /// The name and source code of each module.
+    modules: RefCell<Vec<(String, String)>>,
+
+    /// Incremented each time a module is registered.
+    revision: Cell<u64>,
+}
+
+impl ShaderLibrary {
+    /// Builds an empty library.
+    #[inline]
+    pub fn new() -> ShaderLibrary {
+        ShaderLibrary {
+            modules: RefCell::new(Vec::new()),
+            revision: Cell::new(0),
+        }
+    }
+
+    /// Registers a module. If a module with the same name already exists, its source code is
+    /// replaced and it keeps the same source string number.
+    ///
+    /// The modules must not contain a `#version` directive. Programs that have already been
+    /// built are not modified.
+    pub fn register<N, S>(&self, name: N, source: S) where N: Into<String>, S: Into<String> {
+        let name = name.into();
+        let source = source.into();
+
+        let mut modules = self.modules.borrow_mut();
+        if let Some(module) = modules.iter_mut().find(|m| m.0 == name) {
+            module.1 = source;
+        } else {
+            modules.push((name, source));
+        }
+
+        self.revision.set(self.revision.get() + 1);
+    }
+
+    /// Returns the source code of a module.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.modules.borrow().iter().find(|m| m.0 == name).map(|m| m.1.clone())
+    }
+
+    /// Returns the name of the module corresponding to a source string number reported by the
+    /// compiler. Returns `None` for 0, which is the shader itself.
+    #[inline]
+    pub fn get_source_string_name(&self, source_string: u32) -> Option<String> {
+        if source_string == 0 {
+            return None;
+        }
+
+        self.modules.borrow().get(source_string as usize - 1).map(|m| m.0.clone())
+    }
+
+    /// Returns a number that changes each time a module is registered.
+    #[inline]
+    pub fn get_revision(&self) -> u64 {
+        self.revision.get()
+    }
+}
+
+/// Replaces the `#include` directives of a shader with the source code of the modules.
+///
+/// The source string numbers of the included modules are added to `used`.
+pub fn resolve_includes<'a>(library: &ShaderLibrary, source_code: &'a str, used: &mut Vec<u32>)
+                            -> Result<Cow<'a, str>, ProgramCreationError>
+{
+    if !source_code.lines().any(|l| parse_include(l).is_some()) {
+        return Ok(Cow::Borrowed(source_code));
+    }
+
+    let modules = library.modules.borrow();
+    let offset = line_directive_offset(source_code);
+
+    let mut result = String::with_capacity(source_code.len());
+    let mut stack = Vec::new();
+    try!(expand(&modules, source_code, 0, offset, &mut stack, used, &mut result));
+    Ok(Cow::Owned(result))
+}
+
+/// Appends to the log of a compilation error the names of the modules that correspond to the
+/// source string numbers.
+pub fn annotate_error(library: &ShaderLibrary, error: ProgramCreationError, used: &[u32])
+                      -> ProgramCreationError
+{
+    match error {
+        ProgramCreationError::CompilationError(mut log) if !used.is_empty() => {
+            log.push_str("\nSource strings: 0 = shader");
+            for &number in used {
+                if let Some(name) = library.get_source_string_name(number) {
+                    log.push_str(&format!(", {} = module `{}`", number, name));
+                }
+            }
+            log.push('\n');
+            ProgramCreationError::CompilationError(log)
+        },
+        error => error,
+    }
+}
+
+fn expand(modules: &[(String, String)], source_code: &str, source_string: u32, offset: u32,
+          stack: &mut Vec<u32>, used: &mut Vec<u32>, result: &mut String)
+          -> Result<(), ProgramCreationError>
+{
+    for (line_num, line) in source_code.lines().enumerate() {
+        let name = match parse_include(line) {
+            Some(name) => name,
+            None => {
+                result.push_str(line);
+                result.push('\n');
+                continue;
+            },
+        };
+
+        let index = match modules.iter().position(|m| m.0 == name) {
+            Some(index) => index,
+            None => return Err(ProgramCreationError::ShaderModuleNotFound(name.to_owned())),
+        };
+
+        let number = index as u32 + 1;
+        if number == source_string || stack.contains(&number) {
+            return Err(ProgramCreationError::RecursiveShaderModule(name.to_owned()));
+        }
+
+        if !used.contains(&number) {
+            used.push(number);
+        }
+
+        // the first line of the module is the line 1, and the line that follows the directive
+        // keeps its original number
+        result.push_str(&format!("#line {} {}\n", 1 - offset, number));
+        stack.push(source_string);
+        try!(expand(modules, &modules[index].1, number, offset, stack, used, result));
+        stack.pop();
+        result.push_str(&format!("#line {} {}\n", line_num as u32 + 2 - offset, source_string));
+    }
+
+    Ok(())
+}
+
+/// Returns the name of the module if the line is an `#include` directive.
+fn parse_include(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if !line.starts_with('#') {
+        return None;
+    }
+
+    let line = line[1..].trim_left();
+    if !line.starts_with("include") {
+        return None;
+    }
+
+    let name = line["include".len()..].trim();
+    if name.len() >= 2 && ((name.starts_with('"') && name.ends_with('"')) ||
+                           (name.starts_with('<') && name.ends_with('>')))
+    {
+        Some(&name[1 .. name.len() - 1])
+    } else {
+        None
+    }
+}
+
+/// Before GLSL 3.30 and GLSL ES 3.00, the line that follows `#line N` has the number `N + 1`
+/// instead of `N`.
+fn line_directive_offset(source_code: &str) -> u32 {
+    let version = source_code.lines().map(|l| l.trim())
+                             .find(|l| l.starts_with("#version"))
+                             .map(|l| l["#version".len()..].split_whitespace().collect::<Vec<_>>());
+
+    let (number, es) = match version {
+        Some(ref words) if !words.is_empty() => {
+            (words[0].parse::<u32>().unwrap_or(110), words.get(1) == Some(&"es"))
+        },
+        _ => (110, false),
+    };
+
+    if (es && number >= 300) || (!es && number >= 330) {
+        0
+    } else {
+        1
+    }
+}
@@ -1,5 +1,6 @@
 //! Items related to creating an OpenGL program.
 
+use std::cmp;
 use std::fmt;
 use std::error::Error;
 use std::sync::Mutex;
@@ -9,19 +10,28 @@ use gl;
 use version::Api;
 use version::Version;
 
-pub use self::compute::{ComputeShader, ComputeCommand};
+pub use self::compute::{ComputeShader, ComputeCommand, DispatchIndirectCommand};
+pub use self::library::ShaderLibrary;
 pub use self::program::Program;
+#[doc(hidden)]
+pub use self::program::{CachedProgram, ProgramCacheKey};
 pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives};
+pub use self::reflection::GeometryShaderLayout;
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
 pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform};
+pub use self::reflection::FragmentOutput;
+pub use self::validation::{ValidationReport, ValidationIssue};
 
 mod compute;
+mod library;
 mod program;
 mod raw;
 mod reflection;
+mod port;
 mod shader;
 mod uniforms_storage;
 mod binary_header;
+mod validation;
 
 /// Returns true if the backend supports geometry shaders.
 #[inline]
@@ -35,6 +45,12 @@ pub fn is_tessellation_shader_supported<C: ?Sized>(ctxt: &C) -> bool where C: Ca
     shader::check_shader_type_compatibility(ctxt, gl::TESS_CONTROL_SHADER)
 }
 
+/// Returns true if the backend supports mesh shaders and task shaders.
+#[inline]
+pub fn is_mesh_shader_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    shader::check_shader_type_compatibility(ctxt, gl::MESH_SHADER_NV)
+}
+
 /// Returns true if the backend supports creating and retrieving binary format.
 #[inline]
 pub fn is_binary_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
@@ -55,6 +71,96 @@ pub fn is_subroutine_supported<C: ?Sized>(ctxt: &C) -> bool where C: Capabilitie
     ctxt.get_version() >= &Version(Api::Gl, 4, 0) || ctxt.get_extensions().gl_arb_shader_subroutine
 }
 
+/// Returns true if the backend supports reading the current content of the framebuffer from
+/// fragment shaders.
+///
+/// Check `Capabilities::framebuffer_fetch` to know which extension your shaders have to enable.
+#[inline]
+pub fn is_framebuffer_fetch_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_capabilities().framebuffer_fetch.is_some()
+}
+
+/// Returns true if the backend supports writing `gl_Layer` from vertex shaders, which allows
+/// rendering to a layered framebuffer without a geometry shader.
+///
+/// Check `Capabilities::vertex_shader_layer` to know which extension your shaders have to
+/// enable.
+#[inline]
+pub fn is_vertex_shader_layer_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_capabilities().vertex_shader_layer.is_some()
+}
+
+/// Returns true if the backend supports fragment shader interlock or ordering, which allows
+/// implementing order-independent transparency or programmable blending.
+///
+/// Check `Capabilities::fragment_shader_interlock` to know which extension your shaders have to
+/// enable.
+#[inline]
+pub fn is_fragment_shader_interlock_supported<C: ?Sized>(ctxt: &C) -> bool
+                                                       where C: CapabilitiesSource
+{
+    ctxt.get_capabilities().fragment_shader_interlock.is_some()
+}
+
+/// Returns the maximum number of buffers that a program can capture to with transform feedback
+/// in the given mode, or 0 if transform feedback isn't supported.
+///
+/// Capturing to multiple buffers in interleaved mode requires OpenGL 4.0 or
+/// `GL_ARB_transform_feedback3`.
+#[inline]
+pub fn get_max_transform_feedback_buffers<C: ?Sized>(ctxt: &C, mode: TransformFeedbackMode)
+                                                     -> usize where C: CapabilitiesSource
+{
+    let max = ctxt.get_capabilities().max_indexed_transform_feedback_buffer as usize;
+
+    match mode {
+        TransformFeedbackMode::Separate => max,
+        TransformFeedbackMode::Interleaved => {
+            if ctxt.get_version() >= &Version(Api::Gl, 4, 0) ||
+               ctxt.get_extensions().gl_arb_transform_feedback3
+            {
+                max
+            } else {
+                cmp::min(max, 1)
+            }
+        },
+    }
+}
+
+/// Builds a value for `transform_feedback_varyings` that captures each group of varyings to its
+/// own buffer. The varyings of the same group are interleaved in their buffer, and the buffers
+/// are numbered in the order of the groups.
+///
+/// This separates the groups with the `gl_NextBuffer` marker, which requires OpenGL 4.0 or
+/// `GL_ARB_transform_feedback3` when there is more than one group.
+///
+/// ## Example
+///
+/// ```
+/// use glium::program::{transform_feedback_buffers, TransformFeedbackMode};
+///
+/// let varyings = transform_feedback_buffers(vec![
+///     vec!["position".to_owned(), "velocity".to_owned()],
+///     vec!["color".to_owned()],
+/// ]);
+///
+/// assert_eq!(varyings.0, vec!["position", "velocity", "gl_NextBuffer", "color"]);
+/// assert_eq!(varyings.1, TransformFeedbackMode::Interleaved);
+/// ```
+pub fn transform_feedback_buffers(groups: Vec<Vec<String>>) -> (Vec<String>, TransformFeedbackMode) {
+    let mut names = Vec::new();
+
+    for (num, group) in groups.into_iter().enumerate() {
+        if num != 0 {
+            names.push("gl_NextBuffer".to_owned());
+        }
+
+        names.extend(group);
+    }
+
+    (names, TransformFeedbackMode::Interleaved)
+}
+
 /// Some shader compilers have race-condition issues, so we lock this mutex
 /// in the GL thread every time we compile a shader or link a program.
 // TODO: replace by a StaticMutex
@@ -76,9 +182,14 @@ pub enum ProgramCreationError {
     /// Usually the case for geometry shaders.
     ShaderTypeNotSupported,
 
-    /// The OpenGL implementation doesn't provide a compiler.
+    /// The OpenGL implementation doesn't provide a compiler. Programs can still be created from
+    /// precompiled shaders with `ProgramCreationInput::ShaderBinaries`.
     CompilationNotSupported,
 
+    /// The format of the shader binaries is not one of the formats of
+    /// `Capabilities::shader_binary_formats`.
+    ShaderBinaryFormatNotSupported,
+
     /// You have requested transform feedback varyings, but transform feedback is not supported
     /// by the backend.
     TransformFeedbackNotSupported,
@@ -89,6 +200,49 @@ pub enum ProgramCreationError {
 
     /// The glium-specific binary header was not found or is corrupt.
     BinaryHeaderError,
+
+    /// You have requested explicit locations for fragment shader outputs, but this is not
+    /// supported by the backend.
+    FragmentOutputLocationNotSupported,
+
+    /// The fragment shader enables a framebuffer fetch extension that is not supported by
+    /// the backend.
+    FramebufferFetchNotSupported,
+
+    /// The vertex shader or the tessellation evaluation shader enables an extension that allows
+    /// writing `gl_Layer`, but this extension is not supported by the backend.
+    VertexShaderLayerNotSupported,
+
+    /// The fragment shader enables a fragment shader interlock or ordering extension that is
+    /// not supported by the backend.
+    FragmentShaderInterlockNotSupported,
+
+    /// The transform feedback varyings are captured to more buffers than the backend supports.
+    /// See `get_max_transform_feedback_buffers`.
+    TransformFeedbackBuffersLimitExceeded {
+        /// Number of buffers that the varyings are captured to.
+        requested: usize,
+        /// Maximum number of buffers for the requested mode.
+        max: usize,
+    },
+
+    /// A shader includes a module that isn't registered in the `ShaderLibrary` of the context.
+    ShaderModuleNotFound(String),
+
+    /// A shader module includes itself, directly or through other modules.
+    RecursiveShaderModule(String),
+
+    /// An attribute that was given an explicit location in `attribute_locations` has a
+    /// different location after linking, usually because of a `layout(location = ...)`
+    /// qualifier in the vertex shader.
+    AttributeLocationMismatch {
+        /// Name of the attribute.
+        name: String,
+        /// The location that has been requested.
+        requested: u32,
+        /// The location of the attribute in the linked program.
+        obtained: i32,
+    },
 }
 
 impl fmt::Display for ProgramCreationError {
@@ -99,6 +253,14 @@ impl fmt::Display for ProgramCreationError {
                 write!(fmt, "{}: {}", self.description(), s),
             LinkingError(ref s) =>
                 write!(fmt, "{}: {}", self.description(), s),
+            TransformFeedbackBuffersLimitExceeded { requested, max } =>
+                write!(fmt, "{}: {} buffers requested, the maximum is {}", self.description(),
+                       requested, max),
+            ShaderModuleNotFound(ref name) | RecursiveShaderModule(ref name) =>
+                write!(fmt, "{}: `{}`", self.description(), name),
+            AttributeLocationMismatch { ref name, requested, obtained } =>
+                write!(fmt, "{}: `{}` requested at location {}, linked at location {}",
+                       self.description(), name, requested, obtained),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -117,12 +279,30 @@ impl Error for ProgramCreationError {
                 "One of the request shader type is not supported by the backend",
             CompilationNotSupported =>
                 "The backend doesn't support shaders compilation",
+            ShaderBinaryFormatNotSupported =>
+                "The format of the shader binaries is not supported by the backend.",
             TransformFeedbackNotSupported =>
                 "Transform feedback is not supported by the backend.",
             PointSizeNotSupported =>
                 "Point size is not supported by the backend.",
             BinaryHeaderError =>
                 "The glium-specific binary header was not found or is corrupt.",
+            FragmentOutputLocationNotSupported =>
+                "Binding fragment outputs to explicit locations is not supported by the backend.",
+            FramebufferFetchNotSupported =>
+                "Framebuffer fetch is not supported by the backend.",
+            VertexShaderLayerNotSupported =>
+                "Writing gl_Layer from vertex shaders is not supported by the backend.",
+            FragmentShaderInterlockNotSupported =>
+                "Fragment shader interlock is not supported by the backend.",
+            TransformFeedbackBuffersLimitExceeded { .. } =>
+                "Transform feedback varyings are captured to too many buffers.",
+            ShaderModuleNotFound(_) =>
+                "A shader includes a module that isn't registered in the shader library.",
+            RecursiveShaderModule(_) =>
+                "A shader module includes itself.",
+            AttributeLocationMismatch { .. } =>
+                "An attribute doesn't have the location that has been requested.",
         }
     }
 }
@@ -155,7 +335,7 @@ impl Error for ProgramChooserCreationError {
     }
 
     #[inline]
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::ProgramChooserCreationError::*;
         match *self {
             ProgramCreationError(ref err) => Some(err),
@@ -195,6 +375,37 @@ impl Error for GetBinaryError {
     }
 }
 
+/// Error while assigning a binding point to a uniform block or a shader storage block.
+#[derive(Copy, Clone, Debug)]
+pub enum BlockBindingError {
+    /// The program doesn't have any active block with this name.
+    NotFound,
+
+    /// The binding point is greater than or equal to the number of binding points supported by
+    /// the backend.
+    BindingOutOfRange {
+        /// Number of binding points supported by the backend.
+        max: u32,
+    },
+}
+
+impl fmt::Display for BlockBindingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for BlockBindingError {
+    fn description(&self) -> &str {
+        use self::BlockBindingError::*;
+        match *self {
+            NotFound => "The program doesn't have any active block with this name",
+            BindingOutOfRange { .. } =>
+                "The binding point is not supported by the backend",
+        }
+    }
+}
+
 /// Input when creating a program.
 pub enum ProgramCreationInput<'a> {
     /// Use GLSL source code.
@@ -218,8 +429,53 @@ pub enum ProgramCreationInput<'a> {
         ///
         /// The information specified here will be passed to the OpenGL linker. If you pass
         /// `None`, then you won't be able to use transform feedback.
+        ///
+        /// In separate mode, each variable is written to its own buffer. In interleaved mode,
+        /// the variables are written to the same buffer, unless they are split into groups with
+        /// `transform_feedback_buffers`. Creating the program returns
+        /// `TransformFeedbackBuffersLimitExceeded` if more buffers are used than
+        /// `get_max_transform_feedback_buffers` allows.
         transform_feedback_varyings: Option<(Vec<String>, TransformFeedbackMode)>,
 
+        /// The list of fragment shader outputs to bind to an explicit location.
+        ///
+        /// The locations are passed to the OpenGL linker and override the default assignment.
+        /// Outputs that are declared with a `layout(location = ...)` qualifier in the shader keep
+        /// the location of the qualifier. This is usually empty.
+        fragment_output_locations: Vec<(String, u32)>,
+
+        /// The list of vertex attributes to bind to an explicit location.
+        ///
+        /// The locations are passed to the OpenGL linker with `glBindAttribLocation`. If an
+        /// attribute ends up at another location, for example because of a conflicting
+        /// `layout(location = ...)` qualifier, then `AttributeLocationMismatch` is returned.
+        /// Names that don't correspond to an active attribute are ignored. This is usually empty.
+        ///
+        /// Programs whose attributes have the same names, types and locations share their
+        /// vertex array objects, which avoids creating new ones when switching between
+        /// programs that draw the same vertex buffers.
+        attribute_locations: Vec<(String, u32)>,
+
+        /// A list of `(name, value)` pairs. Each pair is turned into a `#define name value`
+        /// directive and inserted in each shader, after the `#version` directive and the
+        /// `#extension` directives that immediately follow it. This is usually empty.
+        ///
+        /// This allows building multiple permutations of the same shader without modifying its
+        /// source code.
+        defines: Vec<(String, String)>,
+
+        /// If true, shaders whose `#version` directive is `330` or `330 core` are adapted to
+        /// the best version of GLSL supported by the backend if GLSL 3.30 isn't available. This
+        /// is false by default.
+        ///
+        /// The adaptation is purely textual. The `#version` directive is replaced, `precision`
+        /// statements are added for GLSL ES, and with GLSL 1.10, 1.20 and GLSL ES 1.00 the
+        /// `in` and `out` qualifiers are replaced with `attribute` and `varying`, `texture` with
+        /// `texture2D`, and the outputs of the fragment shader with `gl_FragColor` or
+        /// `gl_FragData`. Shaders that use features that don't exist in the target version will
+        /// still fail to compile.
+        adapt_glsl_version: bool,
+
         /// Whether the fragment shader outputs colors in `sRGB` or `RGB`. This is false by default,
         /// meaning that the program outputs `RGB`.
         ///
@@ -231,6 +487,47 @@ pub enum ProgramCreationInput<'a> {
         uses_point_size: bool,
     },
 
+    /// Use GLSL source code of mesh shaders. The program must be drawn with
+    /// `Surface::draw_mesh_tasks`.
+    ///
+    /// Mesh shaders require the `GL_NV_mesh_shader` extension.
+    MeshShaders {
+        /// Source code of the optional task shader.
+        task_shader: Option<&'a str>,
+
+        /// Source code of the mesh shader.
+        mesh_shader: &'a str,
+
+        /// Source code of the fragment shader.
+        fragment_shader: &'a str,
+
+        /// See `SourceCode::outputs_srgb`.
+        outputs_srgb: bool,
+    },
+
+    /// Use precompiled vertex and fragment shaders, which are loaded with `glShaderBinary` and
+    /// then linked together.
+    ///
+    /// The format is vendor-specific and must be one of the formats of
+    /// `Capabilities::shader_binary_formats`. This is mostly useful on OpenGL ES devices that
+    /// don't have a compiler.
+    ShaderBinaries {
+        /// The format of the binaries.
+        format: u32,
+
+        /// Binary of the vertex shader.
+        vertex_shader: &'a [u8],
+
+        /// Binary of the fragment shader.
+        fragment_shader: &'a [u8],
+
+        /// See `SourceCode::outputs_srgb`.
+        outputs_srgb: bool,
+
+        /// Whether the shader uses point size.
+        uses_point_size: bool,
+    },
+
     /// Use a precompiled binary.
     Binary {
         /// The data.
@@ -275,6 +572,10 @@ impl<'a> From<SourceCode<'a>> for ProgramCreationInput<'a> {
             geometry_shader: geometry_shader,
             fragment_shader: fragment_shader,
             transform_feedback_varyings: None,
+            fragment_output_locations: Vec::new(),
+            attribute_locations: Vec::new(),
+            defines: Vec::new(),
+            adapt_glsl_version: false,
             outputs_srgb: false,
             uses_point_size: false,
         }
@@ -0,0 +1,368 @@
+//! Adapts shaders written for GLSL 3.30 core to older versions of GLSL and to GLSL ES.
+//!
+//! Only shaders whose `#version` directive is `330` or `330 core` are modified. The
+//! transformation is purely textual and handles the most common differences between versions:
+//!
+//! - The `#version` directive is replaced with the best version supported by the backend.
+//! - `precision` statements are added for GLSL ES.
+//! - `layout` qualifiers of inputs and outputs are removed if explicit locations are not
+//!   supported.
+//! - With GLSL 1.10, 1.20 and GLSL ES 1.00, `in` and `out` are replaced with `attribute` and
+//!   `varying`, `texture` is replaced with `texture2D`, and the outputs of the fragment shader
+//!   are replaced with `gl_FragColor` or `gl_FragData`.
+
+use std::borrow::Cow;
+
+use gl;
+use version::Api;
+use version::Version;
+use CapabilitiesSource;
+
+use program::shader::insert_after_header;
+
+/// The version of GLSL that a shader is ported to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Target {
+    /// GLSL 1.30, 1.40 or 1.50. Contains the number in the `#version` directive.
+    Desktop(u32),
+    /// GLSL 1.10 or 1.20. Contains the number in the `#version` directive.
+    LegacyDesktop(u32),
+    /// GLSL ES 3.00 or above. Contains the number in the `#version` directive.
+    Es(u32),
+    /// GLSL ES 1.00.
+    LegacyEs,
+}
+
+/// Adapts a shader written for GLSL 3.30 core to the versions supported by the backend.
+///
+/// Returns the source code unchanged if it isn't written for GLSL 3.30 or if GLSL 3.30 is
+/// supported.
+pub fn port_shader<'a, C: ?Sized>(ctxt: &C, shader_type: gl::types::GLenum, source_code: &'a str)
+                                  -> Cow<'a, str> where C: CapabilitiesSource
+{
+    let tokens = tokenize(source_code);
+
+    let version_token = match tokens.iter().find(|t| t.kind == TokenKind::Ignored &&
+                                                     is_version_330(t.text(source_code)))
+    {
+        Some(t) => *t,
+        None => return Cow::Borrowed(source_code),
+    };
+
+    let target = match choose_target(ctxt.get_capabilities().supported_glsl_versions.iter()) {
+        Some(t) => t,
+        None => return Cow::Borrowed(source_code),
+    };
+
+    let legacy = match target {
+        Target::LegacyDesktop(_) | Target::LegacyEs => true,
+        _ => false,
+    };
+
+    let strip_locations = match target {
+        Target::Es(_) => false,
+        _ => true,
+    };
+
+    // indices of the tokens that are not whitespaces, comments or preprocessor directives
+    let significant = tokens.iter().enumerate().filter(|&(_, t)| t.kind != TokenKind::Ignored)
+                            .map(|(i, _)| i).collect::<Vec<_>>();
+    let sig = |n: usize| -> Option<&str> {
+        significant.get(n).map(|&i| tokens[i].text(source_code))
+    };
+
+    // ranges of significant tokens to remove, and names of the fragment outputs
+    let mut removed = vec![false; tokens.len()];
+    let mut fragment_outputs: Vec<&str> = Vec::new();
+
+    let mut depth = 0i32;
+    let mut n = 0;
+    while n < significant.len() {
+        let text = sig(n).unwrap();
+
+        match text {
+            "{" | "(" | "[" => depth += 1,
+            "}" | ")" | "]" => depth -= 1,
+            _ => ()
+        }
+
+        if depth != 0 {
+            n += 1;
+            continue;
+        }
+
+        // finding the end of the `layout(...)` qualifier, if any
+        let (qualifier_start, after_layout) = if text == "layout" && sig(n + 1) == Some("(") {
+            let mut m = n + 1;
+            let mut layout_depth = 0;
+            loop {
+                match sig(m) {
+                    Some("(") => layout_depth += 1,
+                    Some(")") => { layout_depth -= 1; if layout_depth == 0 { break; } },
+                    None => break,
+                    _ => ()
+                }
+                m += 1;
+            }
+            (n, m + 1)
+        } else {
+            (n, n)
+        };
+
+        let storage = sig(after_layout);
+        let is_io = storage == Some("in") || storage == Some("out");
+
+        if after_layout != n && is_io && strip_locations {
+            for m in qualifier_start .. after_layout {
+                removed[significant[m]] = true;
+            }
+        }
+
+        // fragment outputs are declared as `out TYPE NAME;`
+        if legacy && shader_type == gl::FRAGMENT_SHADER && storage == Some("out") &&
+           sig(after_layout + 3) == Some(";")
+        {
+            fragment_outputs.push(sig(after_layout + 2).unwrap());
+            for m in qualifier_start .. after_layout + 4 {
+                removed[significant[m]] = true;
+            }
+            n = after_layout + 4;
+            continue;
+        }
+
+        n = if after_layout != n { after_layout } else { n + 1 };
+    }
+
+    // building the new source code
+    let mut result = String::with_capacity(source_code.len() + 64);
+    let mut depth = 0i32;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if removed[index] {
+            continue;
+        }
+
+        let text = token.text(source_code);
+
+        if index == version_token.index {
+            result.push_str(&version_directive(target));
+            continue;
+        }
+
+        if token.kind != TokenKind::Identifier {
+            match text {
+                "{" | "(" | "[" => depth += 1,
+                "}" | ")" | "]" => depth -= 1,
+                _ => ()
+            }
+
+            result.push_str(text);
+            continue;
+        }
+
+        if !legacy {
+            result.push_str(text);
+            continue;
+        }
+
+        let next = tokens[index + 1 ..].iter().find(|t| t.kind != TokenKind::Ignored)
+                                       .map(|t| t.text(source_code));
+
+        match text {
+            "in" if depth == 0 && shader_type == gl::VERTEX_SHADER => {
+                result.push_str("attribute");
+            },
+            "in" if depth == 0 => result.push_str("varying"),
+            "out" if depth == 0 && shader_type == gl::VERTEX_SHADER => {
+                result.push_str("varying");
+            },
+            "texture" if next == Some("(") => result.push_str("texture2D"),
+            _ => {
+                match fragment_outputs.iter().position(|&o| o == text) {
+                    Some(_) if fragment_outputs.len() == 1 => result.push_str("gl_FragColor"),
+                    Some(i) => result.push_str(&format!("gl_FragData[{}]", i)),
+                    None => result.push_str(text),
+                }
+            },
+        }
+    }
+
+    // GLSL ES requires a default precision for floats in fragment shaders
+    let precision = match target {
+        Target::Es(_) => Some("precision highp float;\nprecision highp int;\n"),
+        Target::LegacyEs if shader_type == gl::FRAGMENT_SHADER => {
+            Some("precision mediump float;\n")
+        },
+        _ => None,
+    };
+
+    match precision {
+        Some(precision) => Cow::Owned(insert_after_header(&result, precision)),
+        None => Cow::Owned(result),
+    }
+}
+
+/// Chooses the version to port a shader to. Returns `None` if GLSL 3.30 is supported or if no
+/// version is supported.
+fn choose_target<'a, I>(supported: I) -> Option<Target> where I: Iterator<Item = &'a Version> {
+    let mut best: Option<Target> = None;
+
+    for version in supported {
+        let candidate = match *version {
+            Version(Api::Gl, 3, 3) => return None,
+            Version(Api::Gl, 1, minor) if minor >= 3 => Target::Desktop(100 + minor as u32 * 10),
+            Version(Api::Gl, 1, minor) => Target::LegacyDesktop(100 + minor as u32 * 10),
+            Version(Api::Gl, _, _) => continue,
+            Version(Api::GlEs, 1, _) => Target::LegacyEs,
+            Version(Api::GlEs, major, minor) => {
+                Target::Es(major as u32 * 100 + minor as u32 * 10)
+            },
+        };
+
+        if best.map(|b| rank(candidate) > rank(b)).unwrap_or(true) {
+            best = Some(candidate);
+        }
+    }
+
+    best
+}
+
+/// Returns a value indicating how close a target is to GLSL 3.30. Higher is better.
+fn rank(target: Target) -> u32 {
+    match target {
+        Target::Desktop(v) => 2000 + v,
+        // GLSL ES 3.00 is the closest version to GLSL 3.30, and higher versions add features
+        // that we don't need
+        Target::Es(v) => 1000 + (1000 - v),
+        Target::LegacyDesktop(v) => 500 + v,
+        Target::LegacyEs => 0,
+    }
+}
+
+/// Returns the `#version` directive of a target.
+fn version_directive(target: Target) -> String {
+    match target {
+        Target::Desktop(v) | Target::LegacyDesktop(v) => format!("#version {}", v),
+        Target::Es(v) => format!("#version {} es", v),
+        Target::LegacyEs => "#version 100".to_owned(),
+    }
+}
+
+/// Returns true if the line is a `#version 330` or `#version 330 core` directive.
+fn is_version_330(line: &str) -> bool {
+    let line = line.trim();
+    if !line.starts_with('#') {
+        return false;
+    }
+
+    let mut words = line[1..].split_whitespace();
+    words.next() == Some("version") && words.next() == Some("330") &&
+        match words.next() { None | Some("core") => true, _ => false }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TokenKind {
+    /// An identifier or a keyword.
+    Identifier,
+    /// A number or a symbol.
+    Symbol,
+    /// Whitespaces, comments and preprocessor directives.
+    Ignored,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Token {
+    kind: TokenKind,
+    index: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    #[inline]
+    fn text<'a>(&self, source_code: &'a str) -> &'a str {
+        &source_code[self.start .. self.end]
+    }
+}
+
+/// Splits a source code into tokens. Concatenating all the tokens gives back the source code.
+fn tokenize(source_code: &str) -> Vec<Token> {
+    let bytes = source_code.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut line_start = true;
+
+    while pos < bytes.len() {
+        let start = pos;
+        let c = bytes[pos];
+
+        let kind = if c == b'#' && line_start {
+            while pos < bytes.len() && bytes[pos] != b'\n' { pos += 1; }
+            TokenKind::Ignored
+
+        } else if c == b'/' && bytes.get(pos + 1) == Some(&b'/') {
+            while pos < bytes.len() && bytes[pos] != b'\n' { pos += 1; }
+            TokenKind::Ignored
+
+        } else if c == b'/' && bytes.get(pos + 1) == Some(&b'*') {
+            pos += 2;
+            while pos < bytes.len() && !(bytes[pos - 1] == b'*' && bytes[pos] == b'/') {
+                pos += 1;
+            }
+            pos = if pos < bytes.len() { pos + 1 } else { pos };
+            TokenKind::Ignored
+
+        } else if c.is_ascii_whitespace() || c >= 0x80 {
+            while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] >= 0x80) {
+                if bytes[pos] == b'\n' { line_start = true; }
+                pos += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Ignored, index: tokens.len(),
+                                start: start, end: pos });
+            continue;
+
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            TokenKind::Identifier
+
+        } else if c.is_ascii_digit() || (c == b'.' && bytes.get(pos + 1)
+                                                           .map(|b| b.is_ascii_digit())
+                                                           .unwrap_or(false))
+        {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'.') {
+                pos += 1;
+            }
+            TokenKind::Symbol
+
+        } else {
+            pos += 1;
+            TokenKind::Symbol
+        };
+
+        line_start = false;
+        tokens.push(Token { kind: kind, index: tokens.len(), start: start, end: pos });
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use version::{Api, Version};
+    use super::{choose_target, Target};
+
+    #[test]
+    fn choose_best_target() {
+        let versions = [Version(Api::Gl, 1, 1), Version(Api::Gl, 1, 2), Version(Api::Gl, 1, 3)];
+        assert_eq!(choose_target(versions.iter()), Some(Target::Desktop(130)));
+
+        let versions = [Version(Api::GlEs, 1, 0), Version(Api::GlEs, 3, 0),
+                        Version(Api::GlEs, 3, 1)];
+        assert_eq!(choose_target(versions.iter()), Some(Target::Es(300)));
+
+        let versions = [Version(Api::Gl, 1, 5), Version(Api::Gl, 3, 3)];
+        assert_eq!(choose_target(versions.iter()), None);
+    }
+}
@@ -5,72 +5,200 @@ use version::Version;
 use version::Api;
 
 use backend::Facade;
+use context::Context;
+use context::FramebufferFetch;
+use context::VertexShaderLayer;
+use context::FragmentShaderInterlock;
 use CapabilitiesSource;
 
 use std::fmt;
+use std::borrow::Cow;
 use std::collections::hash_map::{self, HashMap};
 use std::hash::BuildHasherDefault;
+use std::rc::Rc;
 
 use fnv::FnvHasher;
 
+use ContextExt;
+use FboAttachments;
 use GlObject;
+use DrawError;
 use ProgramExt;
 use Handle;
 use RawUniformValue;
 
+use vertex_array_object;
+
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, Binary};
-use program::GetBinaryError;
+use program::{GetBinaryError, BlockBindingError};
+use program::ValidationReport;
+use program::{is_mesh_shader_supported, get_max_transform_feedback_buffers};
 
-use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
-use program::reflection::{Attribute, TransformFeedbackBuffer};
+use program::reflection::{Uniform, UniformBlock, OutputPrimitives, GeometryShaderLayout};
+use program::reflection::{Attribute, TransformFeedbackBuffer, FragmentOutput};
+use program::reflection::TransformFeedbackMode;
 use program::reflection::{SubroutineData, ShaderStage, SubroutineUniform};
-use program::shader::build_shader;
+use program::shader::{build_shader, build_shader_from_binary, get_requested_framebuffer_fetch};
+use program::shader::{get_requested_vertex_shader_layer, inject_defines};
+use program::shader::get_requested_fragment_shader_interlock;
+use program::library::{annotate_error, resolve_includes};
+use program::port::port_shader;
 
 use program::raw::RawProgram;
 
+use uniforms::{AsUniformValue, UniformHandle, UniformHandleError, Uniforms};
+use uniforms::UniformBlock as UniformBlockContent;
+
 use vertex::VertexFormat;
 
 /// A combination of shaders linked together.
+///
+/// Cloning a `Program` is cheap, as both objects share the same OpenGL program.
+#[derive(Clone)]
 pub struct Program {
-    raw: RawProgram,
+    raw: Rc<RawProgram>,
+    // declared after `raw` so that the program is destroyed before the context
+    context: Rc<Context>,
+    outputs_srgb: bool,
+    uses_point_size: bool,
+}
+
+/// A `Program` as it is stored in the cache of programs of the context.
+///
+/// Contrary to `Program`, this doesn't keep the context alive, as the cache belongs to the
+/// context. The context destroys the cached programs itself when it is destroyed.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct CachedProgram {
+    raw: Rc<RawProgram>,
+    outputs_srgb: bool,
+    uses_point_size: bool,
+}
+
+impl CachedProgram {
+    /// Destroys the OpenGL program. Must only be called by the context while it is destroyed.
+    #[inline]
+    pub fn destroy(&self, ctxt: &mut CommandContext) {
+        self.raw.destroy(ctxt);
+    }
+}
+
+/// Key of a program in the cache of programs.
+///
+/// The whole source code is part of the key, so that two different programs can never be
+/// confused. The revision of the shader library is part of the key too, so that modifying a
+/// module doesn't return programs that were built with its old source code.
+#[doc(hidden)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ProgramCacheKey {
+    library_revision: u64,
+    vertex_shader: String,
+    tessellation_control_shader: Option<String>,
+    tessellation_evaluation_shader: Option<String>,
+    geometry_shader: Option<String>,
+    fragment_shader: String,
+    transform_feedback_varyings: Option<(Vec<String>, TransformFeedbackMode)>,
+    fragment_output_locations: Vec<(String, u32)>,
+    attribute_locations: Vec<(String, u32)>,
+    defines: Vec<(String, String)>,
+    adapt_glsl_version: bool,
     outputs_srgb: bool,
     uses_point_size: bool,
 }
 
 impl Program {
     /// Builds a new program.
+    ///
+    /// If the cache of programs of the context is enabled (see
+    /// `Context::set_program_cache_capacity`) and a program with the same source code has
+    /// already been built, then this function returns the cached program.
     pub fn new<'a, F: ?Sized, I>(facade: &F, input: I) -> Result<Program, ProgramCreationError>
                          where I: Into<ProgramCreationInput<'a>>, F: Facade
     {
         let input = input.into();
 
+        let cache_key = match input {
+            ProgramCreationInput::SourceCode { .. } if
+                facade.get_context().get_program_cache().is_enabled() =>
+            {
+                let key = cache_key(&input,
+                                    facade.get_context().get_shader_library().get_revision());
+                if let Some(cached) = facade.get_context().get_program_cache().get(&key) {
+                    return Ok(Program {
+                        raw: cached.raw,
+                        context: facade.get_context().clone(),
+                        outputs_srgb: cached.outputs_srgb,
+                        uses_point_size: cached.uses_point_size,
+                    });
+                }
+                Some(key)
+            },
+            _ => None,
+        };
+
         let (raw, outputs_srgb, uses_point_size) = match input {
             ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
                                                tessellation_evaluation_shader, geometry_shader,
                                                fragment_shader, transform_feedback_varyings,
-                                               outputs_srgb, uses_point_size } =>
+                                               fragment_output_locations, attribute_locations,
+                                               defines, adapt_glsl_version, outputs_srgb,
+                                               uses_point_size } =>
             {
                 let mut has_geometry_shader = false;
                 let mut has_tessellation_control_shader = false;
                 let mut has_tessellation_evaluation_shader = false;
 
+                let ctxt = &**facade.get_context();
+                let library = ctxt.get_shader_library();
+
+                let mut used_modules = Vec::new();
+                let vertex_shader = try!(resolve_includes(library, vertex_shader,
+                                                          &mut used_modules));
+                let fragment_shader = try!(resolve_includes(library, fragment_shader,
+                                                            &mut used_modules));
+                let geometry_shader = match geometry_shader {
+                    Some(s) => Some(try!(resolve_includes(library, s, &mut used_modules))),
+                    None => None,
+                };
+                let tessellation_control_shader = match tessellation_control_shader {
+                    Some(s) => Some(try!(resolve_includes(library, s, &mut used_modules))),
+                    None => None,
+                };
+                let tessellation_evaluation_shader = match tessellation_evaluation_shader {
+                    Some(s) => Some(try!(resolve_includes(library, s, &mut used_modules))),
+                    None => None,
+                };
+
+                let preprocess = |source, ty| {
+                    preprocess_shader(ctxt, ty, source, adapt_glsl_version, &defines)
+                };
+
+                let vertex_shader = preprocess(&vertex_shader[..], gl::VERTEX_SHADER);
+                let fragment_shader = preprocess(&fragment_shader[..], gl::FRAGMENT_SHADER);
+                let geometry_shader = geometry_shader.as_ref()
+                                            .map(|s| preprocess(&s[..], gl::GEOMETRY_SHADER));
+                let tessellation_control_shader = tessellation_control_shader.as_ref()
+                                            .map(|s| preprocess(&s[..], gl::TESS_CONTROL_SHADER));
+                let tessellation_evaluation_shader = tessellation_evaluation_shader.as_ref()
+                                            .map(|s| preprocess(&s[..], gl::TESS_EVALUATION_SHADER));
+
                 let mut shaders = vec![
-                    (vertex_shader, gl::VERTEX_SHADER),
-                    (fragment_shader, gl::FRAGMENT_SHADER)
+                    (&vertex_shader[..], gl::VERTEX_SHADER),
+                    (&fragment_shader[..], gl::FRAGMENT_SHADER)
                 ];
 
-                if let Some(gs) = geometry_shader {
-                    shaders.push((gs, gl::GEOMETRY_SHADER));
+                if let Some(ref gs) = geometry_shader {
+                    shaders.push((&gs[..], gl::GEOMETRY_SHADER));
                     has_geometry_shader = true;
                 }
 
-                if let Some(ts) = tessellation_control_shader {
-                    shaders.push((ts, gl::TESS_CONTROL_SHADER));
+                if let Some(ref ts) = tessellation_control_shader {
+                    shaders.push((&ts[..], gl::TESS_CONTROL_SHADER));
                     has_tessellation_control_shader = true;
                 }
 
-                if let Some(ts) = tessellation_evaluation_shader {
-                    shaders.push((ts, gl::TESS_EVALUATION_SHADER));
+                if let Some(ref ts) = tessellation_evaluation_shader {
+                    shaders.push((&ts[..], gl::TESS_EVALUATION_SHADER));
                     has_tessellation_evaluation_shader = true;
                 }
 
@@ -82,23 +210,154 @@ impl Program {
                     return Err(ProgramCreationError::TransformFeedbackNotSupported);
                 }
 
+                if let Some((ref names, mode)) = transform_feedback_varyings {
+                    let requested = match mode {
+                        TransformFeedbackMode::Interleaved => {
+                            1 + names.iter().filter(|n| *n == "gl_NextBuffer").count()
+                        },
+                        TransformFeedbackMode::Separate => names.len(),
+                    };
+
+                    let max = get_max_transform_feedback_buffers(&**facade.get_context(), mode);
+                    if requested > max {
+                        return Err(ProgramCreationError::TransformFeedbackBuffersLimitExceeded {
+                            requested: requested,
+                            max: max,
+                        });
+                    }
+                }
+
+                if !fragment_output_locations.is_empty() &&
+                    !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) &&
+                    !facade.get_context().get_extensions().gl_ext_gpu_shader4
+                {
+                    return Err(ProgramCreationError::FragmentOutputLocationNotSupported);
+                }
+
                 if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
+                // giving a clear error instead of a compilation error
+                if let Some(fetch) = get_requested_framebuffer_fetch(&fragment_shader) {
+                    let supported = match fetch {
+                        FramebufferFetch::InoutOutputs => {
+                            facade.get_context().get_extensions().gl_ext_shader_framebuffer_fetch
+                        },
+                        FramebufferFetch::LastFragColor => {
+                            facade.get_context().get_extensions().gl_arm_shader_framebuffer_fetch
+                        },
+                    };
+
+                    if !supported {
+                        return Err(ProgramCreationError::FramebufferFetchNotSupported);
+                    }
+                }
+
+                let requested_layer = get_requested_vertex_shader_layer(&vertex_shader).or_else(|| {
+                    tessellation_evaluation_shader.as_ref()
+                                                  .and_then(|s| get_requested_vertex_shader_layer(s))
+                });
+
+                if let Some(layer) = requested_layer {
+                    let supported = match layer {
+                        VertexShaderLayer::LayerAndViewportIndex => {
+                            facade.get_context().get_extensions().gl_arb_shader_viewport_layer_array
+                        },
+                        VertexShaderLayer::Layer => {
+                            facade.get_context().get_extensions().gl_amd_vertex_shader_layer
+                        },
+                    };
+
+                    if !supported {
+                        return Err(ProgramCreationError::VertexShaderLayerNotSupported);
+                    }
+                }
+
+                if let Some(interlock) = get_requested_fragment_shader_interlock(&fragment_shader) {
+                    let supported = match interlock {
+                        FragmentShaderInterlock::Interlock => {
+                            facade.get_context().get_extensions().gl_arb_fragment_shader_interlock
+                        },
+                        FragmentShaderInterlock::Ordering => {
+                            facade.get_context().get_extensions().gl_intel_fragment_shader_ordering
+                        },
+                    };
+
+                    if !supported {
+                        return Err(ProgramCreationError::FragmentShaderInterlockNotSupported);
+                    }
+                }
+
                 let _lock = COMPILER_GLOBAL_LOCK.lock();
 
                 let shaders_store = {
                     let mut shaders_store = Vec::new();
                     for (src, ty) in shaders.into_iter() {
-                        shaders_store.push(try!(build_shader(facade, ty, src)));
+                        let shader = build_shader(facade, ty, src)
+                                        .map_err(|e| annotate_error(library, e, &used_modules));
+                        shaders_store.push(try!(shader));
                     }
                     shaders_store
                 };
 
                 (try!(RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
                                                has_tessellation_control_shader, has_tessellation_evaluation_shader,
-                                               transform_feedback_varyings)),
+                                               false, transform_feedback_varyings,
+                                               fragment_output_locations, attribute_locations)),
+                 outputs_srgb, uses_point_size)
+            },
+
+            ProgramCreationInput::MeshShaders { task_shader, mesh_shader, fragment_shader,
+                                                outputs_srgb } =>
+            {
+                if !is_mesh_shader_supported(&**facade.get_context()) {
+                    return Err(ProgramCreationError::ShaderTypeNotSupported);
+                }
+
+                let mut shaders = vec![(mesh_shader, gl::MESH_SHADER_NV),
+                                       (fragment_shader, gl::FRAGMENT_SHADER)];
+
+                if let Some(ts) = task_shader {
+                    shaders.push((ts, gl::TASK_SHADER_NV));
+                }
+
+                let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+                let shaders_store = {
+                    let mut shaders_store = Vec::new();
+                    for (src, ty) in shaders.into_iter() {
+                        shaders_store.push(try!(build_shader(facade, ty, src)));
+                    }
+                    shaders_store
+                };
+
+                (try!(RawProgram::from_shaders(facade, &shaders_store, false, false, false, true,
+                                               None, Vec::new(), Vec::new())),
+                 outputs_srgb, false)
+            },
+
+            ProgramCreationInput::ShaderBinaries { format, vertex_shader, fragment_shader,
+                                                   outputs_srgb, uses_point_size } =>
+            {
+                if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
+                    return Err(ProgramCreationError::PointSizeNotSupported);
+                }
+
+                let shaders = vec![(vertex_shader, gl::VERTEX_SHADER),
+                                   (fragment_shader, gl::FRAGMENT_SHADER)];
+
+                let shaders_store = {
+                    let mut shaders_store = Vec::new();
+                    for (binary, ty) in shaders.into_iter() {
+                        shaders_store.push(try!(build_shader_from_binary(facade, ty, format,
+                                                                         binary)));
+                    }
+                    shaders_store
+                };
+
+                (try!(RawProgram::from_shaders(facade, &shaders_store, false, false, false, false,
+                                               None, Vec::new(), Vec::new())),
                  outputs_srgb, uses_point_size)
             },
 
@@ -110,11 +369,22 @@ impl Program {
                 (try!(RawProgram::from_binary(facade, data)), outputs_srgb, uses_point_size)
             },
         };
-        Ok(Program {
-            raw: raw,
+        let program = Program {
+            raw: Rc::new(raw),
+            context: facade.get_context().clone(),
             outputs_srgb: outputs_srgb,
             uses_point_size: uses_point_size,
-        })
+        };
+
+        if let Some(key) = cache_key {
+            facade.get_context().get_program_cache().insert(key, CachedProgram {
+                raw: program.raw.clone(),
+                outputs_srgb: program.outputs_srgb,
+                uses_point_size: program.uses_point_size,
+            });
+        }
+
+        Ok(program)
     }
 
     /// Builds a new program from GLSL source code.
@@ -148,6 +418,10 @@ impl Program {
             tessellation_control_shader: None,
             tessellation_evaluation_shader: None,
             transform_feedback_varyings: None,
+            fragment_output_locations: Vec::new(),
+            attribute_locations: Vec::new(),
+            defines: Vec::new(),
+            adapt_glsl_version: false,
             outputs_srgb: false,
             uses_point_size: false,
         })
@@ -178,12 +452,51 @@ impl Program {
         self.raw.get_frag_data_location(name)
     }
 
+    /// Returns informations about an output of the fragment shader, if it exists.
+    ///
+    /// Always returns `None` if the backend doesn't support program interface queries. Use
+    /// `get_frag_data_location` if you only need the location.
+    #[inline]
+    pub fn get_fragment_output(&self, name: &str) -> Option<&FragmentOutput> {
+        self.raw.get_fragment_output(name)
+    }
+
+    /// Returns an iterator to the list of outputs of the fragment shader.
+    ///
+    /// The iterator is empty if the backend doesn't support program interface queries.
+    #[inline]
+    pub fn fragment_outputs(&self) -> hash_map::Iter<String, FragmentOutput> {
+        self.raw.fragment_outputs()
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    /// Resolves the location of a uniform once, so that it doesn't need to be looked up by its
+    /// name at each draw call.
+    ///
+    /// Returns an error if the program doesn't have an active uniform with this name, or if
+    /// `T` doesn't match the type of the uniform. The handle can then be used with
+    /// `glium::uniforms::HandledUniforms`, and only with this program.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// let matrix = program.uniform_handle::<[[f32; 4]; 4]>("matrix").unwrap();
+    /// ```
+    pub fn uniform_handle<T>(&self, name: &str) -> Result<UniformHandle<T>, UniformHandleError>
+                             where T: AsUniformValue + UniformBlockContent
+    {
+        match self.raw.get_uniform(name) {
+            Some(uniform) => UniformHandle::new(self.raw.get_id(), name, uniform),
+            None => Err(UniformHandleError::NotFound),
+        }
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -215,7 +528,11 @@ impl Program {
         self.raw.get_uniform_blocks()
     }
 
-    /// Returns the list of transform feedback varyings.
+    /// Returns the list of buffers that transform feedback writes to, ordered by binding point,
+    /// with the name, type and offset of each captured varying and the stride of each buffer.
+    ///
+    /// With OpenGL 4.4 or `GL_ARB_enhanced_layouts`, this includes the layout declared in the
+    /// shaders with the `xfb_buffer`, `xfb_offset` and `xfb_stride` qualifiers.
     #[inline]
     pub fn get_transform_feedback_buffers(&self) -> &[TransformFeedbackBuffer] {
         self.raw.get_transform_feedback_buffers()
@@ -230,6 +547,18 @@ impl Program {
         self.raw.transform_feedback_matches(format, stride)
     }
 
+    /// True if the transform feedback output of this program to the buffer `index` matches the
+    /// specified `VertexFormat` and `stride`.
+    ///
+    /// Contrary to `transform_feedback_matches`, this also works with programs that capture to
+    /// multiple buffers.
+    #[inline]
+    pub fn transform_feedback_buffer_matches(&self, index: usize, format: &VertexFormat,
+                                             stride: usize) -> bool
+    {
+        self.raw.transform_feedback_buffer_matches(index, format, stride)
+    }
+
     /// Returns the type of geometry that transform feedback would generate, or `None` if it
     /// depends on the vertex/index data passed when drawing.
     ///
@@ -240,6 +569,17 @@ impl Program {
         self.raw.get_output_primitives()
     }
 
+    /// Returns the output primitives, the maximum number of emitted vertices and the number of
+    /// invocations declared by the geometry shader, or `None` if the program doesn't contain a
+    /// geometry shader.
+    ///
+    /// This corresponds to `GL_GEOMETRY_OUTPUT_TYPE`, `GL_GEOMETRY_VERTICES_OUT` and
+    /// `GL_GEOMETRY_SHADER_INVOCATIONS`.
+    #[inline]
+    pub fn get_geometry_shader_layout(&self) -> Option<GeometryShaderLayout> {
+        self.raw.get_geometry_shader_layout()
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -264,6 +604,13 @@ impl Program {
         self.raw.has_geometry_shader()
     }
 
+    /// Returns true if the program contains a mesh shader. Such a program must be drawn with
+    /// `Surface::draw_mesh_tasks`.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.raw.has_mesh_shader()
+    }
+
     /// Returns informations about an attribute, if it exists.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
@@ -285,6 +632,58 @@ impl Program {
         self.raw.attributes()
     }
 
+    /// Checks that the given vertex formats can be used to draw with this program, without
+    /// drawing anything.
+    ///
+    /// Each attribute of the program must be provided by one of the formats, with the same
+    /// number of components. Integer and 64-bits attributes (`ivec`, `uvec`, `dvec`, etc.) must
+    /// be fed with data of the same kind that isn't normalized, while floating-point
+    /// attributes accept any type that OpenGL can convert. The same checks are performed
+    /// when drawing.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// # let vertex_buffer: glium::vertex::VertexBufferAny = unsafe { std::mem::uninitialized() };
+    /// program.check_vertex_formats(&[vertex_buffer.get_bindings()]).unwrap();
+    /// ```
+    #[inline]
+    pub fn check_vertex_formats(&self, formats: &[&VertexFormat]) -> Result<(), DrawError> {
+        vertex_array_object::check_attributes(formats.iter().map(|f| *f), self)
+    }
+
+    /// Checks whether drawing with this program, the given uniforms and the given framebuffer
+    /// would work, and returns a report of the problems.
+    ///
+    /// Glium checks that each value matches the type of its uniform (including the kind of
+    /// texture of samplers), that each active uniform and block has a value, that each value
+    /// corresponds to a uniform of the program, and that each output of the fragment shader has
+    /// a color attachment in the framebuffer. Then the uniforms and the framebuffer are bound
+    /// and `glValidateProgram` is called.
+    ///
+    /// This is intended to be used for debugging, for example when nothing is drawn on the
+    /// screen. Some of the issues, like unknown uniforms, are not errors when drawing.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// let frame = display.draw();
+    /// let report = program.validate(&glium::uniforms::EmptyUniforms, &frame);
+    /// if !report.is_valid() {
+    ///     println!("{:?}", report);
+    /// }
+    /// # frame.finish().unwrap();
+    /// ```
+    #[inline]
+    pub fn validate<U, F: ?Sized>(&self, uniforms: &U, framebuffer: &F) -> ValidationReport
+                                  where U: Uniforms, F: FboAttachments
+    {
+        self.raw.validate(uniforms, framebuffer)
+    }
+
     /// Returns true if the program has been configured to output sRGB instead of RGB.
     #[inline]
     pub fn has_srgb_output(&self) -> bool {
@@ -333,6 +732,141 @@ impl Program {
     pub fn uses_point_size(&self) -> bool {
       self.uses_point_size
     }
+
+    /// Returns true if glium skips uploading uniform values that are identical to the ones
+    /// that were last uploaded to this program.
+    ///
+    /// This is enabled by default.
+    #[inline]
+    pub fn uses_uniform_value_filtering(&self) -> bool {
+        self.raw.uses_uniform_value_filtering()
+    }
+
+    /// Enables or disables the filtering of redundant uniform values for this program.
+    ///
+    /// When enabled, glium remembers the last value of each uniform of the program and doesn't
+    /// call `glUniform*` again if a draw call uses the same value. This saves a lot of CPU time
+    /// when most uniforms don't change between draw calls.
+    ///
+    /// Filtering is enabled by default. You should disable it if you modify the uniforms of the
+    /// program yourself with raw OpenGL calls, as glium would then be unaware of the real values.
+    #[inline]
+    pub fn set_uniform_value_filtering(&self, enabled: bool) {
+        self.raw.set_uniform_value_filtering(enabled);
+    }
+
+    /// Returns the number of calls to `glUniform*` that glium has made for this program.
+    ///
+    /// This can be used to measure the effect of `set_uniform_value_filtering`.
+    #[inline]
+    pub fn get_uniform_uploads_count(&self) -> u64 {
+        self.raw.get_uniform_uploads_count()
+    }
+
+    /// Returns true if strict uniform checking is enabled for this program.
+    ///
+    /// This is disabled by default.
+    #[inline]
+    pub fn uses_strict_uniforms(&self) -> bool {
+        self.raw.uses_strict_uniforms()
+    }
+
+    /// Enables or disables strict uniform checking for this program.
+    ///
+    /// By default, values that don't correspond to a uniform of the program are ignored, and
+    /// active uniforms that have no value keep their previous value. When strict checking is
+    /// enabled, drawing instead returns `DrawError::UniformsMismatch` with the list of unknown
+    /// and missing uniforms. This is useful to catch typos or uniforms that have been renamed.
+    ///
+    /// Note that the compiler removes the uniforms that are not used by the shaders, and that
+    /// passing a value for them is then reported as an error.
+    #[inline]
+    pub fn set_strict_uniforms(&self, enabled: bool) {
+        self.raw.set_strict_uniforms(enabled);
+    }
+
+    /// Assigns a persistent binding point to a uniform block of the program.
+    ///
+    /// By default, glium chooses a binding point for each uniform block at each draw call. Once
+    /// a block has been given a binding point, its buffer is always bound there instead. Giving
+    /// the same binding point to the blocks of multiple programs allows the buffer to stay bound
+    /// when switching between these programs. See also `Context::reserve_uniform_block_binding`.
+    ///
+    /// The buffer must still be passed as a uniform when drawing.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// program.set_uniform_block_binding("Globals", 0).unwrap();
+    /// ```
+    #[inline]
+    pub fn set_uniform_block_binding(&self, name: &str, binding: u32)
+                                     -> Result<(), BlockBindingError>
+    {
+        self.raw.set_fixed_uniform_block_binding(name, binding)
+    }
+
+    /// Assigns a persistent binding point to a shader storage block of the program.
+    ///
+    /// See `set_uniform_block_binding`.
+    #[inline]
+    pub fn set_shader_storage_block_binding(&self, name: &str, binding: u32)
+                                            -> Result<(), BlockBindingError>
+    {
+        self.raw.set_fixed_shader_storage_block_binding(name, binding)
+    }
+}
+
+/// Applies the transformations requested at program creation to the source code of a shader.
+fn preprocess_shader<'a, C: ?Sized>(ctxt: &C, shader_type: gl::types::GLenum, source_code: &'a str,
+                                    adapt_glsl_version: bool, defines: &[(String, String)])
+                                    -> Cow<'a, str> where C: CapabilitiesSource
+{
+    let source_code = if adapt_glsl_version {
+        port_shader(ctxt, shader_type, source_code)
+    } else {
+        Cow::Borrowed(source_code)
+    };
+
+    match source_code {
+        Cow::Borrowed(s) => inject_defines(s, defines),
+        Cow::Owned(s) => Cow::Owned(inject_defines(&s, defines).into_owned()),
+    }
+}
+
+/// Builds the key of a program in the cache of programs.
+fn cache_key(input: &ProgramCreationInput, library_revision: u64) -> ProgramCacheKey {
+    match *input {
+        ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
+                                           tessellation_evaluation_shader, geometry_shader,
+                                           fragment_shader, ref transform_feedback_varyings,
+                                           ref fragment_output_locations,
+                                           ref attribute_locations, ref defines,
+                                           adapt_glsl_version, outputs_srgb,
+                                           uses_point_size } =>
+        {
+            ProgramCacheKey {
+                library_revision: library_revision,
+                vertex_shader: vertex_shader.to_owned(),
+                tessellation_control_shader: tessellation_control_shader.map(|s| s.to_owned()),
+                tessellation_evaluation_shader: tessellation_evaluation_shader
+                                                                    .map(|s| s.to_owned()),
+                geometry_shader: geometry_shader.map(|s| s.to_owned()),
+                fragment_shader: fragment_shader.to_owned(),
+                transform_feedback_varyings: transform_feedback_varyings.clone(),
+                fragment_output_locations: fragment_output_locations.clone(),
+                attribute_locations: attribute_locations.clone(),
+                defines: defines.clone(),
+                adapt_glsl_version: adapt_glsl_version,
+                outputs_srgb: outputs_srgb,
+                uses_point_size: uses_point_size,
+            }
+        },
+        ProgramCreationInput::MeshShaders { .. } => unreachable!(),
+        ProgramCreationInput::ShaderBinaries { .. } => unreachable!(),
+        ProgramCreationInput::Binary { .. } => unreachable!(),
+    }
 }
 
 impl fmt::Debug for Program {
@@ -363,13 +897,17 @@ impl ProgramExt for Program {
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
            ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
         {
-            if ctxt.state.enabled_framebuffer_srgb == self.outputs_srgb {
-                ctxt.state.enabled_framebuffer_srgb = !self.outputs_srgb;
+            // the default framebuffer is bound before the program is used
+            let convert = !self.outputs_srgb &&
+                          (ctxt.state.draw_framebuffer != 0 || ctxt.default_framebuffer_srgb);
 
-                if self.outputs_srgb {
-                    unsafe { ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB) };
-                } else {
+            if ctxt.state.enabled_framebuffer_srgb != convert {
+                ctxt.state.enabled_framebuffer_srgb = convert;
+
+                if convert {
                     unsafe { ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB) };
+                } else {
+                    unsafe { ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB) };
                 }
             }
         }
@@ -399,6 +937,20 @@ impl ProgramExt for Program {
         self.raw.set_shader_storage_block_binding(ctxt, block_location, value)
     }
 
+    #[inline]
+    fn get_fixed_uniform_block_binding(&self, block_location: gl::types::GLuint)
+                                       -> Option<gl::types::GLuint>
+    {
+        self.raw.get_fixed_uniform_block_binding(block_location)
+    }
+
+    #[inline]
+    fn get_fixed_shader_storage_block_binding(&self, block_location: gl::types::GLuint)
+                                              -> Option<gl::types::GLuint>
+    {
+        self.raw.get_fixed_shader_storage_block_binding(block_location)
+    }
+
     #[inline]
     fn set_subroutine_uniforms_for_stage(&self, ctxt: &mut CommandContext,
                                          stage: ShaderStage,
@@ -407,11 +959,21 @@ impl ProgramExt for Program {
         self.raw.set_subroutine_uniforms_for_stage(ctxt, stage, indices);
     }
 
+    #[inline]
+    fn uses_strict_uniforms(&self) -> bool {
+        self.raw.uses_strict_uniforms()
+    }
+
     #[inline]
     fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    #[inline]
+    fn get_uniforms(&self) -> &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>> {
+        ProgramExt::get_uniforms(&*self.raw)
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_uniform_blocks()
@@ -427,4 +989,9 @@ impl ProgramExt for Program {
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn get_attributes_layout(&self) -> u64 {
+        self.raw.get_attributes_layout()
+    }
 }
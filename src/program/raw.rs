@@ -1,4 +1,5 @@
 use gl;
+use utils::gl_get::{get_integer, get_output};
 
 use context::CommandContext;
 use version::Version;
@@ -10,15 +11,17 @@ use ContextExt;
 use UniformsExt;
 
 use std::{ffi, fmt, mem};
-use std::collections::hash_map::{self, HashMap};
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::collections::HashSet;
+use std::collections::hash_map::{self, DefaultHasher, HashMap};
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
 use std::os::raw;
-use std::hash::BuildHasherDefault;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
 
 use fnv::FnvHasher;
 
 use DrawError;
+use FboAttachments;
 use GlObject;
 use ProgramExt;
 use Handle;
@@ -27,45 +30,61 @@ use RawUniformValue;
 use QueryExt;
 use draw_parameters::TimeElapsedQuery;
 
+use fbo;
+use debug::ObjectKind;
 use buffer::BufferSlice;
 use BufferExt;
 use BufferSliceExt;
+use TransformFeedbackSessionExt;
 
-use program::{ProgramCreationError, Binary, GetBinaryError};
+use program::{ProgramCreationError, Binary, GetBinaryError, BlockBindingError};
+use program::{ValidationReport, ValidationIssue};
 use program::uniforms_storage::UniformsStorage;
+use vertex::TransformFeedbackSession;
 
 use program::compute::ComputeCommand;
 use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use program::reflection::{Attribute, TransformFeedbackMode, TransformFeedbackBuffer};
 use program::reflection::{SubroutineData, ShaderStage};
 use program::reflection::{reflect_uniforms, reflect_attributes, reflect_uniform_blocks};
-use program::reflection::{reflect_transform_feedback, reflect_geometry_output_type};
+use program::reflection::reflect_transform_feedback;
+use program::reflection::{reflect_geometry_shader_layout, GeometryShaderLayout};
 use program::reflection::{reflect_tess_eval_output_type, reflect_shader_storage_blocks};
-use program::reflection::{reflect_subroutine_data};
+use program::reflection::reflect_mesh_output_type;
+use program::reflection::{reflect_subroutine_data, reflect_fragment_outputs, FragmentOutput};
 use program::shader::Shader;
 use program::binary_header::{attach_glium_header, process_glium_header};
 
 use uniforms::Uniforms;
+use uniforms::UniformValue;
 
 use vertex::VertexFormat;
 use vertex_array_object::VertexAttributesSystem;
 
 /// A combination of shaders linked together.
 pub struct RawProgram {
-    context: Rc<Context>,
+    // the cache of programs of the context contains programs, so a strong reference here would
+    // create a cycle ; `Program` and `ComputeShader` are the ones that keep the context alive
+    context: Weak<Context>,
     id: Handle,
     uniform_values: UniformsStorage,
+    strict_uniforms: Cell<bool>,
     uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
     uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     subroutine_data: SubroutineData,
     attributes: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
     frag_data_locations: RefCell<HashMap<String, Option<u32>, BuildHasherDefault<FnvHasher>>>,
+    fragment_outputs: HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>,
     tf_buffers: Vec<TransformFeedbackBuffer>,
     ssbos: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     output_primitives: Option<OutputPrimitives>,
+    geometry_shader_layout: Option<GeometryShaderLayout>,
     has_geometry_shader: bool,
     has_tessellation_control_shader: bool,
     has_tessellation_evaluation_shader: bool,
+    has_mesh_shader: bool,
+    // identifies the names, types and locations of the attributes
+    attributes_layout: u64,
 }
 
 impl RawProgram {
@@ -74,7 +93,10 @@ impl RawProgram {
     pub fn from_shaders<'a, F: ?Sized, I>(facade: &'a F, shaders: I, has_geometry_shader: bool,
                                   has_tessellation_control_shader: bool,
                                   has_tessellation_evaluation_shader: bool,
-                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>)
+                                  has_mesh_shader: bool,
+                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>,
+                                  fragment_output_locations: Vec<(String, u32)>,
+                                  attribute_locations: Vec<(String, u32)>)
                                   -> Result<RawProgram, ProgramCreationError>
                                   where F: Facade, I: IntoIterator<Item = &'a Shader>
     {
@@ -138,6 +160,39 @@ impl RawProgram {
                 }
             }
 
+            // explicit locations of the fragment shader outputs
+            for (name, location) in fragment_output_locations.into_iter() {
+                let id = match id {
+                    Handle::Id(id) => id,
+                    Handle::Handle(_) => unreachable!()     // has been checked in the frontend
+                };
+
+                let name = ffi::CString::new(name.into_bytes()).unwrap();
+
+                if ctxt.version >= &Version(Api::Gl, 3, 0) {
+                    ctxt.gl.BindFragDataLocation(id, location, name.as_ptr());
+                } else if ctxt.extensions.gl_ext_gpu_shader4 {
+                    ctxt.gl.BindFragDataLocationEXT(id, location, name.as_ptr());
+                } else {
+                    unreachable!();     // has been checked in the frontend
+                }
+            }
+
+            // explicit locations of the vertex attributes
+            for &(ref name, location) in attribute_locations.iter() {
+                let name = ffi::CString::new(name.as_bytes()).unwrap();
+
+                match id {
+                    Handle::Id(id) => {
+                        ctxt.gl.BindAttribLocation(id, location, name.as_ptr());
+                    },
+                    Handle::Handle(id) => {
+                        assert!(ctxt.extensions.gl_arb_vertex_shader);
+                        ctxt.gl.BindAttribLocationARB(id, location, name.as_ptr());
+                    }
+                }
+            }
+
             // linking
             {
                 ctxt.report_debug_output_errors.set(false);
@@ -168,43 +223,86 @@ impl RawProgram {
         let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
         let tf_buffers = unsafe { reflect_transform_feedback(&mut ctxt, id) };
         let ssbos = unsafe { reflect_shader_storage_blocks(&mut ctxt, id) };
+        let fragment_outputs = unsafe { reflect_fragment_outputs(&mut ctxt, id) };
         let subroutine_data = unsafe {
             reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
                                     has_tessellation_control_shader,
                                     has_tessellation_evaluation_shader)
             };
 
-        let output_primitives = if has_geometry_shader {
-            Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
+        let geometry_shader_layout = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_shader_layout(&mut ctxt, id) })
+        } else {
+            None
+        };
+
+        let output_primitives = if let Some(layout) = geometry_shader_layout {
+            Some(layout.output_primitives)
         } else if has_tessellation_evaluation_shader {
             Some(unsafe { reflect_tess_eval_output_type(&mut ctxt, id) })
+        } else if has_mesh_shader {
+            Some(unsafe { reflect_mesh_output_type(&mut ctxt, id) })
         } else {
             None
         };
 
-        Ok(RawProgram {
-            context: facade.get_context().clone(),
+        // a `layout(location = ...)` qualifier takes precedence over `glBindAttribLocation`
+        let location_mismatch = attribute_locations.into_iter().filter_map(|(name, requested)| {
+            match attributes.get(&name) {
+                Some(attribute) if attribute.location != requested as i32 => {
+                    Some(ProgramCreationError::AttributeLocationMismatch {
+                        obtained: attribute.location,
+                        name: name,
+                        requested: requested,
+                    })
+                },
+                _ => None,
+            }
+        }).next();
+
+        if let Handle::Id(id) = id {
+            ctxt.live_objects.insert(ObjectKind::Program, id, 0, None, None, None);
+        }
+
+        let attributes_layout = hash_attributes_layout(&attributes);
+
+        let program = RawProgram {
+            context: Rc::downgrade(facade.get_context()),
             id: id,
             uniforms: uniforms,
             uniform_values: UniformsStorage::new(),
+            strict_uniforms: Cell::new(false),
             uniform_blocks: blocks,
             subroutine_data: subroutine_data,
             attributes: attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
+            fragment_outputs: fragment_outputs,
             tf_buffers: tf_buffers,
             ssbos: ssbos,
             output_primitives: output_primitives,
+            geometry_shader_layout: geometry_shader_layout,
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
-        })
+            has_mesh_shader: has_mesh_shader,
+            attributes_layout: attributes_layout,
+        };
+
+        if let Some(err) = location_mismatch {
+            // the program is destroyed when it is dropped, which requires the context
+            mem::drop(ctxt);
+            return Err(err);
+        }
+
+        Ok(program)
     }
 
     /// Creates a program from binary.
     pub fn from_binary<F: ?Sized>(facade: &F, binary: Binary)
                           -> Result<RawProgram, ProgramCreationError> where F: Facade
     {
-        let (has_geometry_shader, has_tessellation_control_shader, has_tessellation_evaluation_shader) = {
+        let (has_geometry_shader, has_tessellation_control_shader, has_tessellation_evaluation_shader,
+             has_mesh_shader) = {
             match process_glium_header(&binary.content) {
                 Some(flags) => flags,
                 None => return Err(ProgramCreationError::BinaryHeaderError)
@@ -232,42 +330,63 @@ impl RawProgram {
             id
         };
 
-        let (uniforms, attributes, blocks, tf_buffers, ssbos, subroutine_data) = unsafe {
+        let (uniforms, attributes, blocks, tf_buffers, ssbos, fragment_outputs,
+             subroutine_data) = unsafe {
             (
                 reflect_uniforms(&mut ctxt, id),
                 reflect_attributes(&mut ctxt, id),
                 reflect_uniform_blocks(&mut ctxt, id),
                 reflect_transform_feedback(&mut ctxt, id),
                 reflect_shader_storage_blocks(&mut ctxt, id),
+                reflect_fragment_outputs(&mut ctxt, id),
                 reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
                                         has_tessellation_control_shader,
                                         has_tessellation_evaluation_shader),
             )
         };
 
-        let output_primitives = if has_geometry_shader {
-            Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
+        let geometry_shader_layout = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_shader_layout(&mut ctxt, id) })
+        } else {
+            None
+        };
+
+        let output_primitives = if let Some(layout) = geometry_shader_layout {
+            Some(layout.output_primitives)
         } else if has_tessellation_evaluation_shader {
             Some(unsafe { reflect_tess_eval_output_type(&mut ctxt, id) })
+        } else if has_mesh_shader {
+            Some(unsafe { reflect_mesh_output_type(&mut ctxt, id) })
         } else {
             None
         };
 
+        if let Handle::Id(id) = id {
+            ctxt.live_objects.insert(ObjectKind::Program, id, 0, None, None, None);
+        }
+
+        let attributes_layout = hash_attributes_layout(&attributes);
+
         Ok(RawProgram {
-            context: facade.get_context().clone(),
+            context: Rc::downgrade(facade.get_context()),
             id: id,
             uniforms: uniforms,
             uniform_values: UniformsStorage::new(),
+            strict_uniforms: Cell::new(false),
             uniform_blocks: blocks,
             subroutine_data: subroutine_data,
             attributes: attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
+            fragment_outputs: fragment_outputs,
             tf_buffers: tf_buffers,
             ssbos: ssbos,
             output_primitives: output_primitives,
+            geometry_shader_layout: geometry_shader_layout,
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
+            has_mesh_shader: has_mesh_shader,
+            attributes_layout: attributes_layout,
         })
     }
 
@@ -277,7 +396,8 @@ impl RawProgram {
     /// the source code every time.
     pub fn get_binary(&self) -> Result<Binary, GetBinaryError> {
         unsafe {
-            let ctxt = self.context.make_current();
+            let context = self.get_context();
+            let ctxt = context.make_current();
 
             if ctxt.version >= &Version(Api::Gl, 4, 1) ||
                ctxt.extensions.gl_arb_get_programy_binary
@@ -287,16 +407,16 @@ impl RawProgram {
                     Handle::Handle(_) => unreachable!()
                 };
 
-                let mut num_supported_formats = mem::uninitialized();
-                ctxt.gl.GetIntegerv(gl::NUM_PROGRAM_BINARY_FORMATS, &mut num_supported_formats);
+                let num_supported_formats = get_integer(ctxt.gl, gl::NUM_PROGRAM_BINARY_FORMATS);
                 if num_supported_formats == 0 {
                     return Err(GetBinaryError::NoFormats)
                 }
 
-                let mut buf_len = mem::uninitialized();
-                ctxt.gl.GetProgramiv(id, gl::PROGRAM_BINARY_LENGTH, &mut buf_len);
+                let mut buf_len = get_output(|ptr| {
+                    ctxt.gl.GetProgramiv(id, gl::PROGRAM_BINARY_LENGTH, ptr)
+                });
 
-                let mut format = mem::uninitialized();
+                let mut format = 0;
                 let mut storage: Vec<u8> = Vec::with_capacity(buf_len as usize);
                 ctxt.gl.GetProgramBinary(id, buf_len, &mut buf_len, &mut format,
                                          storage.as_mut_ptr() as *mut _);
@@ -325,6 +445,11 @@ impl RawProgram {
     /// ```
     ///
     pub fn get_frag_data_location(&self, name: &str) -> Option<u32> {
+        // looking for a reflected output
+        if let Some(output) = self.fragment_outputs.get(name) {
+            return Some(output.location);
+        }
+
         // looking for a cached value
         if let Some(result) = self.frag_data_locations.borrow_mut().get(name) {
             return result.clone();
@@ -333,7 +458,8 @@ impl RawProgram {
         // querying opengl
         let name_c = ffi::CString::new(name.as_bytes()).unwrap();
 
-        let ctxt = self.context.make_current();
+        let context = self.get_context();
+        let ctxt = context.make_current();
 
         let value = unsafe {
             match self.id {
@@ -358,6 +484,22 @@ impl RawProgram {
         location
     }
 
+    /// Returns informations about an output of the fragment shader, if it exists.
+    ///
+    /// Always returns `None` if the backend doesn't support program interface queries.
+    #[inline]
+    pub fn get_fragment_output(&self, name: &str) -> Option<&FragmentOutput> {
+        self.fragment_outputs.get(name)
+    }
+
+    /// Returns an iterator to the list of outputs of the fragment shader.
+    ///
+    /// The iterator is empty if the backend doesn't support program interface queries.
+    #[inline]
+    pub fn fragment_outputs(&self) -> hash_map::Iter<String, FragmentOutput> {
+        self.fragment_outputs.iter()
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
@@ -395,7 +537,11 @@ impl RawProgram {
         &self.uniform_blocks
     }
 
-    /// Returns the list of transform feedback varyings.
+    /// Returns the list of buffers that transform feedback writes to, ordered by binding point,
+    /// with the name, type and offset of each captured varying and the stride of each buffer.
+    ///
+    /// With OpenGL 4.4 or `GL_ARB_enhanced_layouts`, this includes the layout declared in the
+    /// shaders with the `xfb_buffer`, `xfb_offset` and `xfb_stride` qualifiers.
     #[inline]
     pub fn get_transform_feedback_buffers(&self) -> &[TransformFeedbackBuffer] {
         &self.tf_buffers
@@ -406,13 +552,19 @@ impl RawProgram {
     ///
     /// The `stride` is the number of bytes between two vertices.
     pub fn transform_feedback_matches(&self, format: &VertexFormat, stride: usize) -> bool {
-        // TODO: doesn't support multiple buffers
-
-        if self.get_transform_feedback_buffers().len() != 1 {
-            return false;
-        }
+        self.get_transform_feedback_buffers().len() == 1 &&
+            self.transform_feedback_buffer_matches(0, format, stride)
+    }
 
-        let buf = &self.get_transform_feedback_buffers()[0];
+    /// True if the transform feedback output of this program to the buffer `index` matches the
+    /// specified `VertexFormat` and `stride`.
+    pub fn transform_feedback_buffer_matches(&self, index: usize, format: &VertexFormat,
+                                             stride: usize) -> bool
+    {
+        let buf = match self.get_transform_feedback_buffers().get(index) {
+            Some(buf) => buf,
+            None => return false,
+        };
 
         if buf.stride != stride {
             return false;
@@ -439,6 +591,13 @@ impl RawProgram {
         self.output_primitives
     }
 
+    /// Returns the output configuration of the geometry shader, or `None` if the program doesn't
+    /// contain a geometry shader.
+    #[inline]
+    pub fn get_geometry_shader_layout(&self) -> Option<GeometryShaderLayout> {
+        self.geometry_shader_layout
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -463,6 +622,12 @@ impl RawProgram {
         self.has_geometry_shader
     }
 
+    /// Returns true if the program contains a mesh shader.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.has_mesh_shader
+    }
+
     /// Returns informations about an attribute, if it exists.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
@@ -500,6 +665,121 @@ impl RawProgram {
         &self.ssbos
     }
 
+    /// Returns the context of the program.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the context has been destroyed, which can't happen as long as a `Program` or a
+    /// `ComputeShader` owns this object.
+    #[inline]
+    fn get_context(&self) -> Rc<Context> {
+        self.context.upgrade().expect("The context of the program has been destroyed")
+    }
+
+    /// Sends the command to destroy the OpenGL program.
+    ///
+    /// This is called when the program is dropped, and by the context for the programs of its
+    /// cache when it is destroyed itself.
+    pub fn destroy(&self, ctxt: &mut CommandContext) {
+        if let Handle::Id(id) = self.id {
+            ctxt.live_objects.remove(ObjectKind::Program, id);
+        }
+
+        unsafe {
+            match self.id {
+                Handle::Id(id) => {
+                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                            ctxt.version >= &Version(Api::GlEs, 2, 0));
+
+                    if ctxt.state.program == Handle::Id(id) {
+                        ctxt.gl.UseProgram(0);
+                        ctxt.state.program = Handle::Id(0);
+                    }
+
+                    ctxt.gl.DeleteProgram(id);
+                },
+                Handle::Handle(id) => {
+                    assert!(ctxt.extensions.gl_arb_shader_objects);
+
+                    if ctxt.state.program == Handle::Handle(id) {
+                        ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
+                        ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
+                    }
+
+                    ctxt.gl.DeleteObjectARB(id);
+                }
+            }
+        }
+    }
+
+    /// Returns true if uniform values that are identical to the last ones uploaded are skipped.
+    #[inline]
+    pub fn uses_uniform_value_filtering(&self) -> bool {
+        self.uniform_values.is_filtering_values()
+    }
+
+    /// Returns the number of calls to `glUniform*` that have been made for this program.
+    #[inline]
+    pub fn get_uniform_uploads_count(&self) -> u64 {
+        self.uniform_values.get_uploads_count()
+    }
+
+    /// Enables or disables the filtering of redundant uniform values.
+    #[inline]
+    pub fn set_uniform_value_filtering(&self, enabled: bool) {
+        self.uniform_values.set_filtering_values(enabled);
+    }
+
+    /// Returns true if drawing fails when the uniforms don't exactly match the active uniforms.
+    #[inline]
+    pub fn uses_strict_uniforms(&self) -> bool {
+        self.strict_uniforms.get()
+    }
+
+    /// Enables or disables the strict checking of uniforms.
+    #[inline]
+    pub fn set_strict_uniforms(&self, enabled: bool) {
+        self.strict_uniforms.set(enabled);
+    }
+
+    /// Assigns a persistent binding point to a uniform block of the program.
+    pub fn set_fixed_uniform_block_binding(&self, name: &str, binding: u32)
+                                           -> Result<(), BlockBindingError>
+    {
+        let block = match self.uniform_blocks.get(name) {
+            Some(block) => block,
+            None => return Err(BlockBindingError::NotFound),
+        };
+
+        let max = self.get_context().capabilities().max_indexed_uniform_buffer as u32;
+        if binding >= max {
+            return Err(BlockBindingError::BindingOutOfRange { max: max });
+        }
+
+        self.uniform_values.set_fixed_uniform_block_binding(block.id as gl::types::GLuint,
+                                                            binding);
+        Ok(())
+    }
+
+    /// Assigns a persistent binding point to a shader storage block of the program.
+    pub fn set_fixed_shader_storage_block_binding(&self, name: &str, binding: u32)
+                                                  -> Result<(), BlockBindingError>
+    {
+        let block = match self.ssbos.get(name) {
+            Some(block) => block,
+            None => return Err(BlockBindingError::NotFound),
+        };
+
+        let max = self.get_context().capabilities().max_indexed_shader_storage_buffer as u32;
+        if binding >= max {
+            return Err(BlockBindingError::BindingOutOfRange { max: max });
+        }
+
+        self.uniform_values.set_fixed_shader_storage_block_binding(block.id as gl::types::GLuint,
+                                                                   binding);
+        Ok(())
+    }
+
     /// Returns data associated with the programs subroutines.
     #[inline]
     pub fn get_subroutine_data(&self) -> &SubroutineData {
@@ -516,7 +796,8 @@ impl RawProgram {
                                       -> Result<(), DrawError>      // TODO: other error?
                                       where U: Uniforms
     {
-        let mut ctxt = self.context.make_current();
+        let context = self.get_context();
+        let mut ctxt = context.make_current();
 
         // TODO: return an error instead
         assert!(x < ctxt.capabilities.max_compute_work_group_count.0 as u32);
@@ -553,7 +834,8 @@ impl RawProgram {
                                                -> Result<(), DrawError>      // TODO: other error?
                                                where U: Uniforms
     {
-        let mut ctxt = self.context.make_current();
+        let context = self.get_context();
+        let mut ctxt = context.make_current();
 
         assert!(ctxt.version >= &Version(Api::Gl, 4, 3) ||
                 ctxt.version >= &Version(Api::GlEs, 3, 1) ||
@@ -584,6 +866,125 @@ impl RawProgram {
 
         Ok(())
     }
+
+    /// Checks whether drawing with this program, the given uniforms and the given framebuffer
+    /// would work.
+    pub fn validate<U, F: ?Sized>(&self, uniforms: &U, framebuffer: &F) -> ValidationReport
+                                  where U: Uniforms, F: FboAttachments
+    {
+        let mut issues = Vec::new();
+        let mut provided = HashSet::new();
+
+        uniforms.visit_values(|name, value| {
+            provided.insert(name.to_owned());
+
+            if let Some(uniform) = self.uniforms.get(name) {
+                if !value.is_usable_with(&uniform.ty) {
+                    issues.push(ValidationIssue::UniformTypeMismatch {
+                        name: name.to_owned(),
+                        expected: uniform.ty,
+                    });
+                }
+
+            } else if self.uniform_blocks.contains_key(name) || self.ssbos.contains_key(name) {
+                // the content of blocks is checked when binding them
+
+            } else if let UniformValue::Subroutine(stage, _) = value {
+                let key = (name.to_owned(), stage);
+                if !self.subroutine_data.subroutine_uniforms.contains_key(&key) {
+                    issues.push(ValidationIssue::UnknownUniform { name: name.to_owned() });
+                }
+
+            } else {
+                issues.push(ValidationIssue::UnknownUniform { name: name.to_owned() });
+            }
+        });
+
+        uniforms.visit_handles(|handle, value| {
+            provided.insert(handle.get_name().to_owned());
+
+            if handle.get_program() != self.id {
+                issues.push(ValidationIssue::UnknownUniform {
+                    name: handle.get_name().to_owned()
+                });
+            } else if !value.is_usable_with(&handle.get_type()) {
+                issues.push(ValidationIssue::UniformTypeMismatch {
+                    name: handle.get_name().to_owned(),
+                    expected: handle.get_type(),
+                });
+            }
+        });
+
+        for name in self.uniforms.keys() {
+            if !provided.contains(name) {
+                issues.push(ValidationIssue::MissingUniform { name: name.clone() });
+            }
+        }
+
+        for name in self.uniform_blocks.keys().chain(self.ssbos.keys()) {
+            if !provided.contains(name) {
+                issues.push(ValidationIssue::MissingBlock { name: name.clone() });
+            }
+        }
+
+        // the default framebuffer only has one color buffer
+        let draw_buffers = framebuffer.get_attachments()
+                                      .map(|a| a.get_draw_buffers().len())
+                                      .unwrap_or(1);
+
+        for (name, output) in self.fragment_outputs.iter() {
+            if output.location as usize + output.size > draw_buffers {
+                issues.push(ValidationIssue::UnattachedFragmentOutput {
+                    name: name.clone(),
+                    location: output.location,
+                });
+            }
+        }
+
+        // binding the same state as when drawing, then asking the backend
+        let context = self.get_context();
+        let mut ctxt = context.make_current();
+
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt,
+                                                    framebuffer.get_attachments());
+        unsafe { fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false) };
+
+        self.use_program(&mut ctxt);
+
+        let mut fences = Vec::with_capacity(0);
+        if uniforms.bind_uniforms(&mut ctxt, self, &mut fences).is_err() {
+            return ValidationReport {
+                issues: issues,
+                backend_valid: None,
+                backend_log: String::new(),
+            };
+        }
+
+        let mut status: gl::types::GLint = 0;
+
+        unsafe {
+            match self.id {
+                Handle::Id(id) => {
+                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                            ctxt.version >= &Version(Api::GlEs, 2, 0));
+                    ctxt.gl.ValidateProgram(id);
+                    ctxt.gl.GetProgramiv(id, gl::VALIDATE_STATUS, &mut status);
+                },
+                Handle::Handle(id) => {
+                    assert!(ctxt.extensions.gl_arb_shader_objects);
+                    ctxt.gl.ValidateProgramARB(id);
+                    ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_VALIDATE_STATUS_ARB,
+                                                    &mut status);
+                }
+            }
+        }
+
+        ValidationReport {
+            issues: issues,
+            backend_valid: Some(status != 0),
+            backend_log: unsafe { get_program_info_log(&mut ctxt, self.id) },
+        }
+    }
 }
 
 impl fmt::Debug for RawProgram {
@@ -608,12 +1009,19 @@ impl ProgramExt for RawProgram {
         unsafe {
             let program_id = self.get_id();
             if ctxt.state.program != program_id {
+                // the program can't be changed while a capture is running
+                TransformFeedbackSession::suspend(ctxt);
+
                 match program_id {
                     Handle::Id(id) => ctxt.gl.UseProgram(id),
                     Handle::Handle(id) => ctxt.gl.UseProgramObjectARB(id),
                 }
                 ctxt.state.program = program_id;
             }
+
+            if let Handle::Id(id) = program_id {
+                ctxt.live_objects.mark_used(ObjectKind::Program, id);
+            }
         }
     }
 
@@ -639,6 +1047,20 @@ impl ProgramExt for RawProgram {
         self.uniform_values.set_shader_storage_block_binding(ctxt, self.id, block_location, value);
     }
 
+    #[inline]
+    fn get_fixed_uniform_block_binding(&self, block_location: gl::types::GLuint)
+                                       -> Option<gl::types::GLuint>
+    {
+        self.uniform_values.get_fixed_uniform_block_binding(block_location)
+    }
+
+    #[inline]
+    fn get_fixed_shader_storage_block_binding(&self, block_location: gl::types::GLuint)
+                                              -> Option<gl::types::GLuint>
+    {
+        self.uniform_values.get_fixed_shader_storage_block_binding(block_location)
+    }
+
     #[inline]
     fn set_subroutine_uniforms_for_stage(&self, ctxt: &mut CommandContext,
                                          stage: ShaderStage,
@@ -647,11 +1069,21 @@ impl ProgramExt for RawProgram {
         self.uniform_values.set_subroutine_uniforms_for_stage(ctxt, self.id, stage, indices);
     }
 
+    #[inline]
+    fn uses_strict_uniforms(&self) -> bool {
+        self.strict_uniforms.get()
+    }
+
     #[inline]
     fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.uniforms.get(name)
     }
 
+    #[inline]
+    fn get_uniforms(&self) -> &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>> {
+        &self.uniforms
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         &self.uniform_blocks
@@ -667,44 +1099,53 @@ impl ProgramExt for RawProgram {
     fn get_subroutine_data(&self) -> &SubroutineData {
         &self.subroutine_data
     }
+
+    #[inline]
+    fn get_attributes_layout(&self) -> u64 {
+        self.attributes_layout
+    }
 }
 
 impl Drop for RawProgram {
     fn drop(&mut self) {
-        let mut ctxt = self.context.make_current();
+        // the context is being destroyed, and has already destroyed the program
+        let context = match self.context.upgrade() {
+            Some(context) => context,
+            None => return,
+        };
 
-        // removing VAOs which contain this program
-        VertexAttributesSystem::purge_program(&mut ctxt, self.id);
+        let mut ctxt = context.make_current();
 
-        // sending the destroy command
-        unsafe {
-            match self.id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
+        // removing VAOs which correspond to the attributes of this program
+        VertexAttributesSystem::purge_program(&mut ctxt, self.attributes_layout);
 
-                    if ctxt.state.program == Handle::Id(id) {
-                        ctxt.gl.UseProgram(0);
-                        ctxt.state.program = Handle::Id(0);
-                    }
-
-                    ctxt.gl.DeleteProgram(id);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
+        self.destroy(&mut ctxt);
 
-                    if ctxt.state.program == Handle::Handle(id) {
-                        ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
-                        ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
-                    }
-
-                    ctxt.gl.DeleteObjectARB(id);
-                }
-            }
+        if let Handle::Id(id) = self.id {
+            context.purge_shared_object(ObjectKind::Program, id);
         }
     }
 }
 
+/// Returns a value that identifies the names, types and locations of a list of attributes.
+///
+/// Programs with the same value can use the same vertex array objects.
+fn hash_attributes_layout(attributes: &HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>)
+                          -> u64
+{
+    let mut sorted = attributes.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut hasher = DefaultHasher::new();
+    for (name, attribute) in sorted {
+        name.hash(&mut hasher);
+        attribute.location.hash(&mut hasher);
+        attribute.ty.hash(&mut hasher);
+        attribute.size.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 /// Builds an empty program from within the GL context.
 unsafe fn create_program(ctxt: &mut CommandContext) -> Handle {
     let id = if ctxt.version >= &Version(Api::Gl, 2, 0) ||
@@ -727,7 +1168,7 @@ unsafe fn create_program(ctxt: &mut CommandContext) -> Handle {
 unsafe fn check_program_link_errors(ctxt: &mut CommandContext, id: Handle)
                                     -> Result<(), ProgramCreationError>
 {
-    let mut link_success: gl::types::GLint = mem::uninitialized();
+    let mut link_success: gl::types::GLint = 0;
 
     match id {
         Handle::Id(id) => {
@@ -761,42 +1202,47 @@ unsafe fn check_program_link_errors(ctxt: &mut CommandContext, id: Handle)
             }
         };
 
-        let mut error_log_size: gl::types::GLint = mem::uninitialized();
+        let msg = get_program_info_log(ctxt, id);
+        return Err(LinkingError(msg));
+    }
+
+    Ok(())
+}
+
+/// Returns the information log of a program.
+unsafe fn get_program_info_log(ctxt: &mut CommandContext, id: Handle) -> String {
+    let mut error_log_size: gl::types::GLint = 0;
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0));
-                ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
-                                                &mut error_log_size);
-            }
+            ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
+                                            &mut error_log_size);
         }
+    }
 
-        let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+    let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
                     ctxt.version >= &Version(Api::GlEs, 2, 0));
-                ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
-                                          error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+            ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
                                       error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            }
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+                                  error_log.as_mut_ptr() as *mut gl::types::GLchar);
         }
-
-        error_log.set_len(error_log_size as usize);
-
-        let msg = String::from_utf8(error_log).unwrap();
-        return Err(LinkingError(msg));
     }
 
-    Ok(())
+    error_log.set_len(error_log_size as usize);
+
+    String::from_utf8(error_log).unwrap()
 }
@@ -1,9 +1,10 @@
 use gl;
+use utils::gl_get::get_output;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use std::ffi;
-use std::mem;
 use std::ptr;
 use std::os::raw;
 
@@ -15,6 +16,7 @@ use version::Api;
 
 use uniforms::UniformType;
 use vertex::AttributeType;
+use vertex::VertexFormat;
 use program;
 
 use Handle;
@@ -122,10 +124,26 @@ pub struct Attribute {
     pub size: usize,
 }
 
+/// Information about an output of the fragment shader (except its name).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FragmentOutput {
+    /// The location of the output, ie. the index of the color attachment it writes to.
+    ///
+    /// If the output is an array, the elements occupy consecutive locations starting from this
+    /// one.
+    pub location: u32,
+
+    /// Type of the output.
+    pub ty: AttributeType,
+
+    /// Number of elements of the output.
+    pub size: usize,
+}
+
 /// Describes the layout of a buffer that can receive transform feedback output.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransformFeedbackBuffer {
-    /// Slot of this buffer.
+    /// Index of the binding point where the buffer must be bound.
     ///
     /// This is internal information, you probably don't need to use it.
     pub id: i32,
@@ -137,6 +155,25 @@ pub struct TransformFeedbackBuffer {
     pub stride: usize,
 }
 
+impl TransformFeedbackBuffer {
+    /// Builds the `VertexFormat` of the vertices written to this buffer.
+    ///
+    /// Together with `stride`, this can be used to create a buffer that receives the output of
+    /// the program without knowing the type of its vertices in advance, for example with
+    /// `VertexBuffer::new_raw`.
+    pub fn get_bindings(&self) -> VertexFormat {
+        self.elements.iter().map(|elem| {
+            (Cow::Owned(elem.name.clone()), elem.offset, elem.ty, false)
+        }).collect::<Vec<_>>().into()
+    }
+
+    /// Returns the number of bytes needed to store `vertices` vertices in this buffer.
+    #[inline]
+    pub fn get_size_bytes(&self, vertices: usize) -> usize {
+        self.stride * vertices
+    }
+}
+
 /// Describes a varying that is being output with transform feedback.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransformFeedbackVarying {
@@ -154,7 +191,7 @@ pub struct TransformFeedbackVarying {
 }
 
 /// Type of transform feedback. Only used with the legacy interface.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum TransformFeedbackMode {
     /// Each value is interleaved in the same buffer.
     Interleaved,
@@ -176,12 +213,32 @@ pub enum OutputPrimitives {
     Quads,
 }
 
+/// Output configuration of a geometry shader, as declared by its layout qualifiers.
+///
+/// Compare these values with `max_geometry_output_vertices` and
+/// `max_geometry_shader_invocations` in the capabilities to check that a geometry shader
+/// doesn't amplify the geometry more than the backend supports.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GeometryShaderLayout {
+    /// Type of the primitives that are emitted, declared with `layout(points)`,
+    /// `layout(line_strip)` or `layout(triangle_strip) out`.
+    pub output_primitives: OutputPrimitives,
+
+    /// Maximum number of vertices emitted by each invocation, declared with
+    /// `layout(max_vertices = N) out`.
+    pub max_vertices: u32,
+
+    /// Number of times the geometry shader is invoked for each input primitive, declared with
+    /// `layout(invocations = N) in`. This is 1 if geometry shader instancing is not supported.
+    pub invocations: u32,
+}
+
 pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
                                -> HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>
 {
     // number of active uniforms
     let active_uniforms = {
-        let mut active_uniforms: gl::types::GLint = mem::uninitialized();
+        let mut active_uniforms: gl::types::GLint = 0;
         match program {
             Handle::Id(program) => {
                 assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
@@ -205,8 +262,8 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
         let mut uniform_name_tmp: Vec<u8> = Vec::with_capacity(64);
         let mut uniform_name_tmp_len = 63;
 
-        let mut data_type: gl::types::GLenum = mem::uninitialized();
-        let mut data_size: gl::types::GLint = mem::uninitialized();
+        let mut data_type: gl::types::GLenum = 0;
+        let mut data_size: gl::types::GLint = 0;
 
         match program {
             Handle::Id(program) => {
@@ -286,7 +343,7 @@ pub unsafe fn reflect_attributes(ctxt: &mut CommandContext, program: Handle)
 {
     // number of active attributes
     let active_attributes = {
-        let mut active_attributes: gl::types::GLint = mem::uninitialized();
+        let mut active_attributes: gl::types::GLint = 0;
         match program {
             Handle::Id(program) => {
                 assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
@@ -310,8 +367,8 @@ pub unsafe fn reflect_attributes(ctxt: &mut CommandContext, program: Handle)
         let mut attr_name_tmp: Vec<u8> = Vec::with_capacity(64);
         let mut attr_name_tmp_len = 63;
 
-        let mut data_type: gl::types::GLenum = mem::uninitialized();
-        let mut data_size: gl::types::GLint = mem::uninitialized();
+        let mut data_type: gl::types::GLenum = 0;
+        let mut data_size: gl::types::GLint = 0;
 
         match program {
             Handle::Id(program) => {
@@ -377,8 +434,9 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
         _ => unreachable!()
     };
 
-    let mut active_blocks: gl::types::GLint = mem::uninitialized();
-    ctxt.gl.GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCKS, &mut active_blocks);
+    let active_blocks: gl::types::GLint = get_output(|ptr| {
+        ctxt.gl.GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCKS, ptr)
+    });
 
     // WORK-AROUND: AMD OpenGL ES drivers don't accept `GL_ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH`
     //              even though they report OpenGL ES 3.1. So we return early on if possible.
@@ -387,9 +445,9 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
         return HashMap::with_hasher(Default::default());
     }
 
-    let mut active_blocks_max_name_len: gl::types::GLint = mem::uninitialized();
-    ctxt.gl.GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH,
-                         &mut active_blocks_max_name_len);
+    let active_blocks_max_name_len: gl::types::GLint = get_output(|ptr| {
+        ctxt.gl.GetProgramiv(program, gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH, ptr)
+    });
 
     let mut blocks = HashMap::with_hasher(Default::default());
     blocks.reserve(active_blocks as usize);
@@ -409,19 +467,22 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
         };
 
         // binding point for this block
-        let mut binding: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetActiveUniformBlockiv(program, block_id as gl::types::GLuint,
-                                        gl::UNIFORM_BLOCK_BINDING, &mut binding);
+        let binding: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetActiveUniformBlockiv(program, block_id as gl::types::GLuint,
+                                            gl::UNIFORM_BLOCK_BINDING, ptr)
+        });
 
         // number of bytes
-        let mut block_size: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetActiveUniformBlockiv(program, block_id as gl::types::GLuint,
-                                        gl::UNIFORM_BLOCK_DATA_SIZE, &mut block_size);
+        let block_size: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetActiveUniformBlockiv(program, block_id as gl::types::GLuint,
+                                            gl::UNIFORM_BLOCK_DATA_SIZE, ptr)
+        });
 
         // number of members
-        let mut num_members: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetActiveUniformBlockiv(program, block_id as gl::types::GLuint,
-                                        gl::UNIFORM_BLOCK_ACTIVE_UNIFORMS, &mut num_members);
+        let num_members: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetActiveUniformBlockiv(program, block_id as gl::types::GLuint,
+                                            gl::UNIFORM_BLOCK_ACTIVE_UNIFORMS, ptr)
+        });
 
         // indices of the members
         let mut members_indices = ::std::iter::repeat(0).take(num_members as usize)
@@ -500,9 +561,16 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
         return Vec::with_capacity(0);
     }
 
+    // the program interface also knows about the `xfb_*` qualifiers of the shaders
+    if ctxt.version >= &Version(Api::Gl, 4, 4) ||
+       (ctxt.extensions.gl_arb_enhanced_layouts && ctxt.extensions.gl_arb_program_interface_query)
+    {
+        return reflect_transform_feedback_interface(ctxt, program);
+    }
+
     // querying the number of varying
     let num_varyings = {
-        let mut num_varyings: gl::types::GLint = mem::uninitialized();
+        let mut num_varyings: gl::types::GLint = 0;
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) {
             ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_VARYINGS, &mut num_varyings);
@@ -522,7 +590,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
 
     // querying "interleaved" or "separate"
     let buffer_mode = {
-        let mut buffer_mode: gl::types::GLint = mem::uninitialized();
+        let mut buffer_mode: gl::types::GLint = 0;
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) {
             ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_BUFFER_MODE, &mut buffer_mode);
@@ -536,7 +604,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
     };
 
     // the max length includes the null terminator
-    let mut max_buffer_len: gl::types::GLint = mem::uninitialized();
+    let mut max_buffer_len: gl::types::GLint = 0;
     if ctxt.version >= &Version(Api::Gl, 3, 0) {
         ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH,
                              &mut max_buffer_len);
@@ -553,8 +621,8 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
         let mut name_tmp: Vec<u8> = Vec::with_capacity(max_buffer_len as usize);
         let mut name_tmp_len = max_buffer_len;
 
-        let mut size = mem::uninitialized();
-        let mut ty = mem::uninitialized();
+        let mut size = 0;
+        let mut ty = 0;
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) {
             ctxt.gl.GetTransformFeedbackVarying(program, index, name_tmp_len, &mut name_tmp_len,
@@ -581,11 +649,29 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
                 });
             }
 
+            let current = result.len() - 1;
+
+            // markers of `GL_ARB_transform_feedback3`, whose type is `GL_NONE`
+            if name == "gl_NextBuffer" {
+                result.push(TransformFeedbackBuffer {
+                    id: (current + 1) as i32,
+                    elements: vec![],
+                    stride: 0,
+                });
+                continue;
+            }
+
+            if name.starts_with("gl_SkipComponents") {
+                // `size` is the number of skipped floats
+                result[current].stride += size as usize * 4;
+                continue;
+            }
+
             let ty = glenum_to_attribute_type(ty as gl::types::GLenum);
 
-            let prev_size = result[0].stride;
-            result[0].stride += size as usize * ty.get_size_bytes();
-            result[0].elements.push(TransformFeedbackVarying {        // TODO: handle arrays
+            let prev_size = result[current].stride;
+            result[current].stride += size as usize * ty.get_size_bytes();
+            result[current].elements.push(TransformFeedbackVarying {        // TODO: handle arrays
                 name: name,
                 size: size as usize * ty.get_size_bytes(),
                 offset: prev_size,
@@ -616,6 +702,90 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
     result
 }
 
+/// Same as `reflect_transform_feedback`, but uses the program interface query API.
+///
+/// Contrary to the legacy API, this takes into account the buffers, offsets and strides that
+/// the shaders declare with the `xfb_buffer`, `xfb_offset` and `xfb_stride` qualifiers.
+unsafe fn reflect_transform_feedback_interface(ctxt: &mut CommandContext,
+                                               program: gl::types::GLuint)
+                                               -> Vec<TransformFeedbackBuffer>
+{
+    let active_buffers = {
+        let active_buffers: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetProgramInterfaceiv(program, gl::TRANSFORM_FEEDBACK_BUFFER,
+                                          gl::ACTIVE_RESOURCES, ptr)
+        });
+        active_buffers as gl::types::GLuint
+    };
+
+    let mut result: Vec<TransformFeedbackBuffer> = (0 .. active_buffers).map(|buffer_id| {
+        let mut output: [gl::types::GLint; 2] = [0; 2];
+        ctxt.gl.GetProgramResourceiv(program, gl::TRANSFORM_FEEDBACK_BUFFER, buffer_id, 2,
+                                     [gl::BUFFER_BINDING,
+                                      gl::TRANSFORM_FEEDBACK_BUFFER_STRIDE].as_ptr(), 2,
+                                     ptr::null_mut(), output.as_mut_ptr() as *mut _);
+
+        TransformFeedbackBuffer {
+            id: output[0],
+            elements: vec![],
+            stride: output[1] as usize,
+        }
+    }).collect();
+
+    let active_varyings = {
+        let active_varyings: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetProgramInterfaceiv(program, gl::TRANSFORM_FEEDBACK_VARYING,
+                                          gl::ACTIVE_RESOURCES, ptr)
+        });
+        active_varyings as gl::types::GLuint
+    };
+
+    for varying_id in 0 .. active_varyings {
+        let (name_len, ty, array_size, offset, buffer_index) = {
+            let mut output: [gl::types::GLint; 5] = [0; 5];
+            ctxt.gl.GetProgramResourceiv(program, gl::TRANSFORM_FEEDBACK_VARYING, varying_id, 5,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE, gl::OFFSET,
+                                          gl::TRANSFORM_FEEDBACK_BUFFER_INDEX].as_ptr(), 5,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2] as usize,
+             output[3] as usize, output[4])
+        };
+
+        let name = {
+            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
+            let mut name_tmp_len = name_len as gl::types::GLsizei;
+
+            ctxt.gl.GetProgramResourceName(program, gl::TRANSFORM_FEEDBACK_VARYING, varying_id,
+                                           name_tmp_len, &mut name_tmp_len,
+                                           name_tmp.as_mut_ptr() as *mut _);
+            name_tmp.set_len(name_tmp_len as usize);
+            String::from_utf8(name_tmp).unwrap()
+        };
+
+        // the markers of `GL_ARB_transform_feedback3` are already accounted for in the strides
+        // of the buffers
+        if name == "gl_NextBuffer" || name.starts_with("gl_SkipComponents") || buffer_index < 0 {
+            continue;
+        }
+
+        let ty = glenum_to_attribute_type(ty);
+        result[buffer_index as usize].elements.push(TransformFeedbackVarying {
+            name: name,
+            size: array_size * ty.get_size_bytes(),
+            offset: offset,
+            ty: ty,
+        });
+    }
+
+    // buffers are in an unspecified order in the interface
+    result.sort_by_key(|buffer| buffer.id);
+    for buffer in result.iter_mut() {
+        buffer.elements.sort_by_key(|elem| elem.offset);
+    }
+
+    result
+}
+
 /// Obtains the type of data that the geometry shader stage outputs.
 ///
 /// # Unsafety
@@ -625,7 +795,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
 pub unsafe fn reflect_geometry_output_type(ctxt: &mut CommandContext, program: Handle)
                                            -> OutputPrimitives
 {
-    let mut value = mem::uninitialized();
+    let mut value = 0;
 
     match program {
         Handle::Id(program) => {
@@ -647,6 +817,48 @@ pub unsafe fn reflect_geometry_output_type(ctxt: &mut CommandContext, program: H
     }
 }
 
+/// Obtains the output configuration of the geometry shader stage.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a program.
+/// - The program **must** contain a geometry shader.
+pub unsafe fn reflect_geometry_shader_layout(ctxt: &mut CommandContext, program: Handle)
+                                             -> GeometryShaderLayout
+{
+    let output_primitives = reflect_geometry_output_type(ctxt, program);
+
+    let invocations_supported = ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                                ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                                ctxt.extensions.gl_arb_gpu_shader5 ||
+                                ctxt.extensions.gl_ext_geometry_shader ||
+                                ctxt.extensions.gl_oes_geometry_shader;
+
+    let mut max_vertices = 0;
+    let mut invocations = 1;
+
+    match program {
+        Handle::Id(program) => {
+            ctxt.gl.GetProgramiv(program, gl::GEOMETRY_VERTICES_OUT, &mut max_vertices);
+
+            if invocations_supported {
+                ctxt.gl.GetProgramiv(program, gl::GEOMETRY_SHADER_INVOCATIONS, &mut invocations);
+            }
+        },
+        Handle::Handle(program) => {
+            assert!(ctxt.extensions.gl_arb_vertex_shader);
+            ctxt.gl.GetObjectParameterivARB(program, gl::GEOMETRY_VERTICES_OUT,
+                                            &mut max_vertices);
+        }
+    };
+
+    GeometryShaderLayout {
+        output_primitives: output_primitives,
+        max_vertices: max_vertices as u32,
+        invocations: invocations as u32,
+    }
+}
+
 /// Obtains the type of data that the tessellation evaluation shader stage outputs.
 ///
 /// # Unsafety
@@ -656,7 +868,7 @@ pub unsafe fn reflect_geometry_output_type(ctxt: &mut CommandContext, program: H
 pub unsafe fn reflect_tess_eval_output_type(ctxt: &mut CommandContext, program: Handle)
                                             -> OutputPrimitives
 {
-    let mut value = mem::uninitialized();
+    let mut value = 0;
 
     match program {
         Handle::Id(program) => {
@@ -678,6 +890,100 @@ pub unsafe fn reflect_tess_eval_output_type(ctxt: &mut CommandContext, program:
     }
 }
 
+pub unsafe fn reflect_mesh_output_type(ctxt: &mut CommandContext, program: Handle)
+                                       -> OutputPrimitives
+{
+    let mut value = 0;
+
+    match program {
+        Handle::Id(program) => {
+            ctxt.gl.GetProgramiv(program, gl::MESH_OUTPUT_TYPE_NV, &mut value);
+        },
+        Handle::Handle(_) => unreachable!()     // mesh shaders aren't available with handles
+    };
+
+    match value as gl::types::GLenum {
+        gl::POINTS => OutputPrimitives::Points,
+        gl::LINES => OutputPrimitives::Lines,
+        gl::TRIANGLES => OutputPrimitives::Triangles,
+        _ => unreachable!()
+    }
+}
+
+/// Returns the list of outputs of the fragment shader of a program.
+///
+/// Returns an empty list if program interface queries are not supported by the backend.
+pub unsafe fn reflect_fragment_outputs(ctxt: &mut CommandContext, program: Handle)
+    -> HashMap<String, FragmentOutput, BuildHasherDefault<FnvHasher>>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+         ctxt.extensions.gl_arb_program_interface_query)
+    {
+        // not supported
+        return HashMap::with_hasher(Default::default());
+    }
+
+    let program = match program {
+        Handle::Id(program) => program,
+        Handle::Handle(_) => return HashMap::with_hasher(Default::default())
+    };
+
+    // number of active outputs
+    let active_outputs = {
+        let active_outputs: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetProgramInterfaceiv(program, gl::PROGRAM_OUTPUT,
+                                          gl::ACTIVE_RESOURCES, ptr)
+        });
+        active_outputs as gl::types::GLuint
+    };
+
+    // the result of this function
+    let mut outputs = HashMap::with_hasher(Default::default());
+    outputs.reserve(active_outputs as usize);
+
+    for output_id in 0 .. active_outputs {
+        let (name_len, ty, size, location) = {
+            let mut output: [gl::types::GLint; 4] = [0; 4];
+            ctxt.gl.GetProgramResourceiv(program, gl::PROGRAM_OUTPUT, output_id, 4,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE,
+                                          gl::LOCATION].as_ptr(), 4,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2] as usize, output[3])
+        };
+
+        let name = {
+            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
+            let mut name_tmp_len = name_len as gl::types::GLsizei;
+
+            ctxt.gl.GetProgramResourceName(program, gl::PROGRAM_OUTPUT, output_id,
+                                           name_tmp_len, &mut name_tmp_len,
+                                           name_tmp.as_mut_ptr() as *mut _);
+            name_tmp.set_len(name_tmp_len as usize);
+            String::from_utf8(name_tmp).unwrap()
+        };
+
+        // ignoring built-in outputs such as `gl_FragDepth`, which don't have a location
+        if name.starts_with("gl_") || location < 0 {
+            continue;
+        }
+
+        // arrays are reported as `name[0]`
+        let name = if name.ends_with("[0]") {
+            name[.. name.len() - 3].to_owned()
+        } else {
+            name
+        };
+
+        outputs.insert(name, FragmentOutput {
+            location: location as u32,
+            ty: glenum_to_attribute_type(ty),
+            size: size,
+        });
+    }
+
+    outputs
+}
+
 /// Returns the list of shader storage blocks of a program.
 pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program: Handle)
     -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
@@ -696,9 +1002,10 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
 
     // number of active SSBOs
     let active_blocks = {
-        let mut active_blocks: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetProgramInterfaceiv(program, gl::SHADER_STORAGE_BLOCK,
-                                      gl::ACTIVE_RESOURCES, &mut active_blocks);
+        let active_blocks: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetProgramInterfaceiv(program, gl::SHADER_STORAGE_BLOCK,
+                                          gl::ACTIVE_RESOURCES, ptr)
+        });
         active_blocks as gl::types::GLuint
     };
 
@@ -709,7 +1016,7 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
     for block_id in 0 .. active_blocks {
         // getting basic infos
         let (name_len, num_variables, binding, total_size) = {
-            let mut output: [gl::types::GLint; 4] = mem::uninitialized();
+            let mut output: [gl::types::GLint; 4] = [0; 4];
             ctxt.gl.GetProgramResourceiv(program, gl::SHADER_STORAGE_BLOCK, block_id, 4,
                                          [gl::NAME_LENGTH, gl::NUM_ACTIVE_VARIABLES,
                                           gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE].as_ptr(), 4,
@@ -743,7 +1050,7 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
         // iterator over variables
         let members = active_variables.into_iter().map(|variable| {
             let (ty, array_size, offset, _array_stride, name_len, top_level_array_size) = {
-                let mut output: [gl::types::GLint; 6] = mem::uninitialized();
+                let mut output: [gl::types::GLint; 6] = [0; 6];
                 ctxt.gl.GetProgramResourceiv(program, gl::BUFFER_VARIABLE,
                                              variable as gl::types::GLuint, 6,
                                              [gl::TYPE, gl::ARRAY_SIZE, gl::OFFSET,
@@ -952,6 +1259,7 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::SAMPLER_BUFFER => UniformType::SamplerBuffer,
         gl::SAMPLER_2D_RECT => UniformType::Sampler2dRect,
         gl::SAMPLER_2D_RECT_SHADOW => UniformType::Sampler2dRectShadow,
+        gl::SAMPLER_EXTERNAL_OES => UniformType::SamplerExternalOes,
         gl::INT_SAMPLER_1D => UniformType::ISampler1d,
         gl::INT_SAMPLER_2D => UniformType::ISampler2d,
         gl::INT_SAMPLER_3D => UniformType::ISampler3d,
@@ -1172,19 +1480,19 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext, program: Handle
     let mut subroutine_uniforms = HashMap::with_hasher(Default::default());
     let mut location_counts = HashMap::with_hasher(Default::default());
     for stage in shader_stages.iter() {
-        let mut location_count: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
-                                  gl::ACTIVE_SUBROUTINE_UNIFORM_LOCATIONS,
-                                  &mut location_count);
+        let location_count: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
+                                      gl::ACTIVE_SUBROUTINE_UNIFORM_LOCATIONS, ptr)
+        });
         location_counts.insert(*stage, location_count as usize);
-        let mut subroutine_count: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
-                                  gl::ACTIVE_SUBROUTINE_UNIFORMS,
-                                  &mut subroutine_count);
+        let subroutine_count: gl::types::GLint = get_output(|ptr| {
+            ctxt.gl.GetProgramStageiv(program, stage.to_gl_enum(),
+                                      gl::ACTIVE_SUBROUTINE_UNIFORMS, ptr)
+        });
         for i in 0..subroutine_count {
             // Get the name of the uniform
             let mut uniform_name_tmp: Vec<u8> = vec![0; 64];
-            let mut name_len: gl::types::GLsizei = mem::uninitialized();
+            let mut name_len: gl::types::GLsizei = 0;
             ctxt.gl.GetActiveSubroutineUniformName(program, stage.to_gl_enum(),
                                                    i as gl::types::GLuint,
                                                    (uniform_name_tmp.len() - 1) as gl::types::GLint,
@@ -1197,9 +1505,10 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext, program: Handle
             uniform_name_tmp.set_len(name_len as usize);
             let uniform_name = String::from_utf8(uniform_name_tmp).unwrap();
 
-            let mut size: gl::types::GLint = mem::uninitialized();
-            ctxt.gl.GetActiveSubroutineUniformiv(program, stage.to_gl_enum(), i as u32,
-                                         gl::UNIFORM_SIZE, &mut size);
+            let size: gl::types::GLint = get_output(|ptr| {
+                ctxt.gl.GetActiveSubroutineUniformiv(program, stage.to_gl_enum(), i as u32,
+                                             gl::UNIFORM_SIZE, ptr)
+            });
             let size = if size == 1 {
                 None
             } else {
@@ -1207,9 +1516,10 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext, program: Handle
             };
 
             // Get the number of compatible subroutines.
-            let mut compatible_count: gl::types::GLint = mem::uninitialized();
-            ctxt.gl.GetActiveSubroutineUniformiv(program, stage.to_gl_enum(), i as u32,
-                                                 gl::NUM_COMPATIBLE_SUBROUTINES, &mut compatible_count);
+            let compatible_count: gl::types::GLint = get_output(|ptr| {
+                ctxt.gl.GetActiveSubroutineUniformiv(program, stage.to_gl_enum(), i as u32,
+                                                     gl::NUM_COMPATIBLE_SUBROUTINES, ptr)
+            });
 
             // Get the indices of compatible subroutines.
             let mut compatible_sr_indices: Vec<gl::types::GLuint> = Vec::with_capacity(compatible_count as usize);
@@ -1221,7 +1531,7 @@ pub unsafe fn reflect_subroutine_data(ctxt: &mut CommandContext, program: Handle
             for j in 0..compatible_count {
                 // Get the names of compatible subroutines.
                 let mut subroutine_name_tmp: Vec<u8> = vec![0; 64];;
-                let mut name_len: gl::types::GLsizei = mem::uninitialized();
+                let mut name_len: gl::types::GLsizei = 0;
                 ctxt.gl.GetActiveSubroutineName(program, stage.to_gl_enum(), compatible_sr_indices[j as usize],
                                                 subroutine_name_tmp.len() as gl::types::GLint,
                                                 &mut name_len,
@@ -7,12 +7,16 @@ use backend::Facade;
 use context::Context;
 use ContextExt;
 
-use std::{ffi, mem, ptr};
+use std::{ffi, ptr};
+use std::borrow::Cow;
 use std::rc::Rc;
 
 use GlObject;
 use Handle;
 
+use context::FramebufferFetch;
+use context::VertexShaderLayer;
+use context::FragmentShaderInterlock;
 use program::ProgramCreationError;
 
 /// A single, compiled but unlinked, shader.
@@ -114,7 +118,7 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
 
         // checking compilation success by reading a flag on the shader
         let compilation_success = {
-            let mut compilation_success: gl::types::GLint = mem::uninitialized();
+            let mut compilation_success: gl::types::GLint = 0;
             match id {
                 Handle::Id(id) => {
                     assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
@@ -138,7 +142,7 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
 
         } else {
             // compilation error
-            let mut error_log_size: gl::types::GLint = mem::uninitialized();
+            let mut error_log_size: gl::types::GLint = 0;
 
             match id {
                 Handle::Id(id) => {
@@ -182,6 +186,289 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
     }
 }
 
+/// Builds an individual shader from a precompiled binary with `glShaderBinary`.
+pub fn build_shader_from_binary<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum,
+                                           format: gl::types::GLenum, binary: &[u8])
+                                           -> Result<Shader, ProgramCreationError> where F: Facade
+{
+    unsafe {
+        let mut ctxt = facade.get_context().make_current();
+
+        if !ctxt.capabilities.shader_binary_formats.contains(&format) {
+            return Err(ProgramCreationError::ShaderBinaryFormatNotSupported);
+        }
+
+        if !check_shader_type_compatibility(&mut ctxt, shader_type) {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
+
+        // `shader_binary_formats` is only non-empty with OpenGL ES 2, OpenGL 4.1 or
+        // `GL_ARB_ES2_compatibility`, which all provide core shader objects
+        let id = ctxt.gl.CreateShader(shader_type);
+        if id == 0 {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
+
+        // the binary is validated by the implementation, which reports an error if it is rejected
+        ctxt.report_debug_output_errors.set(false);
+        ctxt.gl.ShaderBinary(1, &id, format, binary.as_ptr() as *const _,
+                             binary.len() as gl::types::GLsizei);
+        let error = ctxt.gl.GetError();
+        ctxt.report_debug_output_errors.set(true);
+
+        if error == gl::NO_ERROR {
+            return Ok(Shader {
+                context: facade.get_context().clone(),
+                id: Handle::Id(id),
+            });
+        }
+
+        ctxt.gl.DeleteShader(id);
+
+        match error {
+            gl::INVALID_VALUE => {
+                Err(ProgramCreationError::CompilationError(format!("glShaderBinary triggered \
+                                                                    GL_INVALID_VALUE")))
+            },
+            gl::INVALID_OPERATION => {
+                Err(ProgramCreationError::CompilationError(format!("glShaderBinary triggered \
+                                                                    GL_INVALID_OPERATION")))
+            },
+            _ => {
+                Err(ProgramCreationError::CompilationError(format!("glShaderBinary triggered \
+                                                                    an unknown error")))
+            },
+        }
+    }
+}
+
+/// Returns the kind of framebuffer fetch that the source code enables through an `#extension`
+/// directive, if any.
+pub fn get_requested_framebuffer_fetch(source_code: &str) -> Option<FramebufferFetch> {
+    for line in source_code.lines() {
+        let mut tokens = line.split(|c: char| c.is_whitespace() || c == ':')
+                             .filter(|t| !t.is_empty());
+
+        if tokens.next() != Some("#extension") {
+            continue;
+        }
+
+        let kind = match tokens.next() {
+            Some("GL_EXT_shader_framebuffer_fetch") => FramebufferFetch::InoutOutputs,
+            Some("GL_ARM_shader_framebuffer_fetch") => FramebufferFetch::LastFragColor,
+            _ => continue,
+        };
+
+        if tokens.next() != Some("disable") {
+            return Some(kind);
+        }
+    }
+
+    None
+}
+
+/// Returns the way of writing `gl_Layer` that the source code enables through an `#extension`
+/// directive, if any.
+pub fn get_requested_vertex_shader_layer(source_code: &str) -> Option<VertexShaderLayer> {
+    for line in source_code.lines() {
+        let mut tokens = line.split(|c: char| c.is_whitespace() || c == ':')
+                             .filter(|t| !t.is_empty());
+
+        if tokens.next() != Some("#extension") {
+            continue;
+        }
+
+        let kind = match tokens.next() {
+            Some("GL_ARB_shader_viewport_layer_array") => VertexShaderLayer::LayerAndViewportIndex,
+            Some("GL_AMD_vertex_shader_layer") => VertexShaderLayer::Layer,
+            _ => continue,
+        };
+
+        if tokens.next() != Some("disable") {
+            return Some(kind);
+        }
+    }
+
+    None
+}
+
+/// Returns the kind of fragment shader interlock that the source code enables through an
+/// `#extension` directive, if any.
+pub fn get_requested_fragment_shader_interlock(source_code: &str)
+                                               -> Option<FragmentShaderInterlock>
+{
+    for line in source_code.lines() {
+        let mut tokens = line.split(|c: char| c.is_whitespace() || c == ':')
+                             .filter(|t| !t.is_empty());
+
+        if tokens.next() != Some("#extension") {
+            continue;
+        }
+
+        let kind = match tokens.next() {
+            Some("GL_ARB_fragment_shader_interlock") => FragmentShaderInterlock::Interlock,
+            Some("GL_INTEL_fragment_shader_ordering") => FragmentShaderInterlock::Ordering,
+            _ => continue,
+        };
+
+        if tokens.next() != Some("disable") {
+            return Some(kind);
+        }
+    }
+
+    None
+}
+
+/// Returns the offset in bytes of the end of the `#version` directive and of the `#extension`
+/// directives that immediately follow it. Returns `0` if there is no `#version` directive.
+///
+/// Blank lines and comments, such as a license header, are skipped.
+pub fn find_header_end(source_code: &str) -> usize {
+    parse_header(source_code).0
+}
+
+/// Returns the offset in bytes of the end of the header of a source code (see `find_header_end`),
+/// and the number and the profile of the `#version` directive if there is one.
+fn parse_header(source_code: &str) -> (usize, Option<(u32, String)>) {
+    let is_directive = |line: &str, name: &str| {
+        let line = line.trim_left();
+        line.starts_with('#') && line[1..].trim_left().starts_with(name)
+    };
+
+    let mut header_end = 0;
+    let mut version = None;
+    let mut offset = 0;
+    let mut in_comment = false;
+    // true if a comment starts within the header and isn't terminated yet
+    let mut extend_header = false;
+
+    for line in source_code.split('\n') {
+        offset += line.len() + 1;
+        let code = strip_comments(line, &mut in_comment);
+
+        if code.trim().is_empty() {
+            if extend_header {
+                header_end = offset;
+                extend_header = in_comment;
+            }
+        } else if version.is_none() && is_directive(&code, "version") {
+            let directive = code.trim_left()[1..].trim_left();
+            let mut tokens = directive["version".len() ..].split_whitespace();
+            let number = tokens.next().and_then(|n| n.parse().ok()).unwrap_or(110);
+            let profile = tokens.next().unwrap_or("").to_owned();
+            version = Some((number, profile));
+            header_end = offset;
+            extend_header = in_comment;
+        } else if version.is_some() && is_directive(&code, "extension") {
+            header_end = offset;
+            extend_header = in_comment;
+        } else {
+            break;
+        }
+    }
+
+    let header_end = if header_end > source_code.len() { source_code.len() } else { header_end };
+    (header_end, version)
+}
+
+/// Returns the code of a line of GLSL with its comments removed. `in_comment` indicates whether
+/// the line starts within a `/* */` comment, and is updated for the next line.
+fn strip_comments(line: &str, in_comment: &mut bool) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut rest = line;
+
+    loop {
+        if *in_comment {
+            match rest.find("*/") {
+                Some(end) => {
+                    *in_comment = false;
+                    code.push(' ');
+                    rest = &rest[end + 2 ..];
+                },
+                None => return code,
+            }
+        } else {
+            match (rest.find("//"), rest.find("/*")) {
+                (Some(line_comment), Some(block_comment)) if line_comment < block_comment => {
+                    code.push_str(&rest[.. line_comment]);
+                    return code;
+                },
+                (Some(line_comment), None) => {
+                    code.push_str(&rest[.. line_comment]);
+                    return code;
+                },
+                (_, Some(block_comment)) => {
+                    code.push_str(&rest[.. block_comment]);
+                    *in_comment = true;
+                    rest = &rest[block_comment + 2 ..];
+                },
+                (None, None) => {
+                    code.push_str(rest);
+                    return code;
+                },
+            }
+        }
+    }
+}
+
+/// Inserts some code after the header of a source code. See `find_header_end`.
+pub fn insert_after_header(source_code: &str, code: &str) -> String {
+    let header_end = find_header_end(source_code);
+
+    let mut result = String::with_capacity(source_code.len() + code.len() + 1);
+    result.push_str(&source_code[.. header_end]);
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str(code);
+    result.push_str(&source_code[header_end ..]);
+    result
+}
+
+/// Inserts a `#define NAME VALUE` directive for each element of `defines` into the source code.
+///
+/// The directives are inserted after the `#version` directive and the `#extension` directives
+/// that immediately follow it, and thus before any `precision` statement. If there is no
+/// `#version` directive, they are inserted at the start of the source code.
+///
+/// The directives are followed by a `#line` directive, so that the line numbers in the errors
+/// reported by the compiler match the original source code.
+pub fn inject_defines<'a>(source_code: &'a str, defines: &[(String, String)]) -> Cow<'a, str> {
+    if defines.is_empty() {
+        return Cow::Borrowed(source_code);
+    }
+
+    let (header_end, version) = parse_header(source_code);
+
+    // number of the first line of the source code after the header
+    let header = &source_code[.. header_end];
+    let mut next_line = header.matches('\n').count() + 1;
+    if !header.is_empty() && !header.ends_with('\n') {
+        next_line += 1;
+    }
+
+    // before GLSL 3.30 and GLSL ES 3.00, `#line N` sets the number of the next line to `N + 1`
+    let line_directive_sets_next_line = match version {
+        Some((number, ref profile)) => number >= 330 || (number >= 300 && profile == "es"),
+        None => false,
+    };
+    if !line_directive_sets_next_line {
+        next_line -= 1;
+    }
+
+    let mut directives = String::with_capacity(defines.len() * 32 + 16);
+    for &(ref name, ref value) in defines {
+        directives.push_str("#define ");
+        directives.push_str(name);
+        directives.push(' ');
+        directives.push_str(value);
+        directives.push('\n');
+    }
+    directives.push_str(&format!("#line {}\n", next_line));
+
+    Cow::Owned(insert_after_header(source_code, &directives))
+}
+
 pub fn check_shader_type_compatibility<C: ?Sized>(ctxt: &C, shader_type: gl::types::GLenum)
                                           -> bool where C: CapabilitiesSource
 {
@@ -207,6 +494,11 @@ pub fn check_shader_type_compatibility<C: ?Sized>(ctxt: &C, shader_type: gl::typ
                 return false;
             }
         },
+        gl::TASK_SHADER_NV | gl::MESH_SHADER_NV => {
+            if !ctxt.get_extensions().gl_nv_mesh_shader {
+                return false;
+            }
+        },
         gl::COMPUTE_SHADER => {
             if !(ctxt.get_version() >= &Version(Api::Gl, 4, 3))
                 && !(ctxt.get_version() >= &Version(Api::GlEs, 3, 1))
@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use RawUniformValue;
@@ -14,10 +14,17 @@ use version::Api;
 use program::reflection::ShaderStage;
 
 pub struct UniformsStorage {
+    // if false, `values` is ignored and every value is sent to OpenGL
+    filter_values: Cell<bool>,
+    // number of calls to `glUniform*` made through `set_uniform_value`
+    uploads: Cell<u64>,
     values: RefCell<HashMap<gl::types::GLint, Option<RawUniformValue>,
                             BuildHasherDefault<FnvHasher>>>,
     uniform_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
     shader_storage_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
+    // binding points chosen by the user, which are used instead of allocating one at each draw
+    fixed_uniform_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
+    fixed_shader_storage_blocks: RefCell<SmallVec<[Option<gl::types::GLuint>; 4]>>,
     subroutine_uniforms: RefCell<HashMap<ShaderStage, Vec<gl::types::GLuint>,
                                          BuildHasherDefault<FnvHasher>>>,
 }
@@ -27,20 +34,53 @@ impl UniformsStorage {
     #[inline]
     pub fn new() -> UniformsStorage {
         UniformsStorage {
+            filter_values: Cell::new(true),
+            uploads: Cell::new(0),
             values: RefCell::new(HashMap::with_hasher(Default::default())),
             uniform_blocks: RefCell::new(SmallVec::new()),
             shader_storage_blocks: RefCell::new(SmallVec::new()),
+            fixed_uniform_blocks: RefCell::new(SmallVec::new()),
+            fixed_shader_storage_blocks: RefCell::new(SmallVec::new()),
             subroutine_uniforms: RefCell::new(HashMap::with_hasher(Default::default())),
         }
     }
 
+    /// Returns the number of uniform values that have been sent to OpenGL.
+    #[inline]
+    pub fn get_uploads_count(&self) -> u64 {
+        self.uploads.get()
+    }
+
+    /// Returns true if redundant uniform values are filtered.
+    #[inline]
+    pub fn is_filtering_values(&self) -> bool {
+        self.filter_values.get()
+    }
+
+    /// Enables or disables the filtering of redundant uniform values. Disabling it also forgets
+    /// the values that were stored.
+    #[inline]
+    pub fn set_filtering_values(&self, enabled: bool) {
+        self.filter_values.set(enabled);
+
+        if !enabled {
+            self.values.borrow_mut().clear();
+        }
+    }
+
     /// Compares `value` with the value stored in this object. If the values differ, updates
     /// the storage and calls `glUniform`.
+    ///
+    /// If filtering is disabled, `glUniform` is always called.
     pub fn set_uniform_value(&self, ctxt: &mut CommandContext, program: Handle,
                              location: gl::types::GLint, value: &RawUniformValue)
     {
         let mut values = self.values.borrow_mut();
 
+        if !self.filter_values.get() {
+            values.remove(&location);
+        }
+
         // TODO: don't assume that, instead use DSA if the program is not current
         assert!(ctxt.state.program == program);
 
@@ -84,36 +124,36 @@ impl UniformsStorage {
         );
 
         match (value, values.entry(location).or_insert(None)) {
-            (&RawUniformValue::SignedInt(a), &mut Some(RawUniformValue::SignedInt(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt(a), &mut Some(RawUniformValue::UnsignedInt(b))) if a == b => (),
-            (&RawUniformValue::Float(a), &mut Some(RawUniformValue::Float(b))) if a == b => (),
-            (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a == b => (),
-            (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a == b => (),
-            (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a == b => (),
-            (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a == b => (),
-            (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a == b => (),
-            (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a == b => (),
-            (&RawUniformValue::IntVec2(a), &mut Some(RawUniformValue::IntVec2(b))) if a == b => (),
-            (&RawUniformValue::IntVec3(a), &mut Some(RawUniformValue::IntVec3(b))) if a == b => (),
-            (&RawUniformValue::IntVec4(a), &mut Some(RawUniformValue::IntVec4(b))) if a == b => (),
-            (&RawUniformValue::UnsignedIntVec2(a), &mut Some(RawUniformValue::UnsignedIntVec2(b))) if a == b => (),
-            (&RawUniformValue::UnsignedIntVec3(a), &mut Some(RawUniformValue::UnsignedIntVec3(b))) if a == b => (),
-            (&RawUniformValue::UnsignedIntVec4(a), &mut Some(RawUniformValue::UnsignedIntVec4(b))) if a == b => (),
-            (&RawUniformValue::Double(a), &mut Some(RawUniformValue::Double(b))) if a == b => (),
-            (&RawUniformValue::DoubleMat2(a), &mut Some(RawUniformValue::DoubleMat2(b))) if a == b => (),
-            (&RawUniformValue::DoubleMat3(a), &mut Some(RawUniformValue::DoubleMat3(b))) if a == b => (),
-            (&RawUniformValue::DoubleMat4(a), &mut Some(RawUniformValue::DoubleMat4(b))) if a == b => (),
-            (&RawUniformValue::DoubleVec2(a), &mut Some(RawUniformValue::DoubleVec2(b))) if a == b => (),
-            (&RawUniformValue::DoubleVec3(a), &mut Some(RawUniformValue::DoubleVec3(b))) if a == b => (),
-            (&RawUniformValue::DoubleVec4(a), &mut Some(RawUniformValue::DoubleVec4(b))) if a == b => (),
-            (&RawUniformValue::Int64(a), &mut Some(RawUniformValue::Int64(b))) if a == b => (),
-            (&RawUniformValue::Int64Vec2(a), &mut Some(RawUniformValue::Int64Vec2(b))) if a == b => (),
-            (&RawUniformValue::Int64Vec3(a), &mut Some(RawUniformValue::Int64Vec3(b))) if a == b => (),
-            (&RawUniformValue::Int64Vec4(a), &mut Some(RawUniformValue::Int64Vec4(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64(a), &mut Some(RawUniformValue::UnsignedInt64(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a == b => (),
-            (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a == b => (),
+            (&RawUniformValue::SignedInt(a), &mut Some(RawUniformValue::SignedInt(b))) if a == b => return,
+            (&RawUniformValue::UnsignedInt(a), &mut Some(RawUniformValue::UnsignedInt(b))) if a == b => return,
+            (&RawUniformValue::Float(a), &mut Some(RawUniformValue::Float(b))) if a == b => return,
+            (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a == b => return,
+            (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a == b => return,
+            (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a == b => return,
+            (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a == b => return,
+            (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a == b => return,
+            (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a == b => return,
+            (&RawUniformValue::IntVec2(a), &mut Some(RawUniformValue::IntVec2(b))) if a == b => return,
+            (&RawUniformValue::IntVec3(a), &mut Some(RawUniformValue::IntVec3(b))) if a == b => return,
+            (&RawUniformValue::IntVec4(a), &mut Some(RawUniformValue::IntVec4(b))) if a == b => return,
+            (&RawUniformValue::UnsignedIntVec2(a), &mut Some(RawUniformValue::UnsignedIntVec2(b))) if a == b => return,
+            (&RawUniformValue::UnsignedIntVec3(a), &mut Some(RawUniformValue::UnsignedIntVec3(b))) if a == b => return,
+            (&RawUniformValue::UnsignedIntVec4(a), &mut Some(RawUniformValue::UnsignedIntVec4(b))) if a == b => return,
+            (&RawUniformValue::Double(a), &mut Some(RawUniformValue::Double(b))) if a == b => return,
+            (&RawUniformValue::DoubleMat2(a), &mut Some(RawUniformValue::DoubleMat2(b))) if a == b => return,
+            (&RawUniformValue::DoubleMat3(a), &mut Some(RawUniformValue::DoubleMat3(b))) if a == b => return,
+            (&RawUniformValue::DoubleMat4(a), &mut Some(RawUniformValue::DoubleMat4(b))) if a == b => return,
+            (&RawUniformValue::DoubleVec2(a), &mut Some(RawUniformValue::DoubleVec2(b))) if a == b => return,
+            (&RawUniformValue::DoubleVec3(a), &mut Some(RawUniformValue::DoubleVec3(b))) if a == b => return,
+            (&RawUniformValue::DoubleVec4(a), &mut Some(RawUniformValue::DoubleVec4(b))) if a == b => return,
+            (&RawUniformValue::Int64(a), &mut Some(RawUniformValue::Int64(b))) if a == b => return,
+            (&RawUniformValue::Int64Vec2(a), &mut Some(RawUniformValue::Int64Vec2(b))) if a == b => return,
+            (&RawUniformValue::Int64Vec3(a), &mut Some(RawUniformValue::Int64Vec3(b))) if a == b => return,
+            (&RawUniformValue::Int64Vec4(a), &mut Some(RawUniformValue::Int64Vec4(b))) if a == b => return,
+            (&RawUniformValue::UnsignedInt64(a), &mut Some(RawUniformValue::UnsignedInt64(b))) if a == b => return,
+            (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a == b => return,
+            (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a == b => return,
+            (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a == b => return,
 
             (&RawUniformValue::SignedInt(v), target) => {
                 *target = Some(RawUniformValue::SignedInt(v));
@@ -310,6 +350,8 @@ impl UniformsStorage {
                 uniform_i64!(ctxt, Uniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
         }
+
+        self.uploads.set(self.uploads.get() + 1);
     }
 
     /// Compares `value` with the value stored in this object. If the values differ, updates
@@ -374,6 +416,36 @@ impl UniformsStorage {
         }
     }
 
+    /// Returns the binding point that the user assigned to a uniform block, if any.
+    #[inline]
+    pub fn get_fixed_uniform_block_binding(&self, location: gl::types::GLuint)
+                                           -> Option<gl::types::GLuint>
+    {
+        self.fixed_uniform_blocks.borrow().get(location as usize).and_then(|b| *b)
+    }
+
+    /// Assigns a binding point to a uniform block.
+    pub fn set_fixed_uniform_block_binding(&self, location: gl::types::GLuint,
+                                           value: gl::types::GLuint)
+    {
+        set_fixed_binding(&mut self.fixed_uniform_blocks.borrow_mut(), location, value);
+    }
+
+    /// Returns the binding point that the user assigned to a shader storage block, if any.
+    #[inline]
+    pub fn get_fixed_shader_storage_block_binding(&self, location: gl::types::GLuint)
+                                                  -> Option<gl::types::GLuint>
+    {
+        self.fixed_shader_storage_blocks.borrow().get(location as usize).and_then(|b| *b)
+    }
+
+    /// Assigns a binding point to a shader storage block.
+    pub fn set_fixed_shader_storage_block_binding(&self, location: gl::types::GLuint,
+                                                  value: gl::types::GLuint)
+    {
+        set_fixed_binding(&mut self.fixed_shader_storage_blocks.borrow_mut(), location, value);
+    }
+
     /// Compares `indices` to the value stored in this object. If the values differ,
     /// updates the programs subroutine uniform bindings.
     pub fn set_subroutine_uniforms_for_stage(&self, ctxt: &mut CommandContext,
@@ -395,3 +467,15 @@ impl UniformsStorage {
         }
     }
 }
+
+fn set_fixed_binding(blocks: &mut SmallVec<[Option<gl::types::GLuint>; 4]>,
+                     location: gl::types::GLuint, value: gl::types::GLuint)
+{
+    if blocks.len() <= location as usize {
+        for _ in blocks.len() .. location as usize + 1 {
+            blocks.push(None);
+        }
+    }
+
+    blocks[location as usize] = Some(value);
+}
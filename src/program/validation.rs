@@ -0,0 +1,69 @@
+use uniforms::UniformType;
+
+/// Report returned by `Program::validate`.
+#[derive(Clone, Debug)]
+pub struct ValidationReport {
+    /// The list of problems detected by glium.
+    pub issues: Vec<ValidationIssue>,
+
+    /// The result of `glValidateProgram`.
+    ///
+    /// Contains `None` if the uniforms couldn't be bound, in which case drawing would return an
+    /// error anyway.
+    pub backend_valid: Option<bool>,
+
+    /// The information log of the program after the call to `glValidateProgram`. Usually empty
+    /// if the program is valid, but some implementations also write warnings there.
+    pub backend_log: String,
+}
+
+impl ValidationReport {
+    /// Returns true if no problem has been detected, neither by glium nor by the backend.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty() && self.backend_valid != Some(false)
+    }
+}
+
+/// Problem detected by `Program::validate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// A value has been passed for a uniform that doesn't exist in the program.
+    ///
+    /// Note that the compiler removes the uniforms that are not used by the shaders.
+    UnknownUniform {
+        /// Name of the uniform.
+        name: String,
+    },
+
+    /// An active uniform of the program has no value. It will keep the value it had during the
+    /// previous draw, which is zero by default.
+    MissingUniform {
+        /// Name of the uniform.
+        name: String,
+    },
+
+    /// A uniform block or a shader storage block of the program has no buffer.
+    MissingBlock {
+        /// Name of the block.
+        name: String,
+    },
+
+    /// The type of a value doesn't match the type of the uniform. For samplers, this means that
+    /// the kind of texture doesn't match the type of sampler.
+    UniformTypeMismatch {
+        /// Name of the uniform.
+        name: String,
+        /// The type of the uniform in the program.
+        expected: UniformType,
+    },
+
+    /// An output of the fragment shader writes to a location that has no color attachment in
+    /// the framebuffer. The values written to this output are discarded.
+    UnattachedFragmentOutput {
+        /// Name of the output.
+        name: String,
+        /// Location of the output.
+        location: u32,
+    },
+}
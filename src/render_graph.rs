@@ -0,0 +1,435 @@
+/*!
+Scheduling the passes of a frame and the transient textures that they use.
+
+A `RenderGraph` is a list of passes, each of which declares the transient targets that it reads
+from and the ones that it writes to. A pass is a closure that receives a `PassContext`, which
+gives access to the textures of the pass and to a surface that draws to the targets it writes.
+
+When the graph is executed:
+
+ - The graph is checked: a target must be written by a pass before another pass can read it,
+   and a pass can't read a target that it writes to.
+ - Passes whose outputs are never read are skipped. Passes that don't write to any target are
+   always executed, as they usually draw to the screen.
+ - The textures are acquired from a `TargetPool` right before the first pass that writes to them,
+   and are released after the last pass that uses them. The content of a texture is invalidated
+   when it is released, and the texture can then be reused by another target of the same format
+   and size later in the same frame.
+ - The remaining passes are executed in the order in which they have been added.
+
+Memory barriers between passes are inserted by glium automatically, like for any other draw.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# use glium::Surface;
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let blur: glium::post_process::FullscreenPass = unsafe { ::std::mem::uninitialized() };
+# let tonemap: glium::post_process::FullscreenPass = unsafe { ::std::mem::uninitialized() };
+use glium::framebuffer::TargetPool;
+use glium::render_graph::RenderGraph;
+use glium::texture::UncompressedFloatFormat;
+
+let mut pool = TargetPool::new(&display);
+
+// each frame
+let mut frame = display.draw();
+{
+    // the passes are `move` closures, as they are stored in the graph
+    let (blur, tonemap, frame) = (&blur, &tonemap, &mut frame);
+
+    let mut graph = RenderGraph::new();
+    let scene = graph.create_target("scene", UncompressedFloatFormat::F16F16F16F16, 1.0);
+    let blurred = graph.create_target("blurred", UncompressedFloatFormat::F16F16F16F16, 0.5);
+
+    graph.add_pass("scene", &[], &[scene], move |pass| {
+        let mut surface = pass.surface();
+        surface.clear_color(0.0, 0.0, 0.0, 1.0);
+        // draw the scene
+        Ok(())
+    });
+
+    graph.add_pass("blur", &[scene], &[blurred], move |pass| {
+        blur.draw(&mut pass.surface(), pass.texture(scene), &glium::uniforms::EmptyUniforms)
+    });
+
+    graph.add_pass("tonemap", &[blurred], &[], move |pass| {
+        tonemap.draw(&mut *frame, pass.texture(blurred), &glium::uniforms::EmptyUniforms)
+    });
+
+    graph.execute(&mut pool).unwrap();
+}
+frame.finish().unwrap();
+pool.end_frame();
+# }
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use context::Context;
+use framebuffer::{MultiOutputFrameBuffer, PooledTarget, SimpleFrameBuffer};
+use framebuffer::{TargetPool, TargetPoolError};
+use texture::{Texture2d, UncompressedFloatFormat};
+
+use DrawError;
+
+/// Identifies a transient target of a `RenderGraph`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceId(usize);
+
+/// Error that can happen while executing a `RenderGraph`.
+#[derive(Clone, Debug)]
+pub enum RenderGraphError {
+    /// A pass reads a target that no previous pass writes to.
+    ReadBeforeWrite {
+        /// Name of the pass.
+        pass: String,
+        /// Name of the target.
+        target: String,
+    },
+
+    /// A pass reads a target that it also writes to.
+    ReadWriteConflict {
+        /// Name of the pass.
+        pass: String,
+        /// Name of the target.
+        target: String,
+    },
+
+    /// A pass writes to several targets that don't have the same format or relative size.
+    MismatchedOutputs {
+        /// Name of the pass.
+        pass: String,
+    },
+
+    /// Failed to acquire the texture of a target.
+    TargetPoolError(TargetPoolError),
+
+    /// A pass returned an error.
+    DrawError {
+        /// Name of the pass.
+        pass: String,
+        /// The error returned by the pass.
+        error: DrawError,
+    },
+}
+
+impl fmt::Display for RenderGraphError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use self::RenderGraphError::*;
+        match *self {
+            ReadBeforeWrite { ref pass, ref target } =>
+                write!(fmt, "{}: pass `{}`, target `{}`", self.description(), pass, target),
+            ReadWriteConflict { ref pass, ref target } =>
+                write!(fmt, "{}: pass `{}`, target `{}`", self.description(), pass, target),
+            MismatchedOutputs { ref pass } =>
+                write!(fmt, "{}: pass `{}`", self.description(), pass),
+            TargetPoolError(ref err) => write!(fmt, "{}: {}", self.description(), err),
+            DrawError { ref pass, ref error } =>
+                write!(fmt, "{}: pass `{}`: {}", self.description(), pass, error),
+        }
+    }
+}
+
+impl Error for RenderGraphError {
+    fn description(&self) -> &str {
+        use self::RenderGraphError::*;
+        match *self {
+            ReadBeforeWrite { .. } =>
+                "A pass reads a target that no previous pass writes to",
+            ReadWriteConflict { .. } =>
+                "A pass reads a target that it also writes to",
+            MismatchedOutputs { .. } =>
+                "A pass writes to targets that don't have the same format or relative size",
+            TargetPoolError(_) =>
+                "Failed to acquire the texture of a target",
+            DrawError { .. } =>
+                "A pass returned an error",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::RenderGraphError::*;
+        match *self {
+            TargetPoolError(ref err) => Some(err),
+            DrawError { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<TargetPoolError> for RenderGraphError {
+    #[inline]
+    fn from(err: TargetPoolError) -> RenderGraphError {
+        RenderGraphError::TargetPoolError(err)
+    }
+}
+
+/// Description of a transient target.
+struct Target {
+    name: String,
+    format: UncompressedFloatFormat,
+    scale: f32,
+}
+
+/// A pass of the graph.
+struct Pass<'a> {
+    name: String,
+    reads: Vec<ResourceId>,
+    writes: Vec<ResourceId>,
+    callback: Box<FnMut(&PassContext) -> Result<(), DrawError> + 'a>,
+}
+
+/// A list of passes and of the transient targets that they use.
+pub struct RenderGraph<'a> {
+    targets: Vec<Target>,
+    passes: Vec<Pass<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    /// Builds an empty graph.
+    #[inline]
+    pub fn new() -> RenderGraph<'a> {
+        RenderGraph {
+            targets: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    /// Declares a transient target of the given format, whose dimensions are the ones of the
+    /// default framebuffer multiplied by `scale`.
+    ///
+    /// No texture is created until the graph is executed.
+    pub fn create_target(&mut self, name: &str, format: UncompressedFloatFormat, scale: f32)
+                         -> ResourceId
+    {
+        self.targets.push(Target {
+            name: name.to_owned(),
+            format: format,
+            scale: scale,
+        });
+
+        ResourceId(self.targets.len() - 1)
+    }
+
+    /// Adds a pass at the end of the graph.
+    ///
+    /// The pass can only access the textures of the targets of `reads` and `writes`, and draws
+    /// to the targets of `writes` through `PassContext::surface` or `PassContext::outputs`.
+    pub fn add_pass<F>(&mut self, name: &str, reads: &[ResourceId], writes: &[ResourceId],
+                       callback: F)
+                       where F: FnMut(&PassContext) -> Result<(), DrawError> + 'a
+    {
+        self.passes.push(Pass {
+            name: name.to_owned(),
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Checks the graph, then executes the passes that contribute to the result in order.
+    ///
+    /// The graph can be executed multiple times. The textures are acquired from `pool`, and
+    /// are all released when this function returns.
+    pub fn execute(&mut self, pool: &mut TargetPool) -> Result<(), RenderGraphError> {
+        try!(self.validate());
+
+        let executed = self.executed_passes();
+
+        // index of the last executed pass that uses each target
+        let mut last_use = vec![None; self.targets.len()];
+        for (index, pass) in self.passes.iter().enumerate() {
+            if !executed[index] {
+                continue;
+            }
+
+            for &ResourceId(target) in pass.reads.iter().chain(pass.writes.iter()) {
+                last_use[target] = Some(index);
+            }
+        }
+
+        let mut textures: Vec<Option<PooledTarget>> = (0 .. self.targets.len()).map(|_| None)
+                                                                                .collect();
+
+        for (index, pass) in self.passes.iter_mut().enumerate() {
+            if !executed[index] {
+                continue;
+            }
+
+            for &ResourceId(target) in pass.writes.iter() {
+                if textures[target].is_none() {
+                    let desc = &self.targets[target];
+                    textures[target] = Some(try!(pool.acquire(desc.format, desc.scale)));
+                }
+            }
+
+            {
+                let context = PassContext {
+                    context: pool.get_context(),
+                    textures: &textures,
+                    reads: &pass.reads,
+                    writes: &pass.writes,
+                };
+
+                if let Err(error) = (pass.callback)(&context) {
+                    return Err(RenderGraphError::DrawError {
+                        pass: pass.name.clone(),
+                        error: error,
+                    });
+                }
+            }
+
+            // releasing the targets that are no longer needed, so that the next passes can
+            // reuse their textures
+            for (target, texture) in textures.iter_mut().enumerate() {
+                if last_use[target] == Some(index) {
+                    if let Some(texture) = texture.take() {
+                        texture.main_level().invalidate();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the reads and writes of the passes.
+    fn validate(&self) -> Result<(), RenderGraphError> {
+        let mut written = vec![false; self.targets.len()];
+
+        for pass in self.passes.iter() {
+            for &ResourceId(target) in pass.reads.iter() {
+                if pass.writes.contains(&ResourceId(target)) {
+                    return Err(RenderGraphError::ReadWriteConflict {
+                        pass: pass.name.clone(),
+                        target: self.targets[target].name.clone(),
+                    });
+                }
+
+                if !written[target] {
+                    return Err(RenderGraphError::ReadBeforeWrite {
+                        pass: pass.name.clone(),
+                        target: self.targets[target].name.clone(),
+                    });
+                }
+            }
+
+            if let Some(&ResourceId(first)) = pass.writes.first() {
+                let first = &self.targets[first];
+                for &ResourceId(target) in pass.writes.iter() {
+                    let target = &self.targets[target];
+                    if target.format != first.format || target.scale != first.scale {
+                        return Err(RenderGraphError::MismatchedOutputs {
+                            pass: pass.name.clone(),
+                        });
+                    }
+                }
+            }
+
+            for &ResourceId(target) in pass.writes.iter() {
+                written[target] = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns, for each pass, whether it contributes to the result of the graph.
+    ///
+    /// Passes that don't write to any target are always executed. Other passes are only
+    /// executed if one of their outputs is read by a pass that is executed.
+    fn executed_passes(&self) -> Vec<bool> {
+        let mut needed = vec![false; self.targets.len()];
+        let mut executed = vec![false; self.passes.len()];
+
+        for (index, pass) in self.passes.iter().enumerate().rev() {
+            let execute = pass.writes.is_empty() ||
+                          pass.writes.iter().any(|&ResourceId(target)| needed[target]);
+            if !execute {
+                continue;
+            }
+
+            executed[index] = true;
+
+            // the previous writes of a target only matter if it is read by a later pass
+            for &ResourceId(target) in pass.writes.iter() {
+                needed[target] = false;
+            }
+            for &ResourceId(target) in pass.reads.iter() {
+                needed[target] = true;
+            }
+        }
+
+        executed
+    }
+}
+
+impl<'a> Default for RenderGraph<'a> {
+    #[inline]
+    fn default() -> RenderGraph<'a> {
+        RenderGraph::new()
+    }
+}
+
+impl<'a> fmt::Debug for RenderGraph<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let passes = self.passes.iter().map(|p| &p.name[..]).collect::<Vec<_>>();
+        write!(fmt, "RenderGraph({:?})", passes)
+    }
+}
+
+/// Access to the textures of a pass while it is executed.
+pub struct PassContext<'g> {
+    context: &'g Rc<Context>,
+    textures: &'g [Option<PooledTarget>],
+    reads: &'g [ResourceId],
+    writes: &'g [ResourceId],
+}
+
+impl<'g> PassContext<'g> {
+    /// Returns the texture of a target that the pass reads from or writes to.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the target has not been declared in the reads or writes of the pass.
+    pub fn texture(&self, target: ResourceId) -> &'g Texture2d {
+        assert!(self.reads.contains(&target) || self.writes.contains(&target),
+                "The pass doesn't declare this target");
+        let ResourceId(target) = target;
+        self.textures[target].as_ref().unwrap()
+    }
+
+    /// Returns a framebuffer that draws to the first target that the pass writes to.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the pass doesn't write to any target.
+    #[inline]
+    pub fn surface(&self) -> SimpleFrameBuffer<'g> {
+        let target = *self.writes.first().expect("The pass doesn't write to any target");
+
+        // the attachments have been validated by the target pool
+        SimpleFrameBuffer::new(self.context, self.texture(target)).unwrap()
+    }
+
+    /// Returns a framebuffer that draws to all the targets that the pass writes to. The
+    /// fragment output at location `n` is written to the `n`th target of the pass.
+    pub fn outputs(&self) -> MultiOutputFrameBuffer<'g> {
+        let outputs = self.writes.iter().enumerate().map(|(location, &target)| {
+            (location as u32, self.texture(target))
+        });
+
+        // the textures all have the same format and dimensions
+        MultiOutputFrameBuffer::new(self.context, outputs).unwrap()
+    }
+}
+
+impl<'g> fmt::Debug for PassContext<'g> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "PassContext({:?}, {:?})", self.reads, self.writes)
+    }
+}
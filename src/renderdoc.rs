@@ -0,0 +1,183 @@
+/*!
+Integration with the in-application API of [RenderDoc](https://renderdoc.org).
+
+This module is only available with the `renderdoc` feature.
+
+RenderDoc must be injected in the process, either by launching the application from RenderDoc or
+by loading its library before creating the context. If it isn't, `RenderDoc::get()` returns
+`None` and the methods of `Context` that use it do nothing.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# fn draw_scene() {}
+// captures the next frame that is presented
+display.trigger_capture();
+
+// captures everything that is done in the closure
+display.capture(|| {
+    draw_scene();
+});
+```
+
+*/
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+/// Version 1.1.0 of the API, which is the first one with `TriggerMultiFrameCapture`.
+const RENDERDOC_API_VERSION_1_1_0: c_int = 10100;
+
+type GetApi = extern "C" fn(c_int, *mut *mut c_void) -> c_int;
+
+/// Table of functions returned by `RENDERDOC_GetAPI`. The layout must match
+/// `RENDERDOC_API_1_1_0` from `renderdoc_app.h`.
+#[repr(C)]
+struct Api {
+    get_api_version: extern "C" fn(*mut c_int, *mut c_int, *mut c_int),
+    set_capture_option_u32: *const c_void,
+    set_capture_option_f32: *const c_void,
+    get_capture_option_u32: *const c_void,
+    get_capture_option_f32: *const c_void,
+    set_focus_toggle_keys: *const c_void,
+    set_capture_keys: *const c_void,
+    get_overlay_bits: *const c_void,
+    mask_overlay_bits: *const c_void,
+    shutdown: *const c_void,
+    unload_crash_handler: *const c_void,
+    set_capture_file_path_template: *const c_void,
+    get_capture_file_path_template: *const c_void,
+    get_num_captures: extern "C" fn() -> u32,
+    get_capture: *const c_void,
+    trigger_capture: extern "C" fn(),
+    is_target_control_connected: *const c_void,
+    launch_replay_ui: *const c_void,
+    set_active_window: *const c_void,
+    start_frame_capture: extern "C" fn(*mut c_void, *mut c_void),
+    is_frame_capturing: extern "C" fn() -> u32,
+    end_frame_capture: extern "C" fn(*mut c_void, *mut c_void) -> u32,
+    trigger_multi_frame_capture: extern "C" fn(u32),
+}
+
+/// Handle to the in-application API of RenderDoc.
+#[derive(Copy, Clone)]
+pub struct RenderDoc {
+    api: &'static Api,
+}
+
+impl RenderDoc {
+    /// Returns the API of RenderDoc if it is injected in the current process.
+    pub fn get() -> Option<RenderDoc> {
+        unsafe {
+            let get_api = match find_get_api() {
+                Some(f) => f,
+                None => return None,
+            };
+
+            let mut api: *mut c_void = ptr::null_mut();
+            if get_api(RENDERDOC_API_VERSION_1_1_0, &mut api) != 1 || api.is_null() {
+                return None;
+            }
+
+            Some(RenderDoc { api: &*(api as *const Api) })
+        }
+    }
+
+    /// Returns the version of RenderDoc as `(major, minor, patch)`.
+    pub fn get_version(&self) -> (u32, u32, u32) {
+        let (mut major, mut minor, mut patch) = (0, 0, 0);
+        (self.api.get_api_version)(&mut major, &mut minor, &mut patch);
+        (major as u32, minor as u32, patch as u32)
+    }
+
+    /// Captures the next frame that is presented.
+    #[inline]
+    pub fn trigger_capture(&self) {
+        (self.api.trigger_capture)()
+    }
+
+    /// Captures the next `frames` frames that are presented.
+    #[inline]
+    pub fn trigger_multi_frame_capture(&self, frames: u32) {
+        (self.api.trigger_multi_frame_capture)(frames)
+    }
+
+    /// Starts capturing the commands of the current OpenGL context.
+    ///
+    /// Prefer `Context::capture`, which makes sure that the right context is current.
+    #[inline]
+    pub fn start_frame_capture(&self) {
+        (self.api.start_frame_capture)(ptr::null_mut(), ptr::null_mut())
+    }
+
+    /// Ends the capture started with `start_frame_capture`. Returns false if the capture failed.
+    #[inline]
+    pub fn end_frame_capture(&self) -> bool {
+        (self.api.end_frame_capture)(ptr::null_mut(), ptr::null_mut()) != 0
+    }
+
+    /// Returns true if a capture is in progress.
+    #[inline]
+    pub fn is_frame_capturing(&self) -> bool {
+        (self.api.is_frame_capturing)() != 0
+    }
+
+    /// Returns the number of captures that have been made so far.
+    #[inline]
+    pub fn get_num_captures(&self) -> u32 {
+        (self.api.get_num_captures)()
+    }
+}
+
+/// Looks for `RENDERDOC_GetAPI` in the RenderDoc library, without loading it if it isn't loaded
+/// already.
+#[cfg(all(unix, not(target_os = "macos")))]
+unsafe fn find_get_api() -> Option<GetApi> {
+    const RTLD_NOW: c_int = 0x2;
+    const RTLD_NOLOAD: c_int = 0x4;
+
+    #[link(name = "dl")]
+    extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    let library = dlopen(b"librenderdoc.so\0".as_ptr() as *const c_char, RTLD_NOW | RTLD_NOLOAD);
+    if library.is_null() {
+        return None;
+    }
+
+    let symbol = dlsym(library, b"RENDERDOC_GetAPI\0".as_ptr() as *const c_char);
+    if symbol.is_null() {
+        return None;
+    }
+
+    Some(::std::mem::transmute(symbol))
+}
+
+/// Looks for `RENDERDOC_GetAPI` in the RenderDoc library, without loading it if it isn't loaded
+/// already.
+#[cfg(windows)]
+unsafe fn find_get_api() -> Option<GetApi> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleA(name: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+
+    let library = GetModuleHandleA(b"renderdoc.dll\0".as_ptr() as *const c_char);
+    if library.is_null() {
+        return None;
+    }
+
+    let symbol = GetProcAddress(library, b"RENDERDOC_GetAPI\0".as_ptr() as *const c_char);
+    if symbol.is_null() {
+        return None;
+    }
+
+    Some(::std::mem::transmute(symbol))
+}
+
+/// RenderDoc doesn't support OpenGL on this platform.
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+unsafe fn find_get_api() -> Option<GetApi> {
+    None
+}
@@ -1,8 +1,11 @@
+use std::cmp;
+
 use DrawError;
 
 use uniforms::SamplerBehavior;
 
 use gl;
+use utils::gl_get::get_output;
 use context::CommandContext;
 use version::Version;
 use version::Api;
@@ -24,9 +27,7 @@ impl SamplerObject {
                 ctxt.extensions.gl_arb_sampler_objects);
 
         let sampler = unsafe {
-            use std::mem;
-            let mut sampler: gl::types::GLuint = mem::uninitialized();
-            ctxt.gl.GenSamplers(1, &mut sampler);
+            let sampler: gl::types::GLuint = get_output(|ptr| ctxt.gl.GenSamplers(1, ptr));
             sampler
         };
 
@@ -42,14 +43,9 @@ impl SamplerObject {
             ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_MAG_FILTER,
                                       behavior.magnify_filter.to_glenum() as gl::types::GLint);
 
-            if let Some(max_value) = ctxt.capabilities.max_texture_max_anisotropy {
-                let value = if behavior.max_anisotropy as f32 > max_value {
-                    max_value
-                } else {
-                    behavior.max_anisotropy as f32
-                };
-
-                ctxt.gl.SamplerParameterf(sampler, gl::TEXTURE_MAX_ANISOTROPY_EXT, value);
+            if ctxt.capabilities.max_texture_max_anisotropy.is_some() {
+                let value = get_anisotropy_level(ctxt, behavior.max_anisotropy);
+                ctxt.gl.SamplerParameterf(sampler, gl::TEXTURE_MAX_ANISOTROPY_EXT, value as f32);
             }
         }
 
@@ -96,15 +92,33 @@ pub fn get_sampler(ctxt: &mut CommandContext, behavior: &SamplerBehavior)
         return Err(DrawError::SamplersNotSupported);
     }
 
+    // behaviors that only differ by an anisotropy level that is clamped to the same value share
+    // the same sampler
+    let behavior = SamplerBehavior {
+        max_anisotropy: get_anisotropy_level(ctxt, behavior.max_anisotropy),
+        .. *behavior
+    };
+
     // looking for an existing sampler
-    match ctxt.samplers.get(behavior) {
+    match ctxt.samplers.get(&behavior) {
         Some(obj) => return Ok(obj.get_id()),
         None => ()
     };
 
     // builds a new sampler
-    let sampler = SamplerObject::new(ctxt, behavior);
+    let sampler = SamplerObject::new(ctxt, &behavior);
     let id = sampler.get_id();
-    ctxt.samplers.insert(behavior.clone(), sampler);
+    ctxt.samplers.insert(behavior, sampler);
     Ok(id)
 }
+
+/// Clamps an anisotropy level between `1` and the maximum supported by the backend.
+///
+/// Returns `1`, which disables anisotropic filtering, if the backend doesn't support it.
+#[inline]
+fn get_anisotropy_level(ctxt: &CommandContext, level: u16) -> u16 {
+    match ctxt.capabilities.max_texture_max_anisotropy {
+        Some(max) => cmp::max(1, cmp::min(level, max as u16)),
+        None => 1,
+    }
+}
@@ -100,6 +100,13 @@ impl Drop for LinearSyncFence {
     }
 }
 
+/// Returns true if the backend supports fences.
+#[inline]
+pub fn is_sync_supported(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.extensions.gl_arb_sync || ctxt.extensions.gl_apple_sync
+}
+
 pub unsafe fn new_linear_sync_fence(ctxt: &mut CommandContext)
                                     -> Result<LinearSyncFence, SyncNotSupportedError>
 {
@@ -130,6 +137,26 @@ pub unsafe fn wait_linear_sync_fence_and_drop(mut fence: LinearSyncFence,
     delete_fence(ctxt, fence);
 }
 
+/// Returns true if the fence has been signaled, without waiting or flushing.
+#[inline]
+pub unsafe fn is_linear_sync_fence_signaled(ctxt: &mut CommandContext, fence: &LinearSyncFence)
+                                            -> bool
+{
+    let fence = fence.id.unwrap();
+
+    let result = if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                    ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.ClientWaitSync(fence, 0, 0)
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.ClientWaitSyncAPPLE(fence, 0, 0)
+    } else {
+        unreachable!();
+    };
+
+    result == gl::ALREADY_SIGNALED || result == gl::CONDITION_SATISFIED
+}
+
 /// Destroys a fence, from within the commands context.
 #[inline]
 pub unsafe fn destroy_linear_sync_fence(ctxt: &mut CommandContext, mut fence: LinearSyncFence) {
@@ -1,4 +1,5 @@
 use gl;
+use utils::gl_get::get_output;
 use GlObject;
 
 use backend::Facade;
@@ -17,10 +18,13 @@ use texture::Texture2dDataSink;
 use texture::TextureKind;
 use texture::{MipmapsOption, TextureFormat, TextureCreationError, CubeLayer};
 use texture::{get_format, InternalFormat, GetFormatError};
+use texture::fallback::{self, FormatFallbackPolicy};
+use texture::lod::{self, LodDiagnostics};
 use texture::pixel::PixelValue;
 use texture::pixel_buffer::PixelBuffer;
 
 use fbo::ClearBufferData;
+use debug::ObjectKind;
 
 use buffer::BufferSlice;
 use buffer::BufferAny;
@@ -31,6 +35,7 @@ use std::cmp;
 use std::fmt;
 use std::mem;
 use std::ptr;
+use std::slice;
 use std::borrow::Cow;
 use std::cell::Cell;
 use std::rc::Rc;
@@ -41,6 +46,7 @@ use fbo;
 
 /// Type of a texture.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[allow(missing_docs)]      // TODO: document and remove
 pub enum Dimensions {
     Texture1d { width: u32 },
@@ -148,6 +154,47 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         }
     }
 
+    // applying the fallback policy if the requested compressed format is not supported
+    if let Some(requested) = fallback::get_requested_format(format, data.as_ref().map(|&(c, _)| c)) {
+        let context = facade.get_context();
+        let capabilities = context.get_capabilities();
+        let policy = context.get_format_fallback_policy(requested);
+
+        if policy != FormatFallbackPolicy::Error &&
+           !capabilities.internal_formats_textures.contains_key(&requested)
+        {
+            let uncompressed_only = policy == FormatFallbackPolicy::DecodeOnCpu || data.is_some();
+            let replacement = match fallback::get_fallback_formats(requested, uncompressed_only)
+                                             .into_iter()
+                                             .find(|f| capabilities.internal_formats_textures
+                                                                   .contains_key(f))
+            {
+                Some(f) => TextureFormatRequest::Specific(f),
+                None => return Err(TextureCreationError::FormatNotSupported),
+            };
+
+            let data = match data {
+                Some((client_format, data)) => {
+                    let data = unsafe {
+                        slice::from_raw_parts(data.as_ptr() as *const u8, data_bufsize)
+                    };
+                    let layers = depth.or(array_size).unwrap_or(1);
+                    match fallback::decode(client_format, width, height.unwrap_or(1), layers,
+                                           data)
+                    {
+                        Some((client, decoded)) => {
+                            Some((ClientFormatAny::ClientFormat(client), Cow::Owned(decoded)))
+                        },
+                        None => return Err(TextureCreationError::FormatNotSupported),
+                    }
+                },
+                None => None,
+            };
+
+            return new_texture(facade, replacement, data, mipmaps, ty);
+        }
+    }
+
     // getting the `GLenum` corresponding to this texture type
     let bind_point = get_bind_point(ty);
     if bind_point == gl::TEXTURE_CUBE_MAP || bind_point == gl::TEXTURE_CUBE_MAP_ARRAY {
@@ -177,6 +224,8 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         (&None, TextureFormatRequest::Specific(TextureFormat::DepthFormat(_))) => (gl::DEPTH_COMPONENT, gl::FLOAT),
         (&None, TextureFormatRequest::AnyDepthStencil) => (gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
         (&None, TextureFormatRequest::Specific(TextureFormat::DepthStencilFormat(_))) => (gl::DEPTH_STENCIL, gl::UNSIGNED_INT_24_8),
+        (&None, TextureFormatRequest::AnyStencil) => (gl::STENCIL_INDEX, gl::UNSIGNED_BYTE),
+        (&None, TextureFormatRequest::Specific(TextureFormat::StencilFormat(_))) => (gl::STENCIL_INDEX, gl::UNSIGNED_BYTE),
         (&None, _) => (gl::RGBA, gl::UNSIGNED_BYTE),
     };
 
@@ -214,8 +263,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
 
         BufferAny::unbind_pixel_unpack(&mut ctxt);
 
-        let id: gl::types::GLuint = mem::uninitialized();
-        ctxt.gl.GenTextures(1, mem::transmute(&id));
+        let id: gl::types::GLuint = get_output(|ptr| ctxt.gl.GenTextures(1, ptr));
 
         {
             ctxt.gl.BindTexture(bind_point, id);
@@ -455,6 +503,9 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         id
     };
 
+    ctxt.live_objects.insert(ObjectKind::Texture, id, bind_point, None,
+                             Some(format!("{:?}", format)), Some(ty));
+
     Ok(TextureAny {
         context: facade.get_context().clone(),
         id: id,
@@ -481,10 +532,12 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
     let (width, height, depth, array_size, samples) = extract_dimensions(ty);
     let mipmap_levels = mipmaps.num_levels(width, height, depth);
     let should_generate_mipmaps = mipmaps.should_generate();
+    let ctxt = facade.get_context().make_current();
     if should_generate_mipmaps {
-        let ctxt = facade.get_context().make_current();
         generate_mipmaps(&ctxt, get_bind_point(ty));
     }
+    ctxt.live_objects.insert(ObjectKind::Texture, id, get_bind_point(ty), None,
+                             Some(format!("{:?}", format)), Some(ty));
     TextureAny {
         context: facade.get_context().clone(),
         id: id,
@@ -652,8 +705,8 @@ impl TextureAny {
     pub fn get_depth_stencil_bits(&self) -> (u16, u16) {
         unsafe {
             let mut ctxt = self.context.make_current();
-            let mut depth_bits: gl::types::GLint = mem::uninitialized();
-            let mut stencil_bits: gl::types::GLint = mem::uninitialized();
+            let mut depth_bits: gl::types::GLint = 0;
+            let mut stencil_bits: gl::types::GLint = 0;
             // FIXME: GL version considerations
             ctxt.gl.GetTextureLevelParameteriv(self.id, 0, gl::TEXTURE_DEPTH_SIZE, &mut depth_bits);
             ctxt.gl.GetTextureLevelParameteriv(self.id, 0, gl::TEXTURE_STENCIL_SIZE, &mut stencil_bits);
@@ -667,6 +720,15 @@ impl TextureAny {
         self.levels
     }
 
+    /// Queries the backend about the mipmap levels of the texture and their residency.
+    ///
+    /// Contrary to `get_mipmap_levels`, this function reads the values of `GL_TEXTURE_BASE_LEVEL`
+    /// and `GL_TEXTURE_MAX_LEVEL` and is therefore slower.
+    pub fn get_lod_diagnostics(&self) -> LodDiagnostics {
+        let mut ctxt = self.context.make_current();
+        lod::get_lod_diagnostics(&mut ctxt, self)
+    }
+
     /// Returns a structure that represents the main mipmap level of the texture.
     #[inline]
     pub fn main_level(&self) -> TextureAnyMipmap {
@@ -726,6 +788,8 @@ impl TextureExt for TextureAny {
             ctxt.state.texture_units[texture_unit as usize].texture = self.id;
         }
 
+        ctxt.live_objects.mark_used(ObjectKind::Texture, self.id);
+
         bind_point
     }
 }
@@ -762,8 +826,11 @@ impl Drop for TextureAny {
             }
         }
 
+        ctxt.live_objects.remove(ObjectKind::Texture, self.id);
+
         if self.owned {
             unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
+            self.context.purge_shared_object(ObjectKind::Texture, self.id);
         }
     }
 }
@@ -941,6 +1008,21 @@ impl<'a> TextureAnyMipmap<'a> {
         self.texture.get_array_size()
     }
 
+    /// Discards the content of this mipmap level, which becomes undefined.
+    ///
+    /// This is only a hint for the driver, which can then avoid preserving the content. Does
+    /// nothing if `glInvalidateTexImage` is not supported (OpenGL 4.3 or
+    /// `GL_ARB_invalidate_subdata`).
+    pub fn invalidate(&self) {
+        let ctxt = self.texture.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.extensions.gl_arb_invalidate_subdata {
+            unsafe {
+                ctxt.gl.InvalidateTexImage(self.texture.id, self.level as gl::types::GLint);
+            }
+        }
+    }
+
     /// Uploads data to the texture from a buffer.
     ///
     /// # Panic
@@ -996,6 +1078,12 @@ impl<'a> TextureAnyMipmap<'a> {
 
         let mut ctxt = self.texture.context.make_current();
 
+        // the rows of the pixel buffer are tightly packed
+        if ctxt.state.pixel_store_unpack_alignment != 1 {
+            ctxt.state.pixel_store_unpack_alignment = 1;
+            unsafe { ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1) };
+        }
+
         // binds the pixel buffer
         source.prepare_and_bind_for_pixel_unpack(&mut ctxt);
 
@@ -1239,14 +1327,19 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
         unsafe {
             let bind_point = texture.bind_to_current(&mut ctxt);
 
-            let mut is_compressed = mem::uninitialized();
-            ctxt.gl.GetTexLevelParameteriv(bind_point, level, gl::TEXTURE_COMPRESSED, &mut is_compressed);
+            let is_compressed = get_output(|ptr| {
+                ctxt.gl.GetTexLevelParameteriv(bind_point, level, gl::TEXTURE_COMPRESSED, ptr)
+            });
             if is_compressed != 0 {
 
-                let mut buffer_size = mem::uninitialized();
-                ctxt.gl.GetTexLevelParameteriv(bind_point, level, gl::TEXTURE_COMPRESSED_IMAGE_SIZE, &mut buffer_size);
-                let mut internal_format = mem::uninitialized();
-                ctxt.gl.GetTexLevelParameteriv(bind_point, level, gl::TEXTURE_INTERNAL_FORMAT, &mut internal_format);
+                let buffer_size = get_output(|ptr| {
+                    ctxt.gl.GetTexLevelParameteriv(bind_point, level, gl::TEXTURE_COMPRESSED_IMAGE_SIZE,
+                                                   ptr)
+                });
+                let internal_format = get_output(|ptr| {
+                    ctxt.gl.GetTexLevelParameteriv(bind_point, level, gl::TEXTURE_INTERNAL_FORMAT,
+                                                   ptr)
+                });
 
                 match ClientFormatAny::from_internal_compressed_format(internal_format as gl::types::GLenum) {
                     Some(known_format) => {
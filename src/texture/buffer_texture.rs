@@ -51,14 +51,36 @@ sample from a buffer texture of type `Integral` you need to use a `isamplerBuffe
 to sample from a buffer texture of type `Unsigned` you need to use a `usamplerBuffer`. Using the
 wrong type will result in an error.
 
+# Image load/store
+
+A buffer texture can also be bound as an image with `image_unit`, which lets shaders write to
+the buffer with `imageStore`. The image must be declared as an `imageBuffer`, `iimageBuffer` or
+`uimageBuffer` depending on the `BufferTextureType`, and readable images must specify their format
+in a layout qualifier. Three-component formats can't be used as images.
+
+```no_run
+# #[macro_use]
+# extern crate glium;
+# fn main() {
+# use glium::texture::buffer_texture::{BufferTexture, BufferTextureType, ImageAccess};
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+let texture = BufferTexture::<f32>::empty(&display, 1024, BufferTextureType::Float).unwrap();
+let image = texture.image_unit(ImageAccess::WriteOnly).unwrap();
+let uniforms = uniform! { output: image };
+# }
+```
+
+The required calls to `glMemoryBarrier` are made automatically, so the content of the buffer can
+be read or sampled right after a draw call or a compute dispatch that writes to it.
+
 */
 use std::{ mem, fmt };
-use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 use std::rc::Rc;
 use std::error::Error;
 
 use gl;
+use utils::gl_get::get_output;
 use version::Version;
 use version::Api;
 use backend::Facade;
@@ -66,6 +88,7 @@ use context::Context;
 use context::CommandContext;
 use ContextExt;
 use GlObject;
+use ToGlEnum;
 
 use TextureExt;
 
@@ -74,6 +97,7 @@ use buffer::BufferMode;
 use buffer::BufferType;
 use buffer::Buffer;
 use buffer::BufferCreationError;
+use buffer::BufferAnySlice;
 use buffer::Content as BufferContent;
 
 use uniforms::AsUniformValue;
@@ -90,6 +114,16 @@ pub enum TextureCreationError {
 
     /// The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`.
     TooLarge,
+
+    /// Buffer textures that only use a range of their buffer are not supported.
+    RangeNotSupported,
+
+    /// The requested range is outside of the buffer.
+    RangeOutOfBounds,
+
+    /// The offset of the requested range is not a multiple of
+    /// `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT`.
+    RangeNotAligned,
 }
 
 impl fmt::Display for TextureCreationError {
@@ -108,6 +142,13 @@ impl Error for TextureCreationError {
                 "The requested format is not supported in combination with the given texture buffer type",
             TooLarge =>
                 "The size of the buffer that you are trying to bind exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`",
+            RangeNotSupported =>
+                "Buffer textures that only use a range of their buffer are not supported",
+            RangeOutOfBounds =>
+                "The requested range is outside of the buffer",
+            RangeNotAligned =>
+                "The offset of the requested range is not a multiple of \
+                 `GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT`",
         }
     }
 }
@@ -139,7 +180,7 @@ impl Error for CreationError {
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref err) => Some(err),
@@ -162,6 +203,39 @@ impl From<TextureCreationError> for CreationError {
     }
 }
 
+/// Error that can happen when binding a buffer texture as an image.
+#[derive(Copy, Clone, Debug)]
+pub enum ImageUnitError {
+    /// Image load/store is not supported by the backend.
+    NotSupported,
+
+    /// The format of the buffer texture can't be used with image load/store.
+    FormatNotSupported,
+
+    /// The number of texels in the buffer exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`.
+    TooLarge,
+}
+
+impl fmt::Display for ImageUnitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ImageUnitError {
+    fn description(&self) -> &str {
+        use self::ImageUnitError::*;
+        match *self {
+            NotSupported =>
+                "Image load/store is not supported by the backend",
+            FormatNotSupported =>
+                "The format of the buffer texture can't be used with image load/store",
+            TooLarge =>
+                "The number of texels in the buffer exceeds `GL_MAX_TEXTURE_BUFFER_SIZE`",
+        }
+    }
+}
+
 /// Type of a buffer texture.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum BufferTextureType {
@@ -182,11 +256,45 @@ pub enum BufferTextureType {
     Unsigned,
 }
 
+/// How shaders are allowed to access a buffer texture bound as an image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageAccess {
+    /// The shader can only use `imageLoad`.
+    ReadOnly,
+
+    /// The shader can only use `imageStore`.
+    WriteOnly,
+
+    /// The shader can use both `imageLoad` and `imageStore`, and atomic operations.
+    ReadWrite,
+}
+
+impl ImageAccess {
+    /// Returns true if shaders can modify the content of the image.
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        *self != ImageAccess::ReadOnly
+    }
+}
+
+impl ToGlEnum for ImageAccess {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageAccess::ReadOnly => gl::READ_ONLY,
+            ImageAccess::WriteOnly => gl::WRITE_ONLY,
+            ImageAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
 /// A one-dimensional texture that gets its data from a buffer.
 pub struct BufferTexture<T> where [T]: BufferContent {
     buffer: Buffer<[T]>,
     texture: gl::types::GLuint,
     ty: BufferTextureType,
+    internal_format: gl::types::GLenum,
+    range: Range<usize>,
 }
 
 impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Copy {
@@ -281,20 +389,38 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
     }
 
     /// Builds a new buffer texture by taking ownership of a buffer.
+    #[inline]
     pub fn from_buffer<F: ?Sized>(context: &F, buffer: Buffer<[T]>, ty: BufferTextureType)
                           -> Result<BufferTexture<T>, (TextureCreationError, Buffer<[T]>)>
                           where F: Facade
+    {
+        BufferTexture::from_buffer_impl(context, buffer, None, ty)
+    }
+
+    /// Builds a new buffer texture by taking ownership of a buffer. The texture only contains
+    /// the elements of the buffer within `range`.
+    ///
+    /// The offset of the range in bytes must be a multiple of the
+    /// `texture_buffer_offset_alignment` of the capabilities. The whole buffer is still
+    /// accessible through `Deref`.
+    #[inline]
+    pub fn from_buffer_range<F: ?Sized>(context: &F, buffer: Buffer<[T]>, range: Range<usize>,
+                                        ty: BufferTextureType)
+                                        -> Result<BufferTexture<T>,
+                                                  (TextureCreationError, Buffer<[T]>)>
+                                        where F: Facade
+    {
+        BufferTexture::from_buffer_impl(context, buffer, Some(range), ty)
+    }
+
+    fn from_buffer_impl<F: ?Sized>(context: &F, buffer: Buffer<[T]>, range: Option<Range<usize>>,
+                                   ty: BufferTextureType)
+                                   -> Result<BufferTexture<T>, (TextureCreationError, Buffer<[T]>)>
+                                   where F: Facade
     {
         let context = context.get_context();
         let mut ctxt = context.make_current();
 
-        // checking capabilities
-        if buffer.get_size() / mem::size_of::<T>() > ctxt.capabilities
-                                                         .max_texture_buffer_size.unwrap() as usize
-        {
-            return Err((TextureCreationError::TooLarge, buffer));
-        }
-
         // before starting, we determine the internal format and check that buffer textures are
         // supported
         let internal_format = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
@@ -391,23 +517,57 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
             return Err((TextureCreationError::NotSupported, buffer));
         };
 
+        // checking the range
+        if let Some(ref range) = range {
+            let alignment = match ctxt.capabilities.texture_buffer_offset_alignment {
+                Some(a) => a as usize,
+                None => return Err((TextureCreationError::RangeNotSupported, buffer)),
+            };
+
+            if range.start > range.end || range.end > buffer.len() {
+                return Err((TextureCreationError::RangeOutOfBounds, buffer));
+            }
+
+            if (range.start * mem::size_of::<T>()) % alignment != 0 {
+                return Err((TextureCreationError::RangeNotAligned, buffer));
+            }
+        }
+
+        let range = range.unwrap_or(0 .. buffer.len());
+
+        // checking capabilities
+        if range.len() > ctxt.capabilities.max_texture_buffer_size.unwrap_or(0) as usize {
+            return Err((TextureCreationError::TooLarge, buffer));
+        }
+
+        // offset and size in bytes, only used if the texture doesn't cover the whole buffer
+        let bytes_range = if range.start != 0 || range.end != buffer.len() {
+            Some((range.start * mem::size_of::<T>(), range.len() * mem::size_of::<T>()))
+        } else {
+            None
+        };
+
         // now the texture creation
         debug_assert_eq!(buffer.get_offset_bytes(), 0);
         let id = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                     ctxt.extensions.gl_arb_direct_state_access
         {
             unsafe {
-                let mut id = mem::uninitialized();
-                ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, &mut id);
-                ctxt.gl.TextureBuffer(id, internal_format, buffer.get_id());
+                let id = get_output(|ptr| ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, ptr));
+                if let Some((offset, size)) = bytes_range {
+                    ctxt.gl.TextureBufferRange(id, internal_format, buffer.get_id(),
+                                               offset as gl::types::GLintptr,
+                                               size as gl::types::GLsizeiptr);
+                } else {
+                    ctxt.gl.TextureBuffer(id, internal_format, buffer.get_id());
+                }
                 id
             }
 
         } else {
             // reserving the ID
             let id = unsafe {
-                let mut id = mem::uninitialized();
-                ctxt.gl.GenTextures(1, &mut id);
+                let id = get_output(|ptr| ctxt.gl.GenTextures(1, ptr));
                 id
             };
 
@@ -419,7 +579,28 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
             }
 
             // binding the buffer
-            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+            if let Some((offset, size)) = bytes_range {
+                if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                   ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                   ctxt.extensions.gl_arb_texture_buffer_range
+                {
+                    unsafe {
+                        ctxt.gl.TexBufferRange(gl::TEXTURE_BUFFER, internal_format,
+                                               buffer.get_id(), offset as gl::types::GLintptr,
+                                               size as gl::types::GLsizeiptr);
+                    }
+                } else if ctxt.extensions.gl_oes_texture_buffer {
+                    unsafe {
+                        ctxt.gl.TexBufferRangeOES(gl::TEXTURE_BUFFER, internal_format,
+                                                  buffer.get_id(), offset as gl::types::GLintptr,
+                                                  size as gl::types::GLsizeiptr);
+                    }
+                } else {
+                    // handled when checking the range
+                    unreachable!();
+                }
+
+            } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                ctxt.version >= &Version(Api::GlEs, 3, 2)
             {
                 unsafe {
@@ -456,6 +637,8 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
             buffer: buffer,
             ty: ty,
             texture: id,
+            internal_format: internal_format,
+            range: range,
         })
     }
 }
@@ -491,30 +674,91 @@ impl<T> Drop for BufferTexture<T> where [T]: BufferContent {
     }
 }
 
-impl<T> BufferTexture<T> where [T]: BufferContent {
+impl<T> BufferTexture<T> where [T]: BufferContent, T: Copy {
+    /// Returns the range of elements of the buffer that the texture contains.
+    #[inline]
+    pub fn get_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
     /// Builds a `BufferTextureRef`.
     #[inline]
     pub fn as_buffer_texture_ref(&self) -> BufferTextureRef {
         BufferTextureRef {
             texture: self.texture,
             ty: self.ty,
-            marker: PhantomData,
+            buffer: self.buffer.slice(self.range.clone()).unwrap().as_slice_any(),
+        }
+    }
+
+    /// Builds an object that binds the texture to an image unit when passed as a uniform, so
+    /// that shaders can access it with `imageLoad` and `imageStore`.
+    ///
+    /// Returns an error if image load/store is not supported, if the format of the texture
+    /// can't be used as an image with the requested access, or if the buffer exceeds
+    /// `GL_MAX_TEXTURE_BUFFER_SIZE`.
+    pub fn image_unit(&self, access: ImageAccess)
+                      -> Result<BufferTextureImageUnit, ImageUnitError>
+    {
+        let ctxt = self.buffer.get_context().make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 4, 2) ||
+             ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+             ctxt.extensions.gl_arb_shader_image_load_store)
+        {
+            return Err(ImageUnitError::NotSupported);
+        }
+
+        if self.range.len() > ctxt.capabilities.max_texture_buffer_size.unwrap_or(0) as usize {
+            return Err(ImageUnitError::TooLarge);
+        }
+
+        // OpenGL ES only supports a subset of the formats
+        let format_supported = match self.internal_format {
+            gl::R32F | gl::R32I | gl::R32UI | gl::RGBA8 | gl::RGBA8I | gl::RGBA8UI |
+            gl::RGBA16F | gl::RGBA16I | gl::RGBA16UI | gl::RGBA32F | gl::RGBA32I |
+            gl::RGBA32UI => true,
+            gl::R8 | gl::R8I | gl::R8UI | gl::R16 | gl::R16I | gl::R16UI | gl::R16F |
+            gl::RG8 | gl::RG8I | gl::RG8UI | gl::RG16 | gl::RG16I | gl::RG16UI | gl::RG16F |
+            gl::RG32I | gl::RG32UI | gl::RG32F | gl::RGBA16 => ctxt.version.0 == Api::Gl,
+            _ => false,
+        };
+
+        if !format_supported {
+            return Err(ImageUnitError::FormatNotSupported);
+        }
+
+        // checking the load/store support reported by the backend, if known
+        let image_support = ctxt.capabilities.internal_formats_textures.iter()
+                                .find(|&(format, _)| format.to_glenum() == self.internal_format)
+                                .and_then(|(_, infos)| infos.image);
+
+        if let Some(image_support) = image_support {
+            if (access != ImageAccess::WriteOnly && !image_support.load) ||
+               (access.is_write() && !image_support.store)
+            {
+                return Err(ImageUnitError::FormatNotSupported);
+            }
         }
+
+        Ok(BufferTextureImageUnit {
+            texture: self.as_buffer_texture_ref(),
+            format: self.internal_format,
+            access: access,
+        })
     }
 }
 
-impl<T> AsUniformValue for BufferTexture<T> where [T]: BufferContent {
+impl<T> AsUniformValue for BufferTexture<T> where [T]: BufferContent, T: Copy {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
-        // FIXME: handle `glMemoryBarrier` for the buffer
         UniformValue::BufferTexture(self.as_buffer_texture_ref())
     }
 }
 
-impl<'a, T: 'a> AsUniformValue for &'a BufferTexture<T> where [T]: BufferContent {
+impl<'a, T: 'a> AsUniformValue for &'a BufferTexture<T> where [T]: BufferContent, T: Copy {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
-        // FIXME: handle `glMemoryBarrier` for the buffer
         UniformValue::BufferTexture(self.as_buffer_texture_ref())
     }
 }
@@ -524,7 +768,7 @@ impl<'a, T: 'a> AsUniformValue for &'a BufferTexture<T> where [T]: BufferContent
 pub struct BufferTextureRef<'a> {
     texture: gl::types::GLuint,
     ty: BufferTextureType,
-    marker: PhantomData<&'a ()>,
+    buffer: BufferAnySlice<'a>,
 }
 
 impl<'a> BufferTextureRef<'a> {
@@ -533,6 +777,12 @@ impl<'a> BufferTextureRef<'a> {
     pub fn get_texture_type(&self) -> BufferTextureType {
         self.ty
     }
+
+    /// Returns the buffer that holds the data of the texture.
+    #[inline]
+    pub fn get_buffer(&self) -> BufferAnySlice<'a> {
+        self.buffer
+    }
 }
 
 impl<'a> TextureExt for BufferTextureRef<'a> {
@@ -558,6 +808,48 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
     }
 }
 
+/// A buffer texture that is bound to an image unit when used as a uniform.
+///
+/// Built with `BufferTexture::image_unit`.
+#[derive(Copy, Clone)]
+pub struct BufferTextureImageUnit<'a> {
+    texture: BufferTextureRef<'a>,
+    format: gl::types::GLenum,
+    access: ImageAccess,
+}
+
+impl<'a> BufferTextureImageUnit<'a> {
+    /// Returns the texture that is bound as an image.
+    #[inline]
+    pub fn get_texture(&self) -> BufferTextureRef<'a> {
+        self.texture
+    }
+
+    /// Returns how shaders are allowed to access the image.
+    #[inline]
+    pub fn get_access(&self) -> ImageAccess {
+        self.access
+    }
+
+    /// Binds the texture to the given image unit and calls `glMemoryBarrier` if necessary.
+    #[doc(hidden)]
+    pub fn bind_to_image_unit(&self, ctxt: &mut CommandContext, unit: gl::types::GLuint) {
+        self.texture.buffer.prepare_for_shader_image_access(ctxt, self.access.is_write());
+
+        unsafe {
+            ctxt.gl.BindImageTexture(unit, self.texture.texture, 0, gl::FALSE, 0,
+                                     self.access.to_glenum(), self.format);
+        }
+    }
+}
+
+impl<'a> AsUniformValue for BufferTextureImageUnit<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::BufferTextureImage(*self)
+    }
+}
+
 ///
 ///
 /// Note that some three-component types are missing. This is not a mistake. OpenGL doesn't
@@ -0,0 +1,387 @@
+/*!
+
+An `ExternalTexture` is a read-only texture whose content is provided by something else than
+OpenGL, for example a camera, a video decoder or another process.
+
+External textures are built from an `EGLImage`. You can either create the `EGLImage` yourself and
+pass it to `ExternalTexture::from_egl_image`, or let glium import a Linux dma-buf with
+`ExternalTexture::from_dma_buf`. In both situations no copy is performed: the texture directly
+reads the memory of the image.
+
+Importing requires the `GL_OES_EGL_image_external` extension, and `from_dma_buf` additionally
+requires the backend to use EGL with the `EGL_EXT_image_dma_buf_import` extension.
+
+# Sampling
+
+External textures must be sampled with the special `samplerExternalOES` sampler type. In GLSL ES
+you need to enable the extension in your shader:
+
+```glsl
+#extension GL_OES_EGL_image_external : require
+
+uniform samplerExternalOES tex;
+```
+
+The content of an external texture is always returned as RGBA, even if the image is in a YUV
+format. The conversion is done by the implementation.
+
+External textures don't have mipmaps, can't be rendered to and can't be read or written by glium.
+Only the `Nearest` and `Linear` filters and the `Clamp` wrap function are guaranteed to work.
+
+*/
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+use std::error::Error;
+use std::os::raw::{c_int, c_void};
+
+use gl;
+use utils::gl_get::get_output;
+use backend::Facade;
+use context::Context;
+use context::CommandContext;
+use CapabilitiesSource;
+use ContextExt;
+use GlObject;
+use TextureExt;
+
+use uniforms::AsUniformValue;
+use uniforms::Sampler;
+use uniforms::UniformValue;
+
+// constants of `EGL_EXT_image_dma_buf_import` and `EGL_EXT_image_dma_buf_import_modifiers`
+const EGL_NONE: i32 = 0x3038;
+const EGL_WIDTH: i32 = 0x3057;
+const EGL_HEIGHT: i32 = 0x3056;
+const EGL_LINUX_DMA_BUF_EXT: u32 = 0x3270;
+const EGL_LINUX_DRM_FOURCC_EXT: i32 = 0x3271;
+const EGL_DMA_BUF_PLANE_FD_EXT: [i32; 4] = [0x3272, 0x3275, 0x3278, 0x3440];
+const EGL_DMA_BUF_PLANE_OFFSET_EXT: [i32; 4] = [0x3273, 0x3276, 0x3279, 0x3441];
+const EGL_DMA_BUF_PLANE_PITCH_EXT: [i32; 4] = [0x3274, 0x3277, 0x327A, 0x3442];
+const EGL_DMA_BUF_PLANE_MODIFIER_LO_EXT: [i32; 4] = [0x3443, 0x3445, 0x3447, 0x3449];
+const EGL_DMA_BUF_PLANE_MODIFIER_HI_EXT: [i32; 4] = [0x3444, 0x3446, 0x3448, 0x344A];
+
+type EglCreateImageKhr = extern "system" fn(*const c_void, *const c_void, u32, *const c_void,
+                                            *const i32) -> *const c_void;
+type EglDestroyImageKhr = extern "system" fn(*const c_void, *const c_void) -> u32;
+type EglGetError = extern "system" fn() -> i32;
+
+/// Error that can happen while creating an external texture.
+#[derive(Copy, Clone, Debug)]
+pub enum CreationError {
+    /// External textures are not supported by the backend.
+    NotSupported,
+
+    /// The backend doesn't provide the EGL functions required to import a dma-buf.
+    DmaBufImportNotSupported,
+
+    /// A dma-buf must have between one and four planes.
+    InvalidPlanesCount,
+
+    /// EGL refused to create an image from the dma-buf. Contains the EGL error code.
+    EglImageCreationFailed(i32),
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for CreationError {
+    fn description(&self) -> &str {
+        use self::CreationError::*;
+        match *self {
+            NotSupported =>
+                "External textures are not supported by the backend",
+            DmaBufImportNotSupported =>
+                "The backend doesn't provide the EGL functions required to import a dma-buf",
+            InvalidPlanesCount =>
+                "A dma-buf must have between one and four planes",
+            EglImageCreationFailed(_) =>
+                "EGL refused to create an image from the dma-buf",
+        }
+    }
+}
+
+/// A plane of a dma-buf.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DmaBufPlane {
+    /// File descriptor of the dma-buf. It is not closed by glium.
+    pub fd: c_int,
+
+    /// Offset in bytes of the plane within the dma-buf.
+    pub offset: u32,
+
+    /// Number of bytes between the start of two consecutive rows.
+    pub pitch: u32,
+}
+
+/// Describes a dma-buf to import with `ExternalTexture::from_dma_buf`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DmaBufDescriptor {
+    /// Width of the image in pixels.
+    pub width: u32,
+
+    /// Height of the image in pixels.
+    pub height: u32,
+
+    /// DRM fourcc code of the format of the image, for example `DRM_FORMAT_NV12`.
+    pub fourcc: u32,
+
+    /// The planes of the image. Must contain between one and four elements.
+    pub planes: Vec<DmaBufPlane>,
+
+    /// DRM format modifier that applies to all the planes. Requires the
+    /// `EGL_EXT_image_dma_buf_import_modifiers` extension.
+    pub modifier: Option<u64>,
+}
+
+/// An `EGLImage` created by glium and destroyed alongside the texture.
+struct OwnedEglImage {
+    display: *const c_void,
+    image: *const c_void,
+    destroy: EglDestroyImageKhr,
+}
+
+/// A texture whose content comes from an `EGLImage`.
+pub struct ExternalTexture {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    width: u32,
+    height: u32,
+    image: Option<OwnedEglImage>,
+}
+
+impl ExternalTexture {
+    /// Builds an external texture from an existing `EGLImage`.
+    ///
+    /// # Safety
+    ///
+    /// `image` must be a valid `EGLImageKHR` created on the same EGL display as the context, and
+    /// must stay alive for as long as the texture exists. `width` and `height` must be the
+    /// dimensions of the image.
+    pub unsafe fn from_egl_image<F: ?Sized>(facade: &F, image: *const c_void, width: u32,
+                                            height: u32) -> Result<ExternalTexture, CreationError>
+                                            where F: Facade
+    {
+        let context = facade.get_context();
+        let mut ctxt = context.make_current();
+
+        let id = try!(create_texture(&mut ctxt, image));
+
+        Ok(ExternalTexture {
+            context: context.clone(),
+            id: id,
+            width: width,
+            height: height,
+            image: None,
+        })
+    }
+
+    /// Imports a Linux dma-buf as an external texture.
+    ///
+    /// `egl_display` is the `EGLDisplay` of the backend. The file descriptors of the planes are
+    /// not closed and can be closed as soon as this function returns.
+    ///
+    /// # Safety
+    ///
+    /// `egl_display` must be the `EGLDisplay` of the OpenGL context of `facade`, and the
+    /// descriptor must describe memory that stays valid for as long as the texture exists.
+    pub unsafe fn from_dma_buf<F: ?Sized>(facade: &F, egl_display: *const c_void,
+                                          descriptor: &DmaBufDescriptor)
+                                          -> Result<ExternalTexture, CreationError>
+                                          where F: Facade
+    {
+        if descriptor.planes.len() == 0 || descriptor.planes.len() > 4 {
+            return Err(CreationError::InvalidPlanesCount);
+        }
+
+        let context = facade.get_context();
+        if !context.get_extensions().gl_oes_egl_image_external {
+            return Err(CreationError::NotSupported);
+        }
+
+        let create = context.get_proc_address("eglCreateImageKHR");
+        let destroy = context.get_proc_address("eglDestroyImageKHR");
+        let get_error = context.get_proc_address("eglGetError");
+        if create.is_null() || destroy.is_null() || get_error.is_null() {
+            return Err(CreationError::DmaBufImportNotSupported);
+        }
+        let create: EglCreateImageKhr = mem::transmute(create);
+        let destroy: EglDestroyImageKhr = mem::transmute(destroy);
+        let get_error: EglGetError = mem::transmute(get_error);
+
+        let mut attribs = vec![
+            EGL_WIDTH, descriptor.width as i32,
+            EGL_HEIGHT, descriptor.height as i32,
+            EGL_LINUX_DRM_FOURCC_EXT, descriptor.fourcc as i32,
+        ];
+
+        for (num, plane) in descriptor.planes.iter().enumerate() {
+            attribs.push(EGL_DMA_BUF_PLANE_FD_EXT[num]);
+            attribs.push(plane.fd as i32);
+            attribs.push(EGL_DMA_BUF_PLANE_OFFSET_EXT[num]);
+            attribs.push(plane.offset as i32);
+            attribs.push(EGL_DMA_BUF_PLANE_PITCH_EXT[num]);
+            attribs.push(plane.pitch as i32);
+
+            if let Some(modifier) = descriptor.modifier {
+                attribs.push(EGL_DMA_BUF_PLANE_MODIFIER_LO_EXT[num]);
+                attribs.push((modifier & 0xffffffff) as i32);
+                attribs.push(EGL_DMA_BUF_PLANE_MODIFIER_HI_EXT[num]);
+                attribs.push((modifier >> 32) as i32);
+            }
+        }
+
+        attribs.push(EGL_NONE);
+
+        // `EGL_NO_CONTEXT` and a null client buffer are required for dma-bufs
+        let image = create(egl_display, ptr::null(), EGL_LINUX_DMA_BUF_EXT, ptr::null(),
+                           attribs.as_ptr());
+        if image.is_null() {
+            return Err(CreationError::EglImageCreationFailed(get_error()));
+        }
+
+        let mut ctxt = context.make_current();
+        let id = match create_texture(&mut ctxt, image) {
+            Ok(id) => id,
+            Err(err) => {
+                destroy(egl_display, image);
+                return Err(err);
+            }
+        };
+
+        Ok(ExternalTexture {
+            context: context.clone(),
+            id: id,
+            width: descriptor.width,
+            height: descriptor.height,
+            image: Some(OwnedEglImage {
+                display: egl_display,
+                image: image,
+                destroy: destroy,
+            }),
+        })
+    }
+
+    /// Returns the width of the texture.
+    #[inline]
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the texture.
+    #[inline]
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the width and height of the texture.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Builds a `Sampler` marker object that allows you to indicate how the texture should be
+    /// sampled from inside a shader.
+    #[inline]
+    pub fn sampled(&self) -> Sampler<ExternalTexture> {
+        Sampler(self, Default::default())
+    }
+}
+
+/// Creates an OpenGL texture that uses `image` as storage.
+unsafe fn create_texture(ctxt: &mut CommandContext, image: *const c_void)
+                         -> Result<gl::types::GLuint, CreationError>
+{
+    if !ctxt.extensions.gl_oes_egl_image_external {
+        return Err(CreationError::NotSupported);
+    }
+
+    let id = get_output(|ptr| ctxt.gl.GenTextures(1, ptr));
+
+    ctxt.gl.BindTexture(gl::TEXTURE_EXTERNAL_OES, id);
+    let act = ctxt.state.active_texture as usize;
+    ctxt.state.texture_units[act].texture = id;
+
+    ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_EXTERNAL_OES, image as gl::types::GLeglImageOES);
+
+    Ok(id)
+}
+
+impl Drop for ExternalTexture {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        // resetting the bindings
+        for tex_unit in ctxt.state.texture_units.iter_mut() {
+            if tex_unit.texture == self.id {
+                tex_unit.texture = 0;
+            }
+        }
+
+        unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
+
+        if let Some(ref image) = self.image {
+            (image.destroy)(image.display, image.image);
+        }
+    }
+}
+
+impl fmt::Debug for ExternalTexture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "External texture #{} ({}x{})", self.id, self.width, self.height)
+    }
+}
+
+impl GlObject for ExternalTexture {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl TextureExt for ExternalTexture {
+    #[inline]
+    fn get_texture_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    #[inline]
+    fn get_bind_point(&self) -> gl::types::GLenum {
+        gl::TEXTURE_EXTERNAL_OES
+    }
+
+    fn bind_to_current(&self, ctxt: &mut CommandContext) -> gl::types::GLenum {
+        let texture_unit = ctxt.state.active_texture;
+        if ctxt.state.texture_units[texture_unit as usize].texture != self.id {
+            unsafe { ctxt.gl.BindTexture(gl::TEXTURE_EXTERNAL_OES, self.id) };
+            ctxt.state.texture_units[texture_unit as usize].texture = self.id;
+        }
+
+        gl::TEXTURE_EXTERNAL_OES
+    }
+}
+
+impl<'a> AsUniformValue for &'a ExternalTexture {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::ExternalTexture(*self, None)
+    }
+}
+
+impl<'a> AsUniformValue for Sampler<'a, ExternalTexture> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::ExternalTexture(self.0, Some(self.1))
+    }
+}
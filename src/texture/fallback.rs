@@ -0,0 +1,321 @@
+//! Fallbacks for compressed texture formats that are not supported by the backend.
+//!
+//! Compressed formats are the ones whose support varies the most between drivers. For example
+//! BPTC is available on almost all desktop implementations but on very few OpenGL ES ones. By
+//! default, creating a texture with an unsupported format returns
+//! `TextureCreationError::FormatNotSupported`. The policy can be changed for each format with
+//! `Context::set_format_fallback_policy`.
+//!
+//! When a fallback is used, the texture object keeps its type but its storage uses another
+//! format. In that situation `read_compressed_data` returns `None` if the new format is not
+//! compressed.
+use std::cmp;
+
+use image_format::{ClientFormat, ClientFormatAny, CompressedFormat, CompressedSrgbFormat};
+use image_format::{SrgbFormat, TextureFormat, TextureFormatRequest, UncompressedFloatFormat};
+
+/// What to do when a texture is created with a compressed format that is not supported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FormatFallbackPolicy {
+    /// Return `TextureCreationError::FormatNotSupported`. This is the default.
+    Error,
+
+    /// Use the closest format that is supported, which can be another compressed format if
+    /// the texture is created without data.
+    ///
+    /// If the texture is created with data, only uncompressed formats are considered and the
+    /// data is decoded on the CPU. An error is returned if glium can't decode the format.
+    ClosestFormat,
+
+    /// Decode the data on the CPU and store it with the uncompressed equivalent of the format.
+    ///
+    /// Decoding is available for the S3TC and RGTC formats. An error is returned for the
+    /// other formats if the texture is created with data.
+    DecodeOnCpu,
+}
+
+impl Default for FormatFallbackPolicy {
+    #[inline]
+    fn default() -> FormatFallbackPolicy {
+        FormatFallbackPolicy::Error
+    }
+}
+
+/// Returns the compressed format that was requested, if any.
+///
+/// Fallbacks only apply to compressed formats. `client` is the format of the data that is
+/// used to create the texture.
+pub fn get_requested_format(format: TextureFormatRequest, client: Option<ClientFormatAny>)
+                            -> Option<TextureFormat>
+{
+    match (format, client) {
+        (TextureFormatRequest::Specific(f @ TextureFormat::CompressedFormat(_)), _) => Some(f),
+        (TextureFormatRequest::Specific(f @ TextureFormat::CompressedSrgbFormat(_)), _) => Some(f),
+        (TextureFormatRequest::AnyCompressed, Some(ClientFormatAny::CompressedFormat(f))) => {
+            Some(TextureFormat::CompressedFormat(f))
+        },
+        (TextureFormatRequest::AnyCompressedSrgb,
+         Some(ClientFormatAny::CompressedSrgbFormat(f))) => {
+            Some(TextureFormat::CompressedSrgbFormat(f))
+        },
+        _ => None,
+    }
+}
+
+/// Returns the formats that can replace `format`, from the closest to the farthest.
+///
+/// Compressed formats are skipped if `uncompressed_only` is true.
+pub fn get_fallback_formats(format: TextureFormat, uncompressed_only: bool)
+                            -> Vec<TextureFormat>
+{
+    let list = match format {
+        TextureFormat::CompressedFormat(format) => match format {
+            CompressedFormat::RgtcFormatU => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8),
+            ],
+            CompressedFormat::RgtcFormatI => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8),
+            ],
+            CompressedFormat::RgtcFormatUU => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8),
+            ],
+            CompressedFormat::RgtcFormatII => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::I8I8),
+            ],
+            CompressedFormat::BptcUnorm4 => vec![
+                TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt5Alpha),
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8U8),
+            ],
+            CompressedFormat::BptcSignedFloat3 | CompressedFormat::BptcUnsignedFloat3 => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16F16),
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::F16F16F16F16),
+            ],
+            CompressedFormat::S3tcDxt1NoAlpha => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8),
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8U8),
+            ],
+            CompressedFormat::S3tcDxt1Alpha | CompressedFormat::S3tcDxt3Alpha |
+            CompressedFormat::S3tcDxt5Alpha => vec![
+                TextureFormat::UncompressedFloat(UncompressedFloatFormat::U8U8U8U8),
+            ],
+        },
+
+        TextureFormat::CompressedSrgbFormat(format) => match format {
+            CompressedSrgbFormat::Bptc => vec![
+                TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt5Alpha),
+                TextureFormat::Srgb(SrgbFormat::U8U8U8U8),
+            ],
+            CompressedSrgbFormat::S3tcDxt1NoAlpha => vec![
+                TextureFormat::Srgb(SrgbFormat::U8U8U8),
+                TextureFormat::Srgb(SrgbFormat::U8U8U8U8),
+            ],
+            CompressedSrgbFormat::S3tcDxt1Alpha | CompressedSrgbFormat::S3tcDxt3Alpha |
+            CompressedSrgbFormat::S3tcDxt5Alpha => vec![
+                TextureFormat::Srgb(SrgbFormat::U8U8U8U8),
+            ],
+        },
+
+        _ => Vec::new(),
+    };
+
+    list.into_iter().filter(|f| {
+        !uncompressed_only || match *f {
+            TextureFormat::CompressedFormat(_) | TextureFormat::CompressedSrgbFormat(_) => false,
+            _ => true,
+        }
+    }).collect()
+}
+
+/// Kind of compressed blocks that can be decoded.
+#[derive(Debug, Copy, Clone)]
+enum BlockKind {
+    Dxt1 { alpha: bool },
+    Dxt3,
+    Dxt5,
+    Rgtc1 { signed: bool },
+    Rgtc2 { signed: bool },
+}
+
+/// Decodes compressed data on the CPU.
+///
+/// `layers` is the number of array layers or depth slices, each of which is a separate image of
+/// `width` by `height` texels. Returns the format of the decoded data and the data, or `None`
+/// if the format can't be decoded.
+pub fn decode(format: ClientFormatAny, width: u32, height: u32, layers: u32, data: &[u8])
+              -> Option<(ClientFormat, Vec<u8>)>
+{
+    let (kind, client) = match format {
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatU) =>
+            (BlockKind::Rgtc1 { signed: false }, ClientFormat::U8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatI) =>
+            (BlockKind::Rgtc1 { signed: true }, ClientFormat::I8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatUU) =>
+            (BlockKind::Rgtc2 { signed: false }, ClientFormat::U8U8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::RgtcFormatII) =>
+            (BlockKind::Rgtc2 { signed: true }, ClientFormat::I8I8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha) =>
+            (BlockKind::Dxt1 { alpha: false }, ClientFormat::U8U8U8U8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt1Alpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1Alpha) =>
+            (BlockKind::Dxt1 { alpha: true }, ClientFormat::U8U8U8U8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt3Alpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt3Alpha) =>
+            (BlockKind::Dxt3, ClientFormat::U8U8U8U8),
+        ClientFormatAny::CompressedFormat(CompressedFormat::S3tcDxt5Alpha) |
+        ClientFormatAny::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt5Alpha) =>
+            (BlockKind::Dxt5, ClientFormat::U8U8U8U8),
+        _ => return None,
+    };
+
+    let block_size = match kind {
+        BlockKind::Dxt1 { .. } | BlockKind::Rgtc1 { .. } => 8,
+        BlockKind::Dxt3 | BlockKind::Dxt5 | BlockKind::Rgtc2 { .. } => 16,
+    };
+
+    let components = client.get_num_components() as usize;
+    let (width, height, layers) = (width as usize, height as usize, layers as usize);
+    let (blocks_x, blocks_y) = ((width + 3) / 4, (height + 3) / 4);
+    let blocks_per_layer = blocks_x * blocks_y;
+
+    let mut output = vec![0; width * height * layers * components];
+    let mut texels = [[0; 4]; 16];
+
+    for (num, block) in data.chunks(block_size).take(blocks_per_layer * layers).enumerate() {
+        decode_block(kind, block, &mut texels);
+
+        let layer = num / blocks_per_layer;
+        let block_x = (num % blocks_per_layer) % blocks_x * 4;
+        let block_y = (num % blocks_per_layer) / blocks_x * 4;
+
+        for (texel_num, texel) in texels.iter().enumerate() {
+            let (x, y) = (block_x + texel_num % 4, block_y + texel_num / 4);
+            if x >= width || y >= height {
+                continue;
+            }
+
+            let offset = ((layer * height + y) * width + x) * components;
+            output[offset .. offset + components].copy_from_slice(&texel[.. components]);
+        }
+    }
+
+    Some((client, output))
+}
+
+/// Decodes a block of 4x4 texels. For RGTC, the red and green values are written to the first
+/// two components of each texel.
+fn decode_block(kind: BlockKind, block: &[u8], texels: &mut [[u8; 4]; 16]) {
+    match kind {
+        BlockKind::Dxt1 { alpha } => decode_color_block(block, true, alpha, texels),
+
+        BlockKind::Dxt3 => {
+            decode_color_block(&block[8 ..], false, false, texels);
+            for (num, texel) in texels.iter_mut().enumerate() {
+                let value = (block[num / 2] >> (4 * (num % 2))) & 0xf;
+                texel[3] = value * 17;
+            }
+        },
+
+        BlockKind::Dxt5 => {
+            decode_color_block(&block[8 ..], false, false, texels);
+            let alpha = decode_interpolated_block(block, false);
+            for (texel, alpha) in texels.iter_mut().zip(alpha.iter()) {
+                texel[3] = *alpha;
+            }
+        },
+
+        BlockKind::Rgtc1 { signed } => {
+            let red = decode_interpolated_block(block, signed);
+            for (texel, red) in texels.iter_mut().zip(red.iter()) {
+                texel[0] = *red;
+            }
+        },
+
+        BlockKind::Rgtc2 { signed } => {
+            let red = decode_interpolated_block(&block[.. 8], signed);
+            let green = decode_interpolated_block(&block[8 ..], signed);
+            for (texel, (red, green)) in texels.iter_mut().zip(red.iter().zip(green.iter())) {
+                texel[0] = *red;
+                texel[1] = *green;
+            }
+        },
+    }
+}
+
+/// Decodes the 8 bytes of a S3TC color block.
+///
+/// If `dxt1` is true, the block can use the three colors mode, in which case the fourth color is
+/// black and transparent if `alpha` is true.
+fn decode_color_block(block: &[u8], dxt1: bool, alpha: bool, texels: &mut [[u8; 4]; 16]) {
+    let color0 = block[0] as u16 | (block[1] as u16) << 8;
+    let color1 = block[2] as u16 | (block[3] as u16) << 8;
+    let (rgb0, rgb1) = (expand_rgb565(color0), expand_rgb565(color1));
+
+    let mut palette = [[0, 0, 0, 255]; 4];
+    for component in 0 .. 3 {
+        let (c0, c1) = (rgb0[component] as u32, rgb1[component] as u32);
+        palette[0][component] = c0 as u8;
+        palette[1][component] = c1 as u8;
+
+        if !dxt1 || color0 > color1 {
+            palette[2][component] = ((2 * c0 + c1) / 3) as u8;
+            palette[3][component] = ((c0 + 2 * c1) / 3) as u8;
+        } else {
+            palette[2][component] = ((c0 + c1) / 2) as u8;
+        }
+    }
+
+    if dxt1 && color0 <= color1 && alpha {
+        palette[3][3] = 0;
+    }
+
+    let indices = block[4] as u32 | (block[5] as u32) << 8 | (block[6] as u32) << 16 |
+                  (block[7] as u32) << 24;
+    for (num, texel) in texels.iter_mut().enumerate() {
+        *texel = palette[(indices >> (2 * num)) as usize & 3];
+    }
+}
+
+/// Decodes the 8 bytes of a DXT5 alpha block or of a RGTC channel.
+///
+/// Signed values are returned with the bit pattern of an `i8`.
+fn decode_interpolated_block(block: &[u8], signed: bool) -> [u8; 16] {
+    let (v0, v1) = if signed {
+        // -128 is interpreted as -127
+        (cmp::max(block[0] as i8 as i32, -127), cmp::max(block[1] as i8 as i32, -127))
+    } else {
+        (block[0] as i32, block[1] as i32)
+    };
+
+    let mut palette = [v0, v1, 0, 0, 0, 0, 0, 0];
+    if v0 > v1 {
+        for code in 2 .. 8 {
+            palette[code] = ((8 - code as i32) * v0 + (code as i32 - 1) * v1) / 7;
+        }
+    } else {
+        for code in 2 .. 6 {
+            palette[code] = ((6 - code as i32) * v0 + (code as i32 - 1) * v1) / 5;
+        }
+        palette[6] = if signed { -127 } else { 0 };
+        palette[7] = if signed { 127 } else { 255 };
+    }
+
+    let mut indices = 0u64;
+    for byte in 0 .. 6 {
+        indices |= (block[2 + byte] as u64) << (8 * byte);
+    }
+
+    let mut output = [0; 16];
+    for (num, value) in output.iter_mut().enumerate() {
+        *value = palette[(indices >> (3 * num)) as usize & 7] as u8;
+    }
+    output
+}
+
+#[inline]
+fn expand_rgb565(color: u16) -> [u8; 3] {
+    let red = ((color >> 11) & 0x1f) as u8;
+    let green = ((color >> 5) & 0x3f) as u8;
+    let blue = (color & 0x1f) as u8;
+    [(red << 3) | (red >> 2), (green << 2) | (green >> 4), (blue << 3) | (blue >> 2)]
+}
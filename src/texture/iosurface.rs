@@ -0,0 +1,262 @@
+/*!
+
+Textures whose content is stored in an `IOSurface`. Only available on macOS.
+
+An `IOSurface` is a buffer of memory that can be shared between processes and between the CPU,
+the video decoder and the GPU. Video frames decoded with VideoToolbox and frames captured with
+ScreenCaptureKit are delivered in IOSurfaces, which allows you to draw them without any copy.
+
+If you have a `CVPixelBuffer`, you can obtain its `IOSurface` with `CVPixelBufferGetIOSurface`.
+Multi-planar formats like NV12 must be bound one plane at a time, and converted to RGB in your
+shader.
+
+# Sampling
+
+IOSurface textures are rectangle textures. They must be sampled with a `sampler2DRect`, and
+their coordinates are in pixels instead of being between `0.0` and `1.0`:
+
+```glsl
+uniform sampler2DRect tex;
+
+void main() {
+    color = texture(tex, gl_FragCoord.xy);
+}
+```
+
+IOSurface textures don't have mipmaps and can't be read, written or rendered to by glium.
+
+*/
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+use std::error::Error;
+use std::os::raw::c_void;
+
+use gl;
+use utils::gl_get::get_output;
+use backend::Facade;
+use context::Context;
+use context::CommandContext;
+use ContextExt;
+use GlObject;
+use TextureExt;
+
+use uniforms::AsUniformValue;
+use uniforms::Sampler;
+use uniforms::UniformValue;
+
+type CglGetCurrentContext = extern "C" fn() -> *mut c_void;
+type CglTexImageIOSurface2D = extern "C" fn(*mut c_void, gl::types::GLenum, gl::types::GLenum,
+                                            gl::types::GLsizei, gl::types::GLsizei,
+                                            gl::types::GLenum, gl::types::GLenum,
+                                            *const c_void, gl::types::GLuint) -> i32;
+
+/// Error that can happen while creating an IOSurface texture.
+#[derive(Copy, Clone, Debug)]
+pub enum CreationError {
+    /// The backend doesn't provide the CGL functions required to bind an IOSurface.
+    NotSupported,
+
+    /// CGL refused to bind the IOSurface to the texture. Contains the `CGLError` code.
+    CglError(i32),
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for CreationError {
+    fn description(&self) -> &str {
+        use self::CreationError::*;
+        match *self {
+            NotSupported =>
+                "The backend doesn't provide the CGL functions required to bind an IOSurface",
+            CglError(_) =>
+                "CGL refused to bind the IOSurface to the texture",
+        }
+    }
+}
+
+/// Format of a plane of an `IOSurface`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IOSurfaceFormat {
+    /// `kCVPixelFormatType_32BGRA`. Sampled as RGBA.
+    B8G8R8A8,
+
+    /// One 8 bits component, like the luma plane of NV12.
+    U8,
+
+    /// Two 8 bits components, like the chroma plane of NV12.
+    U8U8,
+
+    /// `kCVPixelFormatType_64RGBAHalf`.
+    F16F16F16F16,
+}
+
+impl IOSurfaceFormat {
+    /// Returns the internal format, the format and the type to pass to `CGLTexImageIOSurface2D`.
+    fn to_glenums(&self) -> (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum) {
+        match *self {
+            IOSurfaceFormat::B8G8R8A8 => (gl::RGBA8, gl::BGRA, gl::UNSIGNED_INT_8_8_8_8_REV),
+            IOSurfaceFormat::U8 => (gl::R8, gl::RED, gl::UNSIGNED_BYTE),
+            IOSurfaceFormat::U8U8 => (gl::RG8, gl::RG, gl::UNSIGNED_BYTE),
+            IOSurfaceFormat::F16F16F16F16 => (gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT),
+        }
+    }
+}
+
+/// A rectangle texture whose content is a plane of an `IOSurface`.
+pub struct IOSurfaceTexture {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    width: u32,
+    height: u32,
+}
+
+impl IOSurfaceTexture {
+    /// Binds a plane of an `IOSurface` to a new texture. Use `0` for `plane` if the surface
+    /// isn't planar.
+    ///
+    /// # Safety
+    ///
+    /// `surface` must be a valid `IOSurfaceRef` and must stay alive for as long as the texture
+    /// exists. `width`, `height` and `format` must match the ones of the plane.
+    pub unsafe fn new<F: ?Sized>(facade: &F, surface: *const c_void, plane: u32,
+                                 format: IOSurfaceFormat, width: u32, height: u32)
+                                 -> Result<IOSurfaceTexture, CreationError> where F: Facade
+    {
+        let context = facade.get_context();
+
+        let get_current = context.get_proc_address("CGLGetCurrentContext");
+        let tex_image = context.get_proc_address("CGLTexImageIOSurface2D");
+        if get_current.is_null() || tex_image.is_null() {
+            return Err(CreationError::NotSupported);
+        }
+        let get_current: CglGetCurrentContext = mem::transmute(get_current);
+        let tex_image: CglTexImageIOSurface2D = mem::transmute(tex_image);
+
+        let mut ctxt = context.make_current();
+
+        let id = get_output(|ptr| ctxt.gl.GenTextures(1, ptr));
+
+        ctxt.gl.BindTexture(gl::TEXTURE_RECTANGLE, id);
+        let act = ctxt.state.active_texture as usize;
+        ctxt.state.texture_units[act].texture = id;
+
+        let (internal_format, client_format, ty) = format.to_glenums();
+        let err = tex_image(get_current(), gl::TEXTURE_RECTANGLE, internal_format,
+                            width as gl::types::GLsizei, height as gl::types::GLsizei,
+                            client_format, ty, surface, plane);
+
+        if err != 0 {
+            ctxt.state.texture_units[act].texture = 0;
+            ctxt.gl.DeleteTextures(1, [ id ].as_ptr());
+            return Err(CreationError::CglError(err));
+        }
+
+        Ok(IOSurfaceTexture {
+            context: context.clone(),
+            id: id,
+            width: width,
+            height: height,
+        })
+    }
+
+    /// Returns the width of the texture.
+    #[inline]
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the texture.
+    #[inline]
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the width and height of the texture.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Builds a `Sampler` marker object that allows you to indicate how the texture should be
+    /// sampled from inside a shader.
+    #[inline]
+    pub fn sampled(&self) -> Sampler<IOSurfaceTexture> {
+        Sampler(self, Default::default())
+    }
+}
+
+impl Drop for IOSurfaceTexture {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        // resetting the bindings
+        for tex_unit in ctxt.state.texture_units.iter_mut() {
+            if tex_unit.texture == self.id {
+                tex_unit.texture = 0;
+            }
+        }
+
+        unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
+    }
+}
+
+impl fmt::Debug for IOSurfaceTexture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "IOSurface texture #{} ({}x{})", self.id, self.width, self.height)
+    }
+}
+
+impl GlObject for IOSurfaceTexture {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl TextureExt for IOSurfaceTexture {
+    #[inline]
+    fn get_texture_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    #[inline]
+    fn get_bind_point(&self) -> gl::types::GLenum {
+        gl::TEXTURE_RECTANGLE
+    }
+
+    fn bind_to_current(&self, ctxt: &mut CommandContext) -> gl::types::GLenum {
+        let texture_unit = ctxt.state.active_texture;
+        if ctxt.state.texture_units[texture_unit as usize].texture != self.id {
+            unsafe { ctxt.gl.BindTexture(gl::TEXTURE_RECTANGLE, self.id) };
+            ctxt.state.texture_units[texture_unit as usize].texture = self.id;
+        }
+
+        gl::TEXTURE_RECTANGLE
+    }
+}
+
+impl<'a> AsUniformValue for &'a IOSurfaceTexture {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::IOSurfaceTexture(*self, None)
+    }
+}
+
+impl<'a> AsUniformValue for Sampler<'a, IOSurfaceTexture> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::IOSurfaceTexture(self.0, Some(self.1))
+    }
+}
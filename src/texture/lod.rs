@@ -0,0 +1,121 @@
+use std::cmp;
+
+use gl;
+use utils::gl_get::get_output;
+
+use context::CommandContext;
+use version::Api;
+use version::Version;
+use GlObject;
+use TextureExt;
+
+use texture::any::TextureAny;
+
+/// Informations about the mipmap levels of a texture, as reported by the backend.
+///
+/// These values are useful to find out which levels of a texture can be sampled, for example
+/// when levels are uploaded progressively.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LodDiagnostics {
+    /// Value of `GL_TEXTURE_BASE_LEVEL`, which is the first level that is sampled.
+    pub base_level: u32,
+
+    /// Value of `GL_TEXTURE_MAX_LEVEL`, which is the last level that can be sampled.
+    pub max_level: u32,
+
+    /// Number of mipmap levels that have been allocated for the texture.
+    pub allocated_levels: u32,
+
+    /// Number of allocated levels between the base level and the max level. These are the
+    /// levels that can be accessed by sampling the texture.
+    pub sampled_levels: u32,
+
+    /// Value of `GL_TEXTURE_IMMUTABLE_LEVELS`. `None` if the storage of the texture is not
+    /// immutable or if this is unknown.
+    pub immutable_levels: Option<u32>,
+
+    /// Residency informations. `None` if the texture is not sparse or if the backend doesn't
+    /// support sparse textures.
+    pub sparse: Option<SparseResidency>,
+}
+
+/// Residency informations about a sparse texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SparseResidency {
+    /// Value of `GL_NUM_SPARSE_LEVELS_ARB`. Levels below this value can be partially committed,
+    /// and the other levels form the mipmap tail that is committed all at once.
+    pub sparse_levels: u32,
+
+    /// Value of `GL_VIRTUAL_PAGE_SIZE_INDEX_ARB`, which is the index of the page size that was
+    /// chosen for the internal format of the texture.
+    pub virtual_page_size_index: u32,
+}
+
+/// Queries the mipmap levels of a texture.
+pub fn get_lod_diagnostics(ctxt: &mut CommandContext, texture: &TextureAny) -> LodDiagnostics {
+    let allocated_levels = texture.get_mipmap_levels();
+
+    // OpenGL ES 2 doesn't allow choosing the levels to sample
+    let (base_level, max_level) = if ctxt.version >= &Version(Api::Gl, 1, 2) ||
+                                     ctxt.version >= &Version(Api::GlEs, 3, 0)
+    {
+        (get_parameter(ctxt, texture, gl::TEXTURE_BASE_LEVEL) as u32,
+         get_parameter(ctxt, texture, gl::TEXTURE_MAX_LEVEL) as u32)
+    } else {
+        (0, allocated_levels - 1)
+    };
+
+    // `GL_TEXTURE_MAX_LEVEL` can legally be smaller than `GL_TEXTURE_BASE_LEVEL`
+    let sampled_levels = if base_level < allocated_levels {
+        (cmp::min(max_level, allocated_levels - 1) + 1).saturating_sub(base_level)
+    } else {
+        0
+    };
+
+    let immutable_levels = if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 0)
+    {
+        match get_parameter(ctxt, texture, gl::TEXTURE_IMMUTABLE_LEVELS) {
+            0 => None,
+            levels => Some(levels as u32),
+        }
+    } else {
+        None
+    };
+
+    let sparse = if ctxt.extensions.gl_arb_sparse_texture &&
+                    get_parameter(ctxt, texture, gl::TEXTURE_SPARSE_ARB) != 0
+    {
+        Some(SparseResidency {
+            sparse_levels: get_parameter(ctxt, texture, gl::NUM_SPARSE_LEVELS_ARB) as u32,
+            virtual_page_size_index: get_parameter(ctxt, texture,
+                                                   gl::VIRTUAL_PAGE_SIZE_INDEX_ARB) as u32,
+        })
+    } else {
+        None
+    };
+
+    LodDiagnostics {
+        base_level: base_level,
+        max_level: max_level,
+        allocated_levels: allocated_levels,
+        sampled_levels: sampled_levels,
+        immutable_levels: immutable_levels,
+        sparse: sparse,
+    }
+}
+
+/// Calls `glGetTexParameteriv` or one of its equivalents.
+fn get_parameter(ctxt: &mut CommandContext, texture: &TextureAny, pname: gl::types::GLenum)
+                 -> gl::types::GLint
+{
+    unsafe {
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+            get_output(|ptr| ctxt.gl.GetTextureParameteriv(texture.get_id(), pname, ptr))
+
+        } else {
+            let bind_point = texture.bind_to_current(ctxt);
+            get_output(|ptr| ctxt.gl.GetTexParameteriv(bind_point, pname, ptr))
+        }
+    }
+}
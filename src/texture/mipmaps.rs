@@ -0,0 +1,498 @@
+/*!
+
+Generates the mipmaps of a texture by rendering each level with a downsampling shader.
+
+Glium normally generates mipmaps with `glGenerateMipmap`. However this function isn't available
+for all formats (for example floating-point textures with OpenGL ES), and the quality of the
+result depends on the driver. Some drivers for example don't do the filtering in linear space
+for sRGB textures.
+
+The `MipmapsGenerator` in this module instead computes each level from the previous one with a
+fragment shader. The filter to use is chosen when creating the generator.
+
+```no_run
+# #[macro_use] extern crate glium;
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+use glium::texture::mipmaps::{MipmapsGenerator, MipmapsFilter};
+
+let texture = glium::texture::SrgbTexture2d::empty_with_mipmaps(&display,
+                                glium::texture::MipmapsOption::EmptyMipmaps, 256, 256).unwrap();
+// ... upload the main level ...
+
+let generator = MipmapsGenerator::new(&display, MipmapsFilter::Kaiser).unwrap();
+generator.generate_srgb(&texture).unwrap();
+# }
+```
+
+For other reductions, such as a hierarchical depth buffer that keeps the maximum depth of each
+block of texels, `render_levels` calls a closure with a framebuffer for each level and leaves
+the drawing to you.
+
+*/
+use std::fmt;
+use std::rc::Rc;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use backend::Facade;
+use context::Context;
+use framebuffer::{SimpleFrameBuffer, ValidationError};
+use index::{NoIndices, PrimitiveType};
+use program::{Program, ProgramChooserCreationError};
+use texture::{Texture2d, SrgbTexture2d, TextureAny, MipmapsOption, TextureCreationError};
+use texture::{UncompressedFloatFormat, SrgbFormat};
+use uniforms::{AsUniformValue, MagnifySamplerFilter};
+use vertex::VertexBuffer;
+use vertex::BufferCreationError;
+
+use BlitTarget;
+use DrawError;
+use DrawParameters;
+use Rect;
+use Surface;
+
+/// Filter used to compute a level from the previous one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MipmapsFilter {
+    /// Each texel is the average of the 2x2 texels of the previous level. This is what most
+    /// implementations of `glGenerateMipmap` do.
+    Box,
+
+    /// Each texel is computed from the 6x6 surrounding texels of the previous level with a
+    /// Kaiser-windowed sinc filter. The result is sharper than with the box filter, at the cost
+    /// of more texture fetches.
+    Kaiser,
+}
+
+/// Error that can happen while creating a `MipmapsGenerator`.
+#[derive(Clone, Debug)]
+pub enum MipmapsGeneratorCreationError {
+    /// Failed to compile the downsampling program.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to create the vertex buffer.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for MipmapsGeneratorCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for MipmapsGeneratorCreationError {
+    fn description(&self) -> &str {
+        use self::MipmapsGeneratorCreationError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile the downsampling program",
+            BufferCreationError(_) =>
+                "Failed to create the vertex buffer",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::MipmapsGeneratorCreationError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for MipmapsGeneratorCreationError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> MipmapsGeneratorCreationError {
+        MipmapsGeneratorCreationError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for MipmapsGeneratorCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> MipmapsGeneratorCreationError {
+        MipmapsGeneratorCreationError::BufferCreationError(err)
+    }
+}
+
+/// Error that can happen while generating mipmaps.
+#[derive(Clone, Debug)]
+pub enum MipmapsGenerationError {
+    /// Failed to create the temporary texture that levels are rendered to.
+    TextureCreationError(TextureCreationError),
+
+    /// A level of the texture can't be used as a framebuffer attachment.
+    ValidationError(ValidationError),
+
+    /// Failed to render a level.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for MipmapsGenerationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for MipmapsGenerationError {
+    fn description(&self) -> &str {
+        use self::MipmapsGenerationError::*;
+        match *self {
+            TextureCreationError(_) =>
+                "Failed to create the temporary texture",
+            ValidationError(_) =>
+                "A level of the texture can't be used as a framebuffer attachment",
+            DrawError(_) =>
+                "Failed to render a level",
+        }
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        use self::MipmapsGenerationError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            ValidationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for MipmapsGenerationError {
+    #[inline]
+    fn from(err: TextureCreationError) -> MipmapsGenerationError {
+        MipmapsGenerationError::TextureCreationError(err)
+    }
+}
+
+impl From<ValidationError> for MipmapsGenerationError {
+    #[inline]
+    fn from(err: ValidationError) -> MipmapsGenerationError {
+        MipmapsGenerationError::ValidationError(err)
+    }
+}
+
+impl From<DrawError> for MipmapsGenerationError {
+    #[inline]
+    fn from(err: DrawError) -> MipmapsGenerationError {
+        MipmapsGenerationError::DrawError(err)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct SpriteVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(SpriteVertex, position);
+
+/// Generates the mipmaps of textures with a downsampling shader.
+///
+/// Creating a generator compiles a program, so you are encouraged to keep it around if you need
+/// to generate the mipmaps of multiple textures.
+pub struct MipmapsGenerator {
+    context: Rc<Context>,
+    program: Program,
+    vertex_buffer: VertexBuffer<SpriteVertex>,
+    filter: MipmapsFilter,
+}
+
+impl MipmapsGenerator {
+    /// Builds a new generator that uses the given filter.
+    ///
+    /// Requires GLSL 1.40 or GLSL ES 3.00, as the shader uses `texelFetch`.
+    pub fn new<F: ?Sized>(facade: &F, filter: MipmapsFilter)
+                          -> Result<MipmapsGenerator, MipmapsGeneratorCreationError>
+                          where F: Facade
+    {
+        let fragment_body = fragment_shader_body(filter);
+        let fragment_140 = format!("#version 140\n{}", fragment_body);
+        let fragment_300es = format!("#version 300 es\nprecision highp float;\n\
+                                      precision highp sampler2D;\n{}", fragment_body);
+
+        let program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    in vec2 position;
+
+                    void main() {
+                        gl_Position = vec4(position, 0.0, 1.0);
+                    }
+                ",
+                fragment: &fragment_140,
+            },
+
+            300 es => {
+                vertex: "
+                    #version 300 es
+
+                    in vec2 position;
+
+                    void main() {
+                        gl_Position = vec4(position, 0.0, 1.0);
+                    }
+                ",
+                fragment: &fragment_300es,
+            },
+        ));
+
+        let vertex_buffer = try!(VertexBuffer::new(facade, &[
+            SpriteVertex { position: [-1.0, -1.0] },
+            SpriteVertex { position: [-1.0,  1.0] },
+            SpriteVertex { position: [ 1.0, -1.0] },
+            SpriteVertex { position: [ 1.0,  1.0] },
+        ]));
+
+        Ok(MipmapsGenerator {
+            context: facade.get_context().clone(),
+            program: program,
+            vertex_buffer: vertex_buffer,
+            filter: filter,
+        })
+    }
+
+    /// Returns the filter used by this generator.
+    #[inline]
+    pub fn get_filter(&self) -> MipmapsFilter {
+        self.filter
+    }
+
+    /// Overwrites all the mipmap levels of the texture, except the main level.
+    ///
+    /// Does nothing if the texture has no mipmap.
+    pub fn generate(&self, texture: &Texture2d) -> Result<(), MipmapsGenerationError> {
+        if texture.get_mipmap_levels() <= 1 {
+            return Ok(());
+        }
+
+        let (width, height) = level_dimensions(texture.get_width(), texture.get_height().unwrap(),
+                                               1);
+
+        // levels are rendered with at least half-float precision if possible, so that
+        // floating-point textures don't lose their precision
+        let temporary = match Texture2d::empty_with_format(&self.context,
+                                                           UncompressedFloatFormat::F16F16F16F16,
+                                                           MipmapsOption::NoMipmap, width, height)
+        {
+            Ok(t) => t,
+            Err(_) => try!(Texture2d::empty_with_format(&self.context,
+                                                        UncompressedFloatFormat::U8U8U8U8,
+                                                        MipmapsOption::NoMipmap, width, height)),
+        };
+
+        for level in 1 .. texture.get_mipmap_levels() {
+            let mut framebuffer = try!(SimpleFrameBuffer::new(&self.context, &temporary));
+            let target = try!(SimpleFrameBuffer::new(&self.context,
+                                                     texture.mipmap(level).unwrap()));
+            try!(self.render_level(&mut framebuffer, &target, texture, texture.get_width(),
+                                   texture.get_height().unwrap(), level));
+        }
+
+        Ok(())
+    }
+
+    /// Overwrites all the mipmap levels of the texture, except the main level.
+    ///
+    /// The filtering is done in linear space. Does nothing if the texture has no mipmap.
+    pub fn generate_srgb(&self, texture: &SrgbTexture2d) -> Result<(), MipmapsGenerationError> {
+        if texture.get_mipmap_levels() <= 1 {
+            return Ok(());
+        }
+
+        let (width, height) = level_dimensions(texture.get_width(), texture.get_height().unwrap(),
+                                               1);
+
+        // rendering to an sRGB texture converts the output of the shader back to sRGB, and
+        // blitting between two textures of the same format copies the texels as they are
+        let temporary = try!(SrgbTexture2d::empty_with_format(&self.context,
+                                                              SrgbFormat::U8U8U8U8,
+                                                              MipmapsOption::NoMipmap,
+                                                              width, height));
+
+        for level in 1 .. texture.get_mipmap_levels() {
+            let mut framebuffer = try!(SimpleFrameBuffer::new(&self.context, &temporary));
+            let target = try!(SimpleFrameBuffer::new(&self.context,
+                                                     texture.mipmap(level).unwrap()));
+            try!(self.render_level(&mut framebuffer, &target, texture, texture.get_width(),
+                                   texture.get_height().unwrap(), level));
+        }
+
+        Ok(())
+    }
+
+    /// Renders `level` into the bottom-left corner of `temporary` by sampling the previous level
+    /// of `texture`, then copies the result to `target`.
+    ///
+    /// The level isn't rendered to directly, as sampling from the texture that is being
+    /// rendered to is undefined behavior even if the levels are different.
+    fn render_level<T>(&self, temporary: &mut SimpleFrameBuffer, target: &SimpleFrameBuffer,
+                       texture: T, width: u32, height: u32, level: u32)
+                       -> Result<(), DrawError>
+                       where T: AsUniformValue
+    {
+        let (width, height) = level_dimensions(width, height, level);
+
+        let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+
+        let uniforms = uniform! {
+            tex: texture,
+            level: (level - 1) as i32,
+        };
+
+        let parameters = DrawParameters {
+            viewport: Some(rect),
+            .. Default::default()
+        };
+
+        try!(temporary.draw(&self.vertex_buffer, &NoIndices(PrimitiveType::TriangleStrip),
+                            &self.program, &uniforms, &parameters));
+
+        temporary.blit_color(&rect, target,
+                             &BlitTarget { left: 0, bottom: 0, width: width as i32,
+                                           height: height as i32 },
+                             MagnifySamplerFilter::Nearest);
+
+        Ok(())
+    }
+}
+
+/// Level of a texture being rendered by `render_levels`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ReductionLevel {
+    /// The level being rendered. The first one is `1`.
+    pub level: u32,
+
+    /// Width and height of the level being rendered.
+    pub dimensions: (u32, u32),
+
+    /// Width and height of the previous level.
+    ///
+    /// A dimension of the previous level can be odd, in which case the last texel of each row
+    /// or column has no matching texel in the level being rendered. Shaders that compute a
+    /// minimum or a maximum must include it in order to be conservative.
+    pub source_dimensions: (u32, u32),
+}
+
+/// Renders each mipmap level of a texture, except the main level, in increasing order.
+///
+/// `draw` is called once per level with a framebuffer that has the level as its attachment,
+/// and must draw the level by reading from the previous one, usually with a full-screen quad
+/// and `texelFetch`. This is how reduction chains such as bloom downsampling or hierarchical
+/// depth buffers are built. The shader must not read from the level that is being rendered.
+///
+/// The dimensions of each level are checked against the dimensions of the framebuffer before
+/// calling `draw`. Does nothing if the texture has no mipmap.
+pub fn render_levels<F: ?Sized, D>(facade: &F, texture: &TextureAny, mut draw: D)
+                                   -> Result<(), MipmapsGenerationError>
+                                   where F: Facade,
+                                         D: FnMut(&mut SimpleFrameBuffer, ReductionLevel)
+                                                  -> Result<(), DrawError>
+{
+    let width = texture.get_width();
+    let height = texture.get_height().unwrap_or(1);
+
+    for level in 1 .. texture.get_mipmap_levels() {
+        let dimensions = level_dimensions(width, height, level);
+        let mut framebuffer = try!(SimpleFrameBuffer::with_mipmap_level(facade, texture, level));
+        assert_eq!(framebuffer.get_dimensions(), dimensions);
+
+        try!(draw(&mut framebuffer, ReductionLevel {
+            level: level,
+            dimensions: dimensions,
+            source_dimensions: level_dimensions(width, height, level - 1),
+        }));
+    }
+
+    Ok(())
+}
+
+/// Returns the dimensions of a mipmap level.
+#[inline]
+fn level_dimensions(width: u32, height: u32, level: u32) -> (u32, u32) {
+    (::std::cmp::max(width >> level, 1), ::std::cmp::max(height >> level, 1))
+}
+
+/// Returns the body of the fragment shader, without the `#version` directive.
+///
+/// The weights of the filter are computed here and written as constants in the source code.
+fn fragment_shader_body(filter: MipmapsFilter) -> String {
+    // offsets of the texels of the previous level along each axis, relative to `2 * coord`, and
+    // their weights
+    let taps: Vec<(i32, f64)> = match filter {
+        MipmapsFilter::Box => vec![(0, 0.5), (1, 0.5)],
+        MipmapsFilter::Kaiser => {
+            let weights = (-2 .. 4).map(|offset| {
+                // distance to the center of the destination texel, in destination texels
+                let distance = (offset as f64 - 0.5) / 2.0;
+                (offset, sinc(distance) * kaiser(distance / KAISER_RADIUS, KAISER_ALPHA))
+            }).collect::<Vec<_>>();
+
+            let sum = weights.iter().fold(0.0, |s, &(_, w)| s + w);
+            weights.into_iter().map(|(o, w)| (o, w / sum)).collect()
+        },
+    };
+
+    let mut fetches = String::new();
+    for &(y, wy) in taps.iter() {
+        for &(x, wx) in taps.iter() {
+            fetches.push_str(&format!("    color += {:.8} * texelFetch(tex, clamp(base + \
+                                       ivec2({}, {}), ivec2(0), max_coord), level);\n",
+                                      wx * wy, x, y));
+        }
+    }
+
+    format!("
+        uniform sampler2D tex;
+        uniform int level;
+
+        out vec4 f_color;
+
+        void main() {{
+            ivec2 base = ivec2(gl_FragCoord.xy) * 2;
+            ivec2 max_coord = textureSize(tex, level) - ivec2(1);
+
+            vec4 color = vec4(0.0);
+        {}
+            f_color = color;
+        }}
+    ", fetches)
+}
+
+/// Number of destination texels covered by the Kaiser filter on each side of the center.
+const KAISER_RADIUS: f64 = 1.5;
+
+/// Shape parameter of the Kaiser window.
+const KAISER_ALPHA: f64 = 4.0;
+
+/// Normalized sinc function.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Kaiser window, where `x` is between -1 and 1.
+fn kaiser(x: f64, alpha: f64) -> f64 {
+    if x.abs() > 1.0 {
+        return 0.0;
+    }
+
+    bessel_i0(alpha * (1.0 - x * x).sqrt()) / bessel_i0(alpha)
+}
+
+/// Modified Bessel function of the first kind of order zero.
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+
+    for k in 1 .. 32 {
+        term *= (half / k as f64) * (half / k as f64);
+        sum += term;
+    }
+
+    sum
+}
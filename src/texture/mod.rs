@@ -82,6 +82,17 @@ alternative to uniform buffers and SSBOs.
 
 See the `buffer_textures` module for more infos.
 
+# External textures
+
+An `ExternalTexture` is a texture whose content comes from an `EGLImage`, for example a frame
+of a camera or of a video decoder imported from a Linux dma-buf. External textures are sampled
+with a `samplerExternalOES` and can't be modified by glium.
+
+See the `external` module for more infos.
+
+On macOS, an `IOSurfaceTexture` can be built from an `IOSurface` in order to draw video frames
+without copying them. See the `iosurface` module.
+
 # About sRGB
 
 For historical reasons, the color data contained in almost all image files are not in RGB but
@@ -96,6 +107,9 @@ fragment shader to be in linear RGB and then turns it into sRGB before writing i
 Sampling from an sRGB texture will convert the texture colors from sRGB to RGB. If you create a
 regular RGB texture and put sRGB data in it, then the result will be too bright.
 
+When drawing on the default framebuffer, the conversion only happens if the default framebuffer
+is in sRGB. See `Context::is_default_framebuffer_srgb` and `Context::set_default_framebuffer_srgb`.
+
 # Bindless textures
 
 *Bindless textures are a very recent feature that is supported only by recent hardware and
@@ -114,6 +128,7 @@ You can use thousands of textures if you want.
 
 use std::borrow::Cow;
 use std::fmt;
+use std::mem;
 use std::error::Error;
 
 use image_format::FormatNotSupportedError;
@@ -125,8 +140,10 @@ pub use image_format::{CompressedSrgbFormat, SrgbFormat};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
 pub use self::any::{TextureAnyImage, Dimensions};
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
+pub use self::fallback::FormatFallbackPolicy;
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
-pub use self::pixel::PixelValue;
+pub use self::lod::{LodDiagnostics, SparseResidency};
+pub use self::pixel::{PixelValue, Half, Rgb10A2, R11G11B10F, Rgb9E5};
 pub use self::ty_support::{is_texture_1d_supported, is_texture_2d_supported};
 pub use self::ty_support::{is_texture_3d_supported, is_texture_1d_array_supported};
 pub use self::ty_support::{is_texture_2d_array_supported, is_texture_2d_multisample_supported};
@@ -135,10 +152,16 @@ pub use self::ty_support::is_cubemap_arrays_supported;
 
 pub mod bindless;
 pub mod buffer_texture;
+pub mod external;
+#[cfg(target_os = "macos")]
+pub mod iosurface;
+pub mod mipmaps;
 pub mod pixel_buffer;
 
 mod any;
+mod fallback;
 mod get_format;
+mod lod;
 mod pixel;
 mod ty_support;
 
@@ -146,6 +169,7 @@ include!(concat!(env!("OUT_DIR"), "/textures.rs"));
 
 /// Represents a layer of a cubemap.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[allow(missing_docs)]      // TODO:
 pub enum CubeLayer {
     PositiveX,
@@ -174,6 +198,43 @@ impl CubeLayer {
     }
 }
 
+/// Alignment in bytes of the start of each row of pixels in memory.
+///
+/// Glium always exchanges tightly-packed rows with OpenGL. Images whose rows are padded, for
+/// example bitmaps whose rows are aligned to 4 bytes, can be converted with
+/// `RawImage2d::from_aligned_rows` and `RawImage2d::to_aligned_rows`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RowAlignment {
+    /// Rows are tightly packed.
+    One,
+    /// Rows start at a multiple of 2 bytes.
+    Two,
+    /// Rows start at a multiple of 4 bytes. This is the default of OpenGL.
+    Four,
+    /// Rows start at a multiple of 8 bytes.
+    Eight,
+}
+
+impl RowAlignment {
+    /// Returns the alignment in bytes.
+    #[inline]
+    pub fn get_bytes(&self) -> usize {
+        match *self {
+            RowAlignment::One => 1,
+            RowAlignment::Two => 2,
+            RowAlignment::Four => 4,
+            RowAlignment::Eight => 8,
+        }
+    }
+
+    /// Returns the number of bytes between the start of two rows of `row_size` bytes.
+    #[inline]
+    pub fn get_row_stride(&self, row_size: usize) -> usize {
+        let alignment = self.get_bytes();
+        (row_size + alignment - 1) / alignment * alignment
+    }
+}
+
 /// Represents a kind of texture.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[allow(missing_docs)]      // TODO:
@@ -188,6 +249,7 @@ pub enum TextureKind {
 
 /// Describes what to do about mipmaps during texture creation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum MipmapsOption {
     /// No mipmap will be allocated or generated.
     NoMipmap,
@@ -265,6 +327,7 @@ impl From<CompressedMipmapsOption> for MipmapsOption {
 
 /// Describes what to do about mipmaps during compressed texture creation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum CompressedMipmapsOption {
     /// No mipmaps will be allocated or generated.
     NoMipmap,
@@ -497,6 +560,51 @@ impl<'a, T: Clone + 'a> RawImage2d<'a, T> {
         RawImage2d::from_raw_rgba(data, dimensions)
     }
 
+    /// Builds a raw image from data whose rows start at a multiple of `alignment` bytes, by
+    /// removing the padding at the end of each row.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `data` is too small, or if the padding isn't a multiple of the size of `T`.
+    pub fn from_aligned_rows(data: &[T], dimensions: (u32, u32), format: ClientFormat,
+                             alignment: RowAlignment) -> RawImage2d<'a, T>
+    {
+        let (row_len, stride_len) = aligned_row_lengths::<T>(dimensions.0, format, alignment);
+        assert!(data.len() >= stride_len * dimensions.1 as usize, "Not enough data");
+
+        let data = data.chunks(stride_len)
+                       .take(dimensions.1 as usize)
+                       .flat_map(|row| row[.. row_len].iter())
+                       .cloned()
+                       .collect();
+
+        RawImage2d {
+            data: Cow::Owned(data),
+            width: dimensions.0,
+            height: dimensions.1,
+            format: format,
+        }
+    }
+
+    /// Returns the data of the image with each row starting at a multiple of `alignment` bytes.
+    /// The padding is filled with `T::default()`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the padding isn't a multiple of the size of `T`.
+    pub fn to_aligned_rows(&self, alignment: RowAlignment) -> Vec<T> where T: Default {
+        let (row_len, stride_len) = aligned_row_lengths::<T>(self.width, self.format, alignment);
+
+        let mut data = Vec::with_capacity(stride_len * self.height as usize);
+        for row in self.data.chunks(row_len).take(self.height as usize) {
+            data.extend(row.iter().cloned());
+            for _ in row_len .. stride_len {
+                data.push(T::default());
+            }
+        }
+        data
+    }
+
     ///Transforms a Vec<RawImage1d> into a RawImage2d
     pub fn from_vec_raw1d(arr: &Vec<RawImage1d<'a, T>>) -> RawImage2d<'a, T> {
         let width   = arr[0].width;
@@ -525,6 +633,21 @@ impl<'a, T: Clone + 'a> RawImage2d<'a, T> {
     }
 }
 
+/// Returns the number of elements of type `T` in a row of pixels, and between the start of two
+/// rows aligned to `alignment`.
+fn aligned_row_lengths<T>(width: u32, format: ClientFormat, alignment: RowAlignment)
+                          -> (usize, usize)
+{
+    let row_size = width as usize * format.get_size();
+    let stride = alignment.get_row_stride(row_size);
+
+    let elem_size = mem::size_of::<T>();
+    assert!(row_size % elem_size == 0 && stride % elem_size == 0,
+            "The rows can't be represented with this type");
+
+    (row_size / elem_size, stride / elem_size)
+}
+
 impl<'a, P: PixelValue + Clone> Texture2dDataSource<'a> for Vec<Vec<P>> {
     type Data = P;
 
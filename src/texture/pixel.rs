@@ -203,6 +203,337 @@ unsafe impl PixelValue for (f32, f32, f32, f32) {
     }
 }
 
+/// A 16-bits floating-point value, as stored in `F16` textures.
+///
+/// Rust doesn't have a native half-float type. This type stores the raw bits of the value and
+/// can be converted from and to `f32`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Half(pub u16);
+
+impl Half {
+    /// Converts a `f32` to the nearest half-float. Values that are too large become infinite.
+    pub fn from_f32(value: f32) -> Half {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7fffff;
+
+        // infinity and NaN
+        if exponent == 0xff {
+            return Half(sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 });
+        }
+
+        let exponent = exponent - 127 + 15;
+
+        if exponent >= 0x1f {
+            return Half(sign | 0x7c00);
+        }
+
+        // the value is too small to be normalized
+        if exponent <= 0 {
+            if exponent < -10 {
+                return Half(sign);
+            }
+
+            let mantissa = mantissa | 0x800000;
+            let shift = (14 - exponent) as u32;
+            let round = (mantissa >> (shift - 1)) & 1;
+            return Half(sign | ((mantissa >> shift) + round) as u16);
+        }
+
+        // rounding may carry into the exponent, which gives the right result
+        let half = ((exponent as u32) << 10) | (mantissa >> 13);
+        let round = (mantissa >> 12) & 1;
+        Half(sign | (half + round) as u16)
+    }
+
+    /// Converts the half-float to a `f32`. This conversion is exact.
+    pub fn to_f32(&self) -> f32 {
+        let bits = self.0 as u32;
+        let sign = (bits & 0x8000) << 16;
+        let exponent = (bits >> 10) & 0x1f;
+        let mantissa = bits & 0x3ff;
+
+        let bits = if exponent == 0 {
+            if mantissa == 0 {
+                sign
+            } else {
+                // denormalized values become normalized in `f32`
+                let mut exponent = 127 - 14;
+                let mut mantissa = mantissa;
+                while mantissa & 0x400 == 0 {
+                    mantissa <<= 1;
+                    exponent -= 1;
+                }
+                sign | (exponent << 23) | ((mantissa & 0x3ff) << 13)
+            }
+
+        } else if exponent == 0x1f {
+            sign | 0x7f800000 | (mantissa << 13)
+
+        } else {
+            sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)
+        };
+
+        f32::from_bits(bits)
+    }
+}
+
+impl From<f32> for Half {
+    #[inline]
+    fn from(value: f32) -> Half {
+        Half::from_f32(value)
+    }
+}
+
+impl From<Half> for f32 {
+    #[inline]
+    fn from(value: Half) -> f32 {
+        value.to_f32()
+    }
+}
+
+unsafe impl PixelValue for Half {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16
+    }
+}
+
+unsafe impl PixelValue for (Half, Half) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16
+    }
+}
+
+unsafe impl PixelValue for (Half, Half, Half) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16F16
+    }
+}
+
+unsafe impl PixelValue for (Half, Half, Half, Half) {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F16F16F16F16
+    }
+}
+
+/// A pixel with 10 bits for each of red, green and blue and 2 bits for alpha, packed in a `u32`,
+/// as stored in `U10U10U10U2` textures.
+///
+/// Red is stored in the lowest bits and alpha in the highest bits.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb10A2(pub u32);
+
+impl Rgb10A2 {
+    /// Packs the components of a pixel. Only the lowest 10 bits of `red`, `green` and `blue`
+    /// and the lowest 2 bits of `alpha` are used.
+    #[inline]
+    pub fn new(red: u16, green: u16, blue: u16, alpha: u8) -> Rgb10A2 {
+        Rgb10A2((red as u32 & 0x3ff) | ((green as u32 & 0x3ff) << 10) |
+                ((blue as u32 & 0x3ff) << 20) | ((alpha as u32 & 0x3) << 30))
+    }
+
+    /// Returns the red, green, blue and alpha components of the pixel.
+    #[inline]
+    pub fn components(&self) -> (u16, u16, u16, u8) {
+        ((self.0 & 0x3ff) as u16, ((self.0 >> 10) & 0x3ff) as u16,
+         ((self.0 >> 20) & 0x3ff) as u16, (self.0 >> 30) as u8)
+    }
+
+    /// Returns the components of the pixel normalized between `0.0` and `1.0`.
+    #[inline]
+    pub fn to_f32(&self) -> (f32, f32, f32, f32) {
+        let (r, g, b, a) = self.components();
+        (r as f32 / 1023.0, g as f32 / 1023.0, b as f32 / 1023.0, a as f32 / 3.0)
+    }
+}
+
+unsafe impl PixelValue for Rgb10A2 {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::U10U10U10U2
+    }
+}
+
+/// A pixel with three unsigned floating-point components packed in a `u32`, as stored in
+/// `F11F11F10` textures.
+///
+/// Red and green have 11 bits and blue has 10 bits. Red is stored in the lowest bits. Negative
+/// values can't be represented and are clamped to zero.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct R11G11B10F(pub u32);
+
+impl R11G11B10F {
+    /// Packs the components of a pixel. Values that are too large become infinite.
+    #[inline]
+    pub fn from_f32(red: f32, green: f32, blue: f32) -> R11G11B10F {
+        R11G11B10F(pack_unsigned_float(red, 6) | (pack_unsigned_float(green, 6) << 11) |
+                   (pack_unsigned_float(blue, 5) << 22))
+    }
+
+    /// Returns the red, green and blue components of the pixel. This conversion is exact.
+    #[inline]
+    pub fn to_f32(&self) -> (f32, f32, f32) {
+        (unpack_unsigned_float(self.0 & 0x7ff, 6),
+         unpack_unsigned_float((self.0 >> 11) & 0x7ff, 6),
+         unpack_unsigned_float(self.0 >> 22, 5))
+    }
+}
+
+impl From<(f32, f32, f32)> for R11G11B10F {
+    #[inline]
+    fn from((red, green, blue): (f32, f32, f32)) -> R11G11B10F {
+        R11G11B10F::from_f32(red, green, blue)
+    }
+}
+
+impl From<R11G11B10F> for (f32, f32, f32) {
+    #[inline]
+    fn from(value: R11G11B10F) -> (f32, f32, f32) {
+        value.to_f32()
+    }
+}
+
+unsafe impl PixelValue for R11G11B10F {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F11F11F10
+    }
+}
+
+/// Converts a `f32` to an unsigned float with a 5-bits exponent and `mantissa_bits` bits of
+/// mantissa.
+fn pack_unsigned_float(value: f32, mantissa_bits: u32) -> u32 {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7fffff;
+    let infinity = 0x1f << mantissa_bits;
+
+    // NaN
+    if exponent == 0xff && mantissa != 0 {
+        return infinity | 1;
+    }
+
+    // negative values, including negative infinity
+    if bits & 0x80000000 != 0 {
+        return 0;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return infinity;
+    }
+
+    let shift = 23 - mantissa_bits;
+
+    // the value is too small to be normalized
+    if exponent <= 0 {
+        let shift = shift as i32 + 1 - exponent;
+        if shift > 24 {
+            return 0;
+        }
+
+        let mantissa = mantissa | 0x800000;
+        let round = (mantissa >> (shift - 1)) & 1;
+        return (mantissa >> shift) + round;
+    }
+
+    // rounding may carry into the exponent, which gives the right result
+    let packed = ((exponent as u32) << mantissa_bits) | (mantissa >> shift);
+    let round = (mantissa >> (shift - 1)) & 1;
+    packed + round
+}
+
+/// Opposite of `pack_unsigned_float`.
+fn unpack_unsigned_float(value: u32, mantissa_bits: u32) -> f32 {
+    let exponent = value >> mantissa_bits;
+    let mantissa = value & ((1 << mantissa_bits) - 1);
+
+    if exponent == 0 {
+        mantissa as f32 / (1u32 << (14 + mantissa_bits)) as f32
+
+    } else if exponent == 0x1f {
+        if mantissa == 0 { ::std::f32::INFINITY } else { ::std::f32::NAN }
+
+    } else {
+        f32::from_bits(((exponent + 127 - 15) << 23) | (mantissa << (23 - mantissa_bits)))
+    }
+}
+
+/// A pixel with three 9-bits components that share a 5-bits exponent, packed in a `u32`, as
+/// stored in `F9F9F9` textures.
+///
+/// Red is stored in the lowest bits and the exponent in the highest bits. Negative values
+/// can't be represented and are clamped to zero.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb9E5(pub u32);
+
+impl Rgb9E5 {
+    /// Packs the components of a pixel. Values that are too large are clamped to the largest
+    /// representable value.
+    pub fn from_f32(red: f32, green: f32, blue: f32) -> Rgb9E5 {
+        // largest representable value, which is `511 / 512 * 2^16`
+        const MAX: f32 = 65408.0;
+
+        // `max` and `min` ignore NaNs, so they become zero
+        let red = red.max(0.0).min(MAX);
+        let green = green.max(0.0).min(MAX);
+        let blue = blue.max(0.0).min(MAX);
+
+        let max_component = red.max(green).max(blue);
+        let mut exponent = max_component.log2().floor().max(-16.0) as i32 + 1 + 15;
+
+        // rounding the largest component may require one more bit
+        if (max_component / 2.0f32.powi(exponent - 15 - 9) + 0.5).floor() >= 512.0 {
+            exponent += 1;
+        }
+
+        let scale = 2.0f32.powi(exponent - 15 - 9);
+        let red = (red / scale + 0.5).floor() as u32;
+        let green = (green / scale + 0.5).floor() as u32;
+        let blue = (blue / scale + 0.5).floor() as u32;
+
+        Rgb9E5(red | (green << 9) | (blue << 18) | ((exponent as u32) << 27))
+    }
+
+    /// Returns the red, green and blue components of the pixel. This conversion is exact.
+    #[inline]
+    pub fn to_f32(&self) -> (f32, f32, f32) {
+        let scale = 2.0f32.powi((self.0 >> 27) as i32 - 15 - 9);
+        ((self.0 & 0x1ff) as f32 * scale, ((self.0 >> 9) & 0x1ff) as f32 * scale,
+         ((self.0 >> 18) & 0x1ff) as f32 * scale)
+    }
+}
+
+impl From<(f32, f32, f32)> for Rgb9E5 {
+    #[inline]
+    fn from((red, green, blue): (f32, f32, f32)) -> Rgb9E5 {
+        Rgb9E5::from_f32(red, green, blue)
+    }
+}
+
+impl From<Rgb9E5> for (f32, f32, f32) {
+    #[inline]
+    fn from(value: Rgb9E5) -> (f32, f32, f32) {
+        value.to_f32()
+    }
+}
+
+unsafe impl PixelValue for Rgb9E5 {
+    #[inline]
+    fn get_format() -> super::ClientFormat {
+        super::ClientFormat::F9F9F9
+    }
+}
+
 #[cfg(feature = "image")]
 unsafe impl PixelValue for image::Rgb<u8> {
     #[inline]
@@ -11,7 +11,7 @@ use std::ops::{Deref, DerefMut};
 use backend::Facade;
 
 use GlObject;
-use buffer::{ReadError, Buffer, BufferType, BufferMode};
+use buffer::{ReadError, Buffer, BufferType};
 use gl;
 
 use texture::PixelValue;
@@ -27,11 +27,15 @@ pub struct PixelBuffer<T> where T: PixelValue {
 
 impl<T> PixelBuffer<T> where T: PixelValue {
     /// Builds a new buffer with an uninitialized content.
+    ///
+    /// Unless the buffer is very large, its storage is taken from a pool managed by the context
+    /// and is returned to this pool when the `PixelBuffer` is destroyed. This avoids allocating
+    /// a new buffer for each transfer.
     #[inline]
     pub fn new_empty<F: ?Sized>(facade: &F, capacity: usize) -> PixelBuffer<T> where F: Facade {
         PixelBuffer {
-            buffer: Buffer::empty_array(facade, BufferType::PixelPackBuffer, capacity,
-                                            BufferMode::Default).unwrap(),
+            buffer: Buffer::empty_array_pooled(facade, BufferType::PixelPackBuffer,
+                                               capacity).unwrap(),
             dimensions: Cell::new(None),
         }
     }
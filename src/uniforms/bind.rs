@@ -5,14 +5,13 @@ Handles binding uniforms to the OpenGL state machine.
 */
 use gl;
 
-use std::collections::HashMap;
-use std::hash::BuildHasherDefault;
-
-use fnv::FnvHasher;
+use smallvec::SmallVec;
 
 use BufferExt;
 use BufferSliceExt;
 use DrawError;
+use GlObject;
+use Handle;
 use ProgramExt;
 use UniformsExt;
 use RawUniformValue;
@@ -23,6 +22,7 @@ use uniforms::UniformValue;
 use uniforms::SamplerBehavior;
 
 use context::CommandContext;
+use debug::ObjectKind;
 use buffer::Inserter;
 
 use utils::bitsfield::Bitsfield;
@@ -36,16 +36,49 @@ impl<U> UniformsExt for U where U: Uniforms {
     fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext, program: &P,
                             fences: &mut Vec<Inserter<'a>>)
                             -> Result<(), DrawError>
-                            where P: ProgramExt
+                            where P: ProgramExt + GlObject<Id = Handle>
     {
+        if program.uses_strict_uniforms() {
+            try!(check_uniforms_names(self, program));
+        }
+
+        // with multi-bind, the textures and buffers are recorded while visiting the uniforms and
+        // bound all at once at the end
+        let multi_bind = is_multi_bind_supported(ctxt);
+        if multi_bind {
+            ctxt.state.pending_multi_bind.active = true;
+        }
+
         let mut texture_bind_points = Bitsfield::new();
+        let mut image_unit_bind_points = Bitsfield::new();
         let mut uniform_buffer_bind_points = Bitsfield::new();
         let mut shared_storage_buffer_bind_points = Bitsfield::new();
 
+        // the binding points assigned by the user must not be chosen for other blocks, but the
+        // ones that are too high to be chosen don't need to be tracked
+        for block in program.get_uniform_blocks().values() {
+            let block_id = block.id as gl::types::GLuint;
+            match program.get_fixed_uniform_block_binding(block_id) {
+                Some(point) if point < Bitsfield::capacity() as gl::types::GLuint => {
+                    uniform_buffer_bind_points.set_used(point as u16);
+                },
+                _ => ()
+            }
+        }
+        for block in program.get_shader_storage_blocks().values() {
+            let block_id = block.id as gl::types::GLuint;
+            match program.get_fixed_shader_storage_block_binding(block_id) {
+                Some(point) if point < Bitsfield::capacity() as gl::types::GLuint => {
+                    shared_storage_buffer_bind_points.set_used(point as u16);
+                },
+                _ => ()
+            }
+        }
+
         // Subroutine uniforms must be bound all at once, so we collect them first and process them at the end.
-        // The vec contains the uniform we want to set and the value we want to set it to.
-        let mut subroutine_bindings: HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, _>
-            = HashMap::with_hasher(Default::default());
+        // Each element contains the stage, the uniform we want to set and the value we want to set it to.
+        let mut subroutine_bindings: SmallVec<[(program::ShaderStage, &program::SubroutineUniform, &str); 8]>
+            = SmallVec::new();
 
         let mut visiting_result = Ok(());
         self.visit_values(|name, value| {
@@ -64,7 +97,7 @@ impl<U> UniformsExt for U where U: Uniforms {
                 }
 
                 match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, name)
+                                   &mut texture_bind_points, &mut image_unit_bind_points, name)
                 {
                     Ok(_) => (),
                     Err(e) => {
@@ -104,14 +137,47 @@ impl<U> UniformsExt for U where U: Uniforms {
                     fences.push(fence);
                 }
             } else if let UniformValue::Subroutine(stage, sr_name) = value {
-                if let Some(subroutine_uniform) = program.get_subroutine_data().subroutine_uniforms.get(&(name.into(), stage)) {
-                    subroutine_bindings.entry(stage).or_insert(Vec::new());
-                    let vec = subroutine_bindings.get_mut(&stage).unwrap();
-                    vec.push((subroutine_uniform, sr_name));
+                if let Some(subroutine_uniform) = find_subroutine_uniform(program, name, stage) {
+                    subroutine_bindings.push((stage, subroutine_uniform, sr_name));
                 }
             }
         });
 
+        if visiting_result.is_err() {
+            if multi_bind {
+                flush_multi_bind(ctxt);
+            }
+            return visiting_result;
+        }
+
+        // uniforms whose location has been resolved in advance
+        let program_id = program.get_id();
+        self.visit_handles(|handle, value| {
+            if visiting_result.is_err() { return; }
+
+            if handle.get_program() != program_id {
+                visiting_result = Err(DrawError::UniformHandleProgramMismatch {
+                    name: handle.get_name().to_owned(),
+                });
+                return;
+            }
+
+            if !value.is_usable_with(&handle.get_type()) {
+                visiting_result = Err(DrawError::UniformTypeMismatch {
+                    name: handle.get_name().to_owned(),
+                    expected: handle.get_type(),
+                });
+                return;
+            }
+
+            if let Err(e) = bind_uniform(&mut ctxt, &value, program, handle.get_location(),
+                                         &mut texture_bind_points, &mut image_unit_bind_points,
+                                         handle.get_name())
+            {
+                visiting_result = Err(e);
+            }
+        });
+
         // Process all subroutine uniforms in one batch.
         if !subroutine_bindings.is_empty() {
             match bind_subroutine_uniforms(&mut ctxt, program, &subroutine_bindings) {
@@ -122,47 +188,144 @@ impl<U> UniformsExt for U where U: Uniforms {
             }
         }
 
+        if multi_bind {
+            flush_multi_bind(ctxt);
+        }
+
         visiting_result
     }
 }
 
+/// Finds a subroutine uniform of the program without building a `String` for the lookup.
+fn find_subroutine_uniform<'p, P>(program: &'p P, name: &str, stage: program::ShaderStage)
+                                  -> Option<&'p program::SubroutineUniform>
+                                  where P: ProgramExt
+{
+    program.get_subroutine_data().subroutine_uniforms.iter()
+           .find(|&(&(ref uniform_name, uniform_stage), _)| {
+               uniform_stage == stage && uniform_name == name
+           })
+           .map(|(_, uniform)| uniform)
+}
+
+/// Returns true if a value with this name corresponds to something in the program.
+fn is_uniform_known<P>(program: &P, name: &str, value: &UniformValue) -> bool
+                       where P: ProgramExt
+{
+    program.get_uniform(name).is_some() ||
+    program.get_uniform_blocks().contains_key(name) ||
+    program.get_shader_storage_blocks().contains_key(name) ||
+    match *value {
+        UniformValue::Subroutine(stage, _) => {
+            find_subroutine_uniform(program, name, stage).is_some()
+        },
+        _ => false,
+    }
+}
+
+/// Returns an error if a value doesn't correspond to anything in the program, or if an active
+/// uniform or block of the program has no value.
+///
+/// The lists of names of the error are only built if the check fails, so that drawing with
+/// strict uniforms doesn't allocate.
+fn check_uniforms_names<U, P>(uniforms: &U, program: &P) -> Result<(), DrawError>
+                              where U: Uniforms, P: ProgramExt
+{
+    let mut has_unknown = false;
+    uniforms.visit_values(|name, value| {
+        has_unknown = has_unknown || !is_uniform_known(program, name, &value);
+    });
+
+    let is_provided = |name: &str| {
+        let mut found = false;
+        uniforms.visit_values(|n, _| found = found || n == name);
+        uniforms.visit_handles(|handle, _| found = found || handle.get_name() == name);
+        found
+    };
+
+    let has_missing = program.get_uniforms().keys()
+                             .chain(program.get_uniform_blocks().keys())
+                             .chain(program.get_shader_storage_blocks().keys())
+                             .any(|name| !is_provided(name));
+
+    if !has_unknown && !has_missing {
+        return Ok(());
+    }
+
+    let mut provided = Vec::new();
+    let mut unknown = Vec::new();
+
+    uniforms.visit_values(|name, value| {
+        provided.push(name.to_owned());
+
+        if !is_uniform_known(program, name, &value) {
+            unknown.push(name.to_owned());
+        }
+    });
+
+    // handles of other programs are reported by `bind_uniforms`
+    uniforms.visit_handles(|handle, _| {
+        provided.push(handle.get_name().to_owned());
+    });
+
+    let mut missing = program.get_uniforms().keys()
+                             .chain(program.get_uniform_blocks().keys())
+                             .chain(program.get_shader_storage_blocks().keys())
+                             .filter(|name| !provided.contains(name))
+                             .cloned()
+                             .collect::<Vec<_>>();
+
+    unknown.sort();
+    missing.sort();
+
+    Err(DrawError::UniformsMismatch {
+        unknown: unknown,
+        missing: missing,
+    })
+}
+
 fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext, program: &P,
-                            subroutine_bindings: &HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, BuildHasherDefault<FnvHasher>>)
+                            subroutine_bindings: &[(program::ShaderStage, &program::SubroutineUniform, &str)])
                             -> Result<(), DrawError>
                             where P: ProgramExt
 {
     let subroutine_data = program.get_subroutine_data();
-    for (stage, bindings) in subroutine_bindings {
+    for (&stage, &location_count) in subroutine_data.location_counts.iter() {
+        let bindings = || subroutine_bindings.iter().filter(move |b| b.0 == stage);
+
+        // Only the stages that have been given a value are bound.
+        let set_cnt = bindings().count();
+        if set_cnt == 0 {
+            continue;
+        }
+
         // Validate that all subroutine uniforms of this stage are set, otherwise OpenGL will throw an error.
-        let set_cnt = bindings.len();
         let expected_cnt = subroutine_data.subroutine_uniforms.iter()
-                                  .filter(|&(&(_, uni_stage), _)| *stage == uni_stage)
+                                  .filter(|&(&(_, uni_stage), _)| stage == uni_stage)
                                   .count();
         if set_cnt != expected_cnt {
             return Err(DrawError::SubroutineUniformMissing {
-                stage: *stage,
+                stage: stage,
                 real_count: set_cnt,
                 expected_count: expected_cnt,
             })
         }
 
         // Build the indices array
-        let mut indices = vec![0 as gl::types::GLuint; *subroutine_data.location_counts.get(stage).unwrap()];
-        for binding in bindings {
-            let uniform = binding.0;
-            let subroutine_str = binding.1;
+        let mut indices: SmallVec<[gl::types::GLuint; 16]> = SmallVec::from_elem(0, location_count);
+        for &(_, uniform, subroutine_str) in bindings() {
             let subroutine = match uniform.compatible_subroutines.iter()
                                    .find(|subroutine| subroutine.name == subroutine_str) {
                 Some(subroutine) => subroutine,
                 None => return Err(DrawError::SubroutineNotFound {
-                                    stage: *stage,
+                                    stage: stage,
                                     name: subroutine_str.into(),
                                 })
             };
 
             indices[uniform.location as usize] = subroutine.index;
         }
-        program.set_subroutine_uniforms_for_stage(ctxt, *stage, &indices);
+        program.set_subroutine_uniforms_for_stage(ctxt, stage, &indices);
     }
     Ok(())
 }
@@ -185,12 +348,18 @@ fn bind_uniform_block<'a, P>(ctxt: &mut context::CommandContext, value: &Uniform
                 }
             }
 
-            let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
-            buffer_bind_points.set_used(bind_point);
+            let block_id = block.id as gl::types::GLuint;
+            let bind_point = match program.get_fixed_uniform_block_binding(block_id) {
+                Some(point) => point as u16,
+                None => {
+                    let point = buffer_bind_points.get_unused().expect("Not enough buffer units");
+                    buffer_bind_points.set_used(point);
+                    point
+                },
+            };
 
             assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
             let fence = buffer.add_fence();
-            let block_id = block.id as gl::types::GLuint;
 
             buffer.prepare_and_bind_for_uniform(ctxt, bind_point as gl::types::GLuint);
             program.set_uniform_block_binding(ctxt, block_id, bind_point as gl::types::GLuint);
@@ -221,12 +390,18 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext, value: &
                 }
             }
 
-            let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
-            buffer_bind_points.set_used(bind_point);
+            let block_id = block.id as gl::types::GLuint;
+            let bind_point = match program.get_fixed_shader_storage_block_binding(block_id) {
+                Some(point) => point as u16,
+                None => {
+                    let point = buffer_bind_points.get_unused().expect("Not enough buffer units");
+                    buffer_bind_points.set_used(point);
+                    point
+                },
+            };
 
             assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
             let fence = buffer.add_fence();
-            let block_id = block.id as gl::types::GLuint;
 
             buffer.prepare_and_bind_for_shared_storage(ctxt, bind_point as gl::types::GLuint);
             program.set_shader_storage_block_binding(ctxt, block_id, bind_point as gl::types::GLuint);
@@ -241,7 +416,8 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext, value: &
 
 fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                    value: &UniformValue, program: &P, location: gl::types::GLint,
-                   texture_bind_points: &mut Bitsfield, name: &str)
+                   texture_bind_points: &mut Bitsfield, image_unit_bind_points: &mut Bitsfield,
+                   name: &str)
                    -> Result<(), DrawError> where P: ProgramExt
 {
     assert!(location >= 0);
@@ -575,8 +751,29 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
         UniformValue::BufferTexture(texture) => {
+            texture.get_buffer().prepare_for_texture_fetch(ctxt);
             bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points)
         },
+        UniformValue::BufferTextureImage(image) => {
+            let unit = match image_unit_bind_points.get_unused() {
+                Some(unit) if (unit as gl::types::GLint) <
+                                  ctxt.capabilities.max_image_units.unwrap_or(0) => unit,
+                _ => return Err(DrawError::TooManyImageUnits),
+            };
+            image_unit_bind_points.set_used(unit);
+
+            image.bind_to_image_unit(ctxt, unit as gl::types::GLuint);
+            program.set_uniform(ctxt, location,
+                                &RawUniformValue::SignedInt(unit as gl::types::GLint));
+            Ok(())
+        },
+        UniformValue::ExternalTexture(texture, sampler) => {
+            bind_texture_uniform(ctxt, texture, sampler, location, program, texture_bind_points)
+        },
+        #[cfg(target_os = "macos")]
+        UniformValue::IOSurfaceTexture(texture, sampler) => {
+            bind_texture_uniform(ctxt, texture, sampler, location, program, texture_bind_points)
+        },
     }
 }
 
@@ -613,7 +810,14 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
                 }
             })
             .unwrap_or_else(|| {
-                texture_bind_points.get_unused().expect("Not enough texture units available")
+                // all the units are occupied, so we replace the least recently used one among
+                // those that are not used by the current draw call
+                ctxt.state.texture_units
+                    .iter().enumerate()
+                    .filter(|&(unit, _)| !texture_bind_points.is_used(unit as u16))
+                    .min_by_key(|&(_, content)| content.last_used)
+                    .map(|(unit, _)| unit as u16)
+                    .expect("Not enough texture units available")
             });
     assert!((texture_unit as gl::types::GLint) <
             ctxt.capabilities.max_combined_texture_image_units);
@@ -630,16 +834,31 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
         }
     }
 
-    // TODO: do better
+    ctxt.state.texture_units[texture_unit as usize].last_used = ctxt.state.next_draw_call_id;
+
     if ctxt.state.texture_units[texture_unit as usize].texture != texture.get_texture_id() ||
        ctxt.state.texture_units[texture_unit as usize].sampler != sampler
     {
+        // the unit is sent later with `glBindTextures` and `glBindSamplers`
+        if ctxt.state.pending_multi_bind.active {
+            let unit = &mut ctxt.state.texture_units[texture_unit as usize];
+            unit.texture = texture.get_texture_id();
+            unit.sampler = sampler;
+            ctxt.state.pending_multi_bind.texture_units.push(texture_unit);
+            ctxt.live_objects.mark_used(ObjectKind::Texture, texture.get_texture_id());
+            return Ok(());
+        }
+
         // TODO: what if it's not supported?
         if ctxt.state.active_texture != texture_unit as gl::types::GLenum {
             unsafe { ctxt.gl.ActiveTexture(texture_unit as gl::types::GLenum + gl::TEXTURE0) };
             ctxt.state.active_texture = texture_unit as gl::types::GLenum;
         }
 
+        if ctxt.state.texture_units[texture_unit as usize].texture != texture.get_texture_id() {
+            ctxt.state.statistics.texture_bind_calls += 1;
+        }
+
         texture.bind_to_current(ctxt);
 
         if ctxt.state.texture_units[texture_unit as usize].sampler != sampler {
@@ -649,8 +868,112 @@ fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
 
             unsafe { ctxt.gl.BindSampler(texture_unit as gl::types::GLenum, sampler); }
             ctxt.state.texture_units[texture_unit as usize].sampler = sampler;
+            ctxt.state.statistics.sampler_bind_calls += 1;
         }
     }
 
     Ok(())
 }
+
+/// Returns true if the resources used by a draw call can be bound with the functions of
+/// `GL_ARB_multi_bind`.
+fn is_multi_bind_supported(ctxt: &context::CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 4) || ctxt.extensions.gl_arb_multi_bind
+}
+
+/// Sends to OpenGL the bindings that have been recorded in `ctxt.state.pending_multi_bind`, with
+/// one call for each range of consecutive units.
+fn flush_multi_bind(ctxt: &mut context::CommandContext) {
+    use std::mem;
+
+    ctxt.state.pending_multi_bind.active = false;
+
+    let mut units = mem::replace(&mut ctxt.state.pending_multi_bind.texture_units,
+                                 Default::default());
+    for (first, count) in consecutive_ranges(&mut units) {
+        let range = first as usize .. first as usize + count;
+        let textures = ctxt.state.texture_units[range.clone()].iter().map(|u| u.texture)
+                                                             .collect::<SmallVec<[_; 16]>>();
+        let samplers = ctxt.state.texture_units[range].iter().map(|u| u.sampler)
+                                                     .collect::<SmallVec<[_; 16]>>();
+
+        unsafe {
+            ctxt.gl.BindTextures(first as gl::types::GLuint, count as gl::types::GLsizei,
+                                 textures.as_ptr());
+            ctxt.gl.BindSamplers(first as gl::types::GLuint, count as gl::types::GLsizei,
+                                 samplers.as_ptr());
+        }
+
+        ctxt.state.statistics.texture_bind_calls += 1;
+        ctxt.state.statistics.sampler_bind_calls += 1;
+    }
+
+    let mut uniform_buffers = mem::replace(&mut ctxt.state.pending_multi_bind.uniform_buffers,
+                                           Default::default());
+    for (first, count) in consecutive_ranges(&mut uniform_buffers) {
+        let range = first as usize .. first as usize + count;
+        let bindings = ctxt.state.indexed_uniform_buffer_bindings[range].iter()
+                                 .map(|b| (b.buffer, b.offset, b.size))
+                                 .collect::<SmallVec<[_; 8]>>();
+        unsafe { bind_buffers_range(ctxt.gl, gl::UNIFORM_BUFFER, first, &bindings); }
+        ctxt.state.statistics.indexed_buffer_bind_calls += 1;
+    }
+
+    let mut storage_buffers = mem::replace(&mut ctxt.state.pending_multi_bind
+                                                          .shader_storage_buffers,
+                                           Default::default());
+    for (first, count) in consecutive_ranges(&mut storage_buffers) {
+        let range = first as usize .. first as usize + count;
+        let bindings = ctxt.state.indexed_shader_storage_buffer_bindings[range].iter()
+                                 .map(|b| (b.buffer, b.offset, b.size))
+                                 .collect::<SmallVec<[_; 8]>>();
+        unsafe { bind_buffers_range(ctxt.gl, gl::SHADER_STORAGE_BUFFER, first, &bindings); }
+        ctxt.state.statistics.indexed_buffer_bind_calls += 1;
+    }
+}
+
+/// Calls `glBindBuffersRange` with a list of `(buffer, offset, size)` tuples.
+unsafe fn bind_buffers_range(gl: &gl::Gl, target: gl::types::GLenum, first: u16,
+                             bindings: &[(gl::types::GLuint, gl::types::GLintptr,
+                                          gl::types::GLsizeiptr)])
+{
+    let buffers = bindings.iter().map(|b| b.0).collect::<SmallVec<[_; 8]>>();
+    let offsets = bindings.iter().map(|b| b.1).collect::<SmallVec<[_; 8]>>();
+    let sizes = bindings.iter().map(|b| b.2).collect::<SmallVec<[_; 8]>>();
+
+    gl.BindBuffersRange(target, first as gl::types::GLuint,
+                        bindings.len() as gl::types::GLsizei, buffers.as_ptr(),
+                        offsets.as_ptr(), sizes.as_ptr());
+}
+
+/// Sorts `units` and returns an iterator over the `(first, count)` ranges of consecutive values.
+fn consecutive_ranges<'a, A>(units: &'a mut SmallVec<A>) -> ConsecutiveRanges<'a>
+                             where A: ::smallvec::Array<Item = u16>
+{
+    units.sort_unstable();
+    units.dedup();
+    ConsecutiveRanges { units: &units[..] }
+}
+
+/// Iterator over the ranges of consecutive values of a sorted list of units.
+struct ConsecutiveRanges<'a> {
+    units: &'a [u16],
+}
+
+impl<'a> Iterator for ConsecutiveRanges<'a> {
+    type Item = (u16, usize);
+
+    fn next(&mut self) -> Option<(u16, usize)> {
+        let first = match self.units.first() {
+            Some(&first) => first,
+            None => return None,
+        };
+
+        let count = self.units.iter().enumerate()
+                              .take_while(|&(index, &unit)| unit as usize == first as usize + index)
+                              .count();
+
+        self.units = &self.units[count ..];
+        Some((first, count))
+    }
+}
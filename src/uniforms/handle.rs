@@ -0,0 +1,216 @@
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use gl;
+use Handle;
+
+use program::BlockLayout;
+use program::Uniform;
+
+use uniforms::{Uniforms, UniformValue, AsUniformValue, UniformBlock, UniformType, EmptyUniforms};
+
+/// Location of a uniform of a program, resolved in advance.
+///
+/// This is the untyped part of a `UniformHandle`.
+#[derive(Debug, Clone)]
+pub struct RawUniformHandle {
+    program: Handle,
+    name: String,
+    location: gl::types::GLint,
+    ty: UniformType,
+}
+
+impl RawUniformHandle {
+    /// Returns the name of the uniform.
+    #[inline]
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the location of the uniform in the program.
+    #[inline]
+    pub fn get_location(&self) -> i32 {
+        self.location
+    }
+
+    /// Returns the type of the uniform.
+    #[inline]
+    pub fn get_type(&self) -> UniformType {
+        self.ty
+    }
+
+    /// Returns the program that this handle belongs to.
+    #[inline]
+    pub fn get_program(&self) -> Handle {
+        self.program
+    }
+}
+
+/// Location of a uniform of a program, resolved once with `Program::uniform_handle`.
+///
+/// Passing a value through a handle instead of through its name avoids looking up the uniform
+/// by its name at each draw call. See `HandledUniforms`.
+#[derive(Debug, Clone)]
+pub struct UniformHandle<T> {
+    raw: RawUniformHandle,
+    marker: PhantomData<T>,
+}
+
+impl<T> UniformHandle<T> where T: AsUniformValue + UniformBlock {
+    /// Builds a handle for the given uniform. Checks that `T` matches the type of the uniform.
+    #[doc(hidden)]
+    pub fn new(program: Handle, name: &str, uniform: &Uniform)
+               -> Result<UniformHandle<T>, UniformHandleError>
+    {
+        // TODO: handle arrays of uniforms
+        let obtained = match T::build_layout(0) {
+            BlockLayout::BasicType { ty, .. } => ty,
+            _ => return Err(UniformHandleError::UnsupportedType),
+        };
+
+        if obtained != uniform.ty || uniform.size.is_some() {
+            return Err(UniformHandleError::TypeMismatch {
+                expected: uniform.ty,
+                obtained: obtained,
+            });
+        }
+
+        Ok(UniformHandle {
+            raw: RawUniformHandle {
+                program: program,
+                name: name.to_owned(),
+                location: uniform.location,
+                ty: uniform.ty,
+            },
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<T> UniformHandle<T> {
+    /// Returns the untyped part of the handle.
+    #[inline]
+    pub fn as_raw(&self) -> &RawUniformHandle {
+        &self.raw
+    }
+}
+
+/// Error that can happen when resolving a uniform handle.
+#[derive(Copy, Clone, Debug)]
+pub enum UniformHandleError {
+    /// The program doesn't have any active uniform with this name.
+    NotFound,
+
+    /// The type of the handle doesn't match the type of the uniform.
+    TypeMismatch {
+        /// Type expected by the shader.
+        expected: UniformType,
+        /// Type of the handle.
+        obtained: UniformType,
+    },
+
+    /// The type of the handle can't be used with uniform handles.
+    UnsupportedType,
+}
+
+impl fmt::Display for UniformHandleError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for UniformHandleError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            UniformHandleError::NotFound =>
+                "The program doesn't have any active uniform with this name",
+            UniformHandleError::TypeMismatch { .. } =>
+                "The type of the handle doesn't match the type of the uniform",
+            UniformHandleError::UnsupportedType =>
+                "The type of the handle can't be used with uniform handles",
+        }
+    }
+
+    #[inline]
+    fn source(&self) -> Option<&(Error + 'static)> {
+        None
+    }
+}
+
+/// Stores uniform values that are associated to pre-resolved handles.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+/// let matrix = program.uniform_handle::<[[f32; 4]; 4]>("matrix").unwrap();
+/// let color = program.uniform_handle::<[f32; 4]>("color").unwrap();
+///
+/// // this is done at each frame
+/// let uniforms = glium::uniforms::HandledUniforms::new(&matrix, [[1.0, 0.0, 0.0, 0.0],
+///                                                               [0.0, 1.0, 0.0, 0.0],
+///                                                               [0.0, 0.0, 1.0, 0.0],
+///                                                               [0.0, 0.0, 0.0, 1.0f32]])
+///                                             .add(&color, [1.0, 0.0, 0.0, 1.0f32]);
+/// ```
+pub struct HandledUniforms<'h, T: 'h, R> where T: AsUniformValue, R: Uniforms {
+    handle: &'h UniformHandle<T>,
+    value: T,
+    rest: R,
+}
+
+impl<'h, T> HandledUniforms<'h, T, EmptyUniforms> where T: AsUniformValue {
+    /// Builds a new storage with a value.
+    #[inline]
+    pub fn new(handle: &'h UniformHandle<T>, value: T) -> HandledUniforms<'h, T, EmptyUniforms> {
+        HandledUniforms {
+            handle: handle,
+            value: value,
+            rest: EmptyUniforms,
+        }
+    }
+}
+
+impl<'h, T, R> HandledUniforms<'h, T, R> where T: AsUniformValue, R: Uniforms {
+    /// Builds a new storage with a value, in addition to other uniforms.
+    ///
+    /// This allows you to mix handles with uniforms that are passed by name.
+    #[inline]
+    pub fn with(rest: R, handle: &'h UniformHandle<T>, value: T) -> HandledUniforms<'h, T, R> {
+        HandledUniforms {
+            handle: handle,
+            value: value,
+            rest: rest,
+        }
+    }
+
+    /// Adds a value to the storage.
+    #[inline]
+    pub fn add<U>(self, handle: &'h UniformHandle<U>, value: U)
+                  -> HandledUniforms<'h, U, HandledUniforms<'h, T, R>>
+                  where U: AsUniformValue
+    {
+        HandledUniforms {
+            handle: handle,
+            value: value,
+            rest: self,
+        }
+    }
+}
+
+impl<'h, T, R> Uniforms for HandledUniforms<'h, T, R> where T: AsUniformValue, R: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, output: F) {
+        self.rest.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_handles<'a, F: FnMut(&RawUniformHandle, UniformValue<'a>)>(&'a self, mut output: F) {
+        output(&self.handle.raw, self.value.as_uniform_value());
+        self.rest.visit_handles(output);
+    }
+}
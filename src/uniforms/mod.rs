@@ -25,6 +25,12 @@ let uniforms = uniform! {
 
 In both situations, each field must implement the `UniformValue` trait.
 
+## Math libraries
+
+The vector, point and matrix types of `cgmath`, `glam`, `mint` and `nalgebra` can be used
+directly as uniform values and as vertex attributes by enabling the feature of the same name.
+Matrices are passed as they are, which means that they must be column-major.
+
 ## Samplers
 
 In order to customize the way a texture is being sampled, you must use a `Sampler`.
@@ -141,6 +147,7 @@ let program = glium::Program::from_source(&display,
 ```
 */
 pub use self::buffer::UniformBuffer;
+pub use self::handle::{UniformHandle, RawUniformHandle, UniformHandleError, HandledUniforms};
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter};
 pub use self::sampler::{Sampler, SamplerBehavior};
 pub use self::uniforms::{EmptyUniforms, UniformsStorage};
@@ -156,6 +163,7 @@ use program::BlockLayout;
 
 mod bind;
 mod buffer;
+mod handle;
 mod sampler;
 mod uniforms;
 mod value;
@@ -166,6 +174,14 @@ mod value;
 pub trait Uniforms {
     /// Calls the parameter once with the name and value of each uniform.
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, F);
+
+    /// Calls the parameter once with the handle and value of each uniform whose location has
+    /// been resolved in advance with `Program::uniform_handle`.
+    ///
+    /// The default implementation doesn't do anything.
+    #[inline]
+    fn visit_handles<'a, F: FnMut(&RawUniformHandle, UniformValue<'a>)>(&'a self, _: F) {
+    }
 }
 
 /// Error about a block layout mismatch.
@@ -229,7 +245,7 @@ impl Error for LayoutMismatchError {
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::LayoutMismatchError::*;
         match *self {
             MemberMismatch{ ref err, .. } => Some(err.as_ref()),
@@ -5,6 +5,7 @@ use gl;
 ///
 /// This is how GL must handle samples that are outside the texture.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SamplerWrapFunction {
     /// Samples at coord `x + 1` map to coord `x`.
     Repeat,
@@ -33,6 +34,7 @@ impl ToGlEnum for SamplerWrapFunction {
 
 /// The function that the GPU will use when loading the value of a texel.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum MagnifySamplerFilter {
     /// The nearest texel will be loaded.
     Nearest,
@@ -53,6 +55,7 @@ impl ToGlEnum for MagnifySamplerFilter {
 
 /// The function that the GPU will use when loading the value of a texel.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum MinifySamplerFilter {
     /// The nearest texel will be loaded.
     ///
@@ -119,7 +122,10 @@ impl<'t, T: 't> Sampler<'t, T> {
         self
     }
 
-    /// Changes the magnifying filter of the sampler.
+    /// Changes the maximum anisotropy level of the sampler.
+    ///
+    /// The level is clamped to the value returned by `Context::get_max_anisotropy_support`. It
+    /// is ignored if anisotropic filtering is not supported.
     pub fn anisotropy(mut self, level: u16) -> Sampler<'t, T> {
         self.1.max_anisotropy = level;
         self
@@ -138,6 +144,7 @@ impl<'t, T: 't> Clone for Sampler<'t, T> {
 // TODO: GL_TEXTURE_BORDER_COLOR, GL_TEXTURE_MIN_LOD, GL_TEXTURE_MAX_LOD, GL_TEXTURE_LOD_BIAS,
 //       GL_TEXTURE_COMPARE_MODE, GL_TEXTURE_COMPARE_FUNC
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct SamplerBehavior {
     /// Functions to use for the X, Y, and Z coordinates.
     pub wrap_function: (SamplerWrapFunction, SamplerWrapFunction, SamplerWrapFunction),
@@ -1,4 +1,4 @@
-use uniforms::{Uniforms, UniformValue, AsUniformValue};
+use uniforms::{Uniforms, UniformValue, AsUniformValue, RawUniformHandle};
 
 /// Object that can be used when you don't have any uniforms.
 #[derive(Debug, Copy, Clone)]
@@ -52,4 +52,9 @@ impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValue, R
         output(self.name, self.value.as_uniform_value());
         self.rest.visit_values(output);
     }
+
+    #[inline]
+    fn visit_handles<'a, F: FnMut(&RawUniformHandle, UniformValue<'a>)>(&'a self, output: F) {
+        self.rest.visit_handles(output);
+    }
 }
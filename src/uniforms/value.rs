@@ -10,6 +10,15 @@ use uniforms::SamplerBehavior;
 
 use buffer::BufferAnySlice;
 
+#[cfg(feature = "cgmath")]
+use cgmath;
+#[cfg(feature = "glam")]
+use glam;
+#[cfg(feature = "mint")]
+use mint;
+#[cfg(feature = "nalgebra")]
+use nalgebra;
+
 /// Type of a uniform in a program.
 #[allow(missing_docs)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -100,6 +109,7 @@ pub enum UniformType {
     Sampler1dArrayShadow,
     Sampler2dArrayShadow,
     SamplerCubeArrayShadow,
+    SamplerExternalOes,
     Image1d,
     IImage1d,
     UImage1d,
@@ -240,6 +250,10 @@ pub enum UniformValue<'a> {
     UnsignedCubemapArray(&'a texture::UnsignedCubemapArray, Option<SamplerBehavior>),
     DepthCubemapArray(&'a texture::DepthCubemapArray, Option<SamplerBehavior>),
     BufferTexture(texture::buffer_texture::BufferTextureRef<'a>),
+    BufferTextureImage(texture::buffer_texture::BufferTextureImageUnit<'a>),
+    ExternalTexture(&'a texture::external::ExternalTexture, Option<SamplerBehavior>),
+    #[cfg(target_os = "macos")]
+    IOSurfaceTexture(&'a texture::iosurface::IOSurfaceTexture, Option<SamplerBehavior>),
 }
 
 impl<'a> Clone for UniformValue<'a> {
@@ -343,11 +357,26 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::BufferTexture(tex), UniformType::USamplerBuffer) => {
                 tex.get_texture_type() == texture::buffer_texture::BufferTextureType::Unsigned
             },
+            (&UniformValue::BufferTextureImage(img), UniformType::ImageBuffer) => {
+                img.get_texture().get_texture_type() ==
+                                                texture::buffer_texture::BufferTextureType::Float
+            },
+            (&UniformValue::BufferTextureImage(img), UniformType::IImageBuffer) => {
+                img.get_texture().get_texture_type() ==
+                                                texture::buffer_texture::BufferTextureType::Integral
+            },
+            (&UniformValue::BufferTextureImage(img), UniformType::UImageBuffer) => {
+                img.get_texture().get_texture_type() ==
+                                                texture::buffer_texture::BufferTextureType::Unsigned
+            },
             (&UniformValue::Texture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
             (&UniformValue::SrgbTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
             (&UniformValue::IntegralTexture2dMultisample(..), UniformType::ISampler2dMultisample) => true,
             (&UniformValue::UnsignedTexture2dMultisample(..), UniformType::USampler2dMultisample) => true,
             (&UniformValue::DepthTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
+            (&UniformValue::ExternalTexture(..), UniformType::SamplerExternalOes) => true,
+            #[cfg(target_os = "macos")]
+            (&UniformValue::IOSurfaceTexture(..), UniformType::Sampler2dRect) => true,
             _ => false,
         }
     }
@@ -758,7 +787,6 @@ impl AsUniformValue for [f32; 4] {
 
 impl_uniform_block_basic!([f32; 4], UniformType::FloatVec4);
 
-//TODO bool, i32, u32 and f64 should also be implemented as cgmath and nalgebra variants (i.e. nalgebra::Vec3<f64>).
 // Start of double type variants
 impl AsUniformValue for f64 {
     #[inline]
@@ -976,6 +1004,204 @@ impl AsUniformValue for (u64, u64, u64, u64) {
 
 impl_uniform_block_basic!((u64, u64, u64, u64), UniformType::UnsignedInt64Vec4);
 
+// Math libraries
+#[cfg(any(feature = "cgmath", feature = "mint", feature = "nalgebra", feature = "glam"))]
+macro_rules! impl_uniform_value_conv {
+    ($ty:ty, $variant:ident, |$value:ident| $conv:expr) => (
+        impl AsUniformValue for $ty {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                let $value = self;
+                UniformValue::$variant($conv)
+            }
+        }
+    );
+
+    ($ty:ty, $variant:ident) => (
+        impl_uniform_value_conv!($ty, $variant, |v| (*v).into());
+    );
+}
+
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector2<f32>, Vec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector3<f32>, Vec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector4<f32>, Vec4);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point2<f32>, Vec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point3<f32>, Vec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector2<f64>, DoubleVec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector3<f64>, DoubleVec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector4<f64>, DoubleVec4);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point2<f64>, DoubleVec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point3<f64>, DoubleVec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector2<i32>, IntVec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector3<i32>, IntVec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector4<i32>, IntVec4);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point2<i32>, IntVec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point3<i32>, IntVec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector2<u32>, UnsignedIntVec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector3<u32>, UnsignedIntVec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Vector4<u32>, UnsignedIntVec4);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point2<u32>, UnsignedIntVec2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Point3<u32>, UnsignedIntVec3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Matrix2<f32>, Mat2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Matrix3<f32>, Mat3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Matrix4<f32>, Mat4);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Matrix2<f64>, DoubleMat2);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Matrix3<f64>, DoubleMat3);
+#[cfg(feature = "cgmath")]
+impl_uniform_value_conv!(cgmath::Matrix4<f64>, DoubleMat4);
+
+#[cfg(feature = "glam")]
+impl_uniform_value_conv!(glam::Vec2, Vec2);
+#[cfg(feature = "glam")]
+impl_uniform_value_conv!(glam::Vec3, Vec3);
+#[cfg(feature = "glam")]
+impl_uniform_value_conv!(glam::Vec4, Vec4);
+#[cfg(feature = "glam")]
+impl_uniform_value_conv!(glam::Mat2, Mat2, |v| v.to_cols_array_2d());
+#[cfg(feature = "glam")]
+impl_uniform_value_conv!(glam::Mat3, Mat3, |v| v.to_cols_array_2d());
+#[cfg(feature = "glam")]
+impl_uniform_value_conv!(glam::Mat4, Mat4, |v| v.to_cols_array_2d());
+
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector2<f32>, Vec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector3<f32>, Vec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector4<f32>, Vec4);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point2<f32>, Vec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point3<f32>, Vec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector2<f64>, DoubleVec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector3<f64>, DoubleVec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector4<f64>, DoubleVec4);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point2<f64>, DoubleVec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point3<f64>, DoubleVec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector2<i32>, IntVec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector3<i32>, IntVec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector4<i32>, IntVec4);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point2<i32>, IntVec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point3<i32>, IntVec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector2<u32>, UnsignedIntVec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector3<u32>, UnsignedIntVec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Vector4<u32>, UnsignedIntVec4);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point2<u32>, UnsignedIntVec2);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::Point3<u32>, UnsignedIntVec3);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::ColumnMatrix2<f32>, Mat2, |v| [v.x.into(), v.y.into()]);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::ColumnMatrix3<f32>, Mat3, |v| [v.x.into(), v.y.into(), v.z.into()]);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::ColumnMatrix4<f32>, Mat4, |v| [v.x.into(), v.y.into(), v.z.into(), v.w.into()]);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::ColumnMatrix2<f64>, DoubleMat2, |v| [v.x.into(), v.y.into()]);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::ColumnMatrix3<f64>, DoubleMat3, |v| [v.x.into(), v.y.into(), v.z.into()]);
+#[cfg(feature = "mint")]
+impl_uniform_value_conv!(mint::ColumnMatrix4<f64>, DoubleMat4, |v| [v.x.into(), v.y.into(), v.z.into(), v.w.into()]);
+
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector2<f32>, Vec2);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector3<f32>, Vec3);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector4<f32>, Vec4);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point2<f32>, Vec2, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point3<f32>, Vec3, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point4<f32>, Vec4, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector2<f64>, DoubleVec2);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector3<f64>, DoubleVec3);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector4<f64>, DoubleVec4);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point2<f64>, DoubleVec2, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point3<f64>, DoubleVec3, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point4<f64>, DoubleVec4, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector2<i32>, IntVec2);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector3<i32>, IntVec3);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector4<i32>, IntVec4);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point2<i32>, IntVec2, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point3<i32>, IntVec3, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point4<i32>, IntVec4, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector2<u32>, UnsignedIntVec2);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector3<u32>, UnsignedIntVec3);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Vector4<u32>, UnsignedIntVec4);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point2<u32>, UnsignedIntVec2, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point3<u32>, UnsignedIntVec3, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Point4<u32>, UnsignedIntVec4, |v| v.coords.into());
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Matrix2<f32>, Mat2);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Matrix3<f32>, Mat3);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Matrix4<f32>, Mat4);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Matrix2<f64>, DoubleMat2);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Matrix3<f64>, DoubleMat3);
+#[cfg(feature = "nalgebra")]
+impl_uniform_value_conv!(nalgebra::Matrix4<f64>, DoubleMat4);
+
 // Subroutines
 impl<'a> AsUniformValue for (&'a str, ShaderStage) {
     #[inline]
@@ -13,6 +13,12 @@ impl Bitsfield {
         }
     }
 
+    /// Returns the number of bits that the bitsfield can hold.
+    #[inline]
+    pub fn capacity() -> u16 {
+        NUM_DWORDS as u16 * 32
+    }
+
     #[inline]
     pub fn set_used(&mut self, mut bit: u16) {
         let mut offset = 0;
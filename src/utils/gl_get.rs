@@ -0,0 +1,86 @@
+//! Helpers that retrieve the values that OpenGL functions write through a pointer.
+use std::mem::MaybeUninit;
+
+use gl;
+
+/// A type that OpenGL functions write through a pointer, and whose value with all bits set to
+/// zero is valid.
+///
+/// This trait is unsafe to implement, as `get_output` relies on the latter property.
+pub unsafe trait GlOutput: Copy {}
+
+macro_rules! impl_gl_output {
+    ($($ty:ty),+) => (
+        $(
+            unsafe impl GlOutput for $ty {}
+        )+
+    );
+}
+
+impl_gl_output!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize, f32, f64);
+
+unsafe impl<T> GlOutput for *const T {}
+unsafe impl<T> GlOutput for *mut T {}
+
+unsafe impl<T> GlOutput for [T; 1] where T: GlOutput {}
+unsafe impl<T> GlOutput for [T; 2] where T: GlOutput {}
+unsafe impl<T> GlOutput for [T; 3] where T: GlOutput {}
+unsafe impl<T> GlOutput for [T; 4] where T: GlOutput {}
+unsafe impl<T> GlOutput for [T; 16] where T: GlOutput {}
+
+/// Calls `f` with a pointer to a zeroed value, and returns the value that `f` has written.
+///
+/// This is meant to be used with the OpenGL functions that return values through a pointer,
+/// like `glGetIntegerv` or `glGenBuffers`. Since the value is zeroed beforehand, the result is
+/// still defined if nothing is written, which some drivers do when the call raises an error.
+/// In that situation zero is returned.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let gl_get_integerv: fn(u32, *mut i32) = unsafe { std::mem::transmute(0usize) };
+/// let max_texture_size: i32 = glium::backend::get_output(|value| {
+///     gl_get_integerv(0x0D33, value)        // GL_MAX_TEXTURE_SIZE
+/// });
+/// ```
+#[inline]
+pub fn get_output<T, F>(f: F) -> T where T: GlOutput, F: FnOnce(*mut T) {
+    let mut value = MaybeUninit::<T>::zeroed();
+    f(value.as_mut_ptr());
+    unsafe { value.assume_init() }
+}
+
+/// Returns the value of an integer parameter with `glGetIntegerv`.
+///
+/// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
+#[inline]
+pub unsafe fn get_integer(gl: &gl::Gl, pname: gl::types::GLenum) -> gl::types::GLint {
+    get_output(|value| gl.GetIntegerv(pname, value))
+}
+
+/// Returns the value of an indexed integer parameter with `glGetIntegeri_v`.
+///
+/// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
+#[inline]
+pub unsafe fn get_integer_indexed(gl: &gl::Gl, pname: gl::types::GLenum,
+                                  index: gl::types::GLuint) -> gl::types::GLint
+{
+    get_output(|value| gl.GetIntegeri_v(pname, index, value))
+}
+
+/// Returns the value of a floating-point parameter with `glGetFloatv`.
+///
+/// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
+#[inline]
+pub unsafe fn get_float(gl: &gl::Gl, pname: gl::types::GLenum) -> gl::types::GLfloat {
+    get_output(|value| gl.GetFloatv(pname, value))
+}
+
+/// Returns the value of a boolean parameter with `glGetBooleanv`.
+///
+/// *Safety*: the OpenGL context corresponding to `gl` must be current in the thread.
+#[inline]
+pub unsafe fn get_boolean(gl: &gl::Gl, pname: gl::types::GLenum) -> bool {
+    let value: gl::types::GLboolean = get_output(|value| gl.GetBooleanv(pname, value));
+    value != 0
+}
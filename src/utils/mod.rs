@@ -1,2 +1,3 @@
 pub mod bitsfield;
+pub mod gl_get;
 pub mod range;
@@ -2,6 +2,8 @@ use std::cmp::Ordering;
 use std::ffi::CStr;
 use gl;
 
+use ContextCreationError;
+
 /// Describes a version.
 ///
 /// A version can only be compared to another version if they belong to the same API.
@@ -39,16 +41,30 @@ impl PartialOrd for Version {
 ///
 /// You must ensure that the functions belong to the current context, otherwise you will get
 /// an undefined behavior.
-pub unsafe fn get_gl_version(gl: &gl::Gl) -> Version {
+///
+/// Returns `ContextCreationError::DriverBug` if the version string can't be parsed.
+pub unsafe fn get_gl_version(gl: &gl::Gl) -> Result<Version, ContextCreationError> {
     let version = gl.GetString(gl::VERSION);
-    let version = String::from_utf8(CStr::from_ptr(version as *const _).to_bytes().to_vec()).unwrap();
+    if version.is_null() {
+        return Err(ContextCreationError::DriverBug("glGetString(GL_VERSION) returned null"
+                                                       .to_owned()));
+    }
+
+    let version = try!(String::from_utf8(CStr::from_ptr(version as *const _).to_bytes().to_vec())
+                           .map_err(|_| ContextCreationError::DriverBug(
+                               "glGetString(GL_VERSION) returned a non-UTF8 string".to_owned())));
 
     // for the moment we mock WebGL as OpenGL ES 2.0
     // TODO: handle the differences between WebGL and OpenGL ES
     if version.starts_with("WebGL ") {
-        return Version(Api::GlEs, 2, 0);
+        return Ok(Version(Api::GlEs, 2, 0));
     }
 
+    let invalid = || {
+        ContextCreationError::DriverBug(format!("glGetString(GL_VERSION) returned an invalid \
+                                                 version: {:?}", version))
+    };
+
     let (version, api) = if version.starts_with("OpenGL ES ") {
         (&version[10..], Api::GlEs)
     } else if version.starts_with("OpenGL ES-") {
@@ -57,18 +73,13 @@ pub unsafe fn get_gl_version(gl: &gl::Gl) -> Version {
         (&version[..], Api::Gl)
     };
 
-    let version = version.split(' ').next().expect("glGetString(GL_VERSION) returned an empty \
-                                                    string");
+    let number = try!(version.split(' ').next().ok_or_else(&invalid));
 
-    let mut iter = version.split(move |c: char| c == '.');
-    let major = iter.next().unwrap();
-    let minor = iter.next().expect("glGetString(GL_VERSION) did not return a correct version");
+    let mut iter = number.split(move |c: char| c == '.');
+    let major = try!(iter.next().and_then(|v| v.parse().ok()).ok_or_else(&invalid));
+    let minor = try!(iter.next().and_then(|v| v.parse().ok()).ok_or_else(&invalid));
 
-    Version(
-        api,
-        major.parse().ok().expect("failed to parse GL major version"),
-        minor.parse().ok().expect("failed to parse GL minor version"),
-    )
+    Ok(Version(api, major, minor))
 }
 
 /// Given an API version, this function returns the GLSL version that the implementation is
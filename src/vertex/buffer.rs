@@ -48,7 +48,7 @@ impl Error for CreationError {
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(Error + 'static)> {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref error) => Some(error),
@@ -90,9 +90,7 @@ impl<'b, T: 'b> VertexBufferSlice<'b, T> where T: Copy + Content {
     #[inline]
     pub fn per_instance(&'b self) -> Result<PerInstance, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.get_context().get_extensions().gl_arb_instanced_arrays
-        {
+        if !is_instancing_supported(self.get_context()) {
             return Err(InstancingNotSupported);
         }
 
@@ -324,9 +322,7 @@ impl<T> VertexBuffer<T> where T: Copy {
     #[inline]
     pub fn per_instance(&self) -> Result<PerInstance, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.buffer.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.buffer.get_context().get_extensions().gl_arb_instanced_arrays
-        {
+        if !is_instancing_supported(self.buffer.get_context()) {
             return Err(InstancingNotSupported);
         }
 
@@ -476,9 +472,7 @@ impl VertexBufferAny {
     #[inline]
     pub fn per_instance(&self) -> Result<PerInstance, InstancingNotSupported> {
         // TODO: don't check this here
-        if !(self.buffer.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
-            !self.buffer.get_context().get_extensions().gl_arb_instanced_arrays
-        {
+        if !is_instancing_supported(self.buffer.get_context()) {
             return Err(InstancingNotSupported);
         }
 
@@ -524,6 +518,16 @@ impl<'a> Into<VerticesSource<'a>> for &'a VertexBufferAny {
     }
 }
 
+/// Returns true if the backend supports per-instance vertex attributes.
+#[inline]
+pub fn is_instancing_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::Gl, 3, 3) ||
+        ctxt.get_version() >= &Version(Api::GlEs, 3, 0) ||
+        ctxt.get_extensions().gl_arb_instanced_arrays ||
+        ctxt.get_extensions().gl_ext_instanced_arrays ||
+        ctxt.get_extensions().gl_angle_instanced_arrays
+}
+
 /// Instancing is not supported by the backend.
 #[derive(Debug, Copy, Clone)]
 pub struct InstancingNotSupported;
@@ -8,11 +8,15 @@ use CapabilitiesSource;
 
 #[cfg(feature = "cgmath")]
 use cgmath;
+#[cfg(feature = "glam")]
+use glam;
+#[cfg(feature = "mint")]
+use mint;
 #[cfg(feature = "nalgebra")]
 use nalgebra;
 
 #[allow(missing_docs)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum AttributeType {
     I8,
     I8I8,
@@ -1395,14 +1399,14 @@ unsafe impl Attribute for cgmath::Matrix4<f64> {
 
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i8> {
+unsafe impl Attribute for nalgebra::Point1<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8
     }
 }
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i8> {
+unsafe impl Attribute for nalgebra::Point2<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8
@@ -1410,7 +1414,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i8> {
+unsafe impl Attribute for nalgebra::Point3<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8
@@ -1418,7 +1422,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i8> {
+unsafe impl Attribute for nalgebra::Point4<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8I8
@@ -1426,7 +1430,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i8> {
+unsafe impl Attribute for nalgebra::Vector1<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8
@@ -1434,7 +1438,7 @@ unsafe impl Attribute for nalgebra::Vec1<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i8> {
+unsafe impl Attribute for nalgebra::Vector2<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8
@@ -1442,7 +1446,7 @@ unsafe impl Attribute for nalgebra::Vec2<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i8> {
+unsafe impl Attribute for nalgebra::Vector3<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8
@@ -1450,7 +1454,7 @@ unsafe impl Attribute for nalgebra::Vec3<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i8> {
+unsafe impl Attribute for nalgebra::Vector4<i8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I8I8I8I8
@@ -1458,7 +1462,7 @@ unsafe impl Attribute for nalgebra::Vec4<i8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u8> {
+unsafe impl Attribute for nalgebra::Point1<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8
@@ -1466,7 +1470,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u8> {
+unsafe impl Attribute for nalgebra::Point2<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8
@@ -1474,7 +1478,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u8> {
+unsafe impl Attribute for nalgebra::Point3<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8
@@ -1482,7 +1486,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u8> {
+unsafe impl Attribute for nalgebra::Point4<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8U8
@@ -1490,7 +1494,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u8> {
+unsafe impl Attribute for nalgebra::Vector1<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8
@@ -1498,7 +1502,7 @@ unsafe impl Attribute for nalgebra::Vec1<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u8> {
+unsafe impl Attribute for nalgebra::Vector2<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8
@@ -1506,7 +1510,7 @@ unsafe impl Attribute for nalgebra::Vec2<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u8> {
+unsafe impl Attribute for nalgebra::Vector3<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8
@@ -1514,7 +1518,7 @@ unsafe impl Attribute for nalgebra::Vec3<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u8> {
+unsafe impl Attribute for nalgebra::Vector4<u8> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U8U8U8U8
@@ -1522,7 +1526,7 @@ unsafe impl Attribute for nalgebra::Vec4<u8> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i16> {
+unsafe impl Attribute for nalgebra::Point1<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16
@@ -1530,7 +1534,7 @@ unsafe impl Attribute for nalgebra::Pnt1<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i16> {
+unsafe impl Attribute for nalgebra::Point2<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16
@@ -1538,7 +1542,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i16> {
+unsafe impl Attribute for nalgebra::Point3<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16
@@ -1546,7 +1550,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i16> {
+unsafe impl Attribute for nalgebra::Point4<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16I16
@@ -1554,7 +1558,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i16> {
+unsafe impl Attribute for nalgebra::Vector1<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16
@@ -1562,7 +1566,7 @@ unsafe impl Attribute for nalgebra::Vec1<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i16> {
+unsafe impl Attribute for nalgebra::Vector2<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16
@@ -1570,7 +1574,7 @@ unsafe impl Attribute for nalgebra::Vec2<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i16> {
+unsafe impl Attribute for nalgebra::Vector3<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16
@@ -1578,7 +1582,7 @@ unsafe impl Attribute for nalgebra::Vec3<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i16> {
+unsafe impl Attribute for nalgebra::Vector4<i16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I16I16I16I16
@@ -1586,7 +1590,7 @@ unsafe impl Attribute for nalgebra::Vec4<i16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u16> {
+unsafe impl Attribute for nalgebra::Point1<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16
@@ -1594,7 +1598,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u16> {
+unsafe impl Attribute for nalgebra::Point2<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16
@@ -1602,7 +1606,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u16> {
+unsafe impl Attribute for nalgebra::Point3<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16
@@ -1610,7 +1614,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u16> {
+unsafe impl Attribute for nalgebra::Point4<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16U16
@@ -1618,7 +1622,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u16> {
+unsafe impl Attribute for nalgebra::Vector1<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16
@@ -1626,7 +1630,7 @@ unsafe impl Attribute for nalgebra::Vec1<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u16> {
+unsafe impl Attribute for nalgebra::Vector2<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16
@@ -1634,7 +1638,7 @@ unsafe impl Attribute for nalgebra::Vec2<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u16> {
+unsafe impl Attribute for nalgebra::Vector3<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16
@@ -1642,7 +1646,7 @@ unsafe impl Attribute for nalgebra::Vec3<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u16> {
+unsafe impl Attribute for nalgebra::Vector4<u16> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U16U16U16U16
@@ -1650,7 +1654,7 @@ unsafe impl Attribute for nalgebra::Vec4<u16> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i32> {
+unsafe impl Attribute for nalgebra::Point1<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32
@@ -1658,7 +1662,7 @@ unsafe impl Attribute for nalgebra::Pnt1<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i32> {
+unsafe impl Attribute for nalgebra::Point2<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32
@@ -1666,7 +1670,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i32> {
+unsafe impl Attribute for nalgebra::Point3<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32
@@ -1674,7 +1678,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i32> {
+unsafe impl Attribute for nalgebra::Point4<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32I32
@@ -1682,7 +1686,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i32> {
+unsafe impl Attribute for nalgebra::Vector1<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32
@@ -1690,7 +1694,7 @@ unsafe impl Attribute for nalgebra::Vec1<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i32> {
+unsafe impl Attribute for nalgebra::Vector2<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32
@@ -1698,7 +1702,7 @@ unsafe impl Attribute for nalgebra::Vec2<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i32> {
+unsafe impl Attribute for nalgebra::Vector3<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32
@@ -1706,7 +1710,7 @@ unsafe impl Attribute for nalgebra::Vec3<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i32> {
+unsafe impl Attribute for nalgebra::Vector4<i32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I32I32I32I32
@@ -1714,7 +1718,7 @@ unsafe impl Attribute for nalgebra::Vec4<i32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u32> {
+unsafe impl Attribute for nalgebra::Point1<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32
@@ -1722,7 +1726,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u32> {
+unsafe impl Attribute for nalgebra::Point2<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32
@@ -1730,7 +1734,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u32> {
+unsafe impl Attribute for nalgebra::Point3<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32
@@ -1738,7 +1742,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u32> {
+unsafe impl Attribute for nalgebra::Point4<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32U32
@@ -1746,7 +1750,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u32> {
+unsafe impl Attribute for nalgebra::Vector1<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32
@@ -1754,7 +1758,7 @@ unsafe impl Attribute for nalgebra::Vec1<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u32> {
+unsafe impl Attribute for nalgebra::Vector2<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32
@@ -1762,7 +1766,7 @@ unsafe impl Attribute for nalgebra::Vec2<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u32> {
+unsafe impl Attribute for nalgebra::Vector3<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32
@@ -1770,7 +1774,7 @@ unsafe impl Attribute for nalgebra::Vec3<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u32> {
+unsafe impl Attribute for nalgebra::Vector4<u32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U32U32U32U32
@@ -1778,7 +1782,7 @@ unsafe impl Attribute for nalgebra::Vec4<u32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<i64> {
+unsafe impl Attribute for nalgebra::Point1<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64
@@ -1786,7 +1790,7 @@ unsafe impl Attribute for nalgebra::Pnt1<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<i64> {
+unsafe impl Attribute for nalgebra::Point2<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64
@@ -1794,7 +1798,7 @@ unsafe impl Attribute for nalgebra::Pnt2<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<i64> {
+unsafe impl Attribute for nalgebra::Point3<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64
@@ -1802,7 +1806,7 @@ unsafe impl Attribute for nalgebra::Pnt3<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<i64> {
+unsafe impl Attribute for nalgebra::Point4<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64I64
@@ -1810,7 +1814,7 @@ unsafe impl Attribute for nalgebra::Pnt4<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<i64> {
+unsafe impl Attribute for nalgebra::Vector1<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64
@@ -1818,7 +1822,7 @@ unsafe impl Attribute for nalgebra::Vec1<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<i64> {
+unsafe impl Attribute for nalgebra::Vector2<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64
@@ -1826,7 +1830,7 @@ unsafe impl Attribute for nalgebra::Vec2<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<i64> {
+unsafe impl Attribute for nalgebra::Vector3<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64
@@ -1834,7 +1838,7 @@ unsafe impl Attribute for nalgebra::Vec3<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<i64> {
+unsafe impl Attribute for nalgebra::Vector4<i64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::I64I64I64I64
@@ -1842,7 +1846,7 @@ unsafe impl Attribute for nalgebra::Vec4<i64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<u64> {
+unsafe impl Attribute for nalgebra::Point1<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64
@@ -1850,7 +1854,7 @@ unsafe impl Attribute for nalgebra::Pnt1<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<u64> {
+unsafe impl Attribute for nalgebra::Point2<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64
@@ -1858,7 +1862,7 @@ unsafe impl Attribute for nalgebra::Pnt2<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<u64> {
+unsafe impl Attribute for nalgebra::Point3<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64
@@ -1866,7 +1870,7 @@ unsafe impl Attribute for nalgebra::Pnt3<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<u64> {
+unsafe impl Attribute for nalgebra::Point4<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64U64
@@ -1874,7 +1878,7 @@ unsafe impl Attribute for nalgebra::Pnt4<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<u64> {
+unsafe impl Attribute for nalgebra::Vector1<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64
@@ -1882,7 +1886,7 @@ unsafe impl Attribute for nalgebra::Vec1<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<u64> {
+unsafe impl Attribute for nalgebra::Vector2<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64
@@ -1890,7 +1894,7 @@ unsafe impl Attribute for nalgebra::Vec2<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<u64> {
+unsafe impl Attribute for nalgebra::Vector3<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64
@@ -1898,7 +1902,7 @@ unsafe impl Attribute for nalgebra::Vec3<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<u64> {
+unsafe impl Attribute for nalgebra::Vector4<u64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::U64U64U64U64
@@ -1906,7 +1910,7 @@ unsafe impl Attribute for nalgebra::Vec4<u64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<f32> {
+unsafe impl Attribute for nalgebra::Point1<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32
@@ -1914,7 +1918,7 @@ unsafe impl Attribute for nalgebra::Pnt1<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<f32> {
+unsafe impl Attribute for nalgebra::Point2<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32
@@ -1922,7 +1926,7 @@ unsafe impl Attribute for nalgebra::Pnt2<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<f32> {
+unsafe impl Attribute for nalgebra::Point3<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32
@@ -1930,7 +1934,7 @@ unsafe impl Attribute for nalgebra::Pnt3<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<f32> {
+unsafe impl Attribute for nalgebra::Point4<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32F32
@@ -1938,7 +1942,7 @@ unsafe impl Attribute for nalgebra::Pnt4<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<f32> {
+unsafe impl Attribute for nalgebra::Vector1<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32
@@ -1946,7 +1950,7 @@ unsafe impl Attribute for nalgebra::Vec1<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<f32> {
+unsafe impl Attribute for nalgebra::Vector2<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32
@@ -1954,7 +1958,7 @@ unsafe impl Attribute for nalgebra::Vec2<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<f32> {
+unsafe impl Attribute for nalgebra::Vector3<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32
@@ -1962,7 +1966,7 @@ unsafe impl Attribute for nalgebra::Vec3<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<f32> {
+unsafe impl Attribute for nalgebra::Vector4<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32F32F32F32
@@ -1970,15 +1974,7 @@ unsafe impl Attribute for nalgebra::Vec4<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat1<f32> {
-    #[inline]
-    fn get_type() -> AttributeType {
-        AttributeType::F32
-    }
-}
-
-#[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat2<f32> {
+unsafe impl Attribute for nalgebra::Matrix2<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32x2x2
@@ -1986,7 +1982,7 @@ unsafe impl Attribute for nalgebra::Mat2<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat3<f32> {
+unsafe impl Attribute for nalgebra::Matrix3<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32x3x3
@@ -1994,7 +1990,7 @@ unsafe impl Attribute for nalgebra::Mat3<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat4<f32> {
+unsafe impl Attribute for nalgebra::Matrix4<f32> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F32x4x4
@@ -2002,7 +1998,7 @@ unsafe impl Attribute for nalgebra::Mat4<f32> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt1<f64> {
+unsafe impl Attribute for nalgebra::Point1<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64
@@ -2010,7 +2006,7 @@ unsafe impl Attribute for nalgebra::Pnt1<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt2<f64> {
+unsafe impl Attribute for nalgebra::Point2<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64
@@ -2018,7 +2014,7 @@ unsafe impl Attribute for nalgebra::Pnt2<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt3<f64> {
+unsafe impl Attribute for nalgebra::Point3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64
@@ -2026,7 +2022,7 @@ unsafe impl Attribute for nalgebra::Pnt3<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Pnt4<f64> {
+unsafe impl Attribute for nalgebra::Point4<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64F64
@@ -2034,7 +2030,7 @@ unsafe impl Attribute for nalgebra::Pnt4<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec1<f64> {
+unsafe impl Attribute for nalgebra::Vector1<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64
@@ -2042,7 +2038,7 @@ unsafe impl Attribute for nalgebra::Vec1<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec2<f64> {
+unsafe impl Attribute for nalgebra::Vector2<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64
@@ -2050,7 +2046,7 @@ unsafe impl Attribute for nalgebra::Vec2<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec3<f64> {
+unsafe impl Attribute for nalgebra::Vector3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64
@@ -2058,7 +2054,7 @@ unsafe impl Attribute for nalgebra::Vec3<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Vec4<f64> {
+unsafe impl Attribute for nalgebra::Vector4<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64F64F64F64
@@ -2066,31 +2062,519 @@ unsafe impl Attribute for nalgebra::Vec4<f64> {
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat1<f64> {
+unsafe impl Attribute for nalgebra::Matrix2<f64> {
     #[inline]
     fn get_type() -> AttributeType {
-        AttributeType::F64
+        AttributeType::F64x2x2
     }
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat2<f64> {
+unsafe impl Attribute for nalgebra::Matrix3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
-        AttributeType::F64x2x2
+        AttributeType::F64x3x3
     }
 }
 
 #[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat3<f64> {
+unsafe impl Attribute for nalgebra::Matrix4<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64x4x4
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec3 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec4 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x2x2
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat3 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x3x3
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat4 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x4x4
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I8I8I8I8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u8> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U8U8U8U8
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I16I16I16I16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u16> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U16U16U16U16
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I32I32I32I32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U32U32U32U32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<i64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I64I64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<i64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I64I64I64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<i64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I64I64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<i64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I64I64I64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<i64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::I64I64I64I64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<u64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U64U64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<u64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U64U64U64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<u64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U64U64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<u64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U64U64U64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<u64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::U64U64U64U64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32F32
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix2<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x2x2
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix3<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x3x3
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix4<f32> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x4x4
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point2<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Point3<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector2<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector3<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::Vector4<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64F64F64F64
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix2<f64> {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F64x2x2
+    }
+}
+
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix3<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64x3x3
     }
 }
 
-#[cfg(feature="nalgebra")]
-unsafe impl Attribute for nalgebra::Mat4<f64> {
+#[cfg(feature="mint")]
+unsafe impl Attribute for mint::ColumnMatrix4<f64> {
     #[inline]
     fn get_type() -> AttributeType {
         AttributeType::F64x4x4
@@ -2103,7 +2587,7 @@ mod tests {
     #[allow(unused_imports)]
     use std::mem;
 
-    #[cfg(feature="cgmath")]
+    #[cfg(any(feature = "cgmath", feature = "nalgebra"))]
     macro_rules! test_layout_val {
         ($from_val:path, $ety:ty, $ncomps:expr, $literal:expr) => {{
             let arr: [$ety; $ncomps] = unsafe { mem::transmute($from_val($literal)) };
@@ -2112,10 +2596,11 @@ mod tests {
     }
 
     #[cfg(feature = "nalgebra")]
-    macro_rules! test_layout_ref {
-        ($from_ref:path, $ety:ty, $ncomps:expr, $literal:expr) => {{
-            let arr: &[$ety; $ncomps] = unsafe { mem::transmute($from_ref(&$literal)) };
-            assert_eq!(*arr, $literal);
+    macro_rules! test_layout_point {
+        ($point:ident, $vector:ident, $ety:ty, $ncomps:expr, $literal:expr) => {{
+            let point = nalgebra::$point::from_coordinates(nalgebra::$vector::from($literal));
+            let arr: [$ety; $ncomps] = unsafe { mem::transmute(point) };
+            assert_eq!(arr, $literal);
         }}
     }
 
@@ -2194,94 +2679,94 @@ mod tests {
     fn test_nalgebra_layout() {
         use nalgebra;
 
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u8, 1, [0u8]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u8, 2, [0u8, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u8, 3, [0u8, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u8, 4, [0u8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i8, 1, [0i8]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i8, 2, [0i8, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i8, 3, [0i8, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i8, 4, [0i8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u8, 1, [0u8]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u8, 2, [0u8, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u8, 3, [0u8, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u8, 4, [0u8, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i8, 1, [0i8]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i8, 2, [0i8, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i8, 3, [0i8, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i8, 4, [0i8, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u16, 1, [0u16]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u16, 2, [0u16, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u16, 3, [0u16, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u16, 4, [0u16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i16, 1, [0i16]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i16, 2, [0i16, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i16, 3, [0i16, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i16, 4, [0i16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u16, 1, [0u16]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u16, 2, [0u16, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u16, 3, [0u16, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u16, 4, [0u16, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i16, 1, [0i16]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i16, 2, [0i16, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i16, 3, [0i16, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i16, 4, [0i16, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, u32, 1, [0u32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, u32, 2, [0u32, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, u32, 3, [0u32, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, u32, 4, [0u32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, i32, 1, [0i32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, i32, 2, [0i32, 1]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, i32, 3, [0i32, 1, 2]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, i32, 4, [0i32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, u32, 1, [0u32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, u32, 2, [0u32, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, u32, 3, [0u32, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, u32, 4, [0u32, 1, 2, 3]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, i32, 1, [0i32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, i32, 2, [0i32, 1]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, i32, 3, [0i32, 1, 2]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, i32, 4, [0i32, 1, 2, 3]);
-
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, f32, 1, [0.0f32]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, f32, 2, [0.0f32, 1.0]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, f32, 3, [0.0f32, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Vec1::from_array_ref, f64, 1, [0.0f64]);
-        test_layout_ref!(nalgebra::Vec2::from_array_ref, f64, 2, [0.0f64, 1.0]);
-        test_layout_ref!(nalgebra::Vec3::from_array_ref, f64, 3, [0.0f64, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Vec4::from_array_ref, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, f32, 1, [0.0f32]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, f32, 2, [0.0f32, 1.0]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, f32, 3, [0.0f32, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
-        test_layout_ref!(nalgebra::Pnt1::from_array_ref, f64, 1, [0.0f64]);
-        test_layout_ref!(nalgebra::Pnt2::from_array_ref, f64, 2, [0.0f64, 1.0]);
-        test_layout_ref!(nalgebra::Pnt3::from_array_ref, f64, 3, [0.0f64, 1.0, 2.0]);
-        test_layout_ref!(nalgebra::Pnt4::from_array_ref, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
-
-        test_layout_ref!(nalgebra::Mat1::from_array_ref, [f32; 1], 1, [[0.0f32]]);
-        test_layout_ref!(nalgebra::Mat2::from_array_ref, [f32; 2], 2, [[0.0f32, 1.0],
-                                                                       [2.0f32, 3.0]]);
-        test_layout_ref!(nalgebra::Mat3::from_array_ref, [f32; 3], 3, [[0.0f32, 1.0, 2.0],
-                                                                       [3.0f32, 4.0, 5.0],
-                                                                       [6.0f32, 7.0, 8.0]]);
-        test_layout_ref!(nalgebra::Mat4::from_array_ref, [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
-                                                                       [4.0f32, 5.0, 6.0, 7.0],
-                                                                       [8.0f32, 9.0, 10.0, 11.0],
-                                                                       [12.0f32, 13.0, 14.0, 15.0]]);
-
-        test_layout_ref!(nalgebra::Mat1::from_array_ref, [f64; 1], 1, [[0.0f64]]);
-        test_layout_ref!(nalgebra::Mat2::from_array_ref, [f64; 2], 2, [[0.0f64, 1.0],
-                                                                       [2.0f64, 3.0]]);
-        test_layout_ref!(nalgebra::Mat3::from_array_ref, [f64; 3], 3, [[0.0f64, 1.0, 2.0],
-                                                                       [3.0f64, 4.0, 5.0],
-                                                                       [6.0f64, 7.0, 8.0]]);
-        test_layout_ref!(nalgebra::Mat4::from_array_ref, [f64; 4], 4, [[0.0f64, 1.0, 2.0, 3.0],
-                                                                       [4.0f64, 5.0, 6.0, 7.0],
-                                                                       [8.0f64, 9.0, 10.0, 11.0],
-                                                                       [12.0f64, 13.0, 14.0, 15.0]]);
+        test_layout_val!(nalgebra::Vector1::from, u8, 1, [0u8]);
+        test_layout_val!(nalgebra::Vector2::from, u8, 2, [0u8, 1]);
+        test_layout_val!(nalgebra::Vector3::from, u8, 3, [0u8, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, u8, 4, [0u8, 1, 2, 3]);
+        test_layout_val!(nalgebra::Vector1::from, i8, 1, [0i8]);
+        test_layout_val!(nalgebra::Vector2::from, i8, 2, [0i8, 1]);
+        test_layout_val!(nalgebra::Vector3::from, i8, 3, [0i8, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, i8, 4, [0i8, 1, 2, 3]);
+        test_layout_point!(Point1, Vector1, u8, 1, [0u8]);
+        test_layout_point!(Point2, Vector2, u8, 2, [0u8, 1]);
+        test_layout_point!(Point3, Vector3, u8, 3, [0u8, 1, 2]);
+        test_layout_point!(Point4, Vector4, u8, 4, [0u8, 1, 2, 3]);
+        test_layout_point!(Point1, Vector1, i8, 1, [0i8]);
+        test_layout_point!(Point2, Vector2, i8, 2, [0i8, 1]);
+        test_layout_point!(Point3, Vector3, i8, 3, [0i8, 1, 2]);
+        test_layout_point!(Point4, Vector4, i8, 4, [0i8, 1, 2, 3]);
+
+        test_layout_val!(nalgebra::Vector1::from, u16, 1, [0u16]);
+        test_layout_val!(nalgebra::Vector2::from, u16, 2, [0u16, 1]);
+        test_layout_val!(nalgebra::Vector3::from, u16, 3, [0u16, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, u16, 4, [0u16, 1, 2, 3]);
+        test_layout_val!(nalgebra::Vector1::from, i16, 1, [0i16]);
+        test_layout_val!(nalgebra::Vector2::from, i16, 2, [0i16, 1]);
+        test_layout_val!(nalgebra::Vector3::from, i16, 3, [0i16, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, i16, 4, [0i16, 1, 2, 3]);
+        test_layout_point!(Point1, Vector1, u16, 1, [0u16]);
+        test_layout_point!(Point2, Vector2, u16, 2, [0u16, 1]);
+        test_layout_point!(Point3, Vector3, u16, 3, [0u16, 1, 2]);
+        test_layout_point!(Point4, Vector4, u16, 4, [0u16, 1, 2, 3]);
+        test_layout_point!(Point1, Vector1, i16, 1, [0i16]);
+        test_layout_point!(Point2, Vector2, i16, 2, [0i16, 1]);
+        test_layout_point!(Point3, Vector3, i16, 3, [0i16, 1, 2]);
+        test_layout_point!(Point4, Vector4, i16, 4, [0i16, 1, 2, 3]);
+
+        test_layout_val!(nalgebra::Vector1::from, u32, 1, [0u32]);
+        test_layout_val!(nalgebra::Vector2::from, u32, 2, [0u32, 1]);
+        test_layout_val!(nalgebra::Vector3::from, u32, 3, [0u32, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, u32, 4, [0u32, 1, 2, 3]);
+        test_layout_val!(nalgebra::Vector1::from, i32, 1, [0i32]);
+        test_layout_val!(nalgebra::Vector2::from, i32, 2, [0i32, 1]);
+        test_layout_val!(nalgebra::Vector3::from, i32, 3, [0i32, 1, 2]);
+        test_layout_val!(nalgebra::Vector4::from, i32, 4, [0i32, 1, 2, 3]);
+        test_layout_point!(Point1, Vector1, u32, 1, [0u32]);
+        test_layout_point!(Point2, Vector2, u32, 2, [0u32, 1]);
+        test_layout_point!(Point3, Vector3, u32, 3, [0u32, 1, 2]);
+        test_layout_point!(Point4, Vector4, u32, 4, [0u32, 1, 2, 3]);
+        test_layout_point!(Point1, Vector1, i32, 1, [0i32]);
+        test_layout_point!(Point2, Vector2, i32, 2, [0i32, 1]);
+        test_layout_point!(Point3, Vector3, i32, 3, [0i32, 1, 2]);
+        test_layout_point!(Point4, Vector4, i32, 4, [0i32, 1, 2, 3]);
+
+        test_layout_val!(nalgebra::Vector1::from, f32, 1, [0.0f32]);
+        test_layout_val!(nalgebra::Vector2::from, f32, 2, [0.0f32, 1.0]);
+        test_layout_val!(nalgebra::Vector3::from, f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_val!(nalgebra::Vector4::from, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_val!(nalgebra::Vector1::from, f64, 1, [0.0f64]);
+        test_layout_val!(nalgebra::Vector2::from, f64, 2, [0.0f64, 1.0]);
+        test_layout_val!(nalgebra::Vector3::from, f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_val!(nalgebra::Vector4::from, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+        test_layout_point!(Point1, Vector1, f32, 1, [0.0f32]);
+        test_layout_point!(Point2, Vector2, f32, 2, [0.0f32, 1.0]);
+        test_layout_point!(Point3, Vector3, f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_point!(Point4, Vector4, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_point!(Point1, Vector1, f64, 1, [0.0f64]);
+        test_layout_point!(Point2, Vector2, f64, 2, [0.0f64, 1.0]);
+        test_layout_point!(Point3, Vector3, f64, 3, [0.0f64, 1.0, 2.0]);
+        test_layout_point!(Point4, Vector4, f64, 4, [0.0f64, 1.0, 2.0, 3.0]);
+
+        test_layout_val!(nalgebra::Matrix1::from, [f32; 1], 1, [[0.0f32]]);
+        test_layout_val!(nalgebra::Matrix2::from, [f32; 2], 2, [[0.0f32, 1.0],
+                                                                [2.0f32, 3.0]]);
+        test_layout_val!(nalgebra::Matrix3::from, [f32; 3], 3, [[0.0f32, 1.0, 2.0],
+                                                                [3.0f32, 4.0, 5.0],
+                                                                [6.0f32, 7.0, 8.0]]);
+        test_layout_val!(nalgebra::Matrix4::from, [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
+                                                                [4.0f32, 5.0, 6.0, 7.0],
+                                                                [8.0f32, 9.0, 10.0, 11.0],
+                                                                [12.0f32, 13.0, 14.0, 15.0]]);
+
+        test_layout_val!(nalgebra::Matrix1::from, [f64; 1], 1, [[0.0f64]]);
+        test_layout_val!(nalgebra::Matrix2::from, [f64; 2], 2, [[0.0f64, 1.0],
+                                                                [2.0f64, 3.0]]);
+        test_layout_val!(nalgebra::Matrix3::from, [f64; 3], 3, [[0.0f64, 1.0, 2.0],
+                                                                [3.0f64, 4.0, 5.0],
+                                                                [6.0f64, 7.0, 8.0]]);
+        test_layout_val!(nalgebra::Matrix4::from, [f64; 4], 4, [[0.0f64, 1.0, 2.0, 3.0],
+                                                                [4.0f64, 5.0, 6.0, 7.0],
+                                                                [8.0f64, 9.0, 10.0, 11.0],
+                                                                [12.0f64, 13.0, 14.0, 15.0]]);
     }
 }
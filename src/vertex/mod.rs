@@ -72,6 +72,11 @@ Each source can be:
  - A marker indicating a number of vertex sources, with `glium::vertex::EmptyVertexAttributes`.
  - A marker indicating a number of instances, with `glium::vertex::EmptyInstanceAttributes`.
 
+Slices of the same buffer at different offsets are drawn with the same vertex array object when
+the backend supports base vertices and base instances (OpenGL 4.2, `GL_ARB_base_instance` or
+`GL_EXT_base_instance` for the latter). One large buffer can therefore hold the per-instance data
+of many draw calls, each drawing its own slice of it.
+
 ```no_run
 # use glium::Surface;
 # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
@@ -131,15 +136,20 @@ type and a program. Then you must pass the `&TransformFeedbackSession` to the dr
 The program you use when drawing must be the same as you the one you created the session
 with, or else you will get an error.
 
+If the program writes its varyings to multiple buffers, the other buffers are added to the
+session with `TransformFeedbackSession::add_buffer`.
+
 */
 use std::iter::Chain;
 use std::option::IntoIter;
 
 pub use self::buffer::{VertexBuffer, VertexBufferAny};
 pub use self::buffer::VertexBufferSlice;
+pub use self::buffer::{is_instancing_supported, InstancingNotSupported};
 pub use self::buffer::CreationError as BufferCreationError;
 pub use self::format::{AttributeType, VertexFormat};
 pub use self::transform_feedback::{is_transform_feedback_supported, TransformFeedbackSession};
+pub use self::transform_feedback::TransformFeedbackCounter;
 
 use buffer::BufferAnySlice;
 use CapabilitiesSource;
@@ -1,9 +1,12 @@
 use std::{ mem, fmt };
 use std::error::Error;
+use std::rc::Rc;
 
 use version::Api;
 use version::Version;
 use context::CommandContext;
+use context::SavedTransformFeedbackCapture;
+use context::Context;
 use backend::Facade;
 use BufferExt;
 use GlObject;
@@ -17,6 +20,7 @@ use program::Program;
 use vertex::Vertex;
 
 use gl;
+use utils::gl_get::get_output;
 
 /// Transform feedback allows you to obtain in a buffer the list of the vertices generated by
 /// the vertex shader, geometry shader, or tessellation evaluation shader of your program. This
@@ -50,9 +54,20 @@ use gl;
 ///   individual triangles will be written to the buffer (meaning that some vertices will be
 ///   duplicated).
 ///
-/// - You can use the same session multiple times in a row, in which case the data will continue
-///   to be pushed in the buffer after the existing data. However you must always use the same type
-///   of primitives and the same program.
+/// - You can use the same session multiple times, in which case the data will continue to be
+///   pushed in the buffer after the existing data. However you must always use the same type of
+///   primitives and the same program.
+///
+/// - Drawing without a session pauses the capture, and the next draw command that uses the
+///   session resumes it. Without support for pausing (OpenGL 4.0 or
+///   `GL_ARB_transform_feedback2`), the capture is ended instead and the next draw command with
+///   the session writes at the start of the buffer again.
+///
+/// - Multiple sessions can be alive at the same time, but drawing with one of them ends the
+///   capture of the others unless they were created with `with_counter`. Each counter is a
+///   transform feedback object that remembers the state of its own capture, so sessions that use
+///   different counters can be interleaved freely and each of them keeps appending to its
+///   buffer.
 ///
 /// # Example
 ///
@@ -92,8 +107,141 @@ use gl;
 /// ```
 #[derive(Debug)]
 pub struct TransformFeedbackSession<'a> {
-    buffer: BufferAnySlice<'a>,
+    // indexed by the buffer of the program
+    buffers: Vec<BufferAnySlice<'a>>,
     program: &'a Program,
+    counter: Option<&'a TransformFeedbackCounter>,
+}
+
+/// Records the number of vertices written by a transform feedback session, so that they can be
+/// drawn without reading this number back on the CPU.
+///
+/// Create a session with `TransformFeedbackSession::with_counter`, then once the session is over
+/// draw the content of the buffer by passing `IndicesSource::TransformFeedback` to `draw()`. If
+/// you use per-instance vertex sources, the vertices are drawn once per instance.
+///
+/// The counter is a transform feedback object, which requires OpenGL 4.0 or the
+/// `GL_ARB_transform_feedback2` extension.
+pub struct TransformFeedbackCounter {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+}
+
+impl TransformFeedbackCounter {
+    /// Builds a new counter.
+    pub fn new<F: ?Sized>(facade: &F)
+                          -> Result<TransformFeedbackCounter, TransformFeedbackSessionCreationError>
+                          where F: Facade
+    {
+        let context = facade.get_context();
+
+        if !(context.get_version() >= &Version(Api::Gl, 4, 0) ||
+             context.get_extensions().gl_arb_transform_feedback2)
+        {
+            return Err(TransformFeedbackSessionCreationError::NotSupported);
+        }
+
+        let ctxt = context.make_current();
+
+        let id = unsafe {
+            let id = get_output(|ptr| ctxt.gl.GenTransformFeedbacks(1, ptr));
+            id
+        };
+
+        Ok(TransformFeedbackCounter {
+            context: context.clone(),
+            id: id,
+        })
+    }
+}
+
+impl fmt::Debug for TransformFeedbackCounter {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "TransformFeedbackCounter #{}", self.id)
+    }
+}
+
+impl GlObject for TransformFeedbackCounter {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl Drop for TransformFeedbackCounter {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        // deleting an object whose capture is active is an error, so a paused capture has to be
+        // ended first
+        if is_capture_saved(&ctxt, self.id) {
+            bind_transform_feedback_object(&mut ctxt, self.id);
+        }
+
+        if ctxt.state.transform_feedback_object == self.id {
+            TransformFeedbackSession::unbind(&mut ctxt);
+            bind_transform_feedback_object(&mut ctxt, 0);
+        }
+
+        unsafe { ctxt.gl.DeleteTransformFeedbacks(1, [self.id].as_ptr()); }
+    }
+}
+
+/// Binds a transform feedback object to `GL_TRANSFORM_FEEDBACK` if it isn't already.
+///
+/// If the capture of the previous object is active, it is paused and saved so that it can be
+/// resumed once the object is bound again.
+fn bind_transform_feedback_object(ctxt: &mut CommandContext, id: gl::types::GLuint) {
+    if ctxt.state.transform_feedback_object == id {
+        return;
+    }
+
+    // an object can only be unbound while its capture is paused
+    if let Some(primitives) = ctxt.state.transform_feedback_enabled {
+        if !ctxt.state.transform_feedback_paused {
+            unsafe { ctxt.gl.PauseTransformFeedback(); }
+        }
+
+        let capture = SavedTransformFeedbackCapture {
+            object: ctxt.state.transform_feedback_object,
+            primitives: primitives,
+            buffers: ctxt.state.indexed_transform_feedback_buffer_bindings.clone(),
+        };
+
+        ctxt.state.saved_transform_feedback_captures.push(capture);
+    }
+
+    unsafe { ctxt.gl.BindTransformFeedback(gl::TRANSFORM_FEEDBACK, id); }
+    ctxt.state.transform_feedback_object = id;
+
+    // the buffer bindings are part of the state of the transform feedback object
+    for binding in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
+        binding.buffer = 0;
+    }
+
+    let saved = ctxt.state.saved_transform_feedback_captures.iter().position(|c| c.object == id);
+    if let Some(saved) = saved {
+        let capture = ctxt.state.saved_transform_feedback_captures.remove(saved);
+        ctxt.state.transform_feedback_enabled = Some(capture.primitives);
+        ctxt.state.transform_feedback_paused = true;
+        ctxt.state.indexed_transform_feedback_buffer_bindings = capture.buffers;
+    } else {
+        ctxt.state.transform_feedback_enabled = None;
+        ctxt.state.transform_feedback_paused = false;
+    }
+}
+
+/// Returns true if the capture of a transform feedback object that isn't bound is active.
+fn is_capture_saved(ctxt: &CommandContext, id: gl::types::GLuint) -> bool {
+    ctxt.state.saved_transform_feedback_captures.iter().any(|c| c.object == id)
+}
+
+/// Returns true if the backend supports transform feedback objects, which allow pausing and
+/// resuming captures.
+fn is_pause_supported(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.extensions.gl_arb_transform_feedback2
 }
 
 /// Error that can happen when creating a `TransformFeedbackSession`.
@@ -147,49 +295,118 @@ impl<'a> TransformFeedbackSession<'a> {
             return Err(TransformFeedbackSessionCreationError::NotSupported);
         }
 
-        if !program.transform_feedback_matches(&<V as Vertex>::build_bindings(),
-                                               mem::size_of::<V>())
+        if !program.transform_feedback_buffer_matches(0, &<V as Vertex>::build_bindings(),
+                                                      mem::size_of::<V>())
         {
             return Err(TransformFeedbackSessionCreationError::WrongVertexFormat);
         }
 
         Ok(TransformFeedbackSession {
-            buffer: buffer.as_slice_any(),
+            buffers: vec![buffer.as_slice_any()],
             program: program,
+            counter: None,
         })
     }
+
+    /// Adds the buffer where the next buffer of the program is written, when the program
+    /// captures its varyings to multiple buffers.
+    ///
+    /// The buffer passed to the constructor receives the first buffer of the program, and each
+    /// call to this function adds the following one. Drawing returns
+    /// `DrawError::TransformFeedbackBufferMissing` until all the buffers have been added.
+    pub fn add_buffer<V>(mut self, buffer: &'a mut Buffer<[V]>)
+                         -> Result<TransformFeedbackSession<'a>, TransformFeedbackSessionCreationError>
+                         where V: Vertex + Copy + Send + 'static
+    {
+        if !self.program.transform_feedback_buffer_matches(self.buffers.len(),
+                                                           &<V as Vertex>::build_bindings(),
+                                                           mem::size_of::<V>())
+        {
+            return Err(TransformFeedbackSessionCreationError::WrongVertexFormat);
+        }
+
+        self.buffers.push(buffer.as_slice_any());
+        Ok(self)
+    }
+
+    /// Returns true if a buffer has been added for each buffer that the program captures to.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.buffers.len() == self.program.get_transform_feedback_buffers().len()
+    }
+
+    /// Builds a new transform feedback session that records the number of written vertices in
+    /// `counter`.
+    ///
+    /// The capture is stored in the counter, which means that it is paused instead of ended when
+    /// another session is used, and resumed the next time this session is used. A counter should
+    /// only be used by one session at a time.
+    pub fn with_counter<F: ?Sized, V>(facade: &F, program: &'a Program,
+                                      buffer: &'a mut Buffer<[V]>,
+                                      counter: &'a TransformFeedbackCounter)
+                     -> Result<TransformFeedbackSession<'a>, TransformFeedbackSessionCreationError>
+                     where F: Facade, V: Vertex + Copy + Send + 'static
+    {
+        let mut session = try!(TransformFeedbackSession::new(facade, program, buffer));
+        session.counter = Some(counter);
+        Ok(session)
+    }
 }
 
 impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
     fn bind(&self, ctxt: &mut CommandContext, draw_primitives: PrimitiveType) {
-        // TODO: check that the state matches what is required
-        if ctxt.state.transform_feedback_enabled.is_some() {
-            unimplemented!();
+        let primitives = match (self.program.get_output_primitives(), draw_primitives) {
+            (Some(OutputPrimitives::Points), _) => gl::POINTS,
+            (Some(OutputPrimitives::Lines), _) => gl::LINES,
+            (Some(OutputPrimitives::Triangles), _) => gl::TRIANGLES,
+            (Some(OutputPrimitives::Quads), _) => panic!(),         // TODO: return a proper error
+            (None, PrimitiveType::Points) => gl::POINTS,
+            (None, PrimitiveType::LinesList) => gl::LINES,
+            (None, PrimitiveType::LinesListAdjacency) => gl::LINES,
+            (None, PrimitiveType::LineStrip) => gl::LINES,
+            (None, PrimitiveType::LineStripAdjacency) => gl::LINES,
+            (None, PrimitiveType::LineLoop) => gl::LINES,
+            (None, PrimitiveType::TrianglesList) => gl::TRIANGLES,
+            (None, PrimitiveType::TrianglesListAdjacency) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleStrip) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleStripAdjacency) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleFan) => gl::TRIANGLES,
+            (None, PrimitiveType::Patches { .. }) => unreachable!(),
+        };
+
+        if is_pause_supported(ctxt) {
+            bind_transform_feedback_object(ctxt, self.counter.map(|c| c.id).unwrap_or(0));
+        }
+
+        // binding point of the first buffer, which isn't necessarily 0 if the program uses the
+        // `xfb_buffer` qualifier
+        let first_binding = self.program.get_transform_feedback_buffers().get(0)
+                                        .map(|b| b.id as usize).unwrap_or(0);
+
+        // continuing the capture of this session, so that the data is appended after the data
+        // written by the previous draw commands
+        if ctxt.state.transform_feedback_enabled == Some(primitives) &&
+           ctxt.state.indexed_transform_feedback_buffer_bindings.get(first_binding)
+                     .map(|b| b.buffer) == Some(self.buffers[0].get_id())
+        {
+            if ctxt.state.transform_feedback_paused {
+                unsafe { ctxt.gl.ResumeTransformFeedback(); }
+                ctxt.state.transform_feedback_paused = false;
+            }
+
+            return;
         }
 
+        // the capture of another session is over
+        Self::unbind(ctxt);
+
         // FIXME: use the memory barrier system
-        self.buffer.bind_to_transform_feedback(ctxt, 0);
+        let layout = self.program.get_transform_feedback_buffers();
+        for (buffer, layout) in self.buffers.iter().zip(layout.iter()) {
+            buffer.bind_to_transform_feedback(ctxt, layout.id as gl::types::GLuint);
+        }
 
         unsafe {
-            let primitives = match (self.program.get_output_primitives(), draw_primitives) {
-                (Some(OutputPrimitives::Points), _) => gl::POINTS,
-                (Some(OutputPrimitives::Lines), _) => gl::LINES,
-                (Some(OutputPrimitives::Triangles), _) => gl::TRIANGLES,
-                (Some(OutputPrimitives::Quads), _) => panic!(),         // TODO: return a proper error
-                (None, PrimitiveType::Points) => gl::POINTS,
-                (None, PrimitiveType::LinesList) => gl::LINES,
-                (None, PrimitiveType::LinesListAdjacency) => gl::LINES,
-                (None, PrimitiveType::LineStrip) => gl::LINES,
-                (None, PrimitiveType::LineStripAdjacency) => gl::LINES,
-                (None, PrimitiveType::LineLoop) => gl::LINES,
-                (None, PrimitiveType::TrianglesList) => gl::TRIANGLES,
-                (None, PrimitiveType::TrianglesListAdjacency) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleStrip) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleStripAdjacency) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleFan) => gl::TRIANGLES,
-                (None, PrimitiveType::Patches { .. }) => unreachable!(),
-            };
-
             ctxt.gl.BeginTransformFeedback(primitives);
             ctxt.state.transform_feedback_enabled = Some(primitives);
             ctxt.state.transform_feedback_paused = false;
@@ -209,6 +426,19 @@ impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
         }
     }
 
+    fn suspend(ctxt: &mut CommandContext) {
+        if ctxt.state.transform_feedback_enabled.is_none() || ctxt.state.transform_feedback_paused {
+            return;
+        }
+
+        if is_pause_supported(ctxt) {
+            unsafe { ctxt.gl.PauseTransformFeedback(); }
+            ctxt.state.transform_feedback_paused = true;
+        } else {
+            Self::unbind(ctxt);
+        }
+    }
+
     fn ensure_buffer_out_of_transform_feedback(ctxt: &mut CommandContext, buffer: gl::types::GLuint) {
         if ctxt.state.transform_feedback_enabled.is_none() {
             return;
@@ -234,7 +464,17 @@ impl<'a> Drop for TransformFeedbackSession<'a> {
         // Since the session can be mem::forget'ed, the code in buffer/alloc.rs ensures that the
         // buffer isn't used by transform feedback.
         // However we end the session now anyway.
-        let mut ctxt = self.buffer.get_context().make_current();
-        Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, self.buffer.get_id());
+        let mut ctxt = self.buffers[0].get_context().make_current();
+
+        // the capture may be paused in a transform feedback object that isn't bound
+        if let Some(counter) = self.counter {
+            if is_capture_saved(&ctxt, counter.id) {
+                bind_transform_feedback_object(&mut ctxt, counter.id);
+            }
+        }
+
+        for buffer in self.buffers.iter() {
+            Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, buffer.get_id());
+        }
     }
 }
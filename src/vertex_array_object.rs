@@ -5,10 +5,11 @@ use std::mem;
 
 use smallvec::SmallVec;
 
-use Handle;
+use ProgramExt;
 use buffer::BufferAnySlice;
 use program::Program;
 use vertex::AttributeType;
+use DrawError;
 use vertex::VertexFormat;
 use GlObject;
 use BufferExt;
@@ -20,18 +21,38 @@ use version::Version;
 
 /// Stores and handles vertex attributes.
 pub struct VertexAttributesSystem {
-    // we maintain a list of VAOs for each vertexbuffer-indexbuffer-program association
-    // the key is a (buffers-list-with-offset, program) ; the buffers list must be sorted
-    vaos: RefCell<HashMap<(Vec<(gl::types::GLuint, usize)>, Handle), VertexArrayObject>>,
+    // we maintain a list of VAOs for each vertexbuffer-indexbuffer-attributes association
+    // the key is a (buffers-list-with-offset, attributes layout of the program) ; the buffers
+    // list must be sorted ; programs with the same attributes layout share their VAOs
+    vaos: RefCell<HashMap<VaoKey, VertexArrayObject>>,
+
+    // maximum number of VAOs in the cache ; the least recently used ones are destroyed
+    // when the limit is reached
+    capacity: Cell<usize>,
+
+    // incremented each time a VAO is used, to determine which one is the least recently used
+    clock: Cell<u64>,
+
+    // number of times a VAO has been found in the cache or had to be created
+    hits: Cell<u64>,
+    misses: Cell<u64>,
 }
 
+/// Default maximum number of VAOs in the cache.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Key of the VAOs cache. The list of buffers is stored inline so that looking up a VAO
+/// doesn't require any allocation.
+type VaoKey = (SmallVec<[(gl::types::GLuint, usize); 4]>, u64);
+
 /// Object allowing one to bind vertex attributes to the current context.
 pub struct Binder<'a, 'b, 'c: 'b> {
     context: &'b mut CommandContext<'c>,
     program: &'a Program,
     element_array_buffer: Option<BufferAnySlice<'a>>,
-    vertex_buffers: SmallVec<[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>); 2]>,
+    vertex_buffers: SmallVec<[(gl::types::GLuint, &'a VertexFormat, usize, usize, Option<u32>); 2]>,
     base_vertex: bool,
+    base_instance: bool,
 }
 
 impl VertexAttributesSystem {
@@ -40,17 +61,60 @@ impl VertexAttributesSystem {
     pub fn new() -> VertexAttributesSystem {
         VertexAttributesSystem {
             vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            capacity: Cell::new(DEFAULT_CAPACITY),
+            clock: Cell::new(0),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of VAOs in the cache, the number of cache hits and the number of
+    /// cache misses.
+    #[inline]
+    pub fn get_statistics(&self) -> (usize, u64, u64) {
+        (self.vaos.borrow().len(), self.hits.get(), self.misses.get())
+    }
+
+    /// Returns the maximum number of VAOs in the cache.
+    #[inline]
+    pub fn get_capacity(&self) -> usize {
+        self.capacity.get()
+    }
+
+    /// Changes the maximum number of VAOs in the cache, and destroys the least recently used
+    /// VAOs if there are too many of them.
+    pub fn set_capacity(ctxt: &mut CommandContext, capacity: usize) {
+        ctxt.vertex_array_objects.capacity.set(capacity);
+
+        let mut vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
+        while vaos.len() > capacity {
+            VertexAttributesSystem::evict_least_recently_used(ctxt, &mut vaos);
         }
     }
 
+    /// Destroys the VAO of the cache that hasn't been used for the longest time.
+    fn evict_least_recently_used(ctxt: &mut CommandContext,
+                                 vaos: &mut HashMap<VaoKey, VertexArrayObject>)
+    {
+        let key = match vaos.iter().min_by_key(|&(_, vao)| vao.last_used.get()) {
+            Some((key, _)) => key.clone(),
+            None => return,
+        };
+
+        vaos.remove(&key).unwrap().destroy(ctxt);
+    }
+
     /// Starts the process of binding vertex attributes.
     ///
     /// `base_vertex` should be set to true if the backend supports the `glDraw*BaseVertex`
     /// functions. If `base_vertex` is true, then `bind` will return the base vertex to use.
+    ///
+    /// `base_instance` should be set to true if the backend supports the `glDraw*BaseInstance`
+    /// functions. If `base_instance` is true, then `bind` will return the base instance to use.
     #[inline]
     pub fn start<'a, 'b, 'c: 'b>(ctxt: &'b mut CommandContext<'c>, program: &'a Program,
-                                 indices: Option<BufferAnySlice<'a>>, base_vertex: bool)
-                                 -> Binder<'a, 'b, 'c>
+                                 indices: Option<BufferAnySlice<'a>>, base_vertex: bool,
+                                 base_instance: bool) -> Binder<'a, 'b, 'c>
     {
         if let Some(indices) = indices {
             indices.prepare_for_element_array(ctxt);
@@ -62,6 +126,7 @@ impl VertexAttributesSystem {
             element_array_buffer: indices,
             vertex_buffers: SmallVec::new(),
             base_vertex: base_vertex,
+            base_instance: base_instance,
         }
     }
 
@@ -74,11 +139,12 @@ impl VertexAttributesSystem {
         })
     }
 
-    /// This function *must* be called whenever you destroy a program so that the system can
-    /// purge its VAOs cache.
+    /// Should be called whenever you destroy a program so that the system can purge its VAOs
+    /// cache. The VAOs of other programs with the same attributes layout are purged as well,
+    /// and are rebuilt the next time they are needed.
     #[inline]
-    pub fn purge_program(ctxt: &mut CommandContext, program: Handle) {
-        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program)
+    pub fn purge_program(ctxt: &mut CommandContext, attributes_layout: u64) {
+        VertexAttributesSystem::purge_if(ctxt, |&(_, l)| l == attributes_layout)
     }
 
     /// Purges the VAOs cache.
@@ -116,7 +182,7 @@ impl VertexAttributesSystem {
 
     /// Purges VAOs that match a certain condition.
     fn purge_if<F>(ctxt: &mut CommandContext, mut condition: F)
-                   where F: FnMut(&(Vec<(gl::types::GLuint, usize)>, Handle)) -> bool
+                   where F: FnMut(&VaoKey) -> bool
     {
         let mut vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
 
@@ -142,14 +208,14 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
     /// - `first`: Offset of the first element of the buffer in number of elements.
     /// - `divisor`: If `Some`, use this value for `glVertexAttribDivisor` (instancing-related).
     #[inline]
-    pub fn add(mut self, buffer: &BufferAnySlice, bindings: &VertexFormat, divisor: Option<u32>)
+    pub fn add(mut self, buffer: &BufferAnySlice, bindings: &'a VertexFormat, divisor: Option<u32>)
                -> Binder<'a, 'b, 'c>
     {
         let offset = buffer.get_offset_bytes();
 
         buffer.prepare_for_vertex_attrib_array(self.context);
 
-        let (buffer, format, stride) = (buffer.get_id(), bindings.clone(),
+        let (buffer, format, stride) = (buffer.get_id(), bindings,
                                         buffer.get_elements_size());
 
         self.vertex_buffers.push((buffer, format, offset, stride, divisor));
@@ -158,8 +224,11 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
 
     /// Finish binding the vertex attributes.
     ///
-    /// If `base_vertex` was set to true, returns the base vertex to use when drawing.
-    pub fn bind(mut self) -> Option<gl::types::GLint> {
+    /// If `base_vertex` was set to true, returns the base vertex to use when drawing. If
+    /// `base_instance` was set to true, returns the base instance to use when drawing.
+    pub fn bind(mut self)
+                -> Result<(Option<gl::types::GLint>, Option<gl::types::GLuint>), DrawError>
+    {
         let ctxt = self.context;
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
@@ -178,42 +247,76 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
                 None
             };
 
-            // removing the offset corresponding to the base vertex
-            if let Some(base_vertex) = base_vertex {
-                for &mut (_, _, ref mut off, stride, _) in self.vertex_buffers.iter_mut() {
-                    *off -= base_vertex * stride;
+            // finding the base instance
+            let base_instance = if self.base_instance {
+                Some(self.vertex_buffers.iter()
+                                        .filter(|&&(_, _, _, _, div)| div.is_some())
+                                        .map(|&(_, _, off, stride, _)| off / stride)
+                                        .min().unwrap_or(0))
+            } else {
+                None
+            };
+
+            // removing the offset corresponding to the base vertex and the base instance, so
+            // that slices of the same buffers at different offsets share the same VAO
+            for &mut (_, _, ref mut off, stride, div) in self.vertex_buffers.iter_mut() {
+                match (div, base_vertex, base_instance) {
+                    (None, Some(base_vertex), _) => *off -= base_vertex * stride,
+                    (Some(_), _, Some(base_instance)) => *off -= base_instance * stride,
+                    _ => ()
                 }
             }
 
-            let mut buffers_list: Vec<_> = self.vertex_buffers.iter()
-                                                              .map(|&(v, _, o, s, _)| (v, o))
-                                                              .collect();
+            let bases = (base_vertex.map(|v| v as gl::types::GLint),
+                         base_instance.map(|i| i as gl::types::GLuint));
+
+            let mut buffers_list: SmallVec<[_; 4]> = self.vertex_buffers.iter()
+                                                                 .map(|&(v, _, o, s, _)| (v, o))
+                                                                 .collect();
             buffers_list.push((self.element_array_buffer.map(|b| b.get_id()).unwrap_or(0), 0));
             buffers_list.sort();
 
-            let program_id = self.program.get_id();
+            let key = (buffers_list, self.program.get_attributes_layout());
+
+            let system = ctxt.vertex_array_objects;
+            let now = system.clock.get() + 1;
+            system.clock.set(now);
 
             // trying to find an existing VAO in the cache
-            if let Some(value) = ctxt.vertex_array_objects.vaos.borrow_mut()
-                                     .get(&(buffers_list.clone(), program_id))
-            {
+            if let Some(value) = system.vaos.borrow_mut().get(&key) {
+                system.hits.set(system.hits.get() + 1);
+                value.last_used.set(now);
                 value.bind(ctxt);
-                return base_vertex.map(|v| v as gl::types::GLint);
+                return Ok(bases);
+            }
+
+            system.misses.set(system.misses.get() + 1);
+
+            // making room in the cache
+            {
+                let mut vaos = system.vaos.borrow_mut();
+                while vaos.len() != 0 && vaos.len() >= system.capacity.get() {
+                    VertexAttributesSystem::evict_least_recently_used(ctxt, &mut vaos);
+                }
             }
 
             // if not found, building a new one
+            try!(check_attributes(self.vertex_buffers.iter().map(|b| b.1), self.program));
+
             let new_vao = unsafe {
                 VertexArrayObject::new(ctxt, &self.vertex_buffers,
                                        self.element_array_buffer, self.program)
             };
 
+            new_vao.last_used.set(now);
             new_vao.bind(ctxt);
-            ctxt.vertex_array_objects.vaos.borrow_mut().insert((buffers_list, program_id), new_vao);
+            system.vaos.borrow_mut().insert(key, new_vao);
 
-            base_vertex.map(|v| v as gl::types::GLint)
+            Ok(bases)
 
         } else {
             // VAOs are not supported
+            try!(check_attributes(self.vertex_buffers.iter().map(|b| b.1), self.program));
 
             // just in case
             bind_vao(ctxt, 0);
@@ -224,20 +327,86 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
 
             for (vertex_buffer, bindings, offset, stride, divisor) in self.vertex_buffers.into_iter() {
                 unsafe {
-                    bind_attribute(ctxt, self.program, vertex_buffer, &bindings, offset, stride,
+                    bind_attribute(ctxt, self.program, vertex_buffer, bindings, offset, stride,
                                    divisor);
                 }
             }
 
             // TODO: it is unlikely that a backend supports base vertex but not VAOs, so we just
             //       ignore this case ; however it would ideally be better to handle it
-            if self.base_vertex {
-                Some(0)
-            } else {
-                None
+            Ok((if self.base_vertex { Some(0) } else { None },
+                if self.base_instance { Some(0) } else { None }))
+        }
+    }
+}
+
+/// Checks that the vertex formats provide all the attributes of the program with the right types.
+pub fn check_attributes<'a, I>(formats: I, program: &Program) -> Result<(), DrawError>
+    where I: Iterator<Item = &'a VertexFormat> + Clone
+{
+    for bindings in formats.clone() {
+        for &(ref name, _, ty, normalize) in bindings.iter() {
+            let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
+                Some(a) => a,
+                None => continue
+            };
+
+            if ty.get_num_components() != attribute.ty.get_num_components() ||
+                attribute.size != 1 || !is_data_type_compatible(attribute.ty, ty)
+            {
+                return Err(DrawError::AttributeTypeMismatch {
+                    name: Borrow::<str>::borrow(name).to_owned(),
+                    expected: attribute.ty,
+                    obtained: ty,
+                });
             }
+
+            if normalize && is_integral(attribute.ty) {
+                return Err(DrawError::NormalizedIntegerAttribute {
+                    name: Borrow::<str>::borrow(name).to_owned(),
+                });
+            }
+        }
+    }
+
+    for (name, _) in program.attributes() {
+        let found = formats.clone().any(|bindings| {
+            bindings.iter().any(|&(ref n, _, _, _)| n == name)
+        });
+
+        if !found {
+            return Err(DrawError::AttributeMissing { name: name.clone() });
         }
     }
+
+    Ok(())
+}
+
+/// Returns true if the attribute of the program is an integer that is not converted to
+/// floating-point, in which case it is bound with `glVertexAttribIPointer`.
+fn is_integral(attribute: AttributeType) -> bool {
+    match vertex_binding_type_to_gl(attribute).0 {
+        gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+        gl::INT | gl::UNSIGNED_INT => true,
+        _ => false,
+    }
+}
+
+/// Returns true if values of type `data` in a vertex buffer can be used to feed an attribute of
+/// type `attribute` of the program.
+///
+/// Floating-point attributes accept anything that `glVertexAttribPointer` can convert, while
+/// integer and 64-bits attributes don't do any conversion.
+fn is_data_type_compatible(attribute: AttributeType, data: AttributeType) -> bool {
+    let attribute_ty = vertex_binding_type_to_gl(attribute).0;
+    let data_ty = vertex_binding_type_to_gl(data).0;
+
+    match attribute_ty {
+        gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+        gl::INT | gl::UNSIGNED_INT => is_integral(data),
+        gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV => data_ty == attribute_ty,
+        _ => data_ty != gl::INT64_NV && data_ty != gl::UNSIGNED_INT64_NV,
+    }
 }
 
 /// Stores informations about how to bind a vertex buffer, an index buffer and a program.
@@ -246,6 +415,8 @@ struct VertexArrayObject {
     destroyed: bool,
     element_array_buffer: gl::types::GLuint,
     element_array_buffer_hijacked: Cell<bool>,
+    // value of the clock of the `VertexAttributesSystem` when this VAO was last used
+    last_used: Cell<u64>,
 }
 
 impl VertexArrayObject {
@@ -254,45 +425,14 @@ impl VertexArrayObject {
     /// The vertex buffer, index buffer and program must not outlive the
     /// VAO, and the VB & program attributes must not change.
     unsafe fn new(mut ctxt: &mut CommandContext,
-                  vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
+                  vertex_buffers: &[(gl::types::GLuint, &VertexFormat, usize, usize, Option<u32>)],
                   index_buffer: Option<BufferAnySlice>, program: &Program) -> VertexArrayObject
     {
-        // checking the attributes types
-        for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for &(ref name, _, ty, _) in bindings.iter() {
-                let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
-                    Some(a) => a,
-                    None => continue
-                };
-
-                if ty.get_num_components() != attribute.ty.get_num_components() ||
-                    attribute.size != 1
-                {
-                    panic!("The program attribute `{}` does not match the vertex format. \
-                            Program expected {:?}, got {:?}.", name, attribute.ty, ty);
-                }
-            }
-        }
-
-        // checking for missing attributes
-        for (&ref name, _) in program.attributes() {
-            let mut found = false;
-            for &(_, ref bindings, _, _, _) in vertex_buffers {
-                if bindings.iter().find(|&&(ref n, _, _, _)| n == name).is_some() {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                panic!("The program attribute `{}` is missing in the vertex bindings", name);
-            }
-        };
-
         // TODO: check for collisions between the vertices sources
 
         // building the VAO
         let id = {
-            let mut id = mem::uninitialized();
+            let mut id = 0;
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                 ctxt.version >= &Version(Api::GlEs, 3, 0) ||
                 ctxt.extensions.gl_arb_vertex_array_object
@@ -317,7 +457,7 @@ impl VertexArrayObject {
             index_buffer.bind_to_element_array(&mut ctxt);
         }
 
-        for &(vertex_buffer, ref bindings, offset, stride, divisor) in vertex_buffers {
+        for &(vertex_buffer, bindings, offset, stride, divisor) in vertex_buffers {
             bind_attribute(ctxt, program, vertex_buffer, bindings, offset, stride, divisor);
         }
 
@@ -326,6 +466,7 @@ impl VertexArrayObject {
             destroyed: false,
             element_array_buffer: index_buffer.map(|b| b.get_id()).unwrap_or(0),
             element_array_buffer_hijacked: Cell::new(false),
+            last_used: Cell::new(0),
         }
     }
 
@@ -570,10 +711,29 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext, program: &Program,
 
             for i in 0..instances_count {
                 if let Some(divisor) = divisor {
-                    ctxt.gl.VertexAttribDivisor((attribute.location + i) as u32, divisor);
+                    set_attribute_divisor(ctxt, (attribute.location + i) as u32, divisor);
                 }
                 ctxt.gl.EnableVertexAttribArray((attribute.location + i) as u32);
             }
         }
     }
 }
+
+/// Calls `glVertexAttribDivisor` or one of its extension equivalents.
+///
+/// The caller must have checked that instancing is supported by the backend.
+unsafe fn set_attribute_divisor(ctxt: &mut CommandContext, location: gl::types::GLuint,
+                                divisor: gl::types::GLuint)
+{
+    if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+        ctxt.gl.VertexAttribDivisor(location, divisor);
+    } else if ctxt.extensions.gl_arb_instanced_arrays {
+        ctxt.gl.VertexAttribDivisorARB(location, divisor);
+    } else if ctxt.extensions.gl_ext_instanced_arrays {
+        ctxt.gl.VertexAttribDivisorEXT(location, divisor);
+    } else if ctxt.extensions.gl_angle_instanced_arrays {
+        ctxt.gl.VertexAttribDivisorANGLE(location, divisor);
+    } else {
+        unreachable!();
+    }
+}
@@ -7,7 +7,6 @@ use glium::index::PrimitiveType;
 mod support;
 
 #[test]
-#[should_panic(expected = "The program attribute `field1` does not match the vertex format")]
 fn attribute_types_mismatch() {
     let display = support::build_display();
 
@@ -79,15 +78,18 @@ fn attribute_types_mismatch() {
 
     // drawing a frame
     let mut target = display.draw();
-    target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
-                &Default::default()).unwrap();
+    match target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                      &Default::default())
+    {
+        Err(glium::DrawError::AttributeTypeMismatch { ref name, .. }) if name == "field1" => (),
+        res => panic!("{:?}", res)
+    }
     target.finish().unwrap();
 
     display.assert_no_error(None);
 }
 
 #[test]
-#[should_panic(expected = "The program attribute `field2` is missing in the vertex bindings")]
 fn missing_attribute() {
     let display = support::build_display();
 
@@ -159,8 +161,12 @@ fn missing_attribute() {
 
     // drawing a frame
     let mut target = display.draw();
-    target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
-                &Default::default()).unwrap();
+    match target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                      &Default::default())
+    {
+        Err(glium::DrawError::AttributeMissing { ref name }) if name == "field2" => (),
+        res => panic!("{:?}", res)
+    }
     target.finish().unwrap();
 
     display.assert_no_error(None);
@@ -307,3 +313,155 @@ attribute_test!(attribute_vec3_i32, [i32; 3], "vec3", [0, 0, 0], "vec4(field1, 1
 attribute_test!(attribute_vec3_tuple_i32, (i32, i32, i32), "vec3", (0, 0, 0), "vec4(field1, 1.0)");
 attribute_test!(attribute_vec4_i32, [i32; 4], "vec4", [0, 0, 0, 0], "field1");
 attribute_test!(attribute_vec4_tuple_i32, (i32, i32, i32, i32), "vec4", (0, 0, 0, 0), "field1");
+
+#[test]
+fn integer_attribute_with_float_data() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, field1);
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &Vec::<Vertex>::new()).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in ivec2 field1;
+
+            void main() {
+                gl_Position = vec4(vec2(field1), 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+            out vec4 color;
+            void main() {
+                color = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    match program.check_vertex_formats(&[vertex_buffer.get_bindings()]) {
+        Err(glium::DrawError::AttributeTypeMismatch { ref name, .. }) if name == "field1" => (),
+        res => panic!("{:?}", res)
+    }
+
+    let mut target = display.draw();
+    match target.draw(&vertex_buffer, &glium::index::NoIndices(PrimitiveType::Points), &program,
+                      &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::AttributeTypeMismatch { ref name, .. }) if name == "field1" => (),
+        res => panic!("{:?}", res)
+    }
+    target.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn integer_attribute_normalized() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [i32; 2],
+    }
+
+    implement_vertex!(Vertex, field1 normalize(true));
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &Vec::<Vertex>::new()).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in ivec2 field1;
+
+            void main() {
+                gl_Position = vec4(vec2(field1), 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+            out vec4 color;
+            void main() {
+                color = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    match program.check_vertex_formats(&[vertex_buffer.get_bindings()]) {
+        Err(glium::DrawError::NormalizedIntegerAttribute { ref name }) if name == "field1" => (),
+        res => panic!("{:?}", res)
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn float_attribute_with_integer_data() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1 normalize(true));
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &Vec::<Vertex>::new()).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 field1;
+
+                void main() {
+                    gl_Position = vec4(field1, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+                void main() {
+                    gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute mediump vec2 field1;
+
+                void main() {
+                    gl_Position = vec4(field1, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+                void main() {
+                    gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            "
+        }
+    ).unwrap();
+
+    program.check_vertex_formats(&[vertex_buffer.get_bindings()]).unwrap();
+
+    display.assert_no_error(None);
+}
@@ -56,3 +56,154 @@ fn blit_texture_to_window() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_and_resolve_second_attachment() {
+    use glium::framebuffer::{BlitMask, MultiOutputFrameBuffer, SimpleFrameBuffer};
+
+    let display = support::build_display();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dest_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+
+    let first = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let second = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+    let source = MultiOutputFrameBuffer::new(&display, [("first", &first), ("second", &second)]
+                                                           .iter().cloned()).unwrap();
+
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let target_fb = SimpleFrameBuffer::new(&display, &target).unwrap();
+
+    glium::framebuffer::blit_and_resolve(&display, &source, &target_fb, &src_rect, &dest_rect,
+                                         glium::uniforms::MagnifySamplerFilter::Nearest,
+                                         BlitMask::color_attachment(1, 0)).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[1][1], (0, 255, 0, 255));
+    assert_eq!(data[2][2], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_and_resolve_attachment_out_of_range() {
+    use glium::framebuffer::{BlitError, BlitMask, SimpleFrameBuffer};
+
+    let display = support::build_display();
+
+    let src_rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let dest_rect = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+
+    let texture = support::build_renderable_texture(&display);
+    let framebuffer = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    match glium::framebuffer::blit_and_resolve(&display, &framebuffer, &framebuffer, &src_rect,
+                                               &dest_rect,
+                                               glium::uniforms::MagnifySamplerFilter::Nearest,
+                                               BlitMask::color_attachment(1, 0))
+    {
+        Err(BlitError::SourceColorAttachmentOutOfRange(1)) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_rects_multiple() {
+    use glium::framebuffer::{BlitMask, SimpleFrameBuffer};
+
+    let display = support::build_display();
+
+    let texture = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+    let source = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let target_fb = SimpleFrameBuffer::new(&display, &target).unwrap();
+
+    let rects = [
+        (Rect { left: 0, bottom: 0, width: 1, height: 1 },
+         BlitTarget { left: 0, bottom: 0, width: 1, height: 1 }),
+        (Rect { left: 0, bottom: 0, width: 1, height: 1 },
+         BlitTarget { left: 2, bottom: 2, width: 2, height: 2 }),
+    ];
+
+    glium::framebuffer::blit_rects(&display, &source, &target_fb, &rects,
+                                   glium::uniforms::MagnifySamplerFilter::Nearest,
+                                   BlitMask::color()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[1][1], (0, 0, 0, 0));
+    assert_eq!(data[2][2], (0, 255, 0, 255));
+    assert_eq!(data[3][3], (0, 255, 0, 255));
+    assert_eq!(data[4][4], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_rects_depth_linear_filter() {
+    use glium::framebuffer::{BlitError, BlitMask, SimpleFrameBuffer};
+
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    let framebuffer = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    let rects = [(Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                  BlitTarget { left: 0, bottom: 0, width: 4, height: 4 })];
+
+    match glium::framebuffer::blit_rects(&display, &framebuffer, &framebuffer, &rects,
+                                         glium::uniforms::MagnifySamplerFilter::Linear,
+                                         BlitMask::depth())
+    {
+        Err(BlitError::DepthStencilFilterNotNearest) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_rects_multisampled_errors() {
+    use glium::framebuffer::{BlitError, BlitMask, RenderBuffer, SimpleFrameBuffer};
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+
+    let buffer = match RenderBuffer::new_multisample(&display, UncompressedFloatFormat::U8U8U8U8,
+                                                     16, 16, 4)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+    let multisampled = SimpleFrameBuffer::new(&display, &buffer).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let framebuffer = SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    let rects = [(Rect { left: 0, bottom: 0, width: 16, height: 16 },
+                  BlitTarget { left: 0, bottom: 0, width: 32, height: 32 })];
+
+    match glium::framebuffer::blit_rects(&display, &multisampled, &framebuffer, &rects,
+                                         glium::uniforms::MagnifySamplerFilter::Nearest,
+                                         BlitMask::color())
+    {
+        Err(BlitError::MultisampledRectsMismatch) => (),
+        a => panic!("{:?}", a)
+    };
+
+    match glium::framebuffer::blit_rects(&display, &framebuffer, &multisampled, &rects,
+                                         glium::uniforms::MagnifySamplerFilter::Nearest,
+                                         BlitMask::color())
+    {
+        Err(BlitError::MultisampledTarget) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
@@ -5,6 +5,7 @@ mod support;
 
 use glium::Surface;
 use glium::buffer::BufferMode;
+use glium::buffer::MapWriteOptions;
 use std::mem;
 
 #[test]
@@ -203,6 +204,59 @@ fn buffer_dynamic_mapping_write() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn buffer_dynamic_map_range_write() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+        field2: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1, field2);
+
+    let mut vb = glium::VertexBuffer::dynamic(&display,
+        &[
+            Vertex { field1: [ 2,  3], field2: [ 5,  7] },
+            Vertex { field1: [12, 13], field2: [15, 17] },
+            Vertex { field1: [22, 23], field2: [25, 27] },
+        ]
+    ).unwrap();
+
+    {
+        let options = MapWriteOptions { invalidate_range: true, .. Default::default() };
+        let mut mapping = vb.map_range_write(1, 1, options);
+        mapping.set(0, Vertex { field1: [0, 1], field2: [4, 5] });
+    }
+
+    let mapping = vb.map();
+    assert_eq!(mapping[0].field1, [2, 3]);
+    assert_eq!(mapping[1].field1, [0, 1]);
+    assert_eq!(mapping[1].field2, [4, 5]);
+    assert_eq!(mapping[2].field2, [25, 27]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn buffer_map_range_write_out_of_bounds() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+        field2: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1, field2);
+
+    let mut vb: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty_dynamic(&display, 2)
+                                                                            .unwrap();
+    vb.map_range_write(1, 2, Default::default());
+}
+
 // TODO: uncomment after std::thread::scoped has been stabilized
 /*#[test]
 fn buffer_mapping_multithread() {
@@ -976,3 +1030,181 @@ fn copy_to_slice() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn arena_allocations() {
+    let display = support::build_display();
+
+    let mut arena = glium::buffer::BufferArena::new(&display,
+                                                    glium::buffer::BufferType::ArrayBuffer,
+                                                    BufferMode::Default, 64);
+
+    let a = arena.allocate_with_data(&[1u32, 2, 3]).unwrap();
+    let b = arena.allocate_with_data(&[4u16, 5]).unwrap();
+    assert_eq!(arena.get_blocks_count(), 1);
+    assert_eq!(a.get_offset_bytes() % 4, 0);
+    assert_eq!(b.get_offset_bytes() % 4, 0);
+
+    // larger than a block, so it gets a block of its own
+    let c = arena.allocate::<u8>(128).unwrap();
+    assert_eq!(arena.get_blocks_count(), 2);
+
+    // the freed space is reused
+    let offset = a.get_offset_bytes();
+    arena.free(a);
+    let d = arena.allocate_with_data(&[6u32, 7]).unwrap();
+    assert_eq!(d.get_offset_bytes(), offset);
+    assert_eq!(arena.get_blocks_count(), 2);
+
+    match arena.slice(&d).read() {
+        Ok(data) => assert_eq!(data, [6, 7]),
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        e => { e.unwrap(); },
+    };
+
+    match arena.slice(&b).read() {
+        Ok(data) => assert_eq!(data, [4, 5]),
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        e => { e.unwrap(); },
+    };
+
+    arena.free(b);
+    arena.free(c);
+    arena.free(d);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn arena_free_foreign_allocation() {
+    let display = support::build_display();
+
+    let mut first = glium::buffer::BufferArena::new(&display,
+                                                    glium::buffer::BufferType::ArrayBuffer,
+                                                    BufferMode::Default, 64);
+    let mut second = glium::buffer::BufferArena::new(&display,
+                                                     glium::buffer::BufferType::ArrayBuffer,
+                                                     BufferMode::Default, 64);
+
+    let _ = second.allocate::<u32>(4).unwrap();
+    let allocation = first.allocate::<u32>(4).unwrap();
+    second.free(allocation);
+}
+
+#[test]
+fn dynamic_buffer_rotation() {
+    let display = support::build_display();
+
+    let mut buffer = glium::buffer::DynamicBuffer::new(&display, &[0u8, 0, 0][..],
+                                                       glium::buffer::BufferType::ArrayBuffer,
+                                                       3).unwrap();
+    assert_eq!(buffer.get_copies_count(), 3);
+
+    for i in 1 .. 5u8 {
+        buffer.write(&[i, i, i]);
+
+        match buffer.current().read() {
+            Ok(data) => assert_eq!(data, [i, i, i]),
+            Err(glium::buffer::ReadError::NotSupported) => (),
+            e => { e.unwrap(); },
+        };
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sparse_buffer_commit() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let page_size = match display.get_capabilities().sparse_buffer_page_size {
+        Some(size) => size as usize,
+        None => return,
+    };
+
+    let buffer = glium::buffer::Buffer::<[u8]>::empty_array_sparse(&display,
+                                                    glium::buffer::BufferType::ArrayBuffer,
+                                                    page_size * 4).unwrap();
+    assert!(buffer.is_sparse());
+
+    buffer.commit(page_size .. page_size * 2);
+    buffer.slice(page_size .. page_size * 2).unwrap().write(&vec![5u8; page_size]);
+
+    match buffer.slice(page_size .. page_size + 4).unwrap().read() {
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        e => panic!("{:?}", e),
+    };
+
+    buffer.decommit(page_size .. page_size * 2);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_clear() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            BufferMode::Default).unwrap();
+
+    buffer.clear(7);
+    buffer.clear_range(1 .. 3, 9);
+
+    match buffer.read() {
+        Ok(data) => assert_eq!(data, [7, 9, 9, 7]),
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        e => { e.unwrap(); },
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_clear_unusual_size() {
+    let display = support::build_display();
+
+    // 3 bytes per element, which can't be cleared with `glClearBufferSubData`
+    let buffer = glium::buffer::Buffer::new(&display, &[[0u8; 3]; 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            BufferMode::Default).unwrap();
+
+    buffer.clear([1, 2, 3]);
+
+    match buffer.read() {
+        Ok(data) => assert_eq!(data, [[1, 2, 3]; 4]),
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        e => { e.unwrap(); },
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn slice_read_async() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            BufferMode::Default).unwrap();
+
+    let mut pending = match buffer.slice(1 .. 3).unwrap().read_async() {
+        Ok(p) => p,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => { e.unwrap(); return; },
+    };
+
+    display.finish();
+    assert!(pending.is_ready());
+
+    match pending.read() {
+        Ok(data) => assert_eq!(data, [2, 3]),
+        Err(glium::buffer::ReadError::NotSupported) => (),
+        e => { e.unwrap(); },
+    };
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate glium;
+#[cfg(feature = "screenshot_png")]
+extern crate image;
+
+use glium::Surface;
+use glium::capture::{self, ScreenshotError, ScreenshotFormat, ScreenshotOptions};
+
+mod support;
+
+fn build_target(display: &glium::Display) -> glium::Texture2d {
+    let texture = glium::Texture2d::empty(display, 64, 32).unwrap();
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    texture.as_surface().clear(Some(&glium::Rect { left: 0, bottom: 16, width: 64, height: 16 }),
+                               Some((0.0, 1.0, 0.0, 1.0)), false, None, None);
+    texture
+}
+
+#[cfg(feature = "screenshot_png")]
+#[test]
+fn save_png() {
+    let display = support::build_display();
+    let texture = build_target(&display);
+
+    let path = std::env::temp_dir().join("glium-capture-test.png");
+    capture::save_screenshot(&display, &texture.as_surface(), &path,
+                             &Default::default()).unwrap();
+
+    let image = image::open(&path).unwrap().to_rgba();
+    assert_eq!(image.dimensions(), (64, 32));
+    // the top of the surface is the first row of the file
+    assert_eq!(image.get_pixel(0, 0).data, [0, 255, 0, 255]);
+    assert_eq!(image.get_pixel(0, 31).data, [255, 0, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[cfg(not(feature = "screenshot_exr"))]
+#[test]
+fn exr_not_supported() {
+    let display = support::build_display();
+    let texture = build_target(&display);
+
+    let options = ScreenshotOptions { format: ScreenshotFormat::Exr, .. Default::default() };
+    let path = std::env::temp_dir().join("glium-capture-test.exr");
+    match capture::save_screenshot(&display, &texture.as_surface(), &path, &options) {
+        Err(ScreenshotError::FormatNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
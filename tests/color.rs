@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate glium;
+
+use glium::color::{self, Linear, Srgb};
+
+mod support;
+
+#[test]
+fn srgb_linear_roundtrip() {
+    for value in 0 .. 256u32 {
+        let value = value as u8;
+        assert_eq!(color::linear_to_srgb_u8(color::srgb_u8_to_linear(value)), value);
+    }
+
+    assert_eq!(color::srgb_to_linear(0.0), 0.0);
+    assert!((color::srgb_to_linear(1.0) - 1.0).abs() < 0.0001);
+    assert!((color::srgb_u8_to_linear(128) - 0.2158).abs() < 0.001);
+    assert_eq!(color::linear_to_srgb_u8(2.0), 255);
+}
+
+#[test]
+fn srgba8_alpha_unchanged() {
+    let linear = color::srgba8_to_linear(&[(128, 128, 128, 128)]);
+    assert!((linear[0].3 - 128.0 / 255.0).abs() < 0.0001);
+    assert_eq!(color::linear_to_srgba8(&linear), vec![(128, 128, 128, 128)]);
+}
+
+#[test]
+fn tagged_texture_creation() {
+    let display = support::build_display();
+
+    let srgb: glium::texture::SrgbTexture2d =
+                color::texture_2d(&display, vec![vec![Srgb((255u8, 0u8, 0u8, 255u8)); 4]; 4])
+                    .unwrap();
+    assert_eq!(srgb.get_width(), 4);
+
+    let linear: glium::texture::Texture2d =
+                color::texture_2d(&display, vec![vec![Linear((128u8, 128u8, 255u8)); 4]; 4])
+                    .unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = linear.read();
+    assert_eq!(data[0][0], (128, 128, 255, 255));
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::culling::{CulledObject, GpuCuller};
+use glium::index::DrawCommandIndices;
+
+mod support;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+// draws the four indices of `build_fullscreen_red_pipeline`
+fn fullscreen_command() -> DrawCommandIndices {
+    DrawCommandIndices {
+        count: 4,
+        instance_count: 1,
+        first_index: 0,
+        base_vertex: 0,
+        base_instance: 0,
+    }
+}
+
+#[test]
+fn cull_and_draw() {
+    let display = support::build_display();
+
+    let mut culler = match GpuCuller::new(&display, 4) {
+        Ok(c) => c,
+        Err(_) => return
+    };
+
+    culler.set_objects(&[
+        CulledObject::new([0.0, 0.0, 0.0], 0.5, fullscreen_command()),
+        CulledObject::new([5.0, 0.0, 0.0], 0.5, fullscreen_command()),
+        CulledObject::new([0.0, -5.0, 0.0], 0.5, fullscreen_command()),
+    ]);
+    culler.cull(IDENTITY);
+    assert_eq!(culler.read_visible_count(), 1);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    culler.draw(&mut texture.as_surface(), &vb, &ib, &program,
+                &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn everything_culled() {
+    let display = support::build_display();
+
+    let mut culler = match GpuCuller::new(&display, 128) {
+        Ok(c) => c,
+        Err(_) => return
+    };
+
+    let objects = (0 .. 100).map(|i| {
+        CulledObject::new([3.0 + i as f32, 0.0, 0.0], 1.0, fullscreen_command())
+    }).collect::<Vec<_>>();
+    culler.set_objects(&objects);
+    culler.cull(IDENTITY);
+    assert_eq!(culler.read_visible_count(), 0);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    culler.draw(&mut texture.as_surface(), &vb, &ib, &program,
+                &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
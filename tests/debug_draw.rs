@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::debug_draw::DebugDraw;
+
+mod support;
+
+#[test]
+fn draw_text() {
+    let display = support::build_display();
+
+    let mut debug_draw = match DebugDraw::new(&display) {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // a full block is easier to find than a character
+    debug_draw.set_text_scale(4.0);
+    debug_draw.text([0.0, 0.0], "#", [1.0, 0.0, 0.0, 1.0]);
+    debug_draw.draw(&mut texture.as_surface(), [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                                                [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]])
+              .unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    let height = data.len();
+
+    // the text starts at the top of the texture
+    assert!(data[height - 32 .. height].iter().any(|row| row[.. 24].iter().any(|p| p.0 == 255)));
+    assert!(data[.. height - 32].iter().all(|row| row.iter().all(|p| p.0 == 0)));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_lines_and_boxes() {
+    let display = support::build_display();
+
+    let mut debug_draw = match DebugDraw::new(&display) {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+
+    debug_draw.line([-1.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0, 1.0]);
+    debug_draw.wire_box([-0.5, -0.5, -0.5], [0.5, 0.5, 0.5], [0.0, 1.0, 0.0, 1.0]);
+    debug_draw.draw(&mut texture.as_surface(), identity).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data.iter().any(|row| row.iter().any(|p| p.1 == 255)));
+
+    // the primitives are removed after being drawn
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    debug_draw.draw(&mut texture.as_surface(), identity).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data.iter().all(|row| row.iter().all(|p| p.1 == 0)));
+
+    display.assert_no_error(None);
+}
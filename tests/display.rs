@@ -3,6 +3,9 @@ extern crate glium;
 
 use glium::Surface;
 
+use std::os::raw::c_void;
+use std::ptr;
+
 mod support;
 
 #[test]
@@ -149,3 +152,349 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+fn vertex_array_objects_cache() {
+    let display = support::build_display();
+
+    display.set_vertex_array_objects_cache_capacity(2);
+    assert_eq!(display.get_vertex_array_objects_cache_statistics().capacity, 2);
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the pipelines are kept alive so that their VAOs are only destroyed by the eviction policy
+    let mut pipelines = Vec::new();
+
+    for _ in 0 .. 4 {
+        // building a new pipeline each time so that a new VAO is required
+        pipelines.push(support::build_fullscreen_red_pipeline(&display));
+        let &(ref vb, ref ib, ref program) = pipelines.last().unwrap();
+
+        for _ in 0 .. 2 {
+            texture.as_surface().draw(vb, ib, program, &glium::uniforms::EmptyUniforms,
+                                      &Default::default()).unwrap();
+        }
+    }
+
+    let stats = display.get_vertex_array_objects_cache_statistics();
+    assert!(stats.entries <= 2);
+
+    // the backend may not support VAOs
+    if stats.misses != 0 {
+        assert_eq!(stats.misses, 4);
+        assert_eq!(stats.hits, 4);
+    }
+
+    display.purge_caches();
+    assert_eq!(display.get_vertex_array_objects_cache_statistics().entries, 0);
+
+    display.assert_no_error(None);
+}
+
+#[cfg(feature = "renderdoc")]
+#[test]
+fn renderdoc_capture() {
+    let display = support::build_display();
+
+    // the closure must be called whether or not RenderDoc is available
+    let value = display.capture(|| {
+        let mut target = display.draw();
+        target.clear_color(0.0, 0.0, 0.0, 0.0);
+        target.finish().unwrap();
+        5
+    });
+    assert_eq!(value, 5);
+
+    display.trigger_capture();
+    display.assert_no_error(None);
+}
+
+#[test]
+fn debug_dump() {
+    use glium::debug::ObjectKind;
+
+    let display = support::build_display();
+
+    let is_reported = |kind, id| {
+        display.debug_dump().objects.iter().any(|o| o.kind == kind && o.id == id)
+    };
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1.0f32, 2.0, 3.0, 4.0][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            glium::buffer::BufferMode::Default).unwrap();
+    let texture = glium::Texture2d::empty(&display, 16, 16).unwrap();
+
+    let buffer_id = glium::GlObject::get_id(&buffer);
+    let texture_id = glium::GlObject::get_id(&texture);
+
+    {
+        let dump = display.debug_dump();
+
+        let report = dump.objects.iter()
+                                 .find(|o| o.kind == ObjectKind::Buffer && o.id == buffer_id)
+                                 .unwrap();
+        assert_eq!(report.size, Some(16));
+
+        let report = dump.objects.iter()
+                                 .find(|o| o.kind == ObjectKind::Texture && o.id == texture_id)
+                                 .unwrap();
+        assert_eq!(report.dimensions,
+                   Some(glium::texture::Dimensions::Texture2d { width: 16, height: 16 }));
+    }
+
+    drop(buffer);
+    assert!(!is_reported(ObjectKind::Buffer, buffer_id));
+    assert!(is_reported(ObjectKind::Texture, texture_id));
+
+    drop(texture);
+    assert!(!is_reported(ObjectKind::Texture, texture_id));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn default_framebuffer_buffers() {
+    use glium::framebuffer::{BufferSelectionError, DefaultFramebufferAttachment};
+
+    let display = support::build_display();
+
+    match display.set_default_framebuffer_draw_buffer(None) {
+        Ok(()) => (),
+        Err(BufferSelectionError::NotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    // the color output is discarded
+    let mut frame = display.draw();
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+    frame.finish().unwrap();
+
+    display.set_default_framebuffer_draw_buffer(Some(DefaultFramebufferAttachment::Back))
+           .unwrap();
+    display.set_default_framebuffer_read_buffer(Some(DefaultFramebufferAttachment::Back))
+           .unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(not(feature = "test_headless"))]
+fn shared_display_objects() {
+    let display = support::build_display();
+    let shared = support::build_shared_display(&display);
+    assert!(display.shares_objects_with(&shared));
+    assert!(!display.shares_objects_with(&support::build_display()));
+
+    // the buffers and the program are created with the first display, but the VAO and the FBO
+    // used when drawing are created by the second one
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&shared);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // destroying the buffers must purge the VAO of the second display
+    drop(vb);
+    drop(ib);
+    drop(program);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+    assert_eq!(texture.read::<Vec<Vec<(u8, u8, u8, u8)>>>()[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+    shared.assert_no_error(None);
+}
+
+#[test]
+fn set_swap_interval() {
+    use glium::pacing::{SwapInterval, SwapIntervalError};
+
+    let display = support::build_display();
+
+    match display.set_swap_interval(SwapInterval::DontWait) {
+        Ok(()) => (),
+        Err(SwapIntervalError::NotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    display.set_swap_interval(SwapInterval::Wait(1)).unwrap();
+    display.finish();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_limiter() {
+    use glium::pacing::FrameLimiter;
+    use std::time::{Duration, Instant};
+
+    let mut limiter = FrameLimiter::new(100);
+    assert_eq!(limiter.get_frame_duration(), Duration::from_millis(10));
+
+    let start = Instant::now();
+    for _ in 0 .. 6 {
+        limiter.wait();
+    }
+
+    // the first call doesn't wait
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn finish_with_damage() {
+    let display = support::build_display();
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.finish_with_damage(&[
+        glium::Rect { left: 0, bottom: 0, width: 16, height: 16 },
+        glium::Rect { left: 32, bottom: 8, width: 4, height: 4 },
+    ]).unwrap();
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.set_finish_with_damage(&[]).unwrap();
+    assert!(frame.set_finish_with_damage(&[]).is_err());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn max_frames_in_flight() {
+    let display = support::build_display();
+    assert_eq!(display.get_max_frames_in_flight(), None);
+
+    if display.set_max_frames_in_flight(Some(1)).is_err() {
+        return;
+    }
+
+    assert_eq!(display.get_max_frames_in_flight(), Some(1));
+
+    for _ in 0 .. 3 {
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 1.0);
+        frame.finish().unwrap();
+    }
+
+    display.set_max_frames_in_flight(None).unwrap();
+    display.assert_no_error(None);
+}
+
+#[test]
+fn default_framebuffer_srgb_override() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let detected = display.get_capabilities().srgb;
+    assert_eq!(display.is_default_framebuffer_srgb(), detected);
+
+    display.set_default_framebuffer_srgb(Some(!detected));
+    assert_eq!(display.is_default_framebuffer_srgb(), !detected);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+    frame.finish().unwrap();
+
+    display.set_default_framebuffer_srgb(None);
+    assert_eq!(display.is_default_framebuffer_srgb(), detected);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn default_framebuffer_color_bits() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let capabilities = display.get_capabilities();
+
+    // the tests don't run with a floating-point framebuffer, but the values must be consistent
+    if capabilities.float_color_buffer {
+        assert!(capabilities.color_bits[0] >= 16);
+    } else {
+        assert!(capabilities.color_bits.iter().all(|&b| b <= 16));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn error_check_policy() {
+    use glium::debug::ErrorCheckPolicy;
+
+    let display = support::build_display();
+    assert_eq!(display.get_error_check_policy(), ErrorCheckPolicy::Never);
+
+    display.set_error_check_policy(ErrorCheckPolicy::Panic);
+    assert_eq!(display.get_error_check_policy(), ErrorCheckPolicy::Panic);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+    frame.finish().unwrap();
+
+    assert_eq!(display.get_swallowed_errors_count(), 0);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn default_framebuffer_samples() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    // the test displays don't request multisampling
+    assert_eq!(display.get_capabilities().samples, 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_offset_limits() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    // the specification requires at least the [-8, 7] range
+    if let Some((min, max)) = display.get_capabilities().program_texel_offset {
+        assert!(min <= -8);
+        assert!(max >= 7);
+    }
+
+    if let Some(gather) = display.get_capabilities().texture_gather {
+        assert!(gather.offset.0 <= -8);
+        assert!(gather.offset.1 >= 7);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn backend_get_integer_and_float() {
+    extern "system" fn get_integerv(_: u32, value: *mut i32) { unsafe { *value = 42 } }
+    extern "system" fn get_floatv(_: u32, value: *mut f32) { unsafe { *value = 1.5 } }
+
+    let loader = |symbol: &str| match symbol {
+        "glGetIntegerv" => get_integerv as *const c_void,
+        "glGetFloatv" => get_floatv as *const c_void,
+        _ => ptr::null(),
+    };
+
+    unsafe {
+        assert_eq!(glium::backend::get_integer(&loader, 0x0D33), Some(42));
+        assert_eq!(glium::backend::get_float(&loader, 0x846E), Some(1.5));
+        assert_eq!(glium::backend::get_integer(|_| ptr::null(), 0x0D33), None);
+        assert_eq!(glium::backend::get_float(|_| ptr::null(), 0x846E), None);
+    }
+}
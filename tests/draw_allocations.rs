@@ -0,0 +1,87 @@
+#[macro_use]
+extern crate glium;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use glium::Surface;
+
+mod support;
+
+/// Allocator that counts the number of allocations.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// this file must only contain this test, as other tests running in parallel would allocate
+#[test]
+fn draw_doesnt_allocate() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    let uniforms = uniform! { unused: [1.0, 0.0, 0.0, 1.0f32] };
+    let params = Default::default();
+
+    // program whose uniforms are checked at each draw
+    let strict_program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+    strict_program.set_strict_uniforms(true);
+    let strict_uniforms = uniform! { color: [1.0, 0.0, 0.0, 1.0f32] };
+
+    // the attachments of this framebuffer are built at each draw
+    let second_texture = support::build_renderable_texture(&display);
+    let mut multi_output = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                                                            [(0u32, &second_texture)].iter()
+                                                                                     .cloned())
+                                                                                     .unwrap();
+
+    // the first draw calls fill the caches of the context
+    framebuffer.draw(&vb, &ib, &program, &uniforms, &params).unwrap();
+    multi_output.draw(&vb, &ib, &strict_program, &strict_uniforms, &params).unwrap();
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0 .. 16 {
+        framebuffer.draw(&vb, &ib, &program, &uniforms, &params).unwrap();
+        multi_output.draw(&vb, &ib, &strict_program, &strict_uniforms, &params).unwrap();
+    }
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert_eq!(after - before, 0);
+
+    display.assert_no_error(None);
+}
@@ -1,5 +1,7 @@
 #[macro_use]
 extern crate glium;
+#[cfg(feature = "serialize")]
+extern crate serde_json;
 
 use glium::Surface;
 use glium::index::PrimitiveType;
@@ -217,6 +219,104 @@ fn viewport() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn float_viewport_relative() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        float_viewport: Some(glium::FloatViewport::Relative {
+            left: 0.0,
+            bottom: 0.0,
+            width: 0.5,
+            height: 1.0,
+        }),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    for &(width, height) in [(64, 32), (128, 16)].iter() {
+        let texture = glium::Texture2d::empty(&display, width, height).unwrap();
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                  &params).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        for row in data.iter() {
+            for (x, pixel) in row.iter().enumerate() {
+                if x < width as usize / 2 {
+                    assert_eq!(pixel, &(255, 0, 0, 255));
+                } else {
+                    assert_eq!(pixel, &(0, 0, 0, 0));
+                }
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn float_viewport_overrides_viewport() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        viewport: Some(glium::Rect { left: 0, bottom: 0, width: 1, height: 1 }),
+        float_viewport: Some(glium::FloatViewport::Pixels {
+            left: 2.0,
+            bottom: 2.0,
+            width: 2.0,
+            height: 2.0,
+        }),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = glium::Texture2d::empty(&display, 8, 8).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for (y, row) in data.iter().enumerate() {
+        for (x, pixel) in row.iter().enumerate() {
+            if x >= 2 && x < 4 && y >= 2 && y < 4 {
+                assert_eq!(pixel, &(255, 0, 0, 255));
+            } else {
+                assert_eq!(pixel, &(0, 0, 0, 0));
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn float_viewport_too_large() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        float_viewport: Some(glium::FloatViewport::Pixels {
+            left: 0.0,
+            bottom: 0.0,
+            width: 1.0e10,
+            height: 1.0e10,
+        }),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut frame = display.draw();
+    match frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::ViewportTooLarge) => (),
+        a => panic!("{:?}", a)
+    };
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn dont_draw_primitives() {
     let display = support::build_display();
@@ -771,6 +871,35 @@ blending_test!(one_plus_one, glium::BlendingFunction::Addition {
                },
                (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (255, 255, 255, 255));
 
+#[test]
+fn blend_constant() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: glium::BlendingFunction::Addition {
+                source: glium::LinearBlendingFactor::ConstantColor,
+                destination: glium::LinearBlendingFactor::Zero,
+            },
+            alpha: glium::BlendingFunction::AlwaysReplace,
+            constant_value: (1.0, 1.0, 1.0, 1.0),
+        },
+        blend_constant: Some((0.0, 0.0, 0.0, 0.0)),
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 
 #[test]
 fn provoking_vertex_last() {
@@ -1407,4 +1536,131 @@ fn primitive_restart_index() {
     }
 
     display.assert_no_error(None);
-}
\ No newline at end of file
+}
+#[test]
+fn default_tessellation_levels() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    if display.get_capabilities().max_patch_vertices.is_none() {
+        return;
+    }
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vertex_buffer = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [3.0, -1.0] },
+        Vertex { position: [-1.0, 3.0] },
+    ]).unwrap();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        vertex_shader: "
+            #version 400
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: Some("
+            #version 400
+
+            layout(triangles, equal_spacing) in;
+
+            void main() {
+                gl_Position = gl_TessCoord.x * gl_in[0].gl_Position +
+                              gl_TessCoord.y * gl_in[1].gl_Position +
+                              gl_TessCoord.z * gl_in[2].gl_Position;
+            }
+        "),
+        geometry_shader: None,
+        fragment_shader: "
+            #version 400
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        transform_feedback_varyings: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::CompilationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        tessellation_levels: Some(glium::draw_parameters::TessellationLevels {
+            outer: [4.0, 4.0, 4.0, 1.0],
+            inner: [4.0, 1.0],
+        }),
+        .. Default::default()
+    };
+
+    texture.as_surface().draw(&vertex_buffer,
+                              &glium::index::NoIndices(PrimitiveType::Patches {
+                                  vertices_per_patch: 3
+                              }),
+                              &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn serialize_parameters() {
+    let blend = glium::Blend::alpha_blending();
+    let json = serde_json::to_string(&blend).unwrap();
+    assert_eq!(serde_json::from_str::<glium::Blend>(&json).unwrap(), blend);
+
+    let depth = glium::Depth {
+        test: glium::DepthTest::IfLess,
+        write: true,
+        .. Default::default()
+    };
+    let json = serde_json::to_string(&depth).unwrap();
+    let deserialized: glium::Depth = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.test, glium::DepthTest::IfLess);
+    assert!(deserialized.write);
+
+    let behavior = glium::uniforms::SamplerBehavior {
+        minify_filter: glium::uniforms::MinifySamplerFilter::Nearest,
+        .. Default::default()
+    };
+    let json = serde_json::to_string(&behavior).unwrap();
+    assert_eq!(serde_json::from_str::<glium::uniforms::SamplerBehavior>(&json).unwrap(),
+               behavior);
+
+    let format = glium::texture::UncompressedFloatFormat::F16F16F16F16;
+    let json = serde_json::to_string(&format).unwrap();
+    assert_eq!(serde_json::from_str::<glium::texture::UncompressedFloatFormat>(&json).unwrap(),
+               format);
+}
@@ -254,6 +254,245 @@ fn multioutput() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn multioutput_color_masks() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            out vec4 color1;
+            out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 1.0, 1.0, 1.0);
+                color2 = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    color1.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    color2.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+
+    let masks = [(true, true, true, true), (false, false, false, false)];
+    let params = glium::DrawParameters {
+        color_masks: Some(&masks),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Ok(_) => (),
+        Err(glium::DrawError::ColorMasksNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    assert_eq!(read_back1[0][0], (255, 255, 255, 255));
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back2[0][0], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multioutput_explicit_locations() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::new(&display,
+        glium::program::ProgramCreationInput::SourceCode {
+            vertex_shader: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment_shader: "
+                #version 130
+
+                out vec4 color1;
+                out vec4 color2;
+
+                void main() {
+                    color1 = vec4(1.0, 1.0, 1.0, 1.0);
+                    color2 = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+            geometry_shader: None,
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            transform_feedback_varyings: None,
+            fragment_output_locations: vec![("color1".to_owned(), 1), ("color2".to_owned(), 0)],
+            attribute_locations: Vec::new(),
+            defines: Vec::new(),
+            adapt_glsl_version: false,
+            outputs_srgb: false,
+            uses_point_size: false,
+        })
+    {
+        Err(glium::CompilationError(_)) => return,
+        Err(glium::program::ProgramCreationError::FragmentOutputLocationNotSupported) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    assert_eq!(program.get_frag_data_location("color1"), Some(1));
+    assert_eq!(program.get_frag_data_location("color2"), Some(0));
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    color1.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    color2.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    // `color1` is written to location 1
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [(1u32, &color1), (0u32, &color2)].iter().cloned()).unwrap();
+
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    assert_eq!(read_back1[64][64], (255, 255, 255, 255));
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back2[64][64], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multioutput_mismatch() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            out vec4 color1;
+
+            void main() {
+                color1 = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = glium::Texture2d::empty(&display, 128, 128).unwrap();
+    let color2 = glium::Texture2d::empty(&display, 128, 128).unwrap();
+
+    // an output that doesn't exist
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("missing", &color2)].iter().cloned()).unwrap();
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                           &Default::default())
+    {
+        Err(glium::DrawError::FragmentOutputNotFound { ref name }) if name == "missing" => (),
+        e => panic!("{:?}", e)
+    }
+
+    // two attachments on the same location
+    let location = program.get_frag_data_location("color1").unwrap();
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               vec![(glium::framebuffer::FragmentOutputBinding::Name("color1"), &color1),
+                                    (glium::framebuffer::FragmentOutputBinding::Location(location),
+                                     &color2)]).unwrap();
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                           &Default::default())
+    {
+        Err(glium::DrawError::FragmentOutputLocationConflict { location: l }) if l == location => (),
+        e => panic!("{:?}", e)
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn implicit_multisampling() {
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+
+    if !glium::framebuffer::is_implicit_multisampling_supported(&display) {
+        match glium::framebuffer::SimpleFrameBuffer::with_implicit_multisampling(&display,
+                                                                                  &texture, 4)
+        {
+            Err(glium::framebuffer::ValidationError::ImplicitMultisamplingNotSupported) => (),
+            _ => panic!()
+        };
+        return;
+    }
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_implicit_multisampling(
+                                                                  &display, &texture, 4).unwrap();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[64][64], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn array_level() {
     let display = support::build_display();
@@ -447,3 +686,527 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn offscreen_target_resolve_to_texture() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut target = glium::framebuffer::OffscreenTarget::new(&display,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    None, 128, 128, None).unwrap();
+    assert_eq!(target.get_dimensions(), (128, 128));
+
+    target.clear_color(0.0, 0.0, 0.0, 0.0);
+    target.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                              glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                              glium::texture::MipmapsOption::NoMipmap,
+                                              128, 128).unwrap();
+    target.resolve_to_texture(&texture).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[127][127], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn offscreen_target_multisample_depth() {
+    use glium::framebuffer::{OffscreenTarget, OffscreenTargetCreationError};
+    use glium::framebuffer::RenderBufferCreationError;
+
+    let display = support::build_display();
+
+    let mut target = match OffscreenTarget::new(&display,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    Some(glium::texture::DepthFormat::I24), 128, 128, Some(4))
+    {
+        Ok(t) => t,
+        Err(OffscreenTargetCreationError::RenderBufferCreationError(
+            RenderBufferCreationError::MultisamplingNotSupported)) => return,
+        Err(e) => panic!("{}", e),
+    };
+
+    assert_eq!(target.get_samples(), Some(4));
+    assert!(target.has_depth_buffer());
+    target.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+
+    display.assert_no_error(None);
+}
+
+struct CountingTarget<'a> {
+    inner: glium::framebuffer::SimpleFrameBuffer<'a>,
+    draw_calls: usize,
+}
+
+impl<'a> glium::Surface for CountingTarget<'a> {
+    fn clear(&mut self, rect: Option<&glium::Rect>, color: Option<(f32, f32, f32, f32)>,
+             color_srgb: bool, depth: Option<f32>, stencil: Option<i32>)
+    {
+        self.inner.clear(rect, color, color_srgb, depth, stencil)
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.inner.get_dimensions()
+    }
+
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.inner.get_depth_buffer_bits()
+    }
+
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        self.inner.get_stencil_buffer_bits()
+    }
+
+    fn draw<'b, 'c, V, I, U>(&mut self, vertices: V, indices: I, program: &glium::Program,
+                             uniforms: &U, draw_parameters: &glium::DrawParameters)
+                             -> Result<(), glium::DrawError>
+        where V: glium::vertex::MultiVerticesSource<'c>,
+              I: Into<glium::index::IndicesSource<'b>>, U: glium::uniforms::Uniforms
+    {
+        self.draw_calls += 1;
+        self.inner.draw(vertices, indices, program, uniforms, draw_parameters)
+    }
+
+    fn blit_from_frame(&self, source_rect: &glium::Rect, target_rect: &glium::BlitTarget,
+                       filter: glium::uniforms::MagnifySamplerFilter)
+    {
+        self.inner.blit_from_frame(source_rect, target_rect, filter)
+    }
+
+    fn blit_from_simple_framebuffer(&self, source: &glium::framebuffer::SimpleFrameBuffer,
+                                    source_rect: &glium::Rect, target_rect: &glium::BlitTarget,
+                                    filter: glium::uniforms::MagnifySamplerFilter)
+    {
+        self.inner.blit_from_simple_framebuffer(source, source_rect, target_rect, filter)
+    }
+
+    fn blit_from_multioutput_framebuffer(&self,
+                                         source: &glium::framebuffer::MultiOutputFrameBuffer,
+                                         source_rect: &glium::Rect,
+                                         target_rect: &glium::BlitTarget,
+                                         filter: glium::uniforms::MagnifySamplerFilter)
+    {
+        self.inner.blit_from_multioutput_framebuffer(source, source_rect, target_rect, filter)
+    }
+
+    fn blit_color<S>(&self, source_rect: &glium::Rect, target: &S,
+                     target_rect: &glium::BlitTarget,
+                     filter: glium::uniforms::MagnifySamplerFilter) where S: glium::Surface
+    {
+        self.inner.blit_color(source_rect, target, target_rect, filter)
+    }
+}
+
+impl<'a> glium::FboAttachments for CountingTarget<'a> {
+    fn get_attachments(&self) -> Option<&glium::framebuffer::ValidatedAttachments> {
+        glium::FboAttachments::get_attachments(&self.inner)
+    }
+}
+
+#[test]
+fn custom_surface() {
+    use glium::FboAttachments;
+
+    fn draw_red<S>(target: &mut S, vertex_buffer: &glium::vertex::VertexBufferAny,
+                   index_buffer: &glium::index::IndexBufferAny, program: &glium::Program)
+        where S: Surface
+    {
+        target.clear_color(0.0, 0.0, 0.0, 0.0);
+        target.draw(vertex_buffer, index_buffer, program, &uniform!{},
+                    &Default::default()).unwrap();
+    }
+
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let mut target = CountingTarget {
+        inner: glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap(),
+        draw_calls: 0,
+    };
+
+    draw_red(&mut target, &vertex_buffer, &index_buffer, &program);
+    assert_eq!(target.draw_calls, 1);
+    assert!(target.get_attachments().is_some());
+    assert!(program.validate(&uniform!{}, &target).is_valid());
+
+    // blitting from the custom surface to a regular one
+    let copy = support::build_renderable_texture(&display);
+    copy.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    target.fill(&copy.as_surface(), glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = copy.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn ping_pong_targets_swap() {
+    let display = support::build_display();
+
+    let mut targets = glium::framebuffer::PingPongTargets::new(&display,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    64, 64).unwrap();
+    assert_eq!(targets.dimensions(), (64, 64));
+
+    targets.write_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    targets.swap();
+    targets.write_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let read: Vec<Vec<(u8, u8, u8, u8)>> = targets.read().read();
+    assert_eq!(read[0][0], (255, 0, 0, 255));
+    let written: Vec<Vec<(u8, u8, u8, u8)>> = targets.write().read();
+    assert_eq!(written[0][0], (0, 255, 0, 255));
+
+    targets.swap();
+    let read: Vec<Vec<(u8, u8, u8, u8)>> = targets.read().read();
+    assert_eq!(read[0][0], (0, 255, 0, 255));
+
+    targets.resize(32, 16).unwrap();
+    assert_eq!(targets.dimensions(), (32, 16));
+    assert_eq!(targets.write().dimensions(), (32, 16));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn target_pool_reuse() {
+    use glium::GlObject;
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+    let mut pool = glium::framebuffer::TargetPool::new(&display);
+
+    let first_id = {
+        let first = pool.acquire(UncompressedFloatFormat::U8U8U8U8, 1.0).unwrap();
+        let second = pool.acquire(UncompressedFloatFormat::U8U8U8U8, 1.0).unwrap();
+        assert!(first.get_id() != second.get_id());
+        assert_eq!(first.dimensions(), pool.target_dimensions(1.0));
+
+        first.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = first.read();
+        assert_eq!(data[0][0], (255, 0, 0, 255));
+
+        first.get_id()
+    };
+    assert_eq!(pool.len(), 2);
+
+    {
+        let target = pool.acquire(UncompressedFloatFormat::U8U8U8U8, 1.0).unwrap();
+        assert_eq!(target.get_id(), first_id);
+    }
+    assert_eq!(pool.len(), 2);
+
+    {
+        let half = pool.acquire(UncompressedFloatFormat::U8U8U8U8, 0.5).unwrap();
+        assert_eq!(half.dimensions(), pool.target_dimensions(0.5));
+    }
+    assert_eq!(pool.len(), 3);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn target_pool_end_frame() {
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+    let mut pool = glium::framebuffer::TargetPool::new(&display);
+
+    let kept = pool.acquire(UncompressedFloatFormat::U8U8U8U8, 1.0).unwrap();
+    drop(pool.acquire(UncompressedFloatFormat::U8U8U8U8, 0.25).unwrap());
+    assert_eq!(pool.len(), 2);
+
+    // both textures have been used during this frame
+    pool.end_frame();
+    assert_eq!(pool.len(), 2);
+
+    // the released texture hasn't been used during the last frame, but the other one is in use
+    pool.end_frame();
+    assert_eq!(pool.len(), 1);
+
+    drop(kept);
+    pool.clear();
+    assert_eq!(pool.len(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_slice() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture3d::empty(&display, 4, 4, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    for z in 0 .. 4 {
+        let mut slice = glium::framebuffer::SimpleFrameBuffer::with_layer(&display, &texture,
+                                                                          0, z).unwrap();
+        assert_eq!(slice.get_dimensions(), (4, 4));
+        slice.clear_color(z as f32 / 3.0, 0.0, 0.0, 1.0);
+    }
+
+    // copying the last slice to a 2D texture in order to read it
+    let slice = glium::framebuffer::SimpleFrameBuffer::with_layer(&display, &texture, 0, 3)
+                                                                                .unwrap();
+    let output = glium::texture::Texture2d::empty(&display, 4, 4).unwrap();
+    slice.blit_color(&glium::Rect { left: 0, bottom: 0, width: 4, height: 4 },
+                     &output.as_surface(),
+                     &glium::BlitTarget { left: 0, bottom: 0, width: 4, height: 4 },
+                     glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    match glium::framebuffer::SimpleFrameBuffer::with_layer(&display, &texture, 0, 4) {
+        Err(glium::framebuffer::ValidationError::LayerOutOfRange { layer: 4, layers: 4 }) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_layered() {
+    let display = support::build_display();
+
+    if !glium::framebuffer::is_layered_supported(&display) {
+        return;
+    }
+
+    let texture = match glium::texture::Texture3d::empty(&display, 4, 4, 8) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::layered(&display, &texture, 0)
+                                                                                    .unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (4, 4));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_texture_2d_array_layer() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::DepthTexture2dArray::empty(&display, 128, 128, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    for layer in 0 .. 4 {
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_layer(&display,
+                                                                                &texture, 0,
+                                                                                layer).unwrap();
+        assert_eq!(framebuffer.get_dimensions(), (128, 128));
+        assert!(framebuffer.get_depth_buffer_bits().is_some());
+        framebuffer.clear_depth(layer as f32 / 4.0);
+    }
+
+    let layer = texture.main_level().layer(2).unwrap();
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::depth_only(&display, layer).unwrap();
+    assert!(framebuffer.get_depth_buffer_bits().is_some());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_cubemap_face() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::DepthCubemap::empty(&display, 128) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    for &face in &[glium::texture::CubeLayer::PositiveX, glium::texture::CubeLayer::NegativeX,
+                   glium::texture::CubeLayer::PositiveY, glium::texture::CubeLayer::NegativeY,
+                   glium::texture::CubeLayer::PositiveZ, glium::texture::CubeLayer::NegativeZ]
+    {
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_cubemap_face(&display,
+                                                                    &texture, 0, 0, face).unwrap();
+        assert_eq!(framebuffer.get_dimensions(), (128, 128));
+        assert!(framebuffer.get_depth_buffer_bits().is_some());
+        framebuffer.clear_depth(1.0);
+    }
+
+    match glium::framebuffer::SimpleFrameBuffer::with_cubemap_face(&display, &texture, 0, 1,
+                                                glium::texture::CubeLayer::PositiveX) {
+        Err(glium::framebuffer::ValidationError::LayerOutOfRange { layer: 1, layers: 1 }) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn render_to_single_and_dual_channel_textures() {
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    for &format in &[UncompressedFloatFormat::U8, UncompressedFloatFormat::U8U8,
+                     UncompressedFloatFormat::F16, UncompressedFloatFormat::F16F16,
+                     UncompressedFloatFormat::F32, UncompressedFloatFormat::F32F32]
+    {
+        if !format.is_color_renderable(&display) {
+            continue;
+        }
+
+        let texture = glium::texture::Texture2d::empty_with_format(&display, format,
+                                            glium::texture::MipmapsOption::NoMipmap, 16, 16).unwrap();
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+        framebuffer.clear_color(0.0, 0.0, 0.0, 0.0);
+        framebuffer.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                         &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0].0, 255);
+        assert_eq!(data[0][0].1, 0);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn unsigned_render_buffer() {
+    use glium::texture::UncompressedUintFormat;
+
+    let display = support::build_display();
+
+    if !UncompressedUintFormat::U32.is_color_renderable(&display) {
+        return;
+    }
+
+    let (vertex_buffer, index_buffer, _) = support::build_fullscreen_red_pipeline(&display);
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            out uint color;
+
+            void main() {
+                color = 5u;
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let buffer = glium::framebuffer::UnsignedRenderBuffer::new(&display,
+                                                               UncompressedUintFormat::U32,
+                                                               16, 16).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &buffer).unwrap();
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stencil_only_texture() {
+    use glium::texture::{MipmapsOption, StencilFormat, StencilTexture2d};
+
+    let display = support::build_display();
+    let (vertex_buffer, index_buffer, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let stencil = match StencilTexture2d::empty_with_format(&display, StencilFormat::I8,
+                                                            MipmapsOption::NoMipmap, 16, 16)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::stencil_only(&display,
+                                                                            &stencil).unwrap();
+    framebuffer.clear_stencil(0);
+
+    let params = glium::DrawParameters {
+        stencil: glium::draw_parameters::Stencil {
+            reference_value_clockwise: 7,
+            depth_pass_operation_clockwise: glium::StencilOperation::Replace,
+            reference_value_counter_clockwise: 7,
+            depth_pass_operation_counter_clockwise: glium::StencilOperation::Replace,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                     &params).unwrap();
+
+    let data: Vec<Vec<u8>> = stencil.read();
+    assert_eq!(data[0][0], 7);
+    assert_eq!(data[15][15], 7);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stencil_only_render_buffer() {
+    use glium::texture::StencilFormat;
+
+    let display = support::build_display();
+
+    if !StencilFormat::I8.is_supported_for_renderbuffers(&display) {
+        return;
+    }
+
+    let color = support::build_renderable_texture(&display);
+    let stencil = glium::framebuffer::StencilRenderBuffer::new(&display, StencilFormat::I8,
+                                                               1024, 1024).unwrap();
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_stencil_buffer(&display,
+                                                                &color, &stencil).unwrap();
+    framebuffer.clear_color_and_stencil((0.0, 0.0, 0.0, 0.0), 3);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn offscreen_target_resolve_dimensions_mismatch() {
+    let display = support::build_display();
+
+    let target = match glium::framebuffer::OffscreenTarget::new(&display,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    None, 128, 128, Some(4))
+    {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                              glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                              glium::texture::MipmapsOption::NoMipmap,
+                                              64, 64).unwrap();
+
+    match target.resolve_to_texture(&texture) {
+        Err(glium::framebuffer::ResolveError::DimensionsMismatch { target, texture }) => {
+            assert_eq!(target, (128, 128));
+            assert_eq!(texture, (64, 64));
+        },
+        _ => panic!(),
+    }
+
+    display.assert_no_error(None);
+}
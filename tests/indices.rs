@@ -108,6 +108,40 @@ fn triangle_strip() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn triangles_list_from_u8() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::index::IndexBufferAny::from_u8(&display, PrimitiveType::TrianglesList,
+                                                        &[0, 1, 2, 2, 1, 3]).unwrap();
+
+    if display.get_capabilities().native_u8_indices {
+        assert_eq!(indices.get_indices_type(), index::IndexType::U8);
+    } else {
+        assert_eq!(indices.get_indices_type(), index::IndexType::U16);
+    }
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn triangle_fan() {
     let display = support::build_display();
@@ -414,3 +448,254 @@ fn multidraw_elements() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn multidraw_array_count() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    if !glium::index::is_draw_count_supported(&display) {
+        return;
+    }
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let multidraw = glium::index::DrawCommandsNoIndicesBuffer::empty(&display, 1);
+    let multidraw = match multidraw {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    multidraw.write(&[
+        glium::index::DrawCommandNoIndices {
+            count: 4,
+            instance_count: 1,
+            first_index: 0,
+            base_instance: 0,
+        }
+    ]);
+
+    let count = glium::buffer::Buffer::new(&display, &0u32,
+                                           glium::buffer::BufferType::ParameterBuffer,
+                                           glium::buffer::BufferMode::Default).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb,
+                              multidraw.with_primitive_type_and_count(PrimitiveType::TriangleStrip,
+                                                                      &count),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+
+    count.write(&1);
+    texture.as_surface().draw(&vb,
+                              multidraw.with_primitive_type_and_count(PrimitiveType::TriangleStrip,
+                                                                      &count),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multidraw_u32_commands() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    // a command packed by hand as raw `u32`s instead of a `DrawArraysIndirectCommand`
+    let commands = glium::buffer::Buffer::new(&display, &[4u32, 1, 0, 0][..],
+                                              glium::buffer::BufferType::DrawIndirectBuffer,
+                                              glium::buffer::BufferMode::Default);
+    let commands = match commands {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    let indices = glium::index::IndicesSource::MultidrawArray {
+        buffer: commands.as_slice_any(),
+        primitives: PrimitiveType::TriangleStrip,
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, indices, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multidraw_wrong_commands_stride() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    // three `u32`s aren't enough for a `DrawArraysIndirectCommand`
+    let commands = glium::buffer::Buffer::new(&display, &[4u32, 1, 0][..],
+                                              glium::buffer::BufferType::DrawIndirectBuffer,
+                                              glium::buffer::BufferMode::Default);
+    let commands = match commands {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    let indices = glium::index::IndicesSource::MultidrawArray {
+        buffer: commands.as_slice_any(),
+        primitives: PrimitiveType::TriangleStrip,
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, indices, &program, &uniform!{}, &Default::default()) {
+        Err(glium::DrawError::IndirectCommandsStrideMismatch { expected: 16, obtained: 4 }) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multidraw_misaligned_commands() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let commands = glium::buffer::Buffer::new(&display, &[0u8; 18][..],
+                                              glium::buffer::BufferType::DrawIndirectBuffer,
+                                              glium::buffer::BufferMode::Default);
+    let commands = match commands {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    let indices = glium::index::IndicesSource::MultidrawArray {
+        buffer: commands.slice(2 .. 18).unwrap().as_slice_any(),
+        primitives: PrimitiveType::TriangleStrip,
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, indices, &program, &uniform!{}, &Default::default()) {
+        Err(glium::DrawError::IndirectCommandsMisaligned) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+#[test]
+fn generate_grid() {
+    let display = support::build_display();
+
+    let indices: glium::IndexBuffer<u16> = index::generate::grid(&display, 2, 1).unwrap();
+    assert_eq!(indices.get_primitives_type(), PrimitiveType::TrianglesList);
+
+    let data = match indices.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data, vec![0, 1, 4, 0, 4, 3, 1, 2, 5, 1, 5, 4]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn generate_grid_too_many_vertices() {
+    let display = support::build_display();
+
+    match index::generate::grid::<u8, _>(&display, 16, 16) {
+        Err(index::generate::GenerationError::TooManyVertices) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn generate_quad_list() {
+    let display = support::build_display();
+
+    let indices: glium::IndexBuffer<u32> = index::generate::quad_list(&display, 2).unwrap();
+
+    let data = match indices.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data, vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn generate_triangle_strip_to_list() {
+    let display = support::build_display();
+
+    // two strips joined by degenerate triangles
+    let strip: &[u16] = &[0, 1, 2, 3, 3, 4, 4, 5, 6];
+    let indices = index::generate::triangle_strip_to_list(&display, strip).unwrap();
+
+    let data = match indices.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data, vec![0, 1, 2, 2, 1, 3, 4, 5, 6]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn generate_triangles_adjacency() {
+    let display = support::build_display();
+
+    let triangles: &[u16] = &[0, 1, 2, 2, 1, 3];
+    let indices = match index::generate::triangles_adjacency(&display, triangles) {
+        Err(index::generate::GenerationError::CreationError(
+            index::BufferCreationError::PrimitiveTypeNotSupported)) => return,
+        i => i.unwrap()
+    };
+
+    assert_eq!(indices.get_primitives_type(), PrimitiveType::TrianglesListAdjacency);
+
+    let data = match indices.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data, vec![0, 2, 1, 3, 2, 1, 2, 0, 1, 2, 3, 1]);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate glium;
+
+use glium::CapabilitiesSource;
+use glium::interop::{ExternalHandle, ImportError, MemoryObject, Semaphore};
+
+mod support;
+
+#[test]
+fn import_memory_object_not_supported() {
+    let display = support::build_display();
+
+    if display.get_extensions().gl_ext_memory_object_fd {
+        return;
+    }
+
+    match unsafe { MemoryObject::import(&display, ExternalHandle::OpaqueFd(-1), 1024, false) } {
+        Err(ImportError::NotSupported) => (),
+        Err(ImportError::HandleTypeNotSupported) => (),
+        Ok(_) => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn import_semaphore_not_supported() {
+    let display = support::build_display();
+
+    if display.get_extensions().gl_ext_semaphore_fd {
+        return;
+    }
+
+    match unsafe { Semaphore::import(&display, ExternalHandle::OpaqueFd(-1)) } {
+        Err(ImportError::NotSupported) => (),
+        Err(ImportError::HandleTypeNotSupported) => (),
+        Ok(_) => panic!()
+    };
+
+    display.assert_no_error(None);
+}
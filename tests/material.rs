@@ -0,0 +1,108 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::material::{MaterialBuffer, MaterialBufferCreationError};
+
+mod support;
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+struct Flat {
+    color: [f32; 4],
+    intensity: f32,
+    _padding: [f32; 3],
+}
+
+implement_material!(Flat, color, intensity);
+
+#[test]
+fn material_glsl_declarations() {
+    let declarations = MaterialBuffer::<Flat>::glsl_declarations();
+
+    assert!(declarations.contains("struct Flat {\n    vec4 color;\n    float intensity;\n};"));
+    assert!(declarations.contains("Flat materials[];"));
+    assert!(declarations.contains("uniform uint material_index;"));
+}
+
+#[test]
+fn material_draw() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let mut materials = match MaterialBuffer::new(&display, 1) {
+        Ok(m) => m,
+        Err(MaterialBufferCreationError::BufferCreationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let red = materials.push(Flat { color: [1.0, 0.0, 0.0, 1.0], intensity: 1.0,
+                                    _padding: [0.0; 3] }).unwrap();
+    let green = materials.push(Flat { color: [0.0, 1.0, 0.0, 1.0], intensity: 1.0,
+                                      _padding: [0.0; 3] }).unwrap();
+    assert_eq!(red, 0);
+    assert_eq!(green, 1);
+    assert!(materials.capacity() >= 2);
+
+    let fragment_shader = format!("
+        #version 430
+        {}
+
+        out vec4 f_color;
+
+        void main() {{
+            f_color = materials[material_index].color * materials[material_index].intensity;
+        }}
+    ", MaterialBuffer::<Flat>::glsl_declarations());
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        &fragment_shader,
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program,
+                              &materials.uniforms(green, &glium::uniforms::EmptyUniforms),
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn material_wrong_size() {
+    #[derive(Copy, Clone)]
+    #[repr(C)]
+    struct Unpadded {
+        color: [f32; 4],
+        intensity: f32,
+    }
+
+    implement_material!(Unpadded, color, intensity);
+
+    let display = support::build_display();
+
+    match MaterialBuffer::<Unpadded>::new(&display, 1) {
+        Err(MaterialBufferCreationError::WrongSize { size: 20, alignment: 16 }) => (),
+        a => panic!("{:?}", a.err())
+    };
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,131 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::texture::mipmaps::{MipmapsGenerator, MipmapsFilter};
+
+mod support;
+
+fn generate_and_check(filter: MipmapsFilter) {
+    let display = support::build_display();
+
+    let generator = match MipmapsGenerator::new(&display, filter) {
+        Ok(g) => g,
+        Err(_) => return
+    };
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                glium::texture::MipmapsOption::EmptyMipmaps,
+                                                8, 8).unwrap();
+    texture.as_surface().clear_color(1.0, 0.0, 1.0, 1.0);
+
+    generator.generate(&texture).unwrap();
+
+    // copying the third level to a texture in order to read it
+    let level = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                                                           texture.mipmap(2).unwrap()).unwrap();
+    let output = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+    level.blit_color(&glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                     &output.as_surface(),
+                     &glium::BlitTarget { left: 0, bottom: 0, width: 2, height: 2 },
+                     glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn generate_mipmaps_box() {
+    generate_and_check(MipmapsFilter::Box);
+}
+
+#[test]
+fn generate_mipmaps_kaiser() {
+    generate_and_check(MipmapsFilter::Kaiser);
+}
+
+#[test]
+fn render_levels() {
+    use glium::texture::mipmaps::{self, ReductionLevel};
+
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                glium::texture::MipmapsOption::EmptyMipmaps,
+                                                8, 5).unwrap();
+
+    let mut levels = Vec::new();
+    mipmaps::render_levels(&display, &texture, |target, level| {
+        target.clear_color(0.0, 1.0, 0.0, 1.0);
+        levels.push(level);
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(levels, vec![
+        ReductionLevel { level: 1, dimensions: (4, 2), source_dimensions: (8, 5) },
+        ReductionLevel { level: 2, dimensions: (2, 1), source_dimensions: (4, 2) },
+        ReductionLevel { level: 3, dimensions: (1, 1), source_dimensions: (2, 1) },
+    ]);
+
+    let level = glium::framebuffer::SimpleFrameBuffer::with_mipmap_level(&display, &texture, 3)
+                                                                            .unwrap();
+    let output = glium::texture::Texture2d::empty(&display, 1, 1).unwrap();
+    level.blit_color(&glium::Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                     &output.as_surface(),
+                     &glium::BlitTarget { left: 0, bottom: 0, width: 1, height: 1 },
+                     glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mipmap_level_out_of_range() {
+    use glium::framebuffer::{SimpleFrameBuffer, ValidationError};
+
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                glium::texture::MipmapsOption::EmptyMipmaps,
+                                                8, 8).unwrap();
+
+    match SimpleFrameBuffer::with_mipmap_level(&display, &texture, 4) {
+        Err(ValidationError::MipmapLevelOutOfRange { level: 4, levels: 4 }) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn lod_diagnostics() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                glium::texture::MipmapsOption::EmptyMipmaps,
+                                                8, 8).unwrap();
+
+    let diagnostics = texture.get_lod_diagnostics();
+    assert_eq!(diagnostics.base_level, 0);
+    assert_eq!(diagnostics.allocated_levels, 4);
+    assert_eq!(diagnostics.sampled_levels, 4);
+    assert!(diagnostics.sparse.is_none());
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                glium::texture::MipmapsOption::NoMipmap,
+                                                8, 8).unwrap();
+
+    let diagnostics = texture.get_lod_diagnostics();
+    assert_eq!(diagnostics.max_level, 0);
+    assert_eq!(diagnostics.sampled_levels, 1);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::occlusion::OcclusionCuller;
+
+mod support;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+#[test]
+fn visible_and_hidden() {
+    let display = support::build_display();
+
+    let mut culler = match OcclusionCuller::new(&display, false) {
+        Ok(c) => c,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                            glium::texture::DepthFormat::I24, 1024, 1024).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                    &texture, &depth).unwrap();
+
+    // the scene is an occluder that covers the whole target at mid-depth
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 0.5);
+
+    culler.next_frame();
+    culler.test(&mut framebuffer, 0, [-0.5, -0.5, -0.9], [0.5, 0.5, -0.8], IDENTITY).unwrap();
+    culler.test(&mut framebuffer, 3, [-0.5, -0.5, 0.8], [0.5, 0.5, 0.9], IDENTITY).unwrap();
+
+    // the results are only available during the next frame
+    assert!(culler.is_visible(3));
+
+    display.finish();
+    culler.next_frame();
+
+    assert!(culler.is_visible(0));
+    assert!(culler.is_hidden(3));
+
+    // objects that have never been tested are visible
+    assert!(culler.is_visible(1));
+    assert!(culler.is_visible(100));
+
+    // nothing is written to the target
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[512][512], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
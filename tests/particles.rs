@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::particles::{Particle, ParticleSystem};
+
+mod support;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+#[test]
+fn emit_update_draw() {
+    let display = support::build_display();
+
+    let mut particles = match ParticleSystem::new(&display, 16) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    assert_eq!(particles.read_count(), 0);
+
+    particles.emit(&[
+        Particle::new([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 10.0),
+        Particle::new([0.5, 0.5, 0.0], [0.0, 0.0, 0.0], 0.5),
+    ]);
+    particles.update(0.0, [0.0, 0.0, 0.0]);
+    assert_eq!(particles.read_count(), 2);
+
+    // the second particle dies, the first one moves
+    particles.update(1.0, [0.0, 0.0, 0.0]);
+    assert_eq!(particles.read_count(), 1);
+
+    let data = particles.particles().read().unwrap();
+    assert_eq!(data[0].position, [0.0, 0.0, 0.0, 9.0]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let parameters = glium::DrawParameters {
+        point_size: Some(4.0),
+        .. Default::default()
+    };
+    particles.draw(&mut texture.as_surface(), IDENTITY, [1.0, 0.0, 0.0, 1.0],
+                   &parameters).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[512][512], (255, 0, 0, 255));
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn capacity_is_respected() {
+    let display = support::build_display();
+
+    let mut particles = match ParticleSystem::new(&display, 4) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    particles.emit(&[Particle::new([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 10.0); 3]);
+    particles.update(0.0, [0.0, 0.0, 0.0]);
+
+    // only one of these fits once the existing particles have been kept
+    particles.emit(&[Particle::new([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 10.0); 3]);
+    particles.update(0.0, [0.0, 0.0, 0.0]);
+    assert_eq!(particles.read_count(), 4);
+
+    particles.clear();
+    assert_eq!(particles.read_count(), 0);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::picking::{self, Picker};
+
+mod support;
+
+#[test]
+fn pick_object() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                // only covers the left half of the target
+                gl_Position = vec4(position.x * 0.5 - 0.5, position.y, 0.0, 1.0);
+            }
+        ",
+        picking::FRAGMENT_SHADER, None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let mut picker = Picker::new(&display);
+
+    {
+        let mut target = picker.target(64, 64).unwrap();
+        target.draw(&vb, &ib, &program, &uniform!{ picking_id: 7u32 },
+                    &Default::default()).unwrap();
+    }
+
+    // reads are asynchronous, so the first call can't return anything
+    assert_eq!(picker.pick(16, 32), None);
+    display.finish();
+    assert_eq!(picker.pick(16, 32), Some(7));
+
+    // nothing is drawn in the right half
+    display.finish();
+    picker.pick(48, 32);
+    display.finish();
+    assert_eq!(picker.pick(48, 32), None);
+
+    // outside of the texture
+    assert_eq!(picker.pick(64, 0), None);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,100 @@
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::pipeline::{PipelineCreationError, PipelineDescriptor, PipelineOutputs};
+
+mod support;
+
+#[test]
+fn pipeline_draw() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let outputs = PipelineOutputs::from_surface(&texture.as_surface());
+
+    let pipeline = PipelineDescriptor::new(&display, &program, &[vb.get_bindings().clone()],
+                                           outputs, Default::default()).unwrap();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    pipeline.draw(&mut texture.as_surface(), &vb, &ib, &glium::uniforms::EmptyUniforms).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn pipeline_missing_attribute() {
+    let display = support::build_display();
+    let (_, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        color: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, color);
+
+    let formats = [<Vertex as glium::vertex::Vertex>::build_bindings()];
+    match PipelineDescriptor::new(&display, &program, &formats, PipelineOutputs::default(),
+                                  Default::default())
+    {
+        Err(PipelineCreationError::DrawError(glium::DrawError::AttributeMissing { .. })) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn pipeline_no_depth_buffer() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    let outputs = PipelineOutputs { depth_buffer: false, .. Default::default() };
+    match PipelineDescriptor::new(&display, &program, &[vb.get_bindings().clone()], outputs,
+                                  params)
+    {
+        Err(PipelineCreationError::DrawError(glium::DrawError::NoDepthBuffer)) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn pipeline_viewport_outside_outputs() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        viewport: Some(glium::Rect { left: 0, bottom: 0, width: 2048, height: 2048 }),
+        .. Default::default()
+    };
+
+    let outputs = PipelineOutputs { dimensions: Some((1024, 1024)), .. Default::default() };
+    match PipelineDescriptor::new(&display, &program, &[vb.get_bindings().clone()], outputs,
+                                  params)
+    {
+        Err(PipelineCreationError::DrawError(glium::DrawError::ViewportTooLarge)) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,70 @@
+#[macro_use]
+extern crate glium;
+
+use glium::framebuffer::PingPongTargets;
+use glium::post_process::{FullscreenPass, PassChain};
+use glium::texture::UncompressedFloatFormat;
+
+mod support;
+
+const INVERT: &'static str = "
+    #version 110
+
+    uniform sampler2D source;
+    uniform float amount;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        vec3 color = texture2D(source, v_tex_coords).rgb;
+        gl_FragColor = vec4(mix(color, 1.0 - color, amount), 1.0);
+    }
+";
+
+#[test]
+fn single_pass() {
+    let display = support::build_display();
+    let pass = FullscreenPass::new(&display, INVERT).unwrap();
+
+    let source = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let output = support::build_renderable_texture(&display);
+
+    pass.draw(&mut output.as_surface(), &source, &uniform! { amount: 1.0f32 }).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn chained_passes() {
+    let display = support::build_display();
+    let pass = FullscreenPass::new(&display, INVERT).unwrap();
+    let mut targets = PingPongTargets::new(&display, UncompressedFloatFormat::U8U8U8U8,
+                                           16, 16).unwrap();
+
+    let source = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let output = support::build_renderable_texture(&display);
+
+    {
+        let mut chain = PassChain::new(&mut targets, &source).unwrap();
+        chain.apply(&pass, &uniform! { amount: 1.0f32 }).unwrap();
+        chain.apply(&pass, &uniform! { amount: 1.0f32 }).unwrap();
+        assert_eq!(chain.output().dimensions(), (2, 2));
+        chain.finish(&pass, &mut output.as_surface(), &uniform! { amount: 1.0f32 }).unwrap();
+    }
+
+    // three inversions
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -129,6 +129,38 @@ fn any_samples_passed_conservative() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn any_samples_passed_reused() {
+    let display = support::build_display();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    for frame in 0 .. 8 {
+        let query = match glium::draw_parameters::AnySamplesPassedQuery::new(&display, true) {
+            Err(_) => return,
+            Ok(q) => q
+        };
+
+        // only drawing one frame out of two, so that stale results would be detected
+        if frame % 2 == 0 {
+            let params = glium::DrawParameters {
+                samples_passed_query: Some((&query).into()),
+                .. Default::default()
+            };
+
+            texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                      &params).unwrap();
+        }
+
+        assert_eq!(query.get(), frame % 2 == 0);
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn time_elapsed() {
     let display = support::build_display();
@@ -0,0 +1,169 @@
+#[macro_use]
+extern crate glium;
+
+use std::cell::Cell;
+
+use glium::Surface;
+use glium::framebuffer::TargetPool;
+use glium::post_process::FullscreenPass;
+use glium::render_graph::{RenderGraph, RenderGraphError};
+use glium::texture::UncompressedFloatFormat;
+
+mod support;
+
+const COPY: &'static str = "
+    #version 110
+
+    uniform sampler2D source;
+    varying vec2 v_tex_coords;
+
+    void main() {
+        gl_FragColor = texture2D(source, v_tex_coords);
+    }
+";
+
+#[test]
+fn passes_executed_in_order() {
+    let display = support::build_display();
+    let copy = FullscreenPass::new(&display, COPY).unwrap();
+    let mut pool = TargetPool::new(&display);
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let (copy, output) = (&copy, &output);
+
+        let mut graph = RenderGraph::new();
+        let first = graph.create_target("first", UncompressedFloatFormat::U8U8U8U8, 1.0);
+        let second = graph.create_target("second", UncompressedFloatFormat::U8U8U8U8, 0.5);
+
+        graph.add_pass("fill", &[], &[first], move |pass| {
+            pass.surface().clear_color(1.0, 0.0, 0.0, 1.0);
+            Ok(())
+        });
+
+        graph.add_pass("downsample", &[first], &[second], move |pass| {
+            copy.draw(&mut pass.surface(), pass.texture(first),
+                      &glium::uniforms::EmptyUniforms)
+        });
+
+        graph.add_pass("output", &[second], &[], move |pass| {
+            copy.draw(&mut output.as_surface(), pass.texture(second),
+                      &glium::uniforms::EmptyUniforms)
+        });
+
+        graph.execute(&mut pool).unwrap();
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn unused_passes_skipped() {
+    let display = support::build_display();
+    let mut pool = TargetPool::new(&display);
+
+    let executed = Cell::new(0);
+    let skipped = Cell::new(0);
+
+    {
+        let mut graph = RenderGraph::new();
+        let used = graph.create_target("used", UncompressedFloatFormat::U8U8U8U8, 0.25);
+        let unused = graph.create_target("unused", UncompressedFloatFormat::U8U8U8U8, 0.25);
+
+        graph.add_pass("unused", &[], &[unused], |_| {
+            skipped.set(skipped.get() + 1);
+            Ok(())
+        });
+
+        graph.add_pass("used", &[], &[used], |_| {
+            executed.set(executed.get() + 1);
+            Ok(())
+        });
+
+        graph.add_pass("output", &[used], &[], |_| {
+            executed.set(executed.get() + 1);
+            Ok(())
+        });
+
+        graph.execute(&mut pool).unwrap();
+    }
+
+    assert_eq!(executed.get(), 2);
+    assert_eq!(skipped.get(), 0);
+    assert_eq!(pool.len(), 1);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn targets_aliased() {
+    let display = support::build_display();
+    let mut pool = TargetPool::new(&display);
+
+    {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_target("a", UncompressedFloatFormat::U8U8U8U8, 0.25);
+        let b = graph.create_target("b", UncompressedFloatFormat::U8U8U8U8, 0.25);
+        let c = graph.create_target("c", UncompressedFloatFormat::U8U8U8U8, 0.25);
+
+        graph.add_pass("a", &[], &[a], |_| Ok(()));
+        graph.add_pass("b", &[a], &[b], |_| Ok(()));
+        // `a` is no longer used, so `c` can reuse its texture
+        graph.add_pass("c", &[b], &[c], |_| Ok(()));
+        graph.add_pass("output", &[c], &[], |_| Ok(()));
+
+        graph.execute(&mut pool).unwrap();
+    }
+
+    assert_eq!(pool.len(), 2);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_before_write() {
+    let display = support::build_display();
+    let mut pool = TargetPool::new(&display);
+
+    let mut graph = RenderGraph::new();
+    let target = graph.create_target("target", UncompressedFloatFormat::U8U8U8U8, 1.0);
+    graph.add_pass("output", &[target], &[], |_| Ok(()));
+    graph.add_pass("fill", &[], &[target], |_| Ok(()));
+
+    match graph.execute(&mut pool) {
+        Err(RenderGraphError::ReadBeforeWrite { ref pass, ref target })
+            if pass == "output" && target == "target" => (),
+        a => panic!("{:?}", a)
+    };
+
+    assert_eq!(pool.len(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_write_conflict() {
+    let display = support::build_display();
+    let mut pool = TargetPool::new(&display);
+
+    let mut graph = RenderGraph::new();
+    let target = graph.create_target("target", UncompressedFloatFormat::U8U8U8U8, 1.0);
+    graph.add_pass("fill", &[], &[target], |_| Ok(()));
+    graph.add_pass("feedback", &[target], &[target], |_| Ok(()));
+
+    match graph.execute(&mut pool) {
+        Err(RenderGraphError::ReadWriteConflict { .. }) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
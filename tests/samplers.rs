@@ -79,3 +79,76 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn anisotropy_clamping() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+                }
+            ",
+        }).unwrap();
+
+    let texture_data = vec![vec![(255u8, 255, 255), (255, 255, 255)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    // levels outside of the supported range are clamped instead of triggering an error
+    for &level in &[0, 1, u16::max_value()] {
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+        let uniforms = uniform! {
+            texture: texture.sampled().anisotropy(level)
+        };
+
+        match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+            Ok(_) => (),
+            Err(glium::DrawError::SamplersNotSupported) => return,
+            Err(e) => panic!("{:?}", e)
+        };
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        assert_eq!(data[0][0], (255, 255, 255, 255));
+    }
+
+    display.assert_no_error(None);
+}
@@ -4,6 +4,7 @@
 #[macro_use]
 extern crate glium;
 
+use glium::CapabilitiesSource;
 use glium::Surface;
 
 mod support;
@@ -47,6 +48,89 @@ fn program_creation() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_cache() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    display.set_program_cache_capacity(1);
+
+    let vertex = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let fragment1 = "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+        }
+    ";
+
+    let fragment2 = "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(0.0, 1.0, 0.0, 1.0);
+        }
+    ";
+
+    let program1 = glium::Program::from_source(&display, vertex, fragment1, None).unwrap();
+    let program2 = glium::Program::from_source(&display, vertex, fragment1, None).unwrap();
+    assert_eq!(program1.get_id(), program2.get_id());
+
+    let program3 = glium::Program::from_source(&display, vertex, fragment2, None).unwrap();
+    assert!(program1.get_id() != program3.get_id());
+
+    let stats = display.get_program_cache_statistics();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+
+    display.purge_caches();
+    assert_eq!(display.get_program_cache_statistics().entries, 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_cache_doesnt_keep_context_alive() {
+    use glium::backend::Facade;
+    use std::rc::Rc;
+
+    let display = support::build_display();
+    display.set_program_cache_capacity(4);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+    assert_eq!(display.get_program_cache_statistics().entries, 1);
+
+    let context = Rc::downgrade(display.get_context());
+    drop(program);
+    drop(display);
+    assert!(context.upgrade().is_none());
+}
+
 #[test]
 fn program_compilation_error() {
     let display = support::build_display();
@@ -77,6 +161,34 @@ fn program_compilation_error() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_compilation_error_as_glium_error() {
+    use std::error::Error;
+
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display, "invalid glsl code",
+                                              "invalid glsl code", None);
+
+    let err = match program {
+        Err(err) => err,
+        Ok(_) => panic!()
+    };
+
+    let message = err.to_string();
+    let err = glium::Error::from(err);
+
+    match err {
+        glium::Error::ProgramCreationError(glium::CompilationError(_)) => (),
+        _ => panic!()
+    };
+
+    assert_eq!(err.to_string(), "Failed to create a program");
+    assert_eq!(err.source().unwrap().to_string(), message);
+
+    display.assert_no_error(None);
+}
+
 // This test is disabled because some OpenGL drivers don't catch
 // the linking error (even though they are supposed to)
 #[test]
@@ -154,6 +266,116 @@ fn get_frag_data_location() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn framebuffer_fetch_not_supported() {
+    let display = support::build_display();
+
+    if glium::program::is_framebuffer_fetch_supported(&display) {
+        return;
+    }
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+            #extension GL_EXT_shader_framebuffer_fetch : require
+
+            void main() {
+                gl_FragColor = gl_LastFragData[0] * 0.5;
+            }
+        ",
+        None);
+
+    match program {
+        Err(glium::ProgramCreationError::FramebufferFetchNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn vertex_shader_layer_not_supported() {
+    let display = support::build_display();
+
+    if glium::program::is_vertex_shader_layer_supported(&display) {
+        return;
+    }
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 410
+            #extension GL_ARB_shader_viewport_layer_array : require
+
+            void main() {
+                gl_Layer = 1;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 410
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None);
+
+    match program {
+        Err(glium::ProgramCreationError::VertexShaderLayerNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn fragment_shader_interlock_not_supported() {
+    let display = support::build_display();
+
+    if glium::program::is_fragment_shader_interlock_supported(&display) {
+        return;
+    }
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 450
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 450
+            #extension GL_ARB_fragment_shader_interlock : require
+
+            layout(pixel_interlock_ordered) in;
+            out vec4 color;
+
+            void main() {
+                beginInvocationInterlockARB();
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+                endInvocationInterlockARB();
+            }
+        ",
+        None);
+
+    match program {
+        Err(glium::ProgramCreationError::FragmentShaderInterlockNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_uniform_blocks() {
     let display = support::build_display();
@@ -363,6 +585,10 @@ fn get_transform_feedback_varyings() {
         tessellation_control_shader: None,
         tessellation_evaluation_shader: None,
         geometry_shader: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
         outputs_srgb: false,
         uses_point_size: false,
 
@@ -458,6 +684,66 @@ fn get_output_primitives_simple() {
     };
 
     assert!(program.get_output_primitives().is_none());
+    assert!(program.get_geometry_shader_layout().is_none());
+    display.assert_no_error(None);
+}
+
+#[test]
+fn get_geometry_shader_layout() {
+    let display = support::build_display();
+
+    let max_vertices = match display.get_capabilities().max_geometry_output_vertices {
+        Some(max) => max,
+        None => return
+    };
+
+    assert!(max_vertices >= 6);
+    assert!(display.get_capabilities().max_geometry_total_output_components.unwrap() >= 24);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 150
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 150
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        Some("
+            #version 150
+
+            layout(points) in;
+            layout(triangle_strip, max_vertices = 6) out;
+
+            void main() {
+                for (int i = 0; i < 6; ++i) {
+                    gl_Position = gl_in[0].gl_Position;
+                    EmitVertex();
+                }
+                EndPrimitive();
+            }
+        "));
+
+    // ignoring test in case of compilation error
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let layout = program.get_geometry_shader_layout().unwrap();
+    assert_eq!(layout.output_primitives, glium::program::OutputPrimitives::Triangles);
+    assert_eq!(layout.max_vertices, 6);
+    assert_eq!(layout.invocations, 1);
+    assert_eq!(program.get_output_primitives(), Some(glium::program::OutputPrimitives::Triangles));
+
     display.assert_no_error(None);
 }
 
@@ -856,3 +1142,516 @@ fn array_layout_offsets() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn mesh_shader_draw() {
+    let display = support::build_display();
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::MeshShaders {
+        task_shader: None,
+        mesh_shader: "
+            #version 450
+            #extension GL_NV_mesh_shader : require
+
+            layout(local_size_x = 1) in;
+            layout(triangles, max_vertices = 3, max_primitives = 1) out;
+
+            void main() {
+                gl_MeshVerticesNV[0].gl_Position = vec4(-1.0, -1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[1].gl_Position = vec4(3.0, -1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[2].gl_Position = vec4(-1.0, 3.0, 0.0, 1.0);
+                gl_PrimitiveIndicesNV[0] = 0;
+                gl_PrimitiveIndicesNV[1] = 1;
+                gl_PrimitiveIndicesNV[2] = 2;
+                gl_PrimitiveCountNV = 1;
+            }
+        ",
+        fragment_shader: "
+            #version 450
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        outputs_srgb: false,
+    });
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::ProgramCreationError::ShaderTypeNotSupported) => {
+            assert!(!glium::program::is_mesh_shader_supported(&display));
+            return;
+        },
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert!(program.has_mesh_shader());
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_mesh_tasks(&program, &glium::uniforms::EmptyUniforms,
+                                         &Default::default(), 0, 1).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(glium::DrawError::MeshShaderMismatch) => (),
+        _ => panic!()
+    };
+
+    let max_count = {
+        use glium::CapabilitiesSource;
+        display.get_capabilities().max_draw_mesh_tasks_count.unwrap() as u32
+    };
+    match texture.as_surface().draw_mesh_tasks(&program, &glium::uniforms::EmptyUniforms,
+                                               &Default::default(), 0, max_count + 1)
+    {
+        Err(glium::DrawError::TooManyMeshTasks) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_defines() {
+    let display = support::build_display();
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::SourceCode {
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(RED, GREEN, 0.0, 1.0);
+            }
+        ",
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        transform_feedback_varyings: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: vec![("RED".to_string(), "1.0".to_string()),
+                      ("GREEN".to_string(), "0.0".to_string())],
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+    });
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::ProgramCreationError::CompilationNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[512][512], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_defines_after_license_comment() {
+    let display = support::build_display();
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::SourceCode {
+        vertex_shader: "
+            // Copyright (c) The authors
+            /* Licensed under the
+               Apache License, Version 2.0 */
+
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            // Copyright (c) The authors
+
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(RED, 0.0, 0.0, 1.0);
+            }
+        ",
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        transform_feedback_varyings: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: vec![("RED".to_string(), "1.0".to_string())],
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+    });
+
+    match program {
+        Ok(_) => (),
+        Err(glium::ProgramCreationError::CompilationNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_adapt_glsl_version() {
+    let display = support::build_display();
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::SourceCode {
+        vertex_shader: "
+            #version 330 core
+
+            layout(location = 0) in vec2 position;
+            out vec2 v_color;
+
+            void main() {
+                v_color = vec2(1.0, 0.0);
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 330 core
+
+            in vec2 v_color;
+            out vec4 color;
+
+            void main() {
+                color = vec4(v_color, 0.0, 1.0);
+            }
+        ",
+        geometry_shader: None,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        transform_feedback_varyings: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: true,
+        outputs_srgb: false,
+        uses_point_size: false,
+    });
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::ProgramCreationError::CompilationNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[512][512], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shader_library_include() {
+    let display = support::build_display();
+
+    display.get_shader_library().register("color", "
+        #include \"red\"
+
+        vec4 get_color() {
+            return vec4(RED_VALUE, 0.0, 0.0, 1.0);
+        }
+    ");
+    display.get_shader_library().register("red", "#define RED_VALUE 1.0");
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+            #include \"color\"
+
+            void main() {
+                gl_FragColor = get_color();
+            }
+        ",
+        None);
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::ProgramCreationError::CompilationNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shader_library_errors() {
+    let display = support::build_display();
+
+    display.get_shader_library().register("loop", "#include \"loop\"");
+    display.get_shader_library().register("broken", "this is not glsl");
+    assert_eq!(display.get_shader_library().get_source_string_name(2),
+               Some("broken".to_owned()));
+
+    let build = |include: &str| {
+        glium::Program::from_source(&display,
+            "
+                #version 110
+
+                void main() {
+                    gl_Position = vec4(0.0);
+                }
+            ",
+            &format!("
+                #version 110
+                #include \"{}\"
+
+                void main() {{
+                    gl_FragColor = vec4(1.0);
+                }}
+            ", include),
+            None)
+    };
+
+    match build("missing") {
+        Err(glium::ProgramCreationError::ShaderModuleNotFound(ref name)) if name == "missing" => (),
+        r => panic!("{:?}", r.err())
+    };
+
+    match build("loop") {
+        Err(glium::ProgramCreationError::RecursiveShaderModule(_)) => (),
+        r => panic!("{:?}", r.err())
+    };
+
+    match build("broken") {
+        Err(glium::ProgramCreationError::CompilationError(ref log)) => {
+            assert!(log.contains("module `broken`"));
+        },
+        Err(glium::ProgramCreationError::CompilationNotSupported) => (),
+        r => panic!("{:?}", r.err())
+    };
+
+    display.assert_no_error(None);
+}
+
+fn build_program_with_attribute_locations<F: ?Sized>(display: &F,
+                                                     locations: Vec<(String, u32)>, red: f32)
+                                                     -> glium::Program
+    where F: glium::backend::Facade
+{
+    let fragment_shader = format!("
+        #version 110
+
+        void main() {{
+            gl_FragColor = vec4({:.1}, 0.0, 0.0, 1.0);
+        }}
+    ", red);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: locations,
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+            attribute float depth;
+
+            void main() {
+                gl_Position = vec4(position, depth, 1.0);
+            }
+        ",
+        fragment_shader: &fragment_shader,
+    };
+
+    glium::Program::new(display, source).unwrap()
+}
+
+#[test]
+fn attribute_locations() {
+    let display = support::build_display();
+
+    let program = build_program_with_attribute_locations(&display, vec![
+        ("position".to_owned(), 3), ("depth".to_owned(), 1), ("unused".to_owned(), 5)
+    ], 1.0);
+
+    assert_eq!(program.get_attribute("position").unwrap().location, 3);
+    assert_eq!(program.get_attribute("depth").unwrap().location, 1);
+    assert!(program.get_attribute("unused").is_none());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn attribute_locations_share_vertex_array_objects() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        depth: f32,
+    }
+
+    implement_vertex!(Vertex, position, depth);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0], depth: 0.0 },
+        Vertex { position: [ 1.0,  1.0], depth: 0.0 },
+        Vertex { position: [-1.0, -1.0], depth: 0.0 },
+        Vertex { position: [ 1.0, -1.0], depth: 0.0 },
+    ]).unwrap();
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+
+    let locations = vec![("position".to_owned(), 0), ("depth".to_owned(), 1)];
+    let program1 = build_program_with_attribute_locations(&display, locations.clone(), 0.0);
+    let program2 = build_program_with_attribute_locations(&display, locations, 1.0);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().draw(&vb, &indices, &program1, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+    texture.as_surface().draw(&vb, &indices, &program2, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    // the backend may not support VAOs
+    let stats = display.get_vertex_array_objects_cache_statistics();
+    if stats.misses != 0 {
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn attribute_location_mismatch() {
+    let display = support::build_display();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: vec![("position".to_owned(), 2)],
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 330
+
+            layout(location = 1) in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 330
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+    };
+
+    match glium::Program::new(&display, source) {
+        Err(glium::program::ProgramCreationError::AttributeLocationMismatch {
+            ref name, requested: 2, obtained: 1 }) if name == "position" => (),
+        Err(glium::program::ProgramCreationError::CompilationError(_)) => return,
+        res => panic!("{:?}", res.err())
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shader_binary_format_not_supported() {
+    let display = support::build_display();
+
+    // finding a format that isn't supported by the backend
+    let formats = display.get_capabilities().shader_binary_formats.clone();
+    let format = (0 ..).find(|f| !formats.contains(f)).unwrap();
+
+    let program = glium::Program::new(&display,
+                                      glium::program::ProgramCreationInput::ShaderBinaries {
+        format: format,
+        vertex_shader: &[0, 1, 2, 3],
+        fragment_shader: &[0, 1, 2, 3],
+        outputs_srgb: false,
+        uses_point_size: false,
+    });
+
+    match program {
+        Err(glium::ProgramCreationError::ShaderBinaryFormatNotSupported) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
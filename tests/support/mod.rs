@@ -49,6 +49,17 @@ pub fn rebuild_display(display: &glium::Display) {
     display.rebuild(window, context, &events_loop).unwrap();
 }
 
+/// Builds a second display whose context shares its objects with an existing display.
+pub fn build_shared_display(display: &glium::Display) -> glium::Display {
+    let version = parse_version();
+    let events_loop = glutin::EventsLoop::new();
+    let window = glutin::WindowBuilder::new().with_visibility(false);
+    let context = glutin::ContextBuilder::new()
+        .with_gl_debug_flag(true)
+        .with_gl(version);
+    glium::Display::new_shared(window, context, &events_loop, display).unwrap()
+}
+
 fn parse_version() -> glutin::GlRequest {
     match env::var("GLIUM_GL_VERSION") {
         Ok(version) => {
@@ -4,6 +4,8 @@ extern crate glium;
 use glium::Surface;
 use glium::texture::buffer_texture::BufferTexture;
 use glium::texture::buffer_texture::BufferTextureType;
+use glium::texture::buffer_texture::{ImageAccess, ImageUnitError};
+use glium::texture::buffer_texture::TextureCreationError;
 
 mod support;
 
@@ -125,3 +127,156 @@ fn wrong_type() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn sample_range() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let alignment = match display.get_capabilities().texture_buffer_offset_alignment {
+        Some(a) => a as usize,
+        None => return
+    };
+
+    // the texel that we sample is the first element of the range
+    let offset = (alignment + 3) / 4;
+    let mut data = vec![(0u8, 0u8, 0u8, 0u8); offset + 4];
+    data[offset] = (255, 0, 255, 255);
+
+    let buffer = glium::buffer::Buffer::new(&display, &data[..],
+                                            glium::buffer::BufferType::TextureBuffer,
+                                            glium::buffer::BufferMode::Default).unwrap();
+
+    let buf_tex = match BufferTexture::from_buffer_range(&display, buffer, offset .. offset + 4,
+                                                         BufferTextureType::Float)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    assert_eq!(buf_tex.get_range(), offset .. offset + 4);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            uniform samplerBuffer tex;
+
+            void main() {
+                gl_FragColor = texelFetch(tex, 0);
+            }
+        ",
+        None);
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ tex: &buf_tex },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn range_not_aligned() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let alignment = match display.get_capabilities().texture_buffer_offset_alignment {
+        Some(a) if a > 4 => a as usize,
+        _ => return
+    };
+
+    let buffer = glium::buffer::Buffer::<[u32]>::empty_array(&display,
+                                            glium::buffer::BufferType::TextureBuffer,
+                                            alignment, glium::buffer::BufferMode::Default)
+                                            .unwrap();
+
+    match BufferTexture::from_buffer_range(&display, buffer, 1 .. 4, BufferTextureType::Unsigned) {
+        Err((TextureCreationError::RangeNotAligned, _)) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_store() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let buf_tex: BufferTexture<u32> = match BufferTexture::empty(&display, 64,
+                                                                 BufferTextureType::Unsigned)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let image = match buf_tex.image_unit(ImageAccess::WriteOnly) {
+        Ok(i) => i,
+        Err(ImageUnitError::NotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 64) in;
+
+        writeonly uniform uimageBuffer output;
+
+        void main() {
+            imageStore(output, int(gl_GlobalInvocationID.x), uvec4(gl_GlobalInvocationID.x * 2u));
+        }
+    ").unwrap();
+
+    program.execute(uniform! { output: image }, 1, 1, 1);
+
+    let data = buf_tex.read().unwrap();
+    for (i, value) in data.iter().enumerate() {
+        assert_eq!(*value, i as u32 * 2);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_wrong_format() {
+    let display = support::build_display();
+
+    let buf_tex: BufferTexture<(f32, f32, f32)> = match BufferTexture::empty(&display, 4,
+                                                                      BufferTextureType::Float)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    match buf_tex.image_unit(ImageAccess::ReadWrite) {
+        Err(ImageUnitError::FormatNotSupported) => (),
+        Err(ImageUnitError::NotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
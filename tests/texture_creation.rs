@@ -154,6 +154,38 @@ fn compressed_texture_2d_creation() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn compressed_texture_2d_decode_fallback() {
+    use glium::texture::{CompressedFormat, CompressedMipmapsOption, FormatFallbackPolicy};
+    use glium::texture::TextureFormat;
+
+    let display = support::build_display();
+
+    let format = TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha);
+    display.set_format_fallback_policy(format, FormatFallbackPolicy::DecodeOnCpu);
+
+    // a single DXT1 block whose texels are all red
+    let data = [0x00, 0xf8, 0x00, 0xf8, 0x00, 0x00, 0x00, 0x00];
+
+    let texture = match glium::texture::CompressedTexture2d::with_compressed_data(&display, &data,
+                                                4, 4, CompressedFormat::S3tcDxt1NoAlpha,
+                                                CompressedMipmapsOption::NoMipmap)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    // whether the texture was decoded by the driver or by glium, the content must be the same
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in read_back.iter() {
+        for texel in row.iter() {
+            assert_eq!(*texel, (255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 macro_rules! empty_texture_test {
     ($test_name:ident, $tex_ty:ident, [$($dims:expr),+],
      $w:expr, $h:expr, $d:expr, $s:expr) =>
@@ -385,3 +417,31 @@ fn upload_from_pixel_buffer_inverted() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn image_load_store_capabilities() {
+    use glium::CapabilitiesSource;
+    use glium::texture::{TextureFormat, UncompressedFloatFormat};
+
+    let display = support::build_display();
+    let capabilities = display.get_capabilities();
+
+    let max_image_units = match capabilities.max_image_units {
+        Some(n) => n,
+        None => return
+    };
+
+    // OpenGL requires at least 8 image units and OpenGL ES at least 4
+    assert!(max_image_units >= 4);
+
+    // rgba32f is required to support image load/store
+    let format = TextureFormat::UncompressedFloat(UncompressedFloatFormat::F32F32F32F32);
+    if let Some(infos) = capabilities.internal_formats_textures.get(&format) {
+        if let Some(image) = infos.image {
+            assert!(image.load);
+            assert!(image.store);
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,28 @@
+#[macro_use]
+extern crate glium;
+
+use std::ptr;
+
+use glium::texture::external::{ExternalTexture, CreationError, DmaBufDescriptor};
+
+mod support;
+
+#[test]
+fn dma_buf_without_planes() {
+    let display = support::build_display();
+
+    let descriptor = DmaBufDescriptor {
+        width: 16,
+        height: 16,
+        fourcc: 0x34325241,     // DRM_FORMAT_ARGB8888
+        planes: Vec::new(),
+        modifier: None,
+    };
+
+    match unsafe { ExternalTexture::from_dma_buf(&display, ptr::null(), &descriptor) } {
+        Err(CreationError::InvalidPlanesCount) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
@@ -40,6 +40,33 @@ fn texture_2d_read_pixelbuffer() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn texture_2d_read_pixelbuffer_reused() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8)],
+        vec![(32u8, 64u8, 128u8), (32u8, 16u8, 4u8)],
+    ]).unwrap();
+
+    // the second and third reads reuse the pixel buffers returned to the pool by the previous ones
+    for _ in 0 .. 3 {
+        let read_back: Vec<Vec<(u8, u8, u8, u8)>> = match texture.read_to_pixel_buffer()
+                                                                 .read_as_texture_2d()
+        {
+            Ok(r) => r,
+            Err(glium::buffer::ReadError::NotSupported) => return,
+            e => e.unwrap()
+        };
+
+        assert_eq!(read_back[0][0], (0, 1, 2, 255));
+        assert_eq!(read_back[1][1], (32, 16, 4, 255));
+    }
+
+    display.purge_caches();
+    display.assert_no_error(None);
+}
+
 macro_rules! read_texture_test {
     ($test_name:ident, $tex_ty:ident, $data_ty:ty, $data:expr) => (
         #[test]
@@ -134,3 +161,197 @@ read_texture_test!(read_unsignedtexture2darray, UnsignedTexture2dArray, (u8, u8,
 read_texture_test!(read_unsignedtexture3d, UnsignedTexture3d, (u8, u8, u8, u8),
     vec![vec![vec![(0, 1, 2, 3), (4, 5, 6, 7)], vec![(8, 9, 10, 11), (12, 13, 14, 15)]]]);*/
 // TODO: srgb textures
+
+#[test]
+fn texture_2d_aligned_rows() {
+    use glium::texture::{ClientFormat, RawImage2d, RowAlignment};
+
+    let display = support::build_display();
+
+    // three RGB pixels per row, padded to 12 bytes
+    let data = [
+        255u8, 0, 0,   0, 255, 0,   0, 0, 255,   0, 0, 0,
+        0u8, 0, 0,   255, 255, 255,   0, 0, 0,   0, 0, 0,
+    ];
+
+    let image = RawImage2d::from_aligned_rows(&data, (3, 2), ClientFormat::U8U8U8,
+                                              RowAlignment::Four);
+    assert_eq!(image.data.len(), 18);
+    assert_eq!(&image.to_aligned_rows(RowAlignment::Four)[..], &data[..]);
+
+    let texture = glium::texture::Texture2d::new(&display, image).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[0][2], (0, 0, 255, 255));
+    assert_eq!(read_back[1][1], (255, 255, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn half_conversions() {
+    use glium::texture::Half;
+
+    for &value in &[0.0f32, 1.0, -2.5, 0.333251953125, 65504.0, 0.00006103515625,
+                    0.000000059604644775390625] {
+        assert_eq!(Half::from_f32(value).to_f32(), value);
+    }
+
+    assert_eq!(Half::from_f32(1.0), Half(0x3c00));
+    assert_eq!(Half::from_f32(100000.0).to_f32(), std::f32::INFINITY);
+}
+
+#[test]
+fn texture_2d_read_f16() {
+    use glium::Surface;
+    use glium::texture::Half;
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture2d::empty_with_format(&display,
+                                glium::texture::UncompressedFloatFormat::F16F16F16F16,
+                                glium::texture::MipmapsOption::NoMipmap, 2, 2)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    texture.as_surface().clear_color(0.5, 2.0, -1.0, 1.0);
+
+    let read_back: Vec<Vec<(Half, Half, Half, Half)>> = unsafe { texture.unchecked_read() };
+    assert_eq!(read_back[1][1], (Half::from_f32(0.5), Half::from_f32(2.0), Half::from_f32(-1.0),
+                                 Half::from_f32(1.0)));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_rgb10a2() {
+    use glium::Surface;
+    use glium::texture::Rgb10A2;
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture2d::empty_with_format(&display,
+                                glium::texture::UncompressedFloatFormat::U10U10U10U2,
+                                glium::texture::MipmapsOption::NoMipmap, 2, 2)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    texture.as_surface().clear_color(1.0, 0.0, 1.0, 1.0);
+
+    let read_back: Vec<Vec<Rgb10A2>> = unsafe { texture.unchecked_read() };
+    assert_eq!(read_back[0][1].components(), (1023, 0, 1023, 3));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn packed_float_conversions() {
+    use glium::texture::{R11G11B10F, Rgb9E5};
+
+    assert_eq!(R11G11B10F::from_f32(0.5, 2.0, 1024.0).to_f32(), (0.5, 2.0, 1024.0));
+    assert_eq!(R11G11B10F::from_f32(-1.0, 0.0, 1.0).to_f32(), (0.0, 0.0, 1.0));
+    assert_eq!(R11G11B10F::from_f32(100000.0, 0.0, 0.0).to_f32().0, std::f32::INFINITY);
+
+    assert_eq!(Rgb9E5::from_f32(0.5, 2.0, 3.25).to_f32(), (0.5, 2.0, 3.25));
+    assert_eq!(Rgb9E5::from_f32(-1.0, 0.0, 1.0).to_f32(), (0.0, 0.0, 1.0));
+    assert_eq!(Rgb9E5::from_f32(100000.0, 0.0, 0.0).to_f32().0, 65408.0);
+}
+
+#[test]
+fn texture_2d_upload_packed_floats() {
+    use glium::texture::{R11G11B10F, Rgb9E5, UncompressedFloatFormat, MipmapsOption};
+
+    let display = support::build_display();
+
+    let data = vec![vec![R11G11B10F::from_f32(0.5, 2.0, 4.0); 2]; 2];
+    let texture = match glium::texture::Texture2d::with_format(&display, data,
+                                UncompressedFloatFormat::F11F11F10, MipmapsOption::NoMipmap)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let read_back: Vec<Vec<R11G11B10F>> = unsafe { texture.unchecked_read() };
+    assert_eq!(read_back[1][1].to_f32(), (0.5, 2.0, 4.0));
+
+    let data = vec![vec![Rgb9E5::from_f32(0.25, 1.0, 3.0); 2]; 2];
+    // shared exponent textures can't be attached to a framebuffer, so they can't be read back
+    let _ = glium::texture::Texture2d::with_format(&display, data,
+                                UncompressedFloatFormat::F9F9F9, MipmapsOption::NoMipmap);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_texture_2d_read_f32() {
+    use glium::texture::{DepthFormat, DepthTexture2d, MipmapsOption};
+
+    let display = support::build_display();
+
+    let data = vec![vec![0.25f32, 0.5], vec![0.75, 1.0]];
+    let texture = match DepthTexture2d::with_format(&display, data, DepthFormat::F32,
+                                                    MipmapsOption::NoMipmap)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let read_back: Vec<Vec<f32>> = texture.read();
+    assert_eq!(read_back, vec![vec![0.25, 0.5], vec![0.75, 1.0]]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_stencil_texture_2d_read_f32i8() {
+    use glium::Surface;
+    use glium::texture::{DepthStencilFormat, DepthStencilTexture2d, MipmapsOption};
+
+    let display = support::build_display();
+
+    let texture = match DepthStencilTexture2d::empty_with_format(&display,
+                                DepthStencilFormat::F32I8, MipmapsOption::NoMipmap, 2, 2)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::depth_stencil_only(&display,
+                                                                                  &texture).unwrap();
+    framebuffer.clear_depth(0.25);
+
+    let read_back: Vec<Vec<f32>> = texture.read();
+    assert_eq!(read_back[1][0], 0.25);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_formats_renderable() {
+    use glium::CapabilitiesSource;
+    use glium::texture::{DepthFormat, TextureFormat};
+
+    let display = support::build_display();
+
+    for format in DepthFormat::get_formats_list() {
+        let infos = display.get_capabilities().internal_formats_textures
+                           .get(&TextureFormat::DepthFormat(format));
+
+        match infos {
+            Some(infos) => {
+                assert!(format.is_supported(&display));
+                if infos.renderable == Some(true) {
+                    assert!(format.is_renderable(&display));
+                }
+            },
+            None => assert!(!format.is_supported(&display)),
+        }
+    }
+
+    display.assert_no_error(None);
+}
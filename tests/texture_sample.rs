@@ -130,3 +130,53 @@ fn bindless_texture() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_units_reuse() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    // using more textures than there are texture units, so that units have to be reused
+    let num_textures = display.get_capabilities().max_combined_texture_image_units as usize + 2;
+    let textures = (0 .. num_textures).map(|i| {
+        let color = (i % 256) as u8;
+        glium::texture::Texture2d::new(&display, vec![vec![(color, 0, 0, 255u8)]]).unwrap()
+    }).collect::<Vec<_>>();
+
+    let output = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+
+    for _ in 0 .. 2 {
+        for (i, texture) in textures.iter().enumerate() {
+            output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: texture },
+                                     &Default::default()).unwrap();
+
+            let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+            assert_eq!(data[0][0], ((i % 256) as u8, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -338,3 +338,108 @@ fn empty_uniform_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn block_binding_shared_between_programs() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex_shader = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let program1 = glium::Program::from_source(&display, vertex_shader,
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec3 color;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None);
+
+    let program2 = glium::Program::from_source(&display, vertex_shader,
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform OtherBlock {
+                float alpha;
+            };
+
+            uniform MyBlock {
+                vec3 color;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, alpha);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let (program1, program2) = match (program1, program2) {
+        (Ok(p1), Ok(p2)) => (p1, p2),
+        _ => return
+    };
+
+    let binding = display.reserve_uniform_block_binding("MyBlock").unwrap();
+    assert_eq!(display.reserve_uniform_block_binding("MyBlock"), Some(binding));
+    program1.set_uniform_block_binding("MyBlock", binding).unwrap();
+    program2.set_uniform_block_binding("MyBlock", binding).unwrap();
+
+    match program1.set_uniform_block_binding("Missing", 0) {
+        Err(glium::program::BlockBindingError::NotFound) => (),
+        r => panic!("{:?}", r)
+    };
+
+    match program1.set_uniform_block_binding("MyBlock", 1 << 20) {
+        Err(glium::program::BlockBindingError::BindingOutOfRange { .. }) => (),
+        r => panic!("{:?}", r)
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: (f32, f32, f32),
+    }
+
+    implement_uniform_block!(Data, color);
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, Data { color: (1.0f32, 1.0f32, 0.0f32) }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let alpha = glium::uniforms::UniformBuffer::new(&display, 0.0f32).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program1, &uniform!{
+        MyBlock: &buffer
+    }, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+
+    texture.as_surface().draw(&vb, &ib, &program2, &uniform!{
+        MyBlock: &buffer,
+        OtherBlock: &alpha
+    }, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 0));
+
+    display.assert_no_error(None);
+}
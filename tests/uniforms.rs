@@ -1,5 +1,7 @@
 #[macro_use]
 extern crate glium;
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
 
 use glium::Surface;
 
@@ -51,6 +53,60 @@ fn uniforms_storage_single_value() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn uniform_value_filtering() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    assert!(program.uses_uniform_value_filtering());
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the second color is the same as the first one, and is only uploaded without filtering
+    for &(filtering, expected_uploads) in &[(true, 2), (false, 3), (true, 2)] {
+        program.set_uniform_value_filtering(filtering);
+        assert_eq!(program.uses_uniform_value_filtering(), filtering);
+
+        let uploads_before = program.get_uniform_uploads_count();
+
+        for &color in &[[1.0, 0.0, 0.0, 1.0f32], [1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]] {
+            let uniforms = uniform! { color: color };
+
+            texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+            texture.as_surface().draw(&vb, &ib, &program, &uniforms,
+                                      &Default::default()).unwrap();
+
+            let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+            assert_eq!(data[0][0], ((color[0] * 255.0) as u8, (color[1] * 255.0) as u8, 0, 255));
+        }
+
+        assert_eq!(program.get_uniform_uploads_count() - uploads_before, expected_uploads);
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn uniforms_storage_multiple_values() {
     let display = support::build_display();
@@ -358,3 +414,299 @@ uniform_test!(uniform_type_boolarr_boolvec3, "bvec3", [true, true, true]);
 uniform_test!(uniform_type_booltup_boolvec3, "bvec3", (false, false, false));
 uniform_test!(uniform_type_boolarr_boolvec4, "bvec4", [true, false, false, true]);
 uniform_test!(uniform_type_booltup_boolvec4, "bvec4", (false, true, true, false));
+
+#[test]
+fn uniform_handles() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+            uniform float alpha;
+
+            void main() {
+                gl_FragColor = vec4(color.rgb, alpha);
+            }
+        ",
+        None).unwrap();
+
+    let color = program.uniform_handle::<[f32; 4]>("color").unwrap();
+
+    match program.uniform_handle::<[f32; 3]>("color") {
+        Err(glium::uniforms::UniformHandleError::TypeMismatch { .. }) => (),
+        _ => panic!()
+    };
+
+    match program.uniform_handle::<f32>("unknown") {
+        Err(glium::uniforms::UniformHandleError::NotFound) => (),
+        _ => panic!()
+    };
+
+    let uniforms = glium::uniforms::HandledUniforms::with(uniform! { alpha: 1.0f32 },
+                                                          &color, [1.0, 0.0, 0.0, 0.0f32]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_handle_wrong_program() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let fragment = "
+        #version 110
+
+        uniform vec4 color;
+
+        void main() {
+            gl_FragColor = color;
+        }
+    ";
+
+    let program1 = glium::Program::from_source(&display, vertex, fragment, None).unwrap();
+    let program2 = glium::Program::from_source(&display, vertex, fragment, None).unwrap();
+
+    let color = program1.uniform_handle::<[f32; 4]>("color").unwrap();
+    let uniforms = glium::uniforms::HandledUniforms::new(&color, [1.0, 0.0, 0.0, 1.0f32]);
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program2, &uniforms, &Default::default()) {
+        Err(glium::DrawError::UniformHandleProgramMismatch { .. }) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_validate() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+            uniform float scale;
+
+            void main() {
+                gl_Position = vec4(position * scale, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    let report = program.validate(&uniform!{ scale: 1.0f32, color: [1.0, 0.0, 0.0, 1.0f32] },
+                                  &texture.as_surface());
+    assert!(report.issues.is_empty());
+    assert!(report.backend_valid.is_some());
+
+    let report = program.validate(&uniform!{ color: 1.0f32, unknown: 1.0f32 },
+                                  &texture.as_surface());
+    assert!(!report.is_valid());
+    assert_eq!(report.issues.len(), 3);
+    assert!(report.issues.contains(&glium::program::ValidationIssue::UniformTypeMismatch {
+        name: "color".to_owned(),
+        expected: glium::uniforms::UniformType::FloatVec4,
+    }));
+    assert!(report.issues.contains(&glium::program::ValidationIssue::UnknownUniform {
+        name: "unknown".to_owned(),
+    }));
+    assert!(report.issues.contains(&glium::program::ValidationIssue::MissingUniform {
+        name: "scale".to_owned(),
+    }));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn strict_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+            uniform float scale;
+
+            void main() {
+                gl_Position = vec4(position * scale, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let uniforms = uniform!{ colour: [1.0, 0.0, 0.0, 1.0f32], scale: 1.0f32 };
+    let texture = support::build_renderable_texture(&display);
+
+    // ignored by default
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    program.set_strict_uniforms(true);
+    assert!(program.uses_strict_uniforms());
+
+    match texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Err(glium::DrawError::UniformsMismatch { unknown, missing }) => {
+            assert_eq!(unknown, vec!["colour".to_owned()]);
+            assert_eq!(missing, vec!["color".to_owned()]);
+        },
+        a => panic!("{:?}", a)
+    };
+
+    let uniforms = uniform!{ color: [1.0, 0.0, 0.0, 1.0f32], scale: 1.0f32 };
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn state_statistics_texture_binds() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture1;
+            uniform sampler2D texture2;
+
+            void main() {
+                gl_FragColor = texture2D(texture1, vec2(0.5, 0.5)) +
+                               texture2D(texture2, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let red = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+    let green = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+    let blue = support::build_unicolor_texture2d(&display, 0.0, 0.0, 1.0);
+    let black = support::build_unicolor_texture2d(&display, 0.0, 0.0, 0.0);
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture1: &red, texture2: &green },
+                             &Default::default()).unwrap();
+
+    // drawing again with the same textures doesn't bind anything
+    display.reset_state_statistics();
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture1: &red, texture2: &green },
+                             &Default::default()).unwrap();
+    assert_eq!(display.get_state_statistics().texture_bind_calls, 0);
+
+    display.reset_state_statistics();
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture1: &blue, texture2: &black },
+                             &Default::default()).unwrap();
+
+    let multi_bind = display.get_opengl_version() >= &glium::Version(glium::Api::Gl, 4, 4) ||
+                     display.get_extensions().gl_arb_multi_bind;
+    let expected = if multi_bind { 1 } else { 2 };
+    assert_eq!(display.get_state_statistics().texture_bind_calls, expected);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[cfg(feature = "cgmath")]
+#[test]
+fn cgmath_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform mat4 matrix;
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = matrix * color;
+            }
+        ",
+        None).unwrap();
+
+    let uniforms = uniform! {
+        matrix: cgmath::Matrix4::from_nonuniform_scale(1.0, 0.0, 1.0f32),
+        color: cgmath::Vector4::new(1.0, 1.0, 0.0, 0.5f32),
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 128));
+
+    display.assert_no_error(None);
+}
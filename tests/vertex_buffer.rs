@@ -22,6 +22,10 @@ fn transform_feedback() {
         tessellation_control_shader: None,
         tessellation_evaluation_shader: None,
         geometry_shader: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
         outputs_srgb: false,
         uses_point_size: false,
 
@@ -88,3 +92,459 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn transform_feedback_counter_draw() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let counter = match glium::vertex::TransformFeedbackCounter::new(&display) {
+        Ok(c) => c,
+        Err(_) => return
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 6).unwrap();
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::with_counter(&display, &program,
+                                                                            &mut out_buffer,
+                                                                            &counter).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        let texture = support::build_renderable_texture(&display);
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+    }
+
+    let draw_program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 output_val;
+
+                void main() {
+                    gl_Position = vec4(output_val, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+
+                void main() {
+                    color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let indices = glium::index::IndicesSource::TransformFeedback {
+        counter: &counter,
+        primitives: glium::index::PrimitiveType::TrianglesList,
+    };
+
+    texture.as_surface().draw(&out_buffer, indices, &draw_program, &uniform!{},
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_interleaved_sessions() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let (counter1, counter2) = match (glium::vertex::TransformFeedbackCounter::new(&display),
+                                      glium::vertex::TransformFeedbackCounter::new(&display))
+    {
+        (Ok(c1), Ok(c2)) => (c1, c2),
+        _ => return
+    };
+
+    let mut buffer1: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 12).unwrap();
+    let mut buffer2: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 12).unwrap();
+
+    {
+        let session1 = glium::vertex::TransformFeedbackSession::with_counter(&display, &program,
+                                                                             &mut buffer1,
+                                                                             &counter1).unwrap();
+        let session2 = glium::vertex::TransformFeedbackSession::with_counter(&display, &program,
+                                                                             &mut buffer2,
+                                                                             &counter2).unwrap();
+
+        let params1 = glium::DrawParameters {
+            transform_feedback: Some(&session1),
+            .. Default::default()
+        };
+
+        let params2 = glium::DrawParameters {
+            transform_feedback: Some(&session2),
+            .. Default::default()
+        };
+
+        // each capture is paused while the other one runs, and resumed afterwards
+        let texture = support::build_renderable_texture(&display);
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params1).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params2).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params1).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params2).unwrap();
+    }
+
+    for buffer in [&buffer1, &buffer2].iter() {
+        let result = match buffer.read() {
+            Ok(r) => r,
+            Err(glium::buffer::ReadError::NotSupported) => return,
+            e => e.unwrap()
+        };
+
+        assert_eq!(result[0].output_val, (-1.0, 1.0));
+        assert_eq!(result[5].output_val, (1.0, -1.0));
+        assert_eq!(&result[0 .. 6], &result[6 .. 12]);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_separate_buffers() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Position {
+        output_position: (f32, f32),
+    }
+
+    implement_vertex!(Position, output_position);
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Sum {
+        output_sum: f32,
+    }
+
+    implement_vertex!(Sum, output_sum);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_position;
+            varying float output_sum;
+
+            void main() {
+                output_position = position;
+                output_sum = position.x + position.y;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_position".to_string(), "output_sum".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(glium::program::ProgramCreationError::TransformFeedbackBuffersLimitExceeded { .. }) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(program.get_transform_feedback_buffers().len(), 2);
+
+    let mut positions: glium::VertexBuffer<Position> = glium::VertexBuffer::empty(&display, 6).unwrap();
+    let mut sums: glium::VertexBuffer<Sum> = glium::VertexBuffer::empty(&display, 6).unwrap();
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+                                                                   &mut positions).unwrap();
+        assert!(!session.is_complete());
+
+        let texture = support::build_renderable_texture(&display);
+
+        match texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        }) {
+            Err(glium::DrawError::TransformFeedbackBufferMissing) => (),
+            r => panic!("{:?}", r)
+        };
+
+        let session = session.add_buffer(&mut sums).unwrap();
+        assert!(session.is_complete());
+
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        }).unwrap();
+    }
+
+    let positions = match positions.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let sums = sums.read().unwrap();
+
+    for (position, sum) in positions.iter().zip(sums.iter()) {
+        assert_eq!(position.output_position.0 + position.output_position.1, sum.output_sum);
+    }
+
+    assert_eq!(positions[0].output_position, (-1.0, 1.0));
+    assert_eq!(sums[5].output_sum, 0.0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_reflection_qualifiers() {
+    let display = support::build_display();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 440
+
+            in vec2 position;
+
+            layout(xfb_buffer = 1, xfb_offset = 0) out vec2 output_position;
+            layout(xfb_buffer = 1, xfb_offset = 12) out float output_sum;
+
+            void main() {
+                output_position = position;
+                output_sum = position.x + position.y;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 440
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ", None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let buffers = program.get_transform_feedback_buffers();
+    assert_eq!(buffers.len(), 1);
+    assert_eq!(buffers[0].id, 1);
+    assert_eq!(buffers[0].stride, 16);
+    assert_eq!(buffers[0].get_size_bytes(6), 96);
+
+    assert_eq!(buffers[0].elements.len(), 2);
+    assert_eq!(buffers[0].elements[0].name, "output_position");
+    assert_eq!(buffers[0].elements[0].offset, 0);
+    assert_eq!(buffers[0].elements[0].ty, glium::vertex::AttributeType::F32F32);
+    assert_eq!(buffers[0].elements[1].name, "output_sum");
+    assert_eq!(buffers[0].elements[1].offset, 12);
+    assert_eq!(buffers[0].elements[1].ty, glium::vertex::AttributeType::F32);
+
+    let bindings = buffers[0].get_bindings();
+    assert!(program.transform_feedback_buffer_matches(0, &bindings, buffers[0].stride));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_buffers_limit() {
+    let display = support::build_display();
+
+    let max = glium::program::get_max_transform_feedback_buffers(&display,
+                                            glium::program::TransformFeedbackMode::Separate);
+    if max == 0 {
+        return;
+    }
+
+    let varyings = (0 .. max + 1).map(|n| format!("output{}", n)).collect::<Vec<_>>();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_output_locations: Vec::new(),
+        attribute_locations: Vec::new(),
+        defines: Vec::new(),
+        adapt_glsl_version: false,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((varyings,
+                                           glium::program::TransformFeedbackMode::Separate)),
+    };
+
+    match glium::Program::new(&display, source) {
+        Err(glium::program::ProgramCreationError::TransformFeedbackBuffersLimitExceeded {
+            requested, max: m
+        }) => {
+            assert_eq!(requested, max + 1);
+            assert_eq!(m, max);
+        },
+        r => panic!("{:?}", r.err())
+    };
+
+    display.assert_no_error(None);
+}
@@ -544,6 +544,100 @@ fn instancing() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn instancing_buffer_slices() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    #[derive(Copy, Clone)]
+    struct Instance {
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Instance, color);
+
+    let vertices = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] },
+        Vertex { position: [ 1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [ 1.0, -1.0] },
+    ]).unwrap();
+
+    let instances = glium::VertexBuffer::new(&display, &[
+        Instance { color: [0.0, 0.0, 0.0] },
+        Instance { color: [0.0, 1.0, 0.0] },
+        Instance { color: [0.0, 0.0, 1.0] },
+        Instance { color: [1.0, 0.0, 0.0] },
+    ]).unwrap();
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec3 color;
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    for &(index, expected) in [(3, (255, 0, 0, 255)), (2, (0, 0, 255, 255)),
+                               (1, (0, 255, 0, 255))].iter()
+    {
+        let instance = instances.slice(index .. index + 1).unwrap();
+        let slice = match instance.per_instance() {
+            Ok(s) => s,
+            Err(_) => return
+        };
+
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw((&vertices, slice), &index_buffer, &program, &uniform!{},
+                                  &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], expected);
+        assert_eq!(data[1023][1023], expected);
+    }
+
+    // with base instances, all the slices share the same VAO
+    let base_instance = display.get_opengl_version() >= &glium::Version(glium::Api::Gl, 4, 2) ||
+                        display.get_extensions().gl_arb_base_instance ||
+                        display.get_extensions().gl_ext_base_instance;
+    if base_instance {
+        assert_eq!(display.get_vertex_array_objects_cache_statistics().entries, 1);
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn per_instance_length_mismatch() {
     let display = support::build_display();
@@ -660,6 +754,82 @@ fn per_instance_length_mismatch() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn instancing_not_supported() {
+    let display = support::build_display();
+
+    if glium::vertex::is_instancing_supported(&display) {
+        return;
+    }
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vertex_buffer = glium::VertexBuffer::new(&display,
+        &[
+            Vertex { position: [-1.0,  1.0] },
+            Vertex { position: [ 1.0,  1.0] },
+            Vertex { position: [-1.0, -1.0] },
+        ]
+    ).unwrap();
+
+    assert!(vertex_buffer.per_instance().is_err());
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        }).unwrap();
+
+    let mut frame = display.draw();
+    match frame.draw((&vertex_buffer, glium::vertex::EmptyInstanceAttributes { len: 2 }),
+                     &glium::index::NoIndices(PrimitiveType::TrianglesList), &program,
+                     &uniform!{}, &Default::default())
+    {
+        Err(glium::DrawError::InstancingNotSupported) => (),
+        a => panic!("{:?}", a)
+    }
+
+    frame.finish().unwrap();
+    display.assert_no_error(None);
+}
+
 #[test]
 fn normalized_vertex() {
         let display = support::build_display();